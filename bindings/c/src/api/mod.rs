@@ -588,12 +588,14 @@ fn handle_message_contents(m: &UnwrappedMessage) -> PacketPayloads {
         MessageContent::TaggedPacket {
             public_payload: p,
             masked_payload: m,
+            ..
         } => (p, m).into(),
 
         MessageContent::SignedPacket {
             pk: _,
             public_payload: p,
             masked_payload: m,
+            ..
         } => (p, m).into(),
 
         _ => PacketPayloads::default(),
@@ -172,6 +172,7 @@ pub fn get_message_contents(msgs: Vec<UnwrappedMessage>) -> Vec<UserResponse> {
                 pk,
                 public_payload: p,
                 masked_payload: m,
+                ..
             } => payloads.push(UserResponse::new(
                 Address::from_string(msg.link.to_string()),
                 None,
@@ -180,6 +181,7 @@ pub fn get_message_contents(msgs: Vec<UnwrappedMessage>) -> Vec<UserResponse> {
             MessageContent::TaggedPacket {
                 public_payload: p,
                 masked_payload: m,
+                ..
             } => payloads.push(UserResponse::new(
                 Address::from_string(msg.link.to_string()),
                 None,
@@ -1,4 +1,5 @@
 pub mod multi_branch;
 pub mod recovery;
+pub mod remote_signer;
 pub mod single_branch;
 pub mod utils;
@@ -0,0 +1,111 @@
+use iota_streams::{
+    app_channels::api::tangle::{
+        Author,
+        ChannelType,
+        Signer,
+        Subscriber,
+        Transport,
+    },
+    core::{
+        panic_if_not,
+        prelude::Rc,
+        println,
+        Result,
+    },
+    core_edsig::signature::ed25519,
+    ddml::types::*,
+};
+
+use anyhow::anyhow;
+use core::cell::RefCell;
+
+use std::{
+    sync::{
+        mpsc,
+        Mutex,
+    },
+    thread,
+};
+
+/// Request to the remote signer: message hash and the channel the signature is sent back on.
+type SignRequest = (NBytes<U64>, mpsc::Sender<ed25519::Signature>);
+
+/// Mock of a remote signer, eg. a KMS: the key pair lives in another thread which is only sent
+/// message hashes. The public key is fetched once when connecting, signing waits for the reply.
+struct RemoteSigner {
+    public_key: ed25519::PublicKey,
+    requests: Mutex<mpsc::Sender<SignRequest>>,
+}
+
+impl RemoteSigner {
+    fn connect(secret: [u8; ed25519::SECRET_KEY_LENGTH]) -> Self {
+        let (requests, incoming) = mpsc::channel::<SignRequest>();
+        let (public_keys, public_key) = mpsc::channel();
+        thread::spawn(move || {
+            let kp = ed25519::keypair_from_secret(secret);
+            public_keys.send(kp.public).unwrap();
+            for (hash, reply) in incoming {
+                if let Ok(signature) = kp.sign_hash(&hash) {
+                    let _ = reply.send(signature);
+                }
+            }
+        });
+        Self {
+            public_key: public_key.recv().unwrap(),
+            requests: Mutex::new(requests),
+        }
+    }
+}
+
+impl Signer for RemoteSigner {
+    fn public_key(&self) -> &ed25519::PublicKey {
+        &self.public_key
+    }
+
+    fn sign_hash(&self, hash: &NBytes<U64>) -> Result<ed25519::Signature> {
+        let (reply, signature) = mpsc::channel();
+        self.requests
+            .lock()
+            .unwrap()
+            .send((*hash, reply))
+            .map_err(|_| anyhow!("remote signer disconnected"))?;
+        Ok(signature.recv()?)
+    }
+}
+
+pub fn example<T: Transport>(transport: Rc<RefCell<T>>, channel_type: ChannelType, seed: &str) -> Result<()> {
+    let mut author = Author::new(seed, channel_type, transport.clone());
+    let mut subscriberA = Subscriber::new("SUBSCRIBERA9SEED", transport);
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+
+    println!("\nAnnounce Channel");
+    let announcement_link = {
+        let msg = author.send_announce()?;
+        println!("  msg => <{}> {}", msg.msgid, msg);
+        msg
+    };
+    subscriberA.receive_announcement(&announcement_link)?;
+
+    println!("\nConnect remote signer");
+    let signer = RemoteSigner::connect([42; ed25519::SECRET_KEY_LENGTH]);
+    let signer_pk = *signer.public_key();
+    author.set_signer(Some(signer));
+
+    println!("\nSigned packet - Author");
+    let packet_link = {
+        let (msg, seq) = author.send_signed_packet(&announcement_link, &public_payload, &masked_payload)?;
+        println!("  msg => <{}> {}", msg.msgid, msg);
+        panic_if_not(seq.is_none());
+        msg
+    };
+
+    println!("\nHandle Signed packet");
+    let (pk, unwrapped_public, _) = subscriberA.receive_signed_packet(&packet_link)?;
+    panic_if_not(pk == signer_pk);
+    panic_if_not(unwrapped_public == public_payload);
+
+    println!("Signed packet verified with the remote signer public key");
+    Ok(())
+}
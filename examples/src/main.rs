@@ -52,6 +52,15 @@ fn run_multi_branch_test<T: Transport>(transport: Rc<RefCell<T>>, seed: &str) {
     println!("#######################################");
 }
 
+fn run_remote_signer_test<T: Transport>(transport: Rc<RefCell<T>>, seed: &str) {
+    println!("\tRunning Remote Signer Test, seed: {}", seed);
+    match branching::remote_signer::example(transport, ChannelType::SingleBranch, seed) {
+        Err(err) => println!("Error in Remote Signer test: {:?}", err),
+        Ok(_) => println!("\tRemote Signer Test completed!!"),
+    }
+    println!("#######################################");
+}
+
 fn run_main<T: Transport>(transport: T) -> Result<()> {
     let seed1: &str = "SEEDSINGLE";
     let seed2: &str = "SEEDMULTI9";
@@ -77,7 +86,8 @@ fn main_pure() {
     let transport = Rc::new(RefCell::new(transport));
     run_single_branch_test(transport.clone(), "PURESEEDA");
     run_multi_branch_test(transport.clone(), "PURESEEDB");
-    run_recovery_test(transport, "PURESEEDC");
+    run_recovery_test(transport.clone(), "PURESEEDC");
+    run_remote_signer_test(transport, "PURESEEDD");
     println!("Done running pure tests without accessing Tangle");
     println!("#######################################");
 }
@@ -0,0 +1,59 @@
+#[macro_use]
+extern crate criterion;
+
+use core::cell::RefCell;
+use criterion::{
+    BatchSize,
+    Criterion,
+};
+use iota_streams_app_channels::api::tangle::{
+    Address,
+    Author,
+    BucketTransport,
+    Bytes,
+    ChannelType,
+};
+use std::rc::Rc;
+
+type Transport = Rc<RefCell<BucketTransport>>;
+
+const BATCH_SIZE: u8 = 32;
+
+fn setup() -> (Author<Transport>, Address) {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("BENCHBULKAUTHORSEED", ChannelType::SingleBranch, transport);
+    let ann_link = author.send_announce().unwrap();
+    (author, ann_link)
+}
+
+fn payloads() -> impl Iterator<Item = (Bytes, Bytes)> {
+    (0..BATCH_SIZE).map(|i| (Bytes(vec![i; 256]), Bytes(vec![i; 256])))
+}
+
+fn bulk_send_benchmark(c: &mut Criterion) {
+    c.bench_function("Send signed packets/loop (32x256B)", |b| {
+        b.iter_batched(
+            setup,
+            |(mut author, mut link)| {
+                for (public_payload, masked_payload) in payloads() {
+                    link = author
+                        .send_signed_packet(&link, &public_payload, &masked_payload)
+                        .unwrap()
+                        .0;
+                }
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("Send signed packets/bulk (32x256B)", |b| {
+        b.iter_batched(
+            setup,
+            |(mut author, link)| author.send_signed_packets(&link, payloads()).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+criterion_group!(benches, bulk_send_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,57 @@
+#[macro_use]
+extern crate criterion;
+
+use core::cell::RefCell;
+use criterion::{
+    BatchSize,
+    Criterion,
+};
+use iota_streams_app_channels::api::tangle::{
+    Address,
+    Author,
+    BucketTransport,
+    Bytes,
+    ChannelType,
+};
+use std::rc::Rc;
+
+type Transport = Rc<RefCell<BucketTransport>>;
+
+const PACKETS: u8 = 32;
+
+fn setup(wrap_size_hints: bool) -> (Author<Transport>, Address) {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("BENCHWRAPHINTAUTHORSEED", ChannelType::SingleBranch, transport);
+    author.set_wrap_size_hints(wrap_size_hints);
+    let ann_link = author.send_announce().unwrap();
+    (author, ann_link)
+}
+
+fn send_packets(author: &mut Author<Transport>, ann_link: &Address) {
+    let public_payload = Bytes(vec![0; 256]);
+    for i in 0..PACKETS {
+        let masked_payload = Bytes(vec![i; 256]);
+        author.send_signed_packet(ann_link, &public_payload, &masked_payload).unwrap();
+    }
+}
+
+fn wrap_size_hint_benchmark(c: &mut Criterion) {
+    c.bench_function("Send signed packets/sizeof (32x256B)", |b| {
+        b.iter_batched(
+            || setup(false),
+            |(mut author, ann_link)| send_packets(&mut author, &ann_link),
+            BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("Send signed packets/size hint (32x256B)", |b| {
+        b.iter_batched(
+            || setup(true),
+            |(mut author, ann_link)| send_packets(&mut author, &ann_link),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+criterion_group!(benches, wrap_size_hint_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,166 @@
+//! Compression of public payloads of signed and tagged packets.
+//!
+//! When a compressor is set and it reduces the size of the public payload, the payload is sent
+//! prefixed with one byte identifying the codec it was compressed with, and the message header
+//! signals the compression. Other payloads are sent as is, so they are read by any receiver. A
+//! receiver which has no compressor, or no decoder for the codec, gets `UnsupportedPayloadCodec`
+//! error instead of a corrupted payload.
+
+use iota_streams_core::{
+    err,
+    prelude::Vec,
+    Errors::UnsupportedPayloadCodec,
+    Result,
+};
+use iota_streams_ddml::types::Bytes;
+
+#[cfg(feature = "deflate")]
+use iota_streams_core::Errors::PayloadDecompressionFailure;
+
+/// Identifier of uncompressed payloads.
+pub const CODEC_NONE: u8 = 0;
+/// Identifier of payloads compressed with deflate.
+pub const CODEC_DEFLATE: u8 = 1;
+
+/// Payload compression codec.
+pub trait Compressor {
+    /// Identifier of the codec sent along with compressed payloads.
+    fn codec_id(&self) -> u8;
+
+    /// Compress data.
+    fn compress(&self, data: &[u8]) -> Result<Vec<u8>>;
+
+    /// Decompress data failing if the decompressed data is longer than `max_len`.
+    fn decompress(&self, data: &[u8], max_len: usize) -> Result<Vec<u8>>;
+}
+
+/// Codec which doesn't compress payloads. It allows receivers to interpret codec identifiers
+/// without supporting any compression.
+#[derive(Clone, Copy, Default)]
+pub struct NoCompression;
+
+impl Compressor for NoCompression {
+    fn codec_id(&self) -> u8 {
+        CODEC_NONE
+    }
+
+    fn compress(&self, data: &[u8]) -> Result<Vec<u8>> {
+        Ok(data.to_vec())
+    }
+
+    fn decompress(&self, data: &[u8], _max_len: usize) -> Result<Vec<u8>> {
+        Ok(data.to_vec())
+    }
+}
+
+/// Deflate codec.
+#[cfg(feature = "deflate")]
+#[derive(Clone, Copy)]
+pub struct Deflate {
+    level: u8,
+}
+
+#[cfg(feature = "deflate")]
+impl Deflate {
+    /// Create codec with compression level [0-10].
+    pub fn new(level: u8) -> Self {
+        Self { level }
+    }
+}
+
+#[cfg(feature = "deflate")]
+impl Default for Deflate {
+    fn default() -> Self {
+        Self::new(6)
+    }
+}
+
+#[cfg(feature = "deflate")]
+impl Compressor for Deflate {
+    fn codec_id(&self) -> u8 {
+        CODEC_DEFLATE
+    }
+
+    fn compress(&self, data: &[u8]) -> Result<Vec<u8>> {
+        Ok(miniz_oxide::deflate::compress_to_vec(data, self.level))
+    }
+
+    fn decompress(&self, data: &[u8], max_len: usize) -> Result<Vec<u8>> {
+        match miniz_oxide::inflate::decompress_to_vec_with_limit(data, max_len) {
+            Ok(decompressed) => Ok(decompressed),
+            Err(_) => err!(PayloadDecompressionFailure),
+        }
+    }
+}
+
+/// Compress payload and prefix it with the codec identifier. `None` if compression doesn't
+/// reduce its size, the payload is then sent as is.
+pub fn encode_payload(compressor: &dyn Compressor, payload: &Bytes) -> Result<Option<Bytes>> {
+    let compressed = compressor.compress(&payload.0)?;
+    if compressor.codec_id() != CODEC_NONE && compressed.len() + 1 < payload.0.len() {
+        let mut encoded = Vec::with_capacity(compressed.len() + 1);
+        encoded.push(compressor.codec_id());
+        encoded.extend_from_slice(&compressed);
+        Ok(Some(Bytes(encoded)))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Decompress payload prefixed with the codec identifier, the payload is signalled compressed by
+/// the message header. Fails if there is no compressor or it doesn't support the codec.
+pub fn decode_payload(compressor: Option<&dyn Compressor>, payload: &Bytes, max_len: usize) -> Result<Bytes> {
+    match (compressor, payload.0.split_first()) {
+        (Some(compressor), Some((&codec_id, data))) if codec_id != CODEC_NONE && codec_id == compressor.codec_id() => {
+            compressor.decompress(data, max_len).map(Bytes)
+        }
+        (_, Some((&codec_id, _))) => err!(UnsupportedPayloadCodec(codec_id)),
+        (_, None) => err!(UnsupportedPayloadCodec(CODEC_NONE)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unsupported_codec() {
+        let payload = Bytes(b"{\"temperature\": 21}".to_vec());
+        assert!(encode_payload(&NoCompression, &payload).unwrap().is_none());
+
+        let mut foreign = vec![CODEC_DEFLATE];
+        foreign.extend_from_slice(&payload.0);
+        assert!(decode_payload(Some(&NoCompression), &Bytes(foreign.clone()), 1024).is_err());
+        assert!(decode_payload(None, &Bytes(foreign), 1024).is_err());
+    }
+
+    #[cfg(feature = "deflate")]
+    #[test]
+    fn deflate_reduces_size() {
+        let mut json = Vec::new();
+        for i in 0..32 {
+            json.extend_from_slice(format!("{{\"sensor\": \"temperature\", \"index\": {}}}, ", i).as_bytes());
+        }
+        let payload = Bytes(json);
+        let encoded = encode_payload(&Deflate::default(), &payload).unwrap().unwrap();
+        assert_eq!(encoded.0[0], CODEC_DEFLATE);
+        let ratio = payload.0.len() as f64 / encoded.0.len() as f64;
+        assert!(
+            ratio > 4.0,
+            "compression ratio {:.2} of {} bytes",
+            ratio,
+            payload.0.len()
+        );
+        assert_eq!(
+            decode_payload(Some(&Deflate::default()), &encoded, 4096).unwrap(),
+            payload
+        );
+        assert!(decode_payload(Some(&Deflate::default()), &encoded, 16).is_err());
+        assert!(decode_payload(None, &encoded, 4096).is_err());
+
+        // Incompressible payloads are sent as is
+        assert!(encode_payload(&Deflate::default(), &Bytes(b"{}".to_vec()))
+            .unwrap()
+            .is_none());
+    }
+}
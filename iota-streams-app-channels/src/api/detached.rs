@@ -0,0 +1,88 @@
+//! Signatures of signed packets detached from the wrapped message.
+//!
+//! A signed packet is signed with Ed25519ph over the 64-byte hash squeezed from the spongos state
+//! once the packet content is absorbed. The hash is the exact pre-image of the signature: keeping
+//! it along with the signature, eg. in an external audit log, lets the signature be checked
+//! against the sender's public key without the channel state or the message itself.
+//!
+//! The pre-image only commits to the packet within its channel, it can't be recomputed from the
+//! payloads alone. Linking a detached signature back to a message is up to the application,
+//! eg. by storing the message address next to it.
+
+use core::cell::RefCell;
+
+use iota_streams_app::identifier::Identifier;
+use iota_streams_core::{
+    err,
+    prelude::Vec,
+    Errors::BadIdentifier,
+    Result,
+};
+use iota_streams_core_edsig::signature::ed25519;
+use iota_streams_ddml::types::{
+    verify_hash,
+    NBytes,
+    Signer,
+    U64,
+};
+
+/// Signature of a signed packet along with the signed pre-image.
+#[derive(Clone)]
+pub struct DetachedSignature {
+    /// Hash of the packet signed with Ed25519ph and `SIGNATURE_CONTEXT`.
+    pub preimage: Vec<u8>,
+    /// Signature of `preimage` generated with the sender's private key.
+    pub signature: ed25519::Signature,
+}
+
+impl DetachedSignature {
+    /// Verify the signature against the sender identifier, see `verify_detached`.
+    pub fn verify(&self, identifier: &Identifier) -> Result<()> {
+        verify_detached(&self.preimage, &self.signature, identifier)
+    }
+}
+
+/// Verify a detached signature of the `preimage` of a signed packet sent by `identifier`.
+///
+/// Only Ed25519 identifiers sign packets, pre-shared key identifiers are rejected.
+pub fn verify_detached(preimage: &[u8], signature: &ed25519::Signature, identifier: &Identifier) -> Result<()> {
+    match identifier {
+        Identifier::EdPubKey(pk) => verify_hash(&pk.0, preimage, signature),
+        _ => err!(BadIdentifier),
+    }
+}
+
+/// Signer recording the signed hash and the signature generated by the wrapped signer.
+pub(crate) struct DetachingSigner<'a> {
+    signer: &'a dyn Signer,
+    detached: RefCell<Option<DetachedSignature>>,
+}
+
+impl<'a> DetachingSigner<'a> {
+    pub(crate) fn new(signer: &'a dyn Signer) -> Self {
+        Self {
+            signer,
+            detached: RefCell::new(None),
+        }
+    }
+
+    /// The last signature generated, if any.
+    pub(crate) fn take(&self) -> Option<DetachedSignature> {
+        self.detached.borrow_mut().take()
+    }
+}
+
+impl<'a> Signer for DetachingSigner<'a> {
+    fn public_key(&self) -> &ed25519::PublicKey {
+        self.signer.public_key()
+    }
+
+    fn sign_hash(&self, hash: &NBytes<U64>) -> Result<ed25519::Signature> {
+        let signature = self.signer.sign_hash(hash)?;
+        *self.detached.borrow_mut() = Some(DetachedSignature {
+            preimage: hash.as_slice().to_vec(),
+            signature,
+        });
+        Ok(signature)
+    }
+}
@@ -25,12 +25,17 @@ pub trait KeyStore<Info, F: PRP>: Default {
     fn contains(&self, id: &Identifier) -> bool;
     fn insert_cursor(&mut self, id: Identifier, info: Info) -> Result<()>;
     fn insert_psk(&mut self, id: Identifier, psk: Option<Psk>, info: Info) -> Result<()>;
+    /// Insert publisher with an explicit key exchange public key instead of the one derived from its identifier.
+    fn insert_ke_pk(&mut self, id: Identifier, ke_pk: x25519::PublicKey, info: Info) -> Result<()>;
     fn get_next_pskid(&self) -> Option<&Identifier>;
     fn keys(&self) -> Vec<(&Identifier, Vec<u8>)>;
     fn iter(&self) -> Vec<(&Identifier, &Info)>;
     fn iter_mut(&mut self) -> Vec<(&Identifier, &mut Info)>;
+    /// Reserve capacity for at least `additional` more publishers. It's only a hint, stores may ignore it.
+    fn reserve(&mut self, _additional: usize) {}
 }
 
+#[derive(Clone)]
 pub struct KeyMap<Info> {
     /// Map from user identity -- ed25519 pk -- to
     /// a precalculated corresponding x25519 pk and some additional info.
@@ -45,6 +50,14 @@ impl<Info> KeyMap<Info> {
             psks: HashMap::new(),
         }
     }
+
+    /// Create an empty store with space for at least `capacity` publishers identified by public keys.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            ke_pks: HashMap::with_capacity(capacity),
+            psks: HashMap::new(),
+        }
+    }
 }
 
 impl<Info> Default for KeyMap<Info> {
@@ -66,6 +79,8 @@ impl<Info, F: PRP> KeyStore<Info, F> for KeyMap<Info> {
                     .get_key_value(id)
                     .filter(|(_, (x, _))| x.is_some())
                     .map(|(e, (x, _))| (e, x.unwrap().to_vec())),
+                // Viewing keys are not stored
+                Identifier::XPubKey(_pk) => None,
             })
             .collect()
     }
@@ -74,12 +89,14 @@ impl<Info, F: PRP> KeyStore<Info, F> for KeyMap<Info> {
         match id {
             Identifier::EdPubKey(_pk) => self.ke_pks.get(id).map(|(_x, i)| i),
             Identifier::PskId(_id) => self.psks.get(id).map(|(_x, i)| i),
+            Identifier::XPubKey(_pk) => None,
         }
     }
     fn get_mut(&mut self, id: &Identifier) -> Option<&mut Info> {
         match id {
             Identifier::EdPubKey(_pk) => self.ke_pks.get_mut(id).map(|(_x, i)| i),
             Identifier::PskId(_id) => self.psks.get_mut(id).map(|(_x, i)| i),
+            Identifier::XPubKey(_pk) => None,
         }
     }
     fn get_ke_pk(&self, id: &Identifier) -> Option<&x25519::PublicKey> {
@@ -128,6 +145,8 @@ impl<Info, F: PRP> KeyStore<Info, F> for KeyMap<Info> {
                 self.psks.insert(id, (None, info));
                 Ok(())
             }
+            // Holders of a viewing key only read, they are not publishers
+            Identifier::XPubKey(_pk) => err(BadIdentifier),
         }
     }
 
@@ -141,6 +160,16 @@ impl<Info, F: PRP> KeyStore<Info, F> for KeyMap<Info> {
         }
     }
 
+    fn insert_ke_pk(&mut self, id: Identifier, ke_pk: x25519::PublicKey, info: Info) -> Result<()> {
+        match &id {
+            Identifier::EdPubKey(_pk) => {
+                self.ke_pks.insert(id, (ke_pk, info));
+                Ok(())
+            }
+            _ => err(BadIdentifier),
+        }
+    }
+
     fn keys(&self) -> Vec<(&Identifier, Vec<u8>)> {
         let mut keys: Vec<(&Identifier, Vec<u8>)> = self
             .ke_pks
@@ -174,6 +203,11 @@ impl<Info, F: PRP> KeyStore<Info, F> for KeyMap<Info> {
         ke_pks.extend(psks);
         ke_pks
     }
+
+    fn reserve(&mut self, additional: usize) {
+        // Pre-shared keys are few, only publishers identified by public keys are reserved for
+        self.ke_pks.reserve(additional);
+    }
 }
 
 impl<Info: fmt::Display> fmt::Display for KeyMap<Info> {
@@ -0,0 +1,43 @@
+//! Application policies checked against the recipients of received keyloads.
+//!
+//! When a keyload policy is set, the recipients of a keyload the user has access to are checked
+//! against the policy before the keyload is processed. A keyload rejected by the policy fails
+//! with `KeyloadPolicyViolation` and its session key is not used, so the application never acts
+//! on a keyload which silently excludes the expected audience.
+//!
+//! Recipients are identified by their Ed25519 public keys or pre-shared key identifiers.
+
+use iota_streams_app::identifier::Identifier;
+use iota_streams_core::prelude::Vec;
+
+/// Policy deciding whether a keyload with the given recipients is accepted.
+pub trait KeyloadPolicy {
+    /// Check the recipients decoded from a keyload, `false` rejects the keyload.
+    fn accept(&self, recipients: &[Identifier]) -> bool;
+}
+
+impl<P: Fn(&[Identifier]) -> bool> KeyloadPolicy for P {
+    fn accept(&self, recipients: &[Identifier]) -> bool {
+        self(recipients)
+    }
+}
+
+/// Policy accepting keyloads granting access to at least `quorum` of the expected members.
+pub struct Quorum {
+    members: Vec<Identifier>,
+    quorum: usize,
+}
+
+impl Quorum {
+    /// Require `quorum` of `members` to be recipients of accepted keyloads.
+    pub fn new(members: Vec<Identifier>, quorum: usize) -> Self {
+        Self { members, quorum }
+    }
+}
+
+impl KeyloadPolicy for Quorum {
+    fn accept(&self, recipients: &[Identifier]) -> bool {
+        let granted = self.members.iter().filter(|member| recipients.contains(member)).count();
+        granted >= self.quorum
+    }
+}
@@ -1,3 +1,18 @@
+/// Compression of public payloads.
+pub mod compression;
+
+/// Application defined transformation of packet payloads.
+pub mod payload_codec;
+
+/// Policies checked against recipients of received keyloads.
+pub mod keyload_policy;
+
+/// Combinable options of sent signed packets.
+pub mod packet_options;
+
+/// Signatures of signed packets detached from the wrapped message.
+pub mod detached;
+
 /// Identifier Key storage. Used for keeping track of channel state
 pub mod key_store;
 
@@ -0,0 +1,87 @@
+//! Options of signed packets sent with `send_signed_packet_with`.
+//!
+//! Options are independent of each other and can be freely combined, eg. a packet published under
+//! a topic can also carry other metadata entries, be bound to an application context and have its
+//! signature returned detached.
+//!
+//! The context, metadata and application type are sent in plain and are covered by the signature.
+
+use crate::message::metadata::{
+    Metadata,
+    TOPIC_KEY,
+};
+use iota_streams_ddml::types::Bytes;
+
+/// Options of a signed packet, the default options send a plain signed packet.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PacketOptions {
+    pub(crate) context: Option<Bytes>,
+    pub(crate) metadata: Option<Metadata>,
+    pub(crate) app_type: u16,
+    pub(crate) standalone: bool,
+    pub(crate) detached: bool,
+    pub(crate) confirmed: bool,
+}
+
+impl PacketOptions {
+    /// Default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bind the packet to an application context (eg. external request id).
+    pub fn with_context(mut self, context: Bytes) -> Self {
+        self.context = Some(context);
+        self
+    }
+
+    /// Add application metadata entries to the packet, entries with the same key are replaced.
+    pub fn with_metadata(mut self, metadata: &Metadata) -> Self {
+        self.metadata
+            .get_or_insert_with(Metadata::new)
+            .0
+            .extend(metadata.0.clone());
+        self
+    }
+
+    /// Publish the packet under a topic, the topic is carried as the `TOPIC_KEY` metadata entry.
+    /// Readers list packets by topic with `messages_for_topic`.
+    pub fn with_topic(mut self, topic: &str) -> Self {
+        self.metadata
+            .get_or_insert_with(Metadata::new)
+            .insert(TOPIC_KEY, topic.as_bytes());
+        self
+    }
+
+    /// Tag the packet with an application defined content type, 0 is the default type and is not
+    /// encoded. Receivers can route the packet by `MessageContent::app_type` without parsing the
+    /// payloads.
+    pub fn with_app_type(mut self, app_type: u16) -> Self {
+        self.app_type = app_type;
+        self
+    }
+
+    /// Attach the packet to the announcement rather than to a previous message. Its address is
+    /// derived from the announcement and the sequence number of the publisher only, so that it can
+    /// be read given the announcement even if other messages are missing. Standalone packets are not
+    /// found by `sync`, they are retrieved with `fetch_standalone_packets`. Not supported in
+    /// multi-branch channels.
+    pub fn with_standalone(mut self, standalone: bool) -> Self {
+        self.standalone = standalone;
+        self
+    }
+
+    /// Also return the signature detached along with the signed pre-image, see `verify_detached`.
+    pub fn with_detached_signature(mut self, detached: bool) -> Self {
+        self.detached = detached;
+        self
+    }
+
+    /// Await the confirmation of the packet by the transport before the user state is committed.
+    /// The state is not advanced past a packet which failed to confirm, note that the packet itself
+    /// is not retracted from the transport. Not supported within a `transaction`.
+    pub fn with_confirmation(mut self, confirmed: bool) -> Self {
+        self.confirmed = confirmed;
+        self
+    }
+}
@@ -0,0 +1,24 @@
+//! Application defined transformation of packet payloads.
+//!
+//! When a payload codec is set, public and masked payloads of sent signed and tagged packets are
+//! encoded with it before they are wrapped, the payloads of received packets are decoded before
+//! they are returned to the application. The codec can implement an application envelope format,
+//! an additional encryption layer, etc. Unlike compression, the encoded payloads carry no codec
+//! identifier: all the participants of a channel must use the same codec.
+//!
+//! Public payloads are encoded with the codec before they are compressed, and decoded after they
+//! are decompressed.
+
+use iota_streams_core::{
+    prelude::Vec,
+    Result,
+};
+
+/// Transformation of packet payloads.
+pub trait PayloadCodec {
+    /// Encode application bytes into the bytes embedded into a packet.
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>>;
+
+    /// Decode bytes embedded into a received packet back into application bytes.
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>>;
+}
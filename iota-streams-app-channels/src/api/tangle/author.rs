@@ -10,7 +10,13 @@ use crate::api::tangle::{
     User,
 };
 
-use iota_streams_app::identifier::Identifier;
+use iota_streams_app::{
+    identifier::Identifier,
+    message::{
+        ContentUnwrap,
+        GenericMessage,
+    },
+};
 use iota_streams_core::{
     panic_if_not,
     prelude::{
@@ -22,11 +28,15 @@ use iota_streams_core::{
         PskId,
     },
 };
-use iota_streams_core_edsig::signature::ed25519;
+use iota_streams_core_edsig::{
+    key_exchange::x25519,
+    signature::ed25519,
+};
 
 /// Author Object. Contains User API.
+#[derive(Clone)]
 pub struct Author<Trans> {
-    user: User<Trans>,
+    pub(crate) user: User<Trans>,
 }
 
 impl<Trans> Author<Trans> {
@@ -45,6 +55,55 @@ impl<Trans> Author<Trans> {
         Self { user }
     }
 
+    /// Create a new Author instance in a network domain, see `set_network_domain`. Addresses of
+    /// the channel differ from those of the channel created by `new` with the same seed.
+    ///
+    /// # Arguments
+    /// * `seed` - A string slice representing the seed of the user [Characters: A-Z, 9]
+    /// * `domain` - Network domain, eg. distinguishing mainnet and testnet channels
+    /// * `channel_type` - Implementation type: [0: Single Branch, 1: Multi Branch , 2: Single Depth]
+    /// * `transport` - Transport object used for sending and receiving
+    pub fn new_in_network_domain(seed: &str, domain: &[u8], channel_type: ChannelType, transport: Trans) -> Self {
+        let mut user = User::new(seed, channel_type, transport);
+        user.set_network_domain(domain);
+        let channel_idx = 0_u64;
+        let _ = user.user.create_channel(channel_idx);
+        Self { user }
+    }
+
+    /// Create a new Author instance from an existing raw Ed25519 secret key (eg. exported from an
+    /// HSM or another library).
+    ///
+    /// # Arguments
+    /// * `secret` - Ed25519 secret key bytes
+    /// * `channel_type` - Implementation type: [0: Single Branch, 1: Multi Branch , 2: Single Depth]
+    /// * `transport` - Transport object used for sending and receiving
+    pub fn from_ed25519_secret(
+        secret: [u8; ed25519::SECRET_KEY_LENGTH],
+        channel_type: ChannelType,
+        transport: Trans,
+    ) -> Self {
+        let mut user = User::from_ed25519_secret(secret, channel_type, transport);
+        let channel_idx = 0_u64;
+        let _ = user.user.create_channel(channel_idx);
+        Self { user }
+    }
+
+    /// Derive a child Author with an independent key pair and create its channel. Channels of
+    /// children with different indices are independent, all of them can be recovered from the
+    /// author seed and the child indices.
+    ///
+    ///   # Arguments
+    ///   * `index` - Index of the child
+    ///   * `channel_type` - Implementation type: [0: Single Branch, 1: Multi Branch , 2: Single Depth]
+    ///   * `transport` - Transport object used for sending and receiving
+    pub fn derive_child(&self, index: u32, channel_type: ChannelType, transport: Trans) -> Self {
+        let mut user = self.user.derive_child(index, channel_type, transport);
+        let channel_idx = 0_u64;
+        let _ = user.user.create_channel(channel_idx);
+        Self { user }
+    }
+
     /// Returns a clone of the transport object
     pub fn get_transport(&self) -> &Trans {
         self.user.get_transport()
@@ -65,11 +124,126 @@ impl<Trans> Author<Trans> {
         self.user.channel_address()
     }
 
+    /// Addresses of the channels the user has created or joined, least recently connected first.
+    /// The history is kept in backups, eg. to present recent channels to the user.
+    pub fn connection_history(&self) -> impl Iterator<Item = Address> + '_ {
+        self.user.connection_history()
+    }
+
+    /// Limit the number of channels kept in the connection history, the least recently connected
+    /// ones are dropped first (16 by default).
+    ///
+    ///   # Arguments
+    ///   * `max_connection_history` - Maximum number of channels kept
+    pub fn set_max_connection_history(&mut self, max_connection_history: usize) {
+        self.user.set_max_connection_history(max_connection_history)
+    }
+
+    /// Create a new channel with the same keys and make it active. The author sends to a single
+    /// active channel, the previous one is stashed and can be switched back to with `switch_channel`.
+    ///
+    ///   # Arguments
+    ///   * `channel_idx` - Index of the new channel, must differ from indices of other channels
+    pub fn new_channel(&mut self, channel_idx: u64) -> Result<()> {
+        if self.user.is_registered() {
+            self.user.stash_channel()?;
+        }
+        self.user.user.create_channel(channel_idx)
+    }
+
+    /// Create a new channel with the same keys, announced with application metadata, eg. channel
+    /// name or schema version. The metadata is authenticated by the announcement signature. The
+    /// current channel is stashed.
+    ///
+    ///   # Arguments
+    ///   * `channel_idx` - Index of the new channel, must differ from indices of other channels
+    ///   * `metadata` - Channel metadata carried by the announcement
+    pub fn new_channel_with_metadata(&mut self, channel_idx: u64, metadata: &[u8]) -> Result<()> {
+        if self.user.is_registered() {
+            self.user.stash_channel()?;
+        }
+        self.user.user.create_channel_with_metadata(channel_idx, metadata)
+    }
+
+    /// Create a new channel with the same keys, the announcement of which is bound to a context
+    /// shared out-of-band with the subscribers, eg. a secret. The context is not sent: subscribers
+    /// must set it with `Subscriber::set_announcement_context`, subscribers without the context
+    /// cannot validate the announcement, even knowing its address. The current channel is stashed.
+    ///
+    ///   # Arguments
+    ///   * `channel_idx` - Index of the new channel, must differ from indices of other channels
+    ///   * `context` - Context absorbed into the announcement
+    pub fn new_channel_with_context(&mut self, channel_idx: u64, context: &[u8]) -> Result<()> {
+        if self.user.is_registered() {
+            self.user.stash_channel()?;
+        }
+        self.user.user.create_channel_with_context(channel_idx, context)
+    }
+
+    /// Channel metadata carried by the announcement, if any.
+    pub fn announcement_metadata(&self) -> Option<&[u8]> {
+        self.user.announcement_metadata()
+    }
+
+    /// Returns true if the channel was closed with `close_channel`.
+    pub fn is_closed(&self) -> bool {
+        self.user.is_closed()
+    }
+
+    /// Returns true if the channel membership was sealed with `seal_membership`.
+    pub fn is_membership_sealed(&self) -> bool {
+        self.user.is_membership_sealed()
+    }
+
+    /// Create a new channel with the same keys and an index derived from a channel name, so that
+    /// subscribers knowing the author public key and the name can compute the announcement link
+    /// with `announcement_link_from_label`. The current channel is stashed.
+    ///
+    ///   # Arguments
+    ///   * `label` - Name of the new channel
+    pub fn new_channel_named(&mut self, label: &str) -> Result<()> {
+        let channel_idx = ChannelAddress::label_index::<DefaultF>(self.user.get_public_key(), label);
+        self.new_channel(channel_idx)
+    }
+
+    /// Make a previously stashed channel active. The active channel is stashed.
+    ///
+    ///   # Arguments
+    ///   * `channel` - Address of the stashed channel
+    pub fn switch_channel(&mut self, channel: &ChannelAddress) -> Result<()> {
+        self.user.switch_channel(channel)
+    }
+
+    /// Fetch the Addresses of stashed channels.
+    pub fn stashed_channels(&self) -> Vec<&ChannelAddress> {
+        self.user.stashed_channels()
+    }
+
+    /// Take a snapshot of the user state, it can be restored with `restore`.
+    pub fn checkpoint(&self) -> Checkpoint {
+        self.user.checkpoint()
+    }
+
+    /// Restore the user state from a snapshot, discarding all changes made since it was taken.
+    /// Messages sent in the meantime are not retracted from the transport.
+    ///
+    ///   # Arguments
+    ///   * `checkpoint` - Snapshot taken with `checkpoint`
+    pub fn restore(&mut self, checkpoint: Checkpoint) {
+        self.user.restore(checkpoint)
+    }
+
     /// Fetch the user ed25519 public key
     pub fn get_public_key(&self) -> &ed25519::PublicKey {
         self.user.get_public_key()
     }
 
+    /// Fetch the user x25519 viewing key. Readers share it with the author to be granted read
+    /// access with `Author::send_keyload_for_viewers`, without being known as publishers.
+    pub fn viewing_key(&self) -> &x25519::PublicKey {
+        self.user.viewing_key()
+    }
+
     /// Store a PSK in the user instance
     ///
     ///   # Arguments
@@ -79,6 +253,12 @@ impl<Trans> Author<Trans> {
         self.user.store_psk(pskid, psk, false)
     }
 
+    /// Fetch the identifiers of the stored pre-shared keys in ascending order, eg. to select
+    /// `psk_ids` of a keyload.
+    pub fn psk_ids(&self) -> Vec<PskId> {
+        self.user.psk_ids()
+    }
+
     /// Generate a vector containing the next sequenced message identifier for each publishing
     /// participant in the channel
     ///
@@ -88,6 +268,15 @@ impl<Trans> Author<Trans> {
         self.user.gen_next_msg_ids(branching)
     }
 
+    /// Address of the next message expected from the publisher: the next message itself in
+    /// single-branch channels, the sequence message referencing it in multi-branch channels.
+    ///
+    ///   # Arguments
+    ///   * `publisher` - Identifier of the publisher
+    pub fn next_address(&self, publisher: &Identifier) -> Result<Address> {
+        self.user.next_address(publisher)
+    }
+
     /// Stores the provided link to the internal sequencing state for the provided participant
     /// [Used for multi-branching sequence state updates]
     ///
@@ -108,6 +297,15 @@ impl<Trans> Author<Trans> {
         self.user.store_state_for_all(link, seq_num)
     }
 
+    /// Compare the channel state with that of a subscriber to find where their views diverge, eg.
+    /// why the subscriber sees fewer messages than expected. No key material is reported.
+    ///
+    ///   # Arguments
+    ///   * `subscriber` - Subscriber to compare the state with
+    pub fn diff<T>(&self, subscriber: &Subscriber<T>) -> StateDiff {
+        self.user.diff(&subscriber.user)
+    }
+
     /// Fetches the latest PublicKey -> Cursor state mapping from the implementation, allowing the
     /// user to see the latest messages present from each publisher
     pub fn fetch_state(&self) -> Result<Vec<(String, Cursor<Address>)>> {
@@ -119,6 +317,69 @@ impl<Trans> Author<Trans> {
         Ok(state)
     }
 
+    /// Fetch the address of the latest processed message of each publisher, eg. to display the
+    /// latest message from each writer. Publishers with no message beyond the announcement are
+    /// skipped, the result is ordered by publisher identifier.
+    pub fn heads(&self) -> Result<Vec<(Identifier, Address)>> {
+        self.user.heads()
+    }
+
+    /// Takes a lightweight snapshot of the sequence state of each publisher, without link store
+    /// and keys, allowing to persist sync progress separately of a full backup
+    pub fn cursor_snapshot(&self) -> Vec<(Identifier, SeqState)> {
+        self.user.cursor_snapshot()
+    }
+
+    /// Restores sequence states from a snapshot, link store and keys are left untouched
+    ///
+    ///   # Arguments
+    ///   * `snapshot` - Sequence states previously taken with `cursor_snapshot`
+    pub fn load_cursor_snapshot(&mut self, snapshot: Vec<(Identifier, SeqState)>) -> Result<()> {
+        self.user.load_cursor_snapshot(snapshot)
+    }
+
+    /// Takes a cursor snapshot authenticated with a key derived from the password, it can be
+    /// stored on untrusted infrastructure
+    ///
+    ///   # Arguments
+    ///   * `pwd` - Password protecting the snapshot
+    pub fn sealed_cursor_snapshot(&self, pwd: &str) -> Result<Vec<u8>> {
+        self.user.sealed_cursor_snapshot(pwd)
+    }
+
+    /// Verifies a sealed cursor snapshot and restores its sequence states, a tampered snapshot is
+    /// rejected
+    ///
+    ///   # Arguments
+    ///   * `bytes` - Snapshot taken with `sealed_cursor_snapshot`
+    ///   * `pwd` - Password protecting the snapshot
+    pub fn load_sealed_cursor_snapshot(&mut self, bytes: &[u8], pwd: &str) -> Result<()> {
+        self.user.load_sealed_cursor_snapshot(bytes, pwd)
+    }
+
+    /// Current position in the channel, just after the last processed message
+    pub fn position(&self) -> Position {
+        self.user.position()
+    }
+
+    /// Continue processing messages just after the position. Positions carry sequence states only,
+    /// they can be saved after each processed message instead of backing up the whole state.
+    ///
+    ///   # Arguments
+    ///   * `position` - Position returned along with a processed message or by `position`
+    pub fn resume_from(&mut self, position: &Position) -> Result<()> {
+        self.user.resume_from(position)
+    }
+
+    /// Create an encoded invitation to the channel for a subscriber, it carries the announcement
+    /// address and the author public keys only.
+    ///
+    ///   # Arguments
+    ///   * `psk` - Id of a stored PSK shared with the subscriber out of band
+    pub fn create_invitation(&self, psk: Option<PskId>) -> Result<Vec<u8>> {
+        self.user.create_invitation(psk)
+    }
+
     /// Serialize user state and encrypt it with password.
     ///
     ///   # Arguments
@@ -136,10 +397,439 @@ impl<Trans> Author<Trans> {
     pub fn import(bytes: &[u8], pwd: &str, tsp: Trans) -> Result<Self> {
         User::<Trans>::import(bytes, 0, pwd, tsp).map(|user| Self { user })
     }
+
+    /// Export the public state of the channel: the channel address, author keys, spongos states
+    /// of processed messages and publisher cursors. Observers import it with
+    /// `Subscriber::import_public_state` to read the channel without the author secret key.
+    /// Spongos states let observers read masked payloads linked to the processed messages.
+    pub fn export_public_state(&self) -> Result<Vec<u8>> {
+        self.user.export_public_state()
+    }
+
+    /// Re-encrypt serialized user state with a new password, without restoring it.
+    ///
+    ///   # Arguments
+    ///   * `bytes` - Encrypted serialized user state
+    ///   * `old_pwd` - Password the state is encrypted with
+    ///   * `new_pwd` - New encryption password
+    pub fn rekey_backup(bytes: &[u8], old_pwd: &str, new_pwd: &str) -> Result<Vec<u8>> {
+        User::<Trans>::rekey_backup(bytes, 0, old_pwd, new_pwd)
+    }
+
+    /// Check serialized user state can be restored with the password, without restoring it, and
+    /// get a summary of the state.
+    ///
+    ///   # Arguments
+    ///   * `bytes` - Encrypted serialized user state
+    ///   * `pwd` - Encryption password
+    pub fn verify_backup(bytes: &[u8], pwd: &str) -> Result<BackupInfo> {
+        User::<Trans>::verify_backup(bytes, 0, pwd)
+    }
+
+    /// Set the length of the MAC protecting exported user state.
+    ///
+    ///   # Arguments
+    ///   * `mac_len` - MAC length in bytes [16-64]
+    pub fn set_backup_mac_len(&mut self, mac_len: usize) -> Result<()> {
+        self.user.set_backup_mac_len(mac_len)
+    }
+
+    /// Attach the fingerprint of the linked message state to sent messages, so that readers
+    /// joined to a wrong state get a distinct error. Such messages can't be read by older versions.
+    ///
+    ///   # Arguments
+    ///   * `link_fingerprint` - Whether the fingerprint is attached
+    pub fn set_link_fingerprint(&mut self, link_fingerprint: bool) {
+        self.user.set_link_fingerprint(link_fingerprint)
+    }
+
+    /// Network domain message addresses are derived in, empty by default.
+    pub fn network_domain(&self) -> &[u8] {
+        self.user.network_domain()
+    }
+
+    /// Limit the length of payloads accepted in received signed and tagged packets. Declared
+    /// lengths are checked before allocation, so oversized messages are rejected cheaply.
+    ///
+    ///   # Arguments
+    ///   * `max_payload_length` - Maximum length in bytes of public and masked payloads
+    pub fn set_max_payload_length(&mut self, max_payload_length: usize) {
+        self.user.set_max_payload_length(max_payload_length)
+    }
+
+    /// Limit the size of message bodies (not limited by default). Sending a larger message fails
+    /// with `MessageSizeExceeded` before its buffer is allocated, received messages larger than the
+    /// limit are rejected before being unwrapped.
+    ///
+    ///   # Arguments
+    ///   * `max_message_size` - Maximum size in bytes of a message body
+    pub fn set_max_message_size(&mut self, max_message_size: usize) {
+        self.user.set_max_message_size(max_message_size)
+    }
+
+    /// Limit the number of recipients, subscribers and pre-shared keys, of sent keyloads (not limited
+    /// by default). A keyload for more recipients fails with `TooManySubscribers` before anything is
+    /// sent, including `send_keyload_for_everyone` when more subscribers are known than the limit;
+    /// recipients then have to be split across several `send_keyload` calls.
+    ///
+    ///   # Arguments
+    ///   * `max_keyload_subscribers` - Maximum number of recipients of a keyload
+    pub fn set_max_keyload_subscribers(&mut self, max_keyload_subscribers: usize) {
+        self.user.set_max_keyload_subscribers(max_keyload_subscribers)
+    }
+
+    /// Compress public payloads of sent signed and tagged packets and decompress the received
+    /// ones. Compressed payloads are signalled in the message header: a receiver without a
+    /// compressor, or with another codec, gets an error instead of a corrupted payload.
+    ///
+    ///   # Arguments
+    ///   * `compressor` - Payload codec, eg. `NoCompression` or `Deflate`, `None` disables compression (default)
+    pub fn set_compressor<C: Compressor + 'static>(&mut self, compressor: Option<C>) {
+        self.user.set_compressor(compressor)
+    }
+
+    /// Encode public and masked payloads of sent signed and tagged packets with an application
+    /// codec, eg. an envelope format or an encryption layer, and decode the received ones. All the
+    /// participants of the channel must use the same codec.
+    ///
+    ///   # Arguments
+    ///   * `payload_codec` - Payload transformation, `None` disables it (default)
+    pub fn set_payload_codec<C: PayloadCodec + 'static>(&mut self, payload_codec: Option<C>) {
+        self.user.set_payload_codec(payload_codec)
+    }
+
+    /// Check recipients of received keyloads against an application policy, eg. a `Quorum` of
+    /// expected members, before they are processed. A keyload rejected by the policy fails with
+    /// an error and its session key is not used.
+    ///
+    ///   # Arguments
+    ///   * `keyload_policy` - Policy over keyload recipients, `None` accepts any keyload (default)
+    pub fn set_keyload_policy<P: KeyloadPolicy + 'static>(&mut self, keyload_policy: Option<P>) {
+        self.user.set_keyload_policy(keyload_policy)
+    }
+
+    /// Sign sent signed packets with `signer` instead of the user key pair, eg. a signer backed by
+    /// an HSM or a remote KMS. Packets are verified with and attributed to the public key of the
+    /// signer, other messages are still signed with the user key pair.
+    ///
+    ///   # Arguments
+    ///   * `signer` - Signer of sent signed packets, `None` restores the user key pair (default)
+    pub fn set_signer<S: Signer + 'static>(&mut self, signer: Option<S>) {
+        self.user.set_signer(signer)
+    }
+
+    /// Derive nonces, session keys and ephemeral keys of sent messages from the seed, the message
+    /// links and contents instead of random generation, so replayed messages are byte-identical. Meant for
+    /// tests and deduplicating storage: deterministic randomness weakens unlinkability and must not be
+    /// used for privacy-sensitive channels.
+    ///
+    ///   # Arguments
+    ///   * `seed` - Secret seed of the randomness, `None` restores random generation (default)
+    pub fn set_deterministic_seed(&mut self, seed: Option<&str>) {
+        self.user.set_deterministic_seed(seed)
+    }
+
+    /// Split masked payloads of sent signed and tagged packets into blocks, each authenticated with
+    /// its own MAC. A truncated or corrupted packet is rejected at the first bad block. Each block
+    /// costs a 16 byte MAC plus its encoded length; splitting is signalled in the message header,
+    /// receivers need no setting.
+    ///
+    ///   # Arguments
+    ///   * `block_size` - Length of blocks in bytes, `None` disables splitting (default)
+    pub fn set_masked_block_size(&mut self, block_size: Option<usize>) -> Result<()> {
+        self.user.set_masked_block_size(block_size)
+    }
+
+    /// Authenticate sent tagged packets with MACs of the given length instead of 32 bytes. The
+    /// length is carried in the messages, receivers need no setting. Shorter MACs save bandwidth
+    /// but are easier to forge: a 16 byte MAC leaves 128 bits of security against forgery.
+    ///
+    ///   # Arguments
+    ///   * `mac_length` - Length of MACs in bytes, one of 16, 24 or 32 (default)
+    pub fn set_tag_mac_length(&mut self, mac_length: usize) -> Result<()> {
+        self.user.set_tag_mac_length(mac_length)
+    }
+
+    /// Number of message spongos states kept by the user
+    pub fn spongos_store_len(&self) -> usize {
+        self.user.spongos_store_len()
+    }
+
+    /// Digest committing to all the processed messages of the channel, eg. to anchor the channel
+    /// integrity in an external system. Users with the same view of the channel get the same
+    /// digest, it changes when a message is added, removed or altered.
+    pub fn chain_digest(&self) -> [u8; 32] {
+        self.user.chain_digest()
+    }
+
+    /// Pairs of processed messages and the messages they are linked to, eg. to render the message
+    /// graph or find orphaned subtrees. Edges of messages restored from a backup are not known.
+    pub fn dependency_edges(&self) -> Vec<(MsgId, MsgId)> {
+        self.user.dependency_edges()
+    }
+
+    /// Recipients, public keys and pre-shared key identifiers, of the keyload recorded when it was
+    /// sent or processed. Recipients are kept in backups. `None` if the keyload is unknown
+    /// or could not be accessed.
+    ///
+    ///   # Arguments
+    ///   * `keyload` - Message identifier of the keyload
+    pub fn keyload_recipients(&self, keyload: &MsgId) -> Option<Vec<Identifier>> {
+        self.user.keyload_recipients(keyload)
+    }
+
+    /// Addresses of processed signed packets published under the topic, in processing order.
+    /// Packets without a topic are listed under `DEFAULT_TOPIC`.
+    ///
+    ///   # Arguments
+    ///   * `topic` - Topic of the packets
+    pub fn messages_for_topic(&self, topic: &str) -> Vec<Address> {
+        self.user.messages_for_topic(topic)
+    }
+
+    /// Highest position, sequence number of the acknowledged message, acknowledged by the
+    /// subscriber in processed ack messages. Acks processed out of order do not lower it.
+    ///
+    ///   # Arguments
+    ///   * `subscriber` - Signature public key of the subscriber
+    pub fn acked_position(&self, subscriber: &ed25519::PublicKey) -> Option<usize> {
+        self.user.acked_position(subscriber)
+    }
+
+    /// Stream message spongos states to external storage as they are stored, instead of
+    /// exporting the whole user state. States restored with `import` are not reported.
+    ///
+    /// The callback is invoked synchronously before the state is stored; a panic in the callback
+    /// propagates to the caller of the send or receive method and the state is not stored.
+    ///
+    ///   # Arguments
+    ///   * `callback` - Callback invoked with message id, spongos state and info, `None` removes it
+    pub fn set_spongos_store_callback(&mut self, callback: Option<SpongosStoreCallback>) {
+        self.user.set_spongos_store_callback(callback)
+    }
+
+    /// Register subscribers with their key exchange public keys without processing their subscribe
+    /// messages, eg. to reconstruct the subscriber set of a channel restored from external records.
+    ///
+    ///   # Arguments
+    ///   * `subscribers` - Identifiers of subscribers together with their x25519 public keys
+    pub fn add_subscribers<I>(&mut self, subscribers: I) -> Result<()>
+    where
+        I: IntoIterator<Item = (Identifier, x25519::PublicKey)>,
+    {
+        self.user.add_subscribers(subscribers)
+    }
+
+    /// Add a subscriber to a named group of keyload recipients, the group is created if needed.
+    ///
+    ///   # Arguments
+    ///   * `group` - Name of the group
+    ///   * `id` - Identifier of a known subscriber or of a stored pre-shared key
+    pub fn add_to_group(&mut self, group: &str, id: Identifier) -> Result<()> {
+        self.user.add_to_group(group, id)
+    }
+
+    /// Remove a subscriber from a named group, empty groups are deleted. Returns true if the
+    /// subscriber was a member of the group.
+    ///
+    ///   # Arguments
+    ///   * `group` - Name of the group
+    ///   * `id` - Identifier of the subscriber
+    pub fn remove_from_group(&mut self, group: &str, id: &Identifier) -> bool {
+        self.user.remove_from_group(group, id)
+    }
+
+    /// Members of the named group of keyload recipients, `None` if the group is not defined.
+    pub fn group_members(&self, group: &str) -> Option<&[Identifier]> {
+        self.user.group_members(group)
+    }
+
+    /// Names of the defined groups of keyload recipients.
+    pub fn subscriber_groups(&self) -> Vec<&str> {
+        self.user.subscriber_groups()
+    }
+
+    /// Verify signature of a signed packet without processing it and get the identifier of its
+    /// publisher. The state of the user is not changed.
+    ///
+    ///   # Arguments
+    ///   * `msg` - Signed packet message, the message it is linked to must have been processed
+    pub fn verify_message(&self, msg: &Message) -> Result<Identifier> {
+        self.user.verify_message(msg)
+    }
+
+    /// Reject signed packets published by identifiers unknown to the user. Publishers become known
+    /// when they are included in a processed keyload, subscribe to the channel or announce it.
+    ///
+    ///   # Arguments
+    ///   * `enforce_known_publishers` - Whether packets from unknown publishers are rejected
+    pub fn set_enforce_known_publishers(&mut self, enforce_known_publishers: bool) {
+        self.user.set_enforce_known_publishers(enforce_known_publishers)
+    }
+
+    /// Reject signed and tagged packets of single-branch channels whose sequence number isn't the
+    /// next one expected from their publisher, so that missing messages are reported with
+    /// `SequenceGap` instead of being skipped. The user state is left unchanged.
+    ///
+    ///   # Arguments
+    ///   * `strict_sequencing` - Whether packets breaking the sequence are rejected
+    pub fn set_strict_sequencing(&mut self, strict_sequencing: bool) {
+        self.user.set_strict_sequencing(strict_sequencing)
+    }
+
+    /// Unmask the payloads of signed packets of the given topics only. Packets of other topics are
+    /// still verified and processed, so that messages linked to them can be read, but their masked
+    /// payload is skipped and returned empty.
+    ///
+    ///   # Arguments
+    ///   * `followed_topics` - Topics whose masked payloads are read, all topics if `None`
+    pub fn set_followed_topics(&mut self, followed_topics: Option<Vec<String>>) {
+        self.user.set_followed_topics(followed_topics)
+    }
+
+    /// Wrap sent messages into a buffer of the size of the last message with the same content type,
+    /// skipping the sizeof pass when sizes are fixed, eg. keyloads to a fixed set of subscribers. A message of a
+    /// different size is wrapped twice.
+    ///
+    ///   # Arguments
+    ///   * `wrap_size_hints` - Whether sizes of sent messages are cached as hints
+    pub fn set_wrap_size_hints(&mut self, wrap_size_hints: bool) {
+        self.user.set_wrap_size_hints(wrap_size_hints)
+    }
+
+    /// Application defined label of the user, kept in backups.
+    pub fn label(&self) -> Option<&str> {
+        self.user.label()
+    }
+
+    /// Set or clear the label of the user, it is not shared with other participants.
+    ///
+    ///   # Arguments
+    ///   * `label` - Label of the user, `None` to clear it
+    pub fn set_label(&mut self, label: Option<String>) {
+        self.user.set_label(label)
+    }
+
+    /// Limit the number of orphaned messages buffered by `sync`, it guards against a transport
+    /// feeding messages linked to messages that never show up.
+    ///
+    ///   # Arguments
+    ///   * `max_orphans` - Maximum number of buffered orphans, 1024 by default
+    ///   * `policy` - Whether to fail or to drop the oldest orphan beyond the limit
+    pub fn set_max_orphans(&mut self, max_orphans: usize, policy: OrphanPolicy) {
+        self.user.set_max_orphans(max_orphans, policy)
+    }
+
+    /// Report the progress of `sync` against an estimate of the number of new messages, the
+    /// estimate fetches new messages once more unless `discover_messages` was called.
+    ///
+    ///   # Arguments
+    ///   * `callback` - Callback invoked with the progress, `None` to disable reporting
+    ///   * `interval` - Number of processed messages between reports
+    pub fn set_sync_progress_callback(&mut self, callback: Option<SyncProgressCallback>, interval: usize) {
+        self.user.set_sync_progress_callback(callback, interval)
+    }
+
+    /// Set the number of consecutive rounds without new messages after which `sync_to_head`
+    /// stops.
+    ///
+    ///   # Arguments
+    ///   * `max_misses` - Number of rounds, at least 1 and 3 by default
+    pub fn set_sync_max_misses(&mut self, max_misses: usize) {
+        self.user.set_sync_max_misses(max_misses)
+    }
+
+    /// Size in bytes of a signed packet with the given payloads, nothing is wrapped or sent.
+    /// Sequence message sent in multi-branch channels is not included.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet would be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    pub fn estimate_signed_packet_size(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+    ) -> Result<usize> {
+        self.user
+            .estimate_signed_packet_size(link_to, public_payload, masked_payload)
+    }
+
+    /// Size in bytes of a tagged packet with the given payloads, nothing is wrapped or sent.
+    /// Sequence message sent in multi-branch channels is not included.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet would be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    pub fn estimate_tagged_packet_size(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+    ) -> Result<usize> {
+        self.user
+            .estimate_tagged_packet_size(link_to, public_payload, masked_payload)
+    }
+
+    /// Size in bytes of a keyload for a list of recipients, nothing is wrapped or sent.
+    /// Sequence message sent in multi-branch channels is not included.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload would be attached to
+    ///  * `psk_ids` - Vector of Pre-shared key ids to be included in message
+    ///  * `ke_pks` - Vector of Public Keys to be included in message
+    pub fn estimate_keyload_size(
+        &mut self,
+        link_to: &Address,
+        psk_ids: &PskIds,
+        ke_pks: &Vec<&Identifier>,
+    ) -> Result<usize> {
+        self.user.estimate_keyload_size(link_to, psk_ids, ke_pks)
+    }
+
+    /// Size in bytes of a keyload for all known subscribers, nothing is wrapped or sent.
+    /// Sequence message sent in multi-branch channels is not included.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload would be attached to
+    pub fn estimate_keyload_for_everyone_size(&mut self, link_to: &Address) -> Result<usize> {
+        self.user.estimate_keyload_for_everyone_size(link_to)
+    }
+
+    /// Deserialize user state and decrypt it with password, checking it was exported with the given
+    /// MAC length. The length is stored in the backup, `import` needs no setting.
+    ///
+    ///   # Arguments
+    ///   * `bytes` - Encrypted serialized user state
+    ///   * `pwd` - Encryption password
+    ///   * `mac_len` - Expected MAC length in bytes
+    ///   * `tsp` - Transport object
+    pub fn import_with_mac_len(bytes: &[u8], pwd: &str, mac_len: usize, tsp: Trans) -> Result<Self> {
+        User::<Trans>::import_with_mac_len(bytes, 0, pwd, mac_len, tsp).map(|user| Self { user })
+    }
 }
 
 #[cfg(not(feature = "async"))]
 impl<Trans: Transport + Clone> Author<Trans> {
+    /// Run a group of operations as a transaction: messages sent by `f` are only published once it
+    /// succeeds. If `f` fails, nothing is published and the user state is restored to that before
+    /// the call.
+    ///
+    ///   # Arguments
+    ///   * `f` - Operations to run, the first error aborts the transaction
+    pub fn transaction<R>(&mut self, f: impl FnOnce(&mut Self) -> Result<R>) -> Result<R> {
+        let scope = self.user.begin_transaction();
+        let result = f(self);
+        self.user.end_transaction(scope, result)
+    }
+
+    /// Publish the messages of a succeeded transaction which could not be published
+    pub fn publish_pending(&mut self) -> Result<()> {
+        self.user.publish_pending()
+    }
+
     /// Generates a new Author implementation from input. If the announcement message generated by
     /// this instance matches that of an existing (and provided) announcement link, the user will
     /// sync to the latest state
@@ -167,12 +857,27 @@ impl<Trans: Transport + Clone> Author<Trans> {
         self.user.send_announce()
     }
 
+    /// Send an announcement message followed by an initial keyload linked to it. Returns the
+    /// links of the announcement, the keyload and the optional keyload sequence message.
+    ///
+    ///  # Arguments
+    ///  * `psk_ids` - Vector of Pre-shared key ids to be included in the keyload
+    ///  * `ke_pks`  - Vector of Public Keys to be included in the keyload
+    pub fn send_announce_with_keyload(
+        &mut self,
+        psk_ids: &PskIds,
+        ke_pks: &Vec<&Identifier>,
+    ) -> Result<(Address, Address, Option<Address>)> {
+        self.user.send_announce_with_keyload(psk_ids, ke_pks)
+    }
+
     /// Create and send a new keyload for a list of subscribers.
     ///
     ///  # Arguments
     ///  * `link_to` - Address of the message the keyload will be attached to
     ///  * `psk_ids` - Vector of Pre-shared key ids to be included in message
-    ///  * `ke_pks`  - Vector of Public Keys to be included in message
+    ///  * `ke_pks`  - Vector of Public Keys to be included in message, viewing keys of readers are
+    ///    included as `Identifier::XPubKey`
     pub fn send_keyload(
         &mut self,
         link_to: &Address,
@@ -182,42 +887,226 @@ impl<Trans: Transport + Clone> Author<Trans> {
         self.user.send_keyload(link_to, psk_ids, ke_pks)
     }
 
+    /// Create and send a new keyload shared with holders of the viewing keys only. Viewers can
+    /// read the protected masked payloads but can't publish, and no cursor is kept for them.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `viewing_keys` - Viewing keys of the readers
+    pub fn send_keyload_for_viewers(
+        &mut self,
+        link_to: &Address,
+        viewing_keys: &[x25519::PublicKey],
+    ) -> Result<(Address, Option<Address>)> {
+        self.user.send_keyload_for_viewers(link_to, viewing_keys)
+    }
+
+    /// Create and send a new keyload for a list of subscribers unless the last keyload sent to
+    /// the channel had the same recipients. Returns `None` if the keyload was skipped.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `psk_ids` - Vector of Pre-shared key ids to be included in message
+    ///  * `ke_pks`  - Vector of Public Keys to be included in message
+    pub fn send_keyload_if_changed(
+        &mut self,
+        link_to: &Address,
+        psk_ids: &PskIds,
+        ke_pks: &Vec<&Identifier>,
+    ) -> Result<Option<(Address, Option<Address>)>> {
+        self.user.send_keyload_if_changed(link_to, psk_ids, ke_pks)
+    }
+
+    /// Create and send keyloads for a list of subscribers split into keyloads of at most
+    /// `max_per_message` recipients, eg. when all of them don't fit into a single transport message.
+    /// All the keyloads are attached to `link_to` and can be opened independently, each subscriber
+    /// gains access with the keyload it is included in. Packets are readable by the recipients of
+    /// the keyload they are linked to.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyloads will be attached to
+    ///  * `psk_ids` - Vector of Pre-shared key ids to be included in the first keyload
+    ///  * `ke_pks`  - Vector of Public Keys to be split across the keyloads
+    ///  * `max_per_message` - Maximum number of public keys per keyload
+    pub fn send_keyload_split(
+        &mut self,
+        link_to: &Address,
+        psk_ids: &PskIds,
+        ke_pks: &Vec<&Identifier>,
+        max_per_message: usize,
+    ) -> Result<Vec<(Address, Option<Address>)>> {
+        self.user
+            .send_keyload_split(link_to, psk_ids, ke_pks, max_per_message)
+    }
+
+    /// Create and send keyload for the members of a group of subscribers, see `add_to_group`.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `group` - Name of the group of recipients
+    pub fn send_keyload_for_group(&mut self, link_to: &Address, group: &str) -> Result<(Address, Option<Address>)> {
+        self.user.send_keyload_for_group(link_to, group)
+    }
+
     /// Create and send keyload for all subscribed subscribers.
     ///
     ///  # Arguments
     ///  * `link_to` - Address of the message the keyload will be attached to
-    pub fn send_keyload_for_everyone(&mut self, link_to: &Address) -> Result<(Address, Option<Address>)> {
-        self.user.send_keyload_for_everyone(link_to)
+    pub fn send_keyload_for_everyone(&mut self, link_to: &Address) -> Result<(Address, Option<Address>)> {
+        self.user.send_keyload_for_everyone(link_to)
+    }
+
+    /// Create and send keyload for all subscribed subscribers unless the last keyload sent to the
+    /// channel had the same recipients. Returns `None` if the keyload was skipped.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    pub fn send_keyload_for_everyone_if_changed(
+        &mut self,
+        link_to: &Address,
+    ) -> Result<Option<(Address, Option<Address>)>> {
+        self.user.send_keyload_for_everyone_if_changed(link_to)
+    }
+
+    /// Create and send a chain of signed packets, each one linked to the previous one. The packets
+    /// are wrapped before any of them is sent, the user state is restored if the batch fails.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the first packet will be attached to
+    ///  * `payloads` - Public and masked payloads of the packets
+    pub fn send_signed_packets<I>(&mut self, link_to: &Address, payloads: I) -> Result<Vec<(Address, Option<Address>)>>
+    where
+        I: IntoIterator<Item = (Bytes, Bytes)>,
+    {
+        self.user.send_signed_packets(link_to, payloads)
+    }
+
+    /// Create and send a close message marking the end of the channel. Subscribers processing it
+    /// expect no new messages in the channel.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the close message will be attached to
+    pub fn close_channel(&mut self, link_to: &Address) -> Result<(Address, Option<Address>)> {
+        self.user.send_close(link_to)
+    }
+
+    /// Create and send a message sealing the channel membership: subscriptions of new subscribers
+    /// are rejected afterwards, unlike `close_channel` messages are still published to the
+    /// existing subscribers.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the seal membership message will be attached to
+    pub fn seal_membership(&mut self, link_to: &Address) -> Result<(Address, Option<Address>)> {
+        self.user.send_seal_membership(link_to)
+    }
+
+    /// Create and send a checkpoint summarizing the channel up to the `link_to` message, so that
+    /// new subscribers can start reading the channel from the checkpoint instead of replaying the
+    /// whole history. Only single-branch channels support checkpoints, the following messages
+    /// should be linked to the checkpoint.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the last message summarized by the checkpoint
+    pub fn publish_checkpoint(&mut self, link_to: &Address) -> Result<(Address, Option<Address>)> {
+        self.user.publish_checkpoint(link_to)
+    }
+
+    /// Create and send a message adding an author to the channel, eg. to co-publish keyloads in a
+    /// federated channel. Keyloads and close messages signed by the added author are accepted by
+    /// users processing the message.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the add author message will be attached to
+    ///  * `author_pk` - Signature public key of the added author
+    pub fn add_author(&mut self, link_to: &Address, author_pk: &ed25519::PublicKey) -> Result<(Address, Option<Address>)> {
+        self.user.send_add_author(link_to, author_pk)
+    }
+
+    /// Create and send a signed packet.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    pub fn send_signed_packet(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+    ) -> Result<(Address, Option<Address>)> {
+        self.user.send_signed_packet(link_to, public_payload, masked_payload)
+    }
+
+    /// Create and send a signed packet with the given options, see `PacketOptions`. The detached
+    /// signature is returned if requested by the options.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    ///  * `options` - Options of the packet
+    pub fn send_signed_packet_with(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        options: &PacketOptions,
+    ) -> Result<((Address, Option<Address>), Option<DetachedSignature>)> {
+        self.user
+            .send_signed_packet_with(link_to, public_payload, masked_payload, options)
+    }
+
+    /// Create and send a tagged packet.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    pub fn send_tagged_packet(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+    ) -> Result<(Address, Option<Address>)> {
+        self.user.send_tagged_packet(link_to, public_payload, masked_payload)
     }
 
-    /// Create and send a signed packet.
+    /// Create and send a tagged packet bound to an application context. The context is sent in
+    /// plain and is covered by the MAC.
     ///
     ///  # Arguments
-    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `link_to` - Address of the message the packet will be attached to
     ///  * `public_payload` - Wrapped vector of Bytes to have public access
     ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
-    pub fn send_signed_packet(
+    ///  * `context` - Application context (eg. external request id)
+    pub fn send_tagged_packet_with_context(
         &mut self,
         link_to: &Address,
         public_payload: &Bytes,
         masked_payload: &Bytes,
+        context: &Bytes,
     ) -> Result<(Address, Option<Address>)> {
-        self.user.send_signed_packet(link_to, public_payload, masked_payload)
+        self.user
+            .send_tagged_packet_with_context(link_to, public_payload, masked_payload, context)
     }
 
-    /// Create and send a tagged packet.
+    /// Create and send a tagged packet tagged with an application type. The type is sent in plain
+    /// and is covered by the MAC.
     ///
     ///  # Arguments
-    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `link_to` - Address of the message the packet will be attached to
     ///  * `public_payload` - Wrapped vector of Bytes to have public access
     ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
-    pub fn send_tagged_packet(
+    ///  * `app_type` - Application defined content type, 0 is the default type
+    pub fn send_tagged_packet_with_app_type(
         &mut self,
         link_to: &Address,
         public_payload: &Bytes,
         masked_payload: &Bytes,
+        app_type: u16,
     ) -> Result<(Address, Option<Address>)> {
-        self.user.send_tagged_packet(link_to, public_payload, masked_payload)
+        self.user
+            .send_tagged_packet_with_app_type(link_to, public_payload, masked_payload, app_type)
     }
 
     /// Receive and process a subscribe message.
@@ -228,6 +1117,15 @@ impl<Trans: Transport + Clone> Author<Trans> {
         self.user.receive_subscribe(link)
     }
 
+    /// Receive and process a subscribe message, get the subscriber identifier and the
+    /// subscription payload, empty if the subscriber sent none.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    pub fn receive_subscribe_with_payload(&mut self, link: &Address) -> Result<(Identifier, Bytes)> {
+        self.user.receive_subscribe_with_payload(link)
+    }
+
     /// Receive and process a signed packet message.
     ///
     ///  # Arguments
@@ -236,6 +1134,15 @@ impl<Trans: Transport + Clone> Author<Trans> {
         self.user.receive_signed_packet(link)
     }
 
+    /// Receive and process an ack message of a subscriber, the acknowledged position is recorded.
+    /// Returns the public key of the subscriber and the acknowledged position.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    pub fn receive_ack(&mut self, link: &Address) -> Result<(ed25519::PublicKey, usize)> {
+        self.user.receive_ack(link)
+    }
+
     /// Receive and process a tagged packet message.
     ///
     ///  # Arguments
@@ -244,6 +1151,18 @@ impl<Trans: Transport + Clone> Author<Trans> {
         self.user.receive_tagged_packet(link)
     }
 
+    /// Receive and process a message with application defined content, see `User::handle_custom`.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///  * `content` - Content used to unwrap the message
+    pub fn handle_custom<C>(&mut self, link: &Address, content: C) -> Result<GenericMessage<Address, C>>
+    where
+        C: ContentUnwrap<DefaultF, LinkStore>,
+    {
+        self.user.handle_custom(link, content)
+    }
+
     /// Receive and process a sequence message.
     ///
     ///  # Arguments
@@ -257,6 +1176,40 @@ impl<Trans: Transport + Clone> Author<Trans> {
         self.user.fetch_next_msgs()
     }
 
+    /// Retrieves and processes standalone packets of other publishers, skipping up to `max_gap`
+    /// consecutive missing packets of each publisher.
+    pub fn fetch_standalone_packets(&mut self, max_gap: usize) -> Result<Vec<UnwrappedMessage>> {
+        self.user.fetch_standalone_packets(max_gap)
+    }
+
+    /// Retrieves the next message for each user along with the position just after the message
+    pub fn fetch_next_msgs_with_positions(&mut self) -> Vec<(UnwrappedMessage, Position)> {
+        self.user.fetch_next_msgs_with_positions()
+    }
+
+    /// Counts the messages available at the next expected address of each user without processing them
+    pub fn pending_count(&mut self) -> Result<usize> {
+        self.user.pending_count()
+    }
+
+    /// Forget spongos states of messages not reachable from the given message. The announcement and
+    /// the last messages of each publisher are kept. Returns the number of removed states.
+    ///
+    ///   # Arguments
+    ///   * `reachable_from` - Address of the most recent message to be kept
+    pub fn gc_spongos(&mut self, reachable_from: &Address) -> Result<usize> {
+        self.user.gc_spongos(reachable_from)
+    }
+
+    /// Delete a message from the transport layer and forget its spongos state. Messages linked to the
+    /// retracted one can no longer be processed by subscribers who have not processed it yet.
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message to be retracted
+    pub fn retract(&mut self, link: &Address) -> Result<()> {
+        self.user.retract(link)
+    }
+
     /// Iteratively fetches next message until no new messages can be found, and return a vector
     /// containing all of them.
     pub fn fetch_all_next_msgs(&mut self) -> Vec<UnwrappedMessage> {
@@ -273,6 +1226,69 @@ impl<Trans: Transport + Clone> Author<Trans> {
         msgs
     }
 
+    /// Fetches all the new messages and returns them newest first. Messages are fetched and
+    /// processed forward and buffered, none is returned before the latest one is fetched.
+    pub fn messages_reverse(&mut self) -> Vec<UnwrappedMessage> {
+        self.user.messages_reverse()
+    }
+
+    /// Iteratively fetches next messages until the message at `stop` has been processed or no new
+    /// messages can be found, and return a vector containing all of them.
+    ///
+    ///   # Arguments
+    ///   * `stop` - Address of the last message to be processed
+    pub fn fetch_next_msgs_until(&mut self, stop: &Address) -> Vec<UnwrappedMessage> {
+        self.user.fetch_next_msgs_until(stop)
+    }
+
+    /// List the messages of the channel stored by the transport so that the next `sync` only
+    /// retrieves listed messages. Fails with `MessageListingUnsupported` if the transport can't
+    /// enumerate messages, `sync` then probes the next address of every publisher. Returns the
+    /// number of listed messages.
+    pub fn discover_messages(&mut self) -> Result<usize> {
+        self.user.discover_messages()
+    }
+
+    /// Fetch and process next messages of all publishers until no progress is made. Messages linked
+    /// to a not yet processed message of another publisher are retried once more messages are
+    /// processed, without fetching them again. Returns the number of processed messages and the
+    /// number of messages left orphaned.
+    pub fn sync(&mut self) -> Result<(usize, usize)> {
+        self.user.sync()
+    }
+
+    /// Fetch and process next messages of all publishers like `sync`, skipping messages which can't
+    /// be processed, eg. corrupt ones, instead of stopping at them. Messages linked to a skipped
+    /// message are skipped as well. Returns the number of processed messages and the addresses of
+    /// the skipped ones.
+    pub fn sync_skipping_errors(&mut self) -> Result<(usize, Vec<Address>)> {
+        self.user.sync_skipping_errors()
+    }
+
+    /// Fetch and process next messages of all publishers until no new message appears for a
+    /// number of consecutive rounds, see `set_sync_max_misses`. It doesn't rely on the transport
+    /// failing on missing addresses. Returns the number of fetched messages and whether the next
+    /// expected addresses were all missing, ie. the head of the channel was reached.
+    pub fn sync_to_head(&mut self) -> Result<SyncToHead> {
+        self.user.sync_to_head()
+    }
+
+    /// Fetch and process next messages of all publishers like `sync`, persisting the user state
+    /// every `batch` processed messages and once synchronization completes. If synchronization is
+    /// interrupted, at most `batch` processed messages are lost and processed again by the next
+    /// `sync` of the imported state.
+    ///
+    ///   # Arguments
+    ///   * `pwd` - Encryption password of the persisted state
+    ///   * `batch` - Number of processed messages between persisted states
+    ///   * `persist` - Callback storing the exported user state
+    pub fn sync_persisting<P>(&mut self, pwd: &str, batch: usize, persist: P) -> Result<(usize, usize)>
+    where
+        P: FnMut(&[u8]) -> Result<()>,
+    {
+        self.user.sync_persisting(0, pwd, batch, persist)
+    }
+
     /// Retrieves the previous message from the message specified (provided the user has access to it)
     pub fn fetch_prev_msg(&mut self, link: &Address) -> Result<UnwrappedMessage> {
         self.user.fetch_prev_msg(link)
@@ -283,6 +1299,16 @@ impl<Trans: Transport + Clone> Author<Trans> {
         self.user.fetch_prev_msgs(link, max)
     }
 
+    /// Verify that the message at `link` chains back to the announcement of the channel and get
+    /// the addresses of the chain, from the announcement to the message. Messages of the chain
+    /// not processed yet are processed.
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message to verify
+    pub fn verify_provenance(&mut self, link: &Address) -> Result<Vec<Address>> {
+        self.user.verify_provenance(link)
+    }
+
     /// Iteratively fetches next messages until internal state has caught up
     pub fn sync_state(&mut self) {
         let mut exists = true;
@@ -300,6 +1326,27 @@ impl<Trans: Transport + Clone> Author<Trans> {
         self.user.receive_message(link)
     }
 
+    /// Receive and process a message expected from the given publisher, the message is rejected
+    /// without processing it if its header declares another publisher
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message to be processed
+    ///   * `expected` - Identifier of the expected publisher
+    pub fn receive_msg_from(&mut self, link: &Address, expected: &Identifier) -> Result<UnwrappedMessage> {
+        self.user.receive_message_from(link, expected)
+    }
+
+    /// Receive and process the messages of a publisher from its sequencing state up to the one with
+    /// the given sequence number, the returned messages end with the requested one. Receiving stops
+    /// with an error if there is no message at the computed address.
+    ///
+    ///   # Arguments
+    ///   * `publisher` - Identifier of the publisher of the message
+    ///   * `sequence` - Sequence number of the message
+    pub fn receive_msg_at(&mut self, publisher: &Identifier, sequence: usize) -> Result<Vec<UnwrappedMessage>> {
+        self.user.receive_message_at(publisher, sequence)
+    }
+
     // Unsubscribe a subscriber
     // pub pub fn receive_unsubscribe(&mut self, link: Address) -> Result<()> {
     // self.user.handle_unsubscribe(link, MsgInfo::Unsubscribe)
@@ -308,6 +1355,11 @@ impl<Trans: Transport + Clone> Author<Trans> {
 
 #[cfg(feature = "async")]
 impl<Trans: Transport + Clone> Author<Trans> {
+    /// Publish the messages of a succeeded transaction which could not be published
+    pub async fn publish_pending(&mut self) -> Result<()> {
+        self.user.publish_pending().await
+    }
+
     /// Generates a new Author implementation from input. If the announcement message generated by
     /// this instance matches that of an existing (and provided) announcement link, the user will
     /// sync to the latest state
@@ -340,12 +1392,27 @@ impl<Trans: Transport + Clone> Author<Trans> {
         self.user.send_announce().await
     }
 
+    /// Send an announcement message followed by an initial keyload linked to it. Returns the
+    /// links of the announcement, the keyload and the optional keyload sequence message.
+    ///
+    ///  # Arguments
+    ///  * `psk_ids` - Vector of Pre-shared key ids to be included in the keyload
+    ///  * `ke_pks`  - Vector of Public Keys to be included in the keyload
+    pub async fn send_announce_with_keyload(
+        &mut self,
+        psk_ids: &PskIds,
+        ke_pks: &Vec<&Identifier>,
+    ) -> Result<(Address, Address, Option<Address>)> {
+        self.user.send_announce_with_keyload(psk_ids, ke_pks).await
+    }
+
     /// Create and send a new keyload for a list of subscribers.
     ///
     ///  # Arguments
     ///  * `link_to` - Address of the message the keyload will be attached to
     ///  * `psk_ids` - Vector of Pre-shared key ids to be included in message
-    ///  * `ke_pks`  - Vector of Public Keys to be included in message
+    ///  * `ke_pks`  - Vector of Public Keys to be included in message, viewing keys of readers are
+    ///    included as `Identifier::XPubKey`
     pub async fn send_keyload(
         &mut self,
         link_to: &Address,
@@ -355,6 +1422,67 @@ impl<Trans: Transport + Clone> Author<Trans> {
         self.user.send_keyload(link_to, psk_ids, ke_pks).await
     }
 
+    /// Create and send a new keyload shared with holders of the viewing keys only. Viewers can
+    /// read the protected masked payloads but can't publish, and no cursor is kept for them.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `viewing_keys` - Viewing keys of the readers
+    pub async fn send_keyload_for_viewers(
+        &mut self,
+        link_to: &Address,
+        viewing_keys: &[x25519::PublicKey],
+    ) -> Result<(Address, Option<Address>)> {
+        self.user.send_keyload_for_viewers(link_to, viewing_keys).await
+    }
+
+    /// Create and send a new keyload for a list of subscribers unless the last keyload sent to
+    /// the channel had the same recipients. Returns `None` if the keyload was skipped.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `psk_ids` - Vector of Pre-shared key ids to be included in message
+    ///  * `ke_pks`  - Vector of Public Keys to be included in message
+    pub async fn send_keyload_if_changed(
+        &mut self,
+        link_to: &Address,
+        psk_ids: &PskIds,
+        ke_pks: &Vec<&Identifier>,
+    ) -> Result<Option<(Address, Option<Address>)>> {
+        self.user.send_keyload_if_changed(link_to, psk_ids, ke_pks).await
+    }
+
+    /// Create and send keyloads for a list of subscribers split into keyloads of at most
+    /// `max_per_message` recipients, eg. when all of them don't fit into a single transport message.
+    /// All the keyloads are attached to `link_to` and can be opened independently, each subscriber
+    /// gains access with the keyload it is included in. Packets are readable by the recipients of
+    /// the keyload they are linked to.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyloads will be attached to
+    ///  * `psk_ids` - Vector of Pre-shared key ids to be included in the first keyload
+    ///  * `ke_pks`  - Vector of Public Keys to be split across the keyloads
+    ///  * `max_per_message` - Maximum number of public keys per keyload
+    pub async fn send_keyload_split(
+        &mut self,
+        link_to: &Address,
+        psk_ids: &PskIds,
+        ke_pks: &Vec<&Identifier>,
+        max_per_message: usize,
+    ) -> Result<Vec<(Address, Option<Address>)>> {
+        self.user
+            .send_keyload_split(link_to, psk_ids, ke_pks, max_per_message).await
+    }
+
+    /// Create and send keyload for the members of a group of subscribers, see `add_to_group`.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `group` - Name of the group of recipients
+    pub async fn send_keyload_for_group(&mut self, link_to: &Address, group: &str) -> Result<(Address, Option<Address>)> {
+        self.user.send_keyload_for_group(link_to, group).await
+    }
+
     /// Create and send keyload for all subscribed subscribers.
     ///
     ///  # Arguments
@@ -363,6 +1491,76 @@ impl<Trans: Transport + Clone> Author<Trans> {
         self.user.send_keyload_for_everyone(link_to).await
     }
 
+    /// Create and send keyload for all subscribed subscribers unless the last keyload sent to the
+    /// channel had the same recipients. Returns `None` if the keyload was skipped.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    pub async fn send_keyload_for_everyone_if_changed(
+        &mut self,
+        link_to: &Address,
+    ) -> Result<Option<(Address, Option<Address>)>> {
+        self.user.send_keyload_for_everyone_if_changed(link_to).await
+    }
+
+    /// Create and send a chain of signed packets, each one linked to the previous one. The packets
+    /// are wrapped before any of them is sent, the user state is restored if the batch fails.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the first packet will be attached to
+    ///  * `payloads` - Public and masked payloads of the packets
+    pub async fn send_signed_packets<I>(
+        &mut self,
+        link_to: &Address,
+        payloads: I,
+    ) -> Result<Vec<(Address, Option<Address>)>>
+    where
+        I: IntoIterator<Item = (Bytes, Bytes)>,
+    {
+        self.user.send_signed_packets(link_to, payloads).await
+    }
+
+    /// Create and send a close message marking the end of the channel. Subscribers processing it
+    /// expect no new messages in the channel.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the close message will be attached to
+    pub async fn close_channel(&mut self, link_to: &Address) -> Result<(Address, Option<Address>)> {
+        self.user.send_close(link_to).await
+    }
+
+    /// Create and send a message sealing the channel membership: subscriptions of new subscribers
+    /// are rejected afterwards, unlike `close_channel` messages are still published to the
+    /// existing subscribers.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the seal membership message will be attached to
+    pub async fn seal_membership(&mut self, link_to: &Address) -> Result<(Address, Option<Address>)> {
+        self.user.send_seal_membership(link_to).await
+    }
+
+    /// Create and send a checkpoint summarizing the channel up to the `link_to` message, so that
+    /// new subscribers can start reading the channel from the checkpoint instead of replaying the
+    /// whole history. Only single-branch channels support checkpoints, the following messages
+    /// should be linked to the checkpoint.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the last message summarized by the checkpoint
+    pub async fn publish_checkpoint(&mut self, link_to: &Address) -> Result<(Address, Option<Address>)> {
+        self.user.publish_checkpoint(link_to).await
+    }
+
+    /// Create and send a message adding an author to the channel, eg. to co-publish keyloads in a
+    /// federated channel. Keyloads and close messages signed by the added author are accepted by
+    /// users processing the message.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the add author message will be attached to
+    ///  * `author_pk` - Signature public key of the added author
+    pub async fn add_author(&mut self, link_to: &Address, author_pk: &ed25519::PublicKey) -> Result<(Address, Option<Address>)> {
+        self.user.send_add_author(link_to, author_pk).await
+    }
+
     /// Create and send a signed packet.
     ///
     ///  # Arguments
@@ -380,6 +1578,26 @@ impl<Trans: Transport + Clone> Author<Trans> {
             .await
     }
 
+    /// Create and send a signed packet with the given options, see `PacketOptions`. The detached
+    /// signature is returned if requested by the options.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    ///  * `options` - Options of the packet
+    pub async fn send_signed_packet_with(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        options: &PacketOptions,
+    ) -> Result<((Address, Option<Address>), Option<DetachedSignature>)> {
+        self.user
+            .send_signed_packet_with(link_to, public_payload, masked_payload, options)
+            .await
+    }
+
     /// Create and send a tagged packet.
     ///
     ///  # Arguments
@@ -397,6 +1615,46 @@ impl<Trans: Transport + Clone> Author<Trans> {
             .await
     }
 
+    /// Create and send a tagged packet bound to an application context. The context is sent in
+    /// plain and is covered by the MAC.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    ///  * `context` - Application context (eg. external request id)
+    pub async fn send_tagged_packet_with_context(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        context: &Bytes,
+    ) -> Result<(Address, Option<Address>)> {
+        self.user
+            .send_tagged_packet_with_context(link_to, public_payload, masked_payload, context)
+            .await
+    }
+
+    /// Create and send a tagged packet tagged with an application type. The type is sent in plain
+    /// and is covered by the MAC.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    ///  * `app_type` - Application defined content type, 0 is the default type
+    pub async fn send_tagged_packet_with_app_type(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        app_type: u16,
+    ) -> Result<(Address, Option<Address>)> {
+        self.user
+            .send_tagged_packet_with_app_type(link_to, public_payload, masked_payload, app_type)
+            .await
+    }
+
     /// Receive and process a subscribe message.
     ///
     ///  # Arguments
@@ -405,6 +1663,15 @@ impl<Trans: Transport + Clone> Author<Trans> {
         self.user.receive_subscribe(link).await
     }
 
+    /// Receive and process a subscribe message, get the subscriber identifier and the
+    /// subscription payload, empty if the subscriber sent none.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    pub async fn receive_subscribe_with_payload(&mut self, link: &Address) -> Result<(Identifier, Bytes)> {
+        self.user.receive_subscribe_with_payload(link).await
+    }
+
     /// Receive and process a signed packet message.
     ///
     ///  # Arguments
@@ -413,6 +1680,15 @@ impl<Trans: Transport + Clone> Author<Trans> {
         self.user.receive_signed_packet(link).await
     }
 
+    /// Receive and process an ack message of a subscriber, the acknowledged position is recorded.
+    /// Returns the public key of the subscriber and the acknowledged position.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    pub async fn receive_ack(&mut self, link: &Address) -> Result<(ed25519::PublicKey, usize)> {
+        self.user.receive_ack(link).await
+    }
+
     /// Receive and process a tagged packet message.
     ///
     ///  # Arguments
@@ -421,6 +1697,18 @@ impl<Trans: Transport + Clone> Author<Trans> {
         self.user.receive_tagged_packet(link).await
     }
 
+    /// Receive and process a message with application defined content, see `User::handle_custom`.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///  * `content` - Content used to unwrap the message
+    pub async fn handle_custom<C>(&mut self, link: &Address, content: C) -> Result<GenericMessage<Address, C>>
+    where
+        C: ContentUnwrap<DefaultF, LinkStore>,
+    {
+        self.user.handle_custom(link, content).await
+    }
+
     /// Receive and process a sequence message.
     ///
     ///  # Arguments
@@ -434,6 +1722,40 @@ impl<Trans: Transport + Clone> Author<Trans> {
         self.user.fetch_next_msgs().await
     }
 
+    /// Retrieves and processes standalone packets of other publishers, skipping up to `max_gap`
+    /// consecutive missing packets of each publisher.
+    pub async fn fetch_standalone_packets(&mut self, max_gap: usize) -> Result<Vec<UnwrappedMessage>> {
+        self.user.fetch_standalone_packets(max_gap).await
+    }
+
+    /// Retrieves the next message for each user along with the position just after the message
+    pub async fn fetch_next_msgs_with_positions(&mut self) -> Vec<(UnwrappedMessage, Position)> {
+        self.user.fetch_next_msgs_with_positions().await
+    }
+
+    /// Counts the messages available at the next expected address of each user without processing them
+    pub async fn pending_count(&mut self) -> Result<usize> {
+        self.user.pending_count().await
+    }
+
+    /// Forget spongos states of messages not reachable from the given message. The announcement and
+    /// the last messages of each publisher are kept. Returns the number of removed states.
+    ///
+    ///   # Arguments
+    ///   * `reachable_from` - Address of the most recent message to be kept
+    pub async fn gc_spongos(&mut self, reachable_from: &Address) -> Result<usize> {
+        self.user.gc_spongos(reachable_from).await
+    }
+
+    /// Delete a message from the transport layer and forget its spongos state. Messages linked to the
+    /// retracted one can no longer be processed by subscribers who have not processed it yet.
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message to be retracted
+    pub async fn retract(&mut self, link: &Address) -> Result<()> {
+        self.user.retract(link).await
+    }
+
     /// Iteratively fetches next message until no new messages can be found, and return a vector
     /// containing all of them.
     pub async fn fetch_all_next_msgs(&mut self) -> Vec<UnwrappedMessage> {
@@ -450,6 +1772,69 @@ impl<Trans: Transport + Clone> Author<Trans> {
         msgs
     }
 
+    /// Fetches all the new messages and returns them newest first. Messages are fetched and
+    /// processed forward and buffered, none is returned before the latest one is fetched.
+    pub async fn messages_reverse(&mut self) -> Vec<UnwrappedMessage> {
+        self.user.messages_reverse().await
+    }
+
+    /// Iteratively fetches next messages until the message at `stop` has been processed or no new
+    /// messages can be found, and return a vector containing all of them.
+    ///
+    ///   # Arguments
+    ///   * `stop` - Address of the last message to be processed
+    pub async fn fetch_next_msgs_until(&mut self, stop: &Address) -> Vec<UnwrappedMessage> {
+        self.user.fetch_next_msgs_until(stop).await
+    }
+
+    /// List the messages of the channel stored by the transport so that the next `sync` only
+    /// retrieves listed messages. Fails with `MessageListingUnsupported` if the transport can't
+    /// enumerate messages, `sync` then probes the next address of every publisher. Returns the
+    /// number of listed messages.
+    pub async fn discover_messages(&mut self) -> Result<usize> {
+        self.user.discover_messages().await
+    }
+
+    /// Fetch and process next messages of all publishers until no progress is made. Messages linked
+    /// to a not yet processed message of another publisher are retried once more messages are
+    /// processed, without fetching them again. Returns the number of processed messages and the
+    /// number of messages left orphaned.
+    pub async fn sync(&mut self) -> Result<(usize, usize)> {
+        self.user.sync().await
+    }
+
+    /// Fetch and process next messages of all publishers like `sync`, skipping messages which can't
+    /// be processed, eg. corrupt ones, instead of stopping at them. Messages linked to a skipped
+    /// message are skipped as well. Returns the number of processed messages and the addresses of
+    /// the skipped ones.
+    pub async fn sync_skipping_errors(&mut self) -> Result<(usize, Vec<Address>)> {
+        self.user.sync_skipping_errors().await
+    }
+
+    /// Fetch and process next messages of all publishers until no new message appears for a
+    /// number of consecutive rounds, see `set_sync_max_misses`. It doesn't rely on the transport
+    /// failing on missing addresses. Returns the number of fetched messages and whether the next
+    /// expected addresses were all missing, ie. the head of the channel was reached.
+    pub async fn sync_to_head(&mut self) -> Result<SyncToHead> {
+        self.user.sync_to_head().await
+    }
+
+    /// Fetch and process next messages of all publishers like `sync`, persisting the user state
+    /// every `batch` processed messages and once synchronization completes. If synchronization is
+    /// interrupted, at most `batch` processed messages are lost and processed again by the next
+    /// `sync` of the imported state.
+    ///
+    ///   # Arguments
+    ///   * `pwd` - Encryption password of the persisted state
+    ///   * `batch` - Number of processed messages between persisted states
+    ///   * `persist` - Callback storing the exported user state
+    pub async fn sync_persisting<P>(&mut self, pwd: &str, batch: usize, persist: P) -> Result<(usize, usize)>
+    where
+        P: FnMut(&[u8]) -> Result<()>,
+    {
+        self.user.sync_persisting(0, pwd, batch, persist).await
+    }
+
     /// Retrieves the previous message from the message specified (provided the user has access to it)
     pub async fn fetch_prev_msg(&mut self, link: &Address) -> Result<UnwrappedMessage> {
         self.user.fetch_prev_msg(link).await
@@ -460,6 +1845,16 @@ impl<Trans: Transport + Clone> Author<Trans> {
         self.user.fetch_prev_msgs(link, max).await
     }
 
+    /// Verify that the message at `link` chains back to the announcement of the channel and get
+    /// the addresses of the chain, from the announcement to the message. Messages of the chain
+    /// not processed yet are processed.
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message to verify
+    pub async fn verify_provenance(&mut self, link: &Address) -> Result<Vec<Address>> {
+        self.user.verify_provenance(link).await
+    }
+
     /// Iteratively fetches next messages until internal state has caught up
     pub async fn sync_state(&mut self) {
         let mut exists = true;
@@ -478,6 +1873,27 @@ impl<Trans: Transport + Clone> Author<Trans> {
         self.user.receive_message(link).await
     }
 
+    /// Receive and process a message expected from the given publisher, the message is rejected
+    /// without processing it if its header declares another publisher
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message to be processed
+    ///   * `expected` - Identifier of the expected publisher
+    pub async fn receive_msg_from(&mut self, link: &Address, expected: &Identifier) -> Result<UnwrappedMessage> {
+        self.user.receive_message_from(link, expected).await
+    }
+
+    /// Receive and process the messages of a publisher from its sequencing state up to the one with
+    /// the given sequence number, the returned messages end with the requested one. Receiving stops
+    /// with an error if there is no message at the computed address.
+    ///
+    ///   # Arguments
+    ///   * `publisher` - Identifier of the publisher of the message
+    ///   * `sequence` - Sequence number of the message
+    pub async fn receive_msg_at(&mut self, publisher: &Identifier, sequence: usize) -> Result<Vec<UnwrappedMessage>> {
+        self.user.receive_message_at(publisher, sequence).await
+    }
+
     // Unsubscribe a subscriber
     // pub async fn receive_unsubscribe(&mut self, link: Address) -> Result<()> {
     // self.user.handle_unsubscribe(link, MsgInfo::Unsubscribe).await
@@ -494,3 +1910,10 @@ impl<Trans: Clone> fmt::Display for Author<Trans> {
         )
     }
 }
+
+impl<Trans> fmt::Debug for Author<Trans> {
+    /// Private keys of the user are redacted.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Author").field("user", &self.user).finish()
+    }
+}
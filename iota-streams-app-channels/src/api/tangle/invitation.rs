@@ -0,0 +1,142 @@
+use iota_streams_core::{
+    err,
+    prelude::{
+        hex,
+        Vec,
+    },
+    psk::{
+        self,
+        PskId,
+    },
+    try_or,
+    Errors::{
+        BadOneof,
+        InputStreamNotFullyConsumed,
+        InvitationKeyMismatch,
+        OutputStreamNotFullyConsumed,
+    },
+    Result,
+};
+use iota_streams_core_edsig::{
+    key_exchange::x25519,
+    signature::ed25519,
+};
+use iota_streams_ddml::{
+    command::*,
+    types::*,
+};
+
+use super::{
+    Address,
+    DefaultF,
+    PublicKey,
+};
+
+/// Length of the MAC protecting encoded invitations.
+const INVITATION_MAC_SIZE: usize = 16;
+
+/// Everything a subscriber needs to join a channel: the announcement address, the author public
+/// keys and optionally the id of a PSK shared with the subscriber out of band. An invitation only
+/// carries public keys, it is created with `User::create_invitation` and passed to
+/// `User::accept_invitation`.
+#[derive(Clone)]
+pub struct Invitation {
+    announcement: Address,
+    author: PublicKey,
+    author_ke_pk: x25519::PublicKey,
+    psk_id: Option<PskId>,
+}
+
+impl Invitation {
+    /// Create invitation to the channel announced at `announcement`, the exchange key must be the
+    /// public key derived from the author signature key.
+    pub fn new(
+        announcement: Address,
+        author: PublicKey,
+        author_ke_pk: x25519::PublicKey,
+        psk_id: Option<PskId>,
+    ) -> Result<Self> {
+        try_or!(
+            x25519::public_from_ed25519(&author)?.as_bytes() == author_ke_pk.as_bytes(),
+            InvitationKeyMismatch(hex::encode(author.as_bytes()))
+        )?;
+        Ok(Self {
+            announcement,
+            author,
+            author_ke_pk,
+            psk_id,
+        })
+    }
+
+    /// Address of the channel announcement.
+    pub fn announcement(&self) -> &Address {
+        &self.announcement
+    }
+
+    /// Signature public key of the channel author.
+    pub fn author(&self) -> &PublicKey {
+        &self.author
+    }
+
+    /// Key exchange public key of the channel author.
+    pub fn author_exchange_key(&self) -> &x25519::PublicKey {
+        &self.author_ke_pk
+    }
+
+    /// Id of the PSK the author grants access with, the PSK itself is not part of the invitation.
+    pub fn psk_id(&self) -> Option<&PskId> {
+        self.psk_id.as_ref()
+    }
+
+    /// Encode invitation.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let oneof = Uint8(self.psk_id.is_some() as u8);
+        let mut ctx = sizeof::Context::<DefaultF>::new();
+        ctx.absorb(<&Fallback<Address>>::from(&self.announcement))?
+            .absorb(&self.author)?
+            .absorb(&self.author_ke_pk)?
+            .absorb(oneof)?;
+        if let Some(psk_id) = &self.psk_id {
+            ctx.absorb(<&NBytes<psk::PskIdSize>>::from(psk_id))?;
+        }
+        ctx.commit()?.squeeze(Mac(INVITATION_MAC_SIZE))?;
+        let mut buf = vec![0; ctx.get_size()];
+
+        let mut ctx = wrap::Context::<DefaultF, &mut [u8]>::new(&mut buf[..]);
+        ctx.absorb(<&Fallback<Address>>::from(&self.announcement))?
+            .absorb(&self.author)?
+            .absorb(&self.author_ke_pk)?
+            .absorb(oneof)?;
+        if let Some(psk_id) = &self.psk_id {
+            ctx.absorb(<&NBytes<psk::PskIdSize>>::from(psk_id))?;
+        }
+        ctx.commit()?.squeeze(Mac(INVITATION_MAC_SIZE))?;
+        try_or!(ctx.stream.is_empty(), OutputStreamNotFullyConsumed(ctx.stream.len()))?;
+        Ok(buf)
+    }
+
+    /// Decode invitation encoded with `to_bytes`, truncated or corrupted invitations are rejected.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut announcement = Fallback(Address::default());
+        let mut author = ed25519::PublicKey::default();
+        let mut author_ke_pk = x25519::PublicKey::from([0_u8; 32]);
+        let mut oneof = Uint8(0);
+        let mut ctx = unwrap::Context::<DefaultF, &[u8]>::new(bytes);
+        ctx.absorb(&mut announcement)?
+            .absorb(&mut author)?
+            .absorb(&mut author_ke_pk)?
+            .absorb(&mut oneof)?;
+        let psk_id = match oneof.0 {
+            0 => None,
+            1 => {
+                let mut psk_id = PskId::default();
+                ctx.absorb(<&mut NBytes<psk::PskIdSize>>::from(&mut psk_id))?;
+                Some(psk_id)
+            }
+            _ => return err!(BadOneof),
+        };
+        ctx.commit()?.squeeze(Mac(INVITATION_MAC_SIZE))?;
+        try_or!(ctx.stream.is_empty(), InputStreamNotFullyConsumed(ctx.stream.len()))?;
+        Self::new(announcement.0, author, author_ke_pk, psk_id)
+    }
+}
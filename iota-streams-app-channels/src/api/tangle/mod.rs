@@ -2,6 +2,7 @@
 
 use super::key_store::KeyMap;
 use iota_streams_app::{
+    identifier::Identifier,
     message::{
         self,
         BinaryBody,
@@ -14,6 +15,7 @@ use iota_streams_app::{
             MsgId,
             TangleAddress,
             TangleMessage,
+            TopicTangleLinkGenerator,
         },
     },
 };
@@ -29,11 +31,47 @@ pub use transport::{
     TransportOptions as _,
 };
 
-pub use super::ChannelType;
+#[cfg(feature = "deflate")]
+pub use super::compression::Deflate;
 use super::DefaultF;
-use iota_streams_core::psk;
-use iota_streams_ddml::link_store::DefaultLinkStore;
-pub use iota_streams_ddml::types::Bytes;
+pub use super::{
+    compression::{
+        Compressor,
+        NoCompression,
+    },
+    detached::{
+        verify_detached,
+        DetachedSignature,
+    },
+    keyload_policy::{
+        KeyloadPolicy,
+        Quorum,
+    },
+    packet_options::PacketOptions,
+    payload_codec::PayloadCodec,
+    ChannelType,
+};
+pub use crate::message::{
+    metadata::{
+        Metadata,
+        DEFAULT_TOPIC,
+    },
+    MessageKind,
+};
+use iota_streams_core::{
+    prelude::Rc,
+    psk,
+    Result,
+};
+use iota_streams_ddml::link_store::{
+    self,
+    DefaultLinkStore,
+};
+pub use iota_streams_ddml::types::{
+    Bytes,
+    Signer,
+    SIGNATURE_CONTEXT,
+};
 
 use iota_streams_core_edsig::signature::ed25519;
 
@@ -59,12 +97,53 @@ pub type WrapState = message::WrapState<DefaultF, Address>;
 pub type WrappedSequence = super::user::WrappedSequence<DefaultF, Address>;
 /// Wrapped sequencing information with optional WrapState
 pub type WrapStateSequence = super::user::WrapStateSequence<DefaultF, Address>;
+/// Access of the user to the session key of a keyload
+pub use super::user::KeyloadAccess;
+/// Author and channel details read from an announcement
+pub use super::user::AnnouncementInfo;
+/// Summary of exported user state
+pub use super::user::BackupInfo;
+/// Detached state of a channel managed by a user
+pub type ChannelState = super::user::ChannelState<DefaultF, Address, LinkStore, KeyStore>;
+/// Differences between the channel states of two users
+pub type StateDiff = super::user::StateDiff<MsgId>;
 /// Ed25519 Public Key
 pub type PublicKey = ed25519::PublicKey;
 
 /// Message type with parsed header.
 pub type Preparsed<'a> = message::PreparsedMessage<'a, DefaultF, Address>;
 
+/// Message header summary parsed from raw message bytes.
+pub type HeaderInfo = message::HeaderInfo<Address>;
+
+/// Parse the header of raw message bytes without a user, eg. to route messages by type or
+/// publisher in a stateless gateway. The header is not authenticated.
+pub fn peek_header(bytes: &[u8]) -> Result<HeaderInfo> {
+    message::peek_header::<DefaultF, Address>(bytes)
+}
+
+/// Class of a raw message: its type, publisher, sequence number and linked message.
+#[derive(Clone)]
+pub struct MessageClass {
+    pub kind: MessageKind,
+    pub publisher: Identifier,
+    pub seq_num: u64,
+    pub linked_msg_address: Address,
+}
+
+/// Classify raw message bytes without a user, keys or spongos states, eg. in an indexer. Only the
+/// header is parsed, messages of unknown types are classified as `MessageKind::Unknown`. The header
+/// is not authenticated.
+pub fn classify(bytes: &[u8]) -> Result<MessageClass> {
+    let header = peek_header(bytes)?;
+    Ok(MessageClass {
+        kind: MessageKind::from(header.content_type),
+        publisher: header.sender_id,
+        seq_num: header.seq_num,
+        linked_msg_address: header.linked_msg_address,
+    })
+}
+
 /// Sequence State information
 pub type SeqState = Cursor<MsgId>;
 /// Identifier Key Mapping for sequence states
@@ -73,12 +152,41 @@ pub type KeyStore = KeyMap<SeqState>;
 /// Link Generator specifies algorithm for generating new message addressed.
 pub type LinkGen = DefaultTangleLinkGenerator<DefaultF>;
 
+/// Link Generator deriving message addresses from an application defined topic, can be used
+/// with the generic `api::user::User`.
+pub type TopicLinkGen = TopicTangleLinkGenerator<DefaultF>;
+
+/// Announcement link of the channel with index `channel_idx` created by the author with public key
+/// `pk`, derived the same way as by `Author::new_channel`. Lets subscribers reconnect to a channel
+/// without storing its announcement link.
+pub fn announcement_link(pk: &PublicKey, channel_idx: u64) -> Address {
+    let mut link_gen = LinkGen::default();
+    link_gen.gen(pk, channel_idx);
+    link_gen.get()
+}
+
+/// Announcement link of the channel named `label` created by the author with public key `pk`,
+/// see `Author::new_channel_named`.
+pub fn announcement_link_from_label(pk: &PublicKey, label: &str) -> Address {
+    announcement_link(pk, ChannelAddress::label_index::<DefaultF>(pk, label))
+}
+
 /// Link Store.
 pub type LinkStore = DefaultLinkStore<DefaultF, MsgId, MsgInfo>;
 
+/// Callback invoked when a message spongos state is stored, see `User::set_spongos_store_callback`.
+pub type SpongosStoreCallback = link_store::LinkStoreCallback<DefaultF, MsgId, MsgInfo>;
+
+/// Callback invoked with the progress of `sync`, see `User::set_sync_progress_callback`.
+pub type SyncProgressCallback = Rc<dyn Fn(&SyncProgress)>;
+
 /// Test Transport.
 pub type BucketTransport = transport::BucketTransport<Address, Message>;
 
+/// Transport selected at run-time, eg. from configuration. Any transport can be boxed into it
+/// with `iota_streams_app::transport::boxed::boxed`.
+pub type BoxedTransport<Details = ()> = transport::BoxedTransport<Address, Message, Details>;
+
 /// Transportation trait for Tangle Client implementation
 // TODO: Use trait synonyms `pub Transport = transport::Transport<DefaultF, Address>;`.
 pub trait Transport: transport::Transport<Address, Message> + Clone {}
@@ -87,7 +195,18 @@ impl<T> Transport for T where T: transport::Transport<Address, Message> + Clone
 mod msginfo;
 pub use msginfo::MsgInfo;
 
+mod position;
+pub use position::Position;
+
+mod invitation;
+pub use invitation::Invitation;
+
 /// Message body returned as part of handle message routine.
+///
+/// Messages linked to a message the user hasn't processed yet are reported as `Orphan` without
+/// being processed. Messages present but not accessible to the user are reported as `Unreadable`,
+/// see `is_accessible`, access to the session key of a `Keyload` is queried with
+/// `User::keyload_access`. The other variants carry the decoded content.
 #[allow(clippy::large_enum_variant)]
 pub enum MessageContent {
     Announce,
@@ -96,15 +215,33 @@ pub enum MessageContent {
         pk: PublicKey,
         public_payload: Bytes,
         masked_payload: Bytes,
+        context: Option<Bytes>,
+        metadata: Option<Metadata>,
+        app_type: u16,
     },
     TaggedPacket {
         public_payload: Bytes,
         masked_payload: Bytes,
+        context: Option<Bytes>,
+        app_type: u16,
     },
     Sequence,
     Subscribe,
     Unsubscribe,
+    Close,
+    AddAuthor {
+        pk: PublicKey,
+    },
+    SealMembership,
+    Ack {
+        pk: PublicKey,
+        position: usize,
+    },
+    Checkpoint {
+        head: Address,
+    },
     Unreadable,
+    Orphan,
 }
 
 impl MessageContent {
@@ -121,6 +258,25 @@ impl MessageContent {
             pk,
             public_payload,
             masked_payload,
+            context: None,
+            metadata: None,
+            app_type: 0,
+        }
+    }
+
+    pub fn new_signed_packet_with_context(
+        pk: PublicKey,
+        public_payload: Bytes,
+        masked_payload: Bytes,
+        context: Option<Bytes>,
+    ) -> Self {
+        Self::SignedPacket {
+            pk,
+            public_payload,
+            masked_payload,
+            context,
+            metadata: None,
+            app_type: 0,
         }
     }
 
@@ -128,12 +284,72 @@ impl MessageContent {
         Self::TaggedPacket {
             public_payload,
             masked_payload,
+            context: None,
+            app_type: 0,
+        }
+    }
+
+    pub fn new_tagged_packet_with_context(
+        public_payload: Bytes,
+        masked_payload: Bytes,
+        context: Option<Bytes>,
+    ) -> Self {
+        Self::TaggedPacket {
+            public_payload,
+            masked_payload,
+            context,
+            app_type: 0,
         }
     }
 
     pub fn unreadable() -> Self {
         Self::Unreadable
     }
+
+    pub fn orphan() -> Self {
+        Self::Orphan
+    }
+
+    /// Returns true if the message is linked to a message the user hasn't processed yet.
+    pub fn is_orphan(&self) -> bool {
+        matches!(self, Self::Orphan)
+    }
+
+    /// Application context the packet is bound to, if any.
+    pub fn context(&self) -> Option<&Bytes> {
+        match self {
+            Self::SignedPacket { context, .. } => context.as_ref(),
+            Self::TaggedPacket { context, .. } => context.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Application metadata carried by the signed packet, if any.
+    pub fn metadata(&self) -> Option<&Metadata> {
+        match self {
+            Self::SignedPacket { metadata, .. } => metadata.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Application type the packet is tagged with, 0 if the packet is not tagged.
+    pub fn app_type(&self) -> u16 {
+        match self {
+            Self::SignedPacket { app_type, .. } => *app_type,
+            Self::TaggedPacket { app_type, .. } => *app_type,
+            _ => 0,
+        }
+    }
+
+    /// Returns false if the message content could not be accessed: either an unreadable sequenced
+    /// message, or an orphan message. Keyloads are processed whether the user is a recipient or not,
+    /// see `User::keyload_access`.
+    pub fn is_accessible(&self) -> bool {
+        match self {
+            Self::Unreadable | Self::Orphan => false,
+            _ => true,
+        }
+    }
 }
 
 /// Generic unwrapped message type containing possible message contents
@@ -144,6 +360,14 @@ pub type BinaryMessage = message::GenericMessage<Address, BinaryBody<DefaultF>>;
 
 #[allow(clippy::ptr_arg)]
 mod user;
+/// Snapshot of the user state used to roll back failed operations
+pub use user::Checkpoint;
+/// Handling of orphaned messages beyond the limit of buffered orphans
+pub use user::OrphanPolicy;
+/// Progress of a synchronization reported to the sync progress callback
+pub use user::SyncProgress;
+/// Outcome of catching up to the head of the channel
+pub use user::SyncToHead;
 /// User object storing the Auth/Sub implementation as well as the transport instance
 pub use user::User;
 
@@ -16,7 +16,7 @@ use iota_streams_ddml::{
 };
 
 /// Message associated info stored internally in User context, just message type indicator.
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 pub enum MsgInfo {
     Announce,
     Keyload,
@@ -25,6 +25,13 @@ pub enum MsgInfo {
     Subscribe,
     Unsubscribe,
     Sequence,
+    /// Message with application defined content
+    Custom,
+    Close,
+    AddAuthor,
+    SealMembership,
+    Ack,
+    Checkpoint,
 }
 
 // Default instance is required by ddml unwrap logic as unwrap modifies/updates an existing object rather producing a
@@ -45,6 +52,12 @@ impl From<MsgInfo> for u8 {
             MsgInfo::Subscribe => 4,
             MsgInfo::Unsubscribe => 5,
             MsgInfo::Sequence => 6,
+            MsgInfo::Custom => 7,
+            MsgInfo::Close => 8,
+            MsgInfo::AddAuthor => 9,
+            MsgInfo::SealMembership => 10,
+            MsgInfo::Ack => 11,
+            MsgInfo::Checkpoint => 12,
         }
     }
 }
@@ -66,6 +79,12 @@ impl TryFrom<u8> for MsgInfo {
             4 => Ok(MsgInfo::Subscribe),
             5 => Ok(MsgInfo::Unsubscribe),
             6 => Ok(MsgInfo::Sequence),
+            7 => Ok(MsgInfo::Custom),
+            8 => Ok(MsgInfo::Close),
+            9 => Ok(MsgInfo::AddAuthor),
+            10 => Ok(MsgInfo::SealMembership),
+            11 => Ok(MsgInfo::Ack),
+            12 => Ok(MsgInfo::Checkpoint),
             _ => Err(()),
         }
     }
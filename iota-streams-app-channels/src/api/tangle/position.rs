@@ -0,0 +1,100 @@
+use iota_streams_app::{
+    identifier::Identifier,
+    message::{
+        ContentSizeof,
+        ContentUnwrapNew,
+        ContentWrap,
+    },
+};
+use iota_streams_core::{
+    prelude::Vec,
+    try_or,
+    Errors::{
+        InputStreamNotFullyConsumed,
+        OutputStreamNotFullyConsumed,
+    },
+    Result,
+};
+use iota_streams_ddml::{
+    command::*,
+    types::*,
+};
+
+use super::{
+    Cursor,
+    DefaultF,
+    MsgId,
+    SeqState,
+};
+
+/// Length of the MAC protecting encoded positions.
+const POSITION_MAC_SIZE: usize = 16;
+
+/// Position in the channel just after a processed message: the sequence state of each publisher
+/// at that moment. Unlike a full backup a position carries no keys nor spongos states, it can be
+/// saved with `to_bytes` after each processed message and passed to `User::resume_from` to
+/// continue processing after the message.
+#[derive(Clone)]
+pub struct Position(pub(crate) Vec<(Identifier, SeqState)>);
+
+impl Position {
+    /// Number of publishers tracked by the position.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Encode position.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut ctx = sizeof::Context::<DefaultF>::new();
+        ctx.absorb(Size(self.0.len()))?
+            .repeated(self.0.iter(), |ctx, (id, cursor)| {
+                let ctx = id.sizeof(ctx)?;
+                ctx.absorb(<&Fallback<MsgId>>::from(&cursor.link))?
+                    .absorb(Uint32(cursor.branch_no))?
+                    .absorb(Uint32(cursor.seq_no))?;
+                Ok(ctx)
+            })?
+            .commit()?
+            .squeeze(Mac(POSITION_MAC_SIZE))?;
+        let mut buf = vec![0; ctx.get_size()];
+
+        let mut ctx = wrap::Context::<DefaultF, &mut [u8]>::new(&mut buf[..]);
+        ctx.absorb(Size(self.0.len()))?
+            .repeated(self.0.iter(), |ctx, (id, cursor)| {
+                let ctx = id.wrap(&(), ctx)?;
+                ctx.absorb(<&Fallback<MsgId>>::from(&cursor.link))?
+                    .absorb(Uint32(cursor.branch_no))?
+                    .absorb(Uint32(cursor.seq_no))?;
+                Ok(ctx)
+            })?
+            .commit()?
+            .squeeze(Mac(POSITION_MAC_SIZE))?;
+        try_or!(ctx.stream.is_empty(), OutputStreamNotFullyConsumed(ctx.stream.len()))?;
+        Ok(buf)
+    }
+
+    /// Decode position encoded with `to_bytes`, truncated or corrupted positions are rejected.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut cursors = Vec::new();
+        let mut ctx = unwrap::Context::<DefaultF, &[u8]>::new(bytes);
+        let mut n = Size(0);
+        ctx.absorb(&mut n)?
+            .repeated(n, |ctx| {
+                let (id, ctx) = Identifier::unwrap_new(&(), ctx)?;
+                let mut link = Fallback(MsgId::default());
+                let mut branch_no = Uint32(0);
+                let mut seq_no = Uint32(0);
+                ctx.absorb(&mut link)?.absorb(&mut branch_no)?.absorb(&mut seq_no)?;
+                cursors.push((id, Cursor::new_at(link.0, branch_no.0, seq_no.0)));
+                Ok(ctx)
+            })?
+            .commit()?
+            .squeeze(Mac(POSITION_MAC_SIZE))?;
+        try_or!(ctx.stream.is_empty(), InputStreamNotFullyConsumed(ctx.stream.len()))?;
+        Ok(Self(cursors))
+    }
+}
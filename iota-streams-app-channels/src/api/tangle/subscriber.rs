@@ -10,7 +10,13 @@ use crate::api::tangle::{
     User,
 };
 
-use iota_streams_app::identifier::Identifier;
+use iota_streams_app::{
+    identifier::Identifier,
+    message::{
+        ContentUnwrap,
+        GenericMessage,
+    },
+};
 use iota_streams_core::{
     prelude::{
         String,
@@ -21,11 +27,15 @@ use iota_streams_core::{
         PskId,
     },
 };
-use iota_streams_core_edsig::signature::ed25519;
+use iota_streams_core_edsig::{
+    key_exchange::x25519,
+    signature::ed25519,
+};
 
 /// Subscriber Object. Contains User API.
+#[derive(Clone)]
 pub struct Subscriber<T> {
-    user: User<T>,
+    pub(crate) user: User<T>,
 }
 
 impl<Trans> Subscriber<Trans> {
@@ -39,6 +49,29 @@ impl<Trans> Subscriber<Trans> {
         Self { user }
     }
 
+    /// Create a new Subscriber instance from an existing raw Ed25519 secret key (eg. exported from an
+    /// HSM or another library).
+    ///
+    /// # Arguments
+    /// * `secret` - Ed25519 secret key bytes
+    /// * `transport` - Transport object used for sending and receiving
+    pub fn from_ed25519_secret(secret: [u8; ed25519::SECRET_KEY_LENGTH], transport: Trans) -> Self {
+        let user = User::from_ed25519_secret(secret, SingleBranch, transport);
+        Self { user }
+    }
+
+    /// Create a read-only Subscriber instance from the secret of another user's viewing key. It
+    /// reads the keyloads shared with the viewing key, but its signature key pair is fresh and
+    /// unknown to the channel, so it can't publish on behalf of the viewing key holder.
+    ///
+    /// # Arguments
+    /// * `secret` - Secret of the viewing key, see `viewing_secret`
+    /// * `transport` - Transport object used for sending and receiving
+    pub fn from_viewing_secret(secret: [u8; 32], transport: Trans) -> Self {
+        let user = User::from_viewing_secret(secret, SingleBranch, transport);
+        Self { user }
+    }
+
     /// Returns a clone of the transport object
     pub fn get_transport(&self) -> &Trans {
         self.user.get_transport()
@@ -54,18 +87,114 @@ impl<Trans> Subscriber<Trans> {
         self.user.unregister()
     }
 
+    /// Detach the active channel state and keep it for later use. The subscriber is left
+    /// unregistered and can process the announcement of another channel. Only the active channel
+    /// is used by send and receive methods, stashed channels are kept in backups.
+    pub fn stash_channel(&mut self) -> Result<()> {
+        self.user.stash_channel()
+    }
+
+    /// Make a previously stashed channel active. The active channel (if any) is stashed.
+    ///
+    ///   # Arguments
+    ///   * `channel` - Address of the stashed channel
+    pub fn switch_channel(&mut self, channel: &ChannelAddress) -> Result<()> {
+        self.user.switch_channel(channel)
+    }
+
+    /// Fetch the Addresses of stashed channels.
+    pub fn stashed_channels(&self) -> Vec<&ChannelAddress> {
+        self.user.stashed_channels()
+    }
+
+    /// Take a snapshot of the user state, it can be restored with `restore`.
+    pub fn checkpoint(&self) -> Checkpoint {
+        self.user.checkpoint()
+    }
+
+    /// Restore the user state from a snapshot, discarding all changes made since it was taken.
+    /// Messages sent in the meantime are not retracted from the transport.
+    ///
+    ///   # Arguments
+    ///   * `checkpoint` - Snapshot taken with `checkpoint`
+    pub fn restore(&mut self, checkpoint: Checkpoint) {
+        self.user.restore(checkpoint)
+    }
+
     /// Fetch the user ed25519 public key
     pub fn get_public_key(&self) -> &ed25519::PublicKey {
         self.user.get_public_key()
     }
 
+    /// Fetch the user x25519 viewing key. Readers share it with the author to be granted read
+    /// access with `Author::send_keyload_for_viewers`, without being known as publishers.
+    pub fn viewing_key(&self) -> &x25519::PublicKey {
+        self.user.viewing_key()
+    }
+
+    /// Fetch the secret of the user viewing key. A read-only Subscriber holding the viewing key
+    /// only is created from it with `Subscriber::from_viewing_secret`.
+    pub fn viewing_secret(&self) -> [u8; 32] {
+        self.user.viewing_secret()
+    }
+
     /// Channel Author's signature public key
     pub fn author_public_key(&self) -> Option<&ed25519::PublicKey> {
         self.user.author_public_key()
     }
 
+    /// Signature public keys of the authors added to the channel, the announcing author excluded.
+    pub fn co_authors(&self) -> &[ed25519::PublicKey] {
+        self.user.co_authors()
+    }
+
+    /// Compare the channel state with that of the channel author to find where their views
+    /// diverge. No key material is reported.
+    ///
+    ///   # Arguments
+    ///   * `author` - Author to compare the state with
+    pub fn diff_author<T>(&self, author: &Author<T>) -> StateDiff {
+        self.user.diff(&author.user)
+    }
+
+    /// Compare the channel state with that of another subscriber. No key material is reported.
+    ///
+    ///   # Arguments
+    ///   * `other` - Subscriber to compare the state with
+    pub fn diff<T>(&self, other: &Subscriber<T>) -> StateDiff {
+        self.user.diff(&other.user)
+    }
+
+    /// Channel Author's key exchange public key, eg. to encrypt data to the Author out of band.
+    /// Returns `None` if the Subscriber has not processed the announcement yet.
+    pub fn author_exchange_key(&self) -> Option<x25519::PublicKey> {
+        self.user.author_exchange_key()
+    }
+
+    /// Application metadata carried by the channel announcement, eg. channel name or schema
+    /// version. Returns `None` if the announcement carries no metadata or is not processed yet.
+    pub fn announcement_metadata(&self) -> Option<&[u8]> {
+        self.user.announcement_metadata()
+    }
+
+    /// Returns true if the channel author has closed the channel, no new messages are expected.
+    pub fn is_closed(&self) -> bool {
+        self.user.is_closed()
+    }
+
+    /// Returns true if the channel author has sealed the channel membership, no new subscribers
+    /// are expected.
+    pub fn is_membership_sealed(&self) -> bool {
+        self.user.is_membership_sealed()
+    }
+
     /// Store a PSK in the user instance
     ///
+    /// The announcement must be processed first. A reader holding a PSK doesn't need to subscribe:
+    /// once the announcement is processed (eg. with `receive_message`) and the PSK is stored, the
+    /// keyloads addressed to the PSK and the packets linked to them are read with `sync` or
+    /// `fetch_next_msgs`.
+    ///
     ///   # Arguments
     ///   * `pskid` - An identifier representing a pre shared key
     ///   * `psk` - A pre shared key
@@ -73,11 +202,32 @@ impl<Trans> Subscriber<Trans> {
         self.user.store_psk(pskid, psk, true)
     }
 
+    /// Fetch the identifiers of the stored pre-shared keys in ascending order, eg. to select
+    /// `psk_ids` of a keyload.
+    pub fn psk_ids(&self) -> Vec<PskId> {
+        self.user.psk_ids()
+    }
+
     /// Fetch the Address (application instance) of the channel.
     pub fn channel_address(&self) -> Option<&ChannelAddress> {
         self.user.channel_address()
     }
 
+    /// Addresses of the channels the user has created or joined, least recently connected first.
+    /// The history is kept in backups, eg. to present recent channels to the user.
+    pub fn connection_history(&self) -> impl Iterator<Item = Address> + '_ {
+        self.user.connection_history()
+    }
+
+    /// Limit the number of channels kept in the connection history, the least recently connected
+    /// ones are dropped first (16 by default).
+    ///
+    ///   # Arguments
+    ///   * `max_connection_history` - Maximum number of channels kept
+    pub fn set_max_connection_history(&mut self, max_connection_history: usize) {
+        self.user.set_max_connection_history(max_connection_history)
+    }
+
     /// Return boolean representing the sequencing nature of the channel
     pub fn is_multi_branching(&self) -> bool {
         self.user.is_multi_branching()
@@ -121,6 +271,60 @@ impl<Trans> Subscriber<Trans> {
         Ok(state)
     }
 
+    /// Fetch the address of the latest processed message of each publisher, eg. to display the
+    /// latest message from each writer. Publishers with no message beyond the announcement are
+    /// skipped, the result is ordered by publisher identifier.
+    pub fn heads(&self) -> Result<Vec<(Identifier, Address)>> {
+        self.user.heads()
+    }
+
+    /// Takes a lightweight snapshot of the sequence state of each publisher, without link store
+    /// and keys, allowing to persist sync progress separately of a full backup
+    pub fn cursor_snapshot(&self) -> Vec<(Identifier, SeqState)> {
+        self.user.cursor_snapshot()
+    }
+
+    /// Restores sequence states from a snapshot, link store and keys are left untouched
+    ///
+    ///   # Arguments
+    ///   * `snapshot` - Sequence states previously taken with `cursor_snapshot`
+    pub fn load_cursor_snapshot(&mut self, snapshot: Vec<(Identifier, SeqState)>) -> Result<()> {
+        self.user.load_cursor_snapshot(snapshot)
+    }
+
+    /// Takes a cursor snapshot authenticated with a key derived from the password, it can be
+    /// stored on untrusted infrastructure
+    ///
+    ///   # Arguments
+    ///   * `pwd` - Password protecting the snapshot
+    pub fn sealed_cursor_snapshot(&self, pwd: &str) -> Result<Vec<u8>> {
+        self.user.sealed_cursor_snapshot(pwd)
+    }
+
+    /// Verifies a sealed cursor snapshot and restores its sequence states, a tampered snapshot is
+    /// rejected
+    ///
+    ///   # Arguments
+    ///   * `bytes` - Snapshot taken with `sealed_cursor_snapshot`
+    ///   * `pwd` - Password protecting the snapshot
+    pub fn load_sealed_cursor_snapshot(&mut self, bytes: &[u8], pwd: &str) -> Result<()> {
+        self.user.load_sealed_cursor_snapshot(bytes, pwd)
+    }
+
+    /// Current position in the channel, just after the last processed message
+    pub fn position(&self) -> Position {
+        self.user.position()
+    }
+
+    /// Continue processing messages just after the position. Positions carry sequence states only,
+    /// they can be saved after each processed message instead of backing up the whole state.
+    ///
+    ///   # Arguments
+    ///   * `position` - Position returned along with a processed message or by `position`
+    pub fn resume_from(&mut self, position: &Position) -> Result<()> {
+        self.user.resume_from(position)
+    }
+
     /// Resets the cursor state storage to allow a Subscriber to retrieve all messages in a channel
     /// from scratch
     pub fn reset_state(&mut self) -> Result<()> {
@@ -136,6 +340,15 @@ impl<Trans> Subscriber<Trans> {
         self.user.gen_next_msg_ids(branching)
     }
 
+    /// Address of the next message expected from the publisher: the next message itself in
+    /// single-branch channels, the sequence message referencing it in multi-branch channels.
+    ///
+    ///   # Arguments
+    ///   * `publisher` - Identifier of the publisher
+    pub fn next_address(&self, publisher: &Identifier) -> Result<Address> {
+        self.user.next_address(publisher)
+    }
+
     /// Serialize user state and encrypt it with password.
     ///
     ///   # Arguments
@@ -153,10 +366,401 @@ impl<Trans> Subscriber<Trans> {
     pub fn import(bytes: &[u8], pwd: &str, tsp: Trans) -> Result<Self> {
         User::<Trans>::import(bytes, 1, pwd, tsp).map(|user| Self { user })
     }
+
+    /// Export the public state of the channel, no secret key is exported.
+    pub fn export_public_state(&self) -> Result<Vec<u8>> {
+        self.user.export_public_state()
+    }
+
+    /// Create a read-only observer from public channel state exported by another user. The
+    /// observer has a fresh key pair unknown to the channel and can't send sequenced messages.
+    ///
+    ///   # Arguments
+    ///   * `bytes` - Public channel state, see `export_public_state`
+    ///   * `tsp` - Transport object
+    pub fn import_public_state(bytes: &[u8], tsp: Trans) -> Result<Self> {
+        User::<Trans>::import_public_state(bytes, tsp).map(|user| Self { user })
+    }
+
+    /// Re-encrypt serialized user state with a new password, without restoring it.
+    ///
+    ///   # Arguments
+    ///   * `bytes` - Encrypted serialized user state
+    ///   * `old_pwd` - Password the state is encrypted with
+    ///   * `new_pwd` - New encryption password
+    pub fn rekey_backup(bytes: &[u8], old_pwd: &str, new_pwd: &str) -> Result<Vec<u8>> {
+        User::<Trans>::rekey_backup(bytes, 1, old_pwd, new_pwd)
+    }
+
+    /// Check serialized user state can be restored with the password, without restoring it, and
+    /// get a summary of the state.
+    ///
+    ///   # Arguments
+    ///   * `bytes` - Encrypted serialized user state
+    ///   * `pwd` - Encryption password
+    pub fn verify_backup(bytes: &[u8], pwd: &str) -> Result<BackupInfo> {
+        User::<Trans>::verify_backup(bytes, 1, pwd)
+    }
+
+    /// Set the length of the MAC protecting exported user state.
+    ///
+    ///   # Arguments
+    ///   * `mac_len` - MAC length in bytes [16-64]
+    pub fn set_backup_mac_len(&mut self, mac_len: usize) -> Result<()> {
+        self.user.set_backup_mac_len(mac_len)
+    }
+
+    /// Attach the fingerprint of the linked message state to sent messages, so that readers
+    /// joined to a wrong state get a distinct error. Such messages can't be read by older versions.
+    ///
+    ///   # Arguments
+    ///   * `link_fingerprint` - Whether the fingerprint is attached
+    pub fn set_link_fingerprint(&mut self, link_fingerprint: bool) {
+        self.user.set_link_fingerprint(link_fingerprint)
+    }
+
+    /// Network domain message addresses are derived in, empty by default.
+    pub fn network_domain(&self) -> &[u8] {
+        self.user.network_domain()
+    }
+
+    /// Derive message addresses in the network domain of the channel author, see
+    /// `Author::new_in_network_domain`. The domain must be set before the announcement is received,
+    /// it is kept in backups.
+    ///
+    ///   # Arguments
+    ///   * `domain` - Network domain, eg. distinguishing mainnet and testnet channels
+    pub fn set_network_domain(&mut self, domain: &[u8]) {
+        self.user.set_network_domain(domain)
+    }
+
+    /// Limit the length of payloads accepted in received signed and tagged packets. Declared
+    /// lengths are checked before allocation, so oversized messages are rejected cheaply.
+    ///
+    ///   # Arguments
+    ///   * `max_payload_length` - Maximum length in bytes of public and masked payloads
+    pub fn set_max_payload_length(&mut self, max_payload_length: usize) {
+        self.user.set_max_payload_length(max_payload_length)
+    }
+
+    /// Limit the size of message bodies (not limited by default). Sending a larger message fails
+    /// with `MessageSizeExceeded` before its buffer is allocated, received messages larger than the
+    /// limit are rejected before being unwrapped.
+    ///
+    ///   # Arguments
+    ///   * `max_message_size` - Maximum size in bytes of a message body
+    pub fn set_max_message_size(&mut self, max_message_size: usize) {
+        self.user.set_max_message_size(max_message_size)
+    }
+
+    /// Reject signed packets published by identifiers unknown to the user. Publishers become known
+    /// when they are included in a processed keyload, subscribe to the channel or announce it.
+    ///
+    ///   # Arguments
+    ///   * `enforce_known_publishers` - Whether packets from unknown publishers are rejected
+    pub fn set_enforce_known_publishers(&mut self, enforce_known_publishers: bool) {
+        self.user.set_enforce_known_publishers(enforce_known_publishers)
+    }
+
+    /// Reject signed and tagged packets of single-branch channels whose sequence number isn't the
+    /// next one expected from their publisher, so that missing messages are reported with
+    /// `SequenceGap` instead of being skipped. The user state is left unchanged.
+    ///
+    ///   # Arguments
+    ///   * `strict_sequencing` - Whether packets breaking the sequence are rejected
+    pub fn set_strict_sequencing(&mut self, strict_sequencing: bool) {
+        self.user.set_strict_sequencing(strict_sequencing)
+    }
+
+    /// Unmask the payloads of signed packets of the given topics only. Packets of other topics are
+    /// still verified and processed, so that messages linked to them can be read, but their masked
+    /// payload is skipped and returned empty.
+    ///
+    ///   # Arguments
+    ///   * `followed_topics` - Topics whose masked payloads are read, all topics if `None`
+    pub fn set_followed_topics(&mut self, followed_topics: Option<Vec<String>>) {
+        self.user.set_followed_topics(followed_topics)
+    }
+
+    /// Wrap sent messages into a buffer of the size of the last message with the same content type,
+    /// skipping the sizeof pass when sizes are fixed, eg. packets with fixed length payloads. A message of a
+    /// different size is wrapped twice.
+    ///
+    ///   # Arguments
+    ///   * `wrap_size_hints` - Whether sizes of sent messages are cached as hints
+    pub fn set_wrap_size_hints(&mut self, wrap_size_hints: bool) {
+        self.user.set_wrap_size_hints(wrap_size_hints)
+    }
+
+    /// Application defined label of the user, kept in backups.
+    pub fn label(&self) -> Option<&str> {
+        self.user.label()
+    }
+
+    /// Set or clear the label of the user, it is not shared with other participants.
+    ///
+    ///   # Arguments
+    ///   * `label` - Label of the user, `None` to clear it
+    pub fn set_label(&mut self, label: Option<String>) {
+        self.user.set_label(label)
+    }
+
+    /// Limit the number of orphaned messages buffered by `sync`, it guards against a transport
+    /// feeding messages linked to messages that never show up.
+    ///
+    ///   # Arguments
+    ///   * `max_orphans` - Maximum number of buffered orphans, 1024 by default
+    ///   * `policy` - Whether to fail or to drop the oldest orphan beyond the limit
+    pub fn set_max_orphans(&mut self, max_orphans: usize, policy: OrphanPolicy) {
+        self.user.set_max_orphans(max_orphans, policy)
+    }
+
+    /// Report the progress of `sync` against an estimate of the number of new messages, the
+    /// estimate fetches new messages once more unless `discover_messages` was called.
+    ///
+    ///   # Arguments
+    ///   * `callback` - Callback invoked with the progress, `None` to disable reporting
+    ///   * `interval` - Number of processed messages between reports
+    pub fn set_sync_progress_callback(&mut self, callback: Option<SyncProgressCallback>, interval: usize) {
+        self.user.set_sync_progress_callback(callback, interval)
+    }
+
+    /// Set the number of consecutive rounds without new messages after which `sync_to_head`
+    /// stops.
+    ///
+    ///   # Arguments
+    ///   * `max_misses` - Number of rounds, at least 1 and 3 by default
+    pub fn set_sync_max_misses(&mut self, max_misses: usize) {
+        self.user.set_sync_max_misses(max_misses)
+    }
+
+    /// Accept announcements only from the given channel authors. Receiving an announcement of
+    /// another author fails and the Subscriber is not bound to the channel.
+    ///
+    ///   # Arguments
+    ///   * `trusted_authors` - Public keys of trusted authors, `None` trusts any author (default)
+    pub fn set_trusted_authors(&mut self, trusted_authors: Option<Vec<ed25519::PublicKey>>) {
+        self.user.set_trusted_authors(trusted_authors)
+    }
+
+    /// Set the context shared out-of-band with the author of a channel created with
+    /// `Author::new_channel_with_context`. It must be set before the announcement is received,
+    /// peeked or handled by `receive_message` and `sync_state`: announcements bound to another
+    /// context, or to no context, fail verification. Subscribers without the context cannot
+    /// validate the announcement.
+    ///
+    ///   # Arguments
+    ///   * `context` - Shared context, eg. a secret, `None` if the announcement is not bound to a context
+    pub fn set_announcement_context(&mut self, context: Option<&[u8]>) {
+        self.user.set_announcement_context(context)
+    }
+
+    /// Compress public payloads of sent signed and tagged packets and decompress the received
+    /// ones. Compressed payloads are signalled in the message header: a receiver without a
+    /// compressor, or with another codec, gets an error instead of a corrupted payload.
+    ///
+    ///   # Arguments
+    ///   * `compressor` - Payload codec, eg. `NoCompression` or `Deflate`, `None` disables compression (default)
+    pub fn set_compressor<C: Compressor + 'static>(&mut self, compressor: Option<C>) {
+        self.user.set_compressor(compressor)
+    }
+
+    /// Encode public and masked payloads of sent signed and tagged packets with an application
+    /// codec, eg. an envelope format or an encryption layer, and decode the received ones. All the
+    /// participants of the channel must use the same codec.
+    ///
+    ///   # Arguments
+    ///   * `payload_codec` - Payload transformation, `None` disables it (default)
+    pub fn set_payload_codec<C: PayloadCodec + 'static>(&mut self, payload_codec: Option<C>) {
+        self.user.set_payload_codec(payload_codec)
+    }
+
+    /// Check recipients of received keyloads against an application policy, eg. a `Quorum` of
+    /// expected members, before they are processed. A keyload rejected by the policy fails with
+    /// an error and its session key is not used.
+    ///
+    ///   # Arguments
+    ///   * `keyload_policy` - Policy over keyload recipients, `None` accepts any keyload (default)
+    pub fn set_keyload_policy<P: KeyloadPolicy + 'static>(&mut self, keyload_policy: Option<P>) {
+        self.user.set_keyload_policy(keyload_policy)
+    }
+
+    /// Sign sent signed packets with `signer` instead of the user key pair, eg. a signer backed by
+    /// an HSM or a remote KMS. Packets are verified with and attributed to the public key of the
+    /// signer, other messages are still signed with the user key pair.
+    ///
+    ///   # Arguments
+    ///   * `signer` - Signer of sent signed packets, `None` restores the user key pair (default)
+    pub fn set_signer<S: Signer + 'static>(&mut self, signer: Option<S>) {
+        self.user.set_signer(signer)
+    }
+
+    /// Derive nonces, session keys and ephemeral keys of sent messages from the seed, the message
+    /// links and contents instead of random generation, so replayed messages are byte-identical. Meant for
+    /// tests and deduplicating storage: deterministic randomness weakens unlinkability and must not be
+    /// used for privacy-sensitive channels.
+    ///
+    ///   # Arguments
+    ///   * `seed` - Secret seed of the randomness, `None` restores random generation (default)
+    pub fn set_deterministic_seed(&mut self, seed: Option<&str>) {
+        self.user.set_deterministic_seed(seed)
+    }
+
+    /// Split masked payloads of sent signed and tagged packets into blocks, each authenticated with
+    /// its own MAC. A truncated or corrupted packet is rejected at the first bad block. Each block
+    /// costs a 16 byte MAC plus its encoded length; splitting is signalled in the message header,
+    /// receivers need no setting.
+    ///
+    ///   # Arguments
+    ///   * `block_size` - Length of blocks in bytes, `None` disables splitting (default)
+    pub fn set_masked_block_size(&mut self, block_size: Option<usize>) -> Result<()> {
+        self.user.set_masked_block_size(block_size)
+    }
+
+    /// Authenticate sent tagged packets with MACs of the given length instead of 32 bytes. The
+    /// length is carried in the messages, receivers need no setting. Shorter MACs save bandwidth
+    /// but are easier to forge: a 16 byte MAC leaves 128 bits of security against forgery.
+    ///
+    ///   # Arguments
+    ///   * `mac_length` - Length of MACs in bytes, one of 16, 24 or 32 (default)
+    pub fn set_tag_mac_length(&mut self, mac_length: usize) -> Result<()> {
+        self.user.set_tag_mac_length(mac_length)
+    }
+
+    /// Number of message spongos states kept by the user
+    pub fn spongos_store_len(&self) -> usize {
+        self.user.spongos_store_len()
+    }
+
+    /// Digest committing to all the processed messages of the channel, eg. to anchor the channel
+    /// integrity in an external system. Users with the same view of the channel get the same
+    /// digest, it changes when a message is added, removed or altered.
+    pub fn chain_digest(&self) -> [u8; 32] {
+        self.user.chain_digest()
+    }
+
+    /// Pairs of processed messages and the messages they are linked to, eg. to render the message
+    /// graph or find orphaned subtrees. Edges of messages restored from a backup are not known.
+    pub fn dependency_edges(&self) -> Vec<(MsgId, MsgId)> {
+        self.user.dependency_edges()
+    }
+
+    /// Recipients, public keys and pre-shared key identifiers, of the keyload recorded when it was
+    /// processed, eg. to verify the subscriber was included. Recipients are kept in backups. `None`
+    /// if the keyload is unknown or could not be accessed.
+    ///
+    ///   # Arguments
+    ///   * `keyload` - Message identifier of the keyload
+    pub fn keyload_recipients(&self, keyload: &MsgId) -> Option<Vec<Identifier>> {
+        self.user.keyload_recipients(keyload)
+    }
+
+    /// The way the subscriber was granted access to the session key of a processed keyload: with
+    /// its own key, a pre-shared key or a viewing key. `Denied` if the keyload is unknown or the
+    /// subscriber is not among its recipients.
+    ///
+    ///   # Arguments
+    ///   * `keyload` - Message identifier of the keyload
+    pub fn keyload_access(&self, keyload: &MsgId) -> KeyloadAccess {
+        self.user.keyload_access(keyload)
+    }
+
+    /// Addresses of processed signed packets published under the topic, in processing order.
+    /// Packets without a topic are listed under `DEFAULT_TOPIC`.
+    ///
+    ///   # Arguments
+    ///   * `topic` - Topic of the packets
+    pub fn messages_for_topic(&self, topic: &str) -> Vec<Address> {
+        self.user.messages_for_topic(topic)
+    }
+
+    /// Stream message spongos states to external storage as they are stored, instead of
+    /// exporting the whole user state. States restored with `import` are not reported.
+    ///
+    /// The callback is invoked synchronously before the state is stored; a panic in the callback
+    /// propagates to the caller of the send or receive method and the state is not stored.
+    ///
+    ///   # Arguments
+    ///   * `callback` - Callback invoked with message id, spongos state and info, `None` removes it
+    pub fn set_spongos_store_callback(&mut self, callback: Option<SpongosStoreCallback>) {
+        self.user.set_spongos_store_callback(callback)
+    }
+
+    /// Verify signature of a signed packet without processing it and get the identifier of its
+    /// publisher. The state of the user is not changed.
+    ///
+    ///   # Arguments
+    ///   * `msg` - Signed packet message, the message it is linked to must have been processed
+    pub fn verify_message(&self, msg: &Message) -> Result<Identifier> {
+        self.user.verify_message(msg)
+    }
+
+    /// Size in bytes of a signed packet with the given payloads, nothing is wrapped or sent.
+    /// Sequence message sent in multi-branch channels is not included.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet would be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    pub fn estimate_signed_packet_size(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+    ) -> Result<usize> {
+        self.user
+            .estimate_signed_packet_size(link_to, public_payload, masked_payload)
+    }
+
+    /// Size in bytes of a tagged packet with the given payloads, nothing is wrapped or sent.
+    /// Sequence message sent in multi-branch channels is not included.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet would be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    pub fn estimate_tagged_packet_size(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+    ) -> Result<usize> {
+        self.user
+            .estimate_tagged_packet_size(link_to, public_payload, masked_payload)
+    }
+
+    /// Deserialize user state and decrypt it with password, checking it was exported with the given
+    /// MAC length. The length is stored in the backup, `import` needs no setting.
+    ///
+    ///   # Arguments
+    ///   * `bytes` - Encrypted serialized user state
+    ///   * `pwd` - Encryption password
+    ///   * `mac_len` - Expected MAC length in bytes
+    ///   * `tsp` - Transport object
+    pub fn import_with_mac_len(bytes: &[u8], pwd: &str, mac_len: usize, tsp: Trans) -> Result<Self> {
+        User::<Trans>::import_with_mac_len(bytes, 1, pwd, mac_len, tsp).map(|user| Self { user })
+    }
 }
 
 #[cfg(not(feature = "async"))]
 impl<Trans: Transport + Clone> Subscriber<Trans> {
+    /// Run a group of operations as a transaction: messages sent by `f` are only published once it
+    /// succeeds. If `f` fails, nothing is published and the user state is restored to that before
+    /// the call.
+    ///
+    ///   # Arguments
+    ///   * `f` - Operations to run, the first error aborts the transaction
+    pub fn transaction<R>(&mut self, f: impl FnOnce(&mut Self) -> Result<R>) -> Result<R> {
+        let scope = self.user.begin_transaction();
+        let result = f(self);
+        self.user.end_transaction(scope, result)
+    }
+
+    /// Publish the messages of a succeeded transaction which could not be published
+    pub fn publish_pending(&mut self) -> Result<()> {
+        self.user.publish_pending()
+    }
+
     /// Generates a new Subscriber implementation from input. It then syncs state of the user from
     /// the given announcement message link
     ///
@@ -180,6 +784,16 @@ impl<Trans: Transport + Clone> Subscriber<Trans> {
         self.user.send_subscribe(link_to)
     }
 
+    /// Create and Send a Subscribe message carrying an application payload for the author, eg. a
+    /// display name. The payload is masked with the key shared with the author.
+    ///
+    /// # Arguments
+    /// * `link_to` - Address of the Channel Announcement message
+    /// * `payload` - Wrapped vector of Bytes readable by the author only
+    pub fn send_subscribe_with_payload(&mut self, link_to: &Address, payload: &Bytes) -> Result<Address> {
+        self.user.send_subscribe_with_payload(link_to, payload)
+    }
+
     /// Create and send a signed packet.
     ///
     ///  # Arguments
@@ -195,6 +809,44 @@ impl<Trans: Transport + Clone> Subscriber<Trans> {
         self.user.send_tagged_packet(link_to, public_payload, masked_payload)
     }
 
+    /// Create and send a tagged packet bound to an application context. The context is sent in
+    /// plain and is covered by the MAC.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    ///  * `context` - Application context (eg. external request id)
+    pub fn send_tagged_packet_with_context(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        context: &Bytes,
+    ) -> Result<(Address, Option<Address>)> {
+        self.user
+            .send_tagged_packet_with_context(link_to, public_payload, masked_payload, context)
+    }
+
+    /// Create and send a tagged packet tagged with an application type. The type is sent in plain
+    /// and is covered by the MAC.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    ///  * `app_type` - Application defined content type, 0 is the default type
+    pub fn send_tagged_packet_with_app_type(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        app_type: u16,
+    ) -> Result<(Address, Option<Address>)> {
+        self.user
+            .send_tagged_packet_with_app_type(link_to, public_payload, masked_payload, app_type)
+    }
+
     /// Create and send a tagged packet.
     ///
     ///  # Arguments
@@ -210,6 +862,38 @@ impl<Trans: Transport + Clone> Subscriber<Trans> {
         self.user.send_signed_packet(link_to, public_payload, masked_payload)
     }
 
+    /// Create and send a signed packet with the given options, see `PacketOptions`. The detached
+    /// signature is returned if requested by the options.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    ///  * `options` - Options of the packet
+    pub fn send_signed_packet_with(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        options: &PacketOptions,
+    ) -> Result<((Address, Option<Address>), Option<DetachedSignature>)> {
+        self.user
+            .send_signed_packet_with(link_to, public_payload, masked_payload, options)
+    }
+
+    /// Create and send a chain of signed packets, each one linked to the previous one. The packets
+    /// are wrapped before any of them is sent, the user state is restored if the batch fails.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the first packet will be attached to
+    ///  * `payloads` - Public and masked payloads of the packets
+    pub fn send_signed_packets<I>(&mut self, link_to: &Address, payloads: I) -> Result<Vec<(Address, Option<Address>)>>
+    where
+        I: IntoIterator<Item = (Bytes, Bytes)>,
+    {
+        self.user.send_signed_packets(link_to, payloads)
+    }
+
     // Unsubscribe from the Channel app instance.
     // pub pub fn unsubscribe(&mut self, link_to: &Address) -> Result<Message> {
     // TODO: lookup link_to Subscribe message.
@@ -224,6 +908,25 @@ impl<Trans: Transport + Clone> Subscriber<Trans> {
         self.user.receive_announcement(link)
     }
 
+    /// Fetch an announcement and read its author identity, exchange key and channel metadata
+    /// without following the channel, eg. to ask the user whether to follow it. Nothing is
+    /// stored, the subscriber may already be connected to another channel.
+    ///
+    /// # Arguments
+    /// * `link` - Address of the Channel Announcement message
+    pub fn peek_announcement(&mut self, link: &Address) -> Result<AnnouncementInfo> {
+        self.user.peek_announcement(link)
+    }
+
+    /// Join the channel with an invitation created by its author, the announcement is fetched and
+    /// processed. The PSK referenced by the invitation, if any, has to be stored with `store_psk`.
+    ///
+    /// # Arguments
+    /// * `bytes` - Invitation encoded by `Author::create_invitation`
+    pub fn accept_invitation(&mut self, bytes: &[u8]) -> Result<Invitation> {
+        self.user.accept_invitation(bytes)
+    }
+
     /// Receive and process a keyload message.
     ///
     ///  # Arguments
@@ -232,6 +935,52 @@ impl<Trans: Transport + Clone> Subscriber<Trans> {
         self.user.receive_keyload(link)
     }
 
+    /// Receive and process a close message, it must be signed by the channel author.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    pub fn receive_close(&mut self, link: &Address) -> Result<()> {
+        self.user.receive_close(link)
+    }
+
+    /// Receive and process a message sealing the channel membership, it must be signed by a
+    /// trusted author.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    pub fn receive_seal_membership(&mut self, link: &Address) -> Result<()> {
+        self.user.receive_seal_membership(link)
+    }
+
+    /// Start reading the channel from a checkpoint published by the channel author instead of
+    /// replaying it from the announcement. The announcement is processed first if the subscriber
+    /// is not registered to the channel yet. Returns the address of the last message summarized
+    /// by the checkpoint, the following messages can be fetched as usual.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the checkpoint message
+    pub fn bootstrap_from_checkpoint(&mut self, link: &Address) -> Result<Address> {
+        self.user.bootstrap_from_checkpoint(link)
+    }
+
+    /// Receive and process a message adding an author to the channel, it must be signed by a
+    /// trusted author. Returns the public key of the added author.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    pub fn receive_add_author(&mut self, link: &Address) -> Result<ed25519::PublicKey> {
+        self.user.receive_add_author(link)
+    }
+
+    /// Create and send an ack message acknowledging that the channel was read up to the message,
+    /// eg. for the author to track delivery. The sequence number of the message is acknowledged.
+    ///
+    ///  # Arguments
+    ///  * `up_to` - Address of the last message read
+    pub fn send_ack(&mut self, up_to: &Address) -> Result<(Address, Option<Address>)> {
+        self.user.send_ack(up_to)
+    }
+
     /// Receive and process a signed packet message.
     ///
     ///  # Arguments
@@ -248,6 +997,18 @@ impl<Trans: Transport + Clone> Subscriber<Trans> {
         self.user.receive_tagged_packet(link)
     }
 
+    /// Receive and process a message with application defined content, see `User::handle_custom`.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///  * `content` - Content used to unwrap the message
+    pub fn handle_custom<C>(&mut self, link: &Address, content: C) -> Result<GenericMessage<Address, C>>
+    where
+        C: ContentUnwrap<DefaultF, LinkStore>,
+    {
+        self.user.handle_custom(link, content)
+    }
+
     /// Receive and process a sequence message.
     ///
     ///  # Arguments
@@ -261,6 +1022,31 @@ impl<Trans: Transport + Clone> Subscriber<Trans> {
         self.user.fetch_next_msgs()
     }
 
+    /// Retrieves and processes standalone packets of other publishers, skipping up to `max_gap`
+    /// consecutive missing packets of each publisher.
+    pub fn fetch_standalone_packets(&mut self, max_gap: usize) -> Result<Vec<UnwrappedMessage>> {
+        self.user.fetch_standalone_packets(max_gap)
+    }
+
+    /// Retrieves the next message for each user along with the position just after the message
+    pub fn fetch_next_msgs_with_positions(&mut self) -> Vec<(UnwrappedMessage, Position)> {
+        self.user.fetch_next_msgs_with_positions()
+    }
+
+    /// Counts the messages available at the next expected address of each user without processing them
+    pub fn pending_count(&mut self) -> Result<usize> {
+        self.user.pending_count()
+    }
+
+    /// Forget spongos states of messages not reachable from the given message. The announcement and
+    /// the last messages of each publisher are kept. Returns the number of removed states.
+    ///
+    ///   # Arguments
+    ///   * `reachable_from` - Address of the most recent message to be kept
+    pub fn gc_spongos(&mut self, reachable_from: &Address) -> Result<usize> {
+        self.user.gc_spongos(reachable_from)
+    }
+
     /// Retrieves the previous message from the message specified (provided the user has access to it)
     pub fn fetch_prev_msg(&mut self, link: &Address) -> Result<UnwrappedMessage> {
         self.user.fetch_prev_msg(link)
@@ -271,6 +1057,16 @@ impl<Trans: Transport + Clone> Subscriber<Trans> {
         self.user.fetch_prev_msgs(link, max)
     }
 
+    /// Verify that the message at `link` chains back to the announcement of the channel and get
+    /// the addresses of the chain, from the announcement to the message. Messages of the chain
+    /// not processed yet are processed.
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message to verify
+    pub fn verify_provenance(&mut self, link: &Address) -> Result<Vec<Address>> {
+        self.user.verify_provenance(link)
+    }
+
     /// Iteratively fetches next message until no new messages can be found, and return a vector
     /// containing all of them.
     pub fn fetch_all_next_msgs(&mut self) -> Vec<UnwrappedMessage> {
@@ -287,6 +1083,69 @@ impl<Trans: Transport + Clone> Subscriber<Trans> {
         msgs
     }
 
+    /// Fetches all the new messages and returns them newest first. Messages are fetched and
+    /// processed forward and buffered, none is returned before the latest one is fetched.
+    pub fn messages_reverse(&mut self) -> Vec<UnwrappedMessage> {
+        self.user.messages_reverse()
+    }
+
+    /// Iteratively fetches next messages until the message at `stop` has been processed or no new
+    /// messages can be found, and return a vector containing all of them.
+    ///
+    ///   # Arguments
+    ///   * `stop` - Address of the last message to be processed
+    pub fn fetch_next_msgs_until(&mut self, stop: &Address) -> Vec<UnwrappedMessage> {
+        self.user.fetch_next_msgs_until(stop)
+    }
+
+    /// List the messages of the channel stored by the transport so that the next `sync` only
+    /// retrieves listed messages. Fails with `MessageListingUnsupported` if the transport can't
+    /// enumerate messages, `sync` then probes the next address of every publisher. Returns the
+    /// number of listed messages.
+    pub fn discover_messages(&mut self) -> Result<usize> {
+        self.user.discover_messages()
+    }
+
+    /// Fetch and process next messages of all publishers until no progress is made. Messages linked
+    /// to a not yet processed message of another publisher are retried once more messages are
+    /// processed, without fetching them again. Returns the number of processed messages and the
+    /// number of messages left orphaned.
+    pub fn sync(&mut self) -> Result<(usize, usize)> {
+        self.user.sync()
+    }
+
+    /// Fetch and process next messages of all publishers like `sync`, skipping messages which can't
+    /// be processed, eg. corrupt ones, instead of stopping at them. Messages linked to a skipped
+    /// message are skipped as well. Returns the number of processed messages and the addresses of
+    /// the skipped ones.
+    pub fn sync_skipping_errors(&mut self) -> Result<(usize, Vec<Address>)> {
+        self.user.sync_skipping_errors()
+    }
+
+    /// Fetch and process next messages of all publishers until no new message appears for a
+    /// number of consecutive rounds, see `set_sync_max_misses`. It doesn't rely on the transport
+    /// failing on missing addresses. Returns the number of fetched messages and whether the next
+    /// expected addresses were all missing, ie. the head of the channel was reached.
+    pub fn sync_to_head(&mut self) -> Result<SyncToHead> {
+        self.user.sync_to_head()
+    }
+
+    /// Fetch and process next messages of all publishers like `sync`, persisting the user state
+    /// every `batch` processed messages and once synchronization completes. If synchronization is
+    /// interrupted, at most `batch` processed messages are lost and processed again by the next
+    /// `sync` of the imported state.
+    ///
+    ///   # Arguments
+    ///   * `pwd` - Encryption password of the persisted state
+    ///   * `batch` - Number of processed messages between persisted states
+    ///   * `persist` - Callback storing the exported user state
+    pub fn sync_persisting<P>(&mut self, pwd: &str, batch: usize, persist: P) -> Result<(usize, usize)>
+    where
+        P: FnMut(&[u8]) -> Result<()>,
+    {
+        self.user.sync_persisting(1, pwd, batch, persist)
+    }
+
     /// Iteratively fetches next messages until internal state has caught up
     pub fn sync_state(&mut self) {
         let mut exists = true;
@@ -304,10 +1163,36 @@ impl<Trans: Transport + Clone> Subscriber<Trans> {
     pub fn receive_msg(&mut self, link: &Address) -> Result<UnwrappedMessage> {
         self.user.receive_message(link)
     }
+
+    /// Receive and process a message expected from the given publisher, the message is rejected
+    /// without processing it if its header declares another publisher
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message to be processed
+    ///   * `expected` - Identifier of the expected publisher
+    pub fn receive_msg_from(&mut self, link: &Address, expected: &Identifier) -> Result<UnwrappedMessage> {
+        self.user.receive_message_from(link, expected)
+    }
+
+    /// Receive and process the messages of a publisher from its sequencing state up to the one with
+    /// the given sequence number, the returned messages end with the requested one. Receiving stops
+    /// with an error if there is no message at the computed address.
+    ///
+    ///   # Arguments
+    ///   * `publisher` - Identifier of the publisher of the message
+    ///   * `sequence` - Sequence number of the message
+    pub fn receive_msg_at(&mut self, publisher: &Identifier, sequence: usize) -> Result<Vec<UnwrappedMessage>> {
+        self.user.receive_message_at(publisher, sequence)
+    }
 }
 
 #[cfg(feature = "async")]
 impl<Trans: Transport + Clone> Subscriber<Trans> {
+    /// Publish the messages of a succeeded transaction which could not be published
+    pub async fn publish_pending(&mut self) -> Result<()> {
+        self.user.publish_pending().await
+    }
+
     /// Generates a new Subscriber implementation from input. It then syncs state of the user from
     /// the given announcement message link
     ///
@@ -331,6 +1216,16 @@ impl<Trans: Transport + Clone> Subscriber<Trans> {
         self.user.send_subscribe(link_to).await
     }
 
+    /// Create and Send a Subscribe message carrying an application payload for the author, eg. a
+    /// display name. The payload is masked with the key shared with the author.
+    ///
+    /// # Arguments
+    /// * `link_to` - Address of the Channel Announcement message
+    /// * `payload` - Wrapped vector of Bytes readable by the author only
+    pub async fn send_subscribe_with_payload(&mut self, link_to: &Address, payload: &Bytes) -> Result<Address> {
+        self.user.send_subscribe_with_payload(link_to, payload).await
+    }
+
     /// Create and send a signed packet.
     ///
     ///  # Arguments
@@ -348,6 +1243,46 @@ impl<Trans: Transport + Clone> Subscriber<Trans> {
             .await
     }
 
+    /// Create and send a tagged packet bound to an application context. The context is sent in
+    /// plain and is covered by the MAC.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    ///  * `context` - Application context (eg. external request id)
+    pub async fn send_tagged_packet_with_context(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        context: &Bytes,
+    ) -> Result<(Address, Option<Address>)> {
+        self.user
+            .send_tagged_packet_with_context(link_to, public_payload, masked_payload, context)
+            .await
+    }
+
+    /// Create and send a tagged packet tagged with an application type. The type is sent in plain
+    /// and is covered by the MAC.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    ///  * `app_type` - Application defined content type, 0 is the default type
+    pub async fn send_tagged_packet_with_app_type(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        app_type: u16,
+    ) -> Result<(Address, Option<Address>)> {
+        self.user
+            .send_tagged_packet_with_app_type(link_to, public_payload, masked_payload, app_type)
+            .await
+    }
+
     /// Create and send a tagged packet.
     ///
     ///  # Arguments
@@ -365,6 +1300,43 @@ impl<Trans: Transport + Clone> Subscriber<Trans> {
             .await
     }
 
+    /// Create and send a signed packet with the given options, see `PacketOptions`. The detached
+    /// signature is returned if requested by the options.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    ///  * `options` - Options of the packet
+    pub async fn send_signed_packet_with(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        options: &PacketOptions,
+    ) -> Result<((Address, Option<Address>), Option<DetachedSignature>)> {
+        self.user
+            .send_signed_packet_with(link_to, public_payload, masked_payload, options)
+            .await
+    }
+
+    /// Create and send a chain of signed packets, each one linked to the previous one. The packets
+    /// are wrapped before any of them is sent, the user state is restored if the batch fails.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the first packet will be attached to
+    ///  * `payloads` - Public and masked payloads of the packets
+    pub async fn send_signed_packets<I>(
+        &mut self,
+        link_to: &Address,
+        payloads: I,
+    ) -> Result<Vec<(Address, Option<Address>)>>
+    where
+        I: IntoIterator<Item = (Bytes, Bytes)>,
+    {
+        self.user.send_signed_packets(link_to, payloads).await
+    }
+
     // Unsubscribe from the Channel app instance.
     // pub pub async fn unsubscribe(&mut self, link_to: &Address) -> Result<Message> {
     // TODO: lookup link_to Subscribe message.
@@ -379,6 +1351,25 @@ impl<Trans: Transport + Clone> Subscriber<Trans> {
         self.user.receive_announcement(link).await
     }
 
+    /// Fetch an announcement and read its author identity, exchange key and channel metadata
+    /// without following the channel, eg. to ask the user whether to follow it. Nothing is
+    /// stored, the subscriber may already be connected to another channel.
+    ///
+    /// # Arguments
+    /// * `link` - Address of the Channel Announcement message
+    pub async fn peek_announcement(&mut self, link: &Address) -> Result<AnnouncementInfo> {
+        self.user.peek_announcement(link).await
+    }
+
+    /// Join the channel with an invitation created by its author, the announcement is fetched and
+    /// processed. The PSK referenced by the invitation, if any, has to be stored with `store_psk`.
+    ///
+    /// # Arguments
+    /// * `bytes` - Invitation encoded by `Author::create_invitation`
+    pub async fn accept_invitation(&mut self, bytes: &[u8]) -> Result<Invitation> {
+        self.user.accept_invitation(bytes).await
+    }
+
     /// Receive and process a keyload message.
     ///
     ///  # Arguments
@@ -387,6 +1378,52 @@ impl<Trans: Transport + Clone> Subscriber<Trans> {
         self.user.receive_keyload(link).await
     }
 
+    /// Receive and process a close message, it must be signed by the channel author.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    pub async fn receive_close(&mut self, link: &Address) -> Result<()> {
+        self.user.receive_close(link).await
+    }
+
+    /// Receive and process a message sealing the channel membership, it must be signed by a
+    /// trusted author.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    pub async fn receive_seal_membership(&mut self, link: &Address) -> Result<()> {
+        self.user.receive_seal_membership(link).await
+    }
+
+    /// Start reading the channel from a checkpoint published by the channel author instead of
+    /// replaying it from the announcement. The announcement is processed first if the subscriber
+    /// is not registered to the channel yet. Returns the address of the last message summarized
+    /// by the checkpoint, the following messages can be fetched as usual.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the checkpoint message
+    pub async fn bootstrap_from_checkpoint(&mut self, link: &Address) -> Result<Address> {
+        self.user.bootstrap_from_checkpoint(link).await
+    }
+
+    /// Receive and process a message adding an author to the channel, it must be signed by a
+    /// trusted author. Returns the public key of the added author.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    pub async fn receive_add_author(&mut self, link: &Address) -> Result<ed25519::PublicKey> {
+        self.user.receive_add_author(link).await
+    }
+
+    /// Create and send an ack message acknowledging that the channel was read up to the message,
+    /// eg. for the author to track delivery. The sequence number of the message is acknowledged.
+    ///
+    ///  # Arguments
+    ///  * `up_to` - Address of the last message read
+    pub async fn send_ack(&mut self, up_to: &Address) -> Result<(Address, Option<Address>)> {
+        self.user.send_ack(up_to).await
+    }
+
     /// Receive and process a signed packet message.
     ///
     ///  # Arguments
@@ -403,6 +1440,18 @@ impl<Trans: Transport + Clone> Subscriber<Trans> {
         self.user.receive_tagged_packet(link).await
     }
 
+    /// Receive and process a message with application defined content, see `User::handle_custom`.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///  * `content` - Content used to unwrap the message
+    pub async fn handle_custom<C>(&mut self, link: &Address, content: C) -> Result<GenericMessage<Address, C>>
+    where
+        C: ContentUnwrap<DefaultF, LinkStore>,
+    {
+        self.user.handle_custom(link, content).await
+    }
+
     /// Receive and process a sequence message.
     ///
     ///  # Arguments
@@ -416,6 +1465,31 @@ impl<Trans: Transport + Clone> Subscriber<Trans> {
         self.user.fetch_next_msgs().await
     }
 
+    /// Retrieves and processes standalone packets of other publishers, skipping up to `max_gap`
+    /// consecutive missing packets of each publisher.
+    pub async fn fetch_standalone_packets(&mut self, max_gap: usize) -> Result<Vec<UnwrappedMessage>> {
+        self.user.fetch_standalone_packets(max_gap).await
+    }
+
+    /// Retrieves the next message for each user along with the position just after the message
+    pub async fn fetch_next_msgs_with_positions(&mut self) -> Vec<(UnwrappedMessage, Position)> {
+        self.user.fetch_next_msgs_with_positions().await
+    }
+
+    /// Counts the messages available at the next expected address of each user without processing them
+    pub async fn pending_count(&mut self) -> Result<usize> {
+        self.user.pending_count().await
+    }
+
+    /// Forget spongos states of messages not reachable from the given message. The announcement and
+    /// the last messages of each publisher are kept. Returns the number of removed states.
+    ///
+    ///   # Arguments
+    ///   * `reachable_from` - Address of the most recent message to be kept
+    pub async fn gc_spongos(&mut self, reachable_from: &Address) -> Result<usize> {
+        self.user.gc_spongos(reachable_from).await
+    }
+
     /// Retrieves the previous message from the message specified (provided the user has access to it)
     pub async fn fetch_prev_msg(&mut self, link: &Address) -> Result<UnwrappedMessage> {
         self.user.fetch_prev_msg(link).await
@@ -426,6 +1500,16 @@ impl<Trans: Transport + Clone> Subscriber<Trans> {
         self.user.fetch_prev_msgs(link, max).await
     }
 
+    /// Verify that the message at `link` chains back to the announcement of the channel and get
+    /// the addresses of the chain, from the announcement to the message. Messages of the chain
+    /// not processed yet are processed.
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message to verify
+    pub async fn verify_provenance(&mut self, link: &Address) -> Result<Vec<Address>> {
+        self.user.verify_provenance(link).await
+    }
+
     /// Iteratively fetches next message until no new messages can be found, and return a vector
     /// containing all of them.
     pub async fn fetch_all_next_msgs(&mut self) -> Vec<UnwrappedMessage> {
@@ -442,6 +1526,69 @@ impl<Trans: Transport + Clone> Subscriber<Trans> {
         msgs
     }
 
+    /// Fetches all the new messages and returns them newest first. Messages are fetched and
+    /// processed forward and buffered, none is returned before the latest one is fetched.
+    pub async fn messages_reverse(&mut self) -> Vec<UnwrappedMessage> {
+        self.user.messages_reverse().await
+    }
+
+    /// Iteratively fetches next messages until the message at `stop` has been processed or no new
+    /// messages can be found, and return a vector containing all of them.
+    ///
+    ///   # Arguments
+    ///   * `stop` - Address of the last message to be processed
+    pub async fn fetch_next_msgs_until(&mut self, stop: &Address) -> Vec<UnwrappedMessage> {
+        self.user.fetch_next_msgs_until(stop).await
+    }
+
+    /// List the messages of the channel stored by the transport so that the next `sync` only
+    /// retrieves listed messages. Fails with `MessageListingUnsupported` if the transport can't
+    /// enumerate messages, `sync` then probes the next address of every publisher. Returns the
+    /// number of listed messages.
+    pub async fn discover_messages(&mut self) -> Result<usize> {
+        self.user.discover_messages().await
+    }
+
+    /// Fetch and process next messages of all publishers until no progress is made. Messages linked
+    /// to a not yet processed message of another publisher are retried once more messages are
+    /// processed, without fetching them again. Returns the number of processed messages and the
+    /// number of messages left orphaned.
+    pub async fn sync(&mut self) -> Result<(usize, usize)> {
+        self.user.sync().await
+    }
+
+    /// Fetch and process next messages of all publishers like `sync`, skipping messages which can't
+    /// be processed, eg. corrupt ones, instead of stopping at them. Messages linked to a skipped
+    /// message are skipped as well. Returns the number of processed messages and the addresses of
+    /// the skipped ones.
+    pub async fn sync_skipping_errors(&mut self) -> Result<(usize, Vec<Address>)> {
+        self.user.sync_skipping_errors().await
+    }
+
+    /// Fetch and process next messages of all publishers until no new message appears for a
+    /// number of consecutive rounds, see `set_sync_max_misses`. It doesn't rely on the transport
+    /// failing on missing addresses. Returns the number of fetched messages and whether the next
+    /// expected addresses were all missing, ie. the head of the channel was reached.
+    pub async fn sync_to_head(&mut self) -> Result<SyncToHead> {
+        self.user.sync_to_head().await
+    }
+
+    /// Fetch and process next messages of all publishers like `sync`, persisting the user state
+    /// every `batch` processed messages and once synchronization completes. If synchronization is
+    /// interrupted, at most `batch` processed messages are lost and processed again by the next
+    /// `sync` of the imported state.
+    ///
+    ///   # Arguments
+    ///   * `pwd` - Encryption password of the persisted state
+    ///   * `batch` - Number of processed messages between persisted states
+    ///   * `persist` - Callback storing the exported user state
+    pub async fn sync_persisting<P>(&mut self, pwd: &str, batch: usize, persist: P) -> Result<(usize, usize)>
+    where
+        P: FnMut(&[u8]) -> Result<()>,
+    {
+        self.user.sync_persisting(1, pwd, batch, persist).await
+    }
+
     /// Iteratively fetches next messages until internal state has caught up
     pub async fn sync_state(&mut self) {
         let mut exists = true;
@@ -459,6 +1606,27 @@ impl<Trans: Transport + Clone> Subscriber<Trans> {
     pub async fn receive_msg(&mut self, link: &Address) -> Result<UnwrappedMessage> {
         self.user.receive_message(link).await
     }
+
+    /// Receive and process a message expected from the given publisher, the message is rejected
+    /// without processing it if its header declares another publisher
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message to be processed
+    ///   * `expected` - Identifier of the expected publisher
+    pub async fn receive_msg_from(&mut self, link: &Address, expected: &Identifier) -> Result<UnwrappedMessage> {
+        self.user.receive_message_from(link, expected).await
+    }
+
+    /// Receive and process the messages of a publisher from its sequencing state up to the one with
+    /// the given sequence number, the returned messages end with the requested one. Receiving stops
+    /// with an error if there is no message at the computed address.
+    ///
+    ///   # Arguments
+    ///   * `publisher` - Identifier of the publisher of the message
+    ///   * `sequence` - Sequence number of the message
+    pub async fn receive_msg_at(&mut self, publisher: &Identifier, sequence: usize) -> Result<Vec<UnwrappedMessage>> {
+        self.user.receive_message_at(publisher, sequence).await
+    }
 }
 
 impl<T: Transport + Clone> fmt::Display for Subscriber<T> {
@@ -471,3 +1639,10 @@ impl<T: Transport + Clone> fmt::Display for Subscriber<T> {
         )
     }
 }
+
+impl<T> fmt::Debug for Subscriber<T> {
+    /// Private keys of the user are redacted.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Subscriber").field("user", &self.user).finish()
+    }
+}
@@ -1,30 +1,61 @@
+use core::fmt;
+
 use iota_streams_app::{
     identifier::Identifier,
     message::{
+        ContentUnwrap,
+        GenericMessage,
         HasLink as _,
         LinkGenerator,
     },
 };
 use iota_streams_core::{
     err,
-    prelude::Vec,
+    prelude::{
+        HashSet,
+        String,
+        ToString,
+        Vec,
+    },
     prng,
     psk::{
         Psk,
         PskId,
     },
+    sponge::spongos::Spongos,
     try_or,
     Errors::{
         ChannelDuplication,
+        ConfirmationInTransaction,
+        InvitationAuthorMismatch,
+        InvitationNotFromAuthor,
+        MessageLinkNotFound,
+        ProvenanceCycle,
+        ProvenanceLinkMissing,
+        ProvenanceRootMismatch,
+        SeqNumAlreadyProcessed,
+        SeqNumMessageNotFound,
+        SeqNumMessageOrphaned,
+        StandalonePacketsUnsupported,
+        TooManyOrphans,
+        TooManySubscribers,
+        UnexpectedPublisher,
         UnknownMsgType,
+        UnknownPsk,
+        UnknownSubscriberGroup,
         UserNotRegistered,
     },
     Result,
 };
+use iota_streams_core_edsig::key_exchange::x25519;
+use iota_streams_ddml::link_store::LinkStore as _;
 
 use super::*;
 use crate::{
-    api,
+    api::{
+        self,
+        key_store::KeyStore as _,
+    },
     message,
 };
 
@@ -32,11 +63,148 @@ type UserImp = api::user::User<DefaultF, Address, LinkGen, LinkStore, KeyStore>;
 
 const ENCODING: &str = "utf-8";
 const PAYLOAD_LENGTH: usize = 32_000;
+const DEFAULT_MAX_ORPHANS: usize = 1024;
+const DEFAULT_SYNC_MAX_MISSES: usize = 3;
+
+/// Policy applied when processing messages would buffer more orphans than allowed. An orphan is a
+/// message linked to a message that is not processed yet, it is retried once more messages are
+/// processed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OrphanPolicy {
+    /// Fail with `TooManyOrphans`.
+    Error,
+    /// Drop the oldest buffered orphan, it can be fetched again later.
+    DropOldest,
+}
+
+impl Default for OrphanPolicy {
+    fn default() -> Self {
+        OrphanPolicy::DropOldest
+    }
+}
+
+/// Progress of a `sync` reported to the callback set with `User::set_sync_progress_callback`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SyncProgress {
+    /// Number of messages processed so far, skipped messages included.
+    pub processed: usize,
+    /// Estimated number of messages to process, raised when more messages are processed than
+    /// estimated.
+    pub total: usize,
+}
+
+impl SyncProgress {
+    /// Processed share of the estimated messages, in percent.
+    pub fn percent(&self) -> u8 {
+        if self.total == 0 {
+            100
+        } else {
+            (self.processed.min(self.total) * 100 / self.total) as u8
+        }
+    }
+}
+
+/// Outcome of `User::sync_to_head`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SyncToHead {
+    /// Number of messages fetched and processed.
+    pub fetched: usize,
+    /// Whether no message was found at the next expected address of any publisher. `false` if
+    /// messages were found but could not be processed until the miss threshold was hit, eg.
+    /// messages linked to a message not retrievable yet.
+    pub reached_head: bool,
+}
+
+/// Reports progress of a running `sync` to the callback every `interval` processed messages.
+struct SyncReporter {
+    callback: SyncProgressCallback,
+    interval: usize,
+    progress: SyncProgress,
+}
+
+impl SyncReporter {
+    fn advance(&mut self) {
+        self.progress.processed += 1;
+        self.progress.total = self.progress.total.max(self.progress.processed);
+        if self.progress.processed % self.interval == 0 {
+            (self.callback)(&self.progress);
+        }
+    }
+
+    /// Report completion, no more messages can be processed.
+    fn finish(mut self) {
+        self.progress.total = self.progress.processed;
+        (self.callback)(&self.progress);
+    }
+}
+
+/// Persists the user state every `batch` messages processed by `sync_persisting`.
+struct SyncPersister<'a> {
+    persist: &'a mut dyn FnMut(&[u8]) -> Result<()>,
+    flag: u8,
+    pwd: &'a str,
+    batch: usize,
+    pending: usize,
+}
+
+impl<'a> SyncPersister<'a> {
+    fn advance(&mut self, user: &UserImp) -> Result<()> {
+        self.pending += 1;
+        if self.pending == self.batch {
+            self.persist(user)?;
+        }
+        Ok(())
+    }
+
+    /// Persist the progress made since the last batch, if any.
+    fn finish(mut self, user: &UserImp) -> Result<()> {
+        if self.pending != 0 {
+            self.persist(user)?;
+        }
+        Ok(())
+    }
+
+    fn persist(&mut self, user: &UserImp) -> Result<()> {
+        let backup = user.export(self.flag, self.pwd)?;
+        (self.persist)(&backup)?;
+        self.pending = 0;
+        Ok(())
+    }
+}
 
 /// Baseline User api object. Contains the api user implementation as well as the transport object
+#[derive(Clone)]
 pub struct User<Trans> {
     pub user: UserImp,
     pub transport: Trans,
+    /// Maximum number of orphaned messages buffered while synchronizing
+    max_orphans: usize,
+    orphan_policy: OrphanPolicy,
+    /// Links listed by the transport for the next `sync`, along with the channel they belong to
+    discovered: Option<(Address, HashSet<Address>)>,
+    /// Callback reporting `sync` progress along with the number of messages between reports
+    sync_progress: Option<(SyncProgressCallback, usize)>,
+    /// Number of consecutive rounds without new messages after which `sync_to_head` stops
+    sync_max_misses: usize,
+    /// Messages sent within a `transaction`, they are published once it succeeds
+    staged: Option<Vec<Message>>,
+    /// Messages of a succeeded `transaction` which are not published yet, see `publish_pending`
+    pending: Vec<Message>,
+}
+
+/// Snapshot of the user state taken with `User::checkpoint`.
+#[derive(Clone)]
+pub struct Checkpoint {
+    user: UserImp,
+}
+
+/// Scope of a transaction started with `User::begin_transaction`.
+#[cfg(not(feature = "async"))]
+pub(crate) struct TransactionScope {
+    checkpoint: Checkpoint,
+    /// Number of messages staged by the enclosing transactions
+    staged: usize,
+    nested: bool,
 }
 
 impl<Trans> User<Trans> {
@@ -55,7 +223,85 @@ impl<Trans> User<Trans> {
             ENCODING.as_bytes().to_vec(),
             PAYLOAD_LENGTH,
         );
-        Self { user, transport }
+        Self {
+            user,
+            transport,
+            max_orphans: DEFAULT_MAX_ORPHANS,
+            orphan_policy: OrphanPolicy::default(),
+            discovered: None,
+            sync_progress: None,
+            sync_max_misses: DEFAULT_SYNC_MAX_MISSES,
+            staged: None,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Create a new User instance from an existing raw Ed25519 secret key.
+    ///
+    /// # Arguments
+    /// * `secret` - Ed25519 secret key bytes
+    /// * `channel_type` - Implementation type: [0: Single Branch, 1: Multi Branch , 2: Single Depth]
+    /// * `transport` - Transport object used for sending and receiving
+    pub fn from_ed25519_secret(
+        secret: [u8; ed25519::SECRET_KEY_LENGTH],
+        channel_type: ChannelType,
+        transport: Trans,
+    ) -> Self {
+        let user = UserImp::from_ed25519_secret(secret, channel_type, ENCODING.as_bytes().to_vec(), PAYLOAD_LENGTH);
+        Self {
+            user,
+            transport,
+            max_orphans: DEFAULT_MAX_ORPHANS,
+            orphan_policy: OrphanPolicy::default(),
+            discovered: None,
+            sync_progress: None,
+            sync_max_misses: DEFAULT_SYNC_MAX_MISSES,
+            staged: None,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Create a read-only User instance from the secret of a viewing key, see
+    /// `api::user::User::from_viewing_secret`.
+    ///
+    /// # Arguments
+    /// * `secret` - Secret of the viewing key
+    /// * `channel_type` - Implementation type: [0: Single Branch, 1: Multi Branch , 2: Single Depth]
+    /// * `transport` - Transport object used for sending and receiving
+    pub fn from_viewing_secret(secret: [u8; 32], channel_type: ChannelType, transport: Trans) -> Self {
+        let user = UserImp::from_viewing_secret(secret, channel_type, ENCODING.as_bytes().to_vec(), PAYLOAD_LENGTH);
+        Self {
+            user,
+            transport,
+            max_orphans: DEFAULT_MAX_ORPHANS,
+            orphan_policy: OrphanPolicy::default(),
+            discovered: None,
+            sync_progress: None,
+            sync_max_misses: DEFAULT_SYNC_MAX_MISSES,
+            staged: None,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Derive a child User with an independent key pair from the user key pair and `index`, see
+    /// `api::user::User::derive_child`. The same user key pair and index always derive the same child.
+    ///
+    /// # Arguments
+    /// * `index` - Index of the child
+    /// * `channel_type` - Implementation type: [0: Single Branch, 1: Multi Branch , 2: Single Depth]
+    /// * `transport` - Transport object used for sending and receiving
+    pub fn derive_child(&self, index: u32, channel_type: ChannelType, transport: Trans) -> Self {
+        Self {
+            user: self.user.derive_child(index, channel_type),
+            transport,
+            max_orphans: DEFAULT_MAX_ORPHANS,
+            orphan_policy: OrphanPolicy::default(),
+            discovered: None,
+            sync_progress: None,
+            sync_max_misses: DEFAULT_SYNC_MAX_MISSES,
+            staged: None,
+            pending: Vec::new(),
+        }
     }
 
     pub fn get_transport(&self) -> &Trans {
@@ -69,11 +315,54 @@ impl<Trans> User<Trans> {
         self.user.appinst.as_ref().map(|x| &x.appinst)
     }
 
+    /// Addresses of the channels the user has created or joined, least recently connected first
+    /// [Author, Subscriber]
+    pub fn connection_history(&self) -> impl Iterator<Item = Address> + '_ {
+        self.user.connection_history().cloned()
+    }
+
+    /// Limit the number of channels kept in the connection history [Author, Subscriber]
+    pub fn set_max_connection_history(&mut self, max_connection_history: usize) {
+        self.user.set_max_connection_history(max_connection_history)
+    }
+
     /// Channel Author's signature public key
     pub fn author_public_key(&self) -> Option<&ed25519::PublicKey> {
         self.user.author_public_key()
     }
 
+    /// Signature public keys of the authors added to the channel, the announcing author excluded
+    pub fn co_authors(&self) -> &[PublicKey] {
+        self.user.co_authors()
+    }
+
+    /// Compare the channel state with that of another user, eg. to find why a subscriber sees
+    /// fewer messages than the author. No key material is reported [Author, Subscriber]
+    pub fn diff<T>(&self, other: &User<T>) -> StateDiff {
+        self.user.diff(&other.user)
+    }
+
+    /// Channel Author's key exchange public key
+    pub fn author_exchange_key(&self) -> Option<x25519::PublicKey> {
+        self.user.author_exchange_key()
+    }
+
+    /// Application metadata carried by the channel announcement, if any [Author, Subscriber]
+    pub fn announcement_metadata(&self) -> Option<&[u8]> {
+        self.user.announcement_metadata()
+    }
+
+    /// Returns true if the channel was closed by its author [Author, Subscriber]
+    pub fn is_closed(&self) -> bool {
+        self.user.is_closed()
+    }
+
+    /// Returns true if the channel membership was sealed by its author, subscriptions of new
+    /// subscribers are rejected [Author, Subscriber]
+    pub fn is_membership_sealed(&self) -> bool {
+        self.user.is_membership_sealed()
+    }
+
     /// Return boolean representing the sequencing nature of the channel
     pub fn is_multi_branching(&self) -> bool {
         self.user.is_multi_branching()
@@ -89,6 +378,17 @@ impl<Trans> User<Trans> {
         &self.user.sig_kp.public
     }
 
+    /// Fetch the user x25519 viewing key, granting read access only when targeted by a keyload
+    /// [Author, Subscriber]
+    pub fn viewing_key(&self) -> &x25519::PublicKey {
+        self.user.viewing_key()
+    }
+
+    /// Fetch the secret of the user viewing key, see `from_viewing_secret` [Author, Subscriber]
+    pub fn viewing_secret(&self) -> [u8; 32] {
+        self.user.viewing_secret()
+    }
+
     pub fn is_registered(&self) -> bool {
         self.user.appinst.is_some()
     }
@@ -96,6 +396,49 @@ impl<Trans> User<Trans> {
     pub fn unregister(&mut self) {
         self.user.appinst = None;
         self.user.author_sig_pk = None;
+        self.user.co_authors.clear();
+    }
+
+    /// Detach the active channel state and keep it for later use. The user is left unregistered
+    /// and can create or subscribe to another channel. Only the active channel is used by send and
+    /// receive methods, stashed channels are kept in backups.
+    pub fn stash_channel(&mut self) -> Result<()> {
+        self.user.stash_channel()
+    }
+
+    /// Make a previously stashed channel active. The active channel (if any) is stashed.
+    ///
+    /// # Arguments
+    /// * `channel` - Address of the stashed channel
+    pub fn switch_channel(&mut self, channel: &ChannelAddress) -> Result<()> {
+        self.user.switch_channel(channel)
+    }
+
+    /// Fetch the Addresses of stashed channels.
+    pub fn stashed_channels(&self) -> Vec<&ChannelAddress> {
+        self.user.stashed_channels()
+    }
+
+    /// Take a snapshot of the user state: link store, sequencing states, keys and stashed channels.
+    /// The snapshot is a deep copy, the transport is not part of it.
+    ///
+    /// Together with `restore` it allows to make a group of operations atomic with respect to the
+    /// user state. In async builds, where `transaction` is not available, use them directly.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            user: self.user.clone(),
+        }
+    }
+
+    /// Restore the user state from a snapshot, discarding all changes made since it was taken.
+    /// Messages sent in the meantime are not retracted from the transport: sending again would
+    /// derive their addresses and fail, use `transaction` to publish messages only once all the
+    /// operations succeed.
+    ///
+    /// # Arguments
+    /// * `checkpoint` - Snapshot taken with `checkpoint`
+    pub fn restore(&mut self, checkpoint: Checkpoint) {
+        self.user = checkpoint.user;
     }
 
     // Utility
@@ -131,6 +474,13 @@ impl<Trans> User<Trans> {
         self.user.fetch_state()
     }
 
+    /// Address of the latest processed message of each publisher, ordered by publisher identifier,
+    /// publishers with no message beyond the announcement are skipped
+    /// [Author, Subscriber]
+    pub fn heads(&self) -> Result<Vec<(Identifier, Address)>> {
+        self.user.heads()
+    }
+
     /// Resets the cursor state storage to allow a Subscriber to retrieve all messages in a channel
     /// from scratch
     /// [Subscriber]
@@ -138,6 +488,177 @@ impl<Trans> User<Trans> {
         self.user.reset_state()
     }
 
+    /// Takes a lightweight snapshot of the sequence state of each publisher, without link store
+    /// and keys, allowing to persist sync progress separately of a full backup
+    /// [Author, Subscriber]
+    pub fn cursor_snapshot(&self) -> Vec<(Identifier, SeqState)> {
+        self.user.cursor_snapshot()
+    }
+
+    /// Number of message spongos states kept by the user [Author, Subscriber]
+    pub fn spongos_store_len(&self) -> usize {
+        self.user.spongos_store_len()
+    }
+
+    /// Digest committing to all the processed messages of the channel, eg. to anchor the channel
+    /// integrity in an external system. Users with the same view of the channel get the same digest
+    /// [Author, Subscriber]
+    pub fn chain_digest(&self) -> [u8; 32] {
+        let mut digest = [0; 32];
+        digest.copy_from_slice(self.user.chain_digest().as_ref());
+        digest
+    }
+
+    /// Pairs of processed messages and the messages they are linked to, eg. to render the message
+    /// graph or find orphaned subtrees [Author, Subscriber]
+    pub fn dependency_edges(&self) -> Vec<(MsgId, MsgId)> {
+        self.user.dependency_edges()
+    }
+
+    /// Recipients of the keyload recorded when it was sent or processed, eg. to publish the set of
+    /// recipients for auditing. `None` if the keyload is unknown or was not accessible
+    /// [Author, Subscriber]
+    pub fn keyload_recipients(&self, keyload: &MsgId) -> Option<Vec<Identifier>> {
+        self.user.keyload_recipients(keyload).map(|recipients| recipients.to_vec())
+    }
+
+    /// The way the user was granted access to the session key of a processed keyload, `Denied` if
+    /// the keyload is unknown or the user is not among its recipients [Subscriber]
+    pub fn keyload_access(&self, keyload: &MsgId) -> KeyloadAccess {
+        self.user.keyload_access(keyload)
+    }
+
+    /// Addresses of processed signed packets published under the topic, in processing order.
+    /// Packets without a topic are listed under `DEFAULT_TOPIC` [Author, Subscriber]
+    pub fn messages_for_topic(&self, topic: &str) -> Vec<Address> {
+        self.user.messages_for_topic(topic).to_vec()
+    }
+
+    /// Highest position acknowledged by the subscriber in processed ack messages, acks processed
+    /// out of order do not lower it [Author]
+    pub fn acked_position(&self, subscriber: &PublicKey) -> Option<usize> {
+        self.user.acked_position(&(*subscriber).into())
+    }
+
+    /// Copy of the spongos state of the message, eg. to link a custom message to it [Author, Subscriber]
+    pub fn spongos_for(&self, msg: &MsgId) -> Option<Spongos<DefaultF>> {
+        self.user.spongos_for(msg)
+    }
+
+    /// Save the committed spongos state of a custom message so that other messages can be linked
+    /// to it, see `handle_custom`. Storing a wrong state corrupts the chain: messages linked to
+    /// the custom message can't be wrapped or unwrapped correctly [Author, Subscriber]
+    pub fn store_spongos(&mut self, msg: &MsgId, spongos: Spongos<DefaultF>) -> Result<()> {
+        self.user.store_spongos(msg, spongos, MsgInfo::Custom)
+    }
+
+    /// Set callback invoked with the message id, spongos state and info each time a message
+    /// spongos state is stored while sending or handling a message. The callback is invoked
+    /// synchronously, a panic in the callback propagates [Author, Subscriber]
+    pub fn set_spongos_store_callback(&mut self, callback: Option<SpongosStoreCallback>) {
+        self.user.link_store.borrow_mut().set_on_update(callback)
+    }
+
+    /// Restores sequence states from a snapshot, link store and keys are left untouched
+    /// [Author, Subscriber]
+    ///
+    ///   # Arguments
+    ///   * `snapshot` - Sequence states previously taken with `cursor_snapshot`
+    pub fn load_cursor_snapshot(&mut self, snapshot: Vec<(Identifier, SeqState)>) -> Result<()> {
+        self.user.load_cursor_snapshot(snapshot)
+    }
+
+    /// Cursor snapshot authenticated with a key derived from the password, for storage on
+    /// untrusted infrastructure. Restore it with `load_sealed_cursor_snapshot` [Author, Subscriber]
+    ///
+    ///   # Arguments
+    ///   * `pwd` - Password protecting the snapshot
+    pub fn sealed_cursor_snapshot(&self, pwd: &str) -> Result<Vec<u8>> {
+        self.user.sealed_cursor_snapshot(pwd)
+    }
+
+    /// Verify a sealed cursor snapshot of the current channel and restore its sequence states, a
+    /// tampered snapshot is rejected and leaves the user untouched [Author, Subscriber]
+    ///
+    ///   # Arguments
+    ///   * `bytes` - Snapshot taken with `sealed_cursor_snapshot`
+    ///   * `pwd` - Password protecting the snapshot
+    pub fn load_sealed_cursor_snapshot(&mut self, bytes: &[u8], pwd: &str) -> Result<()> {
+        self.user.load_sealed_cursor_snapshot(bytes, pwd)
+    }
+
+    /// Current position in the channel, just after the last processed message [Author, Subscriber]
+    pub fn position(&self) -> Position {
+        Position(self.user.cursor_snapshot())
+    }
+
+    /// Continue processing messages just after the position [Author, Subscriber]
+    ///
+    ///   # Arguments
+    ///   * `position` - Position returned along with a processed message or by `position`
+    pub fn resume_from(&mut self, position: &Position) -> Result<()> {
+        self.user.load_cursor_snapshot(position.0.clone())
+    }
+
+    /// Create an encoded invitation to the channel, subscribers join the channel with
+    /// `accept_invitation`. The invitation carries the announcement address and the author public
+    /// keys only [Author]
+    ///
+    ///   # Arguments
+    ///   * `psk` - Id of a PSK stored with `store_psk` and shared with the subscriber out of band
+    pub fn create_invitation(&self, psk: Option<PskId>) -> Result<Vec<u8>> {
+        let announcement = match &self.user.appinst {
+            Some(appinst) => appinst.clone(),
+            None => return err!(UserNotRegistered),
+        };
+        try_or!(
+            self.author_public_key() == Some(self.get_public_key()),
+            InvitationNotFromAuthor(hex::encode(self.get_public_key().as_bytes()))
+        )?;
+        if let Some(pskid) = &psk {
+            try_or!(self.psk_ids().contains(pskid), UnknownPsk(hex::encode(pskid)))?;
+        }
+        Invitation::new(announcement, *self.get_public_key(), self.user.ke_kp.1, psk)?.to_bytes()
+    }
+
+    /// Check the message header declares the expected publisher
+    fn check_publisher(msg: &Message, expected: &Identifier) -> Result<()> {
+        let publisher = msg.binary.parse_header()?.header.sender_id;
+        try_or!(
+            &publisher == expected,
+            UnexpectedPublisher(hex::encode(expected.to_bytes()), hex::encode(publisher.to_bytes()))
+        )
+    }
+
+    /// Check whether the message is linked to a message the user hasn't processed yet. Orphan
+    /// messages are reported without being processed, so the user state is left untouched.
+    fn orphan(&self, msg: &Message) -> Result<Option<UnwrappedMessage>> {
+        let header = self.user.parse_message(&msg.binary)?.header;
+        match header.content_type {
+            message::ANNOUNCE | message::SEQUENCE => Ok(None),
+            _ => {
+                let prev_link = TangleAddress::from_bytes(&header.previous_msg_link.0);
+                if self.user.contains_spongos(&prev_link.msgid) {
+                    Ok(None)
+                } else {
+                    Ok(Some(UnwrappedMessage::new(header.link, prev_link, MessageContent::orphan())))
+                }
+            }
+        }
+    }
+
+    /// Check the announcement fetched for an invitation is signed by the invitation author
+    fn check_invitation_announcement(&self, invitation: &Invitation, msg: &BinaryMessage) -> Result<()> {
+        let author = self.user.unwrap_announcement(self.user.parse_message(msg)?)?.pcf.content.sig_pk;
+        try_or!(
+            &author == invitation.author(),
+            InvitationAuthorMismatch(
+                hex::encode(invitation.author().as_bytes()),
+                hex::encode(author.as_bytes())
+            )
+        )
+    }
+
     /// Generate a vector containing the next sequenced message identifier for each publishing
     /// participant in the channel
     /// [Author, Subscriber]
@@ -148,6 +669,16 @@ impl<Trans> User<Trans> {
         self.user.gen_next_msg_ids(branching)
     }
 
+    /// Address of the next message expected from the publisher: the next message itself in
+    /// single-branch channels, the sequence message referencing it in multi-branch channels
+    /// [Author, Subscriber]
+    ///
+    ///   # Arguments
+    ///   * `publisher` - Identifier of the publisher
+    pub fn next_address(&self, publisher: &Identifier) -> Result<Address> {
+        self.user.next_link(publisher)
+    }
+
     /// Commit to state a wrapped message and type
     /// [Author, Subscriber]
     ///
@@ -158,6 +689,36 @@ impl<Trans> User<Trans> {
         self.user.commit_wrapped(wrapped, info)
     }
 
+    /// Wrap and commit a chain of signed packets, each one linked to the previous one. Returns the
+    /// messages in sending order, sequence messages included, and the links of the packets.
+    fn wrap_signed_packet_chain<I>(
+        &mut self,
+        link_to: &Address,
+        payloads: I,
+    ) -> Result<(Vec<Message>, Vec<(Address, Option<Address>)>)>
+    where
+        I: IntoIterator<Item = (Bytes, Bytes)>,
+    {
+        let mut link = link_to.clone();
+        let mut msgs = Vec::new();
+        let mut links = Vec::new();
+        for (public_payload, masked_payload) in payloads {
+            let seq = self.user.wrap_sequence(link.rel())?;
+            let msg = self.user.sign_packet(&link, &public_payload, &masked_payload)?;
+            msgs.push(Message::new(msg.message));
+            if let Some(seq_msg) = seq.0 {
+                msgs.push(Message::new(seq_msg));
+            }
+            let seq_link = match seq.1 {
+                Some(wrap_state) => self.user.commit_sequence(wrap_state, MsgInfo::Sequence)?,
+                None => None,
+            };
+            link = self.commit_wrapped(msg.wrapped, MsgInfo::SignedPacket)?;
+            links.push((link.clone(), seq_link));
+        }
+        Ok((msgs, links))
+    }
+
     pub fn export(&self, flag: u8, pwd: &str) -> Result<Vec<u8>> {
         self.user.export(flag, pwd)
     }
@@ -165,97 +726,856 @@ impl<Trans> User<Trans> {
         UserImp::import(bytes, flag, pwd).map(|u| Self {
             user: u,
             transport: tsp,
+            max_orphans: DEFAULT_MAX_ORPHANS,
+            orphan_policy: OrphanPolicy::default(),
+            discovered: None,
+            sync_progress: None,
+            sync_max_misses: DEFAULT_SYNC_MAX_MISSES,
+            staged: None,
+            pending: Vec::new(),
         })
     }
 
-    pub fn store_psk(&mut self, pskid: PskId, psk: Psk, use_psk: bool) -> Result<()> {
-        self.user.store_psk(pskid, psk, use_psk)
+    /// Export the public state of the channel for read-only observers, no secret key is exported
+    /// [Author, Subscriber]
+    pub fn export_public_state(&self) -> Result<Vec<u8>> {
+        self.user.export_public_state()
     }
 
-    /// Consume a binary sequence message and return the derived message link
-    fn process_sequence(&mut self, msg: BinaryMessage, store: bool) -> Result<Address> {
-        let unwrapped = self.user.handle_sequence(msg, MsgInfo::Sequence, store)?;
-        let msg_link = self.user.link_gen.link_from(
-            &unwrapped.body.id,
-            Cursor::new_at(&unwrapped.body.ref_link, 0, unwrapped.body.seq_num.0 as u32),
-        );
-        Ok(msg_link)
+    /// Create a read-only observer from exported public channel state [Subscriber]
+    pub fn import_public_state(bytes: &[u8], tsp: Trans) -> Result<Self> {
+        UserImp::import_public_state(bytes).map(|u| Self {
+            user: u,
+            transport: tsp,
+            max_orphans: DEFAULT_MAX_ORPHANS,
+            orphan_policy: OrphanPolicy::default(),
+            discovered: None,
+            sync_progress: None,
+            sync_max_misses: DEFAULT_SYNC_MAX_MISSES,
+            staged: None,
+            pending: Vec::new(),
+        })
     }
-}
 
-#[cfg(not(feature = "async"))]
-impl<Trans: Transport + Clone> User<Trans> {
-    // Send
+    /// Set the length of the MAC protecting exported user state [16-64 bytes].
+    pub fn set_backup_mac_len(&mut self, mac_len: usize) -> Result<()> {
+        self.user.set_backup_mac_len(mac_len)
+    }
 
-    /// Send a message with sequencing logic. If channel is single-branched, then no secondary
-    /// sequence message is sent and None is returned for the address.
-    ///
-    /// # Arguments
-    /// * `wrapped` - A wrapped sequence object containing the sequence message and state
-    fn send_sequence(&mut self, wrapped: WrappedSequence) -> Result<Option<Address>> {
-        if let Some(seq_msg) = wrapped.0 {
-            self.transport.send_message(&Message::new(seq_msg))?;
-        }
+    /// Attach the fingerprint of the linked message state to sent messages, so that readers
+    /// joined to a wrong state get a distinct error
+    /// [Author, Subscriber]
+    pub fn set_link_fingerprint(&mut self, link_fingerprint: bool) {
+        self.user.set_link_fingerprint(link_fingerprint)
+    }
 
-        if let Some(wrap_state) = wrapped.1 {
-            self.user.commit_sequence(wrap_state, MsgInfo::Sequence)
-        } else {
-            Ok(None)
-        }
+    /// Network domain message addresses are derived in, empty by default [Author, Subscriber]
+    pub fn network_domain(&self) -> &[u8] {
+        self.user.link_gen.domain()
     }
 
-    /// Send a message without using sequencing logic. Reserved for Announce and Subscribe messages
-    fn send_message(&mut self, msg: WrappedMessage, info: MsgInfo) -> Result<Address> {
-        self.transport.send_message(&Message::new(msg.message))?;
-        self.commit_wrapped(msg.wrapped, info)
+    /// Derive message addresses in a network domain, so that isolated networks (eg. mainnet and
+    /// testnet) don't share addresses: the same keys and channel index yield different addresses
+    /// in different domains. The domain must be set before the channel is created or the
+    /// announcement is received, it is kept in backups [Author, Subscriber]
+    pub fn set_network_domain(&mut self, domain: &[u8]) {
+        self.user.link_gen.set_domain(domain)
     }
 
-    /// Send a message using sequencing logic.
-    ///
-    /// # Arguments
-    /// * `msg` - Wrapped Message ready for sending
-    /// * `ref_link` - Reference link to be included in sequence message
-    /// * `info` - Enum denominating the type of message being sent and committed
-    fn send_message_sequenced(
-        &mut self,
-        msg: WrappedMessage,
-        ref_link: &MsgId,
-        info: MsgInfo,
-    ) -> Result<(Address, Option<Address>)> {
-        let seq = self.user.wrap_sequence(ref_link)?;
-        self.transport.send_message(&Message::new(msg.message))?;
-        let seq_link = self.send_sequence(seq)?;
-        let msg_link = self.commit_wrapped(msg.wrapped, info)?;
-        Ok((msg_link, seq_link))
+    /// Limit the length of payloads accepted in received signed and tagged packets
+    /// [Author, Subscriber]
+    pub fn set_max_payload_length(&mut self, max_payload_length: usize) {
+        self.user.set_max_payload_length(max_payload_length)
     }
 
-    /// Send an announcement message, generating a channel [Author].
-    pub fn send_announce(&mut self) -> Result<Address> {
-        let msg = self.user.announce()?;
-        try_or!(
-            self.transport.recv_message(&msg.message.link).is_err(),
-            ChannelDuplication
-        )?;
-        self.send_message(msg, MsgInfo::Announce)
+    /// Limit the size of message bodies, larger messages fail with `MessageSizeExceeded` before
+    /// being allocated when sent and before being unwrapped when received [Author, Subscriber]
+    pub fn set_max_message_size(&mut self, max_message_size: usize) {
+        self.user.set_max_message_size(max_message_size)
     }
 
-    /// Create and send a signed packet [Author, Subscriber].
-    ///
-    ///  # Arguments
-    ///  * `link_to` - Address of the message the keyload will be attached to
-    ///  * `public_payload` - Wrapped vector of Bytes to have public access
-    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
-    pub fn send_signed_packet(
-        &mut self,
-        link_to: &Address,
-        public_payload: &Bytes,
-        masked_payload: &Bytes,
-    ) -> Result<(Address, Option<Address>)> {
-        let msg = self.user.sign_packet(link_to, public_payload, masked_payload)?;
-        self.send_message_sequenced(msg, link_to.rel(), MsgInfo::SignedPacket)
+    /// Limit the number of orphaned messages buffered by `sync`, messages linked to a message not
+    /// processed yet. Beyond the limit `policy` applies. Defaults to 1024 orphans, the oldest one
+    /// is dropped [Author, Subscriber]
+    pub fn set_max_orphans(&mut self, max_orphans: usize, policy: OrphanPolicy) {
+        self.max_orphans = max_orphans;
+        self.orphan_policy = policy;
     }
 
-    /// Create and send a tagged packet [Author, Subscriber].
+    /// Report the progress of `sync` to `callback` every `interval` processed messages, and once
+    /// synchronization completes with `total` equal to `processed` [Author, Subscriber]. `None`
+    /// disables reporting, the default.
+    ///
+    /// The number of messages to process is estimated before synchronization starts: from the
+    /// messages listed by `discover_messages` if it was called, otherwise by fetching messages at
+    /// expected addresses without unwrapping them, which fetches every new message twice. In
+    /// multi-branch channels only the next message of each publisher can be probed, the estimate
+    /// is raised as synchronization goes on.
+    pub fn set_sync_progress_callback(&mut self, callback: Option<SyncProgressCallback>, interval: usize) {
+        self.sync_progress = callback.map(|callback| (callback, interval.max(1)));
+    }
+
+    /// Number of consecutive rounds without new messages after which `sync_to_head` considers the
+    /// user caught up, at least 1. Defaults to 3, so that messages not yet visible to an eventually
+    /// consistent transport are fetched again [Author, Subscriber]
+    pub fn set_sync_max_misses(&mut self, max_misses: usize) {
+        self.sync_max_misses = max_misses.max(1);
+    }
+
+    /// Number of messages listed by `discover_messages` for the current channel and not processed
+    /// yet, `None` if no messages were listed.
+    fn discovered_pending(&self) -> Option<usize> {
+        match &self.discovered {
+            Some((appinst, links)) if self.user.appinst.as_ref() == Some(appinst) => {
+                let link_store = self.user.link_store.borrow();
+                Some(links.iter().filter(|link| link_store.lookup(&link.msgid).is_err()).count())
+            }
+            _ => None,
+        }
+    }
+
+    /// Expected addresses of the next message of every publisher in a single-branch channel once
+    /// the message `msgid` with sequence number `seq_no - 1` is processed.
+    fn probe_next_links(&self, msgid: &MsgId, seq_no: u32) -> Vec<Cursor<Address>> {
+        self.user
+            .key_store
+            .iter()
+            .into_iter()
+            .map(|(id, _cursor)| {
+                let link = self.user.link_gen.link_from(id, Cursor::new_at(msgid, 0, seq_no));
+                Cursor::new_at(link, 0, seq_no)
+            })
+            .collect()
+    }
+
+    /// Announcement link standalone packets are attached to, they are only supported in
+    /// single-branch and single-depth channels.
+    fn standalone_link(&self) -> Result<Address> {
+        let appinst = match &self.user.appinst {
+            Some(appinst) => appinst.clone(),
+            None => return err!(UserNotRegistered),
+        };
+        try_or!(!self.user.is_multi_branching(), StandalonePacketsUnsupported)?;
+        Ok(appinst)
+    }
+
+    /// Address of the standalone packet published by `id` with sequence number `seq_no`.
+    fn standalone_packet_link(&self, appinst: &Address, id: &Identifier, seq_no: u32) -> Address {
+        self.user.link_gen.link_from(id, Cursor::new_at(&appinst.msgid, 0, seq_no))
+    }
+
+    /// Publishers other than the user whose standalone packets can be probed, along with their
+    /// current sequence numbers.
+    fn standalone_publishers(&self) -> Vec<(Identifier, u32)> {
+        let own_id = Identifier::EdPubKey(self.user.sig_kp.public.into());
+        self.user
+            .key_store
+            .iter()
+            .into_iter()
+            .filter(|(id, _cursor)| matches!(id, Identifier::EdPubKey(_)) && **id != own_id)
+            .map(|(id, cursor)| (*id, cursor.seq_no))
+            .collect()
+    }
+
+    /// Buffer an orphaned message applying the orphan limit.
+    fn buffer_orphan(&self, orphans: &mut Vec<Message>, msg: Message) -> Result<()> {
+        if orphans.len() < self.max_orphans {
+            orphans.push(msg);
+            return Ok(());
+        }
+        match self.orphan_policy {
+            OrphanPolicy::Error => err!(TooManyOrphans(self.max_orphans)),
+            OrphanPolicy::DropOldest => {
+                debug_event!(link = %msg.binary.link.to_string(), "orphan limit reached, dropping oldest orphan");
+                if !orphans.is_empty() {
+                    orphans.remove(0);
+                    orphans.push(msg);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Limit the number of recipients of sent keyloads, more recipients fail with
+    /// `TooManySubscribers` before wrapping [Author]
+    pub fn set_max_keyload_subscribers(&mut self, max_keyload_subscribers: usize) {
+        self.user.set_max_keyload_subscribers(max_keyload_subscribers)
+    }
+
+    /// Reject signed packets from publishers not known from processed keyloads and subscriptions
+    /// [Author, Subscriber]
+    pub fn set_enforce_known_publishers(&mut self, enforce_known_publishers: bool) {
+        self.user.set_enforce_known_publishers(enforce_known_publishers)
+    }
+
+    /// Reject signed and tagged packets of single-branch channels not following the last processed
+    /// message of their publisher with `SequenceGap`, leaving the user state unchanged. Lenient by
+    /// default [Author, Subscriber]
+    pub fn set_strict_sequencing(&mut self, strict_sequencing: bool) {
+        self.user.set_strict_sequencing(strict_sequencing)
+    }
+
+    /// Unmask the payloads of signed packets of the given topics only, packets of other topics are
+    /// still processed with an empty masked payload. All topics are followed by default [Author,
+    /// Subscriber]
+    pub fn set_followed_topics(&mut self, followed_topics: Option<Vec<String>>) {
+        self.user.set_followed_topics(followed_topics)
+    }
+
+    /// Wrap sent messages into a buffer of the size of the last message with the same content type,
+    /// skipping the sizeof pass. Useful for messages of fixed size, disabled by default [Author, Subscriber]
+    pub fn set_wrap_size_hints(&mut self, wrap_size_hints: bool) {
+        self.user.set_wrap_size_hints(wrap_size_hints)
+    }
+
+    /// Application defined label of the user, kept in backups [Author, Subscriber]
+    pub fn label(&self) -> Option<&str> {
+        self.user.label()
+    }
+
+    /// Set or clear the label of the user, it is not shared with other participants [Author, Subscriber]
+    pub fn set_label(&mut self, label: Option<String>) {
+        self.user.set_label(label)
+    }
+
+    /// Accept announcements only from the given channel authors, `None` trusts any author
+    /// [Subscriber]
+    pub fn set_trusted_authors(&mut self, trusted_authors: Option<Vec<PublicKey>>) {
+        self.user.set_trusted_authors(trusted_authors)
+    }
+
+    /// Set the context shared out-of-band the channel announcement is bound to, `None` if it is
+    /// not bound to a context [Author, Subscriber]
+    pub fn set_announcement_context(&mut self, context: Option<&[u8]>) {
+        self.user.set_announcement_context(context)
+    }
+
+    /// Compress public payloads of sent packets and decompress the received ones, `None` disables
+    /// compression [Author, Subscriber]
+    pub fn set_compressor<C: Compressor + 'static>(&mut self, compressor: Option<C>) {
+        self.user.set_compressor(compressor)
+    }
+
+    /// Encode payloads of sent packets with the codec and decode the received ones, `None`
+    /// disables the codec [Author, Subscriber]
+    pub fn set_payload_codec<C: PayloadCodec + 'static>(&mut self, payload_codec: Option<C>) {
+        self.user.set_payload_codec(payload_codec)
+    }
+
+    /// Check recipients of received keyloads against the policy before they are processed, `None`
+    /// accepts any keyload [Author, Subscriber]
+    pub fn set_keyload_policy<P: KeyloadPolicy + 'static>(&mut self, keyload_policy: Option<P>) {
+        self.user.set_keyload_policy(keyload_policy)
+    }
+
+    /// Sign sent signed packets with `signer`, eg. backed by an HSM or a remote KMS, instead of the
+    /// user key pair, `None` restores the key pair [Author, Subscriber]
+    pub fn set_signer<S: Signer + 'static>(&mut self, signer: Option<S>) {
+        self.user.set_signer(signer)
+    }
+
+    /// Derive randomness of sent messages from the seed, message links and contents, `None` restores random
+    /// generation. Weakens unlinkability, not for privacy-sensitive channels [Author, Subscriber]
+    pub fn set_deterministic_seed(&mut self, seed: Option<&str>) {
+        self.user.set_deterministic_seed(seed)
+    }
+
+    /// Split masked payloads of sent packets into blocks authenticated separately, `None` disables
+    /// splitting [Author, Subscriber]
+    pub fn set_masked_block_size(&mut self, block_size: Option<usize>) -> Result<()> {
+        self.user.set_masked_block_size(block_size)
+    }
+
+    /// Authenticate sent tagged packets with shorter MACs, the length is carried in the messages
+    /// [Author, Subscriber]
+    pub fn set_tag_mac_length(&mut self, mac_length: usize) -> Result<()> {
+        self.user.set_tag_mac_length(mac_length)
+    }
+
+    /// Register subscribers with their key exchange public keys without processing subscribe
+    /// messages [Author]
+    ///
+    ///   # Arguments
+    ///   * `subscribers` - Identifiers of subscribers together with their x25519 public keys
+    pub fn add_subscribers<I>(&mut self, subscribers: I) -> Result<()>
+    where
+        I: IntoIterator<Item = (Identifier, x25519::PublicKey)>,
+    {
+        self.user.add_subscribers(subscribers)
+    }
+
+    /// Add a known subscriber or pre-shared key identifier to the named group of keyload
+    /// recipients, the group is created if needed [Author]
+    pub fn add_to_group(&mut self, group: &str, id: Identifier) -> Result<()> {
+        self.user.add_to_group(group, id)
+    }
+
+    /// Remove a recipient from the named group, empty groups are deleted. Returns true if the
+    /// recipient was a member of the group [Author]
+    pub fn remove_from_group(&mut self, group: &str, id: &Identifier) -> bool {
+        self.user.remove_from_group(group, id)
+    }
+
+    /// Members of the named group of keyload recipients, `None` if the group is not defined [Author]
+    pub fn group_members(&self, group: &str) -> Option<&[Identifier]> {
+        self.user.group_members(group)
+    }
+
+    /// Names of the defined groups of keyload recipients [Author]
+    pub fn subscriber_groups(&self) -> Vec<&str> {
+        self.user.subscriber_groups()
+    }
+
+    /// Verify signature of a signed packet from a known publisher without processing it and get
+    /// the publisher identifier [Author, Subscriber]
+    ///
+    ///   # Arguments
+    ///   * `msg` - Signed packet message, the message it is linked to must have been processed
+    pub fn verify_message(&self, msg: &Message) -> Result<Identifier> {
+        self.user.verify_signed_packet(&msg.binary)
+    }
+
+    /// Size in bytes of a signed packet with the given payloads, nothing is wrapped or sent [Author, Subscriber].
+    /// Sequence message sent in multi-branch channels is not included.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet would be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    pub fn estimate_signed_packet_size(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+    ) -> Result<usize> {
+        self.user
+            .estimate_signed_packet_size(link_to, public_payload, masked_payload)
+    }
+
+    /// Size in bytes of a tagged packet with the given payloads, nothing is wrapped or sent [Author, Subscriber].
+    /// Sequence message sent in multi-branch channels is not included.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet would be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    pub fn estimate_tagged_packet_size(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+    ) -> Result<usize> {
+        self.user
+            .estimate_tagged_packet_size(link_to, public_payload, masked_payload)
+    }
+
+    /// Size in bytes of a keyload for a list of recipients, nothing is wrapped or sent [Author].
+    /// Sequence message sent in multi-branch channels is not included.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload would be attached to
+    ///  * `psk_ids` - Vector of Pre-shared key ids to be included in message
+    ///  * `ke_pks` - Vector of Public Keys to be included in message
+    pub fn estimate_keyload_size(
+        &mut self,
+        link_to: &Address,
+        psk_ids: &PskIds,
+        ke_pks: &Vec<&Identifier>,
+    ) -> Result<usize> {
+        self.user.estimate_keyload_size(link_to, psk_ids, ke_pks)
+    }
+
+    /// Size in bytes of a keyload for all known subscribers, nothing is wrapped or sent [Author].
+    /// Sequence message sent in multi-branch channels is not included.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload would be attached to
+    pub fn estimate_keyload_for_everyone_size(&mut self, link_to: &Address) -> Result<usize> {
+        self.user.estimate_keyload_for_everyone_size(link_to)
+    }
+
+    pub fn import_with_mac_len(bytes: &[u8], flag: u8, pwd: &str, mac_len: usize, tsp: Trans) -> Result<Self> {
+        UserImp::import_with_mac_len(bytes, flag, pwd, mac_len).map(|u| Self {
+            user: u,
+            transport: tsp,
+            max_orphans: DEFAULT_MAX_ORPHANS,
+            orphan_policy: OrphanPolicy::default(),
+            discovered: None,
+            sync_progress: None,
+            sync_max_misses: DEFAULT_SYNC_MAX_MISSES,
+            staged: None,
+            pending: Vec::new(),
+        })
+    }
+
+    /// Re-encrypt exported user state with a new password, no transport is needed. The backup MAC
+    /// is checked with the old password first.
+    pub fn rekey_backup(bytes: &[u8], flag: u8, old_pwd: &str, new_pwd: &str) -> Result<Vec<u8>> {
+        UserImp::rekey_backup(bytes, flag, old_pwd, new_pwd)
+    }
+
+    /// Re-encrypt user state exported with the given MAC length, the MAC length is kept.
+    pub fn rekey_backup_with_mac_len(
+        bytes: &[u8],
+        flag: u8,
+        old_pwd: &str,
+        new_pwd: &str,
+        mac_len: usize,
+    ) -> Result<Vec<u8>> {
+        UserImp::rekey_backup_with_mac_len(bytes, flag, old_pwd, new_pwd, mac_len)
+    }
+
+    /// Check exported user state can be restored with the password, no transport is needed. A
+    /// wrong password or a truncated or corrupt backup fails.
+    pub fn verify_backup(bytes: &[u8], flag: u8, pwd: &str) -> Result<BackupInfo> {
+        UserImp::verify_backup(bytes, flag, pwd)
+    }
+
+    /// Check user state exported with the given MAC length, the length stored in the backup has to
+    /// match.
+    pub fn verify_backup_with_mac_len(bytes: &[u8], flag: u8, pwd: &str, mac_len: usize) -> Result<BackupInfo> {
+        UserImp::verify_backup_with_mac_len(bytes, flag, pwd, mac_len)
+    }
+
+    pub fn store_psk(&mut self, pskid: PskId, psk: Psk, use_psk: bool) -> Result<()> {
+        self.user.store_psk(pskid, psk, use_psk)
+    }
+
+    /// Identifiers of the stored pre-shared keys in ascending order [Author, Subscriber]
+    pub fn psk_ids(&self) -> Vec<PskId> {
+        self.user.psk_ids()
+    }
+
+    /// Check the message at `link` retrieved backward along a provenance chain and get the link
+    /// of the message it is linked to, `None` once the announcement of the channel is reached.
+    fn provenance_step(
+        &self,
+        visited: &mut HashSet<Address>,
+        link: &Address,
+        msg: &Message,
+    ) -> Result<Option<Address>> {
+        let announcement = match &self.user.appinst {
+            Some(announcement) => announcement,
+            None => return err!(UserNotRegistered),
+        };
+        try_or!(visited.insert(link.clone()), ProvenanceCycle(link.to_string()))?;
+        let preparsed = msg.binary.parse_header()?;
+        if preparsed.header.content_type == message::ANNOUNCE {
+            try_or!(
+                link == announcement,
+                ProvenanceRootMismatch(announcement.to_string(), link.to_string())
+            )?;
+            Ok(None)
+        } else {
+            Ok(Some(preparsed.linked_msg_address()))
+        }
+    }
+
+    /// Whether the state of the message at `link` is stored, ie. the message has been processed.
+    fn is_processed(&self, link: &Address) -> bool {
+        self.user.link_store.borrow().lookup(&link.msgid).is_ok()
+    }
+
+    /// Link of the message referenced by a sequence message, the sequence message is not committed.
+    fn sequenced_link(&self, msg: &BinaryMessage) -> Result<Address> {
+        let preparsed = self.user.parse_message(msg)?;
+        let content = self.user.unwrap_sequence(preparsed)?.pcf.content;
+        Ok(self.user.link_gen.link_from(
+            &content.id,
+            Cursor::new_at(&content.ref_link, 0, content.seq_num.0 as u32),
+        ))
+    }
+
+    /// Consume a binary sequence message and return the derived message link
+    fn process_sequence(&mut self, msg: BinaryMessage, store: bool) -> Result<Address> {
+        let unwrapped = self.user.handle_sequence(msg, MsgInfo::Sequence, store)?;
+        let msg_link = self.user.link_gen.link_from(
+            &unwrapped.body.id,
+            Cursor::new_at(&unwrapped.body.ref_link, 0, unwrapped.body.seq_num.0 as u32),
+        );
+        Ok(msg_link)
+    }
+
+    /// Split keyload recipients into groups of at most `max_per_message`, a single empty group if
+    /// there are no recipients.
+    fn split_recipients<'a>(
+        ke_pks: &'a [&'a Identifier],
+        max_per_message: usize,
+    ) -> Result<Vec<&'a [&'a Identifier]>> {
+        try_or!(0 < max_per_message, TooManySubscribers(ke_pks.len(), max_per_message))?;
+        if ke_pks.is_empty() {
+            Ok(vec![ke_pks])
+        } else {
+            Ok(ke_pks.chunks(max_per_message).collect())
+        }
+    }
+}
+
+impl<Trans> fmt::Debug for User<Trans> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("User")
+            .field("user", &self.user)
+            .field("channels", &self.user.stashed_channels())
+            .finish()
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl<Trans: Transport + Clone> User<Trans> {
+    // Send
+
+    /// Send a message with sequencing logic. If channel is single-branched, then no secondary
+    /// sequence message is sent and None is returned for the address.
+    ///
+    /// # Arguments
+    /// * `wrapped` - A wrapped sequence object containing the sequence message and state
+    fn send_sequence(&mut self, wrapped: WrappedSequence) -> Result<Option<Address>> {
+        if let Some(seq_msg) = wrapped.0 {
+            self.publish(Message::new(seq_msg))?;
+        }
+
+        if let Some(wrap_state) = wrapped.1 {
+            self.user.commit_sequence(wrap_state, MsgInfo::Sequence)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Send a message without using sequencing logic. Reserved for Announce and Subscribe messages
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self, msg)))]
+    fn send_message(&mut self, msg: WrappedMessage, info: MsgInfo) -> Result<Address> {
+        self.publish(Message::new(msg.message))?;
+        let msg_link = self.commit_wrapped(msg.wrapped, info)?;
+        debug_event!(link = %msg_link.to_string(), "message sent");
+        Ok(msg_link)
+    }
+
+    /// Send a message using sequencing logic.
+    ///
+    /// # Arguments
+    /// * `msg` - Wrapped Message ready for sending
+    /// * `ref_link` - Reference link to be included in sequence message
+    /// * `info` - Enum denominating the type of message being sent and committed
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self, msg, ref_link)))]
+    fn send_message_sequenced(
+        &mut self,
+        msg: WrappedMessage,
+        ref_link: &MsgId,
+        info: MsgInfo,
+    ) -> Result<(Address, Option<Address>)> {
+        let seq = self.user.wrap_sequence(ref_link)?;
+        self.publish(Message::new(msg.message))?;
+        let seq_link = self.send_sequence(seq)?;
+        let msg_link = self.commit_wrapped(msg.wrapped, info)?;
+        debug_event!(link = %msg_link.to_string(), sequenced = seq_link.is_some(), "message sent");
+        Ok((msg_link, seq_link))
+    }
+
+    /// Send a message using sequencing logic, awaiting confirmation of the message and of its
+    /// sequence message before the user state is committed.
+    fn send_message_sequenced_confirmed(
+        &mut self,
+        msg: WrappedMessage,
+        ref_link: &MsgId,
+        info: MsgInfo,
+    ) -> Result<(Address, Option<Address>)> {
+        try_or!(self.staged.is_none(), ConfirmationInTransaction)?;
+        let WrappedSequence(seq_msg, seq_state) = self.user.wrap_sequence(ref_link)?;
+        let link = msg.message.link.clone();
+        self.transport.send_message(&Message::new(msg.message))?;
+        self.transport.await_confirmation(&link)?;
+        if let Some(seq_msg) = seq_msg {
+            let seq_link = seq_msg.link.clone();
+            self.transport.send_message(&Message::new(seq_msg))?;
+            self.transport.await_confirmation(&seq_link)?;
+        }
+
+        let seq_link = match seq_state {
+            Some(seq_state) => self.user.commit_sequence(seq_state, MsgInfo::Sequence)?,
+            None => None,
+        };
+        let msg_link = self.commit_wrapped(msg.wrapped, info)?;
+        debug_event!(link = %msg_link.to_string(), sequenced = seq_link.is_some(), "message confirmed");
+        Ok((msg_link, seq_link))
+    }
+
+    /// Run a group of operations as a transaction [Author, Subscriber].
+    ///
+    /// Messages sent by `f` are buffered and only published once `f` succeeds. Within a transaction
+    /// the state changes of successful sends are visible to the subsequent ones (eg. cursors and
+    /// linked message states), but if `f` fails, the buffered messages are dropped and the whole user
+    /// state is restored to that before the call, nothing is published. Buffered messages can't be
+    /// received nor confirmed before the transaction succeeds.
+    ///
+    /// If publishing the buffered messages fails, the user state is kept and the messages which
+    /// could not be published are retried with `publish_pending`.
+    ///
+    /// # Arguments
+    /// * `f` - Operations to run, the first error aborts the transaction
+    pub fn transaction<R>(&mut self, f: impl FnOnce(&mut Self) -> Result<R>) -> Result<R> {
+        let scope = self.begin_transaction();
+        let result = f(self);
+        self.end_transaction(scope, result)
+    }
+
+    /// Start buffering sent messages, the transaction is ended with `end_transaction`.
+    pub(crate) fn begin_transaction(&mut self) -> TransactionScope {
+        let nested = self.staged.is_some();
+        let staged = self.staged.get_or_insert_with(Vec::new).len();
+        TransactionScope {
+            checkpoint: self.checkpoint(),
+            staged,
+            nested,
+        }
+    }
+
+    /// Publish the messages buffered since `begin_transaction` if `result` is a success, otherwise
+    /// drop them and restore the user state. Nested transactions leave publishing to the outermost.
+    pub(crate) fn end_transaction<R>(&mut self, scope: TransactionScope, result: Result<R>) -> Result<R> {
+        match result {
+            Ok(r) => {
+                if !scope.nested {
+                    let staged = self.staged.take().unwrap_or_default();
+                    self.pending.extend(staged);
+                    self.publish_pending()?;
+                }
+                Ok(r)
+            }
+            Err(e) => {
+                match &mut self.staged {
+                    Some(staged) if scope.nested => staged.truncate(scope.staged),
+                    _ => self.staged = None,
+                }
+                self.restore(scope.checkpoint);
+                Err(e)
+            }
+        }
+    }
+
+    /// Send the message, or buffer it until the current transaction succeeds.
+    fn publish(&mut self, msg: Message) -> Result<()> {
+        match &mut self.staged {
+            Some(staged) => {
+                staged.push(msg);
+                Ok(())
+            }
+            None => self.transport.send_message(&msg),
+        }
+    }
+
+    /// Publish the messages of a succeeded transaction which could not be published, in the order
+    /// they were sent. The user state already includes them [Author, Subscriber].
+    pub fn publish_pending(&mut self) -> Result<()> {
+        while !self.pending.is_empty() {
+            self.transport.send_message(&self.pending[0])?;
+            self.pending.remove(0);
+        }
+        Ok(())
+    }
+
+    /// Send an announcement message, generating a channel [Author].
+    pub fn send_announce(&mut self) -> Result<Address> {
+        let msg = self.user.announce()?;
+        try_or!(
+            self.transport.recv_message(&msg.message.link).is_err(),
+            ChannelDuplication
+        )?;
+        self.send_message(msg, MsgInfo::Announce)
+    }
+
+    /// Send an announcement message followed by an initial keyload linked to it [Author].
+    ///
+    /// If the keyload can not be sent, the announcement is removed from the link store, own
+    /// sequencing state is restored and the keyload recipients are forgotten, so the user is not
+    /// left half-initialized. Note that the announcement itself may already be published in the
+    /// transport.
+    ///
+    ///  # Arguments
+    ///  * `psk_ids` - Vector of Pre-shared key ids to be included in the keyload
+    ///  * `ke_pks`  - Vector of Public Keys to be included in the keyload
+    pub fn send_announce_with_keyload(
+        &mut self,
+        psk_ids: &PskIds,
+        ke_pks: &Vec<&Identifier>,
+    ) -> Result<(Address, Address, Option<Address>)> {
+        let cursor = self.user.get_own_cursor();
+        let recorded_keyloads = self.user.keyload_recipients.len();
+        let announcement = self.send_announce()?;
+        match self.send_keyload(&announcement, psk_ids, ke_pks) {
+            Ok((keyload, seq)) => Ok((announcement, keyload, seq)),
+            Err(e) => {
+                self.user.keyload_recipients.truncate(recorded_keyloads);
+                self.user.rollback_link(announcement.rel(), cursor)?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Create and send a signed packet [Author, Subscriber].
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    pub fn send_signed_packet(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+    ) -> Result<(Address, Option<Address>)> {
+        let msg = self.user.sign_packet(link_to, public_payload, masked_payload)?;
+        self.send_message_sequenced(msg, link_to.rel(), MsgInfo::SignedPacket)
+    }
+
+    /// Create and send a signed packet with the given options [Author, Subscriber], see
+    /// `PacketOptions`. The detached signature is returned if requested by the options.
+    ///
+    /// Standalone packets must be attached to the announcement, they are retrieved with
+    /// `fetch_standalone_packets`.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    ///  * `options` - Options of the packet
+    pub fn send_signed_packet_with(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        options: &PacketOptions,
+    ) -> Result<((Address, Option<Address>), Option<DetachedSignature>)> {
+        if options.standalone {
+            let appinst = self.standalone_link()?;
+            try_or!(*link_to == appinst, StandaloneLinkMismatch(link_to.to_string()))?;
+        }
+        let (msg, detached) = self
+            .user
+            .sign_packet_with(link_to, public_payload, masked_payload, options)?;
+        let links = if options.confirmed {
+            self.send_message_sequenced_confirmed(msg, link_to.rel(), MsgInfo::SignedPacket)?
+        } else {
+            self.send_message_sequenced(msg, link_to.rel(), MsgInfo::SignedPacket)?
+        };
+        Ok((links, detached))
+    }
+
+    /// Retrieves and processes the standalone packets of other publishers, see
+    /// `PacketOptions::with_standalone` [Author, Subscriber]. The addresses following the current
+    /// sequence number of each publisher are probed, up to `max_gap` missing packets are skipped
+    /// before probing of the publisher stops. Packets numbered below the current sequence number,
+    /// eg. published concurrently with already processed messages, are not probed.
+    ///
+    ///  # Arguments
+    ///  * `max_gap` - Number of consecutive missing packets skipped
+    pub fn fetch_standalone_packets(&mut self, max_gap: usize) -> Result<Vec<UnwrappedMessage>> {
+        let appinst = self.standalone_link()?;
+        let mut msgs = Vec::new();
+        for (id, mut seq_no) in self.standalone_publishers() {
+            let mut gap = 0;
+            while gap <= max_gap {
+                let link = self.standalone_packet_link(&appinst, &id, seq_no + gap as u32);
+                let processed = match self.transport.recv_message(&link) {
+                    Ok(msg) => self.handle_message(msg, true).ok(),
+                    Err(_) => None,
+                };
+                match processed {
+                    Some(msg) => {
+                        msgs.push(msg);
+                        seq_no += gap as u32 + 1;
+                        gap = 0;
+                    }
+                    None => gap += 1,
+                }
+            }
+        }
+        Ok(msgs)
+    }
+
+    /// Create and send a chain of signed packets, each one linked to the previous one [Author, Subscriber].
+    ///
+    /// All the packets are wrapped before any of them is sent, the linked message states are
+    /// chained in memory and the packets are sent as a `transaction`: if any packet can not be
+    /// wrapped, none is published and the user state is restored to that before the call.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the first packet will be attached to
+    ///  * `payloads` - Public and masked payloads of the packets
+    pub fn send_signed_packets<I>(&mut self, link_to: &Address, payloads: I) -> Result<Vec<(Address, Option<Address>)>>
+    where
+        I: IntoIterator<Item = (Bytes, Bytes)>,
+    {
+        self.transaction(|user| {
+            let (msgs, links) = user.wrap_signed_packet_chain(link_to, payloads)?;
+            for msg in msgs {
+                user.publish(msg)?;
+            }
+            debug_event!(count = links.len(), "signed packets sent");
+            Ok(links)
+        })
+    }
+
+    /// Create and send a close message marking the end of the channel [Author].
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the close message will be attached to
+    pub fn send_close(&mut self, link_to: &Address) -> Result<(Address, Option<Address>)> {
+        let msg = self.user.close(link_to)?;
+        let links = self.send_message_sequenced(msg, link_to.rel(), MsgInfo::Close)?;
+        self.user.closed = true;
+        Ok(links)
+    }
+
+    /// Create and send a message sealing the channel membership [Author]. Subscriptions of new
+    /// subscribers are rejected afterwards, messages are still published to the existing ones.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the seal membership message will be attached to
+    pub fn send_seal_membership(&mut self, link_to: &Address) -> Result<(Address, Option<Address>)> {
+        let msg = self.user.seal_membership(link_to)?;
+        let links = self.send_message_sequenced(msg, link_to.rel(), MsgInfo::SealMembership)?;
+        self.user.membership_sealed = true;
+        Ok(links)
+    }
+
+    /// Create and send a checkpoint summarizing a single-branch channel up to the `link_to` message
+    /// [Author]. New subscribers can start reading the channel from the checkpoint with
+    /// `bootstrap_from_checkpoint`, the following messages should be linked to the checkpoint.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the last message summarized by the checkpoint
+    pub fn publish_checkpoint(&mut self, link_to: &Address) -> Result<(Address, Option<Address>)> {
+        let msg = self.user.checkpoint(link_to)?;
+        self.send_message_sequenced(msg, link_to.rel(), MsgInfo::Checkpoint)
+    }
+
+    /// Create and send a message adding an author to the channel [Author]. Keyloads and admin
+    /// messages signed by the added author are accepted by users processing the message.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the add author message will be attached to
+    ///  * `author_pk` - Signature public key of the added author
+    pub fn send_add_author(&mut self, link_to: &Address, author_pk: &PublicKey) -> Result<(Address, Option<Address>)> {
+        let msg = self.user.add_author(link_to, author_pk)?;
+        let links = self.send_message_sequenced(msg, link_to.rel(), MsgInfo::AddAuthor)?;
+        self.user.trust_author(*author_pk)?;
+        Ok(links)
+    }
+
+    /// Create and send an ack message acknowledging that the channel was read up to the message
+    /// [Author, Subscriber]. The ack is attached to the acknowledged message, its sequence number
+    /// is sent as the acknowledged position.
+    ///
+    ///  # Arguments
+    ///  * `up_to` - Address of the last message read
+    pub fn send_ack(&mut self, up_to: &Address) -> Result<(Address, Option<Address>)> {
+        let acked = self.transport.recv_message(up_to)?;
+        let position = acked.binary.parse_header()?.header.seq_num.0 as usize;
+        let msg = self.user.ack(up_to, position)?;
+        self.send_message_sequenced(msg, up_to.rel(), MsgInfo::Ack)
+    }
+
+    /// Create and send a tagged packet [Author, Subscriber].
     ///
     ///  # Arguments
     ///  * `link_to` - Address of the message the keyload will be attached to
@@ -271,20 +1591,145 @@ impl<Trans: Transport + Clone> User<Trans> {
         self.send_message_sequenced(msg, link_to.rel(), MsgInfo::TaggedPacket)
     }
 
+    /// Create and send a tagged packet bound to an application context [Author, Subscriber].
+    /// The context is sent in plain and is covered by the MAC.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    ///  * `context` - Application context (eg. external request id)
+    pub fn send_tagged_packet_with_context(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        context: &Bytes,
+    ) -> Result<(Address, Option<Address>)> {
+        let msg = self
+            .user
+            .tag_packet_with_context(link_to, public_payload, masked_payload, context)?;
+        self.send_message_sequenced(msg, link_to.rel(), MsgInfo::TaggedPacket)
+    }
+
+    /// Create and send a tagged packet tagged with an application type [Author, Subscriber].
+    /// The type is sent in plain and is covered by the MAC.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    ///  * `app_type` - Application defined content type, 0 is the default type
+    pub fn send_tagged_packet_with_app_type(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        app_type: u16,
+    ) -> Result<(Address, Option<Address>)> {
+        let msg = self
+            .user
+            .tag_packet_with_app_type(link_to, public_payload, masked_payload, app_type)?;
+        self.send_message_sequenced(msg, link_to.rel(), MsgInfo::TaggedPacket)
+    }
+
     /// Create and send a new keyload for a list of subscribers [Author].
     ///
     ///  # Arguments
     ///  * `link_to` - Address of the message the keyload will be attached to
     ///  * `psk_ids` - Vector of Pre-shared key ids to be included in message
-    ///  * `ke_pks`  - Vector of Public Keys to be included in message
+    ///  * `ke_pks`  - Vector of Public Keys to be included in message, viewing keys of readers are
+    ///    included as `Identifier::XPubKey`
     pub fn send_keyload(
         &mut self,
         link_to: &Address,
         psk_ids: &PskIds,
         ke_pks: &Vec<&Identifier>,
     ) -> Result<(Address, Option<Address>)> {
+        let fingerprint = self.user.keyload_fingerprint(ke_pks);
         let msg = self.user.share_keyload(link_to, psk_ids, ke_pks)?;
-        self.send_message_sequenced(msg, link_to.rel(), MsgInfo::Keyload)
+        let links = self.send_message_sequenced(msg, link_to.rel(), MsgInfo::Keyload)?;
+        self.user.set_last_keyload(fingerprint);
+        Ok(links)
+    }
+
+    /// Create and send a new keyload shared with holders of the viewing keys only, granting them
+    /// read access without making them publishers [Author].
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `viewing_keys` - Viewing keys of the readers, see `viewing_key`
+    pub fn send_keyload_for_viewers(
+        &mut self,
+        link_to: &Address,
+        viewing_keys: &[x25519::PublicKey],
+    ) -> Result<(Address, Option<Address>)> {
+        let viewers: Vec<Identifier> = viewing_keys.iter().map(|pk| Identifier::from(*pk)).collect();
+        self.send_keyload(link_to, &Vec::new(), &viewers.iter().collect())
+    }
+
+    /// Create and send a new keyload for a list of subscribers unless the last keyload sent to
+    /// the channel had the same recipients [Author]. Returns `None` if the keyload was skipped.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `psk_ids` - Vector of Pre-shared key ids to be included in message
+    ///  * `ke_pks`  - Vector of Public Keys to be included in message
+    pub fn send_keyload_if_changed(
+        &mut self,
+        link_to: &Address,
+        psk_ids: &PskIds,
+        ke_pks: &Vec<&Identifier>,
+    ) -> Result<Option<(Address, Option<Address>)>> {
+        if self.user.is_last_keyload(&self.user.keyload_fingerprint(ke_pks)) {
+            return Ok(None);
+        }
+        self.send_keyload(link_to, psk_ids, ke_pks).map(Some)
+    }
+
+    /// Create and send keyloads for a list of subscribers split into keyloads of at most
+    /// `max_per_message` recipients, all attached to `link_to` [Author]. Each keyload shares its own
+    /// session key and can be opened independently, every subscriber gains access with the keyload
+    /// it is included in. Pre-shared keys are included in the first keyload. If a keyload can not
+    /// be sent, the user state is restored.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyloads will be attached to
+    ///  * `psk_ids` - Vector of Pre-shared key ids to be included in the first keyload
+    ///  * `ke_pks`  - Vector of Public Keys to be split across the keyloads
+    ///  * `max_per_message` - Maximum number of public keys per keyload
+    pub fn send_keyload_split(
+        &mut self,
+        link_to: &Address,
+        psk_ids: &PskIds,
+        ke_pks: &Vec<&Identifier>,
+        max_per_message: usize,
+    ) -> Result<Vec<(Address, Option<Address>)>> {
+        let groups = Self::split_recipients(ke_pks, max_per_message)?;
+        let fingerprint = self.user.keyload_fingerprint(ke_pks);
+        let links = self.transaction(|user| {
+            let mut links = Vec::with_capacity(groups.len());
+            for (i, group) in groups.iter().enumerate() {
+                let group_psk_ids = if i == 0 { psk_ids.clone() } else { Vec::new() };
+                links.push(user.send_keyload(link_to, &group_psk_ids, &group.to_vec())?);
+            }
+            Ok(links)
+        })?;
+        self.user.set_last_keyload(fingerprint);
+        Ok(links)
+    }
+
+    /// Create and send keyload for the members of a group of subscribers [Author].
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `group` - Name of the group of recipients
+    pub fn send_keyload_for_group(&mut self, link_to: &Address, group: &str) -> Result<(Address, Option<Address>)> {
+        let members = match self.user.group_members(group) {
+            Some(members) => members.to_vec(),
+            None => return err!(UnknownSubscriberGroup(group.to_string())),
+        };
+        self.send_keyload(link_to, &Vec::new(), &members.iter().collect())
     }
 
     /// Create and send keyload for all subscribed subscribers [Author].
@@ -292,8 +1737,26 @@ impl<Trans: Transport + Clone> User<Trans> {
     ///  # Arguments
     ///  * `link_to` - Address of the message the keyload will be attached to
     pub fn send_keyload_for_everyone(&mut self, link_to: &Address) -> Result<(Address, Option<Address>)> {
+        let fingerprint = self.user.keyload_fingerprint_for_everyone();
         let msg = self.user.share_keyload_for_everyone(link_to)?;
-        self.send_message_sequenced(msg, link_to.rel(), MsgInfo::Keyload)
+        let links = self.send_message_sequenced(msg, link_to.rel(), MsgInfo::Keyload)?;
+        self.user.set_last_keyload(fingerprint);
+        Ok(links)
+    }
+
+    /// Create and send keyload for all subscribed subscribers unless the last keyload sent to
+    /// the channel had the same recipients [Author]. Returns `None` if the keyload was skipped.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    pub fn send_keyload_for_everyone_if_changed(
+        &mut self,
+        link_to: &Address,
+    ) -> Result<Option<(Address, Option<Address>)>> {
+        if self.user.is_last_keyload(&self.user.keyload_fingerprint_for_everyone()) {
+            return Ok(None);
+        }
+        self.send_keyload_for_everyone(link_to).map(Some)
     }
 
     /// Create and Send a Subscribe message to a Channel app instance [Subscriber].
@@ -305,6 +1768,17 @@ impl<Trans: Transport + Clone> User<Trans> {
         self.send_message(msg, MsgInfo::Subscribe)
     }
 
+    /// Create and Send a Subscribe message carrying an application payload for the author, eg. a
+    /// display name [Subscriber]. The payload is masked with the key shared with the author.
+    ///
+    /// # Arguments
+    /// * `link_to` - Address of the Channel Announcement message
+    /// * `payload` - Wrapped vector of Bytes readable by the author only
+    pub fn send_subscribe_with_payload(&mut self, link_to: &Address, payload: &Bytes) -> Result<Address> {
+        let msg = self.user.subscribe_with_payload(link_to, payload)?;
+        self.send_message(msg, MsgInfo::Subscribe)
+    }
+
     // Receive
 
     /// Receive and process a sequence message [Author, Subscriber].
@@ -337,6 +1811,67 @@ impl<Trans: Transport + Clone> User<Trans> {
         Ok(m.body)
     }
 
+    /// Receive and process a close message of the channel author [Author, Subscriber].
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    pub fn receive_close(&mut self, link: &Address) -> Result<()> {
+        let msg = self.transport.recv_message(link)?;
+        self.user.handle_close(msg.binary, MsgInfo::Close)?;
+        Ok(())
+    }
+
+    /// Receive and process a message sealing the channel membership, it must be signed by a
+    /// trusted author [Author, Subscriber].
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    pub fn receive_seal_membership(&mut self, link: &Address) -> Result<()> {
+        let msg = self.transport.recv_message(link)?;
+        self.user.handle_seal_membership(msg.binary, MsgInfo::SealMembership)?;
+        Ok(())
+    }
+
+    /// Receive and process a message adding an author to the channel, it must be signed by a
+    /// trusted author [Author, Subscriber]. Returns the public key of the added author.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    pub fn receive_add_author(&mut self, link: &Address) -> Result<PublicKey> {
+        let msg = self.transport.recv_message(link)?;
+        let m = self.user.handle_add_author(msg.binary, MsgInfo::AddAuthor)?;
+        Ok(m.body)
+    }
+
+    /// Receive and process an ack message [Author, Subscriber]. Returns the public key of the
+    /// publisher and the acknowledged position, see `acked_position`.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    pub fn receive_ack(&mut self, link: &Address) -> Result<(PublicKey, usize)> {
+        let msg = self.transport.recv_message(link)?;
+        let m = self.user.handle_ack(msg.binary, MsgInfo::Ack)?;
+        Ok(m.body)
+    }
+
+    /// Initialize the user from a checkpoint instead of replaying the channel from the
+    /// announcement [Subscriber]. The announcement linked by the checkpoint is processed first if
+    /// the user is not registered to a channel, the checkpoint must be signed by the channel
+    /// author. Returns the address of the last message summarized by the checkpoint.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the checkpoint message
+    pub fn bootstrap_from_checkpoint(&mut self, link: &Address) -> Result<Address> {
+        let msg = self.transport.recv_message(link)?;
+        if !self.is_registered() {
+            let header = msg.binary.parse_header()?.header;
+            let ann_link = Address::from_bytes(&header.previous_msg_link.0);
+            self.receive_announcement(&ann_link)?;
+        }
+        let m = self.user.handle_checkpoint(msg.binary, MsgInfo::Checkpoint)?;
+        Ok(m.body)
+    }
+
     /// Receive and process a tagged packet message [Author, Subscriber].
     ///
     ///  # Arguments
@@ -347,6 +1882,22 @@ impl<Trans: Transport + Clone> User<Trans> {
         Ok(m.body)
     }
 
+    /// Receive and process a message with application defined content [Author, Subscriber].
+    ///
+    /// The message spongos state is stored and the single-branch sequencing state is updated,
+    /// see `api::user::User::handle_custom` for the checks left to the caller.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///  * `content` - Content used to unwrap the message
+    pub fn handle_custom<C>(&mut self, link: &Address, content: C) -> Result<GenericMessage<Address, C>>
+    where
+        C: ContentUnwrap<DefaultF, LinkStore>,
+    {
+        let msg = self.transport.recv_message(link)?;
+        self.user.handle_custom(msg.binary, MsgInfo::Custom, content)
+    }
+
     /// Receive and process a subscribe message [Author].
     ///
     ///  # Arguments
@@ -357,33 +1908,157 @@ impl<Trans: Transport + Clone> User<Trans> {
         self.user.handle_subscribe(msg.binary, MsgInfo::Subscribe)
     }
 
+    /// Receive and process a subscribe message, get the subscriber identifier and the
+    /// subscription payload, empty if the subscriber sent none [Author].
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    pub fn receive_subscribe_with_payload(&mut self, link: &Address) -> Result<(Identifier, Bytes)> {
+        let msg = self.transport.recv_message(link)?;
+        self.user.handle_subscribe_with_payload(msg.binary, MsgInfo::Subscribe)
+    }
+
     /// Receive and Process an announcement message [Subscriber].
     ///
-    /// # Arguments
-    /// * `link_to` - Address of the Channel Announcement message
-    pub fn receive_announcement(&mut self, link: &Address) -> Result<()> {
-        let msg = self.transport.recv_message(link)?;
-        self.user.handle_announcement(msg.binary, MsgInfo::Announce)
+    /// # Arguments
+    /// * `link_to` - Address of the Channel Announcement message
+    pub fn receive_announcement(&mut self, link: &Address) -> Result<()> {
+        let msg = self.transport.recv_message(link)?;
+        self.user.handle_announcement(msg.binary, MsgInfo::Announce)
+    }
+
+    /// Fetch an announcement and read the author and channel details without binding the user to
+    /// the channel, the user may be bound to another channel. The user state is not changed
+    /// [Subscriber]
+    ///
+    /// # Arguments
+    /// * `link` - Address of the Channel Announcement message
+    pub fn peek_announcement(&mut self, link: &Address) -> Result<AnnouncementInfo> {
+        let msg = self.transport.recv_message(link)?;
+        self.user.peek_announcement(&msg.binary)
+    }
+
+    /// Join the channel with an invitation created by its author: the announcement is fetched and
+    /// checked to be signed by the invitation author. The PSK referenced by the invitation, if any,
+    /// is not carried by it and has to be stored with `store_psk` [Subscriber].
+    ///
+    /// # Arguments
+    /// * `bytes` - Invitation encoded by `create_invitation`
+    pub fn accept_invitation(&mut self, bytes: &[u8]) -> Result<Invitation> {
+        let invitation = Invitation::from_bytes(bytes)?;
+        let msg = self.transport.recv_message(invitation.announcement())?;
+        self.check_invitation_announcement(&invitation, &msg.binary)?;
+        self.user.handle_announcement(msg.binary, MsgInfo::Announce)?;
+        Ok(invitation)
+    }
+
+    /// Receive and process a keyload message [Subscriber].
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    pub fn receive_keyload(&mut self, link: &Address) -> Result<bool> {
+        let msg = self.transport.recv_message(link)?;
+        let m = self.user.handle_keyload(msg.binary, MsgInfo::Keyload)?;
+        Ok(m.body)
+    }
+
+    /// Receive and process a message of unknown type. Message will be handled appropriately and
+    /// the unwrapped contents returned [Author, Subscriber].
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message to be processed
+    pub fn receive_message(&mut self, link: &Address) -> Result<UnwrappedMessage> {
+        let msg = self.transport.recv_message(link)?;
+        if let Some(orphan) = self.orphan(&msg)? {
+            return Ok(orphan);
+        }
+        self.handle_message(msg, true)
+    }
+
+    /// Receive and process a message expected from the given publisher. The message is rejected
+    /// before it is processed, leaving the user state untouched, if its header declares another
+    /// publisher [Author, Subscriber].
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message to be processed
+    ///   * `expected` - Identifier of the expected publisher
+    pub fn receive_message_from(&mut self, link: &Address, expected: &Identifier) -> Result<UnwrappedMessage> {
+        let msg = self.transport.recv_message(link)?;
+        Self::check_publisher(&msg, expected)?;
+        if let Some(orphan) = self.orphan(&msg)? {
+            return Ok(orphan);
+        }
+        self.handle_message(msg, true)
+    }
+
+    /// Receive and process the messages of a publisher from its sequencing state up to the one with
+    /// the given sequence number [Author, Subscriber]. Message links depend on the previous message,
+    /// so the publisher's messages are received in order, the returned messages end with the
+    /// requested one. In single-branch channels the chain is only followed while the publisher is
+    /// the one sending the next message, `sync` should be used to read messages of several
+    /// publishers.
+    ///
+    /// Receiving stops with an error if there is no message at the computed address or if the
+    /// message is linked to a message which was not processed. Messages processed before the error
+    /// are kept in the user state, they can be received again with `receive_message`.
+    ///
+    ///   # Arguments
+    ///   * `publisher` - Identifier of the publisher of the message
+    ///   * `sequence` - Sequence number of the message
+    pub fn receive_message_at(&mut self, publisher: &Identifier, sequence: usize) -> Result<Vec<UnwrappedMessage>> {
+        let mut msgs = Vec::new();
+        loop {
+            let next_seq_no = self.user.next_seq_no(publisher)? as usize;
+            try_or!(sequence >= next_seq_no, SeqNumAlreadyProcessed(sequence, next_seq_no))?;
+            let link = self.user.next_link(publisher)?;
+            let msg = match self.transport.recv_message(&link) {
+                Ok(msg) => msg,
+                Err(_) => return err!(SeqNumMessageNotFound(next_seq_no, link.to_string())),
+            };
+            try_or!(
+                self.orphan(&msg)?.is_none(),
+                SeqNumMessageOrphaned(next_seq_no, link.to_string())
+            )?;
+            msgs.push(self.handle_message(msg, true)?);
+            if next_seq_no == sequence {
+                return Ok(msgs);
+            }
+        }
     }
 
-    /// Receive and process a keyload message [Subscriber].
+    /// Delete the message from the transport layer and forget its spongos state [Author, Subscriber].
     ///
-    ///  # Arguments
-    ///  * `link` - Address of the message to be processed
-    pub fn receive_keyload(&mut self, link: &Address) -> Result<bool> {
-        let msg = self.transport.recv_message(link)?;
-        let m = self.user.handle_keyload(msg.binary, MsgInfo::Keyload)?;
-        Ok(m.body)
+    /// Warning: retraction breaks the chain of messages linked after the deleted one, users who have
+    /// not processed the deleted message yet will not be able to unwrap them. Sequencing states are
+    /// not changed.
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message to be retracted
+    pub fn retract(&mut self, link: &Address) -> Result<()> {
+        self.transport.delete_message(link)?;
+        self.user.rollback_link(link.rel(), None)
     }
 
-    /// Receive and process a message of unknown type. Message will be handled appropriately and
-    /// the unwrapped contents returned [Author, Subscriber].
+    /// Forget spongos states of messages which are not reachable from `reachable_from` following
+    /// the links to previous messages [Author, Subscriber]. The states of the announcement and of
+    /// the messages referred to by sequencing states are kept. Returns the number of removed states.
     ///
     ///   # Arguments
-    ///   * `link` - Address of the message to be processed
-    pub fn receive_message(&mut self, link: &Address) -> Result<UnwrappedMessage> {
-        let msg = self.transport.recv_message(link)?;
-        self.handle_message(msg, true)
+    ///   * `reachable_from` - Address of the most recent message to be kept
+    pub fn gc_spongos(&mut self, reachable_from: &Address) -> Result<usize> {
+        try_or!(
+            self.user.contains_spongos(reachable_from.rel()),
+            MessageLinkNotFound(reachable_from.to_string())
+        )?;
+        let mut reachable = Vec::new();
+        let mut link = reachable_from.clone();
+        while self.user.contains_spongos(link.rel()) && !reachable.contains(link.rel()) {
+            reachable.push(link.rel().clone());
+            let msg = self.transport.recv_message(&link)?;
+            let header = msg.binary.parse_header()?.header;
+            link = Address::from_bytes(&header.previous_msg_link.0);
+        }
+        Ok(self.user.retain_spongos(&reachable))
     }
 
     /// Retrieves the next message for each user (if present in transport layer) and returns them [Author, Subscriber]
@@ -411,6 +2086,337 @@ impl<Trans: Transport + Clone> User<Trans> {
         msgs
     }
 
+    /// Retrieves the next message for each user like `fetch_next_msgs`, each message is returned
+    /// along with the position just after it to resume processing from [Author, Subscriber]
+    pub fn fetch_next_msgs_with_positions(&mut self) -> Vec<(UnwrappedMessage, Position)> {
+        let ids = self.user.gen_next_msg_ids(self.user.is_multi_branching());
+        let mut msgs = Vec::new();
+
+        for (_pk, cursor) in ids {
+            if let Ok(msg) = self.transport.recv_message(&cursor.link) {
+                if let Ok(msg) = self.handle_message(msg, true) {
+                    msgs.push((msg, self.position()));
+                }
+            }
+        }
+        msgs
+    }
+
+    /// Counts the messages present in the transport layer at the next expected address of each
+    /// publisher, without unwrapping them [Author, Subscriber]. Addresses which fail to be retrieved
+    /// are not counted.
+    pub fn pending_count(&mut self) -> Result<usize> {
+        try_or!(self.user.appinst.is_some(), UserNotRegistered)?;
+        let ids = self.user.gen_next_msg_ids(self.user.is_multi_branching());
+        let mut count = 0;
+        for (_pk, cursor) in ids {
+            if self.transport.recv_message(&cursor.link).is_ok() {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Fetches and processes all the new messages of all publishers and returns them newest first
+    /// [Author, Subscriber].
+    ///
+    /// Unwrapping a message requires the spongos state of the message it is linked to, so the
+    /// messages are fetched forward up to the head of each publisher and buffered before they are
+    /// returned: memory grows with the number of new messages and none of them is available
+    /// before the latest one is fetched. Messages are processed as in forward mode, masked payloads
+    /// are decrypted if the user has access to them. Use `fetch_next_msgs` to bound the memory.
+    pub fn messages_reverse(&mut self) -> Vec<UnwrappedMessage> {
+        let mut msgs = Vec::new();
+        loop {
+            let next_msgs = self.fetch_next_msgs();
+            if next_msgs.is_empty() {
+                break;
+            }
+            msgs.extend(next_msgs);
+        }
+        msgs.reverse();
+        msgs
+    }
+
+    /// Iteratively fetches next messages until the message at `stop` has been processed or no new
+    /// messages can be found [Author, Subscriber]. Messages from other publishers retrieved in the
+    /// same round as `stop` are processed and returned as well, so that no cursor state is lost.
+    ///
+    /// # Arguments
+    /// * `stop` - Address of the last message to be processed
+    pub fn fetch_next_msgs_until(&mut self, stop: &Address) -> Vec<UnwrappedMessage> {
+        let mut msgs = Vec::new();
+        loop {
+            let next_msgs = self.fetch_next_msgs();
+            if next_msgs.is_empty() {
+                break;
+            }
+            let reached = next_msgs.iter().any(|msg| &msg.link == stop);
+            msgs.extend(next_msgs);
+            if reached {
+                break;
+            }
+        }
+        msgs
+    }
+
+    /// Lists the messages of the channel stored by the transport, if it supports enumeration, so
+    /// that the next `sync` only retrieves expected addresses which are listed [Author, Subscriber].
+    /// It avoids probing every publisher's next address on initial synchronization with large
+    /// channels. Returns the number of listed messages.
+    ///
+    /// Most transports, including the Tangle client, can't enumerate messages and the call fails
+    /// with `MessageListingUnsupported`: `sync` then probes expected addresses as usual. Messages
+    /// sent after the listing are found by the following `sync`.
+    pub fn discover_messages(&mut self) -> Result<usize> {
+        let appinst = match &self.user.appinst {
+            Some(appinst) => appinst.clone(),
+            None => return err!(UserNotRegistered),
+        };
+        let links: HashSet<Address> = self
+            .transport
+            .list_links(&appinst)?
+            .into_iter()
+            .filter(|link| link.base() == appinst.base())
+            .collect();
+        let count = links.len();
+        self.discovered = Some((appinst, links));
+        Ok(count)
+    }
+
+    /// Fetches and processes next messages of all publishers until no progress is made [Author,
+    /// Subscriber]. Messages linked to a message of another publisher that is not processed yet are
+    /// kept as orphans and retried once more messages are processed, without fetching them again.
+    /// The number of buffered orphans is limited, see `set_max_orphans`. Returns the number of
+    /// processed messages and the number of messages left orphaned.
+    pub fn sync(&mut self) -> Result<(usize, usize)> {
+        let mut reporter = self.sync_reporter();
+        let (resolved, orphans) = self.sync_orphans(&mut reporter, &mut None)?;
+        if let Some(reporter) = reporter {
+            reporter.finish();
+        }
+        Ok((resolved, orphans.len()))
+    }
+
+    /// Synchronizes like `sync`, passing the user state exported with `flag` and `pwd` to
+    /// `persist` every `batch` processed messages and once synchronization completes [Author,
+    /// Subscriber]. A failure to persist stops synchronization. If synchronization is interrupted,
+    /// the progress made since the last persisted state is lost and the messages are processed
+    /// again by the next `sync` of the imported state.
+    pub fn sync_persisting<P>(&mut self, flag: u8, pwd: &str, batch: usize, mut persist: P) -> Result<(usize, usize)>
+    where
+        P: FnMut(&[u8]) -> Result<()>,
+    {
+        let mut reporter = self.sync_reporter();
+        let mut persister = Some(SyncPersister {
+            persist: &mut persist,
+            flag,
+            pwd,
+            batch: batch.max(1),
+            pending: 0,
+        });
+        let (resolved, orphans) = self.sync_orphans(&mut reporter, &mut persister)?;
+        if let Some(persister) = persister {
+            persister.finish(&self.user)?;
+        }
+        if let Some(reporter) = reporter {
+            reporter.finish();
+        }
+        Ok((resolved, orphans.len()))
+    }
+
+    /// Synchronizes like `sync`, skipping messages which can't be processed, eg. corrupt ones,
+    /// instead of stopping at them [Author, Subscriber]. Once no progress is made, the cursor of
+    /// the publisher of every message left orphaned is moved past it as if it was processed and
+    /// synchronization goes on with the following messages. Returns the number of processed
+    /// messages and the addresses of the skipped ones for the caller to investigate.
+    ///
+    /// Skipped messages are not retried. Messages linked to a skipped message can't be processed
+    /// and are skipped as well, as are messages linked to a message not retrievable yet or
+    /// protected by a keyload the user has no access to.
+    pub fn sync_skipping_errors(&mut self) -> Result<(usize, Vec<Address>)> {
+        let mut reporter = self.sync_reporter();
+        let mut resolved = 0;
+        let mut skipped = Vec::new();
+        loop {
+            let (count, orphans) = self.sync_orphans(&mut reporter, &mut None)?;
+            resolved += count;
+            let mut progress = false;
+            for msg in orphans {
+                // Orphans don't advance cursors, an orphan is still expected next from its publisher
+                let link = msg.binary.link;
+                let ids = self.user.gen_next_msg_ids(self.user.is_multi_branching());
+                if let Some((id, cursor)) = ids.into_iter().find(|(_id, cursor)| cursor.link == link) {
+                    debug_event!(link = %link.to_string(), "skipping message which can not be processed");
+                    if self.user.is_multi_branching() {
+                        self.user.store_state(id, link.msgid.clone())?;
+                    } else {
+                        self.user.store_state_for_all(link.msgid.clone(), cursor.seq_no + 1)?;
+                    }
+                    skipped.push(link);
+                    if let Some(reporter) = &mut reporter {
+                        reporter.advance();
+                    }
+                    progress = true;
+                }
+            }
+            if !progress {
+                break;
+            }
+        }
+        if let Some(reporter) = reporter {
+            reporter.finish();
+        }
+        Ok((resolved, skipped))
+    }
+
+    /// Fetches and processes next messages of all publishers until no new message appears at their
+    /// expected addresses for a number of consecutive rounds, see `set_sync_max_misses` [Author,
+    /// Subscriber]. Unlike `sync`, missing addresses are not told apart by the transport errors:
+    /// an error, no message or a message which can't be processed are all missed alike, so that
+    /// transports returning no message for unknown addresses can't make it loop. Messages which
+    /// can't be processed are fetched again in the next round rather than buffered as orphans.
+    pub fn sync_to_head(&mut self) -> Result<SyncToHead> {
+        try_or!(self.user.appinst.is_some(), UserNotRegistered)?;
+        let mut fetched = 0;
+        let mut misses = 0;
+        loop {
+            let mut progress = false;
+            let mut found = false;
+            let ids = self.user.gen_next_msg_ids(self.user.is_multi_branching());
+            for (_pk, cursor) in ids {
+                if let Ok(msg) = self.transport.recv_message(&cursor.link) {
+                    found = true;
+                    if self.handle_message(msg, true).is_ok() {
+                        fetched += 1;
+                        progress = true;
+                    }
+                }
+            }
+
+            if progress {
+                misses = 0;
+                continue;
+            }
+            misses += 1;
+            if misses >= self.sync_max_misses {
+                return Ok(SyncToHead {
+                    fetched,
+                    reached_head: !found,
+                });
+            }
+        }
+    }
+
+    /// Progress reporter of a `sync` about to start if a callback is set, with the estimated
+    /// number of messages to process.
+    fn sync_reporter(&mut self) -> Option<SyncReporter> {
+        let (callback, interval) = self.sync_progress.clone()?;
+        let total = match self.discovered_pending() {
+            Some(total) => total,
+            None => self.probe_pending(),
+        };
+        Some(SyncReporter {
+            callback,
+            interval,
+            progress: SyncProgress { processed: 0, total },
+        })
+    }
+
+    /// Counts the new messages by fetching them at expected addresses without unwrapping them. In
+    /// multi-branch channels addresses following the next message of each publisher can't be
+    /// derived without unwrapping it and only next messages are counted.
+    fn probe_pending(&mut self) -> usize {
+        let branching = self.user.is_multi_branching();
+        let mut links = self
+            .user
+            .gen_next_msg_ids(branching)
+            .into_iter()
+            .map(|(_id, cursor)| cursor)
+            .collect::<Vec<_>>();
+        let mut count = 0;
+        loop {
+            let mut found = None;
+            for cursor in &links {
+                if self.transport.recv_message(&cursor.link).is_ok() {
+                    count += 1;
+                    found = Some((cursor.link.msgid.clone(), cursor.seq_no + 1));
+                    if !branching {
+                        break;
+                    }
+                }
+            }
+            match found {
+                Some((msgid, seq_no)) if !branching => links = self.probe_next_links(&msgid, seq_no),
+                _ => break,
+            }
+        }
+        count
+    }
+
+    /// Processes next messages of all publishers until no progress is made, returning the number
+    /// of processed messages and the messages left orphaned.
+    fn sync_orphans(
+        &mut self,
+        reporter: &mut Option<SyncReporter>,
+        persister: &mut Option<SyncPersister<'_>>,
+    ) -> Result<(usize, Vec<Message>)> {
+        try_or!(self.user.appinst.is_some(), UserNotRegistered)?;
+        let mut orphans: Vec<Message> = Vec::new();
+        // Listed links are only used once, by the sync following the discovery
+        let discovered = match self.discovered.take() {
+            Some((appinst, links)) if self.user.appinst.as_ref() == Some(&appinst) => Some(links),
+            _ => None,
+        };
+        let mut resolved = 0;
+        loop {
+            let mut progress = false;
+            for msg in core::mem::take(&mut orphans) {
+                if self.handle_message(msg.clone(), true).is_ok() {
+                    resolved += 1;
+                    if let Some(reporter) = reporter {
+                        reporter.advance();
+                    }
+                    if let Some(persister) = persister {
+                        persister.advance(&self.user)?;
+                    }
+                    progress = true;
+                } else {
+                    orphans.push(msg);
+                }
+            }
+
+            let ids = self.user.gen_next_msg_ids(self.user.is_multi_branching());
+            for (_pk, cursor) in ids {
+                if orphans.iter().any(|msg| msg.binary.link == cursor.link) {
+                    continue;
+                }
+                if discovered.as_ref().map_or(false, |links| !links.contains(&cursor.link)) {
+                    continue;
+                }
+                if let Ok(msg) = self.transport.recv_message(&cursor.link) {
+                    if self.handle_message(msg.clone(), true).is_ok() {
+                        resolved += 1;
+                        if let Some(reporter) = reporter {
+                            reporter.advance();
+                        }
+                        if let Some(persister) = persister {
+                            persister.advance(&self.user)?;
+                        }
+                        progress = true;
+                    } else {
+                        self.buffer_orphan(&mut orphans, msg)?;
+                    }
+                }
+            }
+
+            if !progress {
+                break;
+            }
+        }
+        Ok((resolved, orphans))
+    }
+
     /// Retrieves the previous message from the message specified (provided the user has access to it) [Author,
     /// Subscriber]
     ///
@@ -455,35 +2461,114 @@ impl<Trans: Transport + Clone> User<Trans> {
         Ok(msgs)
     }
 
+    /// Verify that the message at `link` chains back to the announcement of the channel and get
+    /// the addresses of the chain, from the announcement to the message [Author, Subscriber].
+    ///
+    /// Linked messages are retrieved backward from the message until the announcement is reached,
+    /// then the messages not processed yet are processed forward from the announcement. Each
+    /// message is unwrapped joining the spongos state of the message it is linked to, which proves
+    /// the message is part of the chain published in the channel rather than only signed by a
+    /// participant. Messages the user can't read, eg. packets linked to a keyload the user is not a
+    /// recipient of, fail the verification.
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message to verify
+    pub fn verify_provenance(&mut self, link: &Address) -> Result<Vec<Address>> {
+        let mut visited = HashSet::new();
+        let mut chain = Vec::new();
+        let mut next = Some(link.clone());
+        while let Some(link) = next {
+            let msg = match self.transport.recv_message(&link) {
+                Ok(msg) => msg,
+                Err(_) => return err!(ProvenanceLinkMissing(link.to_string())),
+            };
+            next = self.provenance_step(&mut visited, &link, &msg)?;
+            chain.push((link, msg));
+        }
+
+        chain.reverse();
+        let mut path = Vec::with_capacity(chain.len());
+        for (link, msg) in chain {
+            if !self.is_processed(&link) {
+                if msg.binary.parse_header()?.header.content_type == message::SEQUENCE {
+                    self.process_sequence(msg.binary, false)?;
+                } else {
+                    self.handle_message(msg, false)?;
+                }
+            }
+            path.push(link);
+        }
+        Ok(path)
+    }
+
     /// Handle message of unknown type. Ingests a message and unwraps it according to its determined
     /// content type [Author, Subscriber].
     ///
     /// # Arguments
     /// * `msg` - Binary message of unknown type
     /// * `pk` - Optional ed25519 Public Key of the sending participant. None if unknown
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self, msg0)))]
     pub fn handle_message(&mut self, mut msg0: Message, store: bool) -> Result<UnwrappedMessage> {
         let mut sequenced = false;
         loop {
             // Forget TangleMessage and timestamp
             let msg = msg0.binary;
-            let preparsed = msg.parse_header()?;
+            let preparsed = self.user.parse_message(&msg)?;
             let link = preparsed.header.link.clone();
             let prev_link = TangleAddress::from_bytes(&preparsed.header.previous_msg_link.0);
+            debug_event!(
+                link = %link.to_string(),
+                linked = %prev_link.to_string(),
+                content_type = preparsed.header.content_type,
+                publisher = %hex::encode(preparsed.header.sender_id.to_bytes()),
+                "handling message"
+            );
             match preparsed.header.content_type {
-                message::SIGNED_PACKET => match self.user.handle_signed_packet(msg, MsgInfo::SignedPacket) {
+                message::ANNOUNCE => {
+                    // Registers the user to the channel, eg. a reader holding only a pre-shared key
+                    self.user.handle_announcement(msg, MsgInfo::Announce)?;
+                    return Ok(UnwrappedMessage::new(link, prev_link, MessageContent::new_announce()));
+                }
+                message::SIGNED_PACKET => match self.user.handle_signed_packet_content(msg, MsgInfo::SignedPacket) {
                     Ok(m) => {
-                        return Ok(m.map(|(id, public, masked)| MessageContent::new_signed_packet(id, public, masked)))
+                        return Ok(m.map(|content| MessageContent::SignedPacket {
+                            pk: content.sig_pk,
+                            public_payload: content.public_payload,
+                            masked_payload: content.masked_payload,
+                            context: content.context,
+                            metadata: content.metadata,
+                            app_type: content.app_type.map_or(0, |app_type| app_type.0),
+                        }))
                     }
                     Err(e) => match sequenced {
-                        true => return Ok(UnwrappedMessage::new(link, prev_link, MessageContent::unreadable())),
-                        false => return Err(e),
+                        true => {
+                            debug_event!(error = %e, "sequenced message is unreadable");
+                            return Ok(UnwrappedMessage::new(link, prev_link, MessageContent::unreadable()));
+                        }
+                        false => {
+                            debug_event!(linked = %prev_link.to_string(), error = %e, "message can not be handled");
+                            return Err(e);
+                        }
                     },
                 },
-                message::TAGGED_PACKET => match self.user.handle_tagged_packet(msg, MsgInfo::TaggedPacket) {
-                    Ok(m) => return Ok(m.map(|(public, masked)| MessageContent::new_tagged_packet(public, masked))),
+                message::TAGGED_PACKET => match self.user.handle_tagged_packet_content(msg, MsgInfo::TaggedPacket) {
+                    Ok(m) => {
+                        return Ok(m.map(|content| MessageContent::TaggedPacket {
+                            public_payload: content.public_payload,
+                            masked_payload: content.masked_payload,
+                            context: content.context,
+                            app_type: content.app_type.map_or(0, |app_type| app_type.0),
+                        }))
+                    }
                     Err(e) => match sequenced {
-                        true => return Ok(UnwrappedMessage::new(link, prev_link, MessageContent::unreadable())),
-                        false => return Err(e),
+                        true => {
+                            debug_event!(error = %e, "sequenced message is unreadable");
+                            return Ok(UnwrappedMessage::new(link, prev_link, MessageContent::unreadable()));
+                        }
+                        false => {
+                            debug_event!(linked = %prev_link.to_string(), error = %e, "message can not be handled");
+                            return Err(e);
+                        }
                     },
                 },
                 message::KEYLOAD => {
@@ -491,15 +2576,38 @@ impl<Trans: Transport + Clone> User<Trans> {
                     // inform the user that a message was present, even if the use wasn't part of
                     // the keyload itself. This is to prevent sequencing failures
                     let m = self.user.handle_keyload(msg, MsgInfo::Keyload)?;
-                    // TODO: Verify content, whether user is allowed or not!
                     let u = m.map(|_allowed| MessageContent::new_keyload());
                     return Ok(u);
                 }
+                message::CLOSE => {
+                    let m = self.user.handle_close(msg, MsgInfo::Close)?;
+                    return Ok(m.map(|_| MessageContent::Close));
+                }
+                message::ADD_AUTHOR => {
+                    let m = self.user.handle_add_author(msg, MsgInfo::AddAuthor)?;
+                    return Ok(m.map(|pk| MessageContent::AddAuthor { pk }));
+                }
+                message::SEAL_MEMBERSHIP => {
+                    let m = self.user.handle_seal_membership(msg, MsgInfo::SealMembership)?;
+                    return Ok(m.map(|_| MessageContent::SealMembership));
+                }
+                message::ACK => {
+                    let m = self.user.handle_ack(msg, MsgInfo::Ack)?;
+                    return Ok(m.map(|(pk, position)| MessageContent::Ack { pk, position }));
+                }
+                message::CHECKPOINT => {
+                    let m = self.user.handle_checkpoint(msg, MsgInfo::Checkpoint)?;
+                    return Ok(m.map(|head| MessageContent::Checkpoint { head }));
+                }
                 message::SEQUENCE => {
-                    let msg_link = self.process_sequence(msg, store)?;
-                    let msg = self.transport.recv_message(&msg_link)?;
+                    // The referenced message is retrieved before the sequence message is committed,
+                    // if it can't be retrieved the sequencing state is left as it was
+                    let msg_link = self.sequenced_link(&msg)?;
+                    debug_event!(link = %msg_link.to_string(), "following sequence message");
+                    let next = self.transport.recv_message(&msg_link)?;
+                    self.process_sequence(msg, store)?;
                     sequenced = true;
-                    msg0 = msg;
+                    msg0 = next;
                 }
                 unknown_content => return err!(UnknownMsgType(unknown_content)),
             }
@@ -519,6 +2627,27 @@ impl<Trans: Transport + Clone> User<Trans> {
 impl<Trans: Transport + Clone> User<Trans> {
     // Send
 
+    /// Send the message, or buffer it until the current transaction succeeds.
+    async fn publish(&mut self, msg: Message) -> Result<()> {
+        match &mut self.staged {
+            Some(staged) => {
+                staged.push(msg);
+                Ok(())
+            }
+            None => self.transport.send_message(&msg).await,
+        }
+    }
+
+    /// Publish the messages of a succeeded transaction which could not be published, in the order
+    /// they were sent. The user state already includes them [Author, Subscriber].
+    pub async fn publish_pending(&mut self) -> Result<()> {
+        while !self.pending.is_empty() {
+            self.transport.send_message(&self.pending[0]).await?;
+            self.pending.remove(0);
+        }
+        Ok(())
+    }
+
     /// Send a message with sequencing logic. If channel is single-branched, then no secondary
     /// sequence message is sent and None is returned for the address.
     ///
@@ -526,7 +2655,7 @@ impl<Trans: Transport + Clone> User<Trans> {
     /// * `wrapped` - A wrapped sequence object containing the sequence message and state
     async fn send_sequence(&mut self, wrapped: WrappedSequence) -> Result<Option<Address>> {
         if let Some(seq_msg) = wrapped.0 {
-            self.transport.send_message(&Message::new(seq_msg)).await?;
+            self.publish(Message::new(seq_msg)).await?;
         }
 
         if let Some(wrap_state) = wrapped.1 {
@@ -537,9 +2666,12 @@ impl<Trans: Transport + Clone> User<Trans> {
     }
 
     /// Send a message without using sequencing logic. Reserved for Announce and Subscribe messages
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self, msg)))]
     async fn send_message(&mut self, msg: WrappedMessage, info: MsgInfo) -> Result<Address> {
-        self.transport.send_message(&Message::new(msg.message)).await?;
-        self.commit_wrapped(msg.wrapped, info)
+        self.publish(Message::new(msg.message)).await?;
+        let msg_link = self.commit_wrapped(msg.wrapped, info)?;
+        debug_event!(link = %msg_link.to_string(), "message sent");
+        Ok(msg_link)
     }
 
     /// Send a message using sequencing logic.
@@ -548,6 +2680,7 @@ impl<Trans: Transport + Clone> User<Trans> {
     /// * `msg` - Wrapped Message ready for sending
     /// * `ref_link` - Reference link to be included in sequence message
     /// * `info` - Enum denominating the type of message being sent and committed
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self, msg, ref_link)))]
     async fn send_message_sequenced(
         &mut self,
         msg: WrappedMessage,
@@ -555,9 +2688,37 @@ impl<Trans: Transport + Clone> User<Trans> {
         info: MsgInfo,
     ) -> Result<(Address, Option<Address>)> {
         let seq = self.user.wrap_sequence(ref_link)?;
-        self.transport.send_message(&Message::new(msg.message)).await?;
+        self.publish(Message::new(msg.message)).await?;
         let seq_link = self.send_sequence(seq).await?;
         let msg_link = self.commit_wrapped(msg.wrapped, info)?;
+        debug_event!(link = %msg_link.to_string(), sequenced = seq_link.is_some(), "message sent");
+        Ok((msg_link, seq_link))
+    }
+
+    /// Send a message using sequencing logic, awaiting confirmation of the message and of its
+    /// sequence message before the user state is committed.
+    async fn send_message_sequenced_confirmed(
+        &mut self,
+        msg: WrappedMessage,
+        ref_link: &MsgId,
+        info: MsgInfo,
+    ) -> Result<(Address, Option<Address>)> {
+        let WrappedSequence(seq_msg, seq_state) = self.user.wrap_sequence(ref_link)?;
+        let link = msg.message.link.clone();
+        self.transport.send_message(&Message::new(msg.message)).await?;
+        self.transport.await_confirmation(&link).await?;
+        if let Some(seq_msg) = seq_msg {
+            let seq_link = seq_msg.link.clone();
+            self.transport.send_message(&Message::new(seq_msg)).await?;
+            self.transport.await_confirmation(&seq_link).await?;
+        }
+
+        let seq_link = match seq_state {
+            Some(seq_state) => self.user.commit_sequence(seq_state, MsgInfo::Sequence)?,
+            None => None,
+        };
+        let msg_link = self.commit_wrapped(msg.wrapped, info)?;
+        debug_event!(link = %msg_link.to_string(), sequenced = seq_link.is_some(), "message confirmed");
         Ok((msg_link, seq_link))
     }
 
@@ -571,6 +2732,34 @@ impl<Trans: Transport + Clone> User<Trans> {
         self.send_message(msg, MsgInfo::Announce).await
     }
 
+    /// Send an announcement message followed by an initial keyload linked to it [Author].
+    ///
+    /// If the keyload can not be sent, the announcement is removed from the link store, own
+    /// sequencing state is restored and the keyload recipients are forgotten, so the user is not
+    /// left half-initialized. Note that the announcement itself may already be published in the
+    /// transport.
+    ///
+    ///  # Arguments
+    ///  * `psk_ids` - Vector of Pre-shared key ids to be included in the keyload
+    ///  * `ke_pks`  - Vector of Public Keys to be included in the keyload
+    pub async fn send_announce_with_keyload(
+        &mut self,
+        psk_ids: &PskIds,
+        ke_pks: &Vec<&Identifier>,
+    ) -> Result<(Address, Address, Option<Address>)> {
+        let cursor = self.user.get_own_cursor();
+        let recorded_keyloads = self.user.keyload_recipients.len();
+        let announcement = self.send_announce().await?;
+        match self.send_keyload(&announcement, psk_ids, ke_pks).await {
+            Ok((keyload, seq)) => Ok((announcement, keyload, seq)),
+            Err(e) => {
+                self.user.keyload_recipients.truncate(recorded_keyloads);
+                self.user.rollback_link(announcement.rel(), cursor)?;
+                Err(e)
+            }
+        }
+    }
+
     /// Create and send a signed packet [Author, Subscriber].
     ///
     ///  # Arguments
@@ -588,19 +2777,225 @@ impl<Trans: Transport + Clone> User<Trans> {
             .await
     }
 
-    /// Create and send a tagged packet [Author, Subscriber].
+    /// Create and send a signed packet with the given options [Author, Subscriber], see
+    /// `PacketOptions`. The detached signature is returned if requested by the options.
+    ///
+    /// Standalone packets must be attached to the announcement, they are retrieved with
+    /// `fetch_standalone_packets`.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    ///  * `options` - Options of the packet
+    pub async fn send_signed_packet_with(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        options: &PacketOptions,
+    ) -> Result<((Address, Option<Address>), Option<DetachedSignature>)> {
+        if options.standalone {
+            let appinst = self.standalone_link()?;
+            try_or!(*link_to == appinst, StandaloneLinkMismatch(link_to.to_string()))?;
+        }
+        let (msg, detached) = self
+            .user
+            .sign_packet_with(link_to, public_payload, masked_payload, options)?;
+        let links = if options.confirmed {
+            self.send_message_sequenced_confirmed(msg, link_to.rel(), MsgInfo::SignedPacket)
+                .await?
+        } else {
+            self.send_message_sequenced(msg, link_to.rel(), MsgInfo::SignedPacket)
+                .await?
+        };
+        Ok((links, detached))
+    }
+
+    /// Retrieves and processes the standalone packets of other publishers, see
+    /// `PacketOptions::with_standalone` [Author, Subscriber]. The addresses following the current
+    /// sequence number of each publisher are probed, up to `max_gap` missing packets are skipped
+    /// before probing of the publisher stops. Packets numbered below the current sequence number,
+    /// eg. published concurrently with already processed messages, are not probed.
+    ///
+    ///  # Arguments
+    ///  * `max_gap` - Number of consecutive missing packets skipped
+    pub async fn fetch_standalone_packets(&mut self, max_gap: usize) -> Result<Vec<UnwrappedMessage>> {
+        let appinst = self.standalone_link()?;
+        let mut msgs = Vec::new();
+        for (id, mut seq_no) in self.standalone_publishers() {
+            let mut gap = 0;
+            while gap <= max_gap {
+                let link = self.standalone_packet_link(&appinst, &id, seq_no + gap as u32);
+                let processed = match self.transport.recv_message(&link).await {
+                    Ok(msg) => self.handle_message(msg, true).await.ok(),
+                    Err(_) => None,
+                };
+                match processed {
+                    Some(msg) => {
+                        msgs.push(msg);
+                        seq_no += gap as u32 + 1;
+                        gap = 0;
+                    }
+                    None => gap += 1,
+                }
+            }
+        }
+        Ok(msgs)
+    }
+
+    /// Create and send a chain of signed packets, each one linked to the previous one [Author, Subscriber].
+    ///
+    /// All the packets are wrapped before any of them is sent, the linked message states are
+    /// chained in memory. If any packet can not be wrapped or sent, the user state is restored to
+    /// that before the call; packets sent before the failure are not retracted from the transport.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the first packet will be attached to
+    ///  * `payloads` - Public and masked payloads of the packets
+    pub async fn send_signed_packets<I>(
+        &mut self,
+        link_to: &Address,
+        payloads: I,
+    ) -> Result<Vec<(Address, Option<Address>)>>
+    where
+        I: IntoIterator<Item = (Bytes, Bytes)>,
+    {
+        let checkpoint = self.checkpoint();
+        let (msgs, links) = match self.wrap_signed_packet_chain(link_to, payloads) {
+            Ok(chain) => chain,
+            Err(e) => {
+                self.restore(checkpoint);
+                return Err(e);
+            }
+        };
+        for msg in msgs {
+            if let Err(e) = self.publish(msg).await {
+                self.restore(checkpoint);
+                return Err(e);
+            }
+        }
+        debug_event!(count = links.len(), "signed packets sent");
+        Ok(links)
+    }
+
+    /// Create and send a close message marking the end of the channel [Author].
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the close message will be attached to
+    pub async fn send_close(&mut self, link_to: &Address) -> Result<(Address, Option<Address>)> {
+        let msg = self.user.close(link_to)?;
+        let links = self.send_message_sequenced(msg, link_to.rel(), MsgInfo::Close).await?;
+        self.user.closed = true;
+        Ok(links)
+    }
+
+    /// Create and send a message sealing the channel membership [Author]. Subscriptions of new
+    /// subscribers are rejected afterwards, messages are still published to the existing ones.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the seal membership message will be attached to
+    pub async fn send_seal_membership(&mut self, link_to: &Address) -> Result<(Address, Option<Address>)> {
+        let msg = self.user.seal_membership(link_to)?;
+        let links = self.send_message_sequenced(msg, link_to.rel(), MsgInfo::SealMembership).await?;
+        self.user.membership_sealed = true;
+        Ok(links)
+    }
+
+    /// Create and send a checkpoint summarizing a single-branch channel up to the `link_to` message
+    /// [Author]. New subscribers can start reading the channel from the checkpoint with
+    /// `bootstrap_from_checkpoint`, the following messages should be linked to the checkpoint.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the last message summarized by the checkpoint
+    pub async fn publish_checkpoint(&mut self, link_to: &Address) -> Result<(Address, Option<Address>)> {
+        let msg = self.user.checkpoint(link_to)?;
+        self.send_message_sequenced(msg, link_to.rel(), MsgInfo::Checkpoint).await
+    }
+
+    /// Create and send a message adding an author to the channel [Author]. Keyloads and admin
+    /// messages signed by the added author are accepted by users processing the message.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the add author message will be attached to
+    ///  * `author_pk` - Signature public key of the added author
+    pub async fn send_add_author(&mut self, link_to: &Address, author_pk: &PublicKey) -> Result<(Address, Option<Address>)> {
+        let msg = self.user.add_author(link_to, author_pk)?;
+        let links = self.send_message_sequenced(msg, link_to.rel(), MsgInfo::AddAuthor).await?;
+        self.user.trust_author(*author_pk)?;
+        Ok(links)
+    }
+
+    /// Create and send an ack message acknowledging that the channel was read up to the message
+    /// [Author, Subscriber]. The ack is attached to the acknowledged message, its sequence number
+    /// is sent as the acknowledged position.
+    ///
+    ///  # Arguments
+    ///  * `up_to` - Address of the last message read
+    pub async fn send_ack(&mut self, up_to: &Address) -> Result<(Address, Option<Address>)> {
+        let acked = self.transport.recv_message(up_to).await?;
+        let position = acked.binary.parse_header()?.header.seq_num.0 as usize;
+        let msg = self.user.ack(up_to, position)?;
+        self.send_message_sequenced(msg, up_to.rel(), MsgInfo::Ack).await
+    }
+
+    /// Create and send a tagged packet [Author, Subscriber].
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    pub async fn send_tagged_packet(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+    ) -> Result<(Address, Option<Address>)> {
+        let msg = self.user.tag_packet(link_to, public_payload, masked_payload)?;
+        self.send_message_sequenced(msg, link_to.rel(), MsgInfo::TaggedPacket)
+            .await
+    }
+
+    /// Create and send a tagged packet bound to an application context [Author, Subscriber].
+    /// The context is sent in plain and is covered by the MAC.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    ///  * `context` - Application context (eg. external request id)
+    pub async fn send_tagged_packet_with_context(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        context: &Bytes,
+    ) -> Result<(Address, Option<Address>)> {
+        let msg = self
+            .user
+            .tag_packet_with_context(link_to, public_payload, masked_payload, context)?;
+        self.send_message_sequenced(msg, link_to.rel(), MsgInfo::TaggedPacket)
+            .await
+    }
+
+    /// Create and send a tagged packet tagged with an application type [Author, Subscriber].
+    /// The type is sent in plain and is covered by the MAC.
     ///
     ///  # Arguments
-    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `link_to` - Address of the message the packet will be attached to
     ///  * `public_payload` - Wrapped vector of Bytes to have public access
     ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
-    pub async fn send_tagged_packet(
+    ///  * `app_type` - Application defined content type, 0 is the default type
+    pub async fn send_tagged_packet_with_app_type(
         &mut self,
         link_to: &Address,
         public_payload: &Bytes,
         masked_payload: &Bytes,
+        app_type: u16,
     ) -> Result<(Address, Option<Address>)> {
-        let msg = self.user.tag_packet(link_to, public_payload, masked_payload)?;
+        let msg = self
+            .user
+            .tag_packet_with_app_type(link_to, public_payload, masked_payload, app_type)?;
         self.send_message_sequenced(msg, link_to.rel(), MsgInfo::TaggedPacket)
             .await
     }
@@ -610,15 +3005,104 @@ impl<Trans: Transport + Clone> User<Trans> {
     ///  # Arguments
     ///  * `link_to` - Address of the message the keyload will be attached to
     ///  * `psk_ids` - Vector of Pre-shared key ids to be included in message
-    ///  * `ke_pks`  - Vector of Public Keys to be included in message
+    ///  * `ke_pks`  - Vector of Public Keys to be included in message, viewing keys of readers are
+    ///    included as `Identifier::XPubKey`
     pub async fn send_keyload(
         &mut self,
         link_to: &Address,
         psk_ids: &PskIds,
         ke_pks: &Vec<&Identifier>,
     ) -> Result<(Address, Option<Address>)> {
+        let fingerprint = self.user.keyload_fingerprint(ke_pks);
         let msg = self.user.share_keyload(link_to, psk_ids, ke_pks)?;
-        self.send_message_sequenced(msg, link_to.rel(), MsgInfo::Keyload).await
+        let links = self
+            .send_message_sequenced(msg, link_to.rel(), MsgInfo::Keyload)
+            .await?;
+        self.user.set_last_keyload(fingerprint);
+        Ok(links)
+    }
+
+    /// Create and send a new keyload shared with holders of the viewing keys only, granting them
+    /// read access without making them publishers [Author].
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `viewing_keys` - Viewing keys of the readers, see `viewing_key`
+    pub async fn send_keyload_for_viewers(
+        &mut self,
+        link_to: &Address,
+        viewing_keys: &[x25519::PublicKey],
+    ) -> Result<(Address, Option<Address>)> {
+        let viewers: Vec<Identifier> = viewing_keys.iter().map(|pk| Identifier::from(*pk)).collect();
+        self.send_keyload(link_to, &Vec::new(), &viewers.iter().collect()).await
+    }
+
+    /// Create and send a new keyload for a list of subscribers unless the last keyload sent to
+    /// the channel had the same recipients [Author]. Returns `None` if the keyload was skipped.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `psk_ids` - Vector of Pre-shared key ids to be included in message
+    ///  * `ke_pks`  - Vector of Public Keys to be included in message
+    pub async fn send_keyload_if_changed(
+        &mut self,
+        link_to: &Address,
+        psk_ids: &PskIds,
+        ke_pks: &Vec<&Identifier>,
+    ) -> Result<Option<(Address, Option<Address>)>> {
+        if self.user.is_last_keyload(&self.user.keyload_fingerprint(ke_pks)) {
+            return Ok(None);
+        }
+        self.send_keyload(link_to, psk_ids, ke_pks).await.map(Some)
+    }
+
+    /// Create and send keyloads for a list of subscribers split into keyloads of at most
+    /// `max_per_message` recipients, all attached to `link_to` [Author]. Each keyload shares its own
+    /// session key and can be opened independently, every subscriber gains access with the keyload
+    /// it is included in. Pre-shared keys are included in the first keyload. If a keyload can not
+    /// be sent, the user state is restored.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyloads will be attached to
+    ///  * `psk_ids` - Vector of Pre-shared key ids to be included in the first keyload
+    ///  * `ke_pks`  - Vector of Public Keys to be split across the keyloads
+    ///  * `max_per_message` - Maximum number of public keys per keyload
+    pub async fn send_keyload_split(
+        &mut self,
+        link_to: &Address,
+        psk_ids: &PskIds,
+        ke_pks: &Vec<&Identifier>,
+        max_per_message: usize,
+    ) -> Result<Vec<(Address, Option<Address>)>> {
+        let groups = Self::split_recipients(ke_pks, max_per_message)?;
+        let fingerprint = self.user.keyload_fingerprint(ke_pks);
+        let checkpoint = self.checkpoint();
+        let mut links = Vec::with_capacity(groups.len());
+        for (i, group) in groups.iter().enumerate() {
+            let group_psk_ids = if i == 0 { psk_ids.clone() } else { Vec::new() };
+            match self.send_keyload(link_to, &group_psk_ids, &group.to_vec()).await {
+                Ok(link) => links.push(link),
+                Err(e) => {
+                    self.restore(checkpoint);
+                    return Err(e);
+                }
+            }
+        }
+        self.user.set_last_keyload(fingerprint);
+        Ok(links)
+    }
+
+    /// Create and send keyload for the members of a group of subscribers [Author].
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `group` - Name of the group of recipients
+    pub async fn send_keyload_for_group(&mut self, link_to: &Address, group: &str) -> Result<(Address, Option<Address>)> {
+        let members = match self.user.group_members(group) {
+            Some(members) => members.to_vec(),
+            None => return err!(UnknownSubscriberGroup(group.to_string())),
+        };
+        self.send_keyload(link_to, &Vec::new(), &members.iter().collect()).await
     }
 
     /// Create and send keyload for all subscribed subscribers [Author].
@@ -626,8 +3110,28 @@ impl<Trans: Transport + Clone> User<Trans> {
     ///  # Arguments
     ///  * `link_to` - Address of the message the keyload will be attached to
     pub async fn send_keyload_for_everyone(&mut self, link_to: &Address) -> Result<(Address, Option<Address>)> {
+        let fingerprint = self.user.keyload_fingerprint_for_everyone();
         let msg = self.user.share_keyload_for_everyone(link_to)?;
-        self.send_message_sequenced(msg, link_to.rel(), MsgInfo::Keyload).await
+        let links = self
+            .send_message_sequenced(msg, link_to.rel(), MsgInfo::Keyload)
+            .await?;
+        self.user.set_last_keyload(fingerprint);
+        Ok(links)
+    }
+
+    /// Create and send keyload for all subscribed subscribers unless the last keyload sent to
+    /// the channel had the same recipients [Author]. Returns `None` if the keyload was skipped.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    pub async fn send_keyload_for_everyone_if_changed(
+        &mut self,
+        link_to: &Address,
+    ) -> Result<Option<(Address, Option<Address>)>> {
+        if self.user.is_last_keyload(&self.user.keyload_fingerprint_for_everyone()) {
+            return Ok(None);
+        }
+        self.send_keyload_for_everyone(link_to).await.map(Some)
     }
 
     /// Create and Send a Subscribe message to a Channel app instance [Subscriber].
@@ -639,6 +3143,17 @@ impl<Trans: Transport + Clone> User<Trans> {
         self.send_message(msg, MsgInfo::Subscribe).await
     }
 
+    /// Create and Send a Subscribe message carrying an application payload for the author, eg. a
+    /// display name [Subscriber]. The payload is masked with the key shared with the author.
+    ///
+    /// # Arguments
+    /// * `link_to` - Address of the Channel Announcement message
+    /// * `payload` - Wrapped vector of Bytes readable by the author only
+    pub async fn send_subscribe_with_payload(&mut self, link_to: &Address, payload: &Bytes) -> Result<Address> {
+        let msg = self.user.subscribe_with_payload(link_to, payload)?;
+        self.send_message(msg, MsgInfo::Subscribe).await
+    }
+
     // Receive
 
     /// Receive and process a sequence message [Author, Subscriber].
@@ -671,6 +3186,67 @@ impl<Trans: Transport + Clone> User<Trans> {
         Ok(m.body)
     }
 
+    /// Receive and process a close message of the channel author [Author, Subscriber].
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    pub async fn receive_close(&mut self, link: &Address) -> Result<()> {
+        let msg = self.transport.recv_message(link).await?;
+        self.user.handle_close(msg.binary, MsgInfo::Close)?;
+        Ok(())
+    }
+
+    /// Receive and process a message sealing the channel membership, it must be signed by a
+    /// trusted author [Author, Subscriber].
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    pub async fn receive_seal_membership(&mut self, link: &Address) -> Result<()> {
+        let msg = self.transport.recv_message(link).await?;
+        self.user.handle_seal_membership(msg.binary, MsgInfo::SealMembership)?;
+        Ok(())
+    }
+
+    /// Receive and process a message adding an author to the channel, it must be signed by a
+    /// trusted author [Author, Subscriber]. Returns the public key of the added author.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    pub async fn receive_add_author(&mut self, link: &Address) -> Result<PublicKey> {
+        let msg = self.transport.recv_message(link).await?;
+        let m = self.user.handle_add_author(msg.binary, MsgInfo::AddAuthor)?;
+        Ok(m.body)
+    }
+
+    /// Receive and process an ack message [Author, Subscriber]. Returns the public key of the
+    /// publisher and the acknowledged position, see `acked_position`.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    pub async fn receive_ack(&mut self, link: &Address) -> Result<(PublicKey, usize)> {
+        let msg = self.transport.recv_message(link).await?;
+        let m = self.user.handle_ack(msg.binary, MsgInfo::Ack)?;
+        Ok(m.body)
+    }
+
+    /// Initialize the user from a checkpoint instead of replaying the channel from the
+    /// announcement [Subscriber]. The announcement linked by the checkpoint is processed first if
+    /// the user is not registered to a channel, the checkpoint must be signed by the channel
+    /// author. Returns the address of the last message summarized by the checkpoint.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the checkpoint message
+    pub async fn bootstrap_from_checkpoint(&mut self, link: &Address) -> Result<Address> {
+        let msg = self.transport.recv_message(link).await?;
+        if !self.is_registered() {
+            let header = msg.binary.parse_header()?.header;
+            let ann_link = Address::from_bytes(&header.previous_msg_link.0);
+            self.receive_announcement(&ann_link).await?;
+        }
+        let m = self.user.handle_checkpoint(msg.binary, MsgInfo::Checkpoint)?;
+        Ok(m.body)
+    }
+
     /// Receive and process a tagged packet message [Author, Subscriber].
     ///
     ///  # Arguments
@@ -681,6 +3257,22 @@ impl<Trans: Transport + Clone> User<Trans> {
         Ok(m.body)
     }
 
+    /// Receive and process a message with application defined content [Author, Subscriber].
+    ///
+    /// The message spongos state is stored and the single-branch sequencing state is updated,
+    /// see `api::user::User::handle_custom` for the checks left to the caller.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///  * `content` - Content used to unwrap the message
+    pub async fn handle_custom<C>(&mut self, link: &Address, content: C) -> Result<GenericMessage<Address, C>>
+    where
+        C: ContentUnwrap<DefaultF, LinkStore>,
+    {
+        let msg = self.transport.recv_message(link).await?;
+        self.user.handle_custom(msg.binary, MsgInfo::Custom, content)
+    }
+
     /// Receive and process a subscribe message [Author].
     ///
     ///  # Arguments
@@ -691,6 +3283,16 @@ impl<Trans: Transport + Clone> User<Trans> {
         self.user.handle_subscribe(msg.binary, MsgInfo::Subscribe)
     }
 
+    /// Receive and process a subscribe message, get the subscriber identifier and the
+    /// subscription payload, empty if the subscriber sent none [Author].
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    pub async fn receive_subscribe_with_payload(&mut self, link: &Address) -> Result<(Identifier, Bytes)> {
+        let msg = self.transport.recv_message(link).await?;
+        self.user.handle_subscribe_with_payload(msg.binary, MsgInfo::Subscribe)
+    }
+
     /// Receive and Process an announcement message [Subscriber].
     ///
     /// # Arguments
@@ -700,6 +3302,31 @@ impl<Trans: Transport + Clone> User<Trans> {
         self.user.handle_announcement(msg.binary, MsgInfo::Announce)
     }
 
+    /// Fetch an announcement and read the author and channel details without binding the user to
+    /// the channel, the user may be bound to another channel. The user state is not changed
+    /// [Subscriber]
+    ///
+    /// # Arguments
+    /// * `link` - Address of the Channel Announcement message
+    pub async fn peek_announcement(&mut self, link: &Address) -> Result<AnnouncementInfo> {
+        let msg = self.transport.recv_message(link).await?;
+        self.user.peek_announcement(&msg.binary)
+    }
+
+    /// Join the channel with an invitation created by its author: the announcement is fetched and
+    /// checked to be signed by the invitation author. The PSK referenced by the invitation, if any,
+    /// is not carried by it and has to be stored with `store_psk` [Subscriber].
+    ///
+    /// # Arguments
+    /// * `bytes` - Invitation encoded by `create_invitation`
+    pub async fn accept_invitation(&mut self, bytes: &[u8]) -> Result<Invitation> {
+        let invitation = Invitation::from_bytes(bytes)?;
+        let msg = self.transport.recv_message(invitation.announcement()).await?;
+        self.check_invitation_announcement(&invitation, &msg.binary)?;
+        self.user.handle_announcement(msg.binary, MsgInfo::Announce)?;
+        Ok(invitation)
+    }
+
     /// Receive and process a keyload message [Subscriber].
     ///
     ///  # Arguments
@@ -718,9 +3345,98 @@ impl<Trans: Transport + Clone> User<Trans> {
     ///   * `pk` - Optional ed25519 Public Key of the sending participant. None if unknown
     pub async fn receive_message(&mut self, link: &Address) -> Result<UnwrappedMessage> {
         let msg = self.transport.recv_message(link).await?;
+        if let Some(orphan) = self.orphan(&msg)? {
+            return Ok(orphan);
+        }
+        self.handle_message(msg, true).await
+    }
+
+    /// Receive and process a message expected from the given publisher. The message is rejected
+    /// before it is processed, leaving the user state untouched, if its header declares another
+    /// publisher [Author, Subscriber].
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message to be processed
+    ///   * `expected` - Identifier of the expected publisher
+    pub async fn receive_message_from(&mut self, link: &Address, expected: &Identifier) -> Result<UnwrappedMessage> {
+        let msg = self.transport.recv_message(link).await?;
+        Self::check_publisher(&msg, expected)?;
+        if let Some(orphan) = self.orphan(&msg)? {
+            return Ok(orphan);
+        }
         self.handle_message(msg, true).await
     }
 
+    /// Receive and process the messages of a publisher from its sequencing state up to the one with
+    /// the given sequence number [Author, Subscriber]. Message links depend on the previous message,
+    /// so the publisher's messages are received in order, the returned messages end with the
+    /// requested one. In single-branch channels the chain is only followed while the publisher is
+    /// the one sending the next message, `sync` should be used to read messages of several
+    /// publishers.
+    ///
+    /// Receiving stops with an error if there is no message at the computed address or if the
+    /// message is linked to a message which was not processed. Messages processed before the error
+    /// are kept in the user state, they can be received again with `receive_message`.
+    ///
+    ///   # Arguments
+    ///   * `publisher` - Identifier of the publisher of the message
+    ///   * `sequence` - Sequence number of the message
+    pub async fn receive_message_at(&mut self, publisher: &Identifier, sequence: usize) -> Result<Vec<UnwrappedMessage>> {
+        let mut msgs = Vec::new();
+        loop {
+            let next_seq_no = self.user.next_seq_no(publisher)? as usize;
+            try_or!(sequence >= next_seq_no, SeqNumAlreadyProcessed(sequence, next_seq_no))?;
+            let link = self.user.next_link(publisher)?;
+            let msg = match self.transport.recv_message(&link).await {
+                Ok(msg) => msg,
+                Err(_) => return err!(SeqNumMessageNotFound(next_seq_no, link.to_string())),
+            };
+            try_or!(
+                self.orphan(&msg)?.is_none(),
+                SeqNumMessageOrphaned(next_seq_no, link.to_string())
+            )?;
+            msgs.push(self.handle_message(msg, true).await?);
+            if next_seq_no == sequence {
+                return Ok(msgs);
+            }
+        }
+    }
+
+    /// Delete the message from the transport layer and forget its spongos state [Author, Subscriber].
+    ///
+    /// Warning: retraction breaks the chain of messages linked after the deleted one, users who have
+    /// not processed the deleted message yet will not be able to unwrap them. Sequencing states are
+    /// not changed.
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message to be retracted
+    pub async fn retract(&mut self, link: &Address) -> Result<()> {
+        self.transport.delete_message(link).await?;
+        self.user.rollback_link(link.rel(), None)
+    }
+
+    /// Forget spongos states of messages which are not reachable from `reachable_from` following
+    /// the links to previous messages [Author, Subscriber]. The states of the announcement and of
+    /// the messages referred to by sequencing states are kept. Returns the number of removed states.
+    ///
+    ///   # Arguments
+    ///   * `reachable_from` - Address of the most recent message to be kept
+    pub async fn gc_spongos(&mut self, reachable_from: &Address) -> Result<usize> {
+        try_or!(
+            self.user.contains_spongos(reachable_from.rel()),
+            MessageLinkNotFound(reachable_from.to_string())
+        )?;
+        let mut reachable = Vec::new();
+        let mut link = reachable_from.clone();
+        while self.user.contains_spongos(link.rel()) && !reachable.contains(link.rel()) {
+            reachable.push(link.rel().clone());
+            let msg = self.transport.recv_message(&link).await?;
+            let header = msg.binary.parse_header()?.header;
+            link = Address::from_bytes(&header.previous_msg_link.0);
+        }
+        Ok(self.user.retain_spongos(&reachable))
+    }
+
     /// Retrieves the next message for each user (if present in transport layer) and returns them [Author, Subscriber]
     pub async fn fetch_next_msgs(&mut self) -> Vec<UnwrappedMessage> {
         let ids = self.user.gen_next_msg_ids(self.user.is_multi_branching());
@@ -746,6 +3462,348 @@ impl<Trans: Transport + Clone> User<Trans> {
         msgs
     }
 
+    /// Retrieves the next message for each user like `fetch_next_msgs`, each message is returned
+    /// along with the position just after it to resume processing from [Author, Subscriber]
+    pub async fn fetch_next_msgs_with_positions(&mut self) -> Vec<(UnwrappedMessage, Position)> {
+        let ids = self.user.gen_next_msg_ids(self.user.is_multi_branching());
+        let mut msgs = Vec::new();
+
+        for (_pk, cursor) in ids {
+            if let Ok(msg) = self.transport.recv_message(&cursor.link).await {
+                if let Ok(msg) = self.handle_message(msg, true).await {
+                    msgs.push((msg, self.position()));
+                }
+            }
+        }
+        msgs
+    }
+
+    /// Counts the messages present in the transport layer at the next expected address of each
+    /// publisher, without unwrapping them [Author, Subscriber]. Addresses which fail to be retrieved
+    /// are not counted.
+    pub async fn pending_count(&mut self) -> Result<usize> {
+        try_or!(self.user.appinst.is_some(), UserNotRegistered)?;
+        let ids = self.user.gen_next_msg_ids(self.user.is_multi_branching());
+        let mut count = 0;
+        for (_pk, cursor) in ids {
+            if self.transport.recv_message(&cursor.link).await.is_ok() {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Fetches and processes all the new messages of all publishers and returns them newest first
+    /// [Author, Subscriber].
+    ///
+    /// Unwrapping a message requires the spongos state of the message it is linked to, so the
+    /// messages are fetched forward up to the head of each publisher and buffered before they are
+    /// returned: memory grows with the number of new messages and none of them is available
+    /// before the latest one is fetched. Messages are processed as in forward mode, masked payloads
+    /// are decrypted if the user has access to them. Use `fetch_next_msgs` to bound the memory.
+    pub async fn messages_reverse(&mut self) -> Vec<UnwrappedMessage> {
+        let mut msgs = Vec::new();
+        loop {
+            let next_msgs = self.fetch_next_msgs().await;
+            if next_msgs.is_empty() {
+                break;
+            }
+            msgs.extend(next_msgs);
+        }
+        msgs.reverse();
+        msgs
+    }
+
+    /// Iteratively fetches next messages until the message at `stop` has been processed or no new
+    /// messages can be found [Author, Subscriber]. Messages from other publishers retrieved in the
+    /// same round as `stop` are processed and returned as well, so that no cursor state is lost.
+    ///
+    /// # Arguments
+    /// * `stop` - Address of the last message to be processed
+    pub async fn fetch_next_msgs_until(&mut self, stop: &Address) -> Vec<UnwrappedMessage> {
+        let mut msgs = Vec::new();
+        loop {
+            let next_msgs = self.fetch_next_msgs().await;
+            if next_msgs.is_empty() {
+                break;
+            }
+            let reached = next_msgs.iter().any(|msg| &msg.link == stop);
+            msgs.extend(next_msgs);
+            if reached {
+                break;
+            }
+        }
+        msgs
+    }
+
+    /// Lists the messages of the channel stored by the transport, if it supports enumeration, so
+    /// that the next `sync` only retrieves expected addresses which are listed [Author, Subscriber].
+    /// It avoids probing every publisher's next address on initial synchronization with large
+    /// channels. Returns the number of listed messages.
+    ///
+    /// Most transports, including the Tangle client, can't enumerate messages and the call fails
+    /// with `MessageListingUnsupported`: `sync` then probes expected addresses as usual. Messages
+    /// sent after the listing are found by the following `sync`.
+    pub async fn discover_messages(&mut self) -> Result<usize> {
+        let appinst = match &self.user.appinst {
+            Some(appinst) => appinst.clone(),
+            None => return err!(UserNotRegistered),
+        };
+        let links: HashSet<Address> = self
+            .transport
+            .list_links(&appinst).await?
+            .into_iter()
+            .filter(|link| link.base() == appinst.base())
+            .collect();
+        let count = links.len();
+        self.discovered = Some((appinst, links));
+        Ok(count)
+    }
+
+    /// Fetches and processes next messages of all publishers until no progress is made [Author,
+    /// Subscriber]. Messages linked to a message of another publisher that is not processed yet are
+    /// kept as orphans and retried once more messages are processed, without fetching them again.
+    /// The number of buffered orphans is limited, see `set_max_orphans`. Returns the number of
+    /// processed messages and the number of messages left orphaned.
+    ///
+    /// The future can be dropped at any await point, eg. on shutdown or timeout: messages are
+    /// only processed once they are retrieved, without awaiting in between, so the user state
+    /// reflects the messages processed so far. Buffered orphans and listed links are dropped along
+    /// with the future, the next `sync` fetches them again.
+    pub async fn sync(&mut self) -> Result<(usize, usize)> {
+        let mut reporter = self.sync_reporter().await;
+        let (resolved, orphans) = self.sync_orphans(&mut reporter, &mut None).await?;
+        if let Some(reporter) = reporter {
+            reporter.finish();
+        }
+        Ok((resolved, orphans.len()))
+    }
+
+    /// Synchronizes like `sync`, passing the user state exported with `flag` and `pwd` to
+    /// `persist` every `batch` processed messages and once synchronization completes [Author,
+    /// Subscriber]. A failure to persist stops synchronization. If synchronization is interrupted,
+    /// the progress made since the last persisted state is lost and the messages are processed
+    /// again by the next `sync` of the imported state.
+    pub async fn sync_persisting<P>(
+        &mut self,
+        flag: u8,
+        pwd: &str,
+        batch: usize,
+        mut persist: P,
+    ) -> Result<(usize, usize)>
+    where
+        P: FnMut(&[u8]) -> Result<()>,
+    {
+        let mut reporter = self.sync_reporter().await;
+        let mut persister = Some(SyncPersister {
+            persist: &mut persist,
+            flag,
+            pwd,
+            batch: batch.max(1),
+            pending: 0,
+        });
+        let (resolved, orphans) = self.sync_orphans(&mut reporter, &mut persister).await?;
+        if let Some(persister) = persister {
+            persister.finish(&self.user)?;
+        }
+        if let Some(reporter) = reporter {
+            reporter.finish();
+        }
+        Ok((resolved, orphans.len()))
+    }
+
+    /// Synchronizes like `sync`, skipping messages which can't be processed, eg. corrupt ones,
+    /// instead of stopping at them [Author, Subscriber]. Once no progress is made, the cursor of
+    /// the publisher of every message left orphaned is moved past it as if it was processed and
+    /// synchronization goes on with the following messages. Returns the number of processed
+    /// messages and the addresses of the skipped ones for the caller to investigate.
+    ///
+    /// Skipped messages are not retried. Messages linked to a skipped message can't be processed
+    /// and are skipped as well, as are messages linked to a message not retrievable yet or
+    /// protected by a keyload the user has no access to.
+    pub async fn sync_skipping_errors(&mut self) -> Result<(usize, Vec<Address>)> {
+        let mut reporter = self.sync_reporter().await;
+        let mut resolved = 0;
+        let mut skipped = Vec::new();
+        loop {
+            let (count, orphans) = self.sync_orphans(&mut reporter, &mut None).await?;
+            resolved += count;
+            let mut progress = false;
+            for msg in orphans {
+                // Orphans don't advance cursors, an orphan is still expected next from its publisher
+                let link = msg.binary.link;
+                let ids = self.user.gen_next_msg_ids(self.user.is_multi_branching());
+                if let Some((id, cursor)) = ids.into_iter().find(|(_id, cursor)| cursor.link == link) {
+                    debug_event!(link = %link.to_string(), "skipping message which can not be processed");
+                    if self.user.is_multi_branching() {
+                        self.user.store_state(id, link.msgid.clone())?;
+                    } else {
+                        self.user.store_state_for_all(link.msgid.clone(), cursor.seq_no + 1)?;
+                    }
+                    skipped.push(link);
+                    if let Some(reporter) = &mut reporter {
+                        reporter.advance();
+                    }
+                    progress = true;
+                }
+            }
+            if !progress {
+                break;
+            }
+        }
+        if let Some(reporter) = reporter {
+            reporter.finish();
+        }
+        Ok((resolved, skipped))
+    }
+
+    /// Fetches and processes next messages of all publishers until no new message appears at their
+    /// expected addresses for a number of consecutive rounds, see `set_sync_max_misses` [Author,
+    /// Subscriber]. Unlike `sync`, missing addresses are not told apart by the transport errors:
+    /// an error, no message or a message which can't be processed are all missed alike, so that
+    /// transports returning no message for unknown addresses can't make it loop. Messages which
+    /// can't be processed are fetched again in the next round rather than buffered as orphans.
+    pub async fn sync_to_head(&mut self) -> Result<SyncToHead> {
+        try_or!(self.user.appinst.is_some(), UserNotRegistered)?;
+        let mut fetched = 0;
+        let mut misses = 0;
+        loop {
+            let mut progress = false;
+            let mut found = false;
+            let ids = self.user.gen_next_msg_ids(self.user.is_multi_branching());
+            for (_pk, cursor) in ids {
+                if let Ok(msg) = self.transport.recv_message(&cursor.link).await {
+                    found = true;
+                    if self.handle_message(msg, true).await.is_ok() {
+                        fetched += 1;
+                        progress = true;
+                    }
+                }
+            }
+
+            if progress {
+                misses = 0;
+                continue;
+            }
+            misses += 1;
+            if misses >= self.sync_max_misses {
+                return Ok(SyncToHead {
+                    fetched,
+                    reached_head: !found,
+                });
+            }
+        }
+    }
+
+    /// Progress reporter of a `sync` about to start if a callback is set, with the estimated
+    /// number of messages to process.
+    async fn sync_reporter(&mut self) -> Option<SyncReporter> {
+        let (callback, interval) = self.sync_progress.clone()?;
+        let total = match self.discovered_pending() {
+            Some(total) => total,
+            None => self.probe_pending().await,
+        };
+        Some(SyncReporter {
+            callback,
+            interval,
+            progress: SyncProgress { processed: 0, total },
+        })
+    }
+
+    /// Counts the new messages by fetching them at expected addresses without unwrapping them. In
+    /// multi-branch channels addresses following the next message of each publisher can't be
+    /// derived without unwrapping it and only next messages are counted.
+    async fn probe_pending(&mut self) -> usize {
+        let branching = self.user.is_multi_branching();
+        let mut links = self
+            .user
+            .gen_next_msg_ids(branching)
+            .into_iter()
+            .map(|(_id, cursor)| cursor)
+            .collect::<Vec<_>>();
+        let mut count = 0;
+        loop {
+            let mut found = None;
+            for cursor in &links {
+                if self.transport.recv_message(&cursor.link).await.is_ok() {
+                    count += 1;
+                    found = Some((cursor.link.msgid.clone(), cursor.seq_no + 1));
+                    if !branching {
+                        break;
+                    }
+                }
+            }
+            match found {
+                Some((msgid, seq_no)) if !branching => links = self.probe_next_links(&msgid, seq_no),
+                _ => break,
+            }
+        }
+        count
+    }
+
+    /// Processes next messages of all publishers until no progress is made, returning the number
+    /// of processed messages and the messages left orphaned.
+    async fn sync_orphans(
+        &mut self,
+        reporter: &mut Option<SyncReporter>,
+        persister: &mut Option<SyncPersister<'_>>,
+    ) -> Result<(usize, Vec<Message>)> {
+        try_or!(self.user.appinst.is_some(), UserNotRegistered)?;
+        let mut orphans: Vec<Message> = Vec::new();
+        // Listed links are only used once, by the sync following the discovery
+        let discovered = match self.discovered.take() {
+            Some((appinst, links)) if self.user.appinst.as_ref() == Some(&appinst) => Some(links),
+            _ => None,
+        };
+        let mut resolved = 0;
+        loop {
+            let mut progress = false;
+            for msg in core::mem::take(&mut orphans) {
+                if self.handle_message(msg.clone(), true).await.is_ok() {
+                    resolved += 1;
+                    if let Some(reporter) = reporter {
+                        reporter.advance();
+                    }
+                    if let Some(persister) = persister {
+                        persister.advance(&self.user)?;
+                    }
+                    progress = true;
+                } else {
+                    orphans.push(msg);
+                }
+            }
+
+            let ids = self.user.gen_next_msg_ids(self.user.is_multi_branching());
+            for (_pk, cursor) in ids {
+                if orphans.iter().any(|msg| msg.binary.link == cursor.link) {
+                    continue;
+                }
+                if discovered.as_ref().map_or(false, |links| !links.contains(&cursor.link)) {
+                    continue;
+                }
+                if let Ok(msg) = self.transport.recv_message(&cursor.link).await {
+                    if self.handle_message(msg.clone(), true).await.is_ok() {
+                        resolved += 1;
+                        if let Some(reporter) = reporter {
+                            reporter.advance();
+                        }
+                        if let Some(persister) = persister {
+                            persister.advance(&self.user)?;
+                        }
+                        progress = true;
+                    } else {
+                        self.buffer_orphan(&mut orphans, msg)?;
+                    }
+                }
+            }
+
+            if !progress {
+                break;
+            }
+        }
+        Ok((resolved, orphans))
+    }
+
     /// Retrieves the previous message from the message specified (provided the user has access to it) [Author,
     /// Subscriber]
     ///
@@ -788,34 +3846,113 @@ impl<Trans: Transport + Clone> User<Trans> {
         Ok(msgs)
     }
 
+    /// Verify that the message at `link` chains back to the announcement of the channel and get
+    /// the addresses of the chain, from the announcement to the message [Author, Subscriber].
+    ///
+    /// Linked messages are retrieved backward from the message until the announcement is reached,
+    /// then the messages not processed yet are processed forward from the announcement. Each
+    /// message is unwrapped joining the spongos state of the message it is linked to, which proves
+    /// the message is part of the chain published in the channel rather than only signed by a
+    /// participant. Messages the user can't read, eg. packets linked to a keyload the user is not a
+    /// recipient of, fail the verification.
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message to verify
+    pub async fn verify_provenance(&mut self, link: &Address) -> Result<Vec<Address>> {
+        let mut visited = HashSet::new();
+        let mut chain = Vec::new();
+        let mut next = Some(link.clone());
+        while let Some(link) = next {
+            let msg = match self.transport.recv_message(&link).await {
+                Ok(msg) => msg,
+                Err(_) => return err!(ProvenanceLinkMissing(link.to_string())),
+            };
+            next = self.provenance_step(&mut visited, &link, &msg)?;
+            chain.push((link, msg));
+        }
+
+        chain.reverse();
+        let mut path = Vec::with_capacity(chain.len());
+        for (link, msg) in chain {
+            if !self.is_processed(&link) {
+                if msg.binary.parse_header()?.header.content_type == message::SEQUENCE {
+                    self.process_sequence(msg.binary, false)?;
+                } else {
+                    self.handle_message(msg, false).await?;
+                }
+            }
+            path.push(link);
+        }
+        Ok(path)
+    }
+
     /// Handle message of unknown type. Ingests a message and unwraps it according to its determined
     /// content type [Author, Subscriber].
     ///
     /// # Arguments
     /// * `msg` - Binary message of unknown type
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self, msg0)))]
     pub async fn handle_message(&mut self, mut msg0: Message, store: bool) -> Result<UnwrappedMessage> {
         let mut sequenced = false;
         loop {
             // Forget TangleMessage and timestamp
             let msg = msg0.binary;
-            let preparsed = msg.parse_header()?;
+            let preparsed = self.user.parse_message(&msg)?;
             let link = preparsed.header.link.clone();
             let prev_link = TangleAddress::from_bytes(&preparsed.header.previous_msg_link.0);
+            debug_event!(
+                link = %link.to_string(),
+                linked = %prev_link.to_string(),
+                content_type = preparsed.header.content_type,
+                publisher = %hex::encode(preparsed.header.sender_id.to_bytes()),
+                "handling message"
+            );
             match preparsed.header.content_type {
-                message::SIGNED_PACKET => match self.user.handle_signed_packet(msg, MsgInfo::SignedPacket) {
+                message::ANNOUNCE => {
+                    // Registers the user to the channel, eg. a reader holding only a pre-shared key
+                    self.user.handle_announcement(msg, MsgInfo::Announce)?;
+                    return Ok(UnwrappedMessage::new(link, prev_link, MessageContent::new_announce()));
+                }
+                message::SIGNED_PACKET => match self.user.handle_signed_packet_content(msg, MsgInfo::SignedPacket) {
                     Ok(m) => {
-                        return Ok(m.map(|(pk, public, masked)| MessageContent::new_signed_packet(pk, public, masked)))
+                        return Ok(m.map(|content| MessageContent::SignedPacket {
+                            pk: content.sig_pk,
+                            public_payload: content.public_payload,
+                            masked_payload: content.masked_payload,
+                            context: content.context,
+                            metadata: content.metadata,
+                            app_type: content.app_type.map_or(0, |app_type| app_type.0),
+                        }))
                     }
                     Err(e) => match sequenced {
-                        true => return Ok(UnwrappedMessage::new(link, prev_link, MessageContent::unreadable())),
-                        false => return Err(e),
+                        true => {
+                            debug_event!(error = %e, "sequenced message is unreadable");
+                            return Ok(UnwrappedMessage::new(link, prev_link, MessageContent::unreadable()));
+                        }
+                        false => {
+                            debug_event!(linked = %prev_link.to_string(), error = %e, "message can not be handled");
+                            return Err(e);
+                        }
                     },
                 },
-                message::TAGGED_PACKET => match self.user.handle_tagged_packet(msg, MsgInfo::TaggedPacket) {
-                    Ok(m) => return Ok(m.map(|(public, masked)| MessageContent::new_tagged_packet(public, masked))),
+                message::TAGGED_PACKET => match self.user.handle_tagged_packet_content(msg, MsgInfo::TaggedPacket) {
+                    Ok(m) => {
+                        return Ok(m.map(|content| MessageContent::TaggedPacket {
+                            public_payload: content.public_payload,
+                            masked_payload: content.masked_payload,
+                            context: content.context,
+                            app_type: content.app_type.map_or(0, |app_type| app_type.0),
+                        }))
+                    }
                     Err(e) => match sequenced {
-                        true => return Ok(UnwrappedMessage::new(link, prev_link, MessageContent::unreadable())),
-                        false => return Err(e),
+                        true => {
+                            debug_event!(error = %e, "sequenced message is unreadable");
+                            return Ok(UnwrappedMessage::new(link, prev_link, MessageContent::unreadable()));
+                        }
+                        false => {
+                            debug_event!(linked = %prev_link.to_string(), error = %e, "message can not be handled");
+                            return Err(e);
+                        }
                     },
                 },
                 message::KEYLOAD => {
@@ -823,15 +3960,38 @@ impl<Trans: Transport + Clone> User<Trans> {
                     // inform the user that a message was present, even if the use wasn't part of
                     // the keyload itself. This is to prevent sequencing failures
                     let m = self.user.handle_keyload(msg, MsgInfo::Keyload)?;
-                    // TODO: Verify content, whether user is allowed or not!
                     let u = m.map(|_allowed| MessageContent::new_keyload());
                     return Ok(u);
                 }
+                message::CLOSE => {
+                    let m = self.user.handle_close(msg, MsgInfo::Close)?;
+                    return Ok(m.map(|_| MessageContent::Close));
+                }
+                message::ADD_AUTHOR => {
+                    let m = self.user.handle_add_author(msg, MsgInfo::AddAuthor)?;
+                    return Ok(m.map(|pk| MessageContent::AddAuthor { pk }));
+                }
+                message::SEAL_MEMBERSHIP => {
+                    let m = self.user.handle_seal_membership(msg, MsgInfo::SealMembership)?;
+                    return Ok(m.map(|_| MessageContent::SealMembership));
+                }
+                message::ACK => {
+                    let m = self.user.handle_ack(msg, MsgInfo::Ack)?;
+                    return Ok(m.map(|(pk, position)| MessageContent::Ack { pk, position }));
+                }
+                message::CHECKPOINT => {
+                    let m = self.user.handle_checkpoint(msg, MsgInfo::Checkpoint)?;
+                    return Ok(m.map(|head| MessageContent::Checkpoint { head }));
+                }
                 message::SEQUENCE => {
-                    let msg_link = self.process_sequence(msg, store)?;
-                    let msg = self.transport.recv_message(&msg_link).await?;
+                    // The referenced message is retrieved before the sequence message is committed,
+                    // if it can't be retrieved the sequencing state is left as it was
+                    let msg_link = self.sequenced_link(&msg)?;
+                    debug_event!(link = %msg_link.to_string(), "following sequence message");
+                    let next = self.transport.recv_message(&msg_link).await?;
+                    self.process_sequence(msg, store)?;
                     sequenced = true;
-                    msg0 = msg;
+                    msg0 = next;
                 }
                 unknown_content => return err!(UnknownMsgType(unknown_content)),
             }
@@ -847,3 +4007,6 @@ impl<Trans: Transport + Clone> User<Trans> {
         Ok((link, header.content_type, msg))
     }
 }
+
+#[cfg(test)]
+mod tests;
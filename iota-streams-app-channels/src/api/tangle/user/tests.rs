@@ -0,0 +1,2930 @@
+use super::*;
+use core::cell::RefCell;
+use iota_streams_core::{
+    prelude::Rc,
+    psk,
+    Errors::{
+        self,
+        BackupMacLengthMismatch,
+        LinkedSpongosMismatch,
+        SeqNumAlreadyProcessed,
+        SeqNumMessageNotFound,
+        TransportNotAvailable,
+        UnsupportedBackupVersion,
+        UnsupportedPayloadCodec,
+    },
+};
+use iota_streams_ddml::types::{
+    NBytes,
+    U64,
+};
+
+/// Author of a single-branch channel announced with the given seed, along with the link and the
+/// message of the announcement.
+fn announced_channel(seed: &str) -> (User<()>, Address, Message) {
+    let mut author = User::new(seed, ChannelType::SingleBranch, ());
+    author.user.create_channel(0).unwrap();
+    let announcement = author.user.announce().unwrap();
+    let ann_link = author
+        .user
+        .commit_wrapped(announcement.wrapped, MsgInfo::Announce)
+        .unwrap();
+    (author, ann_link, announcement.message)
+}
+
+/// Reader of the channel which processed the announcement.
+fn reader_of(seed: &str, announcement: &Message) -> User<()> {
+    let mut reader = User::new(seed, ChannelType::SingleBranch, ());
+    reader
+        .user
+        .handle_announcement(announcement.clone(), MsgInfo::Announce)
+        .unwrap();
+    reader
+}
+
+#[test]
+fn clone_is_deep_copy() {
+    let mut user = User::new("CLONETESTSEED", ChannelType::SingleBranch, ());
+    user.user.create_channel(0).unwrap();
+    let clone = user.clone();
+    let export = |user: &User<()>| user.user.export_with_salt(0, "password", &NBytes::default()).unwrap();
+    let backup = export(&user);
+    assert_eq!(export(&clone), backup);
+
+    let psk = psk::psk_from_seed::<DefaultF>(b"clone test psk");
+    user.store_psk(psk::pskid_from_psk::<DefaultF>(&psk), psk, false)
+        .unwrap();
+    assert_ne!(export(&user), backup);
+    assert_eq!(export(&clone), backup);
+}
+
+#[test]
+fn backups_are_salted() {
+    let mut user = User::new("SALTTESTSEED", ChannelType::SingleBranch, ());
+    user.user.create_channel(0).unwrap();
+    let first = user.export(0, "password").unwrap();
+    let second = user.export(0, "password").unwrap();
+    assert_ne!(first, second);
+    for backup in [&first, &second].iter() {
+        let restored = User::import(backup, 0, "password", ()).unwrap();
+        assert_eq!(restored.get_public_key(), user.get_public_key());
+        assert!(User::import(backup, 0, "passw0rd", ()).is_err());
+    }
+}
+
+#[test]
+fn backup_mac_length_is_stored_in_header() {
+    let mut user = User::new("MACLENTESTSEED", ChannelType::SingleBranch, ());
+    user.user.create_channel(0).unwrap();
+    user.set_backup_mac_len(16).unwrap();
+    let backup = user.export(0, "password").unwrap();
+
+    // The length is read from the header, or checked against it when given
+    let restored = User::import(&backup, 0, "password", ()).unwrap();
+    assert_eq!(restored.get_public_key(), user.get_public_key());
+    User::import_with_mac_len(&backup, 0, "password", 16, ()).unwrap();
+    let err = User::import_with_mac_len(&backup, 0, "password", 32, ()).err().unwrap();
+    assert!(matches!(
+        err.downcast_ref::<Errors>(),
+        Some(BackupMacLengthMismatch(32, 16))
+    ));
+    assert!(User::<()>::verify_backup_with_mac_len(&backup, 0, "password", 32).is_err());
+
+    // The length follows the version, flag and salt and is covered by the MAC
+    let mut tampered = backup.clone();
+    tampered[18] = 32;
+    assert!(User::import(&tampered, 0, "password", ()).is_err());
+}
+
+#[test]
+fn backup_of_later_version_is_rejected() {
+    let mut user = User::new("VERSIONTESTSEED", ChannelType::SingleBranch, ());
+    user.user.create_channel(0).unwrap();
+    let mut backup = user.export(0, "password").unwrap();
+    let supported = backup[0];
+    backup[0] = supported + 1;
+    let err = User::import(&backup, 0, "password", ()).err().unwrap();
+    assert!(matches!(
+        err.downcast_ref::<Errors>(),
+        Some(UnsupportedBackupVersion(found, max)) if *found == supported + 1 && *max == supported
+    ));
+    assert!(User::<()>::verify_backup(&backup, 0, "password").is_err());
+
+    // The version is covered by the MAC, the backup can't be read as an earlier version
+    backup[0] = supported - 1;
+    assert!(User::import(&backup, 0, "password", ()).is_err());
+}
+
+#[test]
+fn restore_discards_changes() {
+    let mut user = User::new("CHECKPOINTTESTSEED", ChannelType::SingleBranch, ());
+    user.user.create_channel(0).unwrap();
+    let export = |user: &User<()>| user.user.export_with_salt(0, "password", &NBytes::default()).unwrap();
+    let backup = export(&user);
+    let checkpoint = user.checkpoint();
+
+    let psk = psk::psk_from_seed::<DefaultF>(b"checkpoint test psk");
+    user.store_psk(psk::pskid_from_psk::<DefaultF>(&psk), psk, false)
+        .unwrap();
+    user.stash_channel().unwrap();
+    assert_eq!(user.stashed_channels().len(), 1);
+
+    user.restore(checkpoint);
+    assert!(user.stashed_channels().is_empty());
+    assert_eq!(export(&user), backup);
+}
+
+#[test]
+fn stored_psk_ids() {
+    let mut user = User::new("PSKIDSTESTSEED", ChannelType::SingleBranch, ());
+    user.user.create_channel(0).unwrap();
+    assert!(user.psk_ids().is_empty());
+
+    let mut pskids = Vec::new();
+    for seed in [&b"first psk"[..], &b"second psk"[..]].iter() {
+        let psk = psk::psk_from_seed::<DefaultF>(seed);
+        let pskid = psk::pskid_from_psk::<DefaultF>(&psk);
+        user.store_psk(pskid, psk, false).unwrap();
+        pskids.push(pskid);
+    }
+    pskids.sort();
+    assert_eq!(user.psk_ids(), pskids);
+}
+
+#[test]
+fn debug_redacts_secrets() {
+    let mut user = User::new("DEBUGTESTSEED", ChannelType::SingleBranch, ());
+    user.user.create_channel(0).unwrap();
+    let formatted = format!("{:?}", user);
+    assert!(formatted.contains("<redacted>"));
+
+    let sig_sk = user.user.sig_kp.secret.to_bytes();
+    let ke_sk = user.user.ke_kp.0.to_bytes();
+    for secret in [&sig_sk[..], &ke_sk[..]].iter() {
+        assert!(!formatted.contains(&hex::encode(secret)));
+        assert!(!formatted.contains(&format!("{:?}", secret)));
+    }
+}
+
+#[test]
+fn conflicting_message_at_processed_link_is_rejected() {
+    let (mut author, ann_link, announcement) = announced_channel("REPLAYAUTHORSEED");
+
+    let mut subscriber = reader_of("REPLAYSUBSCRIBERSEED", &announcement);
+
+    // Two different packets wrapped from the same author state get the same link
+    let checkpoint = author.checkpoint();
+    let original = author
+        .user
+        .sign_packet(&ann_link, &Bytes(b"original".to_vec()), &Bytes::default())
+        .unwrap();
+    author.restore(checkpoint);
+    let forged = author
+        .user
+        .sign_packet(&ann_link, &Bytes(b"forged".to_vec()), &Bytes::default())
+        .unwrap();
+    assert_eq!(original.message.link, forged.message.link);
+
+    subscriber
+        .user
+        .handle_signed_packet(original.message.clone(), MsgInfo::SignedPacket)
+        .unwrap();
+    // Processing the same message again is allowed
+    subscriber
+        .user
+        .handle_signed_packet(original.message, MsgInfo::SignedPacket)
+        .unwrap();
+    assert!(subscriber
+        .user
+        .handle_signed_packet(forged.message, MsgInfo::SignedPacket)
+        .is_err());
+}
+
+#[test]
+fn deterministic_keyload_is_byte_identical() {
+    let (mut author, ann_link, announcement) = announced_channel("DETERMINISTICAUTHORSEED");
+
+    let mut subscriber = User::new("DETERMINISTICSUBSCRIBERSEED", ChannelType::SingleBranch, ());
+    subscriber.set_deterministic_seed(Some("deterministic test"));
+    subscriber
+        .user
+        .handle_announcement(announcement, MsgInfo::Announce)
+        .unwrap();
+    let checkpoint = subscriber.checkpoint();
+    let subscription = subscriber.user.subscribe(&ann_link).unwrap();
+    subscriber.restore(checkpoint);
+    assert_eq!(
+        subscription.message.body,
+        subscriber.user.subscribe(&ann_link).unwrap().message.body
+    );
+    author
+        .user
+        .handle_subscribe(subscription.message, MsgInfo::Subscribe)
+        .unwrap();
+
+    let checkpoint = author.checkpoint();
+    let random = author.user.share_keyload_for_everyone(&ann_link).unwrap();
+    author.restore(checkpoint.clone());
+    assert_ne!(
+        random.message.body,
+        author.user.share_keyload_for_everyone(&ann_link).unwrap().message.body
+    );
+
+    author.restore(checkpoint);
+    author.set_deterministic_seed(Some("deterministic test"));
+    let checkpoint = author.checkpoint();
+    let keyload = author.user.share_keyload_for_everyone(&ann_link).unwrap();
+    author.restore(checkpoint.clone());
+    assert_eq!(
+        keyload.message.body,
+        author.user.share_keyload_for_everyone(&ann_link).unwrap().message.body
+    );
+    subscriber
+        .user
+        .handle_keyload(keyload.message.clone(), MsgInfo::Keyload)
+        .unwrap();
+
+    // Secrets are derived from the recipients too: a keyload for others at the same link has a
+    // different nonce right after the common header, just as a randomly generated one
+    author.restore(checkpoint);
+    let others = author.user.share_keyload(&ann_link, &Vec::new(), &Vec::new()).unwrap();
+    assert_eq!(others.message.link, keyload.message.link);
+    let common_prefix = |a: &Vec<u8>, b: &Vec<u8>| a.iter().zip(b.iter()).take_while(|(a, b)| a == b).count();
+    let header_len = common_prefix(&random.message.body.bytes, &keyload.message.body.bytes);
+    assert!(common_prefix(&others.message.body.bytes, &keyload.message.body.bytes) < header_len + 16);
+}
+
+#[test]
+fn peek_header_of_raw_message() {
+    let (mut author, ann_link, _) = announced_channel("PEEKHEADERSEED");
+    let packet = author
+        .user
+        .sign_packet(&ann_link, &Bytes(b"public".to_vec()), &Bytes::default())
+        .unwrap();
+
+    let header = peek_header(packet.message.body.as_bytes()).unwrap();
+    assert_eq!(header.content_type, message::SIGNED_PACKET);
+    assert_eq!(header.linked_msg_address, ann_link);
+    assert!(header.sender_id == Identifier::EdPubKey(author.user.sig_kp.public.into()));
+    assert_eq!(header.seq_num, packet.message.peek_header().unwrap().seq_num);
+    assert!(peek_header(&packet.message.body.as_bytes()[..4]).is_err());
+}
+
+#[test]
+fn classify_raw_messages() {
+    let mut author = User::new("CLASSIFYSEED", ChannelType::SingleBranch, ());
+    author.user.create_channel(0).unwrap();
+    let announcement = author.user.announce().unwrap();
+    let class = classify(announcement.message.body.as_bytes()).unwrap();
+    assert_eq!(class.kind, MessageKind::Announce);
+    assert!(class.publisher == Identifier::EdPubKey(author.user.sig_kp.public.into()));
+
+    let ann_link = author
+        .user
+        .commit_wrapped(announcement.wrapped, MsgInfo::Announce)
+        .unwrap();
+    let packet = author
+        .user
+        .sign_packet(&ann_link, &Bytes(b"public".to_vec()), &Bytes::default())
+        .unwrap();
+    let class = classify(packet.message.body.as_bytes()).unwrap();
+    assert_eq!(class.kind, MessageKind::SignedPacket);
+    assert_eq!(class.linked_msg_address, ann_link);
+    assert_eq!(class.seq_num, packet.message.peek_header().unwrap().seq_num);
+
+    assert_eq!(MessageKind::from(0x0f), MessageKind::Unknown(0x0f));
+}
+
+#[test]
+fn keyload_subscriber_cap() {
+    let (mut author, ann_link, _) = announced_channel("KEYLOADCAPAUTHORSEED");
+
+    let subscribers: Vec<Identifier> = ["KEYLOADCAPSUBA", "KEYLOADCAPSUBB", "KEYLOADCAPSUBC"]
+        .iter()
+        .map(|seed| {
+            let subscriber = User::new(seed, ChannelType::SingleBranch, ());
+            let id = Identifier::EdPubKey(subscriber.user.sig_kp.public.into());
+            author.add_subscribers(vec![(id, subscriber.user.ke_kp.1)]).unwrap();
+            id
+        })
+        .collect();
+
+    author.set_max_keyload_subscribers(2);
+    assert!(author.user.share_keyload_for_everyone(&ann_link).is_err());
+    let recipients: Vec<&Identifier> = subscribers[..2].iter().collect();
+    assert!(author.user.share_keyload(&ann_link, &[], &recipients).is_ok());
+    let recipients: Vec<&Identifier> = subscribers.iter().collect();
+    assert!(author.user.share_keyload(&ann_link, &[], &recipients).is_err());
+}
+
+#[test]
+fn split_keyloads_grant_access_to_all_recipients() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("SPLITAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    author.set_max_keyload_subscribers(2);
+
+    let mut subscribers: Vec<Subscriber<_>> = ["SPLITSUBA", "SPLITSUBB", "SPLITSUBC"]
+        .iter()
+        .map(|seed| {
+            let mut subscriber = Subscriber::new(seed, transport.clone());
+            subscriber.receive_announcement(&ann_link).unwrap();
+            let sub_link = subscriber.send_subscribe(&ann_link).unwrap();
+            author.receive_subscribe(&sub_link).unwrap();
+            subscriber
+        })
+        .collect();
+    let ids: Vec<Identifier> = subscribers
+        .iter()
+        .map(|subscriber| Identifier::EdPubKey((*subscriber.get_public_key()).into()))
+        .collect();
+
+    let recipients: Vec<&Identifier> = ids.iter().collect();
+    assert!(author.send_keyload(&ann_link, &vec![], &recipients).is_err());
+    assert!(author.send_keyload_split(&ann_link, &vec![], &recipients, 0).is_err());
+    let keyloads = author.send_keyload_split(&ann_link, &vec![], &recipients, 2).unwrap();
+    assert_eq!(keyloads.len(), 2);
+
+    for subscriber in subscribers[..2].iter_mut() {
+        assert!(subscriber.receive_keyload(&keyloads[0].0).unwrap());
+    }
+    let last = &mut subscribers[2];
+    assert!(!last.receive_keyload(&keyloads[0].0).unwrap());
+    assert!(last.receive_keyload(&keyloads[1].0).unwrap());
+}
+
+#[test]
+fn keyload_for_subscriber_group() {
+    let (mut author, ann_link, announcement) = announced_channel("GROUPAUTHORSEED");
+
+    let mut subscribers: Vec<(Identifier, User<()>)> = ["GROUPSUBA", "GROUPSUBB", "GROUPSUBC"]
+        .iter()
+        .map(|seed| {
+            let mut subscriber = reader_of(seed, &announcement);
+            let id = Identifier::EdPubKey(subscriber.user.sig_kp.public.into());
+            author.add_subscribers(vec![(id, subscriber.user.ke_kp.1)]).unwrap();
+            (id, subscriber)
+        })
+        .collect();
+
+    let unknown = User::new("GROUPUNKNOWN", ChannelType::SingleBranch, ());
+    let unknown = Identifier::EdPubKey(unknown.user.sig_kp.public.into());
+    assert!(author.add_to_group("field", unknown).is_err());
+    author.add_to_group("field", subscribers[0].0).unwrap();
+    author.add_to_group("field", subscribers[1].0).unwrap();
+    author.add_to_group("office", subscribers[2].0).unwrap();
+    assert_eq!(author.subscriber_groups(), vec!["field", "office"]);
+    assert!(author.user.share_keyload_for_group(&ann_link, "lab").is_err());
+
+    let keyload = author.user.share_keyload_for_group(&ann_link, "office").unwrap();
+    let access: Vec<bool> = subscribers
+        .iter_mut()
+        .map(|(_id, subscriber)| {
+            subscriber
+                .user
+                .handle_keyload(keyload.message.clone(), MsgInfo::Keyload)
+                .unwrap()
+                .body
+        })
+        .collect();
+    assert_eq!(access, vec![false, false, true]);
+
+    let restored = User::import(&author.export(0, "pwd").unwrap(), 0, "pwd", ()).unwrap();
+    assert!(restored.group_members("field") == Some(&[subscribers[0].0, subscribers[1].0][..]));
+
+    assert!(author.remove_from_group("office", &subscribers[2].0));
+    assert!(!author.remove_from_group("office", &subscribers[2].0));
+    assert!(author.group_members("office").is_none());
+}
+
+#[test]
+fn announcement_link_is_rederived() {
+    let mut author = User::new("REDERIVEAUTHORSEED", ChannelType::SingleBranch, ());
+    author.user.create_channel(7).unwrap();
+    let announcement = author.user.announce().unwrap();
+    let ann_link = author
+        .user
+        .commit_wrapped(announcement.wrapped, MsgInfo::Announce)
+        .unwrap();
+    assert_eq!(ann_link, announcement_link(author.get_public_key(), 7));
+    assert_ne!(ann_link, announcement_link(author.get_public_key(), 0));
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn resume_from_saved_position() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("POSITIONAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let mut subscriber = Subscriber::new("POSITIONSUBSCRIBERSEED", transport);
+    let mut link = author.send_announce().unwrap();
+    subscriber.receive_announcement(&link).unwrap();
+    for i in 0_u8..3 {
+        link = author
+            .send_signed_packet(&link, &Bytes(vec![i]), &Bytes::default())
+            .unwrap()
+            .0;
+    }
+
+    let first = subscriber.fetch_next_msgs_with_positions();
+    assert_eq!(first.len(), 1);
+    let saved = first[0].1.to_bytes().unwrap();
+    let rest: Vec<UnwrappedMessage> = (0..2).flat_map(|_| subscriber.fetch_next_msgs()).collect();
+    assert_eq!(rest.len(), 2);
+    assert!(subscriber.fetch_next_msgs().is_empty());
+
+    subscriber.resume_from(&Position::from_bytes(&saved).unwrap()).unwrap();
+    let resumed = subscriber.fetch_next_msgs();
+    assert_eq!(resumed.len(), 1);
+    assert_eq!(resumed[0].link, rest[0].link);
+    assert!(Position::from_bytes(&saved[..saved.len() - 1]).is_err());
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn heads_of_publishers() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("HEADSAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let mut subscriber = Subscriber::new("HEADSSUBSCRIBERSEED", transport);
+    let mut link = author.send_announce().unwrap();
+    subscriber.receive_announcement(&link).unwrap();
+    assert!(subscriber.heads().unwrap().is_empty());
+
+    for i in 0_u8..2 {
+        link = author
+            .send_signed_packet(&link, &Bytes(vec![i]), &Bytes::default())
+            .unwrap()
+            .0;
+    }
+    while !subscriber.fetch_next_msgs().is_empty() {}
+    let author_id = Identifier::EdPubKey((*author.get_public_key()).into());
+    assert!(subscriber.heads().unwrap() == vec![(author_id, link)]);
+}
+
+#[test]
+fn keyload_access_with_identity_and_psk() {
+    let (mut author, ann_link, announcement) = announced_channel("KEYLOADPATHSAUTHORSEED");
+
+    let psk = psk::psk_from_seed::<DefaultF>(b"keyload paths psk");
+    let pskid = psk::pskid_from_psk::<DefaultF>(&psk);
+    author.store_psk(pskid, psk, false).unwrap();
+    let mut subscriber = reader_of("KEYLOADPATHSSUBSCRIBERSEED", &announcement);
+    subscriber.store_psk(pskid, psk, false).unwrap();
+    let id = Identifier::EdPubKey(subscriber.user.sig_kp.public.into());
+    author.add_subscribers(vec![(id, subscriber.user.ke_kp.1)]).unwrap();
+
+    let mut access = |psk_ids: &PskIds, ids: Vec<&Identifier>| {
+        let keyload = author.user.share_keyload(&ann_link, psk_ids, &ids).unwrap();
+        // Keyloads are not committed and share the link, each one is handled by a fresh copy
+        let mut subscriber = subscriber.clone();
+        let granted = subscriber
+            .user
+            .handle_keyload(keyload.message.clone(), MsgInfo::Keyload)
+            .unwrap()
+            .body;
+        let access = subscriber.keyload_access(&keyload.message.link.msgid);
+        assert_eq!(access.is_granted(), granted);
+        access
+    };
+    assert_eq!(access(&[], vec![&id]), KeyloadAccess::Identity);
+    assert_eq!(access(&[pskid], vec![]), KeyloadAccess::Psk(pskid));
+    assert_eq!(access(&[pskid], vec![&id]), KeyloadAccess::Psk(pskid));
+    assert_eq!(access(&[], vec![]), KeyloadAccess::Denied);
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn keyload_linked_to_unprocessed_message_is_retried() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("KEYLOADRETRYAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let psk = psk::psk_from_seed::<DefaultF>(b"KEYLOADRETRYSECRET");
+    let pskid = psk::pskid_from_psk::<DefaultF>(&psk);
+    let ann_link = author.send_announce().unwrap();
+    author.store_psk(pskid, psk).unwrap();
+    let (packet_link, _) = author
+        .send_signed_packet(&ann_link, &Bytes(b"public".to_vec()), &Bytes::default())
+        .unwrap();
+    let (keyload_link, _) = author.send_keyload(&packet_link, &vec![pskid], &vec![]).unwrap();
+
+    let mut subscriber = Subscriber::new("KEYLOADRETRYSUBSCRIBERSEED", transport);
+    subscriber.receive_announcement(&ann_link).unwrap();
+    subscriber.store_psk(pskid, psk).unwrap();
+    // The keyload is neither denied nor tracked before its parent is processed
+    assert!(subscriber.receive_keyload(&keyload_link).is_err());
+    subscriber.receive_signed_packet(&packet_link).unwrap();
+    assert!(subscriber.receive_keyload(&keyload_link).unwrap());
+}
+
+#[test]
+fn announcement_carries_metadata() {
+    let mut author = User::new("ANNMETADATASEED", ChannelType::SingleBranch, ());
+    author.user.create_channel_with_metadata(0, b"schema v2").unwrap();
+    let announcement = author.user.announce().unwrap();
+    assert!(announcement
+        .message
+        .parse_header()
+        .unwrap()
+        .header
+        .has_content_metadata());
+    let subscriber = reader_of("ANNMETADATASUBSCRIBERSEED", &announcement.message);
+    assert_eq!(subscriber.announcement_metadata(), Some(&b"schema v2"[..]));
+
+    // Announcements without metadata are encoded as before
+    let mut author = User::new("ANNMETADATASEED", ChannelType::SingleBranch, ());
+    author.user.create_channel(1).unwrap();
+    let announcement = author.user.announce().unwrap();
+    assert!(!announcement
+        .message
+        .parse_header()
+        .unwrap()
+        .header
+        .has_content_metadata());
+    let subscriber = reader_of("ANNMETADATASUBSCRIBERSEED", &announcement.message);
+    assert_eq!(subscriber.announcement_metadata(), None);
+}
+
+#[test]
+fn announcement_bound_to_context() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("ANNCONTEXTAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let plain_link = author.send_announce().unwrap();
+    author.new_channel_with_context(1, b"shared secret").unwrap();
+    let ann_link = author.send_announce().unwrap();
+
+    let mut outsider = Subscriber::new("ANNCONTEXTOUTSIDERSEED", transport.clone());
+    assert!(outsider.peek_announcement(&ann_link).is_err());
+    assert!(outsider.receive_announcement(&ann_link).is_err());
+    outsider.set_announcement_context(Some(b"guessed secret"));
+    assert!(outsider.receive_announcement(&ann_link).is_err());
+    assert!(outsider.receive_announcement(&plain_link).is_err());
+    assert!(!outsider.is_registered());
+
+    let mut subscriber = Subscriber::new("ANNCONTEXTSUBSCRIBERSEED", transport.clone());
+    subscriber.set_announcement_context(Some(b"shared secret"));
+    let info = subscriber.peek_announcement(&ann_link).unwrap();
+    assert_eq!(&info.author_sig_pk, author.get_public_key());
+    subscriber.receive_announcement(&ann_link).unwrap();
+    let (packet_link, _) = author
+        .send_signed_packet(&ann_link, &Bytes(b"public".to_vec()), &Bytes::default())
+        .unwrap();
+    subscriber.receive_signed_packet(&packet_link).unwrap();
+}
+
+#[test]
+fn peek_announcement_does_not_bind() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("PEEKANNAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let first_link = author.send_announce().unwrap();
+    author.new_channel_with_metadata(1, b"schema v2").unwrap();
+    let second_link = author.send_announce().unwrap();
+
+    let mut subscriber = Subscriber::new("PEEKANNSUBSCRIBERSEED", transport.clone());
+    subscriber.receive_announcement(&first_link).unwrap();
+    let stored = subscriber.spongos_store_len();
+
+    let info = subscriber.peek_announcement(&second_link).unwrap();
+    assert_eq!(&info.author_sig_pk, author.get_public_key());
+    assert_eq!(info.author_ke_pk.as_bytes(), author.user.user.ke_kp.1.as_bytes());
+    assert_eq!(info.metadata, Some(Bytes(b"schema v2".to_vec())));
+
+    assert_eq!(subscriber.channel_address(), Some(&first_link.appinst));
+    assert_eq!(subscriber.spongos_store_len(), stored);
+    assert_eq!(subscriber.announcement_metadata(), None);
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn bulk_signed_packets_are_chained() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("BULKAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let mut subscriber = Subscriber::new("BULKSUBSCRIBERSEED", transport);
+    let ann_link = author.send_announce().unwrap();
+    subscriber.receive_announcement(&ann_link).unwrap();
+
+    let payloads = (0_u8..3).map(|i| (Bytes(vec![i]), Bytes(vec![i + 10])));
+    let links = author.send_signed_packets(&ann_link, payloads).unwrap();
+    assert_eq!(links.len(), 3);
+
+    let msgs: Vec<UnwrappedMessage> = (0..3).flat_map(|_| subscriber.fetch_next_msgs()).collect();
+    assert_eq!(msgs.len(), 3);
+    let mut prev_link = ann_link;
+    for (i, (msg, (link, _))) in msgs.iter().zip(links.iter()).enumerate() {
+        assert_eq!(&msg.link, link);
+        assert_eq!(msg.prev_link, prev_link);
+        match &msg.body {
+            MessageContent::SignedPacket { public_payload, .. } => assert_eq!(public_payload.0, vec![i as u8]),
+            _ => panic!("expected signed packet"),
+        }
+        prev_link = link.clone();
+    }
+}
+
+#[cfg(all(feature = "deflate", not(feature = "async")))]
+#[test]
+fn compressed_payloads_are_signalled() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("COMPRESSIONAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    author.set_compressor(Some(Deflate::default()));
+    let ann_link = author.send_announce().unwrap();
+    let mut with_compressor = Subscriber::new("COMPRESSIONSUBSCRIBERSEED", transport.clone());
+    with_compressor.set_compressor(Some(Deflate::default()));
+    with_compressor.receive_announcement(&ann_link).unwrap();
+    let mut without_compressor = Subscriber::new("NOCOMPRESSIONSUBSCRIBERSEED", transport.clone());
+    without_compressor.receive_announcement(&ann_link).unwrap();
+
+    let short = Bytes(b"21".to_vec());
+    let (short_link, _) = author.send_signed_packet(&ann_link, &short, &Bytes::default()).unwrap();
+    let long = Bytes(b"{\"sensor\": \"temperature\"}, ".repeat(16));
+    let (long_link, _) = author
+        .send_signed_packet(&short_link, &long, &Bytes::default())
+        .unwrap();
+    let header = |link: &Address| {
+        transport
+            .borrow_mut()
+            .recv_message(link)
+            .unwrap()
+            .binary
+            .parse_header()
+            .unwrap()
+            .header
+    };
+    assert!(!header(&short_link).has_content_compressed_payload());
+    assert!(header(&long_link).has_content_compressed_payload());
+
+    // Uncompressed payloads are read by any receiver, compressed ones need the compressor
+    for subscriber in [&mut with_compressor, &mut without_compressor].iter_mut() {
+        let (_, public, _) = subscriber.receive_signed_packet(&short_link).unwrap();
+        assert_eq!(public, short);
+    }
+    let (_, public, _) = with_compressor.receive_signed_packet(&long_link).unwrap();
+    assert_eq!(public, long);
+    let err = without_compressor.receive_signed_packet(&long_link).err().unwrap();
+    assert!(matches!(err.downcast_ref::<Errors>(), Some(UnsupportedPayloadCodec(_))));
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn failed_transaction_publishes_nothing() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("TRANSACTIONAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let mut subscriber = Subscriber::new("TRANSACTIONSUBSCRIBERSEED", transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    subscriber.receive_announcement(&ann_link).unwrap();
+
+    let payload = Bytes(b"packet".to_vec());
+    let err = author
+        .transaction(|author| -> Result<()> {
+            let (link, _) = author.send_signed_packet(&ann_link, &payload, &Bytes::default())?;
+            assert!(transport.borrow_mut().recv_message(&link).is_err());
+            err!(TransportNotAvailable)
+        })
+        .err()
+        .unwrap();
+    assert!(matches!(err.downcast_ref::<Errors>(), Some(TransportNotAvailable)));
+
+    // Nothing was published, sending again reuses the address of the dropped packet
+    let (link, _) = author
+        .send_signed_packet(&ann_link, &payload, &Bytes::default())
+        .unwrap();
+    let (_, public, _) = subscriber.receive_signed_packet(&link).unwrap();
+    assert_eq!(public, payload);
+
+    let links = author
+        .transaction(|author| {
+            let (first, _) = author.send_signed_packet(&link, &payload, &Bytes::default())?;
+            let (second, _) = author.send_signed_packet(&first, &payload, &Bytes::default())?;
+            Ok(vec![first, second])
+        })
+        .unwrap();
+    for link in &links {
+        assert!(subscriber.receive_signed_packet(link).is_ok());
+    }
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn users_over_boxed_transport() {
+    let transport: BoxedTransport =
+        iota_streams_app::transport::boxed::boxed(Rc::new(RefCell::new(BucketTransport::new())));
+    let mut author = Author::new("BOXEDTRANSPORTAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    let (packet_link, _) = author
+        .send_signed_packet(&ann_link, &Bytes(b"public".to_vec()), &Bytes::default())
+        .unwrap();
+
+    let mut subscriber = Subscriber::new("BOXEDTRANSPORTSUBSCRIBERSEED", transport);
+    subscriber.receive_announcement(&ann_link).unwrap();
+    let (_, public, _) = subscriber.receive_signed_packet(&packet_link).unwrap();
+    assert_eq!(public.0, b"public".to_vec());
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn next_address_matches_sent_message() {
+    for channel_type in [ChannelType::SingleBranch, ChannelType::MultiBranch] {
+        let transport = Rc::new(RefCell::new(BucketTransport::new()));
+        let mut author = Author::new("NEXTADDRESSAUTHORSEED", channel_type, transport.clone());
+        let ann_link = author.send_announce().unwrap();
+        let mut subscriber = Subscriber::new("NEXTADDRESSSUBSCRIBERSEED", transport);
+        subscriber.receive_announcement(&ann_link).unwrap();
+
+        let id = Identifier::EdPubKey((*author.get_public_key()).into());
+        let expected = author.next_address(&id).unwrap();
+        assert_eq!(subscriber.next_address(&id).unwrap(), expected);
+        let (msg_link, seq_link) = author
+            .send_signed_packet(&ann_link, &Bytes(b"public".to_vec()), &Bytes::default())
+            .unwrap();
+        assert_eq!(seq_link.unwrap_or(msg_link), expected);
+    }
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn receive_message_at_follows_publisher_chain() {
+    for channel_type in [ChannelType::SingleBranch, ChannelType::MultiBranch] {
+        let transport = Rc::new(RefCell::new(BucketTransport::new()));
+        let mut author = Author::new("MESSAGEATAUTHORSEED", channel_type, transport.clone());
+        let ann_link = author.send_announce().unwrap();
+        let mut subscriber = Subscriber::new("MESSAGEATSUBSCRIBERSEED", transport);
+        subscriber.receive_announcement(&ann_link).unwrap();
+
+        let author_pk = hex::encode(author.get_public_key().as_bytes());
+        let (_, first) = subscriber
+            .fetch_state()
+            .unwrap()
+            .into_iter()
+            .find(|(pk, _)| pk == &author_pk)
+            .unwrap();
+        let mut link_to = ann_link;
+        for i in 0_u8..3 {
+            let (msg_link, _) = author
+                .send_signed_packet(&link_to, &Bytes(vec![i]), &Bytes::default())
+                .unwrap();
+            link_to = msg_link;
+        }
+
+        // The intermediate messages are returned along with the requested one
+        let author_id = Identifier::EdPubKey((*author.get_public_key()).into());
+        let first = first.seq_no as usize;
+        let msgs = subscriber.receive_msg_at(&author_id, first + 2).unwrap();
+        assert_eq!(msgs.last().unwrap().link, link_to);
+        let payloads: Vec<Vec<u8>> = msgs
+            .iter()
+            .map(|msg| match &msg.body {
+                MessageContent::SignedPacket { public_payload, .. } => public_payload.0.clone(),
+                _ => panic!("expected signed packet"),
+            })
+            .collect();
+        assert_eq!(payloads, vec![vec![0], vec![1], vec![2]]);
+
+        let err = subscriber.receive_msg_at(&author_id, first + 1).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<Errors>(),
+            Some(SeqNumAlreadyProcessed(_, _))
+        ));
+        let err = subscriber.receive_msg_at(&author_id, first + 3).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<Errors>(),
+            Some(SeqNumMessageNotFound(_, _))
+        ));
+    }
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn message_from_unexpected_publisher_is_rejected() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("PUBLISHERAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    let mut subscriber = Subscriber::new("PUBLISHERSUBSCRIBERSEED", transport);
+    subscriber.receive_announcement(&ann_link).unwrap();
+    let (packet_link, _) = author
+        .send_signed_packet(&ann_link, &Bytes(b"public".to_vec()), &Bytes::default())
+        .unwrap();
+
+    let author_id = Identifier::EdPubKey((*author.get_public_key()).into());
+    let other_id = Identifier::EdPubKey((*subscriber.get_public_key()).into());
+    let next = subscriber.next_address(&author_id).unwrap();
+    assert!(subscriber.receive_msg_from(&packet_link, &other_id).is_err());
+    assert_eq!(subscriber.next_address(&author_id).unwrap(), next);
+
+    subscriber.receive_msg_from(&packet_link, &author_id).unwrap();
+    assert_ne!(subscriber.next_address(&author_id).unwrap(), next);
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn messages_are_returned_newest_first() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("REVERSEAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    let mut subscriber = Subscriber::new("REVERSESUBSCRIBERSEED", transport);
+    subscriber.receive_announcement(&ann_link).unwrap();
+
+    let mut sent = Vec::new();
+    let mut link = ann_link;
+    for i in 0..3_u8 {
+        let (msg_link, _) = author
+            .send_signed_packet(&link, &Bytes::default(), &Bytes(vec![i]))
+            .unwrap();
+        sent.push(msg_link.clone());
+        link = msg_link;
+    }
+
+    let msgs = subscriber.messages_reverse();
+    let links: Vec<Address> = msgs.iter().map(|msg| msg.link.clone()).collect();
+    sent.reverse();
+    assert_eq!(links, sent);
+    for (i, msg) in msgs.iter().enumerate() {
+        match &msg.body {
+            MessageContent::SignedPacket { masked_payload, .. } => assert_eq!(masked_payload.0, vec![2 - i as u8]),
+            _ => panic!("expected signed packet"),
+        }
+    }
+    assert!(subscriber.messages_reverse().is_empty());
+}
+
+#[test]
+fn inconsistent_backup_is_rejected() {
+    use crate::api::key_store::KeyStore as _;
+
+    let mut author = User::new("CORRUPTBACKUPAUTHORSEED", ChannelType::SingleBranch, ());
+    author.user.create_channel(0).unwrap();
+    let exported = author.export(0, "pwd").unwrap();
+    assert!(User::import(&exported, 0, "pwd", ()).is_ok());
+
+    // Cursors without a channel can't be produced by processing messages
+    let mut user = User::new("CORRUPTBACKUPUSERSEED", ChannelType::SingleBranch, ());
+    let cursor = Cursor::new_at(author.user.appinst.clone().unwrap().rel().clone(), 0, 2);
+    user.user
+        .key_store
+        .insert_cursor(Identifier::EdPubKey(user.user.sig_kp.public.into()), cursor)
+        .unwrap();
+    let exported = user.export(0, "pwd").unwrap();
+    assert!(User::import(&exported, 0, "pwd", ()).is_err());
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn subscription_payload_is_read_by_author() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("SUBPAYLOADAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+
+    let mut subscriber = Subscriber::new("SUBPAYLOADSUBSCRIBERSEED", transport.clone());
+    subscriber.receive_announcement(&ann_link).unwrap();
+    let payload = Bytes(b"display name".to_vec());
+    let sub_link = subscriber.send_subscribe_with_payload(&ann_link, &payload).unwrap();
+    let (id, received) = author.receive_subscribe_with_payload(&sub_link).unwrap();
+    assert!(id == Identifier::from(*subscriber.get_public_key()));
+    assert_eq!(received, payload);
+
+    let mut plain = Subscriber::new("SUBPAYLOADPLAINSEED", transport.clone());
+    plain.receive_announcement(&ann_link).unwrap();
+    let plain_sub_link = plain.send_subscribe(&ann_link).unwrap();
+    let (_, received) = author.receive_subscribe_with_payload(&plain_sub_link).unwrap();
+    assert_eq!(received, Bytes::default());
+
+    // The payload is signalled in the content flags byte of a later header version, plain
+    // subscriptions are still encoded as before
+    let version = |link: &Address| {
+        let msg = transport.borrow_mut().recv_message(link).unwrap();
+        msg.binary.body.as_bytes()[1]
+    };
+    assert_eq!(version(&sub_link), iota_streams_app::message::STREAMS_2_VER.0);
+    assert_eq!(version(&plain_sub_link), iota_streams_app::message::STREAMS_1_VER.0);
+
+    let (keyload_link, _) = author.send_keyload_for_everyone(&ann_link).unwrap();
+    assert!(subscriber.receive_keyload(&keyload_link).unwrap());
+    assert!(plain.receive_keyload(&keyload_link).unwrap());
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn packets_carry_app_type() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("APPTYPEAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    let mut subscriber = Subscriber::new("APPTYPESUBSCRIBERSEED", transport.clone());
+    subscriber.receive_announcement(&ann_link).unwrap();
+
+    let payload = Bytes(b"payload".to_vec());
+    let ((signed_link, _), _) = author
+        .send_signed_packet_with(&ann_link, &payload, &payload, &PacketOptions::new().with_app_type(7))
+        .unwrap();
+    let (tagged_link, _) = author
+        .send_tagged_packet_with_app_type(&signed_link, &payload, &payload, 0x1234)
+        .unwrap();
+    let (plain_link, _) = author.send_signed_packet(&tagged_link, &payload, &payload).unwrap();
+
+    // The application type is signalled in the content flags byte of a later header version
+    let version = |link: &Address| {
+        let msg = transport.borrow_mut().recv_message(link).unwrap();
+        msg.binary.body.as_bytes()[1]
+    };
+    assert_eq!(version(&signed_link), iota_streams_app::message::STREAMS_2_VER.0);
+    assert_eq!(version(&tagged_link), iota_streams_app::message::STREAMS_2_VER.0);
+    assert_eq!(version(&plain_link), iota_streams_app::message::STREAMS_1_VER.0);
+
+    let msgs = subscriber.fetch_next_msgs();
+    let app_types: Vec<u16> = msgs.iter().map(|msg| msg.body.app_type()).collect();
+    assert_eq!(app_types, vec![7, 0x1234, 0]);
+    for msg in &msgs {
+        match &msg.body {
+            MessageContent::SignedPacket { masked_payload, .. } => assert_eq!(masked_payload, &payload),
+            MessageContent::TaggedPacket { masked_payload, .. } => assert_eq!(masked_payload, &payload),
+            _ => panic!("expected packet"),
+        }
+    }
+}
+
+/// Transport storing messages in a shared bucket, which never confirms them.
+#[derive(Clone)]
+struct UnconfirmedTransport(Rc<RefCell<BucketTransport>>);
+
+impl transport::TransportOptions for UnconfirmedTransport {
+    type SendOptions = ();
+    fn get_send_options(&self) {}
+    fn set_send_options(&mut self, _opt: ()) {}
+
+    type RecvOptions = ();
+    fn get_recv_options(&self) {}
+    fn set_recv_options(&mut self, _opt: ()) {}
+}
+
+#[cfg(not(feature = "async"))]
+impl transport::TransportDetails<Address> for UnconfirmedTransport {
+    type Details = ();
+    fn get_link_details(&mut self, _link: &Address) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl transport::Transport<Address, Message> for UnconfirmedTransport {
+    fn send_message(&mut self, msg: &Message) -> Result<()> {
+        self.0.send_message(msg)
+    }
+
+    fn recv_messages(&mut self, link: &Address) -> Result<Vec<Message>> {
+        self.0.recv_messages(link)
+    }
+
+    fn await_confirmation(&mut self, _link: &Address) -> Result<()> {
+        err!(TransportNotAvailable)
+    }
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn unconfirmed_packet_does_not_advance_state() {
+    let shared = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("CONFIRMAUTHORSEED", ChannelType::SingleBranch, shared.clone());
+    let ann_link = author.send_announce().unwrap();
+    let confirmed = PacketOptions::new().with_confirmation(true);
+    author
+        .send_signed_packet_with(&ann_link, &Bytes(b"public".to_vec()), &Bytes::default(), &confirmed)
+        .unwrap();
+
+    let mut author = Author::new(
+        "UNCONFIRMEDAUTHORSEED",
+        ChannelType::SingleBranch,
+        UnconfirmedTransport(shared),
+    );
+    let ann_link = author.send_announce().unwrap();
+    let id = Identifier::EdPubKey((*author.get_public_key()).into());
+    let next = author.next_address(&id).unwrap();
+    assert!(author
+        .send_signed_packet_with(&ann_link, &Bytes(b"public".to_vec()), &Bytes::default(), &confirmed)
+        .is_err());
+    assert_eq!(author.next_address(&id).unwrap(), next);
+}
+
+/// Transport storing messages in a shared bucket, which fails once its send budget is spent.
+#[derive(Clone)]
+struct FailingSendTransport {
+    bucket: Rc<RefCell<BucketTransport>>,
+    sends_left: usize,
+}
+
+impl transport::TransportOptions for FailingSendTransport {
+    type SendOptions = ();
+    fn get_send_options(&self) {}
+    fn set_send_options(&mut self, _opt: ()) {}
+
+    type RecvOptions = ();
+    fn get_recv_options(&self) {}
+    fn set_recv_options(&mut self, _opt: ()) {}
+}
+
+#[cfg(not(feature = "async"))]
+impl transport::TransportDetails<Address> for FailingSendTransport {
+    type Details = ();
+    fn get_link_details(&mut self, _link: &Address) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl transport::Transport<Address, Message> for FailingSendTransport {
+    fn send_message(&mut self, msg: &Message) -> Result<()> {
+        try_or!(self.sends_left > 0, TransportNotAvailable)?;
+        self.sends_left -= 1;
+        self.bucket.send_message(msg)
+    }
+
+    fn recv_messages(&mut self, link: &Address) -> Result<Vec<Message>> {
+        self.bucket.recv_messages(link)
+    }
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn failed_keyload_after_announcement_restores_state() {
+    let transport = FailingSendTransport {
+        bucket: Rc::new(RefCell::new(BucketTransport::new())),
+        sends_left: 1,
+    };
+    let mut author = Author::new("ROLLBACKAUTHORSEED", ChannelType::SingleBranch, transport);
+    let export = |author: &Author<FailingSendTransport>| {
+        author
+            .user
+            .user
+            .export_with_salt(0, "password", &NBytes::default())
+            .unwrap()
+    };
+    let backup = export(&author);
+    let id = Identifier::EdPubKey((*author.get_public_key()).into());
+    let next = author.next_address(&id).unwrap();
+
+    assert!(author.send_announce_with_keyload(&Vec::new(), &vec![&id]).is_err());
+    // The announcement was published, only the keyload failed
+    assert_eq!(author.user.transport.sends_left, 0);
+    assert_eq!(author.next_address(&id).unwrap(), next);
+    assert_eq!(export(&author), backup);
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn signed_packet_with_empty_masked_payload_round_trips() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("OMITAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    let (omitted, _) = author
+        .send_signed_packet(&ann_link, &Bytes(b"public".to_vec()), &Bytes::default())
+        .unwrap();
+    let (masked, _) = author
+        .send_signed_packet(&omitted, &Bytes(b"public".to_vec()), &Bytes(b"m".to_vec()))
+        .unwrap();
+
+    // Only the empty masked payload is omitted, the 1-byte payload costs its 2-byte length and itself
+    let omitted_msg = transport.borrow_mut().recv_message(&omitted).unwrap().binary;
+    let masked_msg = transport.borrow_mut().recv_message(&masked).unwrap().binary;
+    assert!(omitted_msg.parse_header().unwrap().header.is_masked_payload_omitted());
+    assert!(!masked_msg.parse_header().unwrap().header.is_masked_payload_omitted());
+    assert_eq!(omitted_msg.body.bytes.len() + 3, masked_msg.body.bytes.len());
+
+    let mut subscriber = Subscriber::new("OMITSUBSCRIBERSEED", transport);
+    subscriber.receive_announcement(&ann_link).unwrap();
+    let (pk, public, masked_payload) = subscriber.receive_signed_packet(&omitted).unwrap();
+    assert_eq!(&pk, author.get_public_key());
+    assert_eq!(public, Bytes(b"public".to_vec()));
+    assert_eq!(masked_payload, Bytes::default());
+    let (_, public, masked_payload) = subscriber.receive_signed_packet(&masked).unwrap();
+    assert_eq!(public, Bytes(b"public".to_vec()));
+    assert_eq!(masked_payload, Bytes(b"m".to_vec()));
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn packet_context_is_authenticated() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("CONTEXTAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    let context = Bytes(b"request A".to_vec());
+    let options = PacketOptions::new().with_context(context.clone());
+    let ((signed, _), _) = author
+        .send_signed_packet_with(&ann_link, &Bytes(b"public".to_vec()), &Bytes::default(), &options)
+        .unwrap();
+    let (tagged, _) = author
+        .send_tagged_packet_with_context(&signed, &Bytes(b"public".to_vec()), &Bytes::default(), &context)
+        .unwrap();
+
+    // Replace the context the packet was wrapped with by another one of the same length
+    let replace_context = |link: &Address| {
+        let msg = transport.borrow_mut().recv_message(link).unwrap();
+        let mut forged = msg.clone();
+        let bytes = &mut forged.binary.body.bytes;
+        let pos = bytes
+            .windows(context.0.len())
+            .position(|w| w == &context.0[..])
+            .unwrap();
+        bytes[pos..pos + context.0.len()].copy_from_slice(b"request B");
+        transport.borrow_mut().delete_message(link).unwrap();
+        transport.borrow_mut().send_message(&forged).unwrap();
+        msg
+    };
+    let restore = |msg: &Message| {
+        transport.borrow_mut().delete_message(&msg.binary.link).unwrap();
+        transport.borrow_mut().send_message(msg).unwrap();
+    };
+
+    let mut subscriber = Subscriber::new("CONTEXTSUBSCRIBERSEED", transport.clone());
+    subscriber.receive_announcement(&ann_link).unwrap();
+    for link in [&signed, &tagged].iter() {
+        let msg = replace_context(link);
+        assert!(subscriber.receive_msg(link).is_err());
+        restore(&msg);
+        let unwrapped = subscriber.receive_msg(link).unwrap();
+        assert_eq!(unwrapped.body.context(), Some(&context));
+    }
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn link_fingerprint_detects_wrong_linked_state() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("FINGERPRINTAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    author.set_link_fingerprint(true);
+    let ann_link = author.send_announce().unwrap();
+    let (first, _) = author
+        .send_signed_packet(&ann_link, &Bytes(b"first".to_vec()), &Bytes::default())
+        .unwrap();
+    let (second, _) = author
+        .send_signed_packet(&first, &Bytes(b"second".to_vec()), &Bytes::default())
+        .unwrap();
+
+    // The fingerprint is signalled in the header and checked against the linked state
+    let msg = transport.borrow_mut().recv_message(&first).unwrap();
+    assert!(msg.binary.parse_header().unwrap().header.has_link_fingerprint());
+    let mut subscriber = Subscriber::new("FINGERPRINTSUBSCRIBERSEED", transport.clone());
+    subscriber.receive_announcement(&ann_link).unwrap();
+    let (_, public, _) = subscriber.receive_signed_packet(&first).unwrap();
+    assert_eq!(public, Bytes(b"first".to_vec()));
+
+    // A reader holding a wrong state for the linked message gets a distinct error
+    let mut reader = Subscriber::new("FINGERPRINTREADERSEED", transport);
+    reader.receive_announcement(&ann_link).unwrap();
+    {
+        let mut link_store = reader.user.user.link_store.borrow_mut();
+        let (wrong, info) = link_store.lookup(ann_link.rel()).unwrap();
+        link_store.update(first.rel(), wrong, info).unwrap();
+    }
+    let err = reader.receive_signed_packet(&second).err().unwrap();
+    assert!(matches!(err.downcast_ref::<Errors>(), Some(LinkedSpongosMismatch(_))));
+    let (_, public, _) = subscriber.receive_signed_packet(&second).unwrap();
+    assert_eq!(public, Bytes(b"second".to_vec()));
+}
+
+/// Codec xoring payload bytes with a key.
+struct XorCodec(u8);
+
+impl PayloadCodec for XorCodec {
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        Ok(data.iter().map(|b| b ^ self.0).collect())
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        self.encode(data)
+    }
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn payload_codec_is_inverted_on_receive() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("PAYLOADCODECAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    author.set_payload_codec(Some(XorCodec(0x5a)));
+    let ann_link = author.send_announce().unwrap();
+    let (packet_link, _) = author
+        .send_signed_packet(&ann_link, &Bytes(b"public".to_vec()), &Bytes(b"masked".to_vec()))
+        .unwrap();
+
+    let mut subscriber = Subscriber::new("PAYLOADCODECSUBSCRIBERSEED", transport.clone());
+    subscriber.set_payload_codec(Some(XorCodec(0x5a)));
+    subscriber.receive_announcement(&ann_link).unwrap();
+    let (_, public, masked) = subscriber.receive_signed_packet(&packet_link).unwrap();
+    assert_eq!(public.0, b"public".to_vec());
+    assert_eq!(masked.0, b"masked".to_vec());
+
+    // Without the codec the embedded bytes are returned
+    let mut plain = Subscriber::new("PAYLOADCODECPLAINSEED", transport);
+    plain.receive_announcement(&ann_link).unwrap();
+    let (_, public, masked) = plain.receive_signed_packet(&packet_link).unwrap();
+    assert_eq!(public.0, XorCodec(0x5a).encode(b"public").unwrap());
+    assert_eq!(masked.0, XorCodec(0x5a).encode(b"masked").unwrap());
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn subscriber_joins_with_invitation() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("INVITATIONAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let psk = psk::psk_from_seed::<DefaultF>(b"INVITATIONSECRET");
+    let pskid = psk::pskid_from_psk::<DefaultF>(&psk);
+    let ann_link = author.send_announce().unwrap();
+    assert!(author.create_invitation(Some(pskid)).is_err());
+    author.store_psk(pskid, psk).unwrap();
+    let bytes = author.create_invitation(Some(pskid)).unwrap();
+    assert!(Invitation::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+    let mut subscriber = Subscriber::new("INVITATIONSUBSCRIBERSEED", transport.clone());
+    let invitation = subscriber.accept_invitation(&bytes).unwrap();
+    assert_eq!(invitation.announcement(), &ann_link);
+    assert_eq!(invitation.psk_id(), Some(&pskid));
+    assert!(subscriber.author_public_key() == Some(author.get_public_key()));
+
+    // Invitations naming another author or mismatching keys are rejected
+    let other = Author::new("INVITATIONOTHERSEED", ChannelType::SingleBranch, transport.clone());
+    let other_ke_pk = x25519::public_from_ed25519(other.get_public_key()).unwrap();
+    assert!(Invitation::new(ann_link.clone(), *author.get_public_key(), other_ke_pk, None).is_err());
+    let forged = Invitation::new(ann_link, *other.get_public_key(), other_ke_pk, None)
+        .unwrap()
+        .to_bytes()
+        .unwrap();
+    let mut fooled = Subscriber::new("INVITATIONFOOLEDSEED", transport);
+    assert!(fooled.accept_invitation(&forged).is_err());
+    assert!(!fooled.is_registered());
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn psk_reader_without_subscription() {
+    for channel_type in [ChannelType::SingleBranch, ChannelType::MultiBranch] {
+        let transport = Rc::new(RefCell::new(BucketTransport::new()));
+        let mut author = Author::new("PSKREADERAUTHORSEED", channel_type, transport.clone());
+        let psk = psk::psk_from_seed::<DefaultF>(b"PSKREADERSECRET");
+        let pskid = psk::pskid_from_psk::<DefaultF>(&psk);
+        let ann_link = author.send_announce().unwrap();
+        author.store_psk(pskid, psk).unwrap();
+        let (keyload_link, _) = author.send_keyload(&ann_link, &vec![pskid], &vec![]).unwrap();
+        author
+            .send_tagged_packet(&keyload_link, &Bytes(b"public".to_vec()), &Bytes(b"masked".to_vec()))
+            .unwrap();
+
+        let mut synced = Subscriber::new("PSKREADERSYNCSEED", transport.clone());
+        let announcement = synced.receive_message(&ann_link).unwrap();
+        assert!(matches!(announcement.body, MessageContent::Announce));
+        synced.store_psk(pskid, psk).unwrap();
+        assert_eq!(synced.sync().unwrap(), (2, 0));
+
+        let mut fetching = Subscriber::new("PSKREADERFETCHSEED", transport.clone());
+        fetching.receive_message(&ann_link).unwrap();
+        fetching.store_psk(pskid, psk).unwrap();
+        let msgs: Vec<UnwrappedMessage> = (0..2).flat_map(|_| fetching.fetch_next_msgs()).collect();
+        assert_eq!(msgs.len(), 2);
+        assert!(matches!(msgs[0].body, MessageContent::Keyload));
+        assert_eq!(fetching.keyload_access(&msgs[0].link.msgid), KeyloadAccess::Psk(pskid));
+        match &msgs[1].body {
+            MessageContent::TaggedPacket { masked_payload, .. } => assert_eq!(masked_payload.0, b"masked".to_vec()),
+            _ => panic!("expected tagged packet"),
+        }
+
+        // Without the PSK the keyload is processed but the packet under it can't be read
+        let mut outsider = Subscriber::new("PSKREADEROUTSIDERSEED", transport);
+        outsider.receive_message(&ann_link).unwrap();
+        let msgs: Vec<UnwrappedMessage> = (0..2).flat_map(|_| outsider.fetch_next_msgs()).collect();
+        assert_eq!(outsider.keyload_access(&msgs[0].link.msgid), KeyloadAccess::Denied);
+        assert!(!msgs
+            .iter()
+            .any(|msg| matches!(msg.body, MessageContent::TaggedPacket { .. })));
+    }
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn tagged_packets_with_shorter_macs() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("TAGMACAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let psk = psk::psk_from_seed::<DefaultF>(b"TAGMACSECRET");
+    let pskid = psk::pskid_from_psk::<DefaultF>(&psk);
+    let ann_link = author.send_announce().unwrap();
+    author.store_psk(pskid, psk).unwrap();
+    let (keyload_link, _) = author.send_keyload(&ann_link, &vec![pskid], &vec![]).unwrap();
+
+    let mut subscriber = Subscriber::new("TAGMACSUBSCRIBERSEED", transport.clone());
+    subscriber.receive_announcement(&ann_link).unwrap();
+    subscriber.store_psk(pskid, psk).unwrap();
+    subscriber.receive_keyload(&keyload_link).unwrap();
+
+    for mac_length in crate::message::tagged_packet::MAC_LENGTHS.iter() {
+        author.set_tag_mac_length(*mac_length).unwrap();
+        let masked = Bytes(vec![*mac_length as u8; 3]);
+        let (packet_link, _) = author
+            .send_tagged_packet(&keyload_link, &Bytes(b"public".to_vec()), &masked)
+            .unwrap();
+        let (public, unwrapped) = subscriber.receive_tagged_packet(&packet_link).unwrap();
+        assert_eq!(public.0, b"public".to_vec());
+        assert_eq!(unwrapped, masked);
+    }
+
+    // The MAC length and the application type are signalled by distinct content flags
+    author.set_tag_mac_length(16).unwrap();
+    let masked = Bytes(b"masked".to_vec());
+    let (packet_link, _) = author
+        .send_tagged_packet_with_app_type(&keyload_link, &Bytes(b"public".to_vec()), &masked, 9)
+        .unwrap();
+    let msg = transport.borrow_mut().recv_message(&packet_link).unwrap();
+    let header = msg.binary.parse_header().unwrap().header;
+    assert!(header.has_content_mac_length());
+    assert!(header.has_content_app_type());
+    assert!(!header.has_content_masked_payload());
+    let (_, unwrapped) = subscriber.receive_tagged_packet(&packet_link).unwrap();
+    assert_eq!(unwrapped, masked);
+
+    assert!(author.set_tag_mac_length(8).is_err());
+    assert!(author.set_tag_mac_length(64).is_err());
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn masked_blocks_are_signalled_in_header() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("BLOCKSAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let psk = psk::psk_from_seed::<DefaultF>(b"BLOCKSSECRET");
+    let pskid = psk::pskid_from_psk::<DefaultF>(&psk);
+    let ann_link = author.send_announce().unwrap();
+    author.store_psk(pskid, psk).unwrap();
+    let (keyload_link, _) = author.send_keyload(&ann_link, &vec![pskid], &vec![]).unwrap();
+    author.set_masked_block_size(Some(4)).unwrap();
+
+    let masked = Bytes(b"masked payload split into blocks".to_vec());
+    let (signed_link, _) = author
+        .send_signed_packet(&keyload_link, &Bytes(b"public".to_vec()), &masked)
+        .unwrap();
+    let (tagged_link, _) = author
+        .send_tagged_packet(&signed_link, &Bytes(b"public".to_vec()), &masked)
+        .unwrap();
+    let (public_link, _) = author
+        .send_signed_packet(&tagged_link, &Bytes(b"public".to_vec()), &Bytes::default())
+        .unwrap();
+    let has_blocks = |link: &Address| {
+        let msg = transport.borrow_mut().recv_message(link).unwrap();
+        msg.binary.parse_header().unwrap().header.has_content_masked_blocks()
+    };
+    assert!(has_blocks(&signed_link));
+    assert!(has_blocks(&tagged_link));
+    // Empty masked payloads are omitted, there are no blocks
+    assert!(!has_blocks(&public_link));
+
+    // Receivers follow the header, no setting is needed
+    let mut subscriber = Subscriber::new("BLOCKSSUBSCRIBERSEED", transport.clone());
+    subscriber.receive_announcement(&ann_link).unwrap();
+    subscriber.store_psk(pskid, psk).unwrap();
+    subscriber.receive_keyload(&keyload_link).unwrap();
+    let (_, _, unwrapped) = subscriber.receive_signed_packet(&signed_link).unwrap();
+    assert_eq!(unwrapped, masked);
+    let (_, unwrapped) = subscriber.receive_tagged_packet(&tagged_link).unwrap();
+    assert_eq!(unwrapped, masked);
+    subscriber.receive_signed_packet(&public_link).unwrap();
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn interrupted_sync_resumes() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("RESUMEAUTHORSEED", ChannelType::MultiBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    let mut links = Vec::new();
+    let mut link_to = ann_link.clone();
+    for i in 0_u8..3 {
+        let (link, _) = author
+            .send_signed_packet(&link_to, &Bytes(vec![i]), &Bytes::default())
+            .unwrap();
+        links.push(link.clone());
+        link_to = link;
+    }
+
+    // Sync is interrupted while retrieving each packet referenced by a sequence message in turn
+    for (i, link) in links.iter().enumerate() {
+        let msg = transport.borrow_mut().recv_message(link).unwrap();
+        transport.borrow_mut().delete_message(link).unwrap();
+        let mut subscriber = Subscriber::new("RESUMESUBSCRIBERSEED", transport.clone());
+        subscriber.receive_announcement(&ann_link).unwrap();
+        assert_eq!(subscriber.sync().unwrap(), (i, 1));
+
+        transport.borrow_mut().send_message(&msg).unwrap();
+        assert_eq!(subscriber.sync().unwrap(), (links.len() - i, 0));
+        assert!(subscriber.fetch_next_msgs().is_empty());
+    }
+}
+
+/// Transport storing messages in a shared map, receiving stalls forever once the budget of
+/// received messages is spent.
+#[cfg(feature = "async")]
+#[derive(Clone, Default)]
+struct StallingTransport {
+    messages: Rc<RefCell<iota_streams_core::prelude::HashMap<Address, Message>>>,
+    budget: Rc<core::cell::Cell<usize>>,
+}
+
+#[cfg(feature = "async")]
+impl StallingTransport {
+    async fn recv(&self, link: &Address) -> Result<Message> {
+        match self.budget.get() {
+            0 => smol::future::pending().await,
+            budget => self.budget.set(budget - 1),
+        }
+        match self.messages.borrow().get(link) {
+            Some(msg) => Ok(msg.clone()),
+            None => err!(MessageLinkNotFound(link.to_string())),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl transport::TransportOptions for StallingTransport {
+    type SendOptions = ();
+    fn get_send_options(&self) {}
+    fn set_send_options(&mut self, _opt: ()) {}
+
+    type RecvOptions = ();
+    fn get_recv_options(&self) {}
+    fn set_recv_options(&mut self, _opt: ()) {}
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait(?Send)]
+impl transport::TransportDetails<Address> for StallingTransport {
+    type Details = ();
+    async fn get_link_details(&mut self, _link: &Address) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait(?Send)]
+impl transport::Transport<Address, Message> for StallingTransport {
+    async fn send_message(&mut self, msg: &Message) -> Result<()> {
+        self.messages.borrow_mut().insert(msg.binary.link.clone(), msg.clone());
+        Ok(())
+    }
+
+    async fn recv_messages(&mut self, link: &Address) -> Result<Vec<Message>> {
+        self.recv(link).await.map(|msg| vec![msg])
+    }
+
+    async fn recv_message(&mut self, link: &Address) -> Result<Message> {
+        self.recv(link).await
+    }
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn dropped_sync_leaves_state_consistent() {
+    smol::block_on(async {
+        let transport = StallingTransport::default();
+        transport.budget.set(usize::MAX);
+        let mut author = Author::new("DROPAUTHORSEED", ChannelType::MultiBranch, transport.clone());
+        let ann_link = author.send_announce().await.unwrap();
+        let mut link_to = ann_link.clone();
+        for i in 0_u8..3 {
+            let (link, _) = author
+                .send_signed_packet(&link_to, &Bytes(vec![i]), &Bytes::default())
+                .await
+                .unwrap();
+            link_to = link;
+        }
+
+        let state = |subscriber: &Subscriber<StallingTransport>| {
+            subscriber
+                .fetch_state()
+                .unwrap()
+                .into_iter()
+                .map(|(id, cursor)| (id, cursor.link, cursor.seq_no))
+                .collect::<Vec<_>>()
+        };
+        let mut reference = Subscriber::new("DROPSUBSCRIBERSEED", transport.clone());
+        reference.receive_announcement(&ann_link).await.unwrap();
+        assert_eq!(reference.sync().await.unwrap(), (3, 0));
+
+        // Sync is polled once and dropped when the transport stalls, after each received
+        // message in turn, until the budget suffices to complete it
+        let mut interrupted = 0;
+        for budget in 0..32 {
+            let mut subscriber = Subscriber::new("DROPSUBSCRIBERSEED", transport.clone());
+            subscriber.receive_announcement(&ann_link).await.unwrap();
+            transport.budget.set(budget);
+            let polled = smol::future::poll_once(subscriber.sync()).await;
+            transport.budget.set(usize::MAX);
+            match polled {
+                Some(synced) => {
+                    assert_eq!(synced.unwrap(), (3, 0));
+                    break;
+                }
+                None => interrupted += 1,
+            }
+
+            let (processed, orphans) = subscriber.sync().await.unwrap();
+            assert!(processed <= 3);
+            assert_eq!(orphans, 0);
+            assert_eq!(state(&subscriber), state(&reference));
+            assert!(subscriber.fetch_next_msgs().await.is_empty());
+        }
+        assert!(interrupted > 3);
+    });
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn sync_orphan_limit() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("ORPHANAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    // Packet under a keyload without recipients can't be read and is left orphaned
+    let (keyload_link, _) = author.send_keyload(&ann_link, &vec![], &vec![]).unwrap();
+    author
+        .send_tagged_packet(&keyload_link, &Bytes(b"public".to_vec()), &Bytes(b"masked".to_vec()))
+        .unwrap();
+
+    let reader = |max_orphans: Option<(usize, OrphanPolicy)>| {
+        let mut subscriber = Subscriber::new("ORPHANSUBSCRIBERSEED", transport.clone());
+        subscriber.receive_announcement(&ann_link).unwrap();
+        if let Some((max_orphans, policy)) = max_orphans {
+            subscriber.set_max_orphans(max_orphans, policy);
+        }
+        subscriber
+    };
+    assert_eq!(reader(None).sync().unwrap(), (1, 1));
+    assert_eq!(reader(Some((0, OrphanPolicy::DropOldest))).sync().unwrap(), (1, 0));
+    assert!(reader(Some((0, OrphanPolicy::Error))).sync().is_err());
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn sync_skips_corrupt_message() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("SKIPAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    let (corrupt_link, _) = author
+        .send_signed_packet(&ann_link, &Bytes(b"corrupt".to_vec()), &Bytes::default())
+        .unwrap();
+    let (linked_link, _) = author
+        .send_signed_packet(&corrupt_link, &Bytes(b"linked".to_vec()), &Bytes::default())
+        .unwrap();
+
+    let msg = transport.borrow_mut().recv_message(&corrupt_link).unwrap();
+    let mut body = msg.binary.body.as_bytes().to_vec();
+    *body.last_mut().unwrap() ^= 1;
+    let corrupt = BinaryMessage::new(msg.binary.link.clone(), msg.binary.prev_link.clone(), body.into());
+    transport.borrow_mut().delete_message(&corrupt_link).unwrap();
+    transport.borrow_mut().send_message(&Message::new(corrupt)).unwrap();
+
+    let mut subscriber = Subscriber::new("SKIPSUBSCRIBERSEED", transport.clone());
+    subscriber.receive_announcement(&ann_link).unwrap();
+    let mut stalled = subscriber.clone();
+    assert_eq!(stalled.sync().unwrap(), (0, 1));
+    // The message linked to the corrupt one can't be unwrapped either
+    assert_eq!(
+        subscriber.sync_skipping_errors().unwrap(),
+        (0, vec![corrupt_link, linked_link])
+    );
+
+    assert_eq!(subscriber.sync_skipping_errors().unwrap(), (0, vec![]));
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn network_domains_have_disjoint_addresses() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut mainnet = Author::new_in_network_domain(
+        "DOMAINAUTHORSEED",
+        b"mainnet",
+        ChannelType::SingleBranch,
+        transport.clone(),
+    );
+    let mut testnet = Author::new_in_network_domain(
+        "DOMAINAUTHORSEED",
+        b"testnet",
+        ChannelType::SingleBranch,
+        transport.clone(),
+    );
+    let mut default = Author::new("DOMAINAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    assert_eq!(mainnet.channel_address(), testnet.channel_address());
+
+    let mainnet_ann = mainnet.send_announce().unwrap();
+    let testnet_ann = testnet.send_announce().unwrap();
+    assert_ne!(mainnet_ann, testnet_ann);
+    assert_ne!(mainnet_ann, default.send_announce().unwrap());
+
+    let mainnet_packet = mainnet
+        .send_signed_packet(&mainnet_ann, &Bytes(b"mainnet".to_vec()), &Bytes::default())
+        .unwrap()
+        .0;
+    let testnet_packet = testnet
+        .send_signed_packet(&testnet_ann, &Bytes(b"testnet".to_vec()), &Bytes::default())
+        .unwrap()
+        .0;
+    assert_ne!(mainnet_packet, testnet_packet);
+
+    let mut subscriber = Subscriber::new("DOMAINSUBSCRIBERSEED", transport.clone());
+    subscriber.set_network_domain(b"mainnet");
+    subscriber.receive_announcement(&mainnet_ann).unwrap();
+    let msgs = subscriber.fetch_next_msgs();
+    assert_eq!(msgs.len(), 1);
+    assert_eq!(msgs[0].link, mainnet_packet);
+
+    let mut mismatched = Subscriber::new("DOMAINSUBSCRIBERSEED", transport);
+    mismatched.receive_announcement(&testnet_ann).unwrap();
+    assert!(mismatched.fetch_next_msgs().is_empty());
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn network_domain_is_kept_in_backups() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new_in_network_domain(
+        "DOMAINBACKUPAUTHORSEED",
+        b"testnet",
+        ChannelType::SingleBranch,
+        transport.clone(),
+    );
+    let ann_link = author.send_announce().unwrap();
+    let mut subscriber = Subscriber::new("DOMAINBACKUPSUBSCRIBERSEED", transport.clone());
+    subscriber.set_network_domain(b"testnet");
+    subscriber.receive_announcement(&ann_link).unwrap();
+
+    let mut restored = Author::import(&author.export("password").unwrap(), "password", transport.clone()).unwrap();
+    assert_eq!(restored.network_domain(), b"testnet");
+    let (packet, _) = restored
+        .send_signed_packet(&ann_link, &Bytes(b"restored".to_vec()), &Bytes::default())
+        .unwrap();
+    let msgs = subscriber.fetch_next_msgs();
+    assert_eq!(msgs.len(), 1);
+    assert_eq!(msgs[0].link, packet);
+
+    let restored = Subscriber::import(&subscriber.export("password").unwrap(), "password", transport).unwrap();
+    assert_eq!(restored.network_domain(), b"testnet");
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn standalone_packets_skip_missing_messages() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("STANDALONEAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    let links: Vec<Address> = (0_u8..3)
+        .map(|i| {
+            let standalone = PacketOptions::new().with_standalone(true);
+            let ((link, _), _) = author
+                .send_signed_packet_with(&ann_link, &Bytes(vec![i]), &Bytes(b"masked".to_vec()), &standalone)
+                .unwrap();
+            link
+        })
+        .collect();
+    transport.borrow_mut().delete_message(&links[1]).unwrap();
+
+    let mut subscriber = Subscriber::new("STANDALONESUBSCRIBERSEED", transport.clone());
+    subscriber.receive_announcement(&ann_link).unwrap();
+    let mut no_gap = subscriber.clone();
+    assert_eq!(no_gap.fetch_standalone_packets(0).unwrap().len(), 1);
+
+    let msgs = subscriber.fetch_standalone_packets(1).unwrap();
+    let received: Vec<&Address> = msgs.iter().map(|msg| &msg.link).collect();
+    assert_eq!(received, vec![&links[0], &links[2]]);
+    assert!(subscriber.fetch_standalone_packets(1).unwrap().is_empty());
+
+    let standalone = PacketOptions::new().with_standalone(true);
+    assert!(author
+        .send_signed_packet_with(&links[0], &Bytes::default(), &Bytes::default(), &standalone)
+        .is_err());
+
+    let mut multi_branch = Author::new("STANDALONEMULTISEED", ChannelType::MultiBranch, transport);
+    let multi_ann_link = multi_branch.send_announce().unwrap();
+    assert!(multi_branch
+        .send_signed_packet_with(&multi_ann_link, &Bytes::default(), &Bytes::default(), &standalone)
+        .is_err());
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn sync_discovered_messages() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("DISCOVERAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    let mut link = ann_link.clone();
+    for _ in 0..3 {
+        let (msg_link, _) = author
+            .send_signed_packet(&link, &Bytes(b"public".to_vec()), &Bytes::default())
+            .unwrap();
+        link = msg_link;
+    }
+
+    let mut subscriber = Subscriber::new("DISCOVERSUBSCRIBERSEED", transport.clone());
+    assert!(subscriber.discover_messages().is_err());
+    subscriber.receive_announcement(&ann_link).unwrap();
+    assert_eq!(subscriber.discover_messages().unwrap(), 4);
+    assert_eq!(subscriber.sync().unwrap(), (3, 0));
+
+    // Messages sent after the listing are found by the following sync
+    let (link, _) = author
+        .send_signed_packet(&link, &Bytes(b"public".to_vec()), &Bytes::default())
+        .unwrap();
+    subscriber.discover_messages().unwrap();
+    author
+        .send_signed_packet(&link, &Bytes(b"public".to_vec()), &Bytes::default())
+        .unwrap();
+    assert_eq!(subscriber.sync().unwrap(), (1, 0));
+    assert_eq!(subscriber.sync().unwrap(), (1, 0));
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn diff_of_author_and_subscriber() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("DIFFAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    let (packet_link, _) = author
+        .send_signed_packet(&ann_link, &Bytes(b"public".to_vec()), &Bytes::default())
+        .unwrap();
+
+    let mut subscriber = Subscriber::new("DIFFSUBSCRIBERSEED", transport);
+    assert!(author.diff(&subscriber).appinst_differs);
+    subscriber.receive_announcement(&ann_link).unwrap();
+    let diff = author.diff(&subscriber);
+    assert!(!diff.appinst_differs);
+    assert_eq!(diff.links_only_here, vec![packet_link.msgid.clone()]);
+    assert_eq!(diff.cursors.len(), 1);
+
+    subscriber.sync().unwrap();
+    let diff = author.diff(&subscriber);
+    assert!(diff.links_only_here.is_empty() && diff.links_only_there.is_empty());
+    assert!(diff.spongos_mismatches.is_empty() && diff.cursors.is_empty());
+    let subscriber_id = Identifier::EdPubKey((*subscriber.get_public_key()).into());
+    assert!(diff.only_there == vec![subscriber_id]);
+    assert!(subscriber.diff(&subscriber.clone()).is_empty());
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn sync_reports_progress() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("PROGRESSAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    let mut link = ann_link.clone();
+    for _ in 0..5 {
+        let (msg_link, _) = author
+            .send_signed_packet(&link, &Bytes(b"public".to_vec()), &Bytes::default())
+            .unwrap();
+        link = msg_link;
+    }
+
+    let mut subscriber = Subscriber::new("PROGRESSSUBSCRIBERSEED", transport.clone());
+    subscriber.receive_announcement(&ann_link).unwrap();
+    let reports = Rc::new(RefCell::new(Vec::new()));
+    let sink = reports.clone();
+    let callback: SyncProgressCallback = Rc::new(move |progress| sink.borrow_mut().push(*progress));
+    subscriber.set_sync_progress_callback(Some(callback), 2);
+    assert_eq!(subscriber.sync().unwrap(), (5, 0));
+    assert_eq!(
+        *reports.borrow(),
+        vec![
+            SyncProgress { processed: 2, total: 5 },
+            SyncProgress { processed: 4, total: 5 },
+            SyncProgress { processed: 5, total: 5 },
+        ]
+    );
+    assert_eq!(reports.borrow()[0].percent(), 40);
+
+    subscriber.set_sync_progress_callback(None, 1);
+    author
+        .send_signed_packet(&link, &Bytes(b"public".to_vec()), &Bytes::default())
+        .unwrap();
+    assert_eq!(subscriber.sync().unwrap(), (1, 0));
+    assert_eq!(reports.borrow().len(), 3);
+}
+
+#[test]
+fn rekey_backup_changes_password() {
+    let mut user = User::new("REKEYSEED", ChannelType::SingleBranch, ());
+    user.user.create_channel(0).unwrap();
+    let exported = user.export(0, "old").unwrap();
+    assert!(User::<()>::rekey_backup(&exported, 0, "wrong", "new").is_err());
+
+    let rekeyed = User::<()>::rekey_backup(&exported, 0, "old", "new").unwrap();
+    assert!(User::import(&rekeyed, 0, "old", ()).is_err());
+    let restored = User::import(&rekeyed, 0, "new", ()).unwrap();
+    assert_eq!(restored.user.sig_kp.public, user.user.sig_kp.public);
+    assert_eq!(restored.user.appinst, user.user.appinst);
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn packets_are_indexed_by_topic() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("TOPICAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    let public = Bytes(b"public".to_vec());
+    let masked = Bytes(b"masked".to_vec());
+    let options = PacketOptions::new().with_topic("temperature");
+    let ((temperature, _), _) = author
+        .send_signed_packet_with(&ann_link, &public, &masked, &options)
+        .unwrap();
+    let options = PacketOptions::new().with_topic("humidity");
+    let ((humidity, _), _) = author
+        .send_signed_packet_with(&temperature, &public, &masked, &options)
+        .unwrap();
+    let (untagged, _) = author.send_signed_packet(&humidity, &public, &masked).unwrap();
+
+    let mut subscriber = Subscriber::new("TOPICSUBSCRIBERSEED", transport);
+    subscriber.receive_announcement(&ann_link).unwrap();
+    assert_eq!(subscriber.sync().unwrap(), (3, 0));
+    assert_eq!(subscriber.messages_for_topic("temperature"), vec![temperature]);
+    assert_eq!(subscriber.messages_for_topic("humidity"), vec![humidity]);
+    assert_eq!(subscriber.messages_for_topic(DEFAULT_TOPIC), vec![untagged]);
+    assert!(subscriber.messages_for_topic("pressure").is_empty());
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn topic_index_is_deduplicated_pruned_and_backed_up() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("TOPICINDEXAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    let public = Bytes(b"public".to_vec());
+    let masked = Bytes(b"masked".to_vec());
+    let temperature = PacketOptions::new().with_topic("temperature");
+    let humidity = PacketOptions::new().with_topic("humidity");
+    let ((first, _), _) = author
+        .send_signed_packet_with(&ann_link, &public, &masked, &temperature)
+        .unwrap();
+    let ((orphan, _), _) = author
+        .send_signed_packet_with(&first, &public, &masked, &humidity)
+        .unwrap();
+    let ((second, _), _) = author
+        .send_signed_packet_with(&first, &public, &masked, &temperature)
+        .unwrap();
+
+    let mut subscriber = Subscriber::new("TOPICINDEXSUBSCRIBERSEED", transport.clone());
+    subscriber.receive_announcement(&ann_link).unwrap();
+    subscriber.receive_msg(&first).unwrap();
+    subscriber.receive_msg(&first).unwrap();
+    subscriber.receive_msg(&orphan).unwrap();
+    subscriber.receive_msg(&second).unwrap();
+    assert_eq!(
+        subscriber.messages_for_topic("temperature"),
+        vec![first.clone(), second.clone()]
+    );
+    assert_eq!(subscriber.messages_for_topic("humidity"), vec![orphan]);
+
+    let exported = subscriber.export("password").unwrap();
+    let mut restored = Subscriber::import(&exported, "password", transport).unwrap();
+    assert_eq!(
+        restored.messages_for_topic("temperature"),
+        subscriber.messages_for_topic("temperature")
+    );
+    assert_eq!(
+        restored.messages_for_topic("humidity"),
+        subscriber.messages_for_topic("humidity")
+    );
+
+    assert_eq!(restored.gc_spongos(&second).unwrap(), 1);
+    assert_eq!(restored.messages_for_topic("temperature"), vec![first, second]);
+    assert!(restored.messages_for_topic("humidity").is_empty());
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn unfollowed_topics_are_not_unmasked() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("FOLLOWAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    let public = Bytes(b"public".to_vec());
+    let temperature = PacketOptions::new().with_topic("temperature");
+    let humidity = PacketOptions::new().with_topic("humidity");
+    let ((followed, _), _) = author
+        .send_signed_packet_with(&ann_link, &public, &Bytes(b"21C".to_vec()), &temperature)
+        .unwrap();
+    let ((skipped, _), _) = author
+        .send_signed_packet_with(&followed, &public, &Bytes(b"40%".to_vec()), &humidity)
+        .unwrap();
+    let ((linked, _), _) = author
+        .send_signed_packet_with(&skipped, &public, &Bytes(b"22C".to_vec()), &temperature)
+        .unwrap();
+
+    let mut subscriber = Subscriber::new("FOLLOWSUBSCRIBERSEED", transport);
+    subscriber.set_followed_topics(Some(vec!["temperature".to_string()]));
+    subscriber.receive_announcement(&ann_link).unwrap();
+    let (_, public_payload, masked_payload) = subscriber.receive_signed_packet(&followed).unwrap();
+    assert_eq!(public_payload, public);
+    assert_eq!(masked_payload.0, b"21C".to_vec());
+    // The signature is still verified and the packet is processed, only its masked payload is
+    // left out
+    let (_, public_payload, masked_payload) = subscriber.receive_signed_packet(&skipped).unwrap();
+    assert_eq!(public_payload, public);
+    assert!(masked_payload.0.is_empty());
+    let (_, _, masked_payload) = subscriber.receive_signed_packet(&linked).unwrap();
+    assert_eq!(masked_payload.0, b"22C".to_vec());
+    assert_eq!(subscriber.messages_for_topic("humidity"), vec![skipped]);
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn strict_sequencing_rejects_gaps() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("STRICTSEQAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    let public = Bytes(b"public".to_vec());
+    let links: Vec<Address> = (0..3)
+        .map(|_| author.send_signed_packet(&ann_link, &public, &Bytes::default()).unwrap().0)
+        .collect();
+
+    let mut lenient = Subscriber::new("STRICTSEQLENIENTSEED", transport.clone());
+    lenient.receive_announcement(&ann_link).unwrap();
+    lenient.receive_signed_packet(&links[2]).unwrap();
+
+    let mut strict = Subscriber::new("STRICTSEQSTRICTSEED", transport);
+    strict.set_strict_sequencing(true);
+    strict.receive_announcement(&ann_link).unwrap();
+    let stored = strict.spongos_store_len();
+    assert!(strict.receive_signed_packet(&links[2]).is_err());
+    assert_eq!(strict.spongos_store_len(), stored);
+    for link in &links {
+        strict.receive_signed_packet(link).unwrap();
+    }
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn wrap_size_hints_keep_messages_intact() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("WRAPHINTAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    author.set_wrap_size_hints(true);
+    let ann_link = author.send_announce().unwrap();
+    let payloads = vec![vec![1; 64], vec![2; 64], vec![3; 16], vec![4; 128], vec![5; 128]];
+    let links: Vec<Address> = payloads
+        .iter()
+        .map(|payload| {
+            let masked = Bytes(payload.clone());
+            author.send_signed_packet(&ann_link, &Bytes::default(), &masked).unwrap().0
+        })
+        .collect();
+
+    let mut subscriber = Subscriber::new("WRAPHINTSUBSCRIBERSEED", transport);
+    subscriber.receive_announcement(&ann_link).unwrap();
+    for (link, payload) in links.iter().zip(payloads) {
+        let (_, _, masked) = subscriber.receive_signed_packet(link).unwrap();
+        assert_eq!(masked.0, payload);
+    }
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn signed_packets_are_signed_by_signer() {
+    struct CountingSigner {
+        kp: ed25519::Keypair,
+        signed: Rc<core::cell::Cell<usize>>,
+    }
+
+    impl Signer for CountingSigner {
+        fn public_key(&self) -> &ed25519::PublicKey {
+            &self.kp.public
+        }
+
+        fn sign_hash(&self, hash: &NBytes<U64>) -> Result<ed25519::Signature> {
+            self.signed.set(self.signed.get() + 1);
+            self.kp.sign_hash(hash)
+        }
+    }
+
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("SIGNERAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    let signed = Rc::new(core::cell::Cell::new(0));
+    let kp = ed25519::keypair_from_secret([7; ed25519::SECRET_KEY_LENGTH]);
+    let signer_pk = kp.public;
+    author.set_signer(Some(CountingSigner {
+        kp,
+        signed: signed.clone(),
+    }));
+    let public = Bytes(b"public".to_vec());
+    let (packet_link, _) = author.send_signed_packet(&ann_link, &public, &Bytes::default()).unwrap();
+    assert_eq!(signed.get(), 1);
+
+    let mut subscriber = Subscriber::new("SIGNERSUBSCRIBERSEED", transport);
+    subscriber.receive_announcement(&ann_link).unwrap();
+    let (pk, _, _) = subscriber.receive_signed_packet(&packet_link).unwrap();
+    assert_eq!(pk, signer_pk);
+    assert_ne!(pk, *author.get_public_key());
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn subscriber_bootstraps_from_checkpoint() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("CHECKPOINTAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    let mut follower = Subscriber::new("CHECKPOINTFOLLOWERSEED", transport.clone());
+    follower.receive_announcement(&ann_link).unwrap();
+
+    let (first, _) = author
+        .send_signed_packet(&ann_link, &Bytes(vec![1]), &Bytes::default())
+        .unwrap();
+    let (last, _) = author
+        .send_signed_packet(&first, &Bytes(vec![2]), &Bytes::default())
+        .unwrap();
+    let (cp_link, _) = author.publish_checkpoint(&last).unwrap();
+    let (next, _) = author
+        .send_signed_packet(&cp_link, &Bytes(vec![3]), &Bytes::default())
+        .unwrap();
+
+    // A late subscriber starts from the checkpoint and continues with the following messages
+    let mut late = Subscriber::new("CHECKPOINTLATESEED", transport.clone());
+    assert_eq!(late.bootstrap_from_checkpoint(&cp_link).unwrap(), last);
+    let msgs = late.fetch_next_msgs();
+    assert_eq!(msgs.len(), 1);
+    assert_eq!(msgs[0].link, next);
+
+    // A subscriber which followed the history checks the checkpoint digest against its own state
+    let msgs: Vec<UnwrappedMessage> = (0..4).flat_map(|_| follower.fetch_next_msgs()).collect();
+    assert_eq!(msgs.len(), 4);
+    match &msgs[2].body {
+        MessageContent::Checkpoint { head } => assert_eq!(head, &last),
+        _ => panic!("expected checkpoint"),
+    }
+
+    let mut multi = Author::new("CHECKPOINTMULTISEED", ChannelType::MultiBranch, transport);
+    let multi_ann = multi.send_announce().unwrap();
+    assert!(multi.publish_checkpoint(&multi_ann).is_err());
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn connection_history_is_kept_in_backups() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("HISTORYAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let first = author.send_announce().unwrap();
+    author.new_channel(1).unwrap();
+    let second = author.send_announce().unwrap();
+    assert_eq!(author.connection_history().collect::<Vec<_>>(), vec![first.clone(), second.clone()]);
+
+    // Switching back moves the channel to the end of the history
+    author.switch_channel(&first.appinst).unwrap();
+    assert_eq!(author.connection_history().collect::<Vec<_>>(), vec![second.clone(), first.clone()]);
+
+    let mut subscriber = Subscriber::new("HISTORYSUBSCRIBERSEED", transport.clone());
+    subscriber.receive_announcement(&second).unwrap();
+    assert_eq!(subscriber.connection_history().collect::<Vec<_>>(), vec![second.clone()]);
+
+    let restored = Author::import(&author.export("password").unwrap(), "password", transport).unwrap();
+    assert_eq!(restored.connection_history().collect::<Vec<_>>(), vec![second, first.clone()]);
+
+    author.set_max_connection_history(1);
+    assert_eq!(author.connection_history().collect::<Vec<_>>(), vec![first]);
+}
+
+#[test]
+fn stashed_channels_are_kept_in_backups() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("STASHBACKUPAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let first = author.send_announce().unwrap();
+    let mut subscriber = Subscriber::new("STASHBACKUPSUBSCRIBERSEED", transport.clone());
+    subscriber.receive_announcement(&first).unwrap();
+    let (packet, _) = author
+        .send_signed_packet(&first, &Bytes::default(), &Bytes(b"first".to_vec()))
+        .unwrap();
+    author.new_channel(1).unwrap();
+    let second = author.send_announce().unwrap();
+    assert_eq!(author.stashed_channels(), vec![&first.appinst]);
+
+    let mut restored = Author::import(&author.export("password").unwrap(), "password", transport).unwrap();
+    assert_eq!(restored.stashed_channels(), vec![&first.appinst]);
+    assert_eq!(restored.channel_address(), Some(&second.appinst));
+
+    // The stashed channel carries on from the last message sent before the backup
+    restored.switch_channel(&first.appinst).unwrap();
+    assert_eq!(restored.stashed_channels(), vec![&second.appinst]);
+    let (next, _) = restored
+        .send_signed_packet(&packet, &Bytes::default(), &Bytes(b"restored".to_vec()))
+        .unwrap();
+    let (_, _, masked) = subscriber.receive_signed_packet(&packet).unwrap();
+    assert_eq!(masked, Bytes(b"first".to_vec()));
+    let (_, _, masked) = subscriber.receive_signed_packet(&next).unwrap();
+    assert_eq!(masked, Bytes(b"restored".to_vec()));
+}
+
+#[test]
+fn verify_backup_summarizes_state() {
+    let mut user = User::new("VERIFYBACKUPSEED", ChannelType::SingleBranch, ());
+    assert_eq!(User::<()>::verify_backup(&user.export(0, "pwd").unwrap(), 0, "pwd").unwrap().spongos, 0);
+
+    user.user.create_channel(0).unwrap();
+    let announcement = user.user.announce().unwrap();
+    user.user.commit_wrapped(announcement.wrapped, MsgInfo::Announce).unwrap();
+    user.set_label(Some("backup".to_string()));
+    let exported = user.export(0, "pwd").unwrap();
+
+    let info = User::<()>::verify_backup(&exported, 0, "pwd").unwrap();
+    assert!(info.channel);
+    assert_eq!(info.spongos, 1);
+    assert_eq!(info.cursors, 1);
+    assert_eq!(info.psks, 0);
+    assert_eq!(info.label.as_deref(), Some("backup"));
+
+    assert!(User::<()>::verify_backup(&exported, 0, "wrong").is_err());
+    assert!(User::<()>::verify_backup(&exported, 1, "pwd").is_err());
+    assert!(User::<()>::verify_backup(&exported[..exported.len() - 1], 0, "pwd").is_err());
+}
+
+#[test]
+fn close_is_accepted_from_author_only() {
+    let (mut author, ann_link, announcement) = announced_channel("CLOSEAUTHORSEED");
+
+    let mut subscriber = reader_of("CLOSESUBSCRIBERSEED", &announcement);
+    let mut rogue = reader_of("CLOSEROGUESEED", &announcement);
+    assert!(rogue.user.close(&ann_link).is_err());
+
+    // Close message signed by a subscriber pretending to be the author
+    rogue.user.author_sig_pk = Some(rogue.user.sig_kp.public);
+    let forged = rogue.user.close(&ann_link).unwrap();
+    assert!(subscriber.user.handle_close(forged.message, MsgInfo::Close).is_err());
+    assert!(!subscriber.is_closed());
+
+    let close = author.user.close(&ann_link).unwrap();
+    subscriber.user.handle_close(close.message, MsgInfo::Close).unwrap();
+    assert!(subscriber.is_closed());
+
+    // Closing is kept in backups
+    let restored = User::import(&subscriber.export(0, "password").unwrap(), 0, "password", ()).unwrap();
+    assert!(restored.is_closed());
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn sealed_membership_rejects_new_subscribers() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("SEALAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+
+    let mut member = Subscriber::new("SEALMEMBERSEED", transport.clone());
+    member.receive_announcement(&ann_link).unwrap();
+    let member_sub = member.send_subscribe(&ann_link).unwrap();
+    author.receive_subscribe(&member_sub).unwrap();
+
+    let mut latecomer = Subscriber::new("SEALLATECOMERSEED", transport.clone());
+    latecomer.receive_announcement(&ann_link).unwrap();
+    let latecomer_sub = latecomer.send_subscribe(&ann_link).unwrap();
+
+    let (seal_link, _) = author.seal_membership(&ann_link).unwrap();
+    assert!(author.is_membership_sealed());
+    assert!(!author.is_closed());
+    assert!(author.receive_subscribe(&latecomer_sub).is_err());
+    // A known subscriber may still re-subscribe
+    author.receive_subscribe(&member_sub).unwrap();
+
+    let (keyload_link, _) = author.send_keyload_for_everyone(&seal_link).unwrap();
+    member.receive_seal_membership(&seal_link).unwrap();
+    assert!(member.is_membership_sealed());
+    assert!(member.receive_keyload(&keyload_link).unwrap());
+    latecomer.receive_seal_membership(&seal_link).unwrap();
+    assert!(!latecomer.receive_keyload(&keyload_link).unwrap());
+
+    // Sealing is kept in backups
+    let mut restored = Author::import(&author.export("password").unwrap(), "password", transport).unwrap();
+    assert!(restored.is_membership_sealed());
+    assert!(restored.receive_subscribe(&latecomer_sub).is_err());
+}
+
+#[test]
+fn keyload_below_quorum_is_rejected() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("QUORUMAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+
+    let mut alice = Subscriber::new("QUORUMALICESEED", transport.clone());
+    let mut bob = Subscriber::new("QUORUMBOBSEED", transport.clone());
+    let alice_id = Identifier::EdPubKey((*alice.get_public_key()).into());
+    let bob_id = Identifier::EdPubKey((*bob.get_public_key()).into());
+    for subscriber in vec![&mut alice, &mut bob] {
+        subscriber.receive_announcement(&ann_link).unwrap();
+        let sub_link = subscriber.send_subscribe(&ann_link).unwrap();
+        author.receive_subscribe(&sub_link).unwrap();
+    }
+    alice.set_keyload_policy(Some(Quorum::new(vec![alice_id, bob_id], 2)));
+
+    let (partial_link, _) = author.send_keyload(&ann_link, &vec![], &vec![&alice_id]).unwrap();
+    assert!(alice.receive_keyload(&partial_link).is_err());
+
+    let (keyload_link, _) = author.send_keyload_for_everyone(&ann_link).unwrap();
+    assert!(alice.receive_keyload(&keyload_link).unwrap());
+}
+
+#[test]
+fn chain_digest_follows_processed_messages() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("DIGESTAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    let mut subscriber = Subscriber::new("DIGESTSUBSCRIBERSEED", transport.clone());
+    subscriber.receive_announcement(&ann_link).unwrap();
+    assert_eq!(author.chain_digest(), subscriber.chain_digest());
+
+    let announced = author.chain_digest();
+    author
+        .send_signed_packet(&ann_link, &Bytes(b"public".to_vec()), &Bytes::default())
+        .unwrap();
+    assert_ne!(author.chain_digest(), announced);
+    assert_ne!(author.chain_digest(), subscriber.chain_digest());
+
+    subscriber.sync_state();
+    assert_eq!(author.chain_digest(), subscriber.chain_digest());
+}
+
+#[test]
+fn dependency_edges_follow_links() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("EDGESAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    let mut subscriber = Subscriber::new("EDGESSUBSCRIBERSEED", transport.clone());
+    subscriber.receive_announcement(&ann_link).unwrap();
+    assert!(author.dependency_edges().is_empty());
+
+    let (first, _) = author
+        .send_signed_packet(&ann_link, &Bytes(b"first".to_vec()), &Bytes::default())
+        .unwrap();
+    let (second, _) = author
+        .send_signed_packet(&first, &Bytes(b"second".to_vec()), &Bytes::default())
+        .unwrap();
+    subscriber.sync_state();
+
+    for edges in [author.dependency_edges(), subscriber.dependency_edges()].iter() {
+        assert_eq!(edges.len(), 2);
+        assert!(edges.contains(&(first.msgid.clone(), ann_link.msgid.clone())));
+        assert!(edges.contains(&(second.msgid.clone(), first.msgid.clone())));
+    }
+}
+
+#[test]
+fn max_message_size_is_checked_on_send_and_receive() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("SIZEAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    let mut subscriber = Subscriber::new("SIZESUBSCRIBERSEED", transport.clone());
+    subscriber.receive_announcement(&ann_link).unwrap();
+    let large = Bytes(vec![7; 1024]);
+
+    author.set_max_message_size(512);
+    assert!(author.send_signed_packet(&ann_link, &large, &Bytes::default()).is_err());
+    author
+        .send_signed_packet(&ann_link, &Bytes(b"small".to_vec()), &Bytes::default())
+        .unwrap();
+
+    author.set_max_message_size(usize::MAX);
+    let (large_link, _) = author.send_signed_packet(&ann_link, &large, &Bytes::default()).unwrap();
+    subscriber.set_max_message_size(512);
+    assert!(subscriber.receive_signed_packet(&large_link).is_err());
+    subscriber.set_max_message_size(usize::MAX);
+    let (_, public, _) = subscriber.receive_signed_packet(&large_link).unwrap();
+    assert_eq!(public, large);
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn orphan_message_is_reported_unprocessed() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("ORPHANAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    let public = Bytes(b"public".to_vec());
+    let (first, _) = author.send_signed_packet(&ann_link, &public, &Bytes::default()).unwrap();
+    let (second, _) = author.send_signed_packet(&first, &public, &Bytes::default()).unwrap();
+
+    let mut subscriber = Subscriber::new("ORPHANSUBSCRIBERSEED", transport.clone());
+    subscriber.receive_announcement(&ann_link).unwrap();
+    let stored = subscriber.spongos_store_len();
+
+    let orphan = subscriber.receive_message(&second).unwrap();
+    assert!(orphan.body.is_orphan());
+    assert!(!orphan.body.is_accessible());
+    assert_eq!(orphan.link, second);
+    assert_eq!(orphan.prev_link, first);
+    assert_eq!(subscriber.spongos_store_len(), stored);
+
+    let parent = subscriber.receive_message(&first).unwrap();
+    assert!(matches!(parent.body, MessageContent::SignedPacket { .. }));
+    let child = subscriber.receive_message(&second).unwrap();
+    assert!(matches!(child.body, MessageContent::SignedPacket { .. }));
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn sync_persists_progress_in_batches() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("SYNCPERSISTAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    let mut link = ann_link.clone();
+    for _ in 0..5 {
+        link = author
+            .send_signed_packet(&link, &Bytes(b"public".to_vec()), &Bytes::default())
+            .unwrap()
+            .0;
+    }
+
+    let mut subscriber = Subscriber::new("SYNCPERSISTSUBSCRIBERSEED", transport.clone());
+    subscriber.receive_announcement(&ann_link).unwrap();
+    let mut backups = Vec::new();
+    let synced = subscriber
+        .sync_persisting("pwd", 2, |backup: &[u8]| {
+            backups.push(backup.to_vec());
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(synced, (5, 0));
+    assert_eq!(backups.len(), 3);
+
+    let mut resumed = Subscriber::import(&backups[0], "pwd", transport.clone()).unwrap();
+    assert_eq!(resumed.sync().unwrap(), (3, 0));
+    let mut completed = Subscriber::import(&backups[2], "pwd", transport).unwrap();
+    assert_eq!(completed.sync().unwrap(), (0, 0));
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn author_tracks_highest_acked_position() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("ACKAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    let mut subscriber = Subscriber::new("ACKSUBSCRIBERSEED", transport.clone());
+    subscriber.receive_announcement(&ann_link).unwrap();
+    let sub_link = subscriber.send_subscribe(&ann_link).unwrap();
+    author.receive_subscribe(&sub_link).unwrap();
+    let subscriber_pk = *subscriber.get_public_key();
+
+    let (keyload_link, _) = author.send_keyload_for_everyone(&ann_link).unwrap();
+    let (first, _) = author
+        .send_signed_packet(&keyload_link, &Bytes(b"first".to_vec()), &Bytes::default())
+        .unwrap();
+    let (second, _) = author
+        .send_signed_packet(&first, &Bytes(b"second".to_vec()), &Bytes::default())
+        .unwrap();
+    subscriber.sync_state();
+    assert_eq!(author.acked_position(&subscriber_pk), None);
+
+    // The subscriber acknowledges the second packet before the first one
+    let (second_ack, _) = subscriber.send_ack(&second).unwrap();
+    let (first_ack, _) = subscriber.send_ack(&first).unwrap();
+
+    let (pk, second_position) = author.receive_ack(&second_ack).unwrap();
+    assert_eq!(pk, subscriber_pk);
+    assert_eq!(author.acked_position(&subscriber_pk), Some(second_position));
+    let (_, first_position) = author.receive_ack(&first_ack).unwrap();
+    assert!(first_position < second_position);
+    assert_eq!(author.acked_position(&subscriber_pk), Some(second_position));
+}
+
+#[test]
+fn keyload_recipients_are_recorded_and_backed_up() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("RECIPIENTSAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    let mut alice = Subscriber::new("RECIPIENTSALICESEED", transport.clone());
+    let mut bob = Subscriber::new("RECIPIENTSBOBSEED", transport.clone());
+    for subscriber in vec![&mut alice, &mut bob] {
+        subscriber.receive_announcement(&ann_link).unwrap();
+        let sub_link = subscriber.send_subscribe(&ann_link).unwrap();
+        author.receive_subscribe(&sub_link).unwrap();
+    }
+    let alice_id: Identifier = (*alice.get_public_key()).into();
+    let bob_id: Identifier = (*bob.get_public_key()).into();
+
+    let (keyload_link, _) = author.send_keyload(&ann_link, &vec![], &vec![&alice_id]).unwrap();
+    assert!(author.keyload_recipients(&keyload_link.msgid) == Some(vec![alice_id]));
+    assert!(author.keyload_recipients(&ann_link.msgid).is_none());
+
+    assert!(alice.receive_keyload(&keyload_link).unwrap());
+    assert!(alice.keyload_recipients(&keyload_link.msgid) == Some(vec![alice_id]));
+    assert!(!bob.receive_keyload(&keyload_link).unwrap());
+    assert!(bob.keyload_recipients(&keyload_link.msgid).is_none());
+
+    let restored = Subscriber::import(&alice.export("pwd").unwrap(), "pwd", transport.clone()).unwrap();
+    assert!(restored.keyload_recipients(&keyload_link.msgid) == Some(vec![alice_id]));
+    let restored = Author::import(&author.export("pwd").unwrap(), "pwd", transport).unwrap();
+    assert!(restored.keyload_recipients(&keyload_link.msgid) == Some(vec![alice_id]));
+}
+
+#[test]
+fn sealed_cursor_snapshot_detects_tampering() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("SNAPAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    let mut subscriber = Subscriber::new("SNAPSUBSCRIBERSEED", transport.clone());
+    subscriber.receive_announcement(&ann_link).unwrap();
+
+    let snapshot = subscriber.sealed_cursor_snapshot("pwd").unwrap();
+    let seq_nos = |cursors: Vec<(Identifier, SeqState)>| {
+        let mut seq_nos: Vec<u32> = cursors.into_iter().map(|(_, cursor)| cursor.seq_no).collect();
+        seq_nos.sort_unstable();
+        seq_nos
+    };
+    let before = seq_nos(subscriber.cursor_snapshot());
+
+    author
+        .send_signed_packet(&ann_link, &Bytes(b"public".to_vec()), &Bytes::default())
+        .unwrap();
+    subscriber.sync_state();
+    let after = seq_nos(subscriber.cursor_snapshot());
+    assert_ne!(before, after);
+
+    let mut tampered = snapshot.clone();
+    let last = tampered.len() - 1;
+    tampered[last / 2] ^= 1;
+    assert!(subscriber.load_sealed_cursor_snapshot(&tampered, "pwd").is_err());
+    assert!(subscriber.load_sealed_cursor_snapshot(&snapshot, "wrong").is_err());
+    assert_eq!(seq_nos(subscriber.cursor_snapshot()), after);
+
+    subscriber.load_sealed_cursor_snapshot(&snapshot, "pwd").unwrap();
+    assert_eq!(seq_nos(subscriber.cursor_snapshot()), before);
+}
+
+#[test]
+fn added_author_keyload_is_accepted() {
+    let (mut author, ann_link, announcement) = announced_channel("COAUTHORAUTHORSEED");
+
+    let mut co_author = reader_of("COAUTHORSEED", &announcement);
+    let mut subscriber = reader_of("COAUTHORSUBSCRIBERSEED", &announcement);
+    let mut rogue = reader_of("COAUTHORROGUESEED", &announcement);
+    assert!(rogue.user.add_author(&ann_link, &rogue.user.sig_kp.public).is_err());
+
+    let sub_id = Identifier::EdPubKey(subscriber.user.sig_kp.public.into());
+    co_author
+        .user
+        .add_subscribers(vec![(sub_id, subscriber.user.ke_kp.1)])
+        .unwrap();
+    let keyload = co_author.user.share_keyload(&ann_link, &vec![], &vec![&sub_id]).unwrap();
+    assert!(subscriber
+        .clone()
+        .user
+        .handle_keyload(keyload.message.clone(), MsgInfo::Keyload)
+        .is_err());
+
+    // Add author message signed by a user pretending to be the author
+    rogue.user.author_sig_pk = Some(rogue.user.sig_kp.public);
+    let forged = rogue.user.add_author(&ann_link, &rogue.user.sig_kp.public).unwrap();
+    assert!(subscriber
+        .user
+        .handle_add_author(forged.message, MsgInfo::AddAuthor)
+        .is_err());
+
+    let add = author.user.add_author(&ann_link, &co_author.user.sig_kp.public).unwrap();
+    subscriber
+        .user
+        .handle_add_author(add.message, MsgInfo::AddAuthor)
+        .unwrap();
+    assert_eq!(subscriber.co_authors(), &[co_author.user.sig_kp.public]);
+    let keyload = subscriber
+        .user
+        .handle_keyload(keyload.message, MsgInfo::Keyload)
+        .unwrap();
+    assert!(keyload.body);
+
+    let exported = subscriber.export(0, "pwd").unwrap();
+    let restored = User::import(&exported, 0, "pwd", ()).unwrap();
+    assert_eq!(restored.co_authors(), subscriber.co_authors());
+}
+
+#[test]
+fn label_survives_backup() {
+    let mut user = User::new("LABELSEED", ChannelType::SingleBranch, ());
+    assert_eq!(user.label(), None);
+    let restored = User::import(&user.export(0, "pwd").unwrap(), 0, "pwd", ()).unwrap();
+    assert_eq!(restored.label(), None);
+
+    user.set_label(Some("sensor 42".to_string()));
+    let restored = User::import(&user.export(0, "pwd").unwrap(), 0, "pwd", ()).unwrap();
+    assert_eq!(restored.label(), Some("sensor 42"));
+}
+
+#[test]
+fn child_derivation_vectors() {
+    let mut secret = [0_u8; ed25519::SECRET_KEY_LENGTH];
+    for (i, b) in secret.iter_mut().enumerate() {
+        *b = i as u8;
+    }
+    let parent = User::from_ed25519_secret(secret, ChannelType::SingleBranch, ());
+    assert_eq!(
+        hex::encode(parent.get_public_key().as_bytes()),
+        "03a107bff3ce10be1d70dd18e74bc09967e4d6309ba50d5f1ddc8664125531b8"
+    );
+    let vectors = [
+        (0, "19085dd15b2536f40f029d70f6bc9f21c4d2f491e5ca6dc61b49d0ac9841a78c"),
+        (1, "3b2e440befbc1a271587f67f26ad2ef1242077830ed53520e7dc58236467b24f"),
+        (7, "9914576295412f5080032598f2b4f4825522fc34b6aa85b36f9f3bdad2d9e42e"),
+    ];
+    for (index, pk) in vectors.iter() {
+        let child = parent.derive_child(*index, ChannelType::SingleBranch, ());
+        assert_eq!(hex::encode(child.get_public_key().as_bytes()), *pk);
+    }
+
+    let mut first = parent.derive_child(0, ChannelType::SingleBranch, ());
+    let mut second = parent.derive_child(1, ChannelType::MultiBranch, ());
+    first.user.create_channel(0).unwrap();
+    second.user.create_channel(0).unwrap();
+    assert!(first.channel_address() != second.channel_address());
+}
+
+#[test]
+fn repeated_subscription_is_idempotent() {
+    let (mut author, ann_link, announcement) = announced_channel("RESUBSCRIBEAUTHORSEED");
+    let mut subscriber = reader_of("RESUBSCRIBESUBSCRIBERSEED", &announcement);
+    let subscription = subscriber.user.subscribe(&ann_link).unwrap();
+    let id = Identifier::EdPubKey((*subscriber.get_public_key()).into());
+    let seq_no = |author: &User<()>| {
+        let state = author.fetch_state().unwrap();
+        state.iter().find(|(publisher, _)| *publisher == id).unwrap().1.seq_no
+    };
+
+    author
+        .user
+        .handle_subscribe(subscription.message.clone(), MsgInfo::Subscribe)
+        .unwrap();
+    // Subscriber sequencing state moves on, eg. a packet of the subscriber is processed
+    author.store_state(id, &ann_link).unwrap();
+    let publishers = author.fetch_state().unwrap().len();
+    let advanced = seq_no(&author);
+
+    author
+        .user
+        .handle_subscribe(subscription.message, MsgInfo::Subscribe)
+        .unwrap();
+    assert_eq!(author.fetch_state().unwrap().len(), publishers);
+    assert_eq!(seq_no(&author), advanced);
+}
+
+#[test]
+fn packet_linked_to_stored_spongos() {
+    let (mut author, ann_link, announcement) = announced_channel("STORESPONGOSAUTHORSEED");
+    let mut subscriber = reader_of("STORESPONGOSSUBSCRIBERSEED", &announcement);
+    let mut unaware = subscriber.clone();
+
+    // Custom message joined to the announcement, both sides store its state
+    let custom_link = Address::new(ann_link.appinst.clone(), MsgId::from(&[7_u8; 12][..]));
+    assert!(author.spongos_for(&custom_link.msgid).is_none());
+    let spongos = author.spongos_for(&ann_link.msgid).unwrap();
+    author.store_spongos(&custom_link.msgid, spongos.clone()).unwrap();
+    subscriber.store_spongos(&custom_link.msgid, spongos).unwrap();
+
+    let packet = author
+        .user
+        .sign_packet(&custom_link, &Bytes(b"public".to_vec()), &Bytes(b"masked".to_vec()))
+        .unwrap();
+    assert!(unaware
+        .user
+        .handle_signed_packet(packet.message.clone(), MsgInfo::SignedPacket)
+        .is_err());
+    let (_pk, public, masked) = subscriber
+        .user
+        .handle_signed_packet(packet.message, MsgInfo::SignedPacket)
+        .unwrap()
+        .body;
+    assert_eq!((public.0, masked.0), (b"public".to_vec(), b"masked".to_vec()));
+}
+
+#[test]
+fn spongos_store_callback_reports_updates() {
+    let stored = Rc::new(RefCell::new(Vec::new()));
+    let observed = stored.clone();
+    let callback: SpongosStoreCallback =
+        Rc::new(move |msgid: &MsgId, _: &_, _: &MsgInfo| observed.borrow_mut().push(msgid.clone()));
+
+    let mut author = User::new("STORECALLBACKSEED", ChannelType::SingleBranch, ());
+    author.set_spongos_store_callback(Some(callback));
+    author.user.create_channel(0).unwrap();
+    let announcement = author.user.announce().unwrap();
+    let ann_link = author
+        .user
+        .commit_wrapped(announcement.wrapped, MsgInfo::Announce)
+        .unwrap();
+    let packet = author
+        .user
+        .sign_packet(&ann_link, &Bytes(b"public".to_vec()), &Bytes::default())
+        .unwrap();
+    let packet_link = author
+        .user
+        .commit_wrapped(packet.wrapped, MsgInfo::SignedPacket)
+        .unwrap();
+    assert_eq!(
+        *stored.borrow(),
+        vec![ann_link.msgid.clone(), packet_link.msgid.clone()]
+    );
+
+    author.set_spongos_store_callback(None);
+    let packet = author
+        .user
+        .sign_packet(&packet_link, &Bytes(b"public".to_vec()), &Bytes::default())
+        .unwrap();
+    author
+        .user
+        .commit_wrapped(packet.wrapped, MsgInfo::SignedPacket)
+        .unwrap();
+    assert_eq!(stored.borrow().len(), 2);
+}
+
+proptest::proptest! {
+    #![proptest_config(proptest::test_runner::Config::with_cases(64))]
+
+    #[test]
+    fn malformed_message_is_rejected_cleanly(
+        body in proptest::collection::vec(proptest::prelude::any::<u8>(), 0..256),
+        pos in proptest::prelude::any::<usize>(),
+        flip in 1_u8..,
+    ) {
+        let (mut author, ann_link, announcement) = announced_channel("FUZZAUTHORSEED");
+        let mut subscriber = reader_of("FUZZSUBSCRIBERSEED", &announcement);
+        let packet = author
+            .user
+            .sign_packet(&ann_link, &Bytes(b"public".to_vec()), &Bytes(b"masked".to_vec()))
+            .unwrap();
+
+        // Announcements are not handled as regular messages
+        let ann_msg = Message::from_raw_body(ann_link, announcement.message.body.as_bytes());
+        proptest::prop_assert!(subscriber.handle_message(ann_msg, false).is_err());
+
+        // Skipped header fields are not authenticated, a corrupted message may still be
+        // accepted, it must not panic the handler though
+        let link = packet.message.link.clone();
+        let mut corrupted = packet.message.body.as_bytes().to_vec();
+        let n = pos % corrupted.len();
+        corrupted[n] ^= flip;
+        let _ = subscriber.handle_message(Message::from_raw_body(link.clone(), corrupted), false);
+        let _ = subscriber.handle_message(Message::from_raw_body(link, body), false);
+    }
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn detached_signature_verifies() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("DETACHEDAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    let options = PacketOptions::new().with_detached_signature(true);
+    let (public, masked) = (Bytes(b"public".to_vec()), Bytes(b"masked".to_vec()));
+    let ((packet_link, _), detached) = author
+        .send_signed_packet_with(&ann_link, &public, &masked, &options)
+        .unwrap();
+    let detached = detached.unwrap();
+    let author_id: Identifier = (*author.get_public_key()).into();
+    assert!(verify_detached(&detached.preimage, &detached.signature, &author_id).is_ok());
+    assert!(detached.verify(&author_id).is_ok());
+
+    let mut tampered = detached.preimage.clone();
+    tampered[0] ^= 1;
+    assert!(verify_detached(&tampered, &detached.signature, &author_id).is_err());
+    assert!(verify_detached(&detached.preimage[1..], &detached.signature, &author_id).is_err());
+    let pskid = psk::pskid_from_seed::<DefaultF>(b"DETACHEDSECRET");
+    assert!(verify_detached(&detached.preimage, &detached.signature, &Identifier::PskId(pskid)).is_err());
+
+    // The packet sent along with the detached signature is read as any signed packet.
+    let mut subscriber = Subscriber::new("DETACHEDSUBSCRIBERSEED", transport);
+    subscriber.receive_announcement(&ann_link).unwrap();
+    let (pk, public, masked) = subscriber.receive_signed_packet(&packet_link).unwrap();
+    assert_eq!(&pk, author.get_public_key());
+    assert_eq!(public.0, b"public".to_vec());
+    assert_eq!(masked.0, b"masked".to_vec());
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn packet_options_are_combined() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("OPTIONSAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    let mut metadata = Metadata::new();
+    metadata.insert("unit", b"celsius".to_vec());
+    let context = Bytes(b"request A".to_vec());
+    let options = PacketOptions::new()
+        .with_metadata(&metadata)
+        .with_topic("temperature")
+        .with_context(context.clone())
+        .with_app_type(3)
+        .with_detached_signature(true);
+    let ((link, _), detached) = author
+        .send_signed_packet_with(&ann_link, &Bytes(b"public".to_vec()), &Bytes::default(), &options)
+        .unwrap();
+    let author_id: Identifier = (*author.get_public_key()).into();
+    assert!(detached.unwrap().verify(&author_id).is_ok());
+
+    let mut subscriber = Subscriber::new("OPTIONSSUBSCRIBERSEED", transport);
+    subscriber.receive_announcement(&ann_link).unwrap();
+    let unwrapped = subscriber.receive_msg(&link).unwrap();
+    let received = unwrapped.body.metadata().unwrap();
+    assert_eq!(received.topic(), Some("temperature"));
+    assert_eq!(received.get("unit"), Some(&b"celsius"[..]));
+    assert_eq!(unwrapped.body.context(), Some(&context));
+    assert_eq!(unwrapped.body.app_type(), 3);
+    assert_eq!(subscriber.messages_for_topic("temperature"), vec![link]);
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn provenance_chains_back_to_announcement() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("PROVENANCEAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    let mut chain = vec![ann_link.clone()];
+    for i in 0_u8..3 {
+        let (link, _) = author
+            .send_signed_packet(chain.last().unwrap(), &Bytes(vec![i]), &Bytes::default())
+            .unwrap();
+        chain.push(link);
+    }
+
+    let mut subscriber = Subscriber::new("PROVENANCESUBSCRIBERSEED", transport.clone());
+    assert!(subscriber.verify_provenance(&chain[3]).is_err());
+    subscriber.receive_announcement(&ann_link).unwrap();
+    assert_eq!(subscriber.verify_provenance(&chain[3]).unwrap(), chain);
+    // The chain is processed, verifying it again only retrieves the linked messages
+    assert_eq!(subscriber.verify_provenance(&chain[2]).unwrap(), chain[..3].to_vec());
+
+    // A missing link breaks the chain
+    transport.borrow_mut().delete_message(&chain[1]).unwrap();
+    let mut subscriber = Subscriber::new("PROVENANCESUBSCRIBERSEED2", transport);
+    subscriber.receive_announcement(&ann_link).unwrap();
+    assert!(subscriber.verify_provenance(&chain[3]).is_err());
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn public_state_observer_reads_channel() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("PUBLICSTATEAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let psk = psk::psk_from_seed::<DefaultF>(b"PUBLICSTATESECRET");
+    let pskid = psk::pskid_from_psk::<DefaultF>(&psk);
+    let ann_link = author.send_announce().unwrap();
+    author.store_psk(pskid, psk).unwrap();
+    let (first_link, _) = author
+        .send_signed_packet(&ann_link, &Bytes(b"first".to_vec()), &Bytes::default())
+        .unwrap();
+
+    let state = author.export_public_state().unwrap();
+    let contains = |secret: &[u8]| state.windows(secret.len()).any(|w| w == secret);
+    assert!(!contains(&author.user.user.sig_kp.secret.to_bytes()[..]));
+    assert!(!contains(&psk[..]));
+
+    let mut tampered = state.clone();
+    let last = tampered.len() - 1;
+    tampered[last] ^= 1;
+    assert!(Subscriber::import_public_state(&tampered, transport.clone()).is_err());
+
+    let mut observer = Subscriber::import_public_state(&state, transport).unwrap();
+    assert_eq!(observer.channel_address(), author.channel_address());
+    let (second_link, _) = author
+        .send_signed_packet(&first_link, &Bytes(b"second".to_vec()), &Bytes(b"masked".to_vec()))
+        .unwrap();
+    let (pk, public, masked) = observer.receive_signed_packet(&second_link).unwrap();
+    assert_eq!(&pk, author.get_public_key());
+    assert_eq!(public.0, b"second".to_vec());
+    assert_eq!(masked.0, b"masked".to_vec());
+
+    // The observer has no cursor of its own
+    assert!(observer
+        .send_signed_packet(&second_link, &Bytes(b"reply".to_vec()), &Bytes::default())
+        .is_err());
+}
+
+/// Transport returning no message instead of failing on addresses without messages.
+#[derive(Clone)]
+struct LenientTransport(Rc<RefCell<BucketTransport>>);
+
+impl transport::TransportOptions for LenientTransport {
+    type SendOptions = ();
+    fn get_send_options(&self) {}
+    fn set_send_options(&mut self, _opt: ()) {}
+
+    type RecvOptions = ();
+    fn get_recv_options(&self) {}
+    fn set_recv_options(&mut self, _opt: ()) {}
+}
+
+#[cfg(not(feature = "async"))]
+impl transport::TransportDetails<Address> for LenientTransport {
+    type Details = ();
+    fn get_link_details(&mut self, _link: &Address) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl transport::Transport<Address, Message> for LenientTransport {
+    fn send_message(&mut self, msg: &Message) -> Result<()> {
+        self.0.send_message(msg)
+    }
+
+    fn recv_messages(&mut self, link: &Address) -> Result<Vec<Message>> {
+        Ok(self.0.recv_messages(link).unwrap_or_default())
+    }
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn sync_to_head_stops_on_missing_addresses() {
+    let shared = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("HEADAUTHORSEED", ChannelType::SingleBranch, shared.clone());
+    let ann_link = author.send_announce().unwrap();
+    let mut link = ann_link.clone();
+    for i in 0_u8..3 {
+        link = author
+            .send_signed_packet(&link, &Bytes(vec![i]), &Bytes::default())
+            .unwrap()
+            .0;
+    }
+
+    let mut subscriber = Subscriber::new("HEADSUBSCRIBERSEED", LenientTransport(shared.clone()));
+    assert!(subscriber.sync_to_head().is_err());
+    subscriber.receive_announcement(&ann_link).unwrap();
+    subscriber.set_sync_max_misses(2);
+    let head = subscriber.sync_to_head().unwrap();
+    assert_eq!(
+        head,
+        SyncToHead {
+            fetched: 3,
+            reached_head: true,
+        }
+    );
+    assert_eq!(subscriber.sync_to_head().unwrap().fetched, 0);
+
+    // A message which can't be processed stops the sync at the miss threshold
+    let (corrupt_link, _) = author
+        .send_signed_packet(&link, &Bytes(b"corrupt".to_vec()), &Bytes::default())
+        .unwrap();
+    let msg = shared.borrow_mut().recv_message(&corrupt_link).unwrap();
+    let mut body = msg.binary.body.as_bytes().to_vec();
+    *body.last_mut().unwrap() ^= 1;
+    let corrupt = BinaryMessage::new(msg.binary.link.clone(), msg.binary.prev_link.clone(), body.into());
+    shared.borrow_mut().delete_message(&corrupt_link).unwrap();
+    shared.borrow_mut().send_message(&Message::new(corrupt)).unwrap();
+    let head = subscriber.sync_to_head().unwrap();
+    assert_eq!(
+        head,
+        SyncToHead {
+            fetched: 0,
+            reached_head: false,
+        }
+    );
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn viewing_key_grants_read_access_only() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("VIEWINGAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    let mut auditor = Subscriber::new("VIEWINGAUDITORSEED", transport.clone());
+    auditor.receive_announcement(&ann_link).unwrap();
+    let mut bystander = Subscriber::new("VIEWINGBYSTANDERSEED", transport);
+    bystander.receive_announcement(&ann_link).unwrap();
+    assert_ne!(auditor.viewing_key().as_bytes(), auditor.user.user.ke_kp.1.as_bytes());
+
+    let viewing_key = *auditor.viewing_key();
+    let (keyload_link, _) = author.send_keyload_for_viewers(&ann_link, &[viewing_key]).unwrap();
+    let (packet_link, _) = author
+        .send_signed_packet(&keyload_link, &Bytes(b"public".to_vec()), &Bytes(b"masked".to_vec()))
+        .unwrap();
+
+    assert!(auditor.receive_keyload(&keyload_link).unwrap());
+    let (_pk, public, masked) = auditor.receive_signed_packet(&packet_link).unwrap();
+    assert_eq!(public.0, b"public".to_vec());
+    assert_eq!(masked.0, b"masked".to_vec());
+    assert!(!bystander.receive_keyload(&keyload_link).unwrap());
+
+    // Viewers are recorded as recipients but no cursor is kept for them
+    let viewer = Identifier::from(viewing_key);
+    assert!(author.keyload_recipients(&keyload_link.msgid) == Some(vec![viewer]));
+    assert!(!author.user.user.key_store.contains(&viewer));
+    assert!(!auditor.user.user.key_store.contains(&viewer));
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn viewing_keys_in_regular_keyload_and_read_only_user() {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let mut author = Author::new("VIEWERSAUTHORSEED", ChannelType::SingleBranch, transport.clone());
+    let ann_link = author.send_announce().unwrap();
+    let mut subscriber = Subscriber::new("VIEWERSSUBSCRIBERSEED", transport.clone());
+    subscriber.receive_announcement(&ann_link).unwrap();
+    let subscribe_link = subscriber.send_subscribe(&ann_link).unwrap();
+    author.receive_subscribe(&subscribe_link).unwrap();
+    let auditor = Subscriber::new("VIEWERSAUDITORSEED", transport.clone());
+
+    // Only the viewing secret is handed over, not the auditor's signature key
+    let mut viewer = Subscriber::from_viewing_secret(auditor.viewing_secret(), transport.clone());
+    assert_eq!(viewer.viewing_key().as_bytes(), auditor.viewing_key().as_bytes());
+    assert_ne!(viewer.get_public_key(), auditor.get_public_key());
+    viewer.receive_announcement(&ann_link).unwrap();
+
+    let subscriber_id = Identifier::EdPubKey((*subscriber.get_public_key()).into());
+    let viewer_id = Identifier::from(*auditor.viewing_key());
+    let (keyload_link, _) = author
+        .send_keyload(&ann_link, &vec![], &vec![&subscriber_id, &viewer_id])
+        .unwrap();
+    let (packet_link, _) = author
+        .send_signed_packet(&keyload_link, &Bytes(b"public".to_vec()), &Bytes(b"masked".to_vec()))
+        .unwrap();
+    assert!(author.keyload_recipients(&keyload_link.msgid) == Some(vec![subscriber_id, viewer_id]));
+
+    assert!(subscriber.receive_keyload(&keyload_link).unwrap());
+    assert!(viewer.receive_keyload(&keyload_link).unwrap());
+    let (_pk, _public, masked) = viewer.receive_signed_packet(&packet_link).unwrap();
+    assert_eq!(masked.0, b"masked".to_vec());
+
+    // The viewing key survives backups of the read-only user
+    let backup = viewer.export("viewer password").unwrap();
+    let restored = Subscriber::import(&backup, "viewer password", transport).unwrap();
+    assert_eq!(restored.viewing_key().as_bytes(), auditor.viewing_key().as_bytes());
+}
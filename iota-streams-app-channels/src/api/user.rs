@@ -19,9 +19,23 @@ use iota_streams_app::{
 use iota_streams_core::{
     err,
     prelude::{
-        string::ToString,
-        typenum::U32,
+        generic_array::{
+            ArrayLength,
+            GenericArray,
+        },
+        string::{
+            String,
+            ToString,
+        },
+        typenum::{
+            Unsigned as _,
+            U16,
+            U32,
+        },
         vec,
+        BTreeMap,
+        HashMap,
+        Rc,
         Vec,
     },
     prng,
@@ -30,9 +44,12 @@ use iota_streams_core::{
         Psk,
         PskId,
     },
-    sponge::prp::{
-        Inner,
-        PRP,
+    sponge::{
+        prp::{
+            Inner,
+            PRP,
+        },
+        spongos,
     },
     try_or,
     Errors::*,
@@ -54,15 +71,107 @@ use iota_streams_ddml::{
 
 use crate::{
     api::{
+        compression::{
+            self,
+            Compressor,
+        },
+        detached::{
+            DetachedSignature,
+            DetachingSigner,
+        },
         key_store::*,
+        keyload_policy::KeyloadPolicy,
+        packet_options::PacketOptions,
+        payload_codec::PayloadCodec,
         ChannelType,
     },
     message::*,
 };
 
+// A cursor stores the sequence number of the next message of its publisher, the numbers below
+// `INIT_MESSAGE_NUM` are reserved.
+
+/// Sequence number of the announcement.
 const ANN_MESSAGE_NUM: u32 = 0;
+/// Sequence number of subscribe messages, they are not sequenced.
 const SUB_MESSAGE_NUM: u32 = 0;
+/// Sequence number of sequence messages of multi-branch channels, they are derived from the last
+/// message of the publisher.
 const SEQ_MESSAGE_NUM: u32 = 1;
+/// Sequence number of the first message of a publisher, the initial cursor of every publisher
+/// once the announcement is processed.
+const INIT_MESSAGE_NUM: u32 = 2;
+
+/// Domain of child signature key pairs derived with `User::derive_child`.
+const CHILD_KEYPAIR_DOMAIN: &str = "IOTA Streams Channels child sig keypair";
+
+/// Domain of viewing key pairs derived from the user secret key, see `User::viewing_key`.
+const VIEWING_KEYPAIR_DOMAIN: &str = "IOTA Streams Channels viewing keypair";
+
+/// Derive the X25519 viewing key pair of a signature key pair. The viewing key pair is derived
+/// with the spongos based PRNG, neither the signature key pair nor the X25519 key pair derived from
+/// it can be recovered from it.
+fn viewing_keypair<F: PRP>(sig_kp: &ed25519::Keypair) -> (x25519::StaticSecret, x25519::PublicKey) {
+    let prng = prng::Prng::<F>::init_with_seed(sig_kp.secret.as_bytes());
+    let mut secret = [0_u8; 32];
+    prng.gen(VIEWING_KEYPAIR_DOMAIN.as_bytes(), &mut secret[..]);
+    let sk = x25519::StaticSecret::from(secret);
+    let pk = x25519::PublicKey::from(&sk);
+    (sk, pk)
+}
+
+/// Default length of the MAC protecting exported user state.
+pub const DEFAULT_BACKUP_MAC_LEN: usize = 32;
+/// Minimal supported length of the MAC protecting exported user state.
+pub const MIN_BACKUP_MAC_LEN: usize = 16;
+
+/// Version of the legacy backup format, the key is derived from the password only.
+const BACKUP_VERSION_UNSALTED: u8 = 0;
+/// Version of the backup format without added authors, the key is derived from the password and a
+/// random salt stored in the header.
+const BACKUP_VERSION_NO_CO_AUTHORS: u8 = 1;
+/// Version of the backup format without the user label.
+const BACKUP_VERSION_NO_LABEL: u8 = 2;
+/// Version of the backup format without subscriber groups.
+const BACKUP_VERSION_NO_GROUPS: u8 = 3;
+/// Version of the backup format without keyload recipients.
+const BACKUP_VERSION_NO_KEYLOAD_RECIPIENTS: u8 = 4;
+/// Version of the backup format without the connection history.
+const BACKUP_VERSION_NO_CONNECTION_HISTORY: u8 = 5;
+/// Version of the backup format without the membership sealing and channel closing flags.
+const BACKUP_VERSION_NO_SEALED: u8 = 6;
+/// Version of the backup format without the MAC length in the header, the MAC length has to be
+/// known on import.
+const BACKUP_VERSION_NO_MAC_LEN: u8 = 7;
+/// Version of the backup format without the viewing key, it's derived from the user secret key.
+const BACKUP_VERSION_NO_VIEWING_KEY: u8 = 8;
+/// Version of the backup format without stashed channels.
+const BACKUP_VERSION_NO_STASHED_CHANNELS: u8 = 9;
+/// Version of the backup format without the network domain, addresses are derived in the empty
+/// domain.
+const BACKUP_VERSION_NO_NETWORK_DOMAIN: u8 = 10;
+/// Version of the backup format without the topic index of the channels.
+const BACKUP_VERSION_NO_TOPICS: u8 = 11;
+/// Version of the backup format, the key is derived from the password and a random salt stored in
+/// the header along with the MAC length.
+const BACKUP_VERSION: u8 = 12;
+/// Version of the public channel state format exported for observers.
+const PUBLIC_STATE_VERSION: u8 = 0;
+
+/// Default number of channels kept in the connection history.
+pub const DEFAULT_MAX_CONNECTION_HISTORY: usize = 16;
+
+/// Version of the sealed cursor snapshot format.
+const SEALED_SNAPSHOT_VERSION: u8 = 0;
+/// Length of the MAC protecting sealed cursor snapshots.
+const SEALED_SNAPSHOT_MAC_LEN: usize = 32;
+
+/// Limit of stores pre-sized on import: declared counts are not authenticated until the backup MAC
+/// at the end is checked, larger stores grow as usual.
+const MAX_RESTORE_RESERVE: usize = 1 << 16;
+
+/// Payload of subscriptions sent without one, it is not encoded.
+static EMPTY_PAYLOAD: Bytes = Bytes(Vec::new());
 
 /// Wrapped sequencing information with optional WrapState
 pub struct WrapStateSequence<F, Link: HasLink>(
@@ -121,6 +230,153 @@ impl<F, Link: HasLink> WrappedSequence<F, Link> {
     }
 }
 
+/// The way the user has been granted access to the session key of a keyload, see
+/// `User::keyload_access`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum KeyloadAccess {
+    /// User is not among the keyload recipients.
+    Denied,
+    /// Session key was unwrapped with the user's own X25519 key.
+    Identity,
+    /// Session key was unwrapped with the stored pre-shared key.
+    Psk(PskId),
+    /// Session key was unwrapped with the user's viewing key.
+    Viewing,
+}
+
+impl KeyloadAccess {
+    /// Returns true if the user is among the keyload recipients.
+    pub fn is_granted(&self) -> bool {
+        !matches!(self, KeyloadAccess::Denied)
+    }
+}
+
+/// Author and channel details read from an announcement, see `User::peek_announcement`.
+#[derive(Clone)]
+pub struct AnnouncementInfo {
+    /// Ed25519 public key of the channel author, the announcement is signed with it.
+    pub author_sig_pk: ed25519::PublicKey,
+    /// X25519 public key of the channel author, subscriptions are encrypted with it.
+    pub author_ke_pk: x25519::PublicKey,
+    /// Channel flags, eg. `FLAG_BRANCHING_MASK` for multi-branch channels.
+    pub flags: u8,
+    /// Channel metadata published with the announcement, if any.
+    pub metadata: Option<Bytes>,
+}
+
+/// Summary of exported user state, see `User::verify_backup`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BackupInfo {
+    /// Version of the backup format.
+    pub version: u8,
+    /// The user is connected to a channel.
+    pub channel: bool,
+    /// Number of spongos states of processed messages.
+    pub spongos: usize,
+    /// Number of publisher cursors.
+    pub cursors: usize,
+    /// Number of pre-shared key identifiers.
+    pub psks: usize,
+    /// Label of the user, if any.
+    pub label: Option<String>,
+}
+
+/// Differences between the views two users have of a channel, see `User::diff`. Spongos states
+/// are compared but only the links of differing ones are reported, no key material is included.
+#[derive(Clone)]
+pub struct StateDiff<Rel> {
+    /// The users are not connected to the same channel.
+    pub appinst_differs: bool,
+    /// Publishers and pre-shared keys only known to this user.
+    pub only_here: Vec<Identifier>,
+    /// Publishers and pre-shared keys only known to the other user.
+    pub only_there: Vec<Identifier>,
+    /// Publishers known to both users with different cursors: this user's one, then the other's.
+    pub cursors: Vec<(Identifier, Cursor<Rel>, Cursor<Rel>)>,
+    /// Subscribers known to both users with different key exchange public keys.
+    pub exchange_keys: Vec<Identifier>,
+    /// Messages whose spongos state is only stored by this user.
+    pub links_only_here: Vec<Rel>,
+    /// Messages whose spongos state is only stored by the other user.
+    pub links_only_there: Vec<Rel>,
+    /// Messages stored by both users with different spongos states.
+    pub spongos_mismatches: Vec<Rel>,
+}
+
+impl<Rel> StateDiff<Rel> {
+    /// Both users have the same view of the channel.
+    pub fn is_empty(&self) -> bool {
+        !self.appinst_differs
+            && self.only_here.is_empty()
+            && self.only_there.is_empty()
+            && self.cursors.is_empty()
+            && self.exchange_keys.is_empty()
+            && self.links_only_here.is_empty()
+            && self.links_only_there.is_empty()
+            && self.spongos_mismatches.is_empty()
+    }
+}
+
+/// Channel specific part of the user state. Own keys and settings are not included.
+///
+/// A user has a single active channel, all send and handle methods act on it. Other channels are
+/// kept detached, with `take_channel` or `stash_channel`, and one of them is made active again with
+/// `put_channel` or `switch_channel`: own keys are shared, but channels are not addressed per call.
+#[derive(Clone)]
+pub struct ChannelState<F, Link, LS, Keys> {
+    pub(crate) appinst: Link,
+    pub(crate) author_sig_pk: Option<ed25519::PublicKey>,
+    pub(crate) co_authors: Vec<ed25519::PublicKey>,
+    pub(crate) subscriber_groups: BTreeMap<String, Vec<Identifier>>,
+    pub(crate) keyload_recipients: Vec<(Link, Vec<Identifier>)>,
+    pub(crate) key_store: Keys,
+    pub(crate) link_store: LS,
+    pub(crate) flags: u8,
+    pub(crate) use_psk: bool,
+    pub(crate) last_keyload_fingerprint: Option<NBytes<U32>>,
+    pub(crate) announcement_metadata: Option<Bytes>,
+    pub(crate) announcement_context: Option<announce::ContextDigest>,
+    pub(crate) closed: bool,
+    pub(crate) membership_sealed: bool,
+    pub(crate) acks: HashMap<Identifier, usize>,
+    pub(crate) topics: BTreeMap<String, Vec<Link>>,
+    _phantom: core::marker::PhantomData<F>,
+}
+
+impl<F, Link, LS, Keys> ChannelState<F, Link, LS, Keys> {
+    /// Application instance of the channel.
+    pub fn appinst(&self) -> &Link {
+        &self.appinst
+    }
+}
+
+/// Derive the key protecting exported user state from the password, and the salt of salted backups.
+fn backup_key<F: PRP>(pwd: &str, salt: Option<&NBytes<U16>>) -> NBytes<U32> {
+    let prng = prng::from_seed::<F>("IOTA Streams Channels app", pwd);
+    let mut nonce = b"user export key".to_vec();
+    if let Some(salt) = salt {
+        nonce.extend_from_slice(salt.as_ref());
+    }
+    NBytes(prng.gen_arr(nonce))
+}
+
+/// Derive the key protecting sealed cursor snapshots from the password and the snapshot salt.
+fn snapshot_key<F: PRP>(pwd: &str, salt: &NBytes<U16>) -> NBytes<U32> {
+    let prng = prng::from_seed::<F>("IOTA Streams Channels app", pwd);
+    let mut nonce = b"cursor snapshot key".to_vec();
+    nonce.extend_from_slice(salt.as_ref());
+    NBytes(prng.gen_arr(nonce))
+}
+
+/// Backup MAC is squeezed from spongos, its length is limited by spongos capacity.
+fn check_backup_mac_len<F: PRP>(mac_len: usize) -> Result<()> {
+    let max_len = spongos::MacSize::<F>::USIZE;
+    try_or!(
+        MIN_BACKUP_MAC_LEN <= mac_len && mac_len <= max_len,
+        BackupMacLengthUnsupported(MIN_BACKUP_MAC_LEN, max_len, mac_len)
+    )
+}
+
 pub struct User<F, Link, LG, LS, Keys>
 where
     F: PRP,
@@ -136,12 +392,24 @@ where
     /// Own x25519 key pair corresponding to Ed25519 keypair.
     pub(crate) ke_kp: (x25519::StaticSecret, x25519::PublicKey),
 
+    /// Own x25519 viewing key pair, granting read access only, derived from the Ed25519 keypair.
+    pub(crate) viewing_kp: (x25519::StaticSecret, x25519::PublicKey),
+
     /// Users' trusted public keys together with additional sequencing info: (msgid, seq_no).
     pub(crate) key_store: Keys,
 
     /// Author's Ed25519 public key.
     pub(crate) author_sig_pk: Option<ed25519::PublicKey>,
 
+    /// Ed25519 public keys of authors added to the channel by a trusted author.
+    pub(crate) co_authors: Vec<ed25519::PublicKey>,
+
+    /// Named groups of recipients keyloads can be sent to.
+    pub(crate) subscriber_groups: BTreeMap<String, Vec<Identifier>>,
+
+    /// Recipients of the keyloads sent or processed in the current channel.
+    pub(crate) keyload_recipients: Vec<(Link, Vec<Identifier>)>,
+
     /// Link generator.
     pub(crate) link_gen: LG,
 
@@ -152,6 +420,12 @@ where
     /// None if channel is not created or user is not subscribed.
     pub(crate) appinst: Option<Link>,
 
+    /// Channels the user has been connected to, least recently connected first.
+    pub(crate) connection_history: Vec<Link>,
+
+    /// Maximum number of channels kept in the connection history.
+    pub(crate) max_connection_history: usize,
+
     /// Flags bit field
     pub flags: u8,
 
@@ -160,6 +434,84 @@ where
     pub message_encoding: Vec<u8>,
 
     pub uniform_payload_length: usize,
+
+    /// Length of the MAC protecting exported user state.
+    pub(crate) backup_mac_len: usize,
+
+    /// Attach the fingerprint of the linked message state to sent messages.
+    pub(crate) link_fingerprint: bool,
+
+    /// Maximum length of public and masked payloads accepted in received packets.
+    pub(crate) max_payload_length: usize,
+
+    /// Fingerprint of the recipients of the last keyload sent to the current channel.
+    pub(crate) last_keyload_fingerprint: Option<NBytes<U32>>,
+
+    /// Application metadata carried by the announcement of the current channel.
+    pub(crate) announcement_metadata: Option<Bytes>,
+
+    /// Digest of the external context the announcement of the current channel is bound to.
+    pub(crate) announcement_context: Option<announce::ContextDigest>,
+
+    /// The current channel was closed by its author.
+    pub(crate) closed: bool,
+
+    /// Membership of the current channel was sealed by its author, subscriptions are rejected.
+    pub(crate) membership_sealed: bool,
+
+    /// Highest positions acknowledged by the publishers of processed ack messages.
+    pub(crate) acks: HashMap<Identifier, usize>,
+
+    /// Links of processed signed packets by topic, in processing order.
+    pub(crate) topics: BTreeMap<String, Vec<Link>>,
+
+    /// Reject signed packets from publishers not known from processed keyloads and subscriptions.
+    pub(crate) enforce_known_publishers: bool,
+
+    /// Reject packets not following the last processed message of their publisher.
+    pub(crate) strict_sequencing: bool,
+
+    /// Topics whose masked payloads are unmasked, all topics are followed if not set.
+    pub(crate) followed_topics: Option<Vec<String>>,
+
+    /// Public keys of channel authors announcements are accepted from, any author is trusted if not set.
+    pub(crate) trusted_authors: Option<Vec<ed25519::PublicKey>>,
+
+    /// Codec compressing public payloads of sent packets and decompressing received ones.
+    pub(crate) compressor: Option<Rc<dyn Compressor>>,
+
+    /// Codec encoding payloads of sent packets and decoding received ones.
+    pub(crate) payload_codec: Option<Rc<dyn PayloadCodec>>,
+
+    /// Policy recipients of received keyloads are checked against, any keyload is accepted if not set.
+    pub(crate) keyload_policy: Option<Rc<dyn KeyloadPolicy>>,
+
+    /// PRNG per-message randomness is derived from instead of the system RNG, if set.
+    pub(crate) deterministic_prng: Option<prng::Prng<F>>,
+
+    /// Maximum number of recipients of sent keyloads.
+    pub(crate) max_keyload_subscribers: usize,
+
+    /// Maximum size in bytes of sent and received message bodies.
+    pub(crate) max_message_size: usize,
+
+    /// Length of blocks masked payloads of packets are split into, not split if not set.
+    pub(crate) masked_block_size: Option<usize>,
+
+    /// Length in bytes of MACs of sent tagged packets, see `set_tag_mac_length`.
+    pub(crate) tag_mac_length: usize,
+
+    /// Application defined label of the user, kept in backups.
+    pub(crate) label: Option<String>,
+
+    /// Sizes of the last sent message bodies by content type, see `set_wrap_size_hints`.
+    pub(crate) wrap_size_hints: Option<RefCell<HashMap<u8, usize>>>,
+
+    /// Signer of sent signed packets in place of the user key pair, eg. backed by an HSM, if set.
+    pub(crate) signer: Option<Rc<dyn Signer>>,
+
+    /// Detached states of other channels managed by the user, kept in backups.
+    pub(crate) stashed_channels: Vec<ChannelState<F, Link, LS, Keys>>,
 }
 
 impl<F, Link, LG, LS, Keys> Default for User<F, Link, LG, LS, Keys>
@@ -176,25 +528,145 @@ where
             public: ed25519::PublicKey::default(),
         };
         let ke_kp = x25519::keypair_from_ed25519(&sig_kp);
+        let viewing_kp = viewing_keypair::<F>(&sig_kp);
 
         Self {
             _phantom: core::marker::PhantomData,
             sig_kp,
             ke_kp,
+            viewing_kp,
 
             key_store: Keys::default(),
             author_sig_pk: None,
+            co_authors: Vec::new(),
+            subscriber_groups: BTreeMap::new(),
+            keyload_recipients: Vec::new(),
             link_gen: LG::default(),
             link_store: RefCell::new(LS::default()),
             appinst: None,
+            connection_history: Vec::new(),
+            max_connection_history: DEFAULT_MAX_CONNECTION_HISTORY,
             flags: 0,
             message_encoding: Vec::new(),
             uniform_payload_length: 0,
             use_psk: false,
+            backup_mac_len: DEFAULT_BACKUP_MAC_LEN,
+            link_fingerprint: false,
+            max_payload_length: usize::MAX,
+            last_keyload_fingerprint: None,
+            announcement_metadata: None,
+            announcement_context: None,
+            closed: false,
+            membership_sealed: false,
+            acks: HashMap::new(),
+            topics: BTreeMap::new(),
+            enforce_known_publishers: false,
+            strict_sequencing: false,
+            followed_topics: None,
+            trusted_authors: None,
+            compressor: None,
+            payload_codec: None,
+            keyload_policy: None,
+            deterministic_prng: None,
+            max_keyload_subscribers: usize::MAX,
+            max_message_size: usize::MAX,
+            masked_block_size: None,
+            tag_mac_length: spongos::MacSize::<F>::USIZE,
+            label: None,
+            wrap_size_hints: None,
+            signer: None,
+            stashed_channels: Vec::new(),
+        }
+    }
+}
+
+impl<F, Link, LG, LS, Keys> Clone for User<F, Link, LG, LS, Keys>
+where
+    F: PRP,
+    Link: HasLink + Clone,
+    LG: Clone,
+    LS: Clone,
+    Keys: Clone,
+{
+    /// Deep copy of the user state. `ed25519::Keypair` is not `Clone` and is rebuilt from the secret key.
+    fn clone(&self) -> Self {
+        Self {
+            _phantom: core::marker::PhantomData,
+            sig_kp: ed25519::keypair_from_secret(self.sig_kp.secret.to_bytes()),
+            ke_kp: self.ke_kp.clone(),
+            viewing_kp: self.viewing_kp.clone(),
+            key_store: self.key_store.clone(),
+            author_sig_pk: self.author_sig_pk,
+            co_authors: self.co_authors.clone(),
+            subscriber_groups: self.subscriber_groups.clone(),
+            keyload_recipients: self.keyload_recipients.clone(),
+            link_gen: self.link_gen.clone(),
+            link_store: self.link_store.clone(),
+            appinst: self.appinst.clone(),
+            connection_history: self.connection_history.clone(),
+            max_connection_history: self.max_connection_history,
+            flags: self.flags,
+            use_psk: self.use_psk,
+            message_encoding: self.message_encoding.clone(),
+            uniform_payload_length: self.uniform_payload_length,
+            backup_mac_len: self.backup_mac_len,
+            link_fingerprint: self.link_fingerprint,
+            max_payload_length: self.max_payload_length,
+            last_keyload_fingerprint: self.last_keyload_fingerprint.clone(),
+            announcement_metadata: self.announcement_metadata.clone(),
+            announcement_context: self.announcement_context.clone(),
+            closed: self.closed,
+            membership_sealed: self.membership_sealed,
+            acks: self.acks.clone(),
+            topics: self.topics.clone(),
+            enforce_known_publishers: self.enforce_known_publishers,
+            strict_sequencing: self.strict_sequencing,
+            followed_topics: self.followed_topics.clone(),
+            trusted_authors: self.trusted_authors.clone(),
+            compressor: self.compressor.clone(),
+            payload_codec: self.payload_codec.clone(),
+            keyload_policy: self.keyload_policy.clone(),
+            deterministic_prng: self.deterministic_prng.clone(),
+            max_keyload_subscribers: self.max_keyload_subscribers,
+            max_message_size: self.max_message_size,
+            masked_block_size: self.masked_block_size,
+            tag_mac_length: self.tag_mac_length,
+            label: self.label.clone(),
+            wrap_size_hints: self.wrap_size_hints.clone(),
+            signer: self.signer.clone(),
+            stashed_channels: self.stashed_channels.clone(),
         }
     }
 }
 
+/// Marker displayed in place of secret key material.
+const REDACTED: &str = "<redacted>";
+
+impl<F, Link, LG, LS, Keys> fmt::Debug for User<F, Link, LG, LS, Keys>
+where
+    F: PRP,
+    Link: HasLink + fmt::Debug,
+    Keys: fmt::Display,
+{
+    /// Private Ed25519 and X25519 keys are never displayed, only the corresponding public keys are.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("User")
+            .field("sig_pk", &hex::encode(self.sig_kp.public.as_bytes()))
+            .field("sig_sk", &format_args!("{}", REDACTED))
+            .field("ke_pk", &hex::encode(self.ke_kp.1.as_bytes()))
+            .field("ke_sk", &format_args!("{}", REDACTED))
+            .field(
+                "author_sig_pk",
+                &self.author_sig_pk.as_ref().map(|pk| hex::encode(pk.as_bytes())),
+            )
+            .field("appinst", &self.appinst)
+            .field("key_store", &format_args!("{}", self.key_store))
+            .field("flags", &self.flags)
+            .field("use_psk", &self.use_psk)
+            .finish()
+    }
+}
+
 impl<F, Link, LG, LS, Keys> User<F, Link, LG, LS, Keys>
 where
     F: PRP,
@@ -214,7 +686,80 @@ where
         uniform_payload_length: usize,
     ) -> Self {
         let sig_kp = ed25519::Keypair::generate(&mut prng::Rng::new(prng, nonce));
+        Self::from_keypair(sig_kp, channel_type, message_encoding, uniform_payload_length)
+    }
+
+    /// Create a new User from an existing raw Ed25519 secret key (eg. exported from an HSM or another
+    /// library), corresponding X25519 key pair is derived the same way as in `gen`.
+    pub fn from_ed25519_secret(
+        secret: [u8; ed25519::SECRET_KEY_LENGTH],
+        channel_type: ChannelType,
+        message_encoding: Vec<u8>,
+        uniform_payload_length: usize,
+    ) -> Self {
+        let sig_kp = ed25519::keypair_from_secret(secret);
+        Self::from_keypair(sig_kp, channel_type, message_encoding, uniform_payload_length)
+    }
+
+    /// X25519 viewing key of the user. It's derived from the user secret key but independent of
+    /// the user identity: targeted by a keyload, it grants read access without making the user a
+    /// publisher, and it can't sign nor be linked to the user public key.
+    pub fn viewing_key(&self) -> &x25519::PublicKey {
+        &self.viewing_kp.1
+    }
+
+    /// Secret of the user viewing key, a read-only user is created from it with
+    /// `from_viewing_secret`.
+    pub fn viewing_secret(&self) -> [u8; 32] {
+        self.viewing_kp.0.to_bytes()
+    }
+
+    /// Create a read-only User holding the viewing key pair of another user only.
+    ///
+    /// The user gets a fresh random signature key pair unknown to the channel, so it can't
+    /// publish on behalf of the viewing key holder. It reads the masked payloads protected by
+    /// keyloads shared with the viewing key.
+    pub fn from_viewing_secret(
+        secret: [u8; 32],
+        channel_type: ChannelType,
+        message_encoding: Vec<u8>,
+        uniform_payload_length: usize,
+    ) -> Self {
+        let mut sig_secret = [0_u8; ed25519::SECRET_KEY_LENGTH];
+        sig_secret.copy_from_slice(&prng::random_key());
+        let mut user = Self::from_ed25519_secret(sig_secret, channel_type, message_encoding, uniform_payload_length);
+        let sk = x25519::StaticSecret::from(secret);
+        let pk = x25519::PublicKey::from(&sk);
+        user.viewing_kp = (sk, pk);
+        user
+    }
+
+    /// Derive a child User with an independent key pair, eg. to author several channels all
+    /// recoverable from one secret. The child secret key is derived from the user secret key and
+    /// `index` with the spongos based PRNG, so children can't be linked to each other nor to the
+    /// parent without the parent secret key. Settings and channel state are not inherited.
+    pub fn derive_child(&self, index: u32, channel_type: ChannelType) -> Self {
+        let prng = prng::Prng::<F>::init_with_seed(self.sig_kp.secret.as_bytes());
+        let mut nonce = CHILD_KEYPAIR_DOMAIN.as_bytes().to_vec();
+        nonce.extend_from_slice(&index.to_be_bytes());
+        let mut secret = [0_u8; ed25519::SECRET_KEY_LENGTH];
+        prng.gen(&nonce, &mut secret[..]);
+        Self::from_ed25519_secret(
+            secret,
+            channel_type,
+            self.message_encoding.clone(),
+            self.uniform_payload_length,
+        )
+    }
+
+    fn from_keypair(
+        sig_kp: ed25519::Keypair,
+        channel_type: ChannelType,
+        message_encoding: Vec<u8>,
+        uniform_payload_length: usize,
+    ) -> Self {
         let ke_kp = x25519::keypair_from_ed25519(&sig_kp);
+        let viewing_kp = viewing_keypair::<F>(&sig_kp);
 
         let flags: u8 = match channel_type {
             ChannelType::SingleBranch => 0,
@@ -226,16 +771,48 @@ where
             _phantom: core::marker::PhantomData,
             sig_kp,
             ke_kp,
+            viewing_kp,
 
             key_store: Keys::default(),
             author_sig_pk: None,
+            co_authors: Vec::new(),
+            subscriber_groups: BTreeMap::new(),
+            keyload_recipients: Vec::new(),
             link_gen: LG::default(),
             link_store: RefCell::new(LS::default()),
             appinst: None,
+            connection_history: Vec::new(),
+            max_connection_history: DEFAULT_MAX_CONNECTION_HISTORY,
             flags,
             message_encoding,
             uniform_payload_length,
             use_psk: false,
+            backup_mac_len: DEFAULT_BACKUP_MAC_LEN,
+            link_fingerprint: false,
+            max_payload_length: usize::MAX,
+            last_keyload_fingerprint: None,
+            announcement_metadata: None,
+            announcement_context: None,
+            closed: false,
+            membership_sealed: false,
+            acks: HashMap::new(),
+            topics: BTreeMap::new(),
+            enforce_known_publishers: false,
+            strict_sequencing: false,
+            followed_topics: None,
+            trusted_authors: None,
+            compressor: None,
+            payload_codec: None,
+            keyload_policy: None,
+            deterministic_prng: None,
+            max_keyload_subscribers: usize::MAX,
+            max_message_size: usize::MAX,
+            masked_block_size: None,
+            tag_mac_length: spongos::MacSize::<F>::USIZE,
+            label: None,
+            wrap_size_hints: None,
+            signer: None,
+            stashed_channels: Vec::new(),
         }
     }
 
@@ -251,24 +828,419 @@ where
 
         let identifier = self.sig_kp.public.into();
         self.key_store
-            .insert_cursor(identifier, Cursor::new_at(appinst.rel().clone(), 0, 2_u32))?;
+            .insert_cursor(identifier, Cursor::new_at(appinst.rel().clone(), 0, INIT_MESSAGE_NUM))?;
         self.author_sig_pk = Some(self.sig_kp.public);
+        self.record_connection(&appinst);
         self.appinst = Some(appinst);
         Ok(())
     }
 
+    /// Create a new channel announced with application metadata, eg. channel name, description
+    /// or schema version. The metadata is authenticated by the announcement signature and is
+    /// available to subscribers with `announcement_metadata` once the announcement is handled.
+    ///
+    /// Note: the metadata is not part of the exported user state.
+    pub fn create_channel_with_metadata(&mut self, channel_idx: u64, metadata: &[u8]) -> Result<()> {
+        self.create_channel(channel_idx)?;
+        self.announcement_metadata = Some(Bytes(metadata.to_vec()));
+        Ok(())
+    }
+
+    /// Application metadata carried by the announcement of the current channel, if any.
+    pub fn announcement_metadata(&self) -> Option<&[u8]> {
+        self.announcement_metadata.as_ref().map(|metadata| &metadata.0[..])
+    }
+
+    /// Create a new channel with the announcement bound to a context shared out-of-band with the
+    /// subscribers, eg. a secret. The context is not sent: subscribers must set the same context
+    /// with `set_announcement_context` before handling the announcement, subscribers without the
+    /// context cannot validate the announcement.
+    ///
+    /// Note: the context is not part of the exported user state.
+    pub fn create_channel_with_context(&mut self, channel_idx: u64, context: &[u8]) -> Result<()> {
+        self.create_channel(channel_idx)?;
+        self.set_announcement_context(Some(context));
+        Ok(())
+    }
+
+    /// Set the context shared out-of-band the announcement is expected to be bound to, `None` if
+    /// the announcement is not bound to a context. Announcements bound to another context, or to
+    /// no context, fail verification.
+    pub fn set_announcement_context(&mut self, context: Option<&[u8]>) {
+        self.announcement_context = context.map(|context| {
+            let mut s = spongos::Spongos::<F>::init();
+            s.absorb(b"announcement context");
+            s.absorb(context);
+            s.commit();
+            NBytes(s.squeeze_arr())
+        });
+    }
+
+    /// Set the length of the MAC protecting exported user state, the length is stored in the backup header.
+    pub fn set_backup_mac_len(&mut self, mac_len: usize) -> Result<()> {
+        check_backup_mac_len::<F>(mac_len)?;
+        self.backup_mac_len = mac_len;
+        Ok(())
+    }
+
+    /// Attach the fingerprint of the linked message state to sent keyloads, packets and subscriptions.
+    /// Readers joined to a wrong linked state then get a distinct error instead of a failed
+    /// verification. Such messages can't be read by older versions.
+    ///
+    /// The check is advisory: the header bit is skipped, not absorbed, so a message stripped of the
+    /// bit and the fingerprint is unwrapped without the check. It then fails the content MAC or
+    /// signature as it would without the fingerprint, integrity doesn't rest on it.
+    pub fn set_link_fingerprint(&mut self, link_fingerprint: bool) {
+        self.link_fingerprint = link_fingerprint;
+    }
+
+    /// Limit the length of public and masked payloads of received signed and tagged packets.
+    /// Declared lengths are checked before allocation, longer payloads are rejected.
+    pub fn set_max_payload_length(&mut self, max_payload_length: usize) {
+        self.max_payload_length = max_payload_length;
+    }
+
+    /// Limit the number of recipients, subscribers and pre-shared keys, of sent keyloads. Keyloads
+    /// for more recipients fail with `TooManySubscribers` before wrapping, so that applications can
+    /// split recipients across several keyloads instead of failing at send time. Not limited by default.
+    pub fn set_max_keyload_subscribers(&mut self, max_keyload_subscribers: usize) {
+        self.max_keyload_subscribers = max_keyload_subscribers;
+    }
+
+    /// Limit the size in bytes of message bodies. Sent messages exceeding the limit fail with
+    /// `MessageSizeExceeded` before their buffer is allocated, received ones are rejected before
+    /// unwrapping. Not limited by default.
+    pub fn set_max_message_size(&mut self, max_message_size: usize) {
+        self.max_message_size = max_message_size;
+    }
+
+    /// Channels the user has created or joined, least recently connected first. A channel joined
+    /// again is moved to the end, the current channel is the last one. The history is kept in
+    /// backups and survives detaching the channel, eg. to list recent channels or to detect an
+    /// accidental reconnection to an abandoned channel.
+    pub fn connection_history(&self) -> impl Iterator<Item = &Link> + '_ {
+        self.connection_history.iter()
+    }
+
+    /// Limit the number of channels kept in the connection history, the least recently connected
+    /// ones are dropped first. `DEFAULT_MAX_CONNECTION_HISTORY` channels are kept by default.
+    pub fn set_max_connection_history(&mut self, max_connection_history: usize) {
+        self.max_connection_history = max_connection_history;
+        self.trim_connection_history();
+    }
+
+    fn record_connection(&mut self, appinst: &Link) {
+        self.connection_history.retain(|link| link != appinst);
+        self.connection_history.push(appinst.clone());
+        self.trim_connection_history();
+    }
+
+    fn trim_connection_history(&mut self) {
+        let excess = self.connection_history.len().saturating_sub(self.max_connection_history);
+        self.connection_history.drain(..excess);
+    }
+
+    /// Split masked payloads of sent signed and tagged packets into blocks of `block_size` bytes,
+    /// each authenticated with its own MAC, `None` disables splitting. A truncated or corrupted
+    /// packet is then rejected at the first bad block. Each block costs `MASKED_BLOCK_MAC_SIZE`
+    /// bytes of MAC plus its encoded length. Splitting is signalled in the message header,
+    /// receivers need no setting, but split packets can't be read by older versions.
+    pub fn set_masked_block_size(&mut self, block_size: Option<usize>) -> Result<()> {
+        try_or!(block_size != Some(0), ZeroMaskedBlockSize)?;
+        self.masked_block_size = block_size;
+        Ok(())
+    }
+
+    /// Authenticate sent tagged packets with MACs of `mac_length` bytes, one of
+    /// `tagged_packet::MAC_LENGTHS`, 32 bytes by default. The length is carried in the messages,
+    /// receivers need no setting. Shorter MACs save bandwidth but are easier to forge, see
+    /// `tagged_packet` for the tradeoff.
+    pub fn set_tag_mac_length(&mut self, mac_length: usize) -> Result<()> {
+        tagged_packet::check_mac_length::<F>(mac_length)?;
+        self.tag_mac_length = mac_length;
+        Ok(())
+    }
+
+    /// Wrap sent messages into a buffer of the size of the last message with the same content type,
+    /// skipping the sizeof pass, `false` clears the cached sizes. Useful when message sizes are
+    /// fixed, eg. keyloads to a fixed set of subscribers or packets with fixed length payloads. A
+    /// message of a different size is wrapped twice, hints are disabled by default.
+    pub fn set_wrap_size_hints(&mut self, wrap_size_hints: bool) {
+        self.wrap_size_hints = if wrap_size_hints { Some(RefCell::default()) } else { None };
+    }
+
+    /// Sign sent signed packets with `signer` instead of the user key pair, `None` restores the key
+    /// pair. Packets are verified with and attributed to the public key of the signer. Other
+    /// messages are still signed with the user key pair, the key exchange key of the user is
+    /// derived from it.
+    pub fn set_signer<S: Signer + 'static>(&mut self, signer: Option<S>) {
+        self.signer = signer.map(|s| Rc::new(s) as Rc<dyn Signer>);
+    }
+
+    /// Signer of sent signed packets.
+    fn packet_signer(&self) -> &dyn Signer {
+        match &self.signer {
+            Some(signer) => signer.as_ref(),
+            None => &self.sig_kp,
+        }
+    }
+
+    /// Application defined label of the user, eg. to tell apart many users managed together.
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// Set or clear the label of the user. The label is stored in backups and is not shared with
+    /// other channel participants.
+    pub fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    /// Reject signed packets published by identifiers unknown to the user. Publishers become known
+    /// when they are included in a processed keyload, subscribe to the channel or announce it.
+    pub fn set_enforce_known_publishers(&mut self, enforce_known_publishers: bool) {
+        self.enforce_known_publishers = enforce_known_publishers;
+    }
+
+    /// Reject signed and tagged packets of single-branch channels whose sequence number isn't the
+    /// next one expected from their publisher with `SequenceGap`, the user state is left unchanged.
+    /// Missing messages are then reported as soon as a following message is processed instead of
+    /// being skipped. Packets are accepted regardless of their sequence number by default.
+    pub fn set_strict_sequencing(&mut self, strict_sequencing: bool) {
+        self.strict_sequencing = strict_sequencing;
+    }
+
+    /// Follow only the given topics of signed packets, see `metadata::TOPIC_KEY`. Packets of other
+    /// topics are still verified and processed, so that the messages linked to them can be read,
+    /// but their masked payload is skipped without being unmasked and is returned empty. Packets
+    /// without a topic belong to `metadata::DEFAULT_TOPIC`. All topics are followed by default.
+    pub fn set_followed_topics(&mut self, followed_topics: Option<Vec<String>>) {
+        self.followed_topics = followed_topics;
+    }
+
+    /// Check the packet follows the last processed message of its publisher, see
+    /// `set_strict_sequencing`. Packets of publishers without a cursor are not checked.
+    fn check_sequence(&self, preparsed: &PreparsedMessage<'_, F, Link>) -> Result<()> {
+        if !self.strict_sequencing || self.is_multi_branching() {
+            return Ok(());
+        }
+        let seq_no = preparsed.header.seq_num.0 as u32;
+        match self.key_store.get(&preparsed.header.sender_id) {
+            Some(cursor) => try_or!(cursor.seq_no == seq_no, SequenceGap(cursor.seq_no, seq_no)),
+            None => Ok(()),
+        }
+    }
+
+    /// Accept announcements only from the given channel authors, announcements of other authors
+    /// are rejected and the user is not bound to their channels. `None` trusts any author.
+    pub fn set_trusted_authors(&mut self, trusted_authors: Option<Vec<ed25519::PublicKey>>) {
+        self.trusted_authors = trusted_authors;
+    }
+
+    /// Compress public payloads of sent signed and tagged packets and decompress the received ones,
+    /// `None` disables compression. Compressed payloads are signalled in the header, receivers need
+    /// the compressor to read them only.
+    pub fn set_compressor<C: Compressor + 'static>(&mut self, compressor: Option<C>) {
+        self.compressor = compressor.map(|c| Rc::new(c) as Rc<dyn Compressor>);
+    }
+
+    /// Encode public and masked payloads of sent signed and tagged packets with the codec and
+    /// decode the received ones, `None` disables the codec. All the participants of the channel
+    /// must use the same codec.
+    pub fn set_payload_codec<C: PayloadCodec + 'static>(&mut self, payload_codec: Option<C>) {
+        self.payload_codec = payload_codec.map(|c| Rc::new(c) as Rc<dyn PayloadCodec>);
+    }
+
+    /// Check recipients of received keyloads against the policy before they are processed, a
+    /// keyload rejected by the policy fails with `KeyloadPolicyViolation`. `None` accepts any keyload.
+    pub fn set_keyload_policy<P: KeyloadPolicy + 'static>(&mut self, keyload_policy: Option<P>) {
+        self.keyload_policy = keyload_policy.map(|p| Rc::new(p) as Rc<dyn KeyloadPolicy>);
+    }
+
+    /// Derive nonces, session keys and ephemeral keys of sent messages from the seed, the message
+    /// link and its content (keyload recipients, subscribe payload) instead of the system RNG, `None`
+    /// restores random generation. Replaying the same message yields byte-identical output, which is
+    /// useful in tests and for deduplicating storage, while different messages never share secrets.
+    ///
+    /// Deterministic randomness weakens unlinkability: an observer knowing the seed can link and
+    /// recompute keyload secrets. It must not be used for privacy-sensitive channels.
+    pub fn set_deterministic_seed(&mut self, seed: Option<&str>) {
+        self.deterministic_prng = seed.map(|seed| prng::from_seed("IOTA Streams Channels deterministic", seed));
+    }
+
+    /// Randomness of the message with the given link and content derived from the deterministic
+    /// seed, if set.
+    fn deterministic_bytes<N: ArrayLength<u8>>(
+        &self,
+        domain: &str,
+        msg_link: &Link,
+        content: &[u8],
+    ) -> Option<GenericArray<u8, N>> {
+        self.deterministic_prng.as_ref().map(|prng| {
+            let mut nonce = domain.as_bytes().to_vec();
+            nonce.extend_from_slice(&msg_link.to_bytes());
+            nonce.extend_from_slice(content);
+            prng.gen_arr(nonce)
+        })
+    }
+
+    /// Encode payload of a packet to be sent with the payload codec, if any.
+    fn encode_payload(&self, payload: &Bytes) -> Result<Option<Bytes>> {
+        self.payload_codec
+            .as_ref()
+            .map(|codec| codec.encode(&payload.0).map(Bytes))
+            .transpose()
+    }
+
+    /// Decode payload of a received packet with the payload codec, if any.
+    fn decode_payload(&self, payload: &mut Bytes) -> Result<()> {
+        if let Some(codec) = &self.payload_codec {
+            *payload = Bytes(codec.decode(&payload.0)?);
+        }
+        Ok(())
+    }
+
+    /// Encode public payload of a packet to be sent with the payload codec and the compressor, if any.
+    /// Returns true along with the payload if it was compressed, it must be signalled in the header.
+    fn compress_payload(&self, public_payload: &Bytes) -> Result<(Option<Bytes>, bool)> {
+        let encoded = self.encode_payload(public_payload)?;
+        let compressed = match &self.compressor {
+            Some(compressor) => {
+                compression::encode_payload(compressor.as_ref(), encoded.as_ref().unwrap_or(public_payload))?
+            }
+            None => None,
+        };
+        match compressed {
+            Some(compressed) => Ok((Some(compressed), true)),
+            None => Ok((encoded, false)),
+        }
+    }
+
+    /// Decode public payload of a received packet with the compressor, if the header signals it was
+    /// compressed, and the payload codec, if any.
+    fn decompress_payload(&self, public_payload: &mut Bytes, compressed: bool) -> Result<()> {
+        if compressed {
+            let compressor = self.compressor.as_ref().map(|compressor| compressor.as_ref());
+            *public_payload = compression::decode_payload(compressor, public_payload, self.max_payload_length)?;
+        }
+        self.decode_payload(public_payload)
+    }
+
+    /// Detach the state of the current channel from the user. The user is left unregistered and
+    /// can create or subscribe to another channel, own keys are kept.
+    pub fn take_channel(&mut self) -> Result<ChannelState<F, Link, LS, Keys>> {
+        match self.appinst.take() {
+            Some(appinst) => Ok(ChannelState {
+                appinst,
+                author_sig_pk: self.author_sig_pk.take(),
+                co_authors: core::mem::take(&mut self.co_authors),
+                subscriber_groups: core::mem::take(&mut self.subscriber_groups),
+                keyload_recipients: core::mem::take(&mut self.keyload_recipients),
+                key_store: core::mem::take(&mut self.key_store),
+                link_store: self.link_store.replace(LS::default()),
+                flags: self.flags,
+                use_psk: core::mem::replace(&mut self.use_psk, false),
+                last_keyload_fingerprint: self.last_keyload_fingerprint.take(),
+                announcement_metadata: self.announcement_metadata.take(),
+                announcement_context: self.announcement_context.take(),
+                closed: core::mem::replace(&mut self.closed, false),
+                membership_sealed: core::mem::replace(&mut self.membership_sealed, false),
+                acks: core::mem::take(&mut self.acks),
+                topics: core::mem::take(&mut self.topics),
+                _phantom: core::marker::PhantomData,
+            }),
+            None => err!(UserNotRegistered),
+        }
+    }
+
+    /// Attach previously detached channel state to an unregistered user.
+    pub fn put_channel(&mut self, state: ChannelState<F, Link, LS, Keys>) -> Result<()> {
+        if let Some(appinst) = &self.appinst {
+            return err!(UserAlreadyRegistered(appinst.base().to_string()));
+        }
+        self.link_gen.reset(state.appinst.clone());
+        self.record_connection(&state.appinst);
+        self.appinst = Some(state.appinst);
+        self.author_sig_pk = state.author_sig_pk;
+        self.co_authors = state.co_authors;
+        self.subscriber_groups = state.subscriber_groups;
+        self.keyload_recipients = state.keyload_recipients;
+        self.key_store = state.key_store;
+        self.link_store = RefCell::new(state.link_store);
+        self.flags = state.flags;
+        self.use_psk = state.use_psk;
+        self.last_keyload_fingerprint = state.last_keyload_fingerprint;
+        self.announcement_metadata = state.announcement_metadata;
+        self.announcement_context = state.announcement_context;
+        self.closed = state.closed;
+        self.membership_sealed = state.membership_sealed;
+        self.acks = state.acks;
+        self.topics = state.topics;
+        Ok(())
+    }
+
+    /// Detach the active channel state and keep it for later use, see `take_channel`.
+    pub fn stash_channel(&mut self) -> Result<()> {
+        let state = self.take_channel()?;
+        self.stashed_channels.push(state);
+        Ok(())
+    }
+
+    /// Make a previously stashed channel active. The active channel (if any) is stashed.
+    pub fn switch_channel(&mut self, channel: &<Link as HasLink>::Base) -> Result<()> {
+        match self
+            .stashed_channels
+            .iter()
+            .position(|state| state.appinst.base() == channel)
+        {
+            Some(i) => {
+                let state = self.stashed_channels.remove(i);
+                if self.appinst.is_some() {
+                    self.stash_channel()?;
+                }
+                self.put_channel(state)
+            }
+            None => err!(UnknownChannel(channel.to_string())),
+        }
+    }
+
+    /// Channel addresses of stashed channels, in stashing order.
+    pub fn stashed_channels(&self) -> Vec<&<Link as HasLink>::Base> {
+        self.stashed_channels.iter().map(|state| state.appinst.base()).collect()
+    }
+
     /// Channel Author's signature public key
     pub fn author_public_key(&self) -> Option<&ed25519::PublicKey> {
         self.author_sig_pk.as_ref()
     }
 
+    /// Signature public keys of the authors added to the channel, the announcing author excluded.
+    pub fn co_authors(&self) -> &[ed25519::PublicKey] {
+        &self.co_authors
+    }
+
+    /// Check whether keyloads and admin messages signed with the key are accepted: the key is that
+    /// of the announcing author or of an added author.
+    pub fn is_trusted_author(&self, sig_pk: &ed25519::PublicKey) -> bool {
+        self.author_sig_pk.as_ref() == Some(sig_pk) || self.co_authors.contains(sig_pk)
+    }
+
+    /// Channel Author's key exchange public key, eg. to encrypt data to the Author out of band.
+    /// Returns `None` if the user is not connected to a channel or the key is not known.
+    pub fn author_exchange_key(&self) -> Option<x25519::PublicKey> {
+        let author_sig_pk = self.author_sig_pk.as_ref()?;
+        self.key_store
+            .get_ke_pk(&Identifier::EdPubKey((*author_sig_pk).into()))
+            .copied()
+    }
+
     /// Reset link store and key store to original state
     pub fn reset_state(&mut self) -> Result<()> {
         match &self.appinst {
             Some(appinst) => {
                 let mut key_store = Keys::default();
                 for (id, _cursor) in self.key_store.iter() {
-                    key_store.insert_cursor(*id, Cursor::new_at(appinst.rel().clone(), 0, 2_u32))?;
+                    key_store.insert_cursor(*id, Cursor::new_at(appinst.rel().clone(), 0, INIT_MESSAGE_NUM))?;
                 }
                 self.key_store = key_store;
 
@@ -288,6 +1260,24 @@ where
         wrapped.commit(self.link_store.borrow_mut(), info)
     }
 
+    /// Message context wrapping the content, limited to the maximum message size.
+    fn prepared<Content>(&self, header: HDF<Link>, content: Content) -> PreparedMessage<'_, F, Link, LS, Content> {
+        PreparedMessage::new(self.link_store.borrow(), header, content)
+            .with_max_size(self.max_message_size)
+            .with_size_cache(self.wrap_size_hints.as_ref())
+    }
+
+    /// Parse header of a received message, rejecting bodies exceeding the maximum message size
+    /// before the message is unwrapped.
+    pub(crate) fn parse_message<'a>(&self, msg: &'a BinaryMessage<F, Link>) -> Result<PreparsedMessage<'a, F, Link>> {
+        let size = msg.body.as_bytes().len();
+        try_or!(
+            size <= self.max_message_size,
+            MessageSizeExceeded(size, self.max_message_size)
+        )?;
+        msg.parse_header()
+    }
+
     /// Prepare Announcement message.
     pub fn prepare_announcement<'a>(&'a self) -> Result<PreparedMessage<'a, F, Link, LS, announce::ContentWrap<F>>> {
         // Create HDF for the first message in the channel.
@@ -296,9 +1286,12 @@ where
             .with_content_type(ANNOUNCE)?
             .with_payload_length(1)?
             .with_seq_num(ANN_MESSAGE_NUM)
-            .with_identifier(&self.sig_kp.public.into());
-        let content = announce::ContentWrap::new(&self.sig_kp, self.flags);
-        Ok(PreparedMessage::new(self.link_store.borrow(), header, content))
+            .with_identifier(&self.sig_kp.public.into())
+            .with_content_metadata(self.announcement_metadata.is_some());
+        let content = announce::ContentWrap::new(&self.sig_kp, self.flags)
+            .with_metadata(self.announcement_metadata.as_ref())
+            .with_context(self.announcement_context.as_ref());
+        Ok(self.prepared(header, content))
     }
 
     /// Create Announcement message.
@@ -306,6 +1299,11 @@ where
         self.prepare_announcement()?.wrap()
     }
 
+    /// Size in bytes of Announce message, the message is not wrapped.
+    pub fn estimate_announcement_size(&self) -> Result<usize> {
+        self.prepare_announcement()?.size()
+    }
+
     pub fn unwrap_announcement(
         &self,
         preparsed: PreparsedMessage<'_, F, Link>,
@@ -317,45 +1315,79 @@ where
             )?;
         }
 
-        let content = announce::ContentUnwrap::<F>::default();
+        let content = announce::ContentUnwrap::<F>::default()
+            .with_metadata(preparsed.header.has_content_metadata())
+            .with_max_metadata_length(self.max_payload_length)
+            .with_context(self.announcement_context.as_ref());
         let r = preparsed.unwrap(&*self.link_store.borrow(), content);
         r
     }
 
+    /// Unwrap an announcement and verify its signature without binding the user to the channel,
+    /// eg. to inspect the channel author before following the channel. The user state is not
+    /// changed, the user may be bound to another channel. Trusted authors are not checked.
+    pub fn peek_announcement(&self, msg: &BinaryMessage<F, Link>) -> Result<AnnouncementInfo> {
+        let preparsed = self.parse_message(&msg)?;
+        try_or!(
+            preparsed.content_type() == ANNOUNCE,
+            NotAnnouncement(preparsed.content_type())
+        )?;
+        let content = announce::ContentUnwrap::<F>::default()
+            .with_metadata(preparsed.header.has_content_metadata())
+            .with_max_metadata_length(self.max_payload_length)
+            .with_context(self.announcement_context.as_ref());
+        let store = EmptyLinkStore::<F, <Link as HasLink>::Rel, ()>::default();
+        let content = preparsed.unwrap(&store, content)?.pcf.content;
+        Ok(AnnouncementInfo {
+            author_sig_pk: content.sig_pk,
+            author_ke_pk: content.ke_pk,
+            flags: content.flags.0,
+            metadata: content.metadata,
+        })
+    }
+
     /// Bind Subscriber (or anonymously subscribe) to the channel announced
     /// in the message.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
     pub fn handle_announcement(
         &mut self,
         msg: BinaryMessage<F, Link>,
         info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
     ) -> Result<()> {
-        let preparsed = msg.parse_header()?;
+        let preparsed = self.parse_message(&msg)?;
         try_or!(
             preparsed.content_type() == ANNOUNCE,
             NotAnnouncement(preparsed.content_type())
         )?;
 
         let unwrapped = self.unwrap_announcement(preparsed)?;
+        if let Some(trusted_authors) = &self.trusted_authors {
+            // Signature is verified at this point, author identity can be trusted
+            let author_sig_pk = &unwrapped.pcf.content.sig_pk;
+            try_or!(
+                trusted_authors.contains(author_sig_pk),
+                UntrustedAuthor(hex::encode(author_sig_pk.as_bytes()))
+            )?;
+        }
         let link = unwrapped.link.clone();
         let content = unwrapped.commit(self.link_store.borrow_mut(), info)?;
         // TODO: check commit after message is done / before joined
 
-        // TODO: Verify trust to Author's public key?
-        // At the moment the Author is trusted unconditionally.
-
         // TODO: Verify appinst (address) == public key.
         // At the moment the Author is free to choose any address, not tied to PK.
 
-        let cursor = Cursor::new_at(link.rel().clone(), 0, 2_u32);
+        let cursor = Cursor::new_at(link.rel().clone(), 0, INIT_MESSAGE_NUM);
         self.key_store
             .insert_cursor(Identifier::EdPubKey(content.sig_pk.into()), cursor.clone())?;
         self.key_store
             .insert_cursor(Identifier::EdPubKey(self.sig_kp.public.into()), cursor)?;
         // Reset link_gen
         self.link_gen.reset(link.clone());
+        self.record_connection(&link);
         self.appinst = Some(link);
         self.author_sig_pk = Some(content.sig_pk);
         self.flags = content.flags.0;
+        self.announcement_metadata = content.metadata;
         Ok(())
     }
 
@@ -363,6 +1395,16 @@ where
     pub fn prepare_subscribe<'a>(
         &'a mut self,
         link_to: &'a Link,
+    ) -> Result<PreparedMessage<'a, F, Link, LS, subscribe::ContentWrap<'a, F, Link>>> {
+        self.prepare_subscribe_with_payload(link_to, &EMPTY_PAYLOAD)
+    }
+
+    /// Prepare Subscribe message carrying an application payload for the author, eg. a display
+    /// name. The payload is masked with the key shared with the author and covered by the signature.
+    pub fn prepare_subscribe_with_payload<'a>(
+        &'a mut self,
+        link_to: &'a Link,
+        payload: &'a Bytes,
     ) -> Result<PreparedMessage<'a, F, Link, LS, subscribe::ContentWrap<'a, F, Link>>> {
         if let Some(author_sig_pk) = &self.author_sig_pk {
             let identifier = Identifier::EdPubKey(ed25519::PublicKeyWrap(*author_sig_pk));
@@ -371,21 +1413,27 @@ where
                     &self.sig_kp.public.into(),
                     Cursor::new_at(link_to.rel(), 0, SUB_MESSAGE_NUM),
                 );
+                let unsubscribe_key = NBytes::from(
+                    self.deterministic_bytes("unsubscribe key", &msg_link, &payload.0)
+                        .unwrap_or_else(prng::random_key),
+                );
                 let header = HDF::new(msg_link)
                     .with_previous_msg_link(Bytes(link_to.to_bytes()))
                     .with_content_type(SUBSCRIBE)?
                     .with_payload_length(1)?
                     .with_seq_num(SUB_MESSAGE_NUM)
-                    .with_identifier(&self.sig_kp.public.into());
-                let unsubscribe_key = NBytes::from(prng::random_key());
+                    .with_identifier(&self.sig_kp.public.into())
+                    .with_link_fingerprint(self.link_fingerprint)
+                    .with_content_masked_payload(!payload.0.is_empty());
                 let content = subscribe::ContentWrap {
                     link: link_to.rel(),
                     unsubscribe_key,
                     subscriber_sig_kp: &self.sig_kp,
+                    payload,
                     author_ke_pk,
                     _phantom: core::marker::PhantomData,
                 };
-                Ok(PreparedMessage::new(self.link_store.borrow(), header, content))
+                Ok(self.prepared(header, content))
             } else {
                 err!(AuthorExchangeKeyNotFound)
             }
@@ -399,38 +1447,142 @@ where
         self.prepare_subscribe(link_to)?.wrap()
     }
 
+    /// Subscribe to the channel with an application payload for the author.
+    pub fn subscribe_with_payload(&mut self, link_to: &Link, payload: &Bytes) -> Result<WrappedMessage<F, Link>> {
+        self.prepare_subscribe_with_payload(link_to, payload)?.wrap()
+    }
+
+    /// Size in bytes of Subscribe message, the message is not wrapped.
+    pub fn estimate_subscribe_size(&mut self, link_to: &Link) -> Result<usize> {
+        self.prepare_subscribe(link_to)?.size()
+    }
+
     pub fn unwrap_subscribe<'a>(
         &self,
         preparsed: PreparsedMessage<'a, F, Link>,
     ) -> Result<UnwrappedMessage<F, Link, subscribe::ContentUnwrap<F, Link>>> {
         self.ensure_appinst(&preparsed)?;
-        let content = subscribe::ContentUnwrap::new(&self.ke_kp.0)?;
+        let content = subscribe::ContentUnwrap::new(&self.ke_kp.0)?
+            .with_payload(preparsed.header.has_content_masked_payload())
+            .with_max_payload_length(self.max_payload_length);
         preparsed.unwrap(&*self.link_store.borrow(), content)
     }
 
     /// Get public payload, decrypt masked payload and verify MAC.
+    ///
+    /// Handling a subscription of an already registered subscriber, eg. the same message processed
+    /// twice, keeps its sequencing state; the exchange key is updated only if it changed. A different
+    /// message at the address of a processed subscription is still rejected.
     pub fn handle_subscribe(
         &mut self,
         msg: BinaryMessage<F, Link>,
         info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
     ) -> Result<()> {
-        let preparsed = msg.parse_header()?;
+        self.handle_subscribe_with_payload(msg, info).map(|_| ())
+    }
+
+    /// Process a subscription and get the subscriber identifier together with the subscription
+    /// payload, empty if the subscriber sent none. See `handle_subscribe`.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn handle_subscribe_with_payload(
+        &mut self,
+        msg: BinaryMessage<F, Link>,
+        info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
+    ) -> Result<(Identifier, Bytes)> {
+        let preparsed = self.parse_message(&msg)?;
         // TODO: check content type
 
-        let content = self
-            .unwrap_subscribe(preparsed)?
-            .commit(self.link_store.borrow_mut(), info)?;
+        let unwrapped = self.unwrap_subscribe(preparsed)?;
         // TODO: trust content.subscriber_sig_pk
-        let subscriber_sig_pk = content.subscriber_sig_pk;
-        let ref_link = self.appinst.as_ref().unwrap().rel().clone();
-        self.key_store.insert_cursor(
-            Identifier::EdPubKey(subscriber_sig_pk.into()),
-            Cursor::new_at(ref_link, 0, SEQ_MESSAGE_NUM),
+        let subscriber_sig_pk = unwrapped.pcf.content.subscriber_sig_pk;
+        let subscriber = Identifier::EdPubKey(subscriber_sig_pk.into());
+        // Repeated subscriptions of known subscribers are still accepted
+        try_or!(
+            !self.membership_sealed || self.key_store.get(&subscriber).is_some(),
+            MembershipSealed(hex::encode(subscriber.to_bytes()))
         )?;
+        let content = unwrapped.commit(self.link_store.borrow_mut(), info)?;
+        match self.key_store.get(&subscriber).cloned() {
+            // Repeated subscription, eg. a retried send, the subscriber sequencing state is kept
+            Some(cursor) => {
+                let ke_pk = x25519::public_from_ed25519(&subscriber_sig_pk)?;
+                if self.key_store.get_ke_pk(&subscriber).map(|pk| pk.as_bytes()) != Some(ke_pk.as_bytes()) {
+                    warn_event!(
+                        subscriber = %hex::encode(subscriber.to_bytes()),
+                        "subscriber re-subscribed with a different key"
+                    );
+                    self.key_store.insert_ke_pk(subscriber, ke_pk, cursor)?;
+                }
+            }
+            None => {
+                let ref_link = self.appinst.as_ref().unwrap().rel().clone();
+                self.key_store
+                    .insert_cursor(subscriber, Cursor::new_at(ref_link, 0, SEQ_MESSAGE_NUM))?;
+            }
+        }
         // Unwrapped unsubscribe_key is not used explicitly.
+        Ok((subscriber, content.payload.unwrap_or_default()))
+    }
+
+    /// Register subscribers with known key exchange public keys without processing their
+    /// subscribe messages, eg. when restoring a channel from external records. Sequencing states
+    /// of already known subscribers are kept.
+    pub fn add_subscribers<I>(&mut self, subscribers: I) -> Result<()>
+    where
+        I: IntoIterator<Item = (Identifier, x25519::PublicKey)>,
+    {
+        let ref_link = match &self.appinst {
+            Some(appinst) => appinst.rel().clone(),
+            None => return err(UserNotRegistered),
+        };
+        for (id, ke_pk) in subscribers {
+            let cursor = match self.key_store.get(&id) {
+                Some(cursor) => cursor.clone(),
+                None => Cursor::new_at(ref_link.clone(), 0, SEQ_MESSAGE_NUM),
+            };
+            self.key_store.insert_ke_pk(id, ke_pk, cursor)?;
+        }
+        Ok(())
+    }
+
+    /// Add a subscriber, or a pre-shared key identifier, to the named group of keyload recipients.
+    /// The group is created if it doesn't exist yet. The recipient must be known to the user.
+    pub fn add_to_group(&mut self, group: &str, id: Identifier) -> Result<()> {
+        try_or!(self.appinst.is_some(), UserNotRegistered)?;
+        try_or!(self.key_store.contains(&id), UnknownPublisher(hex::encode(id.to_bytes())))?;
+        let members = self.subscriber_groups.entry(group.to_string()).or_default();
+        if !members.contains(&id) {
+            members.push(id);
+        }
         Ok(())
     }
 
+    /// Remove a recipient from the named group, the group is deleted once it has no members.
+    /// Returns true if the recipient was a member of the group.
+    pub fn remove_from_group(&mut self, group: &str, id: &Identifier) -> bool {
+        let members = match self.subscriber_groups.get_mut(group) {
+            Some(members) => members,
+            None => return false,
+        };
+        let len = members.len();
+        members.retain(|member| member != id);
+        let removed = members.len() != len;
+        if members.is_empty() {
+            self.subscriber_groups.remove(group);
+        }
+        removed
+    }
+
+    /// Members of the named group of keyload recipients, `None` if the group is not defined.
+    pub fn group_members(&self, group: &str) -> Option<&[Identifier]> {
+        self.subscriber_groups.get(group).map(|members| &members[..])
+    }
+
+    /// Names of the defined groups of keyload recipients, in lexicographic order.
+    pub fn subscriber_groups(&self) -> Vec<&str> {
+        self.subscriber_groups.keys().map(|group| group.as_str()).collect()
+    }
+
     fn do_prepare_keyload<'a, KePks>(
         &'a self,
         header: HDF<Link>,
@@ -440,17 +1592,38 @@ where
     where
         KePks: Clone + ExactSizeIterator<Item = (&'a Identifier, Vec<u8>)>,
     {
-        let nonce = NBytes::from(prng::random_nonce());
-        let key = NBytes::from(prng::random_key());
+        try_or!(
+            ke_pks.len() <= self.max_keyload_subscribers,
+            TooManySubscribers(ke_pks.len(), self.max_keyload_subscribers)
+        )?;
+        // Recipients are length-prefixed so that different sets never encode to the same bytes
+        let mut recipients = Vec::new();
+        if self.deterministic_prng.is_some() {
+            for (id, key) in ke_pks.clone() {
+                recipients.extend_from_slice(&id.to_bytes());
+                recipients.extend_from_slice(&(key.len() as u64).to_be_bytes());
+                recipients.extend_from_slice(&key);
+            }
+        }
+        let nonce = NBytes::from(
+            self.deterministic_bytes("keyload nonce", &header.link, &recipients)
+                .unwrap_or_else(prng::random_nonce),
+        );
+        let key = NBytes::from(
+            self.deterministic_bytes("keyload key", &header.link, &recipients)
+                .unwrap_or_else(prng::random_key),
+        );
+        let eph_seed = self.deterministic_bytes("keyload ephemeral keys", &header.link, &recipients);
         let content = keyload::ContentWrap {
             link: link_to,
             nonce,
             key,
+            eph_seed,
             keys: ke_pks,
             sig_kp: &self.sig_kp,
             _phantom: core::marker::PhantomData,
         };
-        Ok(PreparedMessage::new(self.link_store.borrow(), header, content))
+        Ok(self.prepared(header, content))
     }
 
     pub fn prepare_keyload<'a>(
@@ -471,8 +1644,9 @@ where
                     .with_content_type(KEYLOAD)?
                     .with_payload_length(1)?
                     .with_seq_num(seq_no)
-                    .with_identifier(&self.sig_kp.public.into());
-                let keys = self.key_store.filter(pks);
+                    .with_identifier(&self.sig_kp.public.into())
+                    .with_link_fingerprint(self.link_fingerprint);
+                let keys = self.keyload_keys(pks);
                 self.do_prepare_keyload(header, link_to.rel(), keys.into_iter())
             }
             None => err!(SeqNumRetrievalFailure),
@@ -495,7 +1669,8 @@ where
                     .with_content_type(KEYLOAD)?
                     .with_payload_length(1)?
                     .with_seq_num(seq_no)
-                    .with_identifier(&self.sig_kp.public.into());
+                    .with_identifier(&self.sig_kp.public.into())
+                    .with_link_fingerprint(self.link_fingerprint);
                 let ike_pks = self.key_store.keys();
                 self.do_prepare_keyload(header, link_to.rel(), ike_pks.into_iter())
             }
@@ -504,36 +1679,155 @@ where
     }
 
     /// Create keyload message with a new session key shared with recipients
-    /// identified by pre-shared key IDs and by Ed25519 public keys.
+    /// identified by pre-shared key IDs, by Ed25519 public keys and by X25519 viewing keys.
     pub fn share_keyload(
         &mut self,
         link_to: &Link,
         psk_ids: &psk::PskIds,
         ke_pks: &Vec<&Identifier>,
     ) -> Result<WrappedMessage<F, Link>> {
-        self.prepare_keyload(link_to, psk_ids, ke_pks)?.wrap()
+        let recipients = self.keyload_keys(ke_pks).into_iter().map(|(id, _)| *id).collect();
+        let wrapped = self.prepare_keyload(link_to, psk_ids, ke_pks)?.wrap()?;
+        self.record_keyload_recipients(&wrapped.message.link, recipients);
+        Ok(wrapped)
+    }
+
+    /// Create keyload message with a new session key shared with holders of the viewing keys only.
+    /// Viewers can read the masked payloads protected by the keyload, but they are not publishers:
+    /// no cursor is stored for them and they can't be told apart from other readers.
+    pub fn share_keyload_for_viewers(
+        &mut self,
+        link_to: &Link,
+        viewing_keys: &[x25519::PublicKey],
+    ) -> Result<WrappedMessage<F, Link>> {
+        let viewers: Vec<Identifier> = viewing_keys.iter().map(|pk| Identifier::from(*pk)).collect();
+        self.share_keyload(link_to, &Vec::new(), &viewers.iter().collect())
+    }
+
+    /// Create keyload message with a new session key shared with the members of the named group.
+    pub fn share_keyload_for_group(&mut self, link_to: &Link, group: &str) -> Result<WrappedMessage<F, Link>> {
+        let members = match self.subscriber_groups.get(group) {
+            Some(members) => members.clone(),
+            None => return err!(UnknownSubscriberGroup(group.to_string())),
+        };
+        self.share_keyload(link_to, &Vec::new(), &members.iter().collect())
     }
 
     /// Create keyload message with a new session key shared with all Subscribers
     /// known to Author.
     pub fn share_keyload_for_everyone(&mut self, link_to: &Link) -> Result<WrappedMessage<F, Link>> {
-        self.prepare_keyload_for_everyone(link_to)?.wrap()
+        let recipients = self.key_store.keys().into_iter().map(|(id, _)| *id).collect();
+        let wrapped = self.prepare_keyload_for_everyone(link_to)?.wrap()?;
+        self.record_keyload_recipients(&wrapped.message.link, recipients);
+        Ok(wrapped)
+    }
+
+    /// Remember the recipients of a sent or processed keyload.
+    fn record_keyload_recipients(&mut self, keyload: &Link, recipients: Vec<Identifier>) {
+        match self.keyload_recipients.iter_mut().find(|(link, _)| link == keyload) {
+            Some((_, known)) => *known = recipients,
+            None => self.keyload_recipients.push((keyload.clone(), recipients)),
+        }
+    }
+
+    /// Recipients, public keys and pre-shared key identifiers, of the keyload recorded when the
+    /// keyload was sent or processed. `None` if the keyload is unknown or could not be accessed.
+    pub fn keyload_recipients(&self, keyload: &<Link as HasLink>::Rel) -> Option<&[Identifier]> {
+        self.keyload_recipients
+            .iter()
+            .find(|(link, _)| link.rel() == keyload)
+            .map(|(_, recipients)| &recipients[..])
+    }
+
+    /// The way the user has been granted access to the session key of a processed keyload: the
+    /// first recipient the user holds a key for, as when the keyload was unwrapped. `Denied` if the
+    /// keyload is unknown or the user is not among its recipients.
+    pub fn keyload_access(&self, keyload: &<Link as HasLink>::Rel) -> KeyloadAccess {
+        self.keyload_recipients(keyload)
+            .and_then(|recipients| {
+                recipients.iter().find_map(|id| match id {
+                    Identifier::PskId(pskid) => self.lookup_psk(id).map(|_| KeyloadAccess::Psk(*pskid)),
+                    Identifier::EdPubKey(_) => self.lookup_ke_sk(id).map(|_| KeyloadAccess::Identity),
+                    Identifier::XPubKey(_) => self.lookup_ke_sk(id).map(|_| KeyloadAccess::Viewing),
+                })
+            })
+            .unwrap_or(KeyloadAccess::Denied)
+    }
+
+    /// Size in bytes of Keyload message for the given recipients, the message is not wrapped.
+    pub fn estimate_keyload_size(
+        &mut self,
+        link_to: &Link,
+        psk_ids: &psk::PskIds,
+        ke_pks: &Vec<&Identifier>,
+    ) -> Result<usize> {
+        self.prepare_keyload(link_to, psk_ids, ke_pks)?.size()
+    }
+
+    /// Size in bytes of Keyload message for all known subscribers, the message is not wrapped.
+    pub fn estimate_keyload_for_everyone_size(&mut self, link_to: &Link) -> Result<usize> {
+        self.prepare_keyload_for_everyone(link_to)?.size()
+    }
+
+    /// Order-independent fingerprint of keyload recipients: their identifiers together with the keys
+    /// the session key is shared with.
+    fn keyload_recipients_fingerprint(keys: &[(&Identifier, Vec<u8>)]) -> NBytes<U32> {
+        let mut entries: Vec<Vec<u8>> = keys
+            .iter()
+            .map(|(id, key)| [id.to_bytes(), key.clone()].concat())
+            .collect();
+        entries.sort();
+        let mut s = spongos::Spongos::<F>::init();
+        s.absorb(b"keyload fingerprint");
+        for entry in entries {
+            s.absorb((entry.len() as u64).to_be_bytes());
+            s.absorb(entry);
+        }
+        s.commit();
+        NBytes(s.squeeze_arr())
+    }
+
+    /// Fingerprint of the recipients of a keyload shared with `ke_pks`.
+    pub fn keyload_fingerprint(&self, ke_pks: &[&Identifier]) -> NBytes<U32> {
+        Self::keyload_recipients_fingerprint(&self.keyload_keys(ke_pks))
+    }
+
+    /// Fingerprint of the recipients of a keyload shared with all known subscribers.
+    pub fn keyload_fingerprint_for_everyone(&self) -> NBytes<U32> {
+        Self::keyload_recipients_fingerprint(&self.key_store.keys())
+    }
+
+    /// Check whether the last keyload sent to the current channel had the same recipients.
+    pub fn is_last_keyload(&self, fingerprint: &NBytes<U32>) -> bool {
+        self.last_keyload_fingerprint.as_ref() == Some(fingerprint)
+    }
+
+    /// Remember the recipients of a sent keyload.
+    pub fn set_last_keyload(&mut self, fingerprint: NBytes<U32>) {
+        self.last_keyload_fingerprint = Some(fingerprint);
     }
 
     fn lookup_psk(&self, pskid: &Identifier) -> Option<psk::Psk> {
         self.key_store.get_psk(pskid)
     }
 
+    /// Keys of the keyload recipients among `ids`: stored PSKs and subscriber exchange keys, and
+    /// viewing keys which are used as given since viewers are not stored.
+    fn keyload_keys<'b>(&'b self, ids: &[&'b Identifier]) -> Vec<(&'b Identifier, Vec<u8>)> {
+        let mut keys = self.key_store.filter(ids);
+        keys.extend(
+            ids.iter()
+                .filter(|id| matches!(id, Identifier::XPubKey(_)))
+                .map(|id| (*id, id.to_bytes())),
+        );
+        keys
+    }
+
     fn lookup_ke_sk<'b>(&'b self, ke_pk: &Identifier) -> Option<&'b x25519::StaticSecret> {
-        match ke_pk.get_pk() {
-            Some(pk) => {
-                if &self.sig_kp.public == pk {
-                    Some(&self.ke_kp.0)
-                } else {
-                    None
-                }
-            }
-            None => None,
+        match ke_pk {
+            Identifier::EdPubKey(pk) if self.sig_kp.public == pk.0 => Some(&self.ke_kp.0),
+            Identifier::XPubKey(pk) if self.viewing_kp.1 == pk.0 => Some(&self.viewing_kp.0),
+            _ => None,
         }
     }
 
@@ -556,6 +1850,13 @@ where
     > {
         self.ensure_appinst(&preparsed)?;
         if let Some(ref author_sig_pk) = self.author_sig_pk {
+            // Keyloads of added authors are verified with their key, others with the author key
+            let sender = preparsed.header.sender_id;
+            let sig_pk = self
+                .co_authors
+                .iter()
+                .find(|pk| sender.get_pk() == Some(*pk))
+                .unwrap_or(author_sig_pk);
             let content = keyload::ContentUnwrap::<
                 'b,
                 F,
@@ -563,7 +1864,7 @@ where
                 Self,
                 for<'c> fn(&'c Self, &Identifier) -> Option<psk::Psk>,
                 for<'c> fn(&'c Self, &Identifier) -> Option<&'c x25519::StaticSecret>,
-            >::new(self, Self::lookup_psk, Self::lookup_ke_sk, author_sig_pk);
+            >::new(self, Self::lookup_psk, Self::lookup_ke_sk, sig_pk);
             let unwrapped = preparsed.unwrap(&*self.link_store.borrow(), content)?;
             Ok(unwrapped)
         } else {
@@ -572,31 +1873,45 @@ where
     }
 
     /// Try unwrapping session key from keyload using Subscriber's pre-shared key or Ed25519 private key (if any).
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
     pub fn handle_keyload(
         &mut self,
         msg: BinaryMessage<F, Link>,
         info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
     ) -> Result<GenericMessage<Link, bool>> {
-        let preparsed = msg.parse_header()?;
+        let preparsed = self.parse_message(&msg)?;
         let prev_link = Link::from_bytes(&preparsed.header.previous_msg_link.0);
         let seq_no = preparsed.header.seq_num;
+
+        // Keyload is anchored to a message the user has not processed yet, it's left untracked so
+        // that it can be retried once its parent is known
+        self.link_store.borrow().lookup(prev_link.rel())?;
+
         let unwrapped = self.unwrap_keyload(preparsed)?;
         let processed;
 
         if unwrapped.pcf.content.key.is_some() {
+            // Recipients are checked before anything is committed, a rejected keyload leaves the
+            // user state untouched
+            if let Some(policy) = &self.keyload_policy {
+                try_or!(
+                    policy.accept(&unwrapped.pcf.content.key_ids),
+                    KeyloadPolicyViolation(hex::encode(msg.link.to_bytes()))
+                )?;
+            }
             // Do not commit if key not found hence spongos state is invalid
             let content = unwrapped.commit(self.link_store.borrow_mut(), info)?;
 
             // Presence of the key indicates the user is allowed
             // Unwrapped nonce and key in content are not used explicitly.
             // The resulting spongos state is joined into a protected message state.
-            // Store any unknown publishers
+            self.record_keyload_recipients(&msg.link, content.key_ids.clone());
+            // Store any unknown publishers, viewers only read
             if let Some(appinst) = &self.appinst {
                 for identifier in content.key_ids {
-                    if !self.key_store.contains(&identifier) {
-                        // Store at state 2 since 0 and 1 are reserved states
+                    if !matches!(identifier, Identifier::XPubKey(_)) && !self.key_store.contains(&identifier) {
                         self.key_store
-                            .insert_cursor(identifier, Cursor::new_at(appinst.rel().clone(), 0, 2))?;
+                            .insert_cursor(identifier, Cursor::new_at(appinst.rel().clone(), 0, INIT_MESSAGE_NUM))?;
                     }
                 }
             }
@@ -617,6 +1932,37 @@ where
         link_to: &'a Link,
         public_payload: &'a Bytes,
         masked_payload: &'a Bytes,
+    ) -> Result<PreparedMessage<'a, F, Link, LS, signed_packet::ContentWrap<'a, F, Link>>> {
+        self.prepare_signed_packet_content(link_to, public_payload, masked_payload, None, None, 0)
+    }
+
+    /// Prepare SignedPacket message with the context, metadata and application type of the options.
+    /// They are sent in plain and are covered by the signature, the default type 0 is not encoded.
+    pub fn prepare_signed_packet_with<'a>(
+        &'a mut self,
+        link_to: &'a Link,
+        public_payload: &'a Bytes,
+        masked_payload: &'a Bytes,
+        options: &'a PacketOptions,
+    ) -> Result<PreparedMessage<'a, F, Link, LS, signed_packet::ContentWrap<'a, F, Link>>> {
+        self.prepare_signed_packet_content(
+            link_to,
+            public_payload,
+            masked_payload,
+            options.context.as_ref(),
+            options.metadata.as_ref(),
+            options.app_type,
+        )
+    }
+
+    fn prepare_signed_packet_content<'a>(
+        &'a mut self,
+        link_to: &'a Link,
+        public_payload: &'a Bytes,
+        masked_payload: &'a Bytes,
+        context: Option<&'a Bytes>,
+        metadata: Option<&'a metadata::Metadata>,
+        app_type: u16,
     ) -> Result<PreparedMessage<'a, F, Link, LS, signed_packet::ContentWrap<'a, F, Link>>> {
         if self.use_psk {
             return err(MessageBuildFailure);
@@ -631,15 +1977,25 @@ where
                     .with_content_type(SIGNED_PACKET)?
                     .with_payload_length(1)?
                     .with_seq_num(seq_no)
-                    .with_identifier(&self.sig_kp.public.into());
+                    .with_identifier(&self.sig_kp.public.into())
+                    .with_link_fingerprint(self.link_fingerprint)
+                    .with_masked_payload_omitted(masked_payload.0.is_empty())
+                    .with_content_context(context.is_some())
+                    .with_content_metadata(metadata.is_some())
+                    .with_content_app_type(app_type != 0)
+                    .with_content_masked_blocks(self.masked_block_size.is_some() && !masked_payload.0.is_empty());
                 let content = signed_packet::ContentWrap {
                     link: link_to.rel(),
                     public_payload,
                     masked_payload,
-                    sig_kp: &self.sig_kp,
+                    context,
+                    metadata,
+                    app_type,
+                    masked_block_size: self.masked_block_size,
+                    signer: self.packet_signer(),
                     _phantom: core::marker::PhantomData,
                 };
-                Ok(PreparedMessage::new(self.link_store.borrow(), header, content))
+                Ok(self.prepared(header, content))
             }
             None => err!(SeqNumRetrievalFailure),
         }
@@ -652,8 +2008,59 @@ where
         public_payload: &Bytes,
         masked_payload: &Bytes,
     ) -> Result<WrappedMessage<F, Link>> {
-        self.prepare_signed_packet(link_to, public_payload, masked_payload)?
-            .wrap()
+        let (compressed, is_compressed) = self.compress_payload(public_payload)?;
+        let public_payload = compressed.as_ref().unwrap_or(public_payload);
+        let encoded = self.encode_payload(masked_payload)?;
+        let masked_payload = encoded.as_ref().unwrap_or(masked_payload);
+        let mut prepared = self.prepare_signed_packet(link_to, public_payload, masked_payload)?;
+        prepared.header.content_compressed_payload = is_compressed;
+        prepared.wrap()
+    }
+
+    /// Create a signed message with public and masked payload and the given options. The signature
+    /// is also returned detached along with the signed pre-image if requested by the options.
+    /// Sending options, eg. standalone or confirmed packets, are not handled here.
+    pub fn sign_packet_with(
+        &mut self,
+        link_to: &Link,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        options: &PacketOptions,
+    ) -> Result<(WrappedMessage<F, Link>, Option<DetachedSignature>)> {
+        let (compressed, is_compressed) = self.compress_payload(public_payload)?;
+        let public_payload = compressed.as_ref().unwrap_or(public_payload);
+        let encoded = self.encode_payload(masked_payload)?;
+        let masked_payload = encoded.as_ref().unwrap_or(masked_payload);
+        let mut prepared = self.prepare_signed_packet_with(link_to, public_payload, masked_payload, options)?;
+        prepared.header.content_compressed_payload = is_compressed;
+        if !options.detached {
+            return Ok((prepared.wrap()?, None));
+        }
+        let signer = DetachingSigner::new(prepared.content.content.signer);
+        prepared.content.content.signer = &signer;
+        let wrapped = prepared.wrap()?;
+        // The prepared message borrows the signer, it must be released first.
+        drop(prepared);
+        match signer.take() {
+            Some(detached) => Ok((wrapped, Some(detached))),
+            None => err(SignatureFailure),
+        }
+    }
+
+    /// Size in bytes of SignedPacket message with the given payloads, the message is not wrapped.
+    pub fn estimate_signed_packet_size(
+        &mut self,
+        link_to: &Link,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+    ) -> Result<usize> {
+        let (compressed, is_compressed) = self.compress_payload(public_payload)?;
+        let public_payload = compressed.as_ref().unwrap_or(public_payload);
+        let encoded = self.encode_payload(masked_payload)?;
+        let masked_payload = encoded.as_ref().unwrap_or(masked_payload);
+        let mut prepared = self.prepare_signed_packet(link_to, public_payload, masked_payload)?;
+        prepared.header.content_compressed_payload = is_compressed;
+        prepared.size()
     }
 
     pub fn unwrap_signed_packet<'a>(
@@ -661,7 +2068,14 @@ where
         preparsed: PreparsedMessage<'a, F, Link>,
     ) -> Result<UnwrappedMessage<F, Link, signed_packet::ContentUnwrap<F, Link>>> {
         self.ensure_appinst(&preparsed)?;
-        let content = signed_packet::ContentUnwrap::default();
+        let content = signed_packet::ContentUnwrap::default()
+            .with_masked_payload_omitted(preparsed.header.is_masked_payload_omitted())
+            .with_context(preparsed.header.has_content_context())
+            .with_metadata(preparsed.header.has_content_metadata())
+            .with_app_type(preparsed.header.has_content_app_type())
+            .with_masked_blocks(preparsed.header.has_content_masked_blocks())
+            .with_max_payload_length(self.max_payload_length)
+            .with_followed_topics(self.followed_topics.clone());
         preparsed.unwrap(&*self.link_store.borrow(), content)
     }
 
@@ -671,141 +2085,839 @@ where
         msg: BinaryMessage<F, Link>,
         info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
     ) -> Result<GenericMessage<Link, (ed25519::PublicKey, Bytes, Bytes)>> {
+        self.handle_signed_packet_with_context(msg, info)
+            .map(|m| m.map(|(pk, public, masked, _context)| (pk, public, masked)))
+    }
+
+    /// Verify signed packet and get its payloads together with the application context, if any.
+    pub fn handle_signed_packet_with_context(
+        &'_ mut self,
+        msg: BinaryMessage<F, Link>,
+        info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
+    ) -> Result<GenericMessage<Link, (ed25519::PublicKey, Bytes, Bytes, Option<Bytes>)>> {
+        self.handle_signed_packet_content(msg, info).map(|m| {
+            m.map(|content| {
+                (
+                    content.sig_pk,
+                    content.public_payload,
+                    content.masked_payload,
+                    content.context,
+                )
+            })
+        })
+    }
+
+    /// Verify signed packet and get its payloads together with the application metadata, if any.
+    pub fn handle_signed_packet_with_metadata(
+        &'_ mut self,
+        msg: BinaryMessage<F, Link>,
+        info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
+    ) -> Result<GenericMessage<Link, (ed25519::PublicKey, Bytes, Bytes, Option<metadata::Metadata>)>> {
+        self.handle_signed_packet_content(msg, info).map(|m| {
+            m.map(|content| {
+                (
+                    content.sig_pk,
+                    content.public_payload,
+                    content.masked_payload,
+                    content.metadata,
+                )
+            })
+        })
+    }
+
+    /// Verify signed packet and get its unwrapped content.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub(crate) fn handle_signed_packet_content(
+        &'_ mut self,
+        msg: BinaryMessage<F, Link>,
+        info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
+    ) -> Result<GenericMessage<Link, signed_packet::ContentUnwrap<F, Link>>> {
         // TODO: pass author_pk to unwrap
-        let preparsed = msg.parse_header()?;
+        let preparsed = self.parse_message(&msg)?;
         let prev_link = Link::from_bytes(&preparsed.header.previous_msg_link.0);
         let seq_no = preparsed.header.seq_num;
-        let content = self
-            .unwrap_signed_packet(preparsed)?
-            .commit(self.link_store.borrow_mut(), info)?;
+        self.check_sequence(&preparsed)?;
+        let compressed = preparsed.header.has_content_compressed_payload();
+        let mut unwrapped = self.unwrap_signed_packet(preparsed)?;
+        self.decompress_payload(&mut unwrapped.pcf.content.public_payload, compressed)?;
+        if !unwrapped.pcf.content.masked_payload_skipped {
+            self.decode_payload(&mut unwrapped.pcf.content.masked_payload)?;
+        }
+        if self.enforce_known_publishers {
+            // Signature is verified at this point, publisher identity can be trusted
+            let publisher = Identifier::EdPubKey(unwrapped.pcf.content.sig_pk.into());
+            try_or!(
+                self.key_store.contains(&publisher),
+                UnknownPublisher(hex::encode(publisher.to_bytes()))
+            )?;
+        }
+        let content = unwrapped.commit(self.link_store.borrow_mut(), info)?;
         if !self.is_multi_branching() {
             self.store_state_for_all(msg.link.rel().clone(), seq_no.0 as u32 + 1)?;
         }
+        let topic = content.metadata.as_ref().and_then(|metadata| metadata.topic());
+        let links = self
+            .topics
+            .entry(topic.unwrap_or(metadata::DEFAULT_TOPIC).to_string())
+            .or_default();
+        // The same packet may be handled again, eg. when it's received at a known address
+        if !links.iter().any(|link| link.rel() == msg.link.rel()) {
+            links.push(msg.link.clone());
+        }
+
+        Ok(GenericMessage::new(msg.link, prev_link, content))
+    }
 
-        let body = (content.sig_pk, content.public_payload, content.masked_payload);
-        Ok(GenericMessage::new(msg.link, prev_link, body))
+    /// Links of processed signed packets published under the topic, in processing order. Packets
+    /// without a topic are listed under `metadata::DEFAULT_TOPIC`, packets of topics which are not
+    /// followed are listed as well. The index is kept with the channel state and its backups,
+    /// retracted packets and packets whose spongos state is forgotten are removed from it.
+    pub fn messages_for_topic(&self, topic: &str) -> &[Link] {
+        self.topics.get(topic).map(|links| links.as_slice()).unwrap_or_default()
     }
 
-    /// Prepare TaggedPacket message.
-    pub fn prepare_tagged_packet<'a>(
+    /// Verify signature of a signed packet and get the identifier of its publisher. The publisher
+    /// must be known to the user, the message is unwrapped without changing the user state.
+    pub fn verify_signed_packet(&self, msg: &BinaryMessage<F, Link>) -> Result<Identifier> {
+        let preparsed = self.parse_message(&msg)?;
+        try_or!(
+            preparsed.content_type() == SIGNED_PACKET,
+            NotSignedPacket(preparsed.content_type())
+        )?;
+        let sig_pk = self.unwrap_signed_packet(preparsed)?.pcf.content.sig_pk;
+        let publisher = Identifier::EdPubKey(sig_pk.into());
+        try_or!(
+            self.key_store.contains(&publisher)
+                || self.author_sig_pk.as_ref() == Some(&sig_pk)
+                || self.sig_kp.public == sig_pk,
+            UnknownPublisher(hex::encode(publisher.to_bytes()))
+        )?;
+        Ok(publisher)
+    }
+
+    /// Prepare Close message.
+    pub fn prepare_close<'a>(
         &'a mut self,
         link_to: &'a Link,
-        public_payload: &'a Bytes,
-        masked_payload: &'a Bytes,
-    ) -> Result<PreparedMessage<'a, F, Link, LS, tagged_packet::ContentWrap<'a, F, Link>>> {
-        let identifier = self.get_identifier()?;
+    ) -> Result<PreparedMessage<'a, F, Link, LS, close::ContentWrap<'a, F, Link>>> {
+        try_or!(
+            self.is_trusted_author(&self.sig_kp.public),
+            CloseNotFromAuthor(hex::encode(self.sig_kp.public.as_bytes()))
+        )?;
         match self.get_seq_no() {
             Some(seq_no) => {
                 let msg_link = self
                     .link_gen
-                    .link_from(&identifier, Cursor::new_at(link_to.rel(), 0, seq_no));
+                    .link_from(&self.sig_kp.public.into(), Cursor::new_at(link_to.rel(), 0, seq_no));
                 let header = HDF::new(msg_link)
                     .with_previous_msg_link(Bytes(link_to.to_bytes()))
-                    .with_content_type(TAGGED_PACKET)?
+                    .with_content_type(CLOSE)?
                     .with_payload_length(1)?
                     .with_seq_num(seq_no)
-                    .with_identifier(&identifier);
-                let content = tagged_packet::ContentWrap {
+                    .with_identifier(&self.sig_kp.public.into())
+                    .with_link_fingerprint(self.link_fingerprint);
+                let content = close::ContentWrap {
                     link: link_to.rel(),
-                    public_payload,
-                    masked_payload,
+                    sig_kp: &self.sig_kp,
                     _phantom: core::marker::PhantomData,
                 };
-                Ok(PreparedMessage::new(self.link_store.borrow(), header, content))
+                Ok(self.prepared(header, content))
             }
             None => err!(SeqNumRetrievalFailure),
         }
     }
 
-    fn get_identifier(&mut self) -> Result<Identifier> {
-        if self.use_psk {
-            match self.key_store.get_next_pskid() {
-                Some(pskid) => Ok(*pskid),
-                None => err(MessageBuildFailure),
-            }
-        } else {
-            Ok(self.sig_kp.public.into())
-        }
-    }
-
-    /// Create a tagged (ie. MACed) message with public and masked payload.
-    /// Tagged messages must be linked to a secret spongos state, ie. keyload or a message linked to keyload.
-    pub fn tag_packet(
-        &mut self,
-        link_to: &Link,
-        public_payload: &Bytes,
-        masked_payload: &Bytes,
-    ) -> Result<WrappedMessage<F, Link>> {
-        self.prepare_tagged_packet(link_to, public_payload, masked_payload)?
-            .wrap()
+    /// Create a Close message marking the end of the channel, only trusted channel authors can
+    /// close the channel.
+    pub fn close(&mut self, link_to: &Link) -> Result<WrappedMessage<F, Link>> {
+        self.prepare_close(link_to)?.wrap()
     }
 
-    pub fn unwrap_tagged_packet(
-        &self,
-        preparsed: PreparsedMessage<'_, F, Link>,
-    ) -> Result<UnwrappedMessage<F, Link, tagged_packet::ContentUnwrap<F, Link>>> {
+    pub fn unwrap_close<'a>(
+        &'a self,
+        preparsed: PreparsedMessage<'a, F, Link>,
+    ) -> Result<UnwrappedMessage<F, Link, close::ContentUnwrap<F, Link>>> {
         self.ensure_appinst(&preparsed)?;
-        let content = tagged_packet::ContentUnwrap::new();
+        try_or!(
+            preparsed.content_type() == CLOSE,
+            NotCloseMessage(preparsed.content_type())
+        )?;
+        let content = close::ContentUnwrap::default();
         preparsed.unwrap(&*self.link_store.borrow(), content)
     }
 
-    /// Get public payload, decrypt masked payload and verify MAC.
-    pub fn handle_tagged_packet(
-        &mut self,
+    /// Verify Close message signed by a trusted channel author and mark the channel as closed.
+    pub fn handle_close(
+        &'_ mut self,
         msg: BinaryMessage<F, Link>,
         info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
-    ) -> Result<GenericMessage<Link, (Bytes, Bytes)>> {
-        let preparsed = msg.parse_header()?;
+    ) -> Result<GenericMessage<Link, ()>> {
+        let preparsed = self.parse_message(&msg)?;
         let prev_link = Link::from_bytes(&preparsed.header.previous_msg_link.0);
         let seq_no = preparsed.header.seq_num;
-        let content = self
-            .unwrap_tagged_packet(preparsed)?
-            .commit(self.link_store.borrow_mut(), info)?;
+        let unwrapped = self.unwrap_close(preparsed)?;
+        let sig_pk = unwrapped.pcf.content.sig_pk;
+        // Signature is verified at this point, the sender can be checked against the authors
+        try_or!(
+            self.is_trusted_author(&sig_pk),
+            CloseNotFromAuthor(hex::encode(sig_pk.as_bytes()))
+        )?;
+        unwrapped.commit(self.link_store.borrow_mut(), info)?;
         if !self.is_multi_branching() {
             self.store_state_for_all(msg.link.rel().clone(), seq_no.0 as u32 + 1)?;
         }
+        self.closed = true;
+        Ok(GenericMessage::new(msg.link, prev_link, ()))
+    }
 
-        let body = (content.public_payload, content.masked_payload);
-        Ok(GenericMessage::new(msg.link, prev_link, body))
+    /// Returns true if the current channel was closed by its author, no new messages are expected.
+    ///
+    /// Note: the flag is not part of the exported user state.
+    pub fn is_closed(&self) -> bool {
+        self.closed
     }
 
-    pub fn prepare_sequence<'a>(
+    /// Prepare SealMembership message.
+    pub fn prepare_seal_membership<'a>(
         &'a mut self,
         link_to: &'a Link,
-        seq_no: u64,
-        ref_link: &'a <Link as HasLink>::Rel,
-    ) -> Result<PreparedMessage<'a, F, Link, LS, sequence::ContentWrap<'a, Link>>> {
-        let identifier = self.get_identifier()?;
-        let msg_link = self
-            .link_gen
-            .link_from(&identifier, Cursor::new_at(link_to.rel(), 0, SEQ_MESSAGE_NUM));
-        let header = HDF::new(msg_link)
-            .with_previous_msg_link(Bytes(link_to.to_bytes()))
-            .with_content_type(SEQUENCE)?
-            .with_payload_length(1)?
-            .with_seq_num(SEQ_MESSAGE_NUM)
-            .with_identifier(&identifier);
+    ) -> Result<PreparedMessage<'a, F, Link, LS, seal_membership::ContentWrap<'a, F, Link>>> {
+        try_or!(
+            self.is_trusted_author(&self.sig_kp.public),
+            SealNotFromAuthor(hex::encode(self.sig_kp.public.as_bytes()))
+        )?;
+        match self.get_seq_no() {
+            Some(seq_no) => {
+                let msg_link = self
+                    .link_gen
+                    .link_from(&self.sig_kp.public.into(), Cursor::new_at(link_to.rel(), 0, seq_no));
+                let header = HDF::new(msg_link)
+                    .with_previous_msg_link(Bytes(link_to.to_bytes()))
+                    .with_content_type(SEAL_MEMBERSHIP)?
+                    .with_payload_length(1)?
+                    .with_seq_num(seq_no)
+                    .with_identifier(&self.sig_kp.public.into())
+                    .with_link_fingerprint(self.link_fingerprint);
+                let content = seal_membership::ContentWrap {
+                    link: link_to.rel(),
+                    sig_kp: &self.sig_kp,
+                    _phantom: core::marker::PhantomData,
+                };
+                Ok(self.prepared(header, content))
+            }
+            None => err!(SeqNumRetrievalFailure),
+        }
+    }
 
-        let content = sequence::ContentWrap {
-            link: link_to.rel(),
-            id: identifier,
-            seq_num: seq_no,
-            ref_link,
-        };
+    /// Create a SealMembership message freezing the channel membership, only trusted channel
+    /// authors can seal the membership.
+    pub fn seal_membership(&mut self, link_to: &Link) -> Result<WrappedMessage<F, Link>> {
+        self.prepare_seal_membership(link_to)?.wrap()
+    }
 
-        Ok(PreparedMessage::new(self.link_store.borrow(), header, content))
+    pub fn unwrap_seal_membership<'a>(
+        &'a self,
+        preparsed: PreparsedMessage<'a, F, Link>,
+    ) -> Result<UnwrappedMessage<F, Link, seal_membership::ContentUnwrap<F, Link>>> {
+        self.ensure_appinst(&preparsed)?;
+        try_or!(
+            preparsed.content_type() == SEAL_MEMBERSHIP,
+            NotSealMembershipMessage(preparsed.content_type())
+        )?;
+        let content = seal_membership::ContentUnwrap::default();
+        preparsed.unwrap(&*self.link_store.borrow(), content)
     }
 
-    pub fn wrap_sequence(&mut self, ref_link: &<Link as HasLink>::Rel) -> Result<WrappedSequence<F, Link>> {
-        let identifier = self.get_identifier()?;
-        match self.key_store.get(&identifier) {
-            Some(cursor) => {
-                let mut cursor = cursor.clone();
-                if (self.flags & FLAG_BRANCHING_MASK) != 0 {
-                    let msg_link = self
-                        .link_gen
-                        .link_from(&identifier, Cursor::new_at(&cursor.link, 0, SEQ_MESSAGE_NUM));
-                    let previous_msg_link = Link::from_base_rel(self.appinst.as_ref().unwrap().base(), &cursor.link);
+    /// Verify SealMembership message signed by a trusted channel author and mark the channel
+    /// membership as sealed, further subscriptions are rejected.
+    pub fn handle_seal_membership(
+        &'_ mut self,
+        msg: BinaryMessage<F, Link>,
+        info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
+    ) -> Result<GenericMessage<Link, ()>> {
+        let preparsed = self.parse_message(&msg)?;
+        let prev_link = Link::from_bytes(&preparsed.header.previous_msg_link.0);
+        let seq_no = preparsed.header.seq_num;
+        let unwrapped = self.unwrap_seal_membership(preparsed)?;
+        let sig_pk = unwrapped.pcf.content.sig_pk;
+        // Signature is verified at this point, the sender can be checked against the authors
+        try_or!(
+            self.is_trusted_author(&sig_pk),
+            SealNotFromAuthor(hex::encode(sig_pk.as_bytes()))
+        )?;
+        unwrapped.commit(self.link_store.borrow_mut(), info)?;
+        if !self.is_multi_branching() {
+            self.store_state_for_all(msg.link.rel().clone(), seq_no.0 as u32 + 1)?;
+        }
+        self.membership_sealed = true;
+        Ok(GenericMessage::new(msg.link, prev_link, ()))
+    }
+
+    /// Returns true if the membership of the current channel was sealed by its author, no new
+    /// subscribers are accepted.
+    ///
+    /// Note: the flag is not part of the exported user state.
+    pub fn is_membership_sealed(&self) -> bool {
+        self.membership_sealed
+    }
+
+    /// Prepare Ack message.
+    pub fn prepare_ack<'a>(
+        &'a mut self,
+        link_to: &'a Link,
+        position: usize,
+    ) -> Result<PreparedMessage<'a, F, Link, LS, ack::ContentWrap<'a, F, Link>>> {
+        if self.use_psk {
+            return err(MessageBuildFailure);
+        }
+        match self.get_seq_no() {
+            Some(seq_no) => {
+                let msg_link = self
+                    .link_gen
+                    .link_from(&self.sig_kp.public.into(), Cursor::new_at(link_to.rel(), 0, seq_no));
+                let header = HDF::new(msg_link)
+                    .with_previous_msg_link(Bytes(link_to.to_bytes()))
+                    .with_content_type(ACK)?
+                    .with_payload_length(1)?
+                    .with_seq_num(seq_no)
+                    .with_identifier(&self.sig_kp.public.into())
+                    .with_link_fingerprint(self.link_fingerprint);
+                let content = ack::ContentWrap {
+                    link: link_to.rel(),
+                    sig_kp: &self.sig_kp,
+                    position: position as u64,
+                    _phantom: core::marker::PhantomData,
+                };
+                Ok(self.prepared(header, content))
+            }
+            None => err!(SeqNumRetrievalFailure),
+        }
+    }
+
+    /// Create an Ack message acknowledging that the channel was read up to the linked message,
+    /// `position` is the sequence number of the acknowledged message.
+    pub fn ack(&mut self, link_to: &Link, position: usize) -> Result<WrappedMessage<F, Link>> {
+        self.prepare_ack(link_to, position)?.wrap()
+    }
+
+    pub fn unwrap_ack<'a>(
+        &'a self,
+        preparsed: PreparsedMessage<'a, F, Link>,
+    ) -> Result<UnwrappedMessage<F, Link, ack::ContentUnwrap<F, Link>>> {
+        self.ensure_appinst(&preparsed)?;
+        try_or!(preparsed.content_type() == ACK, NotAckMessage(preparsed.content_type()))?;
+        let content = ack::ContentUnwrap::default();
+        preparsed.unwrap(&*self.link_store.borrow(), content)
+    }
+
+    /// Verify Ack message and record the position acknowledged by its publisher, the highest
+    /// position is kept when acks are processed out of order. Returns the public key of the
+    /// publisher and the acknowledged position.
+    pub fn handle_ack(
+        &'_ mut self,
+        msg: BinaryMessage<F, Link>,
+        info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
+    ) -> Result<GenericMessage<Link, (ed25519::PublicKey, usize)>> {
+        let preparsed = self.parse_message(&msg)?;
+        let prev_link = Link::from_bytes(&preparsed.header.previous_msg_link.0);
+        let seq_no = preparsed.header.seq_num;
+        let unwrapped = self.unwrap_ack(preparsed)?;
+        let content = unwrapped.commit(self.link_store.borrow_mut(), info)?;
+        if !self.is_multi_branching() {
+            self.store_state_for_all(msg.link.rel().clone(), seq_no.0 as u32 + 1)?;
+        }
+        let position = content.position.0 as usize;
+        let acked = self.acks.entry(content.sig_pk.into()).or_insert(position);
+        *acked = core::cmp::max(*acked, position);
+        Ok(GenericMessage::new(msg.link, prev_link, (content.sig_pk, position)))
+    }
+
+    /// Highest position acknowledged by the publisher in processed ack messages, if any.
+    pub fn acked_position(&self, publisher: &Identifier) -> Option<usize> {
+        self.acks.get(publisher).copied()
+    }
+
+    /// Prepare Checkpoint message summarizing the channel up to the `head` message.
+    pub fn prepare_checkpoint<'a>(
+        &'a mut self,
+        head: &'a Link,
+    ) -> Result<PreparedMessage<'a, F, Link, LS, checkpoint::ContentWrap<'a, F, Link>>> {
+        try_or!(!self.is_multi_branching(), CheckpointsUnsupported)?;
+        try_or!(
+            self.is_trusted_author(&self.sig_kp.public),
+            CheckpointNotFromAuthor(hex::encode(self.sig_kp.public.as_bytes()))
+        )?;
+        let appinst = match &self.appinst {
+            Some(appinst) => appinst,
+            None => return err!(UserNotRegistered),
+        };
+        let (head_spongos, _info) = self.link_store.borrow().lookup(head.rel())?;
+        let digest = checkpoint::digest(head_spongos);
+        let ids = self.key_store.iter().into_iter().map(|(id, _cursor)| *id).collect();
+        match self.get_seq_no() {
+            Some(seq_no) => {
+                let msg_link = self
+                    .link_gen
+                    .link_from(&self.sig_kp.public.into(), Cursor::new_at(head.rel(), 0, seq_no));
+                let header = HDF::new(msg_link)
+                    .with_previous_msg_link(Bytes(appinst.to_bytes()))
+                    .with_content_type(CHECKPOINT)?
+                    .with_payload_length(1)?
+                    .with_seq_num(seq_no)
+                    .with_identifier(&self.sig_kp.public.into())
+                    .with_link_fingerprint(self.link_fingerprint);
+                let content = checkpoint::ContentWrap {
+                    link: appinst.rel(),
+                    head: head.rel(),
+                    digest,
+                    ids,
+                    sig_kp: &self.sig_kp,
+                    _phantom: core::marker::PhantomData,
+                };
+                Ok(self.prepared(header, content))
+            }
+            None => err!(SeqNumRetrievalFailure),
+        }
+    }
+
+    /// Create a Checkpoint message summarizing a single-branch channel up to the `head` message,
+    /// only trusted channel authors can publish checkpoints. The checkpoint is joined to the
+    /// announcement and the following messages should be linked to it.
+    pub fn checkpoint(&mut self, head: &Link) -> Result<WrappedMessage<F, Link>> {
+        self.prepare_checkpoint(head)?.wrap()
+    }
+
+    pub fn unwrap_checkpoint<'a>(
+        &'a self,
+        preparsed: PreparsedMessage<'a, F, Link>,
+    ) -> Result<UnwrappedMessage<F, Link, checkpoint::ContentUnwrap<F, Link>>> {
+        self.ensure_appinst(&preparsed)?;
+        try_or!(
+            preparsed.content_type() == CHECKPOINT,
+            NotCheckpointMessage(preparsed.content_type())
+        )?;
+        let content = checkpoint::ContentUnwrap::default();
+        preparsed.unwrap(&*self.link_store.borrow(), content)
+    }
+
+    /// Verify Checkpoint message signed by a trusted channel author and continue reading the channel
+    /// from it: cursors of all publishers are moved to the checkpoint and publishers listed in the
+    /// checkpoint are added to the key store. The digest is checked if the summarized message was
+    /// processed by the user, so only the announcement is required. Returns the link to the
+    /// summarized message.
+    pub fn handle_checkpoint(
+        &'_ mut self,
+        msg: BinaryMessage<F, Link>,
+        info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
+    ) -> Result<GenericMessage<Link, Link>> {
+        try_or!(!self.is_multi_branching(), CheckpointsUnsupported)?;
+        let preparsed = self.parse_message(&msg)?;
+        let prev_link = Link::from_bytes(&preparsed.header.previous_msg_link.0);
+        let seq_no = preparsed.header.seq_num.0 as u32 + 1;
+        let unwrapped = self.unwrap_checkpoint(preparsed)?;
+        let sig_pk = unwrapped.pcf.content.sig_pk;
+        // Signature is verified at this point, the sender can be checked against the authors
+        try_or!(
+            self.is_trusted_author(&sig_pk),
+            CheckpointNotFromAuthor(hex::encode(sig_pk.as_bytes()))
+        )?;
+        let head = Link::from_base_rel(msg.link.base(), &unwrapped.pcf.content.head);
+        if let Ok((head_spongos, _info)) = self.link_store.borrow().lookup(head.rel()) {
+            try_or!(
+                checkpoint::digest(head_spongos) == unwrapped.pcf.content.digest,
+                CheckpointDigestMismatch(hex::encode(head.to_bytes()))
+            )?;
+        }
+        let content = unwrapped.commit(self.link_store.borrow_mut(), info)?;
+        for id in content.ids {
+            if !self.key_store.contains(&id) {
+                self.key_store.insert_cursor(id, Cursor::new_at(msg.link.rel().clone(), 0, seq_no))?;
+            }
+        }
+        self.store_state_for_all(msg.link.rel().clone(), seq_no)?;
+        Ok(GenericMessage::new(msg.link, prev_link, head))
+    }
+
+    /// Prepare AddAuthor message.
+    pub fn prepare_add_author<'a>(
+        &'a mut self,
+        link_to: &'a Link,
+        author_sig_pk: &'a ed25519::PublicKey,
+    ) -> Result<PreparedMessage<'a, F, Link, LS, add_author::ContentWrap<'a, F, Link>>> {
+        try_or!(
+            self.is_trusted_author(&self.sig_kp.public),
+            AddAuthorNotFromAuthor(hex::encode(self.sig_kp.public.as_bytes()))
+        )?;
+        match self.get_seq_no() {
+            Some(seq_no) => {
+                let msg_link = self
+                    .link_gen
+                    .link_from(&self.sig_kp.public.into(), Cursor::new_at(link_to.rel(), 0, seq_no));
+                let header = HDF::new(msg_link)
+                    .with_previous_msg_link(Bytes(link_to.to_bytes()))
+                    .with_content_type(ADD_AUTHOR)?
+                    .with_payload_length(1)?
+                    .with_seq_num(seq_no)
+                    .with_identifier(&self.sig_kp.public.into())
+                    .with_link_fingerprint(self.link_fingerprint);
+                let content = add_author::ContentWrap {
+                    link: link_to.rel(),
+                    sig_kp: &self.sig_kp,
+                    author_sig_pk,
+                    _phantom: core::marker::PhantomData,
+                };
+                Ok(self.prepared(header, content))
+            }
+            None => err!(SeqNumRetrievalFailure),
+        }
+    }
+
+    /// Create an AddAuthor message trusting another author of the channel, eg. to co-publish
+    /// keyloads in a federated channel. Only trusted channel authors can add authors.
+    pub fn add_author(&mut self, link_to: &Link, author_sig_pk: &ed25519::PublicKey) -> Result<WrappedMessage<F, Link>> {
+        self.prepare_add_author(link_to, author_sig_pk)?.wrap()
+    }
+
+    pub fn unwrap_add_author<'a>(
+        &'a self,
+        preparsed: PreparsedMessage<'a, F, Link>,
+    ) -> Result<UnwrappedMessage<F, Link, add_author::ContentUnwrap<F, Link>>> {
+        self.ensure_appinst(&preparsed)?;
+        try_or!(
+            preparsed.content_type() == ADD_AUTHOR,
+            NotAddAuthorMessage(preparsed.content_type())
+        )?;
+        let content = add_author::ContentUnwrap::default();
+        preparsed.unwrap(&*self.link_store.borrow(), content)
+    }
+
+    /// Verify AddAuthor message signed by a trusted channel author and trust the added author.
+    /// Returns the public key of the added author.
+    pub fn handle_add_author(
+        &'_ mut self,
+        msg: BinaryMessage<F, Link>,
+        info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
+    ) -> Result<GenericMessage<Link, ed25519::PublicKey>> {
+        let preparsed = self.parse_message(&msg)?;
+        let prev_link = Link::from_bytes(&preparsed.header.previous_msg_link.0);
+        let seq_no = preparsed.header.seq_num;
+        let unwrapped = self.unwrap_add_author(preparsed)?;
+        let sig_pk = unwrapped.pcf.content.sig_pk;
+        // Signature is verified at this point, the sender can be checked against the authors
+        try_or!(
+            self.is_trusted_author(&sig_pk),
+            AddAuthorNotFromAuthor(hex::encode(sig_pk.as_bytes()))
+        )?;
+        let content = unwrapped.commit(self.link_store.borrow_mut(), info)?;
+        if !self.is_multi_branching() {
+            self.store_state_for_all(msg.link.rel().clone(), seq_no.0 as u32 + 1)?;
+        }
+        self.trust_author(content.author_sig_pk)?;
+        Ok(GenericMessage::new(msg.link, prev_link, content.author_sig_pk))
+    }
+
+    /// Add the author to the trusted ones, messages of the author are fetched from now on.
+    pub(crate) fn trust_author(&mut self, author_sig_pk: ed25519::PublicKey) -> Result<()> {
+        if self.is_trusted_author(&author_sig_pk) {
+            return Ok(());
+        }
+        let ref_link = match &self.appinst {
+            Some(appinst) => appinst.rel().clone(),
+            None => return err(UserNotRegistered),
+        };
+        let author = Identifier::EdPubKey(author_sig_pk.into());
+        if !self.key_store.contains(&author) {
+            self.key_store
+                .insert_cursor(author, Cursor::new_at(ref_link, 0, INIT_MESSAGE_NUM))?;
+        }
+        self.co_authors.push(author_sig_pk);
+        Ok(())
+    }
+
+    /// Prepare TaggedPacket message.
+    pub fn prepare_tagged_packet<'a>(
+        &'a mut self,
+        link_to: &'a Link,
+        public_payload: &'a Bytes,
+        masked_payload: &'a Bytes,
+    ) -> Result<PreparedMessage<'a, F, Link, LS, tagged_packet::ContentWrap<'a, F, Link>>> {
+        self.prepare_tagged_packet_with_context(link_to, public_payload, masked_payload, None)
+    }
+
+    /// Prepare TaggedPacket message bound to an application context. The context is sent in plain
+    /// and is covered by the MAC.
+    pub fn prepare_tagged_packet_with_context<'a>(
+        &'a mut self,
+        link_to: &'a Link,
+        public_payload: &'a Bytes,
+        masked_payload: &'a Bytes,
+        context: Option<&'a Bytes>,
+    ) -> Result<PreparedMessage<'a, F, Link, LS, tagged_packet::ContentWrap<'a, F, Link>>> {
+        self.prepare_tagged_packet_content(link_to, public_payload, masked_payload, context, 0)
+    }
+
+    /// Prepare TaggedPacket message tagged with an application type, eg. to route the message on
+    /// reception. The type is sent in plain and is covered by the MAC, the default type 0 is not
+    /// encoded.
+    pub fn prepare_tagged_packet_with_app_type<'a>(
+        &'a mut self,
+        link_to: &'a Link,
+        public_payload: &'a Bytes,
+        masked_payload: &'a Bytes,
+        app_type: u16,
+    ) -> Result<PreparedMessage<'a, F, Link, LS, tagged_packet::ContentWrap<'a, F, Link>>> {
+        self.prepare_tagged_packet_content(link_to, public_payload, masked_payload, None, app_type)
+    }
+
+    fn prepare_tagged_packet_content<'a>(
+        &'a mut self,
+        link_to: &'a Link,
+        public_payload: &'a Bytes,
+        masked_payload: &'a Bytes,
+        context: Option<&'a Bytes>,
+        app_type: u16,
+    ) -> Result<PreparedMessage<'a, F, Link, LS, tagged_packet::ContentWrap<'a, F, Link>>> {
+        let identifier = self.get_identifier()?;
+        match self.get_seq_no() {
+            Some(seq_no) => {
+                let msg_link = self
+                    .link_gen
+                    .link_from(&identifier, Cursor::new_at(link_to.rel(), 0, seq_no));
+                let header = HDF::new(msg_link)
+                    .with_previous_msg_link(Bytes(link_to.to_bytes()))
+                    .with_content_type(TAGGED_PACKET)?
+                    .with_payload_length(1)?
+                    .with_seq_num(seq_no)
+                    .with_identifier(&identifier)
+                    .with_link_fingerprint(self.link_fingerprint)
+                    .with_content_context(context.is_some())
+                    .with_content_app_type(app_type != 0)
+                    .with_content_mac_length(self.tag_mac_length != spongos::MacSize::<F>::USIZE)
+                    .with_content_masked_blocks(self.masked_block_size.is_some());
+                let content = tagged_packet::ContentWrap {
+                    link: link_to.rel(),
+                    public_payload,
+                    masked_payload,
+                    context,
+                    app_type,
+                    masked_block_size: self.masked_block_size,
+                    mac_length: self.tag_mac_length,
+                    _phantom: core::marker::PhantomData,
+                };
+                Ok(self.prepared(header, content))
+            }
+            None => err!(SeqNumRetrievalFailure),
+        }
+    }
+
+    fn get_identifier(&mut self) -> Result<Identifier> {
+        if self.use_psk {
+            match self.key_store.get_next_pskid() {
+                Some(pskid) => Ok(*pskid),
+                None => err(MessageBuildFailure),
+            }
+        } else {
+            Ok(self.sig_kp.public.into())
+        }
+    }
+
+    /// Create a tagged (ie. MACed) message with public and masked payload.
+    /// Tagged messages must be linked to a secret spongos state, ie. keyload or a message linked to keyload.
+    pub fn tag_packet(
+        &mut self,
+        link_to: &Link,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+    ) -> Result<WrappedMessage<F, Link>> {
+        let (compressed, is_compressed) = self.compress_payload(public_payload)?;
+        let public_payload = compressed.as_ref().unwrap_or(public_payload);
+        let encoded = self.encode_payload(masked_payload)?;
+        let masked_payload = encoded.as_ref().unwrap_or(masked_payload);
+        let mut prepared = self.prepare_tagged_packet(link_to, public_payload, masked_payload)?;
+        prepared.header.content_compressed_payload = is_compressed;
+        prepared.wrap()
+    }
+
+    /// Size in bytes of TaggedPacket message with the given payloads, the message is not wrapped.
+    pub fn estimate_tagged_packet_size(
+        &mut self,
+        link_to: &Link,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+    ) -> Result<usize> {
+        let (compressed, is_compressed) = self.compress_payload(public_payload)?;
+        let public_payload = compressed.as_ref().unwrap_or(public_payload);
+        let encoded = self.encode_payload(masked_payload)?;
+        let masked_payload = encoded.as_ref().unwrap_or(masked_payload);
+        let mut prepared = self.prepare_tagged_packet(link_to, public_payload, masked_payload)?;
+        prepared.header.content_compressed_payload = is_compressed;
+        prepared.size()
+    }
+
+    /// Create a tagged message with public and masked payload bound to an application context.
+    pub fn tag_packet_with_context(
+        &mut self,
+        link_to: &Link,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        context: &Bytes,
+    ) -> Result<WrappedMessage<F, Link>> {
+        let (compressed, is_compressed) = self.compress_payload(public_payload)?;
+        let public_payload = compressed.as_ref().unwrap_or(public_payload);
+        let encoded = self.encode_payload(masked_payload)?;
+        let masked_payload = encoded.as_ref().unwrap_or(masked_payload);
+        let mut prepared =
+            self.prepare_tagged_packet_with_context(link_to, public_payload, masked_payload, Some(context))?;
+        prepared.header.content_compressed_payload = is_compressed;
+        prepared.wrap()
+    }
+
+    /// Create a tagged message with public and masked payload tagged with an application type.
+    pub fn tag_packet_with_app_type(
+        &mut self,
+        link_to: &Link,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        app_type: u16,
+    ) -> Result<WrappedMessage<F, Link>> {
+        let (compressed, is_compressed) = self.compress_payload(public_payload)?;
+        let public_payload = compressed.as_ref().unwrap_or(public_payload);
+        let encoded = self.encode_payload(masked_payload)?;
+        let masked_payload = encoded.as_ref().unwrap_or(masked_payload);
+        let mut prepared =
+            self.prepare_tagged_packet_with_app_type(link_to, public_payload, masked_payload, app_type)?;
+        prepared.header.content_compressed_payload = is_compressed;
+        prepared.wrap()
+    }
+
+    pub fn unwrap_tagged_packet(
+        &self,
+        preparsed: PreparsedMessage<'_, F, Link>,
+    ) -> Result<UnwrappedMessage<F, Link, tagged_packet::ContentUnwrap<F, Link>>> {
+        self.ensure_appinst(&preparsed)?;
+        let content = tagged_packet::ContentUnwrap::new()
+            .with_context(preparsed.header.has_content_context())
+            .with_app_type(preparsed.header.has_content_app_type())
+            .with_mac_length(preparsed.header.has_content_mac_length())
+            .with_masked_blocks(preparsed.header.has_content_masked_blocks())
+            .with_max_payload_length(self.max_payload_length);
+        preparsed.unwrap(&*self.link_store.borrow(), content)
+    }
+
+    /// Get public payload, decrypt masked payload and verify MAC.
+    pub fn handle_tagged_packet(
+        &mut self,
+        msg: BinaryMessage<F, Link>,
+        info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
+    ) -> Result<GenericMessage<Link, (Bytes, Bytes)>> {
+        self.handle_tagged_packet_with_context(msg, info)
+            .map(|m| m.map(|(public, masked, _context)| (public, masked)))
+    }
+
+    /// Verify tagged packet and get its payloads together with the application context, if any.
+    pub fn handle_tagged_packet_with_context(
+        &mut self,
+        msg: BinaryMessage<F, Link>,
+        info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
+    ) -> Result<GenericMessage<Link, (Bytes, Bytes, Option<Bytes>)>> {
+        self.handle_tagged_packet_content(msg, info)
+            .map(|m| m.map(|content| (content.public_payload, content.masked_payload, content.context)))
+    }
+
+    /// Verify tagged packet and get its unwrapped content.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub(crate) fn handle_tagged_packet_content(
+        &mut self,
+        msg: BinaryMessage<F, Link>,
+        info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
+    ) -> Result<GenericMessage<Link, tagged_packet::ContentUnwrap<F, Link>>> {
+        let preparsed = self.parse_message(&msg)?;
+        let prev_link = Link::from_bytes(&preparsed.header.previous_msg_link.0);
+        let seq_no = preparsed.header.seq_num;
+        self.check_sequence(&preparsed)?;
+        let compressed = preparsed.header.has_content_compressed_payload();
+        let mut unwrapped = self.unwrap_tagged_packet(preparsed)?;
+        self.decompress_payload(&mut unwrapped.pcf.content.public_payload, compressed)?;
+        self.decode_payload(&mut unwrapped.pcf.content.masked_payload)?;
+        let content = unwrapped.commit(self.link_store.borrow_mut(), info)?;
+        if !self.is_multi_branching() {
+            self.store_state_for_all(msg.link.rel().clone(), seq_no.0 as u32 + 1)?;
+        }
+
+        Ok(GenericMessage::new(msg.link, prev_link, content))
+    }
+
+    /// Unwrap a message with application defined content, eg. of a custom content type not known
+    /// to the user.
+    ///
+    /// The message is processed as the built-in packets are: the header is parsed, the message is
+    /// checked to belong to the user channel, the content is unwrapped starting from the linked
+    /// message spongos state, the resulting state is saved to the link store with `info` so that
+    /// other messages can be linked to this one, and, in single-branch channels, the sequence states
+    /// of all publishers are moved past the message.
+    ///
+    /// The caller is responsible for checking the content type of the message, for authenticating
+    /// its sender within the content (eg. with `ed25519` signature command), and in multi-branch
+    /// channels for handling the sequence message referring to it, if any.
+    pub fn handle_custom<C>(
+        &mut self,
+        msg: BinaryMessage<F, Link>,
+        info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
+        content: C,
+    ) -> Result<GenericMessage<Link, C>>
+    where
+        C: ContentUnwrap<F, LS>,
+    {
+        let preparsed = self.parse_message(&msg)?;
+        self.ensure_appinst(&preparsed)?;
+        let prev_link = Link::from_bytes(&preparsed.header.previous_msg_link.0);
+        let seq_no = preparsed.header.seq_num;
+        let content = preparsed
+            .unwrap(&*self.link_store.borrow(), content)?
+            .commit(self.link_store.borrow_mut(), info)?;
+        if !self.is_multi_branching() {
+            self.store_state_for_all(msg.link.rel().clone(), seq_no.0 as u32 + 1)?;
+        }
+        Ok(GenericMessage::new(msg.link, prev_link, content))
+    }
+
+    pub fn prepare_sequence<'a>(
+        &'a mut self,
+        link_to: &'a Link,
+        seq_no: u64,
+        ref_link: &'a <Link as HasLink>::Rel,
+    ) -> Result<PreparedMessage<'a, F, Link, LS, sequence::ContentWrap<'a, Link>>> {
+        let identifier = self.get_identifier()?;
+        let msg_link = self
+            .link_gen
+            .link_from(&identifier, Cursor::new_at(link_to.rel(), 0, SEQ_MESSAGE_NUM));
+        let header = HDF::new(msg_link)
+            .with_previous_msg_link(Bytes(link_to.to_bytes()))
+            .with_content_type(SEQUENCE)?
+            .with_payload_length(1)?
+            .with_seq_num(SEQ_MESSAGE_NUM)
+            .with_identifier(&identifier);
+
+        let content = sequence::ContentWrap {
+            link: link_to.rel(),
+            id: identifier,
+            seq_num: seq_no,
+            ref_link,
+        };
+
+        Ok(self.prepared(header, content))
+    }
+
+    pub fn wrap_sequence(&mut self, ref_link: &<Link as HasLink>::Rel) -> Result<WrappedSequence<F, Link>> {
+        let identifier = self.get_identifier()?;
+        match self.key_store.get(&identifier) {
+            Some(cursor) => {
+                let mut cursor = cursor.clone();
+                if (self.flags & FLAG_BRANCHING_MASK) != 0 {
+                    let msg_link = self
+                        .link_gen
+                        .link_from(&identifier, Cursor::new_at(&cursor.link, 0, SEQ_MESSAGE_NUM));
+                    let previous_msg_link = Link::from_base_rel(self.appinst.as_ref().unwrap().base(), &cursor.link);
                     let header = HDF::new(msg_link)
                         .with_previous_msg_link(Bytes(previous_msg_link.to_bytes()))
                         .with_content_type(SEQUENCE)?
@@ -821,7 +2933,7 @@ where
                     };
 
                     let wrapped = {
-                        let prepared = PreparedMessage::new(self.link_store.borrow(), header, content);
+                        let prepared = self.prepared(header, content);
                         prepared.wrap()?
                     };
 
@@ -873,13 +2985,14 @@ where
     }
 
     // Fetch unwrapped sequence message to fetch referenced message
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
     pub fn handle_sequence(
         &mut self,
         msg: BinaryMessage<F, Link>,
         info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
         store: bool,
     ) -> Result<GenericMessage<Link, sequence::ContentUnwrap<Link>>> {
-        let preparsed = msg.parse_header()?;
+        let preparsed = self.parse_message(&msg)?;
         let sender_id = preparsed.header.sender_id;
         let prev_link = Link::from_bytes(&preparsed.header.previous_msg_link.0);
         let content = self
@@ -906,6 +3019,38 @@ where
             .map(|cursor| cursor.seq_no)
     }
 
+    /// Sequence number of the next message expected from the publisher.
+    pub fn next_seq_no(&self, id: &Identifier) -> Result<u32> {
+        try_or!(self.appinst.is_some(), UserNotRegistered)?;
+        match self.key_store.get(id) {
+            Some(cursor) => Ok(cursor.seq_no),
+            None => err!(UnknownPublisher(hex::encode(id.to_bytes()))),
+        }
+    }
+
+    /// Retrieve own sequencing state.
+    pub fn get_own_cursor(&self) -> Option<Cursor<<Link as HasLink>::Rel>> {
+        self.key_store
+            .get(&Identifier::EdPubKey(self.sig_kp.public.into()))
+            .cloned()
+    }
+
+    /// Forget the spongos state associated to the message link and restore own sequencing state.
+    /// Used to roll back a partially sent chain of messages.
+    pub fn rollback_link(
+        &mut self,
+        link: &<Link as HasLink>::Rel,
+        cursor: Option<Cursor<<Link as HasLink>::Rel>>,
+    ) -> Result<()> {
+        self.link_store.borrow_mut().erase(link);
+        self.unindex_topics(core::slice::from_ref(link));
+        if let Some(cursor) = cursor {
+            self.key_store
+                .insert_cursor(Identifier::EdPubKey(self.sig_kp.public.into()), cursor)?;
+        }
+        Ok(())
+    }
+
     pub fn ensure_appinst<'a>(&self, preparsed: &PreparsedMessage<'a, F, Link>) -> Result<()> {
         try_or!(self.appinst.is_some(), UserNotRegistered)?;
         try_or!(
@@ -929,7 +3074,7 @@ where
                     self.key_store.insert_psk(
                         (&pskid).into(),
                         Some(psk),
-                        Cursor::new_at(appinst.rel().clone(), 0, 2_u32),
+                        Cursor::new_at(appinst.rel().clone(), 0, INIT_MESSAGE_NUM),
                     )?;
                     self.use_psk = use_psk;
                     Ok(())
@@ -941,82 +3086,706 @@ where
         }
     }
 
+    /// Identifiers of the pre-shared keys stored by the user in ascending order. They can be passed
+    /// as `psk_ids` of a keyload.
+    pub fn psk_ids(&self) -> Vec<PskId> {
+        let mut psk_ids: Vec<PskId> = self
+            .key_store
+            .keys()
+            .into_iter()
+            .filter_map(|(id, _psk)| match id {
+                Identifier::PskId(pskid) => Some(*pskid),
+                _ => None,
+            })
+            .collect();
+        psk_ids.sort();
+        psk_ids
+    }
+
+    /// Link and sequence number of the next message expected from the publisher with the stored
+    /// cursor. In single-branch channels it is the next message itself, derived from the last
+    /// message and the stored sequence number. In multi-branch channels it is the sequence message
+    /// referencing the next message, derived from the last message of the publisher at
+    /// `SEQ_MESSAGE_NUM`.
+    fn next_msg_cursor(
+        link_gen: &LG,
+        id: &Identifier,
+        cursor: &Cursor<<Link as HasLink>::Rel>,
+        branching: bool,
+    ) -> Cursor<Link> {
+        let seq_no = if branching { SEQ_MESSAGE_NUM } else { cursor.seq_no };
+        let msg_id = link_gen.link_from(id, Cursor::new_at(&cursor.link, 0, seq_no));
+        Cursor::new_at(msg_id, 0, seq_no)
+    }
+
     fn gen_next_msg_id(
         ids: &mut Vec<(Identifier, Cursor<Link>)>,
         link_gen: &LG,
         pk_info: (&Identifier, &Cursor<<Link as HasLink>::Rel>),
         branching: bool,
     ) {
-        let (
-            id,
+        let (id, cursor) = pk_info;
+        ids.push((*id, Self::next_msg_cursor(link_gen, id, cursor, branching)));
+    }
+
+    /// Derive the link of the next message expected from the publisher, see `gen_next_msg_ids`.
+    pub fn next_link(&self, id: &Identifier) -> Result<Link> {
+        try_or!(self.appinst.is_some(), UserNotRegistered)?;
+        match self.key_store.get(id) {
+            Some(cursor) => Ok(Self::next_msg_cursor(&self.link_gen, id, cursor, self.is_multi_branching()).link),
+            None => err!(UnknownPublisher(hex::encode(id.to_bytes()))),
+        }
+    }
+
+    // TODO: Turn it into iterator.
+    pub fn gen_next_msg_ids(&self, branching: bool) -> Vec<(Identifier, Cursor<Link>)> {
+        let mut ids = Vec::new();
+
+        // TODO: Do the same for self.sig_kp.public
+        for pk_info in self.key_store.iter() {
+            Self::gen_next_msg_id(&mut ids, &self.link_gen, pk_info, branching);
+        }
+        ids
+    }
+
+    pub fn store_state(&mut self, id: Identifier, link: <Link as HasLink>::Rel) -> Result<()> {
+        if let Some(cursor) = self.key_store.get(&id) {
+            let mut cursor = cursor.clone();
+            cursor.link = link;
+            cursor.next_seq();
+            debug_event!(publisher = %hex::encode(id.to_bytes()), seq_no = cursor.seq_no, "cursor advanced");
+            self.key_store.insert_cursor(id, cursor)?;
+        }
+        Ok(())
+    }
+
+    pub fn store_state_for_all(&mut self, link: <Link as HasLink>::Rel, seq_no: u32) -> Result<()> {
+        if &seq_no > self.get_seq_no().as_ref().unwrap_or(&0) {
+            self.key_store.insert_cursor(
+                Identifier::EdPubKey(self.sig_kp.public.into()),
+                Cursor::new_at(link.clone(), 0, seq_no),
+            )?;
+            for (_pk, cursor) in self.key_store.iter_mut() {
+                cursor.link = link.clone();
+                cursor.seq_no = seq_no;
+            }
+            debug_event!(seq_no, "cursors of all publishers advanced");
+        }
+        Ok(())
+    }
+
+    /// Number of message spongos states saved in the link store.
+    pub fn spongos_store_len(&self) -> usize {
+        self.link_store.borrow().iter().len()
+    }
+
+    /// Edges of the message graph: links of processed messages paired with the links of the
+    /// messages they are linked to. Announcements are linked to no message and have no edge.
+    /// Edges are recorded as messages are sent or handled, they are not kept in backups.
+    pub fn dependency_edges(&self) -> Vec<(<Link as HasLink>::Rel, <Link as HasLink>::Rel)> {
+        self.link_store
+            .borrow()
+            .linked()
+            .into_iter()
+            .map(|(link, linked)| (link.clone(), linked.clone()))
+            .collect()
+    }
+
+    /// Digest committing to the processed message chain: the links and spongos states of all the
+    /// messages saved in the link store, ordered by link. Users with the same view of the channel
+    /// get the same digest, it changes when a message is added, removed or altered. Spongos states
+    /// are hashed, so the digest can be published without revealing them.
+    pub fn chain_digest(&self) -> NBytes<U32> {
+        let link_store = self.link_store.borrow();
+        let mut entries: Vec<(Vec<u8>, &[u8])> = match &self.appinst {
+            Some(appinst) => link_store
+                .iter()
+                .into_iter()
+                .map(|(link, (s, _info))| (Link::from_base_rel(appinst.base(), link).to_bytes(), s.arr().as_slice()))
+                .collect(),
+            None => Vec::new(),
+        };
+        entries.sort();
+        let mut s = spongos::Spongos::<F>::init();
+        s.absorb(b"chain digest");
+        for (link, inner) in entries {
+            s.absorb((link.len() as u64).to_be_bytes());
+            s.absorb(link);
+            s.absorb(inner);
+        }
+        s.commit();
+        NBytes(s.squeeze_arr())
+    }
+
+    /// Compare the channel state of the user with that of another user, eg. an author with one of
+    /// its subscribers, to find where their views diverge. Cursors, known publishers and
+    /// pre-shared key identifiers, exchange keys and spongos states are compared; own keys,
+    /// settings and pre-shared keys themselves are not.
+    pub fn diff(&self, other: &Self) -> StateDiff<<Link as HasLink>::Rel> {
+        let mut diff = StateDiff {
+            appinst_differs: self.appinst.as_ref().map(|appinst| appinst.base())
+                != other.appinst.as_ref().map(|appinst| appinst.base()),
+            only_here: Vec::new(),
+            only_there: Vec::new(),
+            cursors: Vec::new(),
+            exchange_keys: Vec::new(),
+            links_only_here: Vec::new(),
+            links_only_there: Vec::new(),
+            spongos_mismatches: Vec::new(),
+        };
+
+        for (id, cursor) in self.key_store.iter() {
+            match other.key_store.get(id) {
+                Some(other_cursor) => {
+                    if cursor.link != other_cursor.link
+                        || cursor.branch_no != other_cursor.branch_no
+                        || cursor.seq_no != other_cursor.seq_no
+                    {
+                        diff.cursors.push((*id, cursor.clone(), other_cursor.clone()));
+                    }
+                    let ke_pk = self.key_store.get_ke_pk(id).map(|pk| pk.as_bytes());
+                    if ke_pk != other.key_store.get_ke_pk(id).map(|pk| pk.as_bytes()) {
+                        diff.exchange_keys.push(*id);
+                    }
+                }
+                None => diff.only_here.push(*id),
+            }
+        }
+        for (id, _cursor) in other.key_store.iter() {
+            if !self.key_store.contains(id) {
+                diff.only_there.push(*id);
+            }
+        }
+
+        let link_store = self.link_store.borrow();
+        let other_link_store = other.link_store.borrow();
+        let other_links = other_link_store.iter();
+        for (link, (s, _info)) in link_store.iter() {
+            match other_links.iter().find(|(other_link, _)| *other_link == link) {
+                Some((_, (other_s, _))) => {
+                    if s.arr() != other_s.arr() {
+                        diff.spongos_mismatches.push(link.clone());
+                    }
+                }
+                None => diff.links_only_here.push(link.clone()),
+            }
+        }
+        for (link, _) in other_links {
+            if link_store.lookup(link).is_err() {
+                diff.links_only_there.push(link.clone());
+            }
+        }
+        diff
+    }
+
+    /// Check whether spongos state of the message is saved in the link store.
+    pub fn contains_spongos(&self, link: &<Link as HasLink>::Rel) -> bool {
+        self.link_store.borrow().lookup(link).is_ok()
+    }
+
+    /// Get a copy of the spongos state of the message, eg. to join a message of a custom type to it.
+    pub fn spongos_for(&self, link: &<Link as HasLink>::Rel) -> Option<spongos::Spongos<F>> {
+        self.link_store
+            .borrow()
+            .lookup(link)
+            .ok()
+            .map(|(spongos, _info)| spongos)
+    }
+
+    /// Save the spongos state of a message of a custom type wrapped or unwrapped by the application,
+    /// so that other messages can be joined to it. The state must be committed.
+    ///
+    /// Warning: the state is not checked, storing a wrong state for a message breaks all the
+    /// messages joined to it.
+    pub fn store_spongos(
+        &mut self,
+        link: &<Link as HasLink>::Rel,
+        spongos: spongos::Spongos<F>,
+        info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
+    ) -> Result<()> {
+        self.link_store.borrow_mut().update(link, spongos, info)
+    }
+
+    /// Forget spongos states of all messages except `links`, the announcement and the messages
+    /// the sequencing states of known publishers refer to. Returns the number of forgotten states.
+    pub fn retain_spongos(&mut self, links: &[<Link as HasLink>::Rel]) -> usize
+    where
+        <Link as HasLink>::Rel: PartialEq,
+    {
+        let cursor_links: Vec<<Link as HasLink>::Rel> = self
+            .key_store
+            .iter()
+            .into_iter()
+            .map(|(_id, cursor)| cursor.link.clone())
+            .collect();
+        let mut link_store = self.link_store.borrow_mut();
+        let orphans: Vec<<Link as HasLink>::Rel> = link_store
+            .iter()
+            .into_iter()
+            .map(|(link, _)| link)
+            .filter(|link| {
+                !links.contains(*link)
+                    && !cursor_links.contains(*link)
+                    && self.appinst.as_ref().map_or(true, |appinst| appinst.rel() != *link)
+            })
+            .cloned()
+            .collect();
+        for link in orphans.iter() {
+            link_store.erase(link);
+        }
+        drop(link_store);
+        self.unindex_topics(&orphans);
+        orphans.len()
+    }
+
+    /// Remove the messages from the topic index, topics left without messages are removed too.
+    fn unindex_topics(&mut self, links: &[<Link as HasLink>::Rel]) {
+        for topic_links in self.topics.values_mut() {
+            topic_links.retain(|link| !links.contains(link.rel()));
+        }
+        self.topics.retain(|_topic, topic_links| !topic_links.is_empty());
+    }
+
+    /// Copy of the cursor of each known publisher, excluding link store and keys.
+    pub fn cursor_snapshot(&self) -> Vec<(Identifier, Cursor<<Link as HasLink>::Rel>)> {
+        self.key_store
+            .iter()
+            .into_iter()
+            .map(|(id, cursor)| (*id, cursor.clone()))
+            .collect()
+    }
+
+    /// Restore cursor positions from a snapshot. Link store and keys (including psks) are kept intact,
+    /// unknown publishers are added with the snapshot cursor.
+    pub fn load_cursor_snapshot(&mut self, snapshot: Vec<(Identifier, Cursor<<Link as HasLink>::Rel>)>) -> Result<()> {
+        try_or!(self.appinst.is_some(), UserNotRegistered)?;
+        for (id, cursor) in snapshot {
+            match self.key_store.get_mut(&id) {
+                Some(c) => *c = cursor,
+                None => self.key_store.insert_cursor(id, cursor)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Cursor snapshot sealed with a key derived from the password, for storage on untrusted
+    /// infrastructure. The snapshot is bound to the current channel and authenticated with a MAC,
+    /// a tampered snapshot is rejected by `load_sealed_cursor_snapshot`. Cursors are not encrypted,
+    /// publisher identifiers and message links are public channel data.
+    pub fn sealed_cursor_snapshot(&self, pwd: &str) -> Result<Vec<u8>> {
+        try_or!(self.appinst.is_some(), UserNotRegistered)?;
+        let appinst = self.appinst.as_ref().unwrap();
+        let salt = NBytes::<U16>(prng::random_nonce());
+        let key = snapshot_key::<F>(pwd, &salt);
+        let snapshot = self.cursor_snapshot();
+
+        let buf_size = {
+            let mut ctx = sizeof::Context::<F>::new();
+            ctx.absorb(Uint8(SEALED_SNAPSHOT_VERSION))?
+                .absorb(&salt)?
+                .absorb(External(&key))?
+                .absorb(External(Fallback(appinst)))?
+                .absorb(Size(snapshot.len()))?
+                .repeated(snapshot.iter(), |ctx, (id, cursor)| {
+                    let ctx = id.sizeof(ctx)?;
+                    ctx.absorb(<&Fallback<<Link as HasLink>::Rel>>::from(&cursor.link))?
+                        .absorb(Uint32(cursor.branch_no))?
+                        .absorb(Uint32(cursor.seq_no))
+                })?
+                .commit()?
+                .squeeze(Mac(SEALED_SNAPSHOT_MAC_LEN))?;
+            ctx.get_size()
+        };
+
+        let mut buf = vec![0; buf_size];
+        {
+            let store = EmptyLinkStore::<F, <Link as HasLink>::Rel, ()>::default();
+            let mut ctx = wrap::Context::new(&mut buf[..]);
+            ctx.absorb(Uint8(SEALED_SNAPSHOT_VERSION))?
+                .absorb(&salt)?
+                .absorb(External(&key))?
+                .absorb(External(Fallback(appinst)))?
+                .absorb(Size(snapshot.len()))?
+                .repeated(snapshot.iter(), |ctx, (id, cursor)| {
+                    let ctx = id.wrap(&store, ctx)?;
+                    ctx.absorb(<&Fallback<<Link as HasLink>::Rel>>::from(&cursor.link))?
+                        .absorb(Uint32(cursor.branch_no))?
+                        .absorb(Uint32(cursor.seq_no))
+                })?
+                .commit()?
+                .squeeze(Mac(SEALED_SNAPSHOT_MAC_LEN))?;
+            try_or!(ctx.stream.is_empty(), OutputStreamNotFullyConsumed(ctx.stream.len()))?;
+        }
+        Ok(buf)
+    }
+
+    /// Verify a snapshot sealed with `sealed_cursor_snapshot` and restore its cursors. Cursors are
+    /// applied only once the whole snapshot is verified, a snapshot failing verification leaves the
+    /// user untouched.
+    pub fn load_sealed_cursor_snapshot(&mut self, bytes: &[u8], pwd: &str) -> Result<()> {
+        try_or!(self.appinst.is_some(), UserNotRegistered)?;
+        let mut ctx = unwrap::Context::new(bytes);
+        let mut version = Uint8(0);
+        ctx.absorb(&mut version)?.guard(
+            version.0 == SEALED_SNAPSHOT_VERSION,
+            UserVersionRecoveryFailure(SEALED_SNAPSHOT_VERSION, version.0),
+        )?;
+        let snapshot = self
+            .unwrap_sealed_cursor_snapshot(&mut ctx, pwd)
+            .or_else(|_| err!(CursorSnapshotIntegrityFailure))?;
+        self.load_cursor_snapshot(snapshot)
+    }
+
+    fn unwrap_sealed_cursor_snapshot(
+        &self,
+        ctx: &mut unwrap::Context<F, &[u8]>,
+        pwd: &str,
+    ) -> Result<Vec<(Identifier, Cursor<<Link as HasLink>::Rel>)>> {
+        let mut salt = NBytes::<U16>::default();
+        ctx.absorb(&mut salt)?;
+        let key = snapshot_key::<F>(pwd, &salt);
+        let store = EmptyLinkStore::<F, <Link as HasLink>::Rel, ()>::default();
+        let mut snapshot = Vec::new();
+        let mut n = Size(0);
+        ctx.absorb(External(&key))?
+            .absorb(External(Fallback(self.appinst.as_ref().unwrap())))?
+            .absorb(&mut n)?
+            .repeated(n, |ctx| {
+                let (id, ctx) = Identifier::unwrap_new(&store, ctx)?;
+                let mut link = Fallback(<Link as HasLink>::Rel::default());
+                let mut branch_no = Uint32(0);
+                let mut seq_no = Uint32(0);
+                ctx.absorb(&mut link)?.absorb(&mut branch_no)?.absorb(&mut seq_no)?;
+                snapshot.push((id, Cursor::new_at(link.0, branch_no.0, seq_no.0)));
+                Ok(ctx)
+            })?
+            .commit()?
+            .squeeze(Mac(SEALED_SNAPSHOT_MAC_LEN))?;
+        try_or!(ctx.stream.is_empty(), InputStreamNotFullyConsumed(ctx.stream.len()))?;
+        Ok(snapshot)
+    }
+
+    /// Link of the latest processed message of each publisher, ordered by publisher identifier.
+    /// Publishers with no message beyond the announcement are skipped.
+    pub fn heads(&self) -> Result<Vec<(Identifier, Link)>> {
+        try_or!(self.appinst.is_some(), UserNotRegistered)?;
+        let appinst = self.appinst.as_ref().unwrap();
+        let mut heads: Vec<(Identifier, Link)> = self
+            .key_store
+            .iter()
+            .into_iter()
+            .filter(|(_id, cursor)| cursor.link != *appinst.rel())
+            .map(|(id, cursor)| (*id, Link::from_base_rel(appinst.base(), &cursor.link)))
+            .collect();
+        heads.sort_by_key(|(id, _link)| id.to_bytes());
+        Ok(heads)
+    }
+
+    pub fn fetch_state(&self) -> Result<Vec<(Identifier, Cursor<Link>)>> {
+        let mut state = Vec::new();
+        try_or!(self.appinst.is_some(), UserNotRegistered)?;
+
+        for (
+            pk,
             Cursor {
-                link: seq_link,
-                branch_no: _,
+                link,
+                branch_no,
                 seq_no,
             },
-        ) = pk_info;
+        ) in self.key_store.iter()
+        {
+            let link = Link::from_base_rel(self.appinst.as_ref().unwrap().base(), link);
+            state.push((*pk, Cursor::new_at(link, *branch_no, *seq_no)))
+        }
+        Ok(state)
+    }
+}
+
+impl<F, Link, LS, Keys> ChannelState<F, Link, LS, Keys>
+where
+    F: PRP,
+    Link: HasLink + AbsorbExternalFallback<F> + AbsorbFallback<F>,
+    <Link as HasLink>::Rel: Eq + fmt::Debug + SkipFallback<F> + AbsorbFallback<F>,
+    LS: LinkStore<F, <Link as HasLink>::Rel>,
+    <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info: AbsorbFallback<F>,
+    Keys: KeyStore<Cursor<<Link as HasLink>::Rel>, F>,
+{
+    /// Size of the channel state kept in backups, it follows the layout of the current channel
+    /// state in the user backup.
+    fn sizeof_stashed<'c>(&self, ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
+        let oneof_author_sig_pk = Uint8(if self.author_sig_pk.is_some() { 1 } else { 0 });
+        ctx.absorb(<&Fallback<Link>>::from(&self.appinst))?
+            .absorb(Uint8(self.flags))?
+            .absorb(&oneof_author_sig_pk)?;
+        if let Some(ref author_sig_pk) = self.author_sig_pk {
+            ctx.absorb(author_sig_pk)?;
+        }
+        ctx.absorb(&self.co_authors)?
+            .absorb(Size(self.subscriber_groups.len()))?
+            .repeated(self.subscriber_groups.iter(), |ctx, (group, members)| {
+                ctx.absorb(&Bytes(group.as_bytes().to_vec()))?
+                    .absorb(Size(members.len()))?
+                    .repeated(members.iter(), |ctx, id| id.sizeof(ctx))
+            })?
+            .absorb(Size(self.keyload_recipients.len()))?
+            .repeated(self.keyload_recipients.iter(), |ctx, (keyload, recipients)| {
+                ctx.absorb(<&Fallback<Link>>::from(keyload))?
+                    .absorb(Size(recipients.len()))?
+                    .repeated(recipients.iter(), |ctx, id| id.sizeof(ctx))
+            })?
+            .absorb(Uint8(self.membership_sealed as u8))?
+            .absorb(Uint8(self.closed as u8))?
+            .absorb(Size(self.topics.len()))?
+            .repeated(self.topics.iter(), |ctx, (topic, links)| {
+                ctx.absorb(&Bytes(topic.as_bytes().to_vec()))?
+                    .absorb(Size(links.len()))?
+                    .repeated(links.iter(), |ctx, link| ctx.absorb(<&Fallback<Link>>::from(link)))
+            })?;
+
+        let links = self.link_store.iter();
+        let repeated_links = Size(links.len());
+        let keys = self.key_store.iter();
+        let repeated_keys = Size(keys.len());
+        ctx.absorb(repeated_links)?
+            .repeated(links.into_iter(), |ctx, (link, (s, info))| {
+                ctx.absorb(<&Fallback<<Link as HasLink>::Rel>>::from(link))?
+                    .mask(<&NBytes<F::CapacitySize>>::from(s.arr()))?
+                    .absorb(<&Fallback<<LS as LinkStore<F, <Link as HasLink>::Rel>>::Info>>::from(
+                        info,
+                    ))?;
+                Ok(ctx)
+            })?
+            .absorb(repeated_keys)?
+            .repeated(keys.into_iter(), |ctx, (id, cursor)| {
+                let ctx = id.sizeof(ctx)?;
+                ctx.absorb(<&Fallback<<Link as HasLink>::Rel>>::from(&cursor.link))?
+                    .absorb(Uint32(cursor.branch_no))?
+                    .absorb(Uint32(cursor.seq_no))?;
+                Ok(ctx)
+            })
+    }
+
+    /// Wrap the channel state kept in backups.
+    fn wrap_stashed<'c, Store, OS: io::OStream>(
+        &self,
+        store: &Store,
+        ctx: &'c mut wrap::Context<F, OS>,
+    ) -> Result<&'c mut wrap::Context<F, OS>>
+    where
+        Store: LinkStore<F, <Link as HasLink>::Rel>,
+    {
+        let oneof_author_sig_pk = Uint8(if self.author_sig_pk.is_some() { 1 } else { 0 });
+        ctx.absorb(<&Fallback<Link>>::from(&self.appinst))?
+            .absorb(Uint8(self.flags))?
+            .absorb(&oneof_author_sig_pk)?;
+        if let Some(ref author_sig_pk) = self.author_sig_pk {
+            ctx.absorb(author_sig_pk)?;
+        }
+        ctx.absorb(&self.co_authors)?
+            .absorb(Size(self.subscriber_groups.len()))?
+            .repeated(self.subscriber_groups.iter(), |ctx, (group, members)| {
+                ctx.absorb(&Bytes(group.as_bytes().to_vec()))?
+                    .absorb(Size(members.len()))?
+                    .repeated(members.iter(), |ctx, id| id.wrap(store, ctx))
+            })?
+            .absorb(Size(self.keyload_recipients.len()))?
+            .repeated(self.keyload_recipients.iter(), |ctx, (keyload, recipients)| {
+                ctx.absorb(<&Fallback<Link>>::from(keyload))?
+                    .absorb(Size(recipients.len()))?
+                    .repeated(recipients.iter(), |ctx, id| id.wrap(store, ctx))
+            })?
+            .absorb(Uint8(self.membership_sealed as u8))?
+            .absorb(Uint8(self.closed as u8))?
+            .absorb(Size(self.topics.len()))?
+            .repeated(self.topics.iter(), |ctx, (topic, links)| {
+                ctx.absorb(&Bytes(topic.as_bytes().to_vec()))?
+                    .absorb(Size(links.len()))?
+                    .repeated(links.iter(), |ctx, link| ctx.absorb(<&Fallback<Link>>::from(link)))
+            })?;
+
+        let links = self.link_store.iter();
+        let repeated_links = Size(links.len());
+        let keys = self.key_store.iter();
+        let repeated_keys = Size(keys.len());
+        ctx.absorb(repeated_links)?
+            .repeated(links.into_iter(), |ctx, (link, (s, info))| {
+                ctx.absorb(<&Fallback<<Link as HasLink>::Rel>>::from(link))?
+                    .mask(<&NBytes<F::CapacitySize>>::from(s.arr()))?
+                    .absorb(<&Fallback<<LS as LinkStore<F, <Link as HasLink>::Rel>>::Info>>::from(
+                        info,
+                    ))?;
+                Ok(ctx)
+            })?
+            .absorb(repeated_keys)?
+            .repeated(keys.into_iter(), |ctx, (id, cursor)| {
+                let ctx = id.wrap(store, ctx)?;
+                ctx.absorb(<&Fallback<<Link as HasLink>::Rel>>::from(&cursor.link))?
+                    .absorb(Uint32(cursor.branch_no))?
+                    .absorb(Uint32(cursor.seq_no))?;
+                Ok(ctx)
+            })
+    }
+}
+
+impl<F, Link, LS, Keys> ChannelState<F, Link, LS, Keys>
+where
+    F: PRP,
+    Link: HasLink + AbsorbExternalFallback<F> + AbsorbFallback<F>,
+    <Link as HasLink>::Rel: Eq + fmt::Debug + SkipFallback<F> + AbsorbFallback<F>,
+    LS: LinkStore<F, <Link as HasLink>::Rel> + Default,
+    <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info: Default + AbsorbFallback<F>,
+    Keys: KeyStore<Cursor<<Link as HasLink>::Rel>, F> + Default,
+{
+    /// Unwrap channel state kept in backups, settings which are not kept are left to their
+    /// defaults.
+    fn unwrap_stashed<'c, Store, IS: io::IStream>(
+        version: u8,
+        store: &Store,
+        ctx: &'c mut unwrap::Context<F, IS>,
+    ) -> Result<(Self, &'c mut unwrap::Context<F, IS>)>
+    where
+        Store: LinkStore<F, <Link as HasLink>::Rel>,
+    {
+        let mut appinst = Link::default();
+        let mut flags = Uint8(0);
+        let mut oneof_author_sig_pk = Uint8(0);
+        ctx.absorb(<&mut Fallback<Link>>::from(&mut appinst))?
+            .absorb(&mut flags)?
+            .absorb(&mut oneof_author_sig_pk)?
+            .guard(
+                oneof_author_sig_pk.0 < 2,
+                AuthorSigPkRecoveryFailure(oneof_author_sig_pk.0),
+            )?;
 
-        if branching {
-            let msg_id = link_gen.link_from(id, Cursor::new_at(&*seq_link, 0, 1));
-            ids.push((*id, Cursor::new_at(msg_id, 0, 1)));
+        let author_sig_pk = if oneof_author_sig_pk.0 == 1 {
+            let mut author_sig_pk = ed25519::PublicKey::default();
+            ctx.absorb(&mut author_sig_pk)?;
+            Some(author_sig_pk)
         } else {
-            let msg_id = link_gen.link_from(id, Cursor::new_at(&*seq_link, 0, *seq_no));
-            ids.push((*id, Cursor::new_at(msg_id, 0, *seq_no)));
-        }
-    }
+            None
+        };
 
-    // TODO: Turn it into iterator.
-    pub fn gen_next_msg_ids(&self, branching: bool) -> Vec<(Identifier, Cursor<Link>)> {
-        let mut ids = Vec::new();
+        let mut co_authors = Vec::<ed25519::PublicKey>::new();
+        let mut subscriber_groups = BTreeMap::new();
+        let mut repeated_groups = Size(0);
+        ctx.absorb(&mut co_authors)?
+            .absorb(&mut repeated_groups)?
+            .repeated(repeated_groups, |ctx| {
+                let mut group = Bytes::new();
+                let mut repeated_members = Size(0);
+                ctx.absorb(&mut group)?.absorb(&mut repeated_members)?;
+                let group = match String::from_utf8(group.0) {
+                    Ok(group) => group,
+                    Err(_) => return err!(CorruptBackup("subscriber group name is not valid UTF-8".into())),
+                };
+                let mut members = Vec::new();
+                let ctx = ctx.repeated(repeated_members, |ctx| {
+                    let (id, ctx) = Identifier::unwrap_new(store, ctx)?;
+                    members.push(id);
+                    Ok(ctx)
+                })?;
+                subscriber_groups.insert(group, members);
+                Ok(ctx)
+            })?;
 
-        // TODO: Do the same for self.sig_kp.public
-        for pk_info in self.key_store.iter() {
-            Self::gen_next_msg_id(&mut ids, &self.link_gen, pk_info, branching);
-        }
-        ids
-    }
+        let mut keyload_recipients = Vec::new();
+        let mut repeated_keyloads = Size(0);
+        ctx.absorb(&mut repeated_keyloads)?.repeated(repeated_keyloads, |ctx| {
+            let mut keyload = Link::default();
+            let mut repeated_recipients = Size(0);
+            ctx.absorb(<&mut Fallback<Link>>::from(&mut keyload))?
+                .absorb(&mut repeated_recipients)?;
+            let mut recipients = Vec::new();
+            let ctx = ctx.repeated(repeated_recipients, |ctx| {
+                let (id, ctx) = Identifier::unwrap_new(store, ctx)?;
+                recipients.push(id);
+                Ok(ctx)
+            })?;
+            keyload_recipients.push((keyload, recipients));
+            Ok(ctx)
+        })?;
 
-    pub fn store_state(&mut self, id: Identifier, link: <Link as HasLink>::Rel) -> Result<()> {
-        if let Some(cursor) = self.key_store.get(&id) {
-            let mut cursor = cursor.clone();
-            cursor.link = link;
-            cursor.next_seq();
-            self.key_store.insert_cursor(id, cursor)?;
-        }
-        Ok(())
-    }
+        let mut membership_sealed = Uint8(0);
+        let mut closed = Uint8(0);
+        ctx.absorb(&mut membership_sealed)?
+            .guard(
+                membership_sealed.0 < 2,
+                CorruptBackup("invalid membership sealing flag".into()),
+            )?
+            .absorb(&mut closed)?
+            .guard(closed.0 < 2, CorruptBackup("invalid channel closing flag".into()))?;
 
-    pub fn store_state_for_all(&mut self, link: <Link as HasLink>::Rel, seq_no: u32) -> Result<()> {
-        if &seq_no > self.get_seq_no().as_ref().unwrap_or(&0) {
-            self.key_store.insert_cursor(
-                Identifier::EdPubKey(self.sig_kp.public.into()),
-                Cursor::new_at(link.clone(), 0, seq_no),
-            )?;
-            for (_pk, cursor) in self.key_store.iter_mut() {
-                cursor.link = link.clone();
-                cursor.seq_no = seq_no;
-            }
+        let mut topics = BTreeMap::new();
+        if version > BACKUP_VERSION_NO_TOPICS {
+            let mut repeated_topics = Size(0);
+            ctx.absorb(&mut repeated_topics)?.repeated(repeated_topics, |ctx| {
+                let mut topic = Bytes::new();
+                let mut repeated_topic_links = Size(0);
+                ctx.absorb(&mut topic)?.absorb(&mut repeated_topic_links)?;
+                let topic = match String::from_utf8(topic.0) {
+                    Ok(topic) => topic,
+                    Err(_) => return err!(CorruptBackup("topic is not valid UTF-8".into())),
+                };
+                let mut links = Vec::new();
+                let ctx = ctx.repeated(repeated_topic_links, |ctx| {
+                    let mut link = Link::default();
+                    ctx.absorb(<&mut Fallback<Link>>::from(&mut link))?;
+                    links.push(link);
+                    Ok(ctx)
+                })?;
+                topics.insert(topic, links);
+                Ok(ctx)
+            })?;
         }
-        Ok(())
-    }
 
-    pub fn fetch_state(&self) -> Result<Vec<(Identifier, Cursor<Link>)>> {
-        let mut state = Vec::new();
-        try_or!(self.appinst.is_some(), UserNotRegistered)?;
+        let mut repeated_links = Size(0);
+        let mut link_store = LS::default();
+        ctx.absorb(&mut repeated_links)?;
+        link_store.reserve(repeated_links.0.min(MAX_RESTORE_RESERVE));
+        ctx.repeated(repeated_links, |ctx| {
+            let mut link = Fallback(<Link as HasLink>::Rel::default());
+            let mut s = NBytes::<F::CapacitySize>::default();
+            let mut info = Fallback(<LS as LinkStore<F, <Link as HasLink>::Rel>>::Info::default());
+            ctx.absorb(&mut link)?.mask(&mut s)?.absorb(&mut info)?;
+            let a: GenericArray<u8, F::CapacitySize> = s.into();
+            link_store.insert(&link.0, Inner::<F>::from(a), info.0)?;
+            Ok(ctx)
+        })?;
 
-        for (
-            pk,
-            Cursor {
-                link,
-                branch_no,
-                seq_no,
-            },
-        ) in self.key_store.iter()
-        {
-            let link = Link::from_base_rel(self.appinst.as_ref().unwrap().base(), link);
-            state.push((*pk, Cursor::new_at(link, *branch_no, *seq_no)))
-        }
-        Ok(state)
+        let mut repeated_keys = Size(0);
+        let mut key_store = Keys::default();
+        ctx.absorb(&mut repeated_keys)?;
+        key_store.reserve(repeated_keys.0.min(MAX_RESTORE_RESERVE));
+        ctx.repeated(repeated_keys, |ctx| {
+            let mut link = Fallback(<Link as HasLink>::Rel::default());
+            let mut branch_no = Uint32(0);
+            let mut seq_no = Uint32(0);
+            let (id, ctx) = Identifier::unwrap_new(store, ctx)?;
+            ctx.absorb(&mut link)?.absorb(&mut branch_no)?.absorb(&mut seq_no)?;
+            key_store.insert_cursor(id, Cursor::new_at(link.0, branch_no.0, seq_no.0))?;
+            Ok(ctx)
+        })?;
+
+        let state = ChannelState {
+            appinst,
+            author_sig_pk,
+            co_authors,
+            subscriber_groups,
+            keyload_recipients,
+            key_store,
+            link_store,
+            flags: flags.0,
+            use_psk: false,
+            last_keyload_fingerprint: None,
+            announcement_metadata: None,
+            announcement_context: None,
+            closed: closed.0 == 1,
+            membership_sealed: membership_sealed.0 == 1,
+            acks: HashMap::new(),
+            topics,
+            _phantom: core::marker::PhantomData,
+        };
+        Ok((state, ctx))
     }
 }
 
@@ -1048,6 +3817,41 @@ where
         if let Some(ref author_sig_pk) = self.author_sig_pk {
             ctx.absorb(author_sig_pk)?;
         }
+        ctx.absorb(&self.co_authors)?;
+
+        let oneof_label = Uint8(if self.label.is_some() { 1 } else { 0 });
+        ctx.absorb(&oneof_label)?;
+        if let Some(ref label) = self.label {
+            ctx.mask(&Bytes(label.as_bytes().to_vec()))?;
+        }
+        ctx.absorb(Size(self.subscriber_groups.len()))?
+            .repeated(self.subscriber_groups.iter(), |ctx, (group, members)| {
+                ctx.absorb(&Bytes(group.as_bytes().to_vec()))?
+                    .absorb(Size(members.len()))?
+                    .repeated(members.iter(), |ctx, id| id.sizeof(ctx))
+            })?
+            .absorb(Size(self.keyload_recipients.len()))?
+            .repeated(self.keyload_recipients.iter(), |ctx, (keyload, recipients)| {
+                ctx.absorb(<&Fallback<Link>>::from(keyload))?
+                    .absorb(Size(recipients.len()))?
+                    .repeated(recipients.iter(), |ctx, id| id.sizeof(ctx))
+            })?
+            .absorb(Size(self.connection_history.len()))?
+            .repeated(self.connection_history.iter(), |ctx, link| {
+                ctx.absorb(<&Fallback<Link>>::from(link))
+            })?
+            .absorb(Uint8(self.membership_sealed as u8))?
+            .absorb(Uint8(self.closed as u8))?
+            .mask(<&NBytes<U32>>::from(&self.viewing_kp.0.to_bytes()[..]))?
+            .absorb(Size(self.stashed_channels.len()))?
+            .repeated(self.stashed_channels.iter(), |ctx, state| state.sizeof_stashed(ctx))?
+            .absorb(&Bytes(self.link_gen.domain().to_vec()))?
+            .absorb(Size(self.topics.len()))?
+            .repeated(self.topics.iter(), |ctx, (topic, links)| {
+                ctx.absorb(&Bytes(topic.as_bytes().to_vec()))?
+                    .absorb(Size(links.len()))?
+                    .repeated(links.iter(), |ctx, link| ctx.absorb(<&Fallback<Link>>::from(link)))
+            })?;
 
         let link_store = self.link_store.borrow();
         let links = link_store.iter();
@@ -1072,7 +3876,7 @@ where
                 Ok(ctx)
             })?
             .commit()?
-            .squeeze(Mac(32))?;
+            .squeeze(Mac(self.backup_mac_len))?;
         Ok(ctx)
     }
 }
@@ -1110,6 +3914,43 @@ where
         if let Some(ref author_sig_pk) = self.author_sig_pk {
             ctx.absorb(author_sig_pk)?;
         }
+        ctx.absorb(&self.co_authors)?;
+
+        let oneof_label = Uint8(if self.label.is_some() { 1 } else { 0 });
+        ctx.absorb(&oneof_label)?;
+        if let Some(ref label) = self.label {
+            ctx.mask(&Bytes(label.as_bytes().to_vec()))?;
+        }
+        ctx.absorb(Size(self.subscriber_groups.len()))?
+            .repeated(self.subscriber_groups.iter(), |ctx, (group, members)| {
+                ctx.absorb(&Bytes(group.as_bytes().to_vec()))?
+                    .absorb(Size(members.len()))?
+                    .repeated(members.iter(), |ctx, id| id.wrap(_store, ctx))
+            })?
+            .absorb(Size(self.keyload_recipients.len()))?
+            .repeated(self.keyload_recipients.iter(), |ctx, (keyload, recipients)| {
+                ctx.absorb(<&Fallback<Link>>::from(keyload))?
+                    .absorb(Size(recipients.len()))?
+                    .repeated(recipients.iter(), |ctx, id| id.wrap(_store, ctx))
+            })?
+            .absorb(Size(self.connection_history.len()))?
+            .repeated(self.connection_history.iter(), |ctx, link| {
+                ctx.absorb(<&Fallback<Link>>::from(link))
+            })?
+            .absorb(Uint8(self.membership_sealed as u8))?
+            .absorb(Uint8(self.closed as u8))?
+            .mask(<&NBytes<U32>>::from(&self.viewing_kp.0.to_bytes()[..]))?
+            .absorb(Size(self.stashed_channels.len()))?
+            .repeated(self.stashed_channels.iter(), |ctx, state| {
+                state.wrap_stashed(_store, ctx)
+            })?
+            .absorb(&Bytes(self.link_gen.domain().to_vec()))?
+            .absorb(Size(self.topics.len()))?
+            .repeated(self.topics.iter(), |ctx, (topic, links)| {
+                ctx.absorb(&Bytes(topic.as_bytes().to_vec()))?
+                    .absorb(Size(links.len()))?
+                    .repeated(links.iter(), |ctx, link| ctx.absorb(<&Fallback<Link>>::from(link)))
+            })?;
 
         let link_store = self.link_store.borrow();
         let links = link_store.iter();
@@ -1134,7 +3975,7 @@ where
                 Ok(ctx)
             })?
             .commit()?
-            .squeeze(Mac(32))?;
+            .squeeze(Mac(self.backup_mac_len))?;
         Ok(ctx)
     }
 }
@@ -1153,9 +3994,35 @@ where
 {
     fn unwrap<'c, IS: io::IStream>(
         &mut self,
-        _store: &Store,
+        store: &Store,
         ctx: &'c mut unwrap::Context<F, IS>,
     ) -> Result<&'c mut unwrap::Context<F, IS>> {
+        self.unwrap_backup(BACKUP_VERSION, store, ctx)
+    }
+}
+
+impl<F, Link, LG, LS, Keys> User<F, Link, LG, LS, Keys>
+where
+    F: PRP,
+    Link: HasLink + AbsorbExternalFallback<F> + AbsorbFallback<F>,
+    <Link as HasLink>::Base: Eq + fmt::Debug + fmt::Display,
+    <Link as HasLink>::Rel: Eq + fmt::Debug + SkipFallback<F> + AbsorbFallback<F>,
+    LG: LinkGenerator<Link>,
+    LS: LinkStore<F, <Link as HasLink>::Rel> + Default,
+    <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info: Default + AbsorbFallback<F>,
+    Keys: KeyStore<Cursor<<Link as HasLink>::Rel>, F> + Default,
+{
+    /// Unwrap user state exported in the given backup format version, fields introduced by later
+    /// versions are left to their defaults.
+    fn unwrap_backup<'c, Store, IS: io::IStream>(
+        &mut self,
+        version: u8,
+        _store: &Store,
+        ctx: &'c mut unwrap::Context<F, IS>,
+    ) -> Result<&'c mut unwrap::Context<F, IS>>
+    where
+        Store: LinkStore<F, <Link as HasLink>::Rel>,
+    {
         let mut sig_sk_bytes = NBytes::<U32>::default();
         let mut flags = Uint8(0);
         let mut message_encoding = Bytes::new();
@@ -1193,9 +4060,145 @@ where
             None
         };
 
+        let mut co_authors = Vec::<ed25519::PublicKey>::new();
+        if version > BACKUP_VERSION_NO_CO_AUTHORS {
+            ctx.absorb(&mut co_authors)?;
+        }
+
+        let mut label = None;
+        if version > BACKUP_VERSION_NO_LABEL {
+            let mut oneof_label = Uint8(0);
+            ctx.absorb(&mut oneof_label)?
+                .guard(oneof_label.0 < 2, CorruptBackup("invalid label flag".into()))?;
+            if oneof_label.0 == 1 {
+                let mut label_bytes = Bytes::new();
+                ctx.mask(&mut label_bytes)?;
+                label = match String::from_utf8(label_bytes.0) {
+                    Ok(label) => Some(label),
+                    Err(_) => return err!(CorruptBackup("label is not valid UTF-8".into())),
+                };
+            }
+        }
+
+        let mut subscriber_groups = BTreeMap::new();
+        if version > BACKUP_VERSION_NO_GROUPS {
+            let mut repeated_groups = Size(0);
+            ctx.absorb(&mut repeated_groups)?
+                .repeated(repeated_groups, |ctx| {
+                    let mut group = Bytes::new();
+                    let mut repeated_members = Size(0);
+                    ctx.absorb(&mut group)?.absorb(&mut repeated_members)?;
+                    let group = match String::from_utf8(group.0) {
+                        Ok(group) => group,
+                        Err(_) => return err!(CorruptBackup("subscriber group name is not valid UTF-8".into())),
+                    };
+                    let mut members = Vec::new();
+                    let ctx = ctx.repeated(repeated_members, |ctx| {
+                        let (id, ctx) = Identifier::unwrap_new(_store, ctx)?;
+                        members.push(id);
+                        Ok(ctx)
+                    })?;
+                    subscriber_groups.insert(group, members);
+                    Ok(ctx)
+                })?;
+        }
+
+        let mut keyload_recipients = Vec::new();
+        if version > BACKUP_VERSION_NO_KEYLOAD_RECIPIENTS {
+            let mut repeated_keyloads = Size(0);
+            ctx.absorb(&mut repeated_keyloads)?
+                .repeated(repeated_keyloads, |ctx| {
+                    let mut keyload = Link::default();
+                    let mut repeated_recipients = Size(0);
+                    ctx.absorb(<&mut Fallback<Link>>::from(&mut keyload))?
+                        .absorb(&mut repeated_recipients)?;
+                    let mut recipients = Vec::new();
+                    let ctx = ctx.repeated(repeated_recipients, |ctx| {
+                        let (id, ctx) = Identifier::unwrap_new(_store, ctx)?;
+                        recipients.push(id);
+                        Ok(ctx)
+                    })?;
+                    keyload_recipients.push((keyload, recipients));
+                    Ok(ctx)
+                })?;
+        }
+
+        let mut connection_history = Vec::new();
+        if version > BACKUP_VERSION_NO_CONNECTION_HISTORY {
+            let mut repeated_connections = Size(0);
+            ctx.absorb(&mut repeated_connections)?
+                .repeated(repeated_connections, |ctx| {
+                    let mut link = Link::default();
+                    ctx.absorb(<&mut Fallback<Link>>::from(&mut link))?;
+                    connection_history.push(link);
+                    Ok(ctx)
+                })?;
+        }
+
+        let mut membership_sealed = Uint8(0);
+        let mut closed = Uint8(0);
+        if version > BACKUP_VERSION_NO_SEALED {
+            ctx.absorb(&mut membership_sealed)?
+                .guard(
+                    membership_sealed.0 < 2,
+                    CorruptBackup("invalid membership sealing flag".into()),
+                )?
+                .absorb(&mut closed)?
+                .guard(closed.0 < 2, CorruptBackup("invalid channel closing flag".into()))?;
+        }
+
+        let mut viewing_sk_bytes = None;
+        if version > BACKUP_VERSION_NO_VIEWING_KEY {
+            let mut bytes = NBytes::<U32>::default();
+            ctx.mask(&mut bytes)?;
+            viewing_sk_bytes = Some(bytes);
+        }
+
+        let mut stashed_channels = Vec::new();
+        if version > BACKUP_VERSION_NO_STASHED_CHANNELS {
+            let mut repeated_channels = Size(0);
+            ctx.absorb(&mut repeated_channels)?.repeated(repeated_channels, |ctx| {
+                let (state, ctx) = ChannelState::unwrap_stashed(version, _store, ctx)?;
+                stashed_channels.push(state);
+                Ok(ctx)
+            })?;
+        }
+
+        let mut network_domain = None;
+        if version > BACKUP_VERSION_NO_NETWORK_DOMAIN {
+            let mut domain = Bytes::new();
+            ctx.absorb(&mut domain)?;
+            network_domain = Some(domain.0);
+        }
+
+        let mut topics = BTreeMap::new();
+        if version > BACKUP_VERSION_NO_TOPICS {
+            let mut repeated_topics = Size(0);
+            ctx.absorb(&mut repeated_topics)?.repeated(repeated_topics, |ctx| {
+                let mut topic = Bytes::new();
+                let mut repeated_topic_links = Size(0);
+                ctx.absorb(&mut topic)?.absorb(&mut repeated_topic_links)?;
+                let topic = match String::from_utf8(topic.0) {
+                    Ok(topic) => topic,
+                    Err(_) => return err!(CorruptBackup("topic is not valid UTF-8".into())),
+                };
+                let mut links = Vec::new();
+                let ctx = ctx.repeated(repeated_topic_links, |ctx| {
+                    let mut link = Link::default();
+                    ctx.absorb(<&mut Fallback<Link>>::from(&mut link))?;
+                    links.push(link);
+                    Ok(ctx)
+                })?;
+                topics.insert(topic, links);
+                Ok(ctx)
+            })?;
+        }
+
         let mut repeated_links = Size(0);
         let mut link_store = LS::default();
-        ctx.absorb(&mut repeated_links)?.repeated(repeated_links, |ctx| {
+        ctx.absorb(&mut repeated_links)?;
+        link_store.reserve(repeated_links.0.min(MAX_RESTORE_RESERVE));
+        ctx.repeated(repeated_links, |ctx| {
             let mut link = Fallback(<Link as HasLink>::Rel::default());
             let mut s = NBytes::<F::CapacitySize>::default();
             let mut info = Fallback(<LS as LinkStore<F, <Link as HasLink>::Rel>>::Info::default());
@@ -1207,31 +4210,139 @@ where
 
         let mut repeated_keys = Size(0);
         let mut key_store = Keys::default();
-        ctx.absorb(&mut repeated_keys)?
-            .repeated(repeated_keys, |ctx| {
-                let mut link = Fallback(<Link as HasLink>::Rel::default());
-                let mut branch_no = Uint32(0);
-                let mut seq_no = Uint32(0);
-                let (id, ctx) = Identifier::unwrap_new(_store, ctx)?;
-                ctx.absorb(&mut link)?.absorb(&mut branch_no)?.absorb(&mut seq_no)?;
-                key_store.insert_cursor(id, Cursor::new_at(link.0, branch_no.0, seq_no.0))?;
-                Ok(ctx)
-            })?
-            .commit()?
-            .squeeze(Mac(32))?;
+        ctx.absorb(&mut repeated_keys)?;
+        key_store.reserve(repeated_keys.0.min(MAX_RESTORE_RESERVE));
+        ctx.repeated(repeated_keys, |ctx| {
+            let mut link = Fallback(<Link as HasLink>::Rel::default());
+            let mut branch_no = Uint32(0);
+            let mut seq_no = Uint32(0);
+            let (id, ctx) = Identifier::unwrap_new(_store, ctx)?;
+            ctx.absorb(&mut link)?.absorb(&mut branch_no)?.absorb(&mut seq_no)?;
+            key_store.insert_cursor(id, Cursor::new_at(link.0, branch_no.0, seq_no.0))?;
+            Ok(ctx)
+        })?
+        .commit()?
+        .squeeze(Mac(self.backup_mac_len))?;
 
         let sig_sk = ed25519::SecretKey::from_bytes(sig_sk_bytes.as_ref()).unwrap();
         let sig_pk = ed25519::PublicKey::from(&sig_sk);
-        self.sig_kp = ed25519::Keypair {
+        let sig_kp = ed25519::Keypair {
             secret: sig_sk,
             public: sig_pk,
         };
-        self.ke_kp = x25519::keypair_from_ed25519(&self.sig_kp);
+        let ke_kp = x25519::keypair_from_ed25519(&sig_kp);
+
+        // The MAC only proves the backup was made with the password, check the state is one a user
+        // could have been in: cursors are only stored for a channel, along with the author cursor
+        try_or!(
+            appinst.is_some() || key_store.iter().is_empty(),
+            CorruptBackup("publisher cursors are stored without a channel".into())
+        )?;
+        try_or!(
+            appinst.is_some() == author_sig_pk.is_some(),
+            CorruptBackup("channel and author key are not restored together".into())
+        )?;
+        try_or!(
+            author_sig_pk.is_some() || co_authors.is_empty(),
+            CorruptBackup("added authors are stored without a channel".into())
+        )?;
+        try_or!(
+            appinst.is_some() || keyload_recipients.is_empty(),
+            CorruptBackup("keyload recipients are stored without a channel".into())
+        )?;
+        try_or!(
+            appinst.is_some() || membership_sealed.0 == 0,
+            CorruptBackup("membership is sealed without a channel".into())
+        )?;
+        try_or!(
+            appinst.is_some() || closed.0 == 0,
+            CorruptBackup("channel is closed without a channel".into())
+        )?;
+        try_or!(
+            appinst.is_some() || topics.is_empty(),
+            CorruptBackup("topic index is stored without a channel".into())
+        )?;
+        try_or!(
+            subscriber_groups
+                .values()
+                .all(|members| !members.is_empty() && members.iter().all(|id| key_store.contains(id))),
+            CorruptBackup("subscriber groups have members unknown to the user".into())
+        )?;
+        if let Some(author_sig_pk) = &author_sig_pk {
+            let author_id = Identifier::EdPubKey((*author_sig_pk).into());
+            try_or!(
+                key_store.contains(&author_id),
+                CorruptBackup("no cursor of the channel author is stored".into())
+            )?;
+        }
+        for (i, state) in stashed_channels.iter().enumerate() {
+            let author_stored = match &state.author_sig_pk {
+                Some(author_sig_pk) => state.key_store.contains(&Identifier::EdPubKey((*author_sig_pk).into())),
+                None => false,
+            };
+            try_or!(
+                author_stored,
+                CorruptBackup("no cursor of the author of a stashed channel is stored".into())
+            )?;
+            try_or!(
+                state
+                    .subscriber_groups
+                    .values()
+                    .all(|members| !members.is_empty() && members.iter().all(|id| state.key_store.contains(id))),
+                CorruptBackup("subscriber groups of a stashed channel have unknown members".into())
+            )?;
+            try_or!(
+                appinst
+                    .as_ref()
+                    .map_or(true, |appinst| appinst.base() != state.appinst.base())
+                    && stashed_channels[..i]
+                        .iter()
+                        .all(|other| other.appinst.base() != state.appinst.base()),
+                CorruptBackup("a stashed channel is restored twice".into())
+            )?;
+        }
+        let user_id = Identifier::EdPubKey(sig_pk.into());
+        if let Some(user_ke_pk) = key_store.get_ke_pk(&user_id) {
+            try_or!(
+                user_ke_pk.as_bytes() == ke_kp.1.as_bytes(),
+                CorruptBackup("exchange key of the user does not match its identifier".into())
+            )?;
+        }
+
+        // Read-only users don't hold the viewing key derived from their secret key
+        self.viewing_kp = match viewing_sk_bytes {
+            Some(bytes) => {
+                let mut secret = [0_u8; 32];
+                secret.copy_from_slice(bytes.as_ref());
+                let sk = x25519::StaticSecret::from(secret);
+                let pk = x25519::PublicKey::from(&sk);
+                (sk, pk)
+            }
+            None => viewing_keypair::<F>(&sig_kp),
+        };
+        self.sig_kp = sig_kp;
+        self.ke_kp = ke_kp;
         self.link_store = RefCell::new(link_store);
         self.key_store = key_store;
         self.author_sig_pk = author_sig_pk;
+        self.co_authors = co_authors;
+        self.subscriber_groups = subscriber_groups;
+        self.keyload_recipients = keyload_recipients;
+        self.label = label;
+        self.connection_history = connection_history;
+        self.membership_sealed = membership_sealed.0 == 1;
+        self.closed = closed.0 == 1;
+        self.stashed_channels = stashed_channels;
+        self.topics = topics;
+        if let Some(domain) = network_domain {
+            self.link_gen.set_domain(&domain);
+        }
         if let Some(ref seed) = appinst {
             self.link_gen.reset(seed.clone());
+            // Backups made before the history was kept still know the current channel
+            if !self.connection_history.contains(seed) {
+                self.connection_history.push(seed.clone());
+            }
         }
         self.appinst = appinst;
         self.flags = flags.0;
@@ -1252,11 +4363,20 @@ where
     <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info: AbsorbFallback<F>,
     Keys: KeyStore<Cursor<<Link as HasLink>::Rel>, F>,
 {
+    /// Export user state protected with the password. The header holds the format version, a
+    /// random salt mixed into the key derivation and the MAC length, exporting twice with the same
+    /// password yields different backups.
     pub fn export(&self, flag: u8, pwd: &str) -> Result<Vec<u8>> {
-        const VERSION: u8 = 0;
+        self.export_with_salt(flag, pwd, &NBytes(prng::random_nonce()))
+    }
+
+    pub(crate) fn export_with_salt(&self, flag: u8, pwd: &str, salt: &NBytes<U16>) -> Result<Vec<u8>> {
         let buf_size = {
             let mut ctx = sizeof::Context::<F>::new();
-            ctx.absorb(Uint8(VERSION))?.absorb(Uint8(flag))?;
+            ctx.absorb(Uint8(BACKUP_VERSION))?
+                .absorb(Uint8(flag))?
+                .absorb(salt)?
+                .absorb(Uint8(self.backup_mac_len as u8))?;
             self.sizeof(&mut ctx)?;
             ctx.get_size()
         };
@@ -1265,10 +4385,11 @@ where
 
         {
             let mut ctx = wrap::Context::new(&mut buf[..]);
-            let prng = prng::from_seed::<F>("IOTA Streams Channels app", pwd);
-            let key = NBytes::<U32>(prng.gen_arr("user export key"));
-            ctx.absorb(Uint8(VERSION))?
+            let key = backup_key::<F>(pwd, Some(salt));
+            ctx.absorb(Uint8(BACKUP_VERSION))?
                 .absorb(Uint8(flag))?
+                .absorb(salt)?
+                .absorb(Uint8(self.backup_mac_len as u8))?
                 .absorb(External(&key))?;
             let store = EmptyLinkStore::<F, <Link as HasLink>::Rel, ()>::default();
             self.wrap(&store, &mut ctx)?;
@@ -1277,6 +4398,92 @@ where
 
         Ok(buf)
     }
+
+    /// Export the public state of the channel for an observer, see `import_public_state`.
+    ///
+    /// Only the channel, its author keys, the spongos states of processed messages and the
+    /// publisher cursors are exported, unencrypted and ended with a MAC checksum. The signature
+    /// secret key, pre-shared keys, the user label, subscriber groups, keyload recipients and the
+    /// connection history are not. Spongos states let anyone holding the export read masked
+    /// payloads of messages linked to the processed ones, share it with trusted observers only.
+    pub fn export_public_state(&self) -> Result<Vec<u8>> {
+        let appinst = match &self.appinst {
+            Some(appinst) => appinst,
+            None => return err!(UserNotRegistered),
+        };
+        let author_sig_pk = match &self.author_sig_pk {
+            Some(author_sig_pk) => author_sig_pk,
+            None => return err!(UserNotRegistered),
+        };
+        let link_store = self.link_store.borrow();
+        let store = EmptyLinkStore::<F, <Link as HasLink>::Rel, ()>::default();
+
+        let buf_size = {
+            let links = link_store.iter();
+            let keys = self.key_store.iter();
+            let mut ctx = sizeof::Context::<F>::new();
+            ctx.absorb(Uint8(PUBLIC_STATE_VERSION))?
+                .absorb(Uint8(self.flags))?
+                .absorb(<&Bytes>::from(&self.message_encoding))?
+                .absorb(Uint64(self.uniform_payload_length as u64))?
+                .absorb(<&Fallback<Link>>::from(appinst))?
+                .absorb(author_sig_pk)?
+                .absorb(&self.co_authors)?
+                .absorb(Size(links.len()))?
+                .repeated(links.into_iter(), |ctx, (link, (s, info))| {
+                    ctx.absorb(<&Fallback<<Link as HasLink>::Rel>>::from(link))?
+                        .absorb(<&NBytes<F::CapacitySize>>::from(s.arr()))?
+                        .absorb(<&Fallback<<LS as LinkStore<F, <Link as HasLink>::Rel>>::Info>>::from(
+                            info,
+                        ))
+                })?
+                .absorb(Size(keys.len()))?
+                .repeated(keys.into_iter(), |ctx, (id, cursor)| {
+                    id.sizeof(ctx)?
+                        .absorb(<&Fallback<<Link as HasLink>::Rel>>::from(&cursor.link))?
+                        .absorb(Uint32(cursor.branch_no))?
+                        .absorb(Uint32(cursor.seq_no))
+                })?
+                .commit()?
+                .squeeze(Mac(DEFAULT_BACKUP_MAC_LEN))?;
+            ctx.get_size()
+        };
+
+        let mut buf = vec![0; buf_size];
+
+        {
+            let links = link_store.iter();
+            let keys = self.key_store.iter();
+            let mut ctx = wrap::Context::new(&mut buf[..]);
+            ctx.absorb(Uint8(PUBLIC_STATE_VERSION))?
+                .absorb(Uint8(self.flags))?
+                .absorb(<&Bytes>::from(&self.message_encoding))?
+                .absorb(Uint64(self.uniform_payload_length as u64))?
+                .absorb(<&Fallback<Link>>::from(appinst))?
+                .absorb(author_sig_pk)?
+                .absorb(&self.co_authors)?
+                .absorb(Size(links.len()))?
+                .repeated(links.into_iter(), |ctx, (link, (s, info))| {
+                    ctx.absorb(<&Fallback<<Link as HasLink>::Rel>>::from(link))?
+                        .absorb(<&NBytes<F::CapacitySize>>::from(s.arr()))?
+                        .absorb(<&Fallback<<LS as LinkStore<F, <Link as HasLink>::Rel>>::Info>>::from(
+                            info,
+                        ))
+                })?
+                .absorb(Size(keys.len()))?
+                .repeated(keys.into_iter(), |ctx, (id, cursor)| {
+                    id.wrap(&store, ctx)?
+                        .absorb(<&Fallback<<Link as HasLink>::Rel>>::from(&cursor.link))?
+                        .absorb(Uint32(cursor.branch_no))?
+                        .absorb(Uint32(cursor.seq_no))
+                })?
+                .commit()?
+                .squeeze(Mac(DEFAULT_BACKUP_MAC_LEN))?;
+            try_or!(ctx.stream.is_empty(), OutputStreamNotFullyConsumed(ctx.stream.len()))?;
+        }
+
+        Ok(buf)
+    }
 }
 
 impl<F, Link, LG, LS, Keys> User<F, Link, LG, LS, Keys>
@@ -1290,24 +4497,195 @@ where
     <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info: Default + AbsorbFallback<F>,
     Keys: KeyStore<Cursor<<Link as HasLink>::Rel>, F> + Default,
 {
+    /// Import user state exported with the password. The MAC length is read from the header,
+    /// backups of earlier format versions are expected to have a MAC of the default length.
+    /// Backups of a later format version, made by a newer version of the crate, are rejected with
+    /// `UnsupportedBackupVersion`.
     pub fn import(bytes: &[u8], flag: u8, pwd: &str) -> Result<Self> {
-        const VERSION: u8 = 0;
+        Self::import_versioned(bytes, flag, pwd, None).map(|(_version, user)| user)
+    }
+
+    /// Import user state exported with the given MAC length, a backup with a different MAC length
+    /// in its header is rejected with `BackupMacLengthMismatch`. Legacy backups without a salt in
+    /// the header are still accepted, the key is then derived from the password only.
+    pub fn import_with_mac_len(bytes: &[u8], flag: u8, pwd: &str, mac_len: usize) -> Result<Self> {
+        Self::import_versioned(bytes, flag, pwd, Some(mac_len)).map(|(_version, user)| user)
+    }
 
+    /// Import user state along with the version of its backup format. The MAC length is checked
+    /// against the header if given, and read from it otherwise.
+    fn import_versioned(bytes: &[u8], flag: u8, pwd: &str, mac_len: Option<usize>) -> Result<(u8, Self)> {
         let mut ctx = unwrap::Context::new(bytes);
-        let prng = prng::from_seed::<F>("IOTA Streams Channels app", pwd);
-        let key = NBytes::<U32>(prng.gen_arr("user export key"));
         let mut version = Uint8(0);
         let mut flag2 = Uint8(0);
+        // The version is absorbed and covered by the MAC, but the layout of later versions is
+        // unknown and they are rejected before anything else is read
         ctx.absorb(&mut version)?
-            .guard(version.0 == VERSION, UserVersionRecoveryFailure(VERSION, version.0))?
+            .guard(
+                version.0 <= BACKUP_VERSION,
+                UnsupportedBackupVersion(version.0, BACKUP_VERSION),
+            )?
             .absorb(&mut flag2)?
-            .guard(flag2.0 == flag, UserFlagRecoveryFailure(flag, flag2.0))?
-            .absorb(External(&key))?;
+            .guard(flag2.0 == flag, UserFlagRecoveryFailure(flag, flag2.0))?;
+        let key = if version.0 != BACKUP_VERSION_UNSALTED {
+            let mut salt = NBytes::<U16>::default();
+            ctx.absorb(&mut salt)?;
+            backup_key::<F>(pwd, Some(&salt))
+        } else {
+            backup_key::<F>(pwd, None)
+        };
+        let mac_len = if version.0 > BACKUP_VERSION_NO_MAC_LEN {
+            let mut stored_mac_len = Uint8(0);
+            ctx.absorb(&mut stored_mac_len)?;
+            let stored_mac_len = stored_mac_len.0 as usize;
+            if let Some(mac_len) = mac_len {
+                try_or!(
+                    mac_len == stored_mac_len,
+                    BackupMacLengthMismatch(mac_len, stored_mac_len)
+                )?;
+            }
+            stored_mac_len
+        } else {
+            mac_len.unwrap_or(DEFAULT_BACKUP_MAC_LEN)
+        };
+        ctx.absorb(External(&key))?;
 
+        check_backup_mac_len::<F>(mac_len)?;
         let mut user = User::default();
+        user.backup_mac_len = mac_len;
+        let store = EmptyLinkStore::<F, <Link as HasLink>::Rel, ()>::default();
+        user.unwrap_backup(version.0, &store, &mut ctx)?;
+        try_or!(ctx.stream.is_empty(), InputStreamNotFullyConsumed(ctx.stream.len()))?;
+        Ok((version.0, user))
+    }
+
+    /// Check exported user state can be restored with the password without a live user, eg. for
+    /// backup health checks. The backup is decrypted and checked as on import, a wrong password or
+    /// a truncated or corrupt backup fails, and a summary of the state is returned.
+    pub fn verify_backup(bytes: &[u8], flag: u8, pwd: &str) -> Result<BackupInfo> {
+        Self::verify_backup_info(bytes, flag, pwd, None)
+    }
+
+    /// Check user state exported with the given MAC length, see `verify_backup`.
+    pub fn verify_backup_with_mac_len(bytes: &[u8], flag: u8, pwd: &str, mac_len: usize) -> Result<BackupInfo> {
+        Self::verify_backup_info(bytes, flag, pwd, Some(mac_len))
+    }
+
+    fn verify_backup_info(bytes: &[u8], flag: u8, pwd: &str, mac_len: Option<usize>) -> Result<BackupInfo> {
+        let (version, user) = Self::import_versioned(bytes, flag, pwd, mac_len)?;
+        let ids = user.key_store.iter();
+        let psks = ids.iter().filter(|(id, _cursor)| matches!(id, Identifier::PskId(_))).count();
+        let cursors = ids.len() - psks;
+        let spongos = user.link_store.borrow().iter().len();
+        Ok(BackupInfo {
+            version,
+            channel: user.appinst.is_some(),
+            spongos,
+            cursors,
+            psks,
+            label: user.label.clone(),
+        })
+    }
+
+    /// Re-encrypt exported user state with a new password without a live user. The backup is
+    /// fully decrypted and its MAC checked with the old password before it's exported again in the
+    /// current format with a fresh salt, legacy backups are upgraded on the way.
+    pub fn rekey_backup(bytes: &[u8], flag: u8, old_pwd: &str, new_pwd: &str) -> Result<Vec<u8>> {
+        Self::import(bytes, flag, old_pwd)?.export(flag, new_pwd)
+    }
+
+    /// Re-encrypt user state exported with a non-default MAC length, the MAC length is kept.
+    pub fn rekey_backup_with_mac_len(
+        bytes: &[u8],
+        flag: u8,
+        old_pwd: &str,
+        new_pwd: &str,
+        mac_len: usize,
+    ) -> Result<Vec<u8>> {
+        Self::import_with_mac_len(bytes, flag, old_pwd, mac_len)?.export(flag, new_pwd)
+    }
+
+    /// Create a read-only observer from the public state exported with `export_public_state`.
+    ///
+    /// The observer gets a fresh random key pair unknown to the channel, it can fetch and unwrap
+    /// public and signed messages from the exported point on, but it has no cursor of its own and
+    /// can't send sequenced messages. Keyloads are only readable if it's added as a subscriber.
+    pub fn import_public_state(bytes: &[u8]) -> Result<Self> {
+        let mut ctx = unwrap::Context::new(bytes);
+        let mut version = Uint8(0);
+        let mut flags = Uint8(0);
+        let mut message_encoding = Bytes::new();
+        let mut uniform_payload_length = Uint64(0);
+        let mut appinst = Link::default();
+        let mut author_sig_pk = ed25519::PublicKey::default();
+        let mut co_authors = Vec::<ed25519::PublicKey>::new();
+        ctx.absorb(&mut version)?
+            .guard(
+                version.0 == PUBLIC_STATE_VERSION,
+                UserVersionRecoveryFailure(PUBLIC_STATE_VERSION, version.0),
+            )?
+            .absorb(&mut flags)?
+            .absorb(&mut message_encoding)?
+            .absorb(&mut uniform_payload_length)?
+            .absorb(<&mut Fallback<Link>>::from(&mut appinst))?
+            .absorb(&mut author_sig_pk)?
+            .absorb(&mut co_authors)?;
+
+        let mut repeated_links = Size(0);
+        let mut link_store = LS::default();
+        ctx.absorb(&mut repeated_links)?;
+        link_store.reserve(repeated_links.0.min(MAX_RESTORE_RESERVE));
+        ctx.repeated(repeated_links, |ctx| {
+            let mut link = Fallback(<Link as HasLink>::Rel::default());
+            let mut s = NBytes::<F::CapacitySize>::default();
+            let mut info = Fallback(<LS as LinkStore<F, <Link as HasLink>::Rel>>::Info::default());
+            ctx.absorb(&mut link)?.absorb(&mut s)?.absorb(&mut info)?;
+            let a: GenericArray<u8, F::CapacitySize> = s.into();
+            link_store.insert(&link.0, Inner::<F>::from(a), info.0)?;
+            Ok(ctx)
+        })?;
+
         let store = EmptyLinkStore::<F, <Link as HasLink>::Rel, ()>::default();
-        user.unwrap(&store, &mut ctx)?;
+        let mut repeated_keys = Size(0);
+        let mut key_store = Keys::default();
+        ctx.absorb(&mut repeated_keys)?;
+        key_store.reserve(repeated_keys.0.min(MAX_RESTORE_RESERVE));
+        ctx.repeated(repeated_keys, |ctx| {
+            let mut link = Fallback(<Link as HasLink>::Rel::default());
+            let mut branch_no = Uint32(0);
+            let mut seq_no = Uint32(0);
+            let (id, ctx) = Identifier::unwrap_new(&store, ctx)?;
+            ctx.absorb(&mut link)?.absorb(&mut branch_no)?.absorb(&mut seq_no)?;
+            key_store.insert_cursor(id, Cursor::new_at(link.0, branch_no.0, seq_no.0))?;
+            Ok(ctx)
+        })?
+        .commit()?
+        .squeeze(Mac(DEFAULT_BACKUP_MAC_LEN))?;
         try_or!(ctx.stream.is_empty(), InputStreamNotFullyConsumed(ctx.stream.len()))?;
+
+        let author_id = Identifier::EdPubKey(author_sig_pk.into());
+        try_or!(
+            key_store.contains(&author_id),
+            CorruptBackup("no cursor of the channel author is stored".into())
+        )?;
+
+        let mut secret = [0_u8; ed25519::SECRET_KEY_LENGTH];
+        secret.copy_from_slice(&prng::random_key());
+        let sig_kp = ed25519::keypair_from_secret(secret);
+        let mut user = User::default();
+        user.ke_kp = x25519::keypair_from_ed25519(&sig_kp);
+        user.viewing_kp = viewing_keypair::<F>(&sig_kp);
+        user.sig_kp = sig_kp;
+        user.link_store = RefCell::new(link_store);
+        user.key_store = key_store;
+        user.author_sig_pk = Some(author_sig_pk);
+        user.co_authors = co_authors;
+        user.link_gen.reset(appinst.clone());
+        user.connection_history.push(appinst.clone());
+        user.appinst = Some(appinst);
+        user.flags = flags.0;
+        user.message_encoding = message_encoding.0;
+        user.uniform_payload_length = uniform_payload_length.0 as usize;
         Ok(user)
     }
 }
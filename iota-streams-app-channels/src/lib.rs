@@ -24,6 +24,25 @@ extern crate alloc;
 #[macro_use]
 extern crate std;
 
+#[cfg(feature = "tracing")]
+extern crate tracing_dep as tracing;
+
+/// Emit a `tracing` debug event, compiled out unless the `tracing` feature is enabled.
+macro_rules! debug_event {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($arg)*);
+    };
+}
+
+/// Emit a `tracing` warning event, compiled out unless the `tracing` feature is enabled.
+macro_rules! warn_event {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::warn!($($arg)*);
+    };
+}
+
 /// Channel Messages.
 pub mod message;
 
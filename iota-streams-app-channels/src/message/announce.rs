@@ -7,6 +7,9 @@
 //! ```ddml
 //! message Announce {
 //!     absorb u8 ed25519pk[32];
+//!     absorb u8 flags;
+//!     absorb bytes metadata;
+//!     absorb external u8 context[32];
 //!     commit;
 //!     squeeze external u8 tag[32];
 //!     ed25519(tag) sig;
@@ -17,6 +20,16 @@
 //!
 //! * `ed25519pk` -- channel owner's Ed25519 public key.
 //!
+//! * `flags` -- channel flags, eg. the branching mode.
+//!
+//! * `metadata` -- optional application defined channel metadata, eg. name or schema version.
+//! Its presence is signalled in the message header, announcements without metadata are encoded as before.
+//!
+//! * `context` -- optional digest of a context shared out-of-band between the author and the
+//! subscribers, eg. a secret. It is not sent, the announcement can only be verified with the same
+//! context, so that subscribers without it cannot validate the announcement. Announcements without
+//! context are encoded as before.
+//!
 //! * `tag` -- hash-value to be signed.
 //!
 //! * `sig` -- signature of `tag` field produced with the Ed25519 private key corresponding to ed25519pk`.
@@ -35,9 +48,14 @@ use iota_streams_ddml::{
     types::*,
 };
 
+/// Digest of the external context absorbed into announcements.
+pub type ContextDigest = NBytes<U32>;
+
 pub struct ContentWrap<'a, F> {
     sig_kp: &'a ed25519::Keypair,
     flags: Uint8,
+    metadata: Option<&'a Bytes>,
+    context: Option<&'a ContextDigest>,
     _phantom: core::marker::PhantomData<F>,
 }
 
@@ -46,15 +64,35 @@ impl<'a, F> ContentWrap<'a, F> {
         Self {
             sig_kp,
             flags: Uint8(flags),
+            metadata: None,
+            context: None,
             _phantom: core::marker::PhantomData,
         }
     }
+
+    /// Attach channel metadata, it must be signalled in the message header.
+    pub fn with_metadata(mut self, metadata: Option<&'a Bytes>) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Bind the announcement to the digest of an external context, it is not sent.
+    pub fn with_context(mut self, context: Option<&'a ContextDigest>) -> Self {
+        self.context = context;
+        self
+    }
 }
 
 impl<'a, F: PRP> message::ContentSizeof<F> for ContentWrap<'a, F> {
     fn sizeof<'c>(&self, ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
         ctx.absorb(&self.sig_kp.public)?;
         ctx.absorb(&self.flags)?;
+        if let Some(metadata) = self.metadata {
+            ctx.absorb(metadata)?;
+        }
+        if let Some(context) = self.context {
+            ctx.absorb(External(context))?;
+        }
         ctx.ed25519(self.sig_kp, HashSig)?;
         Ok(ctx)
     }
@@ -68,6 +106,12 @@ impl<'a, F: PRP, Store> message::ContentWrap<F, Store> for ContentWrap<'a, F> {
     ) -> Result<&'c mut wrap::Context<F, OS>> {
         ctx.absorb(&self.sig_kp.public)?;
         ctx.absorb(&self.flags)?;
+        if let Some(metadata) = self.metadata {
+            ctx.absorb(metadata)?;
+        }
+        if let Some(context) = self.context {
+            ctx.absorb(External(context))?;
+        }
         ctx.ed25519(self.sig_kp, HashSig)?;
         Ok(ctx)
     }
@@ -75,13 +119,34 @@ impl<'a, F: PRP, Store> message::ContentWrap<F, Store> for ContentWrap<'a, F> {
 
 pub struct ContentUnwrap<F> {
     pub(crate) sig_pk: ed25519::PublicKey,
-
-    #[allow(dead_code)]
     pub(crate) ke_pk: x25519::PublicKey,
     pub(crate) flags: Uint8,
+    pub(crate) metadata: Option<Bytes>,
+    pub(crate) max_metadata_length: usize,
+    pub(crate) context: Option<ContextDigest>,
     _phantom: core::marker::PhantomData<F>,
 }
 
+impl<F> ContentUnwrap<F> {
+    /// Expect channel metadata as signalled in the message header.
+    pub fn with_metadata(mut self, has_metadata: bool) -> Self {
+        self.metadata = if has_metadata { Some(Bytes::default()) } else { None };
+        self
+    }
+
+    /// Reject metadata declared longer than `max_metadata_length` bytes.
+    pub fn with_max_metadata_length(mut self, max_metadata_length: usize) -> Self {
+        self.max_metadata_length = max_metadata_length;
+        self
+    }
+
+    /// Expect the announcement to be bound to the digest of an external context.
+    pub fn with_context(mut self, context: Option<&ContextDigest>) -> Self {
+        self.context = context.cloned();
+        self
+    }
+}
+
 impl<F> Default for ContentUnwrap<F> {
     fn default() -> Self {
         let sig_pk = ed25519::PublicKey::default();
@@ -92,6 +157,9 @@ impl<F> Default for ContentUnwrap<F> {
             sig_pk,
             ke_pk,
             flags,
+            metadata: None,
+            max_metadata_length: usize::MAX,
+            context: None,
             _phantom: core::marker::PhantomData,
         }
     }
@@ -109,6 +177,12 @@ where
         ctx.absorb(&mut self.sig_pk)?;
         self.ke_pk = x25519::public_from_ed25519(&self.sig_pk)?;
         ctx.absorb(&mut self.flags)?;
+        if let Some(metadata) = &mut self.metadata {
+            ctx.absorb_bounded_bytes(metadata, self.max_metadata_length)?;
+        }
+        if let Some(context) = &self.context {
+            ctx.absorb(External(context))?;
+        }
         ctx.ed25519(&self.sig_pk, HashSig)?;
         Ok(ctx)
     }
@@ -0,0 +1,186 @@
+//! `Checkpoint` message content. This message summarizes the state of a single-branch channel up
+//! to a message, it is signed by a trusted channel author.
+//!
+//! The message is joined to the announcement, so a new subscriber can process it right after the
+//! announcement and read the channel from the checkpoint on instead of replaying the whole channel
+//! history. The spongos state of the summarized message depends on all the preceding messages of
+//! the branch, its digest commits the checkpoint to the prior content.
+//!
+//! ```ddml
+//! message Checkpoint {
+//!     join link msgid;
+//!     absorb u8 ed25519pk[32];
+//!     absorb link head;
+//!     absorb u8 digest[32];
+//!     absorb size n;
+//!     repeated(n) {
+//!         mask identifier id;
+//!     }
+//!     commit;
+//!     squeeze external byte hash[78];
+//!     ed25519(hash) sig;
+//! }
+//! ```
+//!
+//! # Fields
+//!
+//! * `msgid` -- link to the announcement.
+//!
+//! * `ed25519pk` -- public key of the sender, it must be trusted as a channel author.
+//!
+//! * `head` -- link to the last message summarized by the checkpoint.
+//!
+//! * `digest` -- digest of the spongos state of the `head` message.
+//!
+//! * `id` -- identifiers of the channel publishers known to the sender, ie. keyload recipients.
+//!
+//! * `hash` -- hash value to be signed.
+//!
+//! * `sig` -- message signature generated with the sender's private key.
+
+use iota_streams_app::{
+    identifier::Identifier,
+    message::{
+        self,
+        ContentUnwrapNew,
+        HasLink,
+    },
+};
+use iota_streams_core::{
+    prelude::Vec,
+    sponge::{
+        prp::PRP,
+        spongos::Spongos,
+    },
+    Result,
+};
+use iota_streams_core_edsig::signature::ed25519;
+use iota_streams_ddml::{
+    command::*,
+    io,
+    link_store::{
+        EmptyLinkStore,
+        LinkStore,
+    },
+    types::*,
+};
+
+pub type DigestSize = U32;
+
+/// Digest of the spongos state of the summarized message.
+pub fn digest<F: PRP>(mut head: Spongos<F>) -> NBytes<DigestSize> {
+    head.absorb(b"checkpoint digest");
+    head.commit();
+    let mut digest = NBytes::<DigestSize>::default();
+    head.squeeze(digest.as_mut());
+    digest
+}
+
+pub struct ContentWrap<'a, F, Link>
+where
+    Link: HasLink,
+    <Link as HasLink>::Rel: 'a,
+{
+    pub(crate) link: &'a <Link as HasLink>::Rel,
+    pub(crate) head: &'a <Link as HasLink>::Rel,
+    pub(crate) digest: NBytes<DigestSize>,
+    pub(crate) ids: Vec<Identifier>,
+    pub(crate) sig_kp: &'a ed25519::Keypair,
+    pub(crate) _phantom: core::marker::PhantomData<(F, Link)>,
+}
+
+impl<'a, F, Link> message::ContentSizeof<F> for ContentWrap<'a, F, Link>
+where
+    F: PRP,
+    Link: HasLink,
+    <Link as HasLink>::Rel: 'a + Eq + SkipFallback<F> + AbsorbFallback<F>,
+{
+    fn sizeof<'c>(&self, ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
+        let store = EmptyLinkStore::<F, <Link as HasLink>::Rel, ()>::default();
+        ctx.join(&store, self.link)?
+            .absorb(&self.sig_kp.public)?
+            .absorb(<&Fallback<<Link as HasLink>::Rel>>::from(self.head))?
+            .absorb(&self.digest)?
+            .absorb(Size(self.ids.len()))?
+            .repeated(self.ids.iter(), |ctx, id| message::ContentSizeof::<F>::sizeof(id, ctx))?
+            .ed25519(self.sig_kp, HashSig)?;
+        Ok(ctx)
+    }
+}
+
+impl<'a, F, Link, Store> message::ContentWrap<F, Store> for ContentWrap<'a, F, Link>
+where
+    F: PRP,
+    Link: HasLink,
+    <Link as HasLink>::Rel: 'a + Eq + SkipFallback<F> + AbsorbFallback<F>,
+    Store: LinkStore<F, <Link as HasLink>::Rel>,
+{
+    fn wrap<'c, OS: io::OStream>(
+        &self,
+        store: &Store,
+        ctx: &'c mut wrap::Context<F, OS>,
+    ) -> Result<&'c mut wrap::Context<F, OS>> {
+        ctx.join(store, self.link)?
+            .absorb(&self.sig_kp.public)?
+            .absorb(<&Fallback<<Link as HasLink>::Rel>>::from(self.head))?
+            .absorb(&self.digest)?
+            .absorb(Size(self.ids.len()))?
+            .repeated(self.ids.iter(), |ctx, id| message::ContentWrap::<F, Store>::wrap(id, store, ctx))?
+            .ed25519(self.sig_kp, HashSig)?;
+        Ok(ctx)
+    }
+}
+
+pub struct ContentUnwrap<F, Link: HasLink> {
+    pub(crate) link: <Link as HasLink>::Rel,
+    pub(crate) sig_pk: ed25519::PublicKey,
+    pub(crate) head: <Link as HasLink>::Rel,
+    pub(crate) digest: NBytes<DigestSize>,
+    pub(crate) ids: Vec<Identifier>,
+    pub(crate) _phantom: core::marker::PhantomData<(F, Link)>,
+}
+
+impl<F, Link> Default for ContentUnwrap<F, Link>
+where
+    Link: HasLink,
+    <Link as HasLink>::Rel: Eq + Default + SkipFallback<F>,
+{
+    fn default() -> Self {
+        Self {
+            link: <<Link as HasLink>::Rel as Default>::default(),
+            sig_pk: ed25519::PublicKey::default(),
+            head: <<Link as HasLink>::Rel as Default>::default(),
+            digest: NBytes::default(),
+            ids: Vec::new(),
+            _phantom: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<F, Link, Store> message::ContentUnwrap<F, Store> for ContentUnwrap<F, Link>
+where
+    F: PRP,
+    Link: HasLink,
+    <Link as HasLink>::Rel: Eq + Default + SkipFallback<F> + AbsorbFallback<F>,
+    Store: LinkStore<F, <Link as HasLink>::Rel>,
+{
+    fn unwrap<'c, IS: io::IStream>(
+        &mut self,
+        store: &Store,
+        ctx: &'c mut unwrap::Context<F, IS>,
+    ) -> Result<&'c mut unwrap::Context<F, IS>> {
+        let mut repeated_ids = Size(0);
+        ctx.join(store, &mut self.link)?
+            .absorb(&mut self.sig_pk)?
+            .absorb(<&mut Fallback<<Link as HasLink>::Rel>>::from(&mut self.head))?
+            .absorb(&mut self.digest)?
+            .absorb(&mut repeated_ids)?
+            .repeated(repeated_ids, |ctx| {
+                let (id, ctx) = Identifier::unwrap_new(store, ctx)?;
+                self.ids.push(id);
+                Ok(ctx)
+            })?
+            .ed25519(&self.sig_pk, HashSig)?;
+        Ok(ctx)
+    }
+}
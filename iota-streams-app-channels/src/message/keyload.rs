@@ -1,6 +1,8 @@
 //! `Keyload` message content. This message contains key information for the set of recipients.
 //!
 //! Recipients are identified either by pre-shared keys or by Ed/X25519 public key identifiers.
+//! Recipients identified by an X25519 viewing key get the session key the same way as those
+//! identified by an Ed25519 public key, but they are not publishers of the channel.
 //!
 //! ```ddml
 //! message Keyload {
@@ -49,6 +51,21 @@
 //! 1) Keys identities are not encrypted and may be linked to recipients identities.
 //! 2) Keyload is not authenticated (signed). It can later be implicitly authenticated
 //!     via `SignedPacket`.
+//!
+//! Duration of the access granted to a recipient is encoded as:
+//!
+//! ```ddml
+//! type PermissionDuration {
+//!     absorb u8 oneof;
+//!     absorb bytes bound;
+//! }
+//! ```
+//!
+//! * `oneof` -- 0 for `Perpetual` with empty `bound`, 1 for `Expiring` with `bound` holding a
+//! big-endian `u64`.
+//!
+//! * `bound` -- variant data; readers skip the data of unknown variants, so new variants can be
+//! added without breaking them.
 
 use core::convert::TryFrom;
 use iota_streams_app::{
@@ -59,17 +76,24 @@ use iota_streams_app::{
     },
 };
 use iota_streams_core::{
+    err,
     prelude::{
         typenum::Unsigned as _,
         Vec,
     },
+    prng,
     psk,
     sponge::{
         prp::PRP,
         spongos,
     },
+    Errors::{
+        BadOneof,
+        LengthMismatch,
+    },
     Result,
 };
+use iota_streams_app_derive::Content;
 use iota_streams_core_edsig::{
     key_exchange::x25519,
     signature::ed25519,
@@ -84,10 +108,97 @@ use iota_streams_ddml::{
     types::*,
 };
 
+/// Duration of the access granted to a keyload recipient.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PermissionDuration {
+    /// Access is not limited.
+    Perpetual,
+    /// Access is limited by a bound, a sequence number or a time, interpreted by the application.
+    Expiring(u64),
+    /// Variant unknown to this reader, its data was skipped. It can't be wrapped.
+    Unknown(u8),
+}
+
+/// Encoding of `PermissionDuration`: variant tag and bound.
+#[derive(Content)]
+struct EncodedDuration {
+    #[content(absorb)]
+    oneof: Uint8,
+    #[content(absorb)]
+    bound: Bytes,
+}
+
+impl PermissionDuration {
+    fn encode(&self) -> Result<EncodedDuration> {
+        match self {
+            PermissionDuration::Perpetual => Ok(EncodedDuration {
+                oneof: Uint8(0),
+                bound: Bytes::default(),
+            }),
+            PermissionDuration::Expiring(bound) => Ok(EncodedDuration {
+                oneof: Uint8(1),
+                bound: Bytes(bound.to_be_bytes().to_vec()),
+            }),
+            PermissionDuration::Unknown(_) => err!(BadOneof),
+        }
+    }
+
+    fn decode(encoded: EncodedDuration) -> Result<Self> {
+        let EncodedDuration { oneof, bound } = encoded;
+        match oneof.0 {
+            0 => {
+                if !bound.0.is_empty() {
+                    return err!(LengthMismatch(0, bound.0.len()));
+                }
+                Ok(PermissionDuration::Perpetual)
+            }
+            1 => match <[u8; 8]>::try_from(bound.0.as_ref()) {
+                Ok(bound) => Ok(PermissionDuration::Expiring(u64::from_be_bytes(bound))),
+                Err(_) => err!(LengthMismatch(8, bound.0.len())),
+            },
+            tag => Ok(PermissionDuration::Unknown(tag)),
+        }
+    }
+}
+
+impl<F> message::ContentSizeof<F> for PermissionDuration {
+    fn sizeof<'c>(&self, ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
+        message::ContentSizeof::sizeof(&self.encode()?, ctx)
+    }
+}
+
+impl<F: PRP, Store> message::ContentWrap<F, Store> for PermissionDuration {
+    fn wrap<'c, OS: io::OStream>(
+        &self,
+        store: &Store,
+        ctx: &'c mut wrap::Context<F, OS>,
+    ) -> Result<&'c mut wrap::Context<F, OS>> {
+        message::ContentWrap::wrap(&self.encode()?, store, ctx)
+    }
+}
+
+impl<F: PRP, Store> message::ContentUnwrap<F, Store> for PermissionDuration {
+    fn unwrap<'c, IS: io::IStream>(
+        &mut self,
+        store: &Store,
+        ctx: &'c mut unwrap::Context<F, IS>,
+    ) -> Result<&'c mut unwrap::Context<F, IS>> {
+        let mut encoded = EncodedDuration {
+            oneof: Uint8(0),
+            bound: Bytes::default(),
+        };
+        let ctx = message::ContentUnwrap::unwrap(&mut encoded, store, ctx)?;
+        *self = Self::decode(encoded)?;
+        Ok(ctx)
+    }
+}
+
 pub struct ContentWrap<'a, F, Link: HasLink, Keys> {
     pub(crate) link: &'a <Link as HasLink>::Rel,
     pub nonce: NBytes<U16>,
     pub key: NBytes<U32>,
+    /// Seed of ephemeral X25519 keys, random ephemeral keys are generated if not set.
+    pub(crate) eph_seed: Option<prng::Key>,
     pub(crate) keys: Keys,
     pub(crate) sig_kp: &'a ed25519::Keypair,
     pub(crate) _phantom: core::marker::PhantomData<(F, Link)>,
@@ -117,7 +228,7 @@ where
                                     .absorb(External(<&NBytes<psk::PskSize>>::from(<&[u8]>::from(&store_id))))?
                                     .commit()?
                                     .mask(&self.key),
-                                Identifier::EdPubKey(_pk) => ctx.x25519(
+                                Identifier::EdPubKey(_) | Identifier::XPubKey(_) => ctx.x25519(
                                     &x25519::PublicKey::from(<[u8; 32]>::try_from(store_id.as_ref())?),
                                     &self.key,
                                 ),
@@ -161,10 +272,22 @@ where
                                     .absorb(External(<&NBytes<psk::PskSize>>::from(<&[u8]>::from(&store_id))))?
                                     .commit()?
                                     .mask(&self.key),
-                                Identifier::EdPubKey(_pk) => ctx.x25519(
-                                    &x25519::PublicKey::from(<[u8; 32]>::try_from(store_id.as_ref())?),
-                                    &self.key,
-                                ),
+                                Identifier::EdPubKey(_) | Identifier::XPubKey(_) => {
+                                    let ke_pk = x25519::PublicKey::from(<[u8; 32]>::try_from(store_id.as_ref())?);
+                                    match &self.eph_seed {
+                                        Some(eph_seed) => {
+                                            // ephemeral key derived from the seed, encoded as by `x25519(pk, key)`
+                                            let mut eph_sk = [0_u8; 32];
+                                            prng::Prng::<F>::init_with_seed(eph_seed).gen(&store_id, &mut eph_sk);
+                                            let eph_sk = x25519::StaticSecret::from(eph_sk);
+                                            ctx.absorb(&x25519::PublicKey::from(&eph_sk))?
+                                                .x25519(&eph_sk, &ke_pk)?
+                                                .commit()?
+                                                .mask(&self.key)
+                                        }
+                                        None => ctx.x25519(&ke_pk, &self.key),
+                                    }
+                                }
                             }
                         })
                     })?
@@ -223,6 +346,17 @@ where
             _phantom: core::marker::PhantomData,
         }
     }
+
+    /// Look up the user's key for a recipient entry. All the entries the user holds a key for,
+    /// pre-shared or own X25519 key, are tried, the session key is unwrapped with the first one
+    /// and the following entries are skipped.
+    fn lookup_key<K>(&self, lookup: impl FnOnce() -> Option<K>) -> Option<K> {
+        if self.key.is_none() {
+            lookup()
+        } else {
+            None
+        }
+    }
 }
 
 impl<'a, F, Link, Store, LookupArg, LookupPsk, LookupKeSk> message::ContentUnwrap<F, Store>
@@ -250,7 +384,7 @@ where
                     ctx.fork(|ctx| {
                         match &id {
                             Identifier::PskId(_id) => {
-                                if let Some(psk) = (self.lookup_psk)(self.lookup_arg, &id) {
+                                if let Some(psk) = self.lookup_key(|| (self.lookup_psk)(self.lookup_arg, &id)) {
                                     let mut key = NBytes::<U32>::default();
                                     ctx.absorb(External(<&NBytes<psk::PskSize>>::from(&psk)))?
                                         .commit()?
@@ -266,7 +400,7 @@ where
                                 }
                             }
                             Identifier::EdPubKey(ke_pk) => {
-                                if let Some(ke_sk) = (self.lookup_ke_sk)(self.lookup_arg, &id) {
+                                if let Some(ke_sk) = self.lookup_key(|| (self.lookup_ke_sk)(self.lookup_arg, &id)) {
                                     let mut key = NBytes::<U32>::default();
                                     ctx.x25519(ke_sk, &mut key)?;
                                     self.key = Some(key);
@@ -282,6 +416,19 @@ where
                                     ctx.drop(n)
                                 }
                             }
+                            Identifier::XPubKey(_pk) => {
+                                if let Some(ke_sk) = self.lookup_key(|| (self.lookup_ke_sk)(self.lookup_arg, &id)) {
+                                    let mut key = NBytes::<U32>::default();
+                                    ctx.x25519(ke_sk, &mut key)?;
+                                    self.key = Some(key);
+                                    self.key_ids.push(id);
+                                    Ok(ctx)
+                                } else {
+                                    self.key_ids.push(id);
+                                    let n = Size(64);
+                                    ctx.drop(n)
+                                }
+                            }
                         }
                     })
                 })?
@@ -300,4 +447,84 @@ where
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iota_streams_core_keccak::sponge::prp::keccak::KeccakF1600;
+
+    type F = KeccakF1600;
+
+    fn unwrap_duration(buf: &[u8]) -> Result<(PermissionDuration, Uint8)> {
+        let store = EmptyLinkStore::<F, (), ()>::default();
+        let mut duration = PermissionDuration::Perpetual;
+        let mut next = Uint8(0);
+        let mut ctx = unwrap::Context::<F, &[u8]>::new(buf);
+        message::ContentUnwrap::unwrap(&mut duration, &store, &mut ctx)?.absorb(&mut next)?;
+        Ok((duration, next))
+    }
+
+    #[test]
+    fn permission_duration_roundtrip() -> Result<()> {
+        let store = EmptyLinkStore::<F, (), ()>::default();
+        for duration in &[
+            PermissionDuration::Perpetual,
+            PermissionDuration::Expiring(u64::MAX - 1),
+        ] {
+            let mut ctx = sizeof::Context::<F>::new();
+            message::ContentSizeof::sizeof(duration, &mut ctx)?.absorb(Uint8(42))?;
+            let mut buf = vec![0_u8; ctx.get_size()];
+            let mut ctx = wrap::Context::<F, &mut [u8]>::new(&mut buf[..]);
+            message::ContentWrap::wrap(duration, &store, &mut ctx)?.absorb(Uint8(42))?;
+
+            assert_eq!((*duration, Uint8(42)), unwrap_duration(&buf)?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn derived_encoding_matches_hand_written() -> Result<()> {
+        let store = EmptyLinkStore::<F, (), ()>::default();
+        for duration in &[PermissionDuration::Perpetual, PermissionDuration::Expiring(7)] {
+            let encoded = duration.encode()?;
+            let size = sizeof::Context::<F>::new()
+                .absorb(encoded.oneof)?
+                .absorb(&encoded.bound)?
+                .get_size();
+            let mut expected = vec![0_u8; size];
+            wrap::Context::<F, &mut [u8]>::new(&mut expected[..])
+                .absorb(encoded.oneof)?
+                .absorb(&encoded.bound)?;
+
+            let mut ctx = sizeof::Context::<F>::new();
+            assert_eq!(size, message::ContentSizeof::sizeof(duration, &mut ctx)?.get_size());
+            let mut buf = vec![0_u8; size];
+            let mut ctx = wrap::Context::<F, &mut [u8]>::new(&mut buf[..]);
+            message::ContentWrap::wrap(duration, &store, &mut ctx)?;
+            assert_eq!(expected, buf);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_permission_duration_is_skipped() -> Result<()> {
+        let bound = Bytes(vec![1, 2, 3]);
+        let size = sizeof::Context::<F>::new()
+            .absorb(Uint8(7))?
+            .absorb(&bound)?
+            .absorb(Uint8(42))?
+            .get_size();
+        let mut buf = vec![0_u8; size];
+        wrap::Context::<F, &mut [u8]>::new(&mut buf[..])
+            .absorb(Uint8(7))?
+            .absorb(&bound)?
+            .absorb(Uint8(42))?;
+
+        assert_eq!((PermissionDuration::Unknown(7), Uint8(42)), unwrap_duration(&buf)?);
+        let store = EmptyLinkStore::<F, (), ()>::default();
+        let mut ctx = wrap::Context::<F, &mut [u8]>::new(&mut buf[..]);
+        assert!(message::ContentWrap::wrap(&PermissionDuration::Unknown(7), &store, &mut ctx).is_err());
+        Ok(())
+    }
+}
+
 // TODO: add test cases: 0,1,2 pks + 0,1,2 psks + key found/notfound + unwrap modify/fuzz to check sig does work
@@ -0,0 +1,191 @@
+//! Masked payload split into blocks, each block is authenticated with its own MAC.
+//!
+//! ```ddml
+//! message MaskedBlocks {
+//!     absorb size block_size;
+//!     absorb size n;
+//!     repeated(n):
+//!         mask bytes block;
+//!         commit;
+//!         squeeze byte mac[16];
+//! }
+//! ```
+//!
+//! # Fields
+//!
+//! * `block_size` -- length of blocks, all the blocks but the last one are exactly `block_size` bytes long.
+//!
+//! * `block` -- masked part of payload.
+//!
+//! * `mac` -- MAC of the message up to and including the block.
+//!
+//! The MAC of a block is checked as soon as the block is unwrapped, so a truncated or corrupted
+//! message is rejected at the first bad block instead of at the end of the message. The overhead is
+//! `MASKED_BLOCK_MAC_SIZE` bytes plus the encoded block length per block.
+
+use iota_streams_core::{
+    sponge::prp::PRP,
+    try_or,
+    Errors::{
+        BytesLengthExceeded,
+        MaskedBlockLengthMismatch,
+        ZeroMaskedBlockSize,
+    },
+    Result,
+};
+use iota_streams_ddml::{
+    command::*,
+    io,
+    types::*,
+};
+
+/// Length of the MAC squeezed after each block.
+pub const MASKED_BLOCK_MAC_SIZE: usize = 16;
+
+pub(crate) fn sizeof<'c, F>(
+    ctx: &'c mut sizeof::Context<F>,
+    block_size: usize,
+    payload: &Bytes,
+) -> Result<&'c mut sizeof::Context<F>> {
+    try_or!(block_size != 0, ZeroMaskedBlockSize)?;
+    let blocks = payload.0.chunks(block_size);
+    ctx.absorb(Size(block_size))?
+        .absorb(Size(blocks.len()))?
+        .repeated(blocks, |ctx, block| {
+            ctx.mask(&Bytes(block.to_vec()))?
+                .commit()?
+                .squeeze(Mac(MASKED_BLOCK_MAC_SIZE))
+        })
+}
+
+pub(crate) fn wrap<'c, F: PRP, OS: io::OStream>(
+    ctx: &'c mut wrap::Context<F, OS>,
+    block_size: usize,
+    payload: &Bytes,
+) -> Result<&'c mut wrap::Context<F, OS>> {
+    try_or!(block_size != 0, ZeroMaskedBlockSize)?;
+    let blocks = payload.0.chunks(block_size);
+    ctx.absorb(Size(block_size))?
+        .absorb(Size(blocks.len()))?
+        .repeated(blocks, |ctx, block| {
+            ctx.mask(&Bytes(block.to_vec()))?
+                .commit()?
+                .squeeze(Mac(MASKED_BLOCK_MAC_SIZE))
+        })
+}
+
+/// Unwrap blocks into `payload` rejecting payloads longer than `max_len` bytes.
+pub(crate) fn unwrap<'c, F: PRP, IS: io::IStream>(
+    ctx: &'c mut unwrap::Context<F, IS>,
+    payload: &mut Bytes,
+    max_len: usize,
+) -> Result<&'c mut unwrap::Context<F, IS>> {
+    unwrap_or_skip(ctx, Some(payload), max_len)
+}
+
+/// Skip blocks without unmasking them, the MAC of each block is still checked.
+pub(crate) fn skip<'c, F: PRP, IS: io::IStream>(
+    ctx: &'c mut unwrap::Context<F, IS>,
+    max_len: usize,
+) -> Result<&'c mut unwrap::Context<F, IS>> {
+    unwrap_or_skip(ctx, None, max_len)
+}
+
+fn unwrap_or_skip<'c, F: PRP, IS: io::IStream>(
+    ctx: &'c mut unwrap::Context<F, IS>,
+    mut payload: Option<&mut Bytes>,
+    max_len: usize,
+) -> Result<&'c mut unwrap::Context<F, IS>> {
+    let mut block_size = Size(0);
+    let mut n = Size(0);
+    ctx.absorb(&mut block_size)?.absorb(&mut n)?;
+    try_or!(block_size.0 != 0, ZeroMaskedBlockSize)?;
+    // The shortest payload with `n` blocks is checked before any block is unwrapped
+    let min_len = n.0.saturating_sub(1).saturating_mul(block_size.0).saturating_add(1);
+    try_or!(n.0 == 0 || min_len <= max_len, BytesLengthExceeded(min_len, max_len))?;
+
+    if let Some(payload) = &mut payload {
+        payload.0.clear();
+    }
+    let mut len = 0_usize;
+    for i in 0..n.0 {
+        let mut block = Bytes::default();
+        let block_len = if payload.is_some() {
+            ctx.mask_bounded_bytes(&mut block, block_size.0)?;
+            block.0.len()
+        } else {
+            let mut size = Size(0);
+            ctx.skip_mask_bounded_bytes(&mut size, block_size.0)?;
+            size.0
+        };
+        try_or!(
+            block_len == block_size.0 || (i + 1 == n.0 && block_len != 0),
+            MaskedBlockLengthMismatch(i, block_len)
+        )?;
+        ctx.commit()?.squeeze(Mac(MASKED_BLOCK_MAC_SIZE))?;
+        if let Some(payload) = &mut payload {
+            payload.0.extend_from_slice(&block.0);
+        }
+        len += block_len;
+    }
+    try_or!(len <= max_len, BytesLengthExceeded(len, max_len))?;
+    Ok(ctx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iota_streams_core::prelude::Vec;
+    use iota_streams_core_keccak::sponge::prp::keccak::KeccakF1600;
+
+    type F = KeccakF1600;
+
+    fn wrap_blocks(payload: &Bytes, block_size: usize) -> Result<Vec<u8>> {
+        let mut ctx = sizeof::Context::<F>::new();
+        sizeof(&mut ctx, block_size, payload)?;
+        let mut buf = vec![0_u8; ctx.get_size()];
+        let mut ctx = wrap::Context::<F, &mut [u8]>::new(&mut buf[..]);
+        wrap(&mut ctx, block_size, payload)?;
+        Ok(buf)
+    }
+
+    fn unwrap_blocks(buf: &[u8], max_len: usize) -> Result<Bytes> {
+        let mut payload = Bytes::default();
+        let mut ctx = unwrap::Context::<F, &[u8]>::new(buf);
+        unwrap(&mut ctx, &mut payload, max_len)?;
+        Ok(payload)
+    }
+
+    #[test]
+    fn corrupted_block_is_rejected() -> Result<()> {
+        let payload = Bytes((0_u8..30).collect());
+        let buf = wrap_blocks(&payload, 8)?;
+        assert_eq!(unwrap_blocks(&buf, 1024)?, payload);
+        assert!(unwrap_blocks(&buf, 16).is_err());
+
+        // Flip a bit of the payload in the last block
+        let mut corrupted = buf.clone();
+        let n = corrupted.len() - MASKED_BLOCK_MAC_SIZE - 1;
+        corrupted[n] ^= 1;
+        assert!(unwrap_blocks(&corrupted, 1024).is_err());
+        assert!(unwrap_blocks(&buf[..buf.len() - 1], 1024).is_err());
+        assert!(wrap_blocks(&payload, 0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn skipped_blocks_are_checked() -> Result<()> {
+        let payload = Bytes((0_u8..30).collect());
+        let buf = wrap_blocks(&payload, 8)?;
+        let mut ctx = unwrap::Context::<F, &[u8]>::new(&buf[..]);
+        skip(&mut ctx, 1024)?;
+        assert!(ctx.stream.is_empty());
+
+        let mut corrupted = buf.clone();
+        let n = corrupted.len() - MASKED_BLOCK_MAC_SIZE - 1;
+        corrupted[n] ^= 1;
+        assert!(skip(&mut unwrap::Context::<F, &[u8]>::new(&corrupted[..]), 1024).is_err());
+        assert!(skip(&mut unwrap::Context::<F, &[u8]>::new(&buf[..]), 16).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,195 @@
+//! Application metadata: a map of named binary values carried by `SignedPacket` messages.
+//!
+//! ```ddml
+//! message Metadata {
+//!     absorb size n;
+//!     repeated(n):
+//!         absorb bytes key;
+//!         absorb bytes value;
+//! }
+//! ```
+//!
+//! # Fields
+//!
+//! * `key` -- utf-8 encoded name of the entry.
+//!
+//! * `value` -- value of the entry.
+//!
+//! Entries are encoded in ascending order of their keys, so that a map has a unique encoding.
+//! Unwrapping rejects keys which are not valid utf-8 strings or are not in strictly ascending order.
+//!
+//! The entry with the `TOPIC_KEY` key holds the utf-8 encoded topic of the packet, packets without
+//! a topic belong to the `DEFAULT_TOPIC`.
+
+use iota_streams_app::message;
+use iota_streams_core::{
+    err,
+    prelude::{
+        hex,
+        BTreeMap,
+        String,
+        Vec,
+    },
+    sponge::prp::PRP,
+    Errors::BadMetadataKey,
+    Result,
+};
+use iota_streams_ddml::{
+    command::*,
+    io,
+    types::*,
+};
+
+/// Key of the entry holding the topic of the packet.
+pub const TOPIC_KEY: &str = "topic";
+
+/// Topic of packets carrying no topic entry.
+pub const DEFAULT_TOPIC: &str = "";
+
+/// Metadata entries ordered by key.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Metadata(pub BTreeMap<String, Vec<u8>>);
+
+impl Metadata {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert an entry returning the previous value of the key, if any.
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<Vec<u8>>) -> Option<Vec<u8>> {
+        self.0.insert(key.into(), value.into())
+    }
+
+    /// Get value of the entry with the given key.
+    pub fn get(&self, key: &str) -> Option<&[u8]> {
+        self.0.get(key).map(|value| value.as_ref())
+    }
+
+    /// Topic of the packet, `None` if the entry is missing or is not a valid utf-8 string.
+    pub fn topic(&self) -> Option<&str> {
+        self.get(TOPIC_KEY).and_then(|topic| core::str::from_utf8(topic).ok())
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<BTreeMap<String, Vec<u8>>> for Metadata {
+    fn from(entries: BTreeMap<String, Vec<u8>>) -> Self {
+        Self(entries)
+    }
+}
+
+impl<F> message::ContentSizeof<F> for Metadata {
+    fn sizeof<'c>(&self, ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
+        ctx.absorb(Size(self.0.len()))?
+            .repeated(self.0.iter(), |ctx, (key, value)| {
+                ctx.absorb(&Bytes(key.as_bytes().to_vec()))?
+                    .absorb(&Bytes(value.clone()))
+            })?;
+        Ok(ctx)
+    }
+}
+
+impl<F: PRP, Store> message::ContentWrap<F, Store> for Metadata {
+    fn wrap<'c, OS: io::OStream>(
+        &self,
+        _store: &Store,
+        ctx: &'c mut wrap::Context<F, OS>,
+    ) -> Result<&'c mut wrap::Context<F, OS>> {
+        ctx.absorb(Size(self.0.len()))?
+            .repeated(self.0.iter(), |ctx, (key, value)| {
+                ctx.absorb(&Bytes(key.as_bytes().to_vec()))?
+                    .absorb(&Bytes(value.clone()))
+            })?;
+        Ok(ctx)
+    }
+}
+
+impl<F: PRP, Store> message::ContentUnwrap<F, Store> for Metadata {
+    fn unwrap<'c, IS: io::IStream>(
+        &mut self,
+        _store: &Store,
+        ctx: &'c mut unwrap::Context<F, IS>,
+    ) -> Result<&'c mut unwrap::Context<F, IS>> {
+        let entries = &mut self.0;
+        entries.clear();
+        let mut n = Size(0);
+        ctx.absorb(&mut n)?.repeated(n, |ctx| {
+            let mut key = Bytes::default();
+            let mut value = Bytes::default();
+            ctx.absorb(&mut key)?.absorb(&mut value)?;
+            let key = match String::from_utf8(key.0) {
+                Ok(key) => key,
+                Err(e) => return err!(BadMetadataKey(hex::encode(e.into_bytes()))),
+            };
+            if entries.keys().next_back().map_or(false, |last| *last >= key) {
+                return err!(BadMetadataKey(key));
+            }
+            entries.insert(key, value.0);
+            Ok(ctx)
+        })?;
+        Ok(ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iota_streams_core_keccak::sponge::prp::keccak::KeccakF1600;
+    use iota_streams_ddml::link_store::EmptyLinkStore;
+
+    #[test]
+    fn metadata_roundtrip() -> Result<()> {
+        type F = KeccakF1600;
+        let store = EmptyLinkStore::<F, (), ()>::default();
+        let mut metadata = Metadata::new();
+        metadata.insert("type", "reading");
+        metadata.insert("unit", "celsius");
+        metadata.insert("empty", Vec::<u8>::new());
+
+        let mut ctx = sizeof::Context::<F>::new();
+        message::ContentSizeof::sizeof(&metadata, &mut ctx)?;
+        let mut buf = vec![0_u8; ctx.get_size()];
+
+        let mut ctx = wrap::Context::<F, &mut [u8]>::new(&mut buf[..]);
+        message::ContentWrap::wrap(&metadata, &store, &mut ctx)?;
+        let wrapped = ctx.spongos.squeeze_n(32);
+
+        let mut unwrapped = Metadata::new();
+        let mut ctx = unwrap::Context::<F, &[u8]>::new(&buf[..]);
+        message::ContentUnwrap::unwrap(&mut unwrapped, &store, &mut ctx)?;
+        assert_eq!(wrapped, ctx.spongos.squeeze_n(32));
+        assert_eq!(metadata, unwrapped);
+        assert_eq!(Some(&b"celsius"[..]), unwrapped.get("unit"));
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_pcf_frame_type_is_rejected() -> Result<()> {
+        type F = KeccakF1600;
+        let store = EmptyLinkStore::<F, (), ()>::default();
+        let mut metadata = Metadata::new();
+        metadata.insert("type", "reading");
+
+        for (frame_type, valid) in &[(message::FINAL_PCF_ID, true), (Uint8(0), false), (Uint8(0xff), false)] {
+            let frame = message::PCF::new(*frame_type, 1, metadata.clone())?;
+            let mut ctx = sizeof::Context::<F>::new();
+            message::ContentSizeof::sizeof(&frame, &mut ctx)?;
+            let mut buf = vec![0_u8; ctx.get_size()];
+            let mut ctx = wrap::Context::<F, &mut [u8]>::new(&mut buf[..]);
+            message::ContentWrap::wrap(&frame, &store, &mut ctx)?;
+
+            let mut unwrapped = message::PCF::default_with_content(Metadata::new());
+            let mut ctx = unwrap::Context::<F, &[u8]>::new(&buf[..]);
+            let result = message::ContentUnwrap::unwrap(&mut unwrapped, &store, &mut ctx).map(|_| ());
+            assert_eq!(result.is_ok(), *valid);
+        }
+        Ok(())
+    }
+}
@@ -18,6 +18,27 @@ pub mod subscribe;
 /// Sequence message.
 pub mod sequence;
 
+/// Close message.
+pub mod close;
+
+/// AddAuthor message.
+pub mod add_author;
+
+/// SealMembership message.
+pub mod seal_membership;
+
+/// Ack message.
+pub mod ack;
+
+/// Checkpoint message.
+pub mod checkpoint;
+
+/// Application metadata carried by SignedPacket message.
+pub mod metadata;
+
+/// Masked payload of SignedPacket and TaggedPacket messages split into authenticated blocks.
+pub mod masked_blocks;
+
 /// Announcement Message Type
 pub const ANNOUNCE: u8 = 0;
 /// Keyload Message Type
@@ -32,6 +53,56 @@ pub const TAGGED_PACKET: u8 = 4;
 pub const SUBSCRIBE: u8 = 5;
 /// Unsubscribe Message Type
 pub const UNSUBSCRIBE: u8 = 6;
+/// Close Message Type
+pub const CLOSE: u8 = 7;
+/// AddAuthor Message Type
+pub const ADD_AUTHOR: u8 = 8;
+/// SealMembership Message Type
+pub const SEAL_MEMBERSHIP: u8 = 9;
+/// Ack Message Type
+pub const ACK: u8 = 10;
+/// Checkpoint Message Type
+pub const CHECKPOINT: u8 = 11;
+
+/// Type of a channel message given by the content type of its header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MessageKind {
+    Announce,
+    Keyload,
+    Sequence,
+    SignedPacket,
+    TaggedPacket,
+    Subscribe,
+    Unsubscribe,
+    Close,
+    AddAuthor,
+    SealMembership,
+    Ack,
+    Checkpoint,
+    /// Content type not known to this version, eg. a message type added later or a message with
+    /// application defined content.
+    Unknown(u8),
+}
+
+impl From<u8> for MessageKind {
+    fn from(content_type: u8) -> Self {
+        match content_type {
+            ANNOUNCE => MessageKind::Announce,
+            KEYLOAD => MessageKind::Keyload,
+            SEQUENCE => MessageKind::Sequence,
+            SIGNED_PACKET => MessageKind::SignedPacket,
+            TAGGED_PACKET => MessageKind::TaggedPacket,
+            SUBSCRIBE => MessageKind::Subscribe,
+            UNSUBSCRIBE => MessageKind::Unsubscribe,
+            CLOSE => MessageKind::Close,
+            ADD_AUTHOR => MessageKind::AddAuthor,
+            SEAL_MEMBERSHIP => MessageKind::SealMembership,
+            ACK => MessageKind::Ack,
+            CHECKPOINT => MessageKind::Checkpoint,
+            unknown => MessageKind::Unknown(unknown),
+        }
+    }
+}
 
 // Unsubscribe message.
 // pub mod unsubscribe;
@@ -9,7 +9,10 @@
 //! message SignedPacket {
 //!     join link msgid;
 //!     absorb bytes public_payload;
-//!     mask bytes masked_payload;
+//!     absorb bytes context; // optional
+//!     absorb Metadata metadata; // optional
+//!     absorb u16 app_type; // optional
+//!     mask bytes masked_payload; // omitted if empty, or masked_blocks::MaskedBlocks
 //!     commit;
 //!     squeeze external byte hash[78];
 //!     mssig(hash) sig;
@@ -22,17 +25,41 @@
 //!
 //! * `public_payload` -- public part of payload.
 //!
+//! * `context` -- application supplied context (eg. external request id), presence is signalled by a header flag.
+//!
+//! * `metadata` -- application metadata entries, see `metadata::Metadata`, presence is signalled by a header flag.
+//!
+//! * `app_type` -- application defined content type used for routing, presence is signalled by a header flag,
+//! the default type 0 is not encoded.
+//!
 //! * `masked_payload` -- masked part of payload.
 //!
 //! * `hash` -- hash value to be signed.
 //!
 //! * `sig` -- message signature generated with the senders private key.
+//!
+//! An empty `masked_payload` is omitted from the message, this is signalled by a header flag.
+//! It saves the encoded payload size (1 byte) and the corresponding `mask` operation, which is
+//! meaningful for public broadcast packets. Such packets can't be read by older versions, packets
+//! with non-empty masked payload are encoded as before.
+//!
+//! A non-empty `masked_payload` can be split into blocks authenticated separately, see
+//! `masked_blocks`. This is signalled by a header flag.
+//!
+//! The topic of the packet, see `metadata::TOPIC_KEY`, is read before the masked payload. When
+//! unwrapping packets of topics the reader does not follow, the masked payload is skipped: the
+//! spongos state is updated with the encrypted payload and the signature is checked as usual, but
+//! the payload is not unmasked.
 
 use iota_streams_app::message::{
     self,
     HasLink,
 };
 use iota_streams_core::{
+    prelude::{
+        String,
+        Vec,
+    },
     sponge::prp::PRP,
     Result,
 };
@@ -47,6 +74,14 @@ use iota_streams_ddml::{
     types::*,
 };
 
+use super::{
+    masked_blocks,
+    metadata::{
+        Metadata,
+        DEFAULT_TOPIC,
+    },
+};
+
 pub struct ContentWrap<'a, F, Link>
 where
     Link: HasLink,
@@ -55,7 +90,11 @@ where
     pub(crate) link: &'a <Link as HasLink>::Rel,
     pub(crate) public_payload: &'a Bytes,
     pub(crate) masked_payload: &'a Bytes,
-    pub(crate) sig_kp: &'a ed25519::Keypair,
+    pub(crate) context: Option<&'a Bytes>,
+    pub(crate) metadata: Option<&'a Metadata>,
+    pub(crate) app_type: u16,
+    pub(crate) masked_block_size: Option<usize>,
+    pub(crate) signer: &'a dyn Signer,
     pub(crate) _phantom: core::marker::PhantomData<(F, Link)>,
 }
 
@@ -68,10 +107,24 @@ where
     fn sizeof<'c>(&self, ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
         let store = EmptyLinkStore::<F, <Link as HasLink>::Rel, ()>::default();
         ctx.join(&store, self.link)?
-            .absorb(&self.sig_kp.public)?
-            .absorb(self.public_payload)?
-            .mask(self.masked_payload)?
-            .ed25519(self.sig_kp, HashSig)?;
+            .absorb(self.signer.public_key())?
+            .absorb(self.public_payload)?;
+        if let Some(context) = self.context {
+            ctx.absorb(context)?;
+        }
+        if let Some(metadata) = self.metadata {
+            message::ContentSizeof::<F>::sizeof(metadata, ctx)?;
+        }
+        if self.app_type != 0 {
+            ctx.absorb(Uint16(self.app_type))?;
+        }
+        if !self.masked_payload.0.is_empty() {
+            match self.masked_block_size {
+                Some(block_size) => masked_blocks::sizeof(ctx, block_size, self.masked_payload)?,
+                None => ctx.mask(self.masked_payload)?,
+            };
+        }
+        ctx.ed25519(self.signer, HashSig)?;
         // TODO: Is both public and masked payloads are ok? Leave public only or masked only?
         Ok(ctx)
     }
@@ -90,10 +143,24 @@ where
         ctx: &'c mut wrap::Context<F, OS>,
     ) -> Result<&'c mut wrap::Context<F, OS>> {
         ctx.join(store, self.link)?
-            .absorb(&self.sig_kp.public)?
-            .absorb(self.public_payload)?
-            .mask(self.masked_payload)?
-            .ed25519(self.sig_kp, HashSig)?;
+            .absorb(self.signer.public_key())?
+            .absorb(self.public_payload)?;
+        if let Some(context) = self.context {
+            ctx.absorb(context)?;
+        }
+        if let Some(metadata) = self.metadata {
+            message::ContentWrap::<F, Store>::wrap(metadata, store, ctx)?;
+        }
+        if self.app_type != 0 {
+            ctx.absorb(Uint16(self.app_type))?;
+        }
+        if !self.masked_payload.0.is_empty() {
+            match self.masked_block_size {
+                Some(block_size) => masked_blocks::wrap(ctx, block_size, self.masked_payload)?,
+                None => ctx.mask(self.masked_payload)?,
+            };
+        }
+        ctx.ed25519(self.signer, HashSig)?;
         Ok(ctx)
     }
 }
@@ -102,10 +169,79 @@ pub struct ContentUnwrap<F, Link: HasLink> {
     pub(crate) link: <Link as HasLink>::Rel,
     pub(crate) public_payload: Bytes,
     pub(crate) masked_payload: Bytes,
+    pub(crate) masked_payload_omitted: bool,
+    pub(crate) masked_blocks: bool,
+    pub(crate) context: Option<Bytes>,
+    pub(crate) metadata: Option<Metadata>,
+    pub(crate) app_type: Option<Uint16>,
+    pub(crate) max_payload_length: usize,
+    pub(crate) followed_topics: Option<Vec<String>>,
+    pub(crate) masked_payload_skipped: bool,
     pub(crate) sig_pk: ed25519::PublicKey,
     pub(crate) _phantom: core::marker::PhantomData<(F, Link)>,
 }
 
+impl<F, Link> ContentUnwrap<F, Link>
+where
+    Link: HasLink,
+    <Link as HasLink>::Rel: Eq + Default + SkipFallback<F>,
+{
+    /// Expect the masked payload to be omitted as signalled in the message header.
+    pub fn with_masked_payload_omitted(mut self, masked_payload_omitted: bool) -> Self {
+        self.masked_payload_omitted = masked_payload_omitted;
+        self
+    }
+
+    /// Expect the masked payload split into blocks, see `masked_blocks`.
+    pub fn with_masked_blocks(mut self, masked_blocks: bool) -> Self {
+        self.masked_blocks = masked_blocks;
+        self
+    }
+
+    /// Expect the application context as signalled in the message header.
+    pub fn with_context(mut self, has_context: bool) -> Self {
+        self.context = if has_context { Some(Bytes::default()) } else { None };
+        self
+    }
+
+    /// Expect the application metadata as signalled in the message header.
+    pub fn with_metadata(mut self, has_metadata: bool) -> Self {
+        self.metadata = if has_metadata { Some(Metadata::default()) } else { None };
+        self
+    }
+
+    /// Expect the application type as signalled in the message header.
+    pub fn with_app_type(mut self, has_app_type: bool) -> Self {
+        self.app_type = if has_app_type { Some(Uint16(0)) } else { None };
+        self
+    }
+
+    /// Reject public and masked payloads declared longer than `max_payload_length` bytes.
+    pub fn with_max_payload_length(mut self, max_payload_length: usize) -> Self {
+        self.max_payload_length = max_payload_length;
+        self
+    }
+
+    /// Skip the masked payload of packets whose topic is not one of `followed_topics`, all the
+    /// payloads are unmasked if not set.
+    pub fn with_followed_topics(mut self, followed_topics: Option<Vec<String>>) -> Self {
+        self.followed_topics = followed_topics;
+        self
+    }
+
+    /// Whether the topic of the packet is followed, its masked payload is skipped otherwise.
+    fn is_followed(&self) -> bool {
+        let topic = self
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.topic())
+            .unwrap_or(DEFAULT_TOPIC);
+        self.followed_topics
+            .as_ref()
+            .map_or(true, |topics| topics.iter().any(|followed| followed == topic))
+    }
+}
+
 impl<F, Link> Default for ContentUnwrap<F, Link>
 where
     Link: HasLink,
@@ -116,6 +252,14 @@ where
             link: <<Link as HasLink>::Rel as Default>::default(),
             public_payload: Bytes::default(),
             masked_payload: Bytes::default(),
+            masked_payload_omitted: false,
+            masked_blocks: false,
+            context: None,
+            metadata: None,
+            app_type: None,
+            max_payload_length: usize::MAX,
+            followed_topics: None,
+            masked_payload_skipped: false,
             sig_pk: ed25519::PublicKey::default(),
             _phantom: core::marker::PhantomData,
         }
@@ -136,9 +280,31 @@ where
     ) -> Result<&'c mut unwrap::Context<F, IS>> {
         ctx.join(store, &mut self.link)?
             .absorb(&mut self.sig_pk)?
-            .absorb(&mut self.public_payload)?
-            .mask(&mut self.masked_payload)?
-            .ed25519(&self.sig_pk, HashSig)?;
+            .absorb_bounded_bytes(&mut self.public_payload, self.max_payload_length)?;
+        if let Some(context) = &mut self.context {
+            ctx.absorb(context)?;
+        }
+        if let Some(metadata) = &mut self.metadata {
+            message::ContentUnwrap::<F, Store>::unwrap(metadata, store, ctx)?;
+        }
+        if let Some(app_type) = &mut self.app_type {
+            ctx.absorb(app_type)?;
+        }
+        self.masked_payload_skipped = !self.masked_payload_omitted && !self.is_followed();
+        if self.masked_payload_skipped {
+            if self.masked_blocks {
+                masked_blocks::skip(ctx, self.max_payload_length)?;
+            } else {
+                ctx.skip_mask_bounded_bytes(&mut Size(0), self.max_payload_length)?;
+            }
+        } else if !self.masked_payload_omitted {
+            if self.masked_blocks {
+                masked_blocks::unwrap(ctx, &mut self.masked_payload, self.max_payload_length)?;
+            } else {
+                ctx.mask_bounded_bytes(&mut self.masked_payload, self.max_payload_length)?;
+            }
+        }
+        ctx.ed25519(&self.sig_pk, HashSig)?;
         Ok(ctx)
     }
 }
@@ -16,6 +16,7 @@
 //!     x25519(key) byte unsubscribe_key[32];
 //!     commit;
 //!     mask byte pk[32];
+//!     mask bytes payload; // optional
 //!     commit;
 //!     squeeze external byte hash[78];
 //!     mssig(hash) sig;
@@ -32,6 +33,9 @@
 //!
 //! * `pk` -- subscriber's Ed25519 public key.
 //!
+//! * `payload` -- application payload for the channel owner (eg. display name), presence is signalled by a header flag.
+//!   It is masked with the key shared with the owner and covered by the signature.
+//!
 //! * `hash` -- hash value to be signed.
 //!
 //! * `sig` -- message signature generated with the senders private key.
@@ -68,6 +72,7 @@ pub struct ContentWrap<'a, F, Link: HasLink> {
     pub(crate) link: &'a <Link as HasLink>::Rel,
     pub unsubscribe_key: NBytes<U32>,
     pub(crate) subscriber_sig_kp: &'a ed25519::Keypair,
+    pub(crate) payload: &'a Bytes,
     pub(crate) author_ke_pk: &'a x25519::PublicKey,
     pub(crate) _phantom: core::marker::PhantomData<(Link, F)>,
 }
@@ -82,8 +87,11 @@ where
         let store = EmptyLinkStore::<F, <Link as HasLink>::Rel, ()>::default();
         ctx.join(&store, self.link)?
             .x25519(self.author_ke_pk, &self.unsubscribe_key)?
-            .mask(&self.subscriber_sig_kp.public)?
-            .ed25519(self.subscriber_sig_kp, HashSig)?;
+            .mask(&self.subscriber_sig_kp.public)?;
+        if !self.payload.0.is_empty() {
+            ctx.mask(self.payload)?;
+        }
+        ctx.ed25519(self.subscriber_sig_kp, HashSig)?;
         Ok(ctx)
     }
 }
@@ -102,8 +110,11 @@ where
     ) -> Result<&'c mut wrap::Context<F, OS>> {
         ctx.join(store, self.link)?
             .x25519(self.author_ke_pk, &self.unsubscribe_key)?
-            .mask(&self.subscriber_sig_kp.public)?
-            .ed25519(self.subscriber_sig_kp, HashSig)?;
+            .mask(&self.subscriber_sig_kp.public)?;
+        if !self.payload.0.is_empty() {
+            ctx.mask(self.payload)?;
+        }
+        ctx.ed25519(self.subscriber_sig_kp, HashSig)?;
         Ok(ctx)
     }
 }
@@ -112,6 +123,8 @@ pub struct ContentUnwrap<'a, F, Link: HasLink> {
     pub link: <Link as HasLink>::Rel,
     pub unsubscribe_key: NBytes<U32>,
     pub subscriber_sig_pk: ed25519::PublicKey,
+    pub payload: Option<Bytes>,
+    max_payload_length: usize,
     author_ke_sk: &'a x25519::StaticSecret,
     _phantom: core::marker::PhantomData<(F, Link)>,
 }
@@ -128,12 +141,26 @@ where
                 link: <<Link as HasLink>::Rel as Default>::default(),
                 unsubscribe_key: NBytes::<U32>::default(),
                 subscriber_sig_pk: pk,
+                payload: None,
+                max_payload_length: usize::MAX,
                 author_ke_sk,
                 _phantom: core::marker::PhantomData,
             }),
             Err(e) => Err(wrapped_err!(MessageCreationFailure, WrappedError(e))),
         }
     }
+
+    /// Expect the subscription payload as signalled in the message header.
+    pub fn with_payload(mut self, has_payload: bool) -> Self {
+        self.payload = if has_payload { Some(Bytes::default()) } else { None };
+        self
+    }
+
+    /// Reject subscription payloads declared longer than `max_payload_length` bytes.
+    pub fn with_max_payload_length(mut self, max_payload_length: usize) -> Self {
+        self.max_payload_length = max_payload_length;
+        self
+    }
 }
 
 impl<'a, F, Link, Store> message::ContentUnwrap<F, Store> for ContentUnwrap<'a, F, Link>
@@ -150,8 +177,11 @@ where
     ) -> Result<&'c mut unwrap::Context<F, IS>> {
         ctx.join(store, &mut self.link)?
             .x25519(self.author_ke_sk, &mut self.unsubscribe_key)?
-            .mask(&mut self.subscriber_sig_pk)?
-            .ed25519(&self.subscriber_sig_pk, HashSig)?;
+            .mask(&mut self.subscriber_sig_pk)?;
+        if let Some(payload) = &mut self.payload {
+            ctx.mask_bounded_bytes(payload, self.max_payload_length)?;
+        }
+        ctx.ed25519(&self.subscriber_sig_pk, HashSig)?;
         Ok(ctx)
     }
 }
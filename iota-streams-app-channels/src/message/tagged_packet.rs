@@ -8,9 +8,12 @@
 //! message TaggedPacket {
 //!     join link msgid;
 //!     absorb bytes public_payload;
+//!     absorb bytes context; // optional
+//!     absorb u16 app_type; // optional
+//!     absorb u8 mac_length; // optional
 //!     mask bytes masked_payload;
 //!     commit;
-//!     squeeze byte mac[32];
+//!     squeeze byte mac[mac_length];
 //! }
 //! ```
 //!
@@ -20,9 +23,24 @@
 //!
 //! * `public_payload` -- public part of payload.
 //!
+//! * `context` -- application supplied context (eg. external request id), presence is signalled by a header flag.
+//!
+//! * `app_type` -- application defined content type used for routing, presence is signalled by a header flag,
+//! the default type 0 is not encoded.
+//!
+//! * `mac_length` -- length of the MAC in bytes, one of `MAC_LENGTHS`, presence is signalled by a header flag,
+//! the default length of 32 bytes is not encoded.
+//!
 //! * `masked_payload` -- masked part of payload.
 //!
 //! * `mac` -- MAC of the message.
+//!
+//! A shorter MAC saves bytes on bandwidth-constrained channels at the cost of security: a forged
+//! 16-byte tag is accepted with probability 2^-128 and the tag offers 64 bits of security against
+//! collision-style attacks, instead of 2^-256 and 128 bits for the default 32-byte tag.
+//!
+//! The `masked_payload` can be split into blocks authenticated separately, see `masked_blocks`.
+//! This is signalled by a header flag.
 
 use iota_streams_app::message::{
     self,
@@ -34,6 +52,8 @@ use iota_streams_core::{
         prp::PRP,
         spongos,
     },
+    try_or,
+    Errors::TagMacLengthUnsupported,
     Result,
 };
 use iota_streams_ddml::{
@@ -46,6 +66,20 @@ use iota_streams_ddml::{
     types::*,
 };
 
+use super::masked_blocks;
+
+/// Supported lengths in bytes of tagged packet MACs.
+pub const MAC_LENGTHS: [usize; 3] = [16, 24, 32];
+
+/// The MAC is squeezed from spongos, its length is limited by spongos capacity.
+pub fn check_mac_length<F: PRP>(mac_length: usize) -> Result<()> {
+    let max_length = spongos::MacSize::<F>::USIZE;
+    try_or!(
+        MAC_LENGTHS.contains(&mac_length) && mac_length <= max_length,
+        TagMacLengthUnsupported(max_length, mac_length)
+    )
+}
+
 pub struct ContentWrap<'a, F, Link>
 where
     Link: HasLink,
@@ -54,6 +88,10 @@ where
     pub(crate) link: &'a <Link as HasLink>::Rel,
     pub(crate) public_payload: &'a Bytes,
     pub(crate) masked_payload: &'a Bytes,
+    pub(crate) context: Option<&'a Bytes>,
+    pub(crate) app_type: u16,
+    pub(crate) masked_block_size: Option<usize>,
+    pub(crate) mac_length: usize,
     pub(crate) _phantom: core::marker::PhantomData<(F, Link)>,
 }
 
@@ -65,12 +103,23 @@ where
 {
     fn sizeof<'c>(&self, ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
         let store = EmptyLinkStore::<F, <Link as HasLink>::Rel, ()>::default();
-        let mac = Mac(spongos::MacSize::<F>::USIZE);
-        ctx.join(&store, self.link)?
-            .absorb(self.public_payload)?
-            .mask(self.masked_payload)?
-            .commit()?
-            .squeeze(&mac)?;
+        let mac = Mac(self.mac_length);
+        ctx.join(&store, self.link)?.absorb(self.public_payload)?;
+        if let Some(context) = self.context {
+            ctx.absorb(context)?;
+        }
+        if self.app_type != 0 {
+            ctx.absorb(Uint16(self.app_type))?;
+        }
+        if self.mac_length != spongos::MacSize::<F>::USIZE {
+            ctx.absorb(Uint8(self.mac_length as u8))?;
+        }
+        match self.masked_block_size {
+            Some(block_size) => masked_blocks::sizeof(ctx, block_size, self.masked_payload)?,
+            None => ctx.mask(self.masked_payload)?,
+        }
+        .commit()?
+        .squeeze(&mac)?;
         // TODO: Is bot public and masked payloads are ok? Leave public only or masked only?
         Ok(ctx)
     }
@@ -88,12 +137,23 @@ where
         store: &Store,
         ctx: &'c mut wrap::Context<F, OS>,
     ) -> Result<&'c mut wrap::Context<F, OS>> {
-        let mac = Mac(spongos::MacSize::<F>::USIZE);
-        ctx.join(store, self.link)?
-            .absorb(self.public_payload)?
-            .mask(self.masked_payload)?
-            .commit()?
-            .squeeze(&mac)?;
+        let mac = Mac(self.mac_length);
+        ctx.join(store, self.link)?.absorb(self.public_payload)?;
+        if let Some(context) = self.context {
+            ctx.absorb(context)?;
+        }
+        if self.app_type != 0 {
+            ctx.absorb(Uint16(self.app_type))?;
+        }
+        if self.mac_length != spongos::MacSize::<F>::USIZE {
+            ctx.absorb(Uint8(self.mac_length as u8))?;
+        }
+        match self.masked_block_size {
+            Some(block_size) => masked_blocks::wrap(ctx, block_size, self.masked_payload)?,
+            None => ctx.mask(self.masked_payload)?,
+        }
+        .commit()?
+        .squeeze(&mac)?;
         Ok(ctx)
     }
 }
@@ -103,6 +163,11 @@ pub struct ContentUnwrap<F, Link: HasLink> {
     pub(crate) link: <Link as HasLink>::Rel,
     pub(crate) public_payload: Bytes,
     pub(crate) masked_payload: Bytes,
+    pub(crate) context: Option<Bytes>,
+    pub(crate) app_type: Option<Uint16>,
+    pub(crate) mac_length: Option<Uint8>,
+    pub(crate) masked_blocks: bool,
+    pub(crate) max_payload_length: usize,
     pub(crate) _phantom: core::marker::PhantomData<(F, Link)>,
 }
 
@@ -117,9 +182,44 @@ where
             link: <<Link as HasLink>::Rel as Default>::default(),
             public_payload: Bytes::default(),
             masked_payload: Bytes::default(),
+            context: None,
+            app_type: None,
+            mac_length: None,
+            masked_blocks: false,
+            max_payload_length: usize::MAX,
             _phantom: core::marker::PhantomData,
         }
     }
+
+    /// Expect the application context as signalled in the message header.
+    pub fn with_context(mut self, has_context: bool) -> Self {
+        self.context = if has_context { Some(Bytes::default()) } else { None };
+        self
+    }
+
+    /// Expect the application type as signalled in the message header.
+    pub fn with_app_type(mut self, has_app_type: bool) -> Self {
+        self.app_type = if has_app_type { Some(Uint16(0)) } else { None };
+        self
+    }
+
+    /// Expect a non-default MAC length as signalled in the message header.
+    pub fn with_mac_length(mut self, has_mac_length: bool) -> Self {
+        self.mac_length = if has_mac_length { Some(Uint8(0)) } else { None };
+        self
+    }
+
+    /// Expect the masked payload split into blocks, see `masked_blocks`.
+    pub fn with_masked_blocks(mut self, masked_blocks: bool) -> Self {
+        self.masked_blocks = masked_blocks;
+        self
+    }
+
+    /// Reject public and masked payloads declared longer than `max_payload_length` bytes.
+    pub fn with_max_payload_length(mut self, max_payload_length: usize) -> Self {
+        self.max_payload_length = max_payload_length;
+        self
+    }
 }
 
 impl<F, Link, Store> message::ContentUnwrap<F, Store> for ContentUnwrap<F, Link>
@@ -134,12 +234,28 @@ where
         store: &Store,
         ctx: &'c mut unwrap::Context<F, IS>,
     ) -> Result<&'c mut unwrap::Context<F, IS>> {
-        let mac = Mac(spongos::MacSize::<F>::USIZE);
         ctx.join(store, &mut self.link)?
-            .absorb(&mut self.public_payload)?
-            .mask(&mut self.masked_payload)?
-            .commit()?
-            .squeeze(&mac)?;
+            .absorb_bounded_bytes(&mut self.public_payload, self.max_payload_length)?;
+        if let Some(context) = &mut self.context {
+            ctx.absorb(context)?;
+        }
+        if let Some(app_type) = &mut self.app_type {
+            ctx.absorb(app_type)?;
+        }
+        let mac = match &mut self.mac_length {
+            Some(mac_length) => {
+                ctx.absorb(&mut *mac_length)?;
+                check_mac_length::<F>(mac_length.0 as usize)?;
+                Mac(mac_length.0 as usize)
+            }
+            None => Mac(spongos::MacSize::<F>::USIZE),
+        };
+        if self.masked_blocks {
+            masked_blocks::unwrap(ctx, &mut self.masked_payload, self.max_payload_length)?;
+        } else {
+            ctx.mask_bounded_bytes(&mut self.masked_payload, self.max_payload_length)?;
+        }
+        ctx.commit()?.squeeze(&mac)?;
         Ok(ctx)
     }
 }
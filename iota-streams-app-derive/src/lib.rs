@@ -0,0 +1,171 @@
+//! `#[derive(Content)]` implements `ContentSizeof`, `ContentWrap` and `ContentUnwrap` of
+//! `iota_streams_app::message` for a struct whose fields are processed in declaration order.
+//!
+//! ```ignore
+//! #[derive(Content)]
+//! struct Content {
+//!     #[content(absorb)]
+//!     pk: ed25519::PublicKey,
+//!     #[content(mask, commit)]
+//!     payload: Bytes,
+//! }
+//! ```
+//!
+//! Every field takes exactly one of the `absorb`, `mask` and `skip` commands, `commit` commits the
+//! spongos state once the field is processed. The three implementations are generated from the
+//! same list of commands, so sizeof, wrap and unwrap can't get out of step.
+//!
+//! The same fields are wrapped and unwrapped, commands taking different arguments on both sides,
+//! eg. `ed25519` signatures or `join` of the linked message, are not supported: contents using them
+//! keep hand-written implementations.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse_macro_input,
+    parse_quote,
+    Data,
+    DeriveInput,
+    Error,
+    Fields,
+    Ident,
+    Meta,
+    NestedMeta,
+};
+
+/// Commands accepted in `#[content(...)]` field attributes, along with `commit`.
+const COMMANDS: [&str; 3] = ["absorb", "mask", "skip"];
+
+#[proc_macro_derive(Content, attributes(content))]
+pub fn derive_content(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// DDML command applied to a field.
+struct FieldCommand {
+    field: Ident,
+    command: Ident,
+    commit: bool,
+}
+
+fn field_commands(input: &DeriveInput) -> syn::Result<Vec<FieldCommand>> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(Error::new_spanned(&input.ident, "Content requires named fields")),
+        },
+        _ => return Err(Error::new_spanned(&input.ident, "Content can only be derived for structs")),
+    };
+
+    let mut commands = Vec::new();
+    for field in fields {
+        let mut command: Option<Ident> = None;
+        let mut commit = false;
+        for attr in field.attrs.iter().filter(|attr| attr.path.is_ident("content")) {
+            let list = match attr.parse_meta()? {
+                Meta::List(list) => list,
+                meta => return Err(Error::new_spanned(meta, "expected #[content(...)]")),
+            };
+            for nested in list.nested {
+                let path = match nested {
+                    NestedMeta::Meta(Meta::Path(path)) => path,
+                    nested => return Err(Error::new_spanned(nested, "expected a command")),
+                };
+                if path.is_ident("commit") {
+                    commit = true;
+                } else if COMMANDS.iter().any(|name| path.is_ident(name)) {
+                    if command.is_some() {
+                        return Err(Error::new_spanned(path, "a field takes a single command"));
+                    }
+                    command = path.get_ident().cloned();
+                } else {
+                    return Err(Error::new_spanned(path, "expected `absorb`, `mask`, `skip` or `commit`"));
+                }
+            }
+        }
+        match command {
+            Some(command) => commands.push(FieldCommand {
+                field: field.ident.clone().expect("named field"),
+                command,
+                commit,
+            }),
+            None => return Err(Error::new_spanned(field, "missing #[content(absorb | mask | skip)] command")),
+        }
+    }
+    Ok(commands)
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let commands = field_commands(input)?;
+    let commit = |c: &FieldCommand| if c.commit { quote!(ctx.commit()?;) } else { quote!() };
+    let wrap_steps: Vec<_> = commands
+        .iter()
+        .map(|c| {
+            let (field, command, commit) = (&c.field, &c.command, commit(c));
+            quote!(ctx.#command(&self.#field)?; #commit)
+        })
+        .collect();
+    let unwrap_steps: Vec<_> = commands
+        .iter()
+        .map(|c| {
+            let (field, command, commit) = (&c.field, &c.command, commit(c));
+            quote!(ctx.#command(&mut self.#field)?; #commit)
+        })
+        .collect();
+
+    let name = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let mut sizeof_generics = input.generics.clone();
+    sizeof_generics.params.push(parse_quote!(F));
+    let (sizeof_impl, _, sizeof_where) = sizeof_generics.split_for_impl();
+    let mut wrap_generics = input.generics.clone();
+    wrap_generics
+        .params
+        .push(parse_quote!(F: ::iota_streams_core::sponge::prp::PRP));
+    wrap_generics.params.push(parse_quote!(Store));
+    let (wrap_impl, _, wrap_where) = wrap_generics.split_for_impl();
+
+    Ok(quote! {
+        impl #sizeof_impl ::iota_streams_app::message::ContentSizeof<F> for #name #ty_generics #sizeof_where {
+            fn sizeof<'c>(
+                &self,
+                ctx: &'c mut ::iota_streams_ddml::command::sizeof::Context<F>,
+            ) -> ::iota_streams_core::Result<&'c mut ::iota_streams_ddml::command::sizeof::Context<F>> {
+                use ::iota_streams_ddml::command::*;
+                #(#wrap_steps)*
+                Ok(ctx)
+            }
+        }
+
+        impl #wrap_impl ::iota_streams_app::message::ContentWrap<F, Store> for #name #ty_generics #wrap_where {
+            fn wrap<'c, OS: ::iota_streams_ddml::io::OStream>(
+                &self,
+                _store: &Store,
+                ctx: &'c mut ::iota_streams_ddml::command::wrap::Context<F, OS>,
+            ) -> ::iota_streams_core::Result<&'c mut ::iota_streams_ddml::command::wrap::Context<F, OS>> {
+                use ::iota_streams_ddml::command::*;
+                #(#wrap_steps)*
+                Ok(ctx)
+            }
+        }
+
+        impl #wrap_impl ::iota_streams_app::message::ContentUnwrap<F, Store> for #name #ty_generics #wrap_where {
+            fn unwrap<'c, IS: ::iota_streams_ddml::io::IStream>(
+                &mut self,
+                _store: &Store,
+                ctx: &'c mut ::iota_streams_ddml::command::unwrap::Context<F, IS>,
+            ) -> ::iota_streams_core::Result<&'c mut ::iota_streams_ddml::command::unwrap::Context<F, IS>> {
+                use ::iota_streams_ddml::command::*;
+                #(#unwrap_steps)*
+                Ok(ctx)
+            }
+        }
+    })
+}
@@ -17,7 +17,10 @@ use iota_streams_core::{
     Result,
 };
 
-use iota_streams_core_edsig::signature::ed25519;
+use iota_streams_core_edsig::{
+    key_exchange::x25519,
+    signature::ed25519,
+};
 
 use iota_streams_ddml::{
     command::*,
@@ -31,6 +34,9 @@ use crate::message::*;
 pub enum Identifier {
     EdPubKey(ed25519::PublicKeyWrap),
     PskId(PskId),
+    /// X25519 viewing key of a keyload recipient granted read access only, it can't identify a
+    /// publisher.
+    XPubKey(x25519::PublicKeyWrap),
 }
 
 impl Identifier {
@@ -38,6 +44,7 @@ impl Identifier {
         match self {
             Identifier::EdPubKey(id) => id.0.as_bytes().to_vec(),
             Identifier::PskId(id) => id.to_vec(),
+            Identifier::XPubKey(pk) => pk.0.as_bytes().to_vec(),
         }
     }
 
@@ -64,6 +71,12 @@ impl From<ed25519::PublicKey> for Identifier {
     }
 }
 
+impl From<x25519::PublicKey> for Identifier {
+    fn from(pk: x25519::PublicKey) -> Self {
+        Identifier::XPubKey(pk.into())
+    }
+}
+
 impl From<&PskId> for Identifier {
     fn from(pskid: &PskId) -> Self {
         Identifier::PskId(*pskid)
@@ -83,6 +96,11 @@ impl<F: PRP> ContentSizeof<F> for Identifier {
                 ctx.mask(&oneof)?.mask(<&NBytes<psk::PskIdSize>>::from(&pskid))?;
                 Ok(ctx)
             }
+            Identifier::XPubKey(pk) => {
+                let oneof = Uint8(2);
+                ctx.mask(&oneof)?.mask(&pk.0)?;
+                Ok(ctx)
+            }
         }
     }
 }
@@ -104,6 +122,11 @@ impl<F: PRP, Store> ContentWrap<F, Store> for Identifier {
                 ctx.mask(&oneof)?.mask(<&NBytes<psk::PskIdSize>>::from(&pskid))?;
                 Ok(ctx)
             }
+            Identifier::XPubKey(pk) => {
+                let oneof = Uint8(2);
+                ctx.mask(&oneof)?.mask(&pk.0)?;
+                Ok(ctx)
+            }
         }
     }
 }
@@ -140,6 +163,12 @@ impl<F: PRP, Store> ContentUnwrapNew<F, Store> for Identifier {
                 let id = Identifier::PskId(pskid);
                 Ok((id, ctx))
             }
+            2 => {
+                let mut pk = x25519::PublicKey::from([0_u8; x25519::PUBLIC_KEY_LENGTH]);
+                ctx.mask(&mut pk)?;
+                let id = Identifier::XPubKey(x25519::PublicKeyWrap(pk));
+                Ok((id, ctx))
+            }
             _ => err(BadOneof),
         }
     }
@@ -5,6 +5,8 @@ use super::*;
 use iota_streams_core::{
     prelude::Vec,
     sponge::prp::PRP,
+    try_or,
+    Errors::MalformedLinkedMsgAddress,
 };
 use iota_streams_ddml::{
     command::unwrap,
@@ -31,7 +33,7 @@ impl<F> PartialEq for BinaryBody<F> {
 impl<F> fmt::Debug for BinaryBody<F> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // TODO: first 10 bytes of body is average HDF
-        write!(f, "{}", hex::encode(&self.bytes[..10]))
+        write!(f, "{}", hex::encode(&self.bytes[..self.bytes.len().min(10)]))
     }
 }
 
@@ -50,6 +52,24 @@ impl<F> Clone for BinaryBody<F> {
     }
 }
 
+impl<F> BinaryBody<F> {
+    /// Raw message bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..]
+    }
+
+    /// Take raw message bytes without copying.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+impl<F> From<&[u8]> for BinaryBody<F> {
+    fn from(bytes: &[u8]) -> Self {
+        Self::from(bytes.to_vec())
+    }
+}
+
 impl<F> From<Vec<u8>> for BinaryBody<F> {
     fn from(bytes: Vec<u8>) -> Self {
         Self {
@@ -70,7 +90,22 @@ where
             HDF::<Link>::new(self.link().clone()).with_previous_msg_link(Bytes(self.prev_link().to_bytes()));
         let store = EmptyLinkStore::<F, Link, ()>::default();
         header.unwrap(&store, &mut ctx)?;
+        let linked = &header.previous_msg_link.0;
+        try_or!(
+            linked.is_empty() || Link::is_link_bytes(linked),
+            MalformedLinkedMsgAddress(hex::encode(linked))
+        )?;
+        let header_bytes = &self.body.bytes[..self.body.bytes.len() - ctx.stream.len()];
+
+        Ok(PreparsedMessage {
+            header,
+            header_bytes,
+            ctx,
+        })
+    }
 
-        Ok(PreparsedMessage { header, ctx })
+    /// Summarize the message header without unwrapping the content.
+    pub fn peek_header(&self) -> Result<MessageInfo<Link>> {
+        Ok(self.parse_header()?.peek())
     }
 }
@@ -2,7 +2,10 @@ use core::fmt;
 use iota_streams_core::Result;
 
 use iota_streams_core::{
-    sponge::prp::PRP,
+    sponge::{
+        prp::PRP,
+        spongos::Spongos,
+    },
     try_or,
     Errors::*,
 };
@@ -14,6 +17,7 @@ use iota_streams_ddml::{
         typenum::{
             U2,
             U3,
+            U8,
         },
         *,
     },
@@ -24,6 +28,55 @@ use crate::identifier::Identifier;
 
 pub const FLAG_BRANCHING_MASK: u8 = 1;
 
+/// Header bit (in the content type byte) signalling the linked message fingerprint follows the header.
+pub const HDF_LINK_FINGERPRINT_BIT: u8 = 0x04;
+
+/// Header bit (in the content type byte) signalling the empty masked payload is omitted from the content.
+pub const HDF_MASKED_PAYLOAD_OMITTED_BIT: u8 = 0x08;
+
+/// Header bit (in the payload frame count byte) signalling the content carries an application context.
+pub const HDF_CONTENT_CONTEXT_BIT: u8 = 0x40;
+
+/// Content flags bit signalling the content carries an application type.
+pub const HDF_CONTENT_APP_TYPE_BIT: u8 = 0x02;
+
+/// Content flags bit signalling the content carries an optional masked payload.
+pub const HDF_CONTENT_MASKED_PAYLOAD_BIT: u8 = 0x01;
+
+/// Content flags bit signalling the tagged packet content carries a non-default MAC length.
+pub const HDF_CONTENT_MAC_LENGTH_BIT: u8 = 0x04;
+
+/// Content flags bit signalling the masked payload is split into separately authenticated blocks.
+pub const HDF_CONTENT_MASKED_BLOCKS_BIT: u8 = 0x08;
+
+/// Content flags bit signalling the public payload is compressed.
+pub const HDF_CONTENT_COMPRESSED_PAYLOAD_BIT: u8 = 0x10;
+
+/// Header bit (in the payload frame count byte) signalling the content carries application metadata.
+pub const HDF_CONTENT_METADATA_BIT: u8 = 0x80;
+
+/// Content flags bits known to this version, other bits are reserved.
+pub const HDF_CONTENT_FLAGS_MASK: u8 = HDF_CONTENT_MASKED_PAYLOAD_BIT
+    | HDF_CONTENT_APP_TYPE_BIT
+    | HDF_CONTENT_MAC_LENGTH_BIT
+    | HDF_CONTENT_MASKED_BLOCKS_BIT
+    | HDF_CONTENT_COMPRESSED_PAYLOAD_BIT;
+
+pub type LinkFingerprintSize = U8;
+
+/// Short fingerprint of the linked message spongos state. It allows to detect a message joined
+/// to a wrong linked state before unwrapping its content.
+///
+/// The check is advisory, it only gives a distinct error: `HDF_LINK_FINGERPRINT_BIT` is skipped,
+/// not absorbed, and stripping it along with the fingerprint goes undetected by the check.
+pub fn link_fingerprint<F: PRP>(mut linked: Spongos<F>) -> NBytes<LinkFingerprintSize> {
+    linked.absorb(b"link fingerprint");
+    linked.commit();
+    let mut fingerprint = NBytes::<LinkFingerprintSize>::default();
+    linked.squeeze(fingerprint.as_mut());
+    fingerprint
+}
+
 #[derive(Clone)]
 #[allow(clippy::upper_case_acronyms)]
 pub struct HDF<Link> {
@@ -40,6 +93,24 @@ pub struct HDF<Link> {
     pub previous_msg_link: Bytes,
     pub seq_num: Uint64,
     pub sender_id: Identifier,
+    // linked message fingerprint follows the header
+    pub link_fingerprint: bool,
+    // empty masked payload is not encoded in the content
+    pub masked_payload_omitted: bool,
+    // content absorbs an application supplied context
+    pub content_context: bool,
+    // content absorbs application metadata entries
+    pub content_metadata: bool,
+    // content absorbs an application type tag, signalled in the content flags byte
+    pub content_app_type: bool,
+    // content masks an optional application payload, signalled in the content flags byte
+    pub content_masked_payload: bool,
+    // content absorbs a non-default MAC length, signalled in the content flags byte
+    pub content_mac_length: bool,
+    // masked payload is split into blocks, signalled in the content flags byte
+    pub content_masked_blocks: bool,
+    // public payload is compressed, signalled in the content flags byte
+    pub content_compressed_payload: bool,
 }
 
 impl<Link: Default> HDF<Link> {
@@ -55,6 +126,15 @@ impl<Link: Default> HDF<Link> {
             previous_msg_link: Bytes::default(),
             seq_num: Uint64(0),
             sender_id: Identifier::EdPubKey(ed25519::PublicKey::default().into()),
+            link_fingerprint: false,
+            masked_payload_omitted: false,
+            content_context: false,
+            content_metadata: false,
+            content_app_type: false,
+            content_masked_payload: false,
+            content_mac_length: false,
+            content_masked_blocks: false,
+            content_compressed_payload: false,
         }
     }
 
@@ -118,6 +198,137 @@ impl<Link: Default> HDF<Link> {
         &self.previous_msg_link
     }
 
+    /// Attach the fingerprint of the linked message state so that readers joined to a wrong state
+    /// get a distinct error. Messages with the fingerprint can't be read by older versions.
+    pub fn with_link_fingerprint(mut self, link_fingerprint: bool) -> Self {
+        self.link_fingerprint = link_fingerprint;
+        self
+    }
+
+    pub fn has_link_fingerprint(&self) -> bool {
+        self.link_fingerprint
+    }
+
+    /// Signal that the content doesn't encode its masked payload as it's empty.
+    /// Messages with omitted payload can't be read by older versions.
+    pub fn with_masked_payload_omitted(mut self, masked_payload_omitted: bool) -> Self {
+        self.masked_payload_omitted = masked_payload_omitted;
+        self
+    }
+
+    pub fn is_masked_payload_omitted(&self) -> bool {
+        self.masked_payload_omitted
+    }
+
+    /// Signal that the content carries an authenticated application context.
+    /// Messages with context can't be read by older versions.
+    pub fn with_content_context(mut self, content_context: bool) -> Self {
+        self.content_context = content_context;
+        self
+    }
+
+    pub fn has_content_context(&self) -> bool {
+        self.content_context
+    }
+
+    /// Signal that the content carries authenticated application metadata.
+    /// Messages with metadata can't be read by older versions.
+    pub fn with_content_metadata(mut self, content_metadata: bool) -> Self {
+        self.content_metadata = content_metadata;
+        self
+    }
+
+    pub fn has_content_metadata(&self) -> bool {
+        self.content_metadata
+    }
+
+    /// Signal that the content carries an authenticated application type tag.
+    /// Messages with a non-default application type can't be read by older versions.
+    pub fn with_content_app_type(mut self, content_app_type: bool) -> Self {
+        self.content_app_type = content_app_type;
+        self
+    }
+
+    pub fn has_content_app_type(&self) -> bool {
+        self.content_app_type
+    }
+
+    /// Signal that the content carries an optional masked application payload, eg. the payload of
+    /// a subscription. Such messages can't be read by older versions.
+    pub fn with_content_masked_payload(mut self, content_masked_payload: bool) -> Self {
+        self.content_masked_payload = content_masked_payload;
+        self
+    }
+
+    pub fn has_content_masked_payload(&self) -> bool {
+        self.content_masked_payload
+    }
+
+    /// Signal that the tagged packet content carries a non-default MAC length. The length itself is
+    /// absorbed by the content. Such messages can't be read by older versions.
+    pub fn with_content_mac_length(mut self, content_mac_length: bool) -> Self {
+        self.content_mac_length = content_mac_length;
+        self
+    }
+
+    pub fn has_content_mac_length(&self) -> bool {
+        self.content_mac_length
+    }
+
+    /// Signal that the masked payload is split into blocks authenticated separately. Such
+    /// messages can't be read by older versions.
+    pub fn with_content_masked_blocks(mut self, content_masked_blocks: bool) -> Self {
+        self.content_masked_blocks = content_masked_blocks;
+        self
+    }
+
+    pub fn has_content_masked_blocks(&self) -> bool {
+        self.content_masked_blocks
+    }
+
+    /// Signal that the public payload is compressed and prefixed with the codec identifier.
+    /// Such messages can't be read by older versions.
+    pub fn with_content_compressed_payload(mut self, content_compressed_payload: bool) -> Self {
+        self.content_compressed_payload = content_compressed_payload;
+        self
+    }
+
+    pub fn has_content_compressed_payload(&self) -> bool {
+        self.content_compressed_payload
+    }
+
+    /// Content flags byte, absorbed after the payload frame count in `STREAMS_2_VER` headers. The
+    /// flags don't fit the reserved bits of the `STREAMS_1_VER` header, so they are carried in
+    /// their own byte, present only if a flag is set.
+    fn content_flags(&self) -> u8 {
+        let mut flags = 0;
+        if self.content_masked_payload {
+            flags |= HDF_CONTENT_MASKED_PAYLOAD_BIT;
+        }
+        if self.content_app_type {
+            flags |= HDF_CONTENT_APP_TYPE_BIT;
+        }
+        if self.content_mac_length {
+            flags |= HDF_CONTENT_MAC_LENGTH_BIT;
+        }
+        if self.content_masked_blocks {
+            flags |= HDF_CONTENT_MASKED_BLOCKS_BIT;
+        }
+        if self.content_compressed_payload {
+            flags |= HDF_CONTENT_COMPRESSED_PAYLOAD_BIT;
+        }
+        flags
+    }
+
+    /// Version of the header as wrapped, `STREAMS_2_VER` if content flags are set.
+    fn wrapped_version(&self) -> Uint8 {
+        if self.content_flags() != 0 {
+            STREAMS_2_VER
+        } else {
+            STREAMS_1_VER
+        }
+    }
+
     pub fn new_with_fields(
         link: Link,
         previous_msg_link: Bytes,
@@ -139,6 +350,15 @@ impl<Link: Default> HDF<Link> {
             link,
             seq_num: Uint64(seq_num),
             sender_id: *identifier,
+            link_fingerprint: false,
+            masked_payload_omitted: false,
+            content_context: false,
+            content_metadata: false,
+            content_app_type: false,
+            content_masked_payload: false,
+            content_mac_length: false,
+            content_masked_blocks: false,
+            content_compressed_payload: false,
         })
     }
 }
@@ -156,6 +376,15 @@ impl<Link: Default> Default for HDF<Link> {
             link: Link::default(),
             seq_num: Uint64(0),
             sender_id: Identifier::EdPubKey(ed25519::PublicKey::default().into()),
+            link_fingerprint: false,
+            masked_payload_omitted: false,
+            content_context: false,
+            content_metadata: false,
+            content_app_type: false,
+            content_masked_payload: false,
+            content_mac_length: false,
+            content_masked_blocks: false,
+            content_compressed_payload: false,
         }
     }
 }
@@ -185,12 +414,16 @@ where
         let content_type_and_payload_length = NBytes::<U2>::default();
         let payload_frame_count = NBytes::<U3>::default();
         ctx.absorb(self.encoding)?
-            .absorb(self.version)?
+            .absorb(self.wrapped_version())?
             .skip(&content_type_and_payload_length)?
             .absorb(External(Uint8(self.content_type << 4)))?
             .absorb(self.frame_type)?
-            .skip(&payload_frame_count)?
-            .absorb(External(Fallback(&self.link)))?
+            .skip(&payload_frame_count)?;
+        let content_flags = self.content_flags();
+        if content_flags != 0 {
+            ctx.absorb(Uint8(content_flags))?;
+        }
+        ctx.absorb(External(Fallback(&self.link)))?
             .absorb(&self.previous_msg_link)?
             .skip(self.seq_num)?;
 
@@ -214,6 +447,12 @@ where
             let mut nbytes = NBytes::<U2>::default();
             let v = nbytes.as_mut();
             v[0] = (self.content_type << 4) | ((self.payload_length >> 8) as u8 & 0x03);
+            if self.link_fingerprint {
+                v[0] |= HDF_LINK_FINGERPRINT_BIT;
+            }
+            if self.masked_payload_omitted {
+                v[0] |= HDF_MASKED_PAYLOAD_OMITTED_BIT;
+            }
             v[1] = self.payload_length as u8;
             nbytes
         };
@@ -222,18 +461,28 @@ where
             let v = nbytes.as_mut();
             let x = self.payload_frame_count.to_be_bytes();
             v[0] = x[1] & 0x3f;
+            if self.content_context {
+                v[0] |= HDF_CONTENT_CONTEXT_BIT;
+            }
+            if self.content_metadata {
+                v[0] |= HDF_CONTENT_METADATA_BIT;
+            }
             v[1] = x[2];
             v[2] = x[3];
             nbytes
         };
 
         ctx.absorb(self.encoding)?
-            .absorb(self.version)?
+            .absorb(self.wrapped_version())?
             .skip(&content_type_and_payload_length)?
             .absorb(External(Uint8(self.content_type << 4)))?
             .absorb(self.frame_type)?
-            .skip(&payload_frame_count)?
-            .absorb(External(Fallback(&self.link)))?
+            .skip(&payload_frame_count)?;
+        let content_flags = self.content_flags();
+        if content_flags != 0 {
+            ctx.absorb(Uint8(content_flags))?;
+        }
+        ctx.absorb(External(Fallback(&self.link)))?
             .absorb(&self.previous_msg_link)?
             .skip(self.seq_num)?;
 
@@ -259,13 +508,14 @@ where
         ctx.absorb(&mut self.encoding)?
             .absorb(&mut self.version)?
             .guard(
-                self.version == STREAMS_1_VER,
-                InvalidMsgVersion(STREAMS_1_VER.0, self.version.0),
+                self.version == STREAMS_1_VER || self.version == STREAMS_2_VER,
+                InvalidMsgVersion(STREAMS_2_VER.0, self.version.0),
             )?
             .skip(&mut content_type_and_payload_length)?;
         {
             let v = content_type_and_payload_length.as_ref();
-            try_or!(0 == v[0] & 0x0c, InvalidBitReservation)?;
+            self.link_fingerprint = 0 != v[0] & HDF_LINK_FINGERPRINT_BIT;
+            self.masked_payload_omitted = 0 != v[0] & HDF_MASKED_PAYLOAD_OMITTED_BIT;
             self.content_type = v[0] >> 4;
             self.payload_length = (((v[0] & 0x03) as usize) << 8) | (v[1] as usize);
         }
@@ -276,14 +526,28 @@ where
             .skip(&mut payload_frame_count)?;
         {
             let v = payload_frame_count.as_ref();
-            try_or!(0 == v[0] & 0xc0, InvalidBitReservation)?;
+            self.content_context = 0 != v[0] & HDF_CONTENT_CONTEXT_BIT;
+            self.content_metadata = 0 != v[0] & HDF_CONTENT_METADATA_BIT;
             let mut x = [0_u8; 4];
-            x[1] = v[0];
+            x[1] = v[0] & 0x3f;
             x[2] = v[1];
             x[3] = v[2];
             self.payload_frame_count = u32::from_be_bytes(x);
         }
 
+        if self.version == STREAMS_2_VER {
+            let mut content_flags = Uint8(0);
+            ctx.absorb(&mut content_flags)?.guard(
+                content_flags.0 != 0 && 0 == content_flags.0 & !HDF_CONTENT_FLAGS_MASK,
+                InvalidBitReservation,
+            )?;
+            self.content_masked_payload = 0 != content_flags.0 & HDF_CONTENT_MASKED_PAYLOAD_BIT;
+            self.content_app_type = 0 != content_flags.0 & HDF_CONTENT_APP_TYPE_BIT;
+            self.content_mac_length = 0 != content_flags.0 & HDF_CONTENT_MAC_LENGTH_BIT;
+            self.content_masked_blocks = 0 != content_flags.0 & HDF_CONTENT_MASKED_BLOCKS_BIT;
+            self.content_compressed_payload = 0 != content_flags.0 & HDF_CONTENT_COMPRESSED_PAYLOAD_BIT;
+        }
+
         ctx.absorb(External(Fallback(&self.link)))?
             .absorb(&mut self.previous_msg_link)?
             .skip(&mut self.seq_num)?;
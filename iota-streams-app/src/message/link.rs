@@ -32,6 +32,11 @@ pub trait HasLink: Sized + Default + Clone + Eq {
 
     /// Get link from bytes
     fn from_bytes(bytes: &[u8]) -> Self;
+
+    /// Check whether `bytes` encode a link and can be passed to `from_bytes`.
+    fn is_link_bytes(_bytes: &[u8]) -> bool {
+        true
+    }
 }
 
 /// Represents an input state for message identifier generation.
@@ -125,6 +130,15 @@ pub trait LinkGenerator<Link: HasLink>: Default {
     /// Used by users to pseudo-randomly generate a new message link from a cursor
     fn link_from(&self, id: &Identifier, cursor: Cursor<&<Link as HasLink>::Rel>) -> Link;
 
+    /// Network domain links are derived in, empty by default
+    fn domain(&self) -> &[u8] {
+        &[]
+    }
+
+    /// Used by users to separate links of isolated networks, generators without domain separation
+    /// ignore the domain
+    fn set_domain(&mut self, _domain: &[u8]) {}
+
     /// Derive a new link and construct a header with given content type.
     fn uniform_header_from(
         &self,
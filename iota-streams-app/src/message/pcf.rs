@@ -3,7 +3,10 @@ use iota_streams_core::Result;
 use iota_streams_core::{
     sponge::prp::PRP,
     try_or,
-    Errors::ValueOutOfRange,
+    Errors::{
+        InvalidPcfFrameType,
+        ValueOutOfRange,
+    },
 };
 use iota_streams_ddml::{
     command::*,
@@ -59,8 +62,19 @@ impl PCF<()> {
     }
 }
 
+/// Payload frame numbers are 22-bit values encoded as 3 big-endian bytes, the 2 most significant
+/// bits must be zero.
+const PAYLOAD_FRAME_NUM_BOUND: u32 = 0x400000;
+
+fn payload_frame_num_check_u32(n: u32) -> Result<()> {
+    try_or!(
+        n < PAYLOAD_FRAME_NUM_BOUND,
+        ValueOutOfRange(PAYLOAD_FRAME_NUM_BOUND as usize, n as usize)
+    )
+}
+
 fn payload_frame_num_from(n: u32) -> Result<NBytes<U3>> {
-    try_or!(n < 0x400000, ValueOutOfRange(0x400000_usize, n as usize))?;
+    payload_frame_num_check_u32(n)?;
     let v = n.to_be_bytes();
     let g = <GenericArray<u8, U3>>::from_slice(&v[1..]);
     Ok(NBytes::from(*g))
@@ -72,12 +86,10 @@ fn payload_frame_num_to(v: &NBytes<U3>) -> u32 {
     u32::from_be_bytes(u)
 }
 
+/// Check the encoded frame number, it's done on sizeof, wrap and unwrap as the field is public
+/// and can be set without `payload_frame_num_from`.
 fn payload_frame_num_check(v: &NBytes<U3>) -> Result<()> {
-    try_or!(
-        v.as_ref()[0] < 0x40,
-        ValueOutOfRange(0x40_usize, v.as_ref()[0] as usize)
-    )?;
-    Ok(())
+    payload_frame_num_check_u32(payload_frame_num_to(v))
 }
 
 impl<Content> PCF<Content> {
@@ -117,6 +129,7 @@ where
     Content: ContentSizeof<F>,
 {
     fn sizeof<'c>(&self, mut ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
+        payload_frame_num_check(&self.payload_frame_num)?;
         ctx.absorb(&self.frame_type)?.skip(&self.payload_frame_num)?;
         self.content.sizeof(&mut ctx)?;
         Ok(ctx)
@@ -133,6 +146,7 @@ where
         store: &Store,
         mut ctx: &'c mut wrap::Context<F, OS>,
     ) -> Result<&'c mut wrap::Context<F, OS>> {
+        payload_frame_num_check(&self.payload_frame_num)?;
         ctx.absorb(&self.frame_type)?.skip(&self.payload_frame_num)?;
         self.content.wrap(store, &mut ctx)?;
         Ok(ctx)
@@ -149,9 +163,96 @@ where
         store: &Store,
         mut ctx: &'c mut unwrap::Context<F, IS>,
     ) -> Result<&'c mut unwrap::Context<F, IS>> {
-        ctx.absorb(&mut self.frame_type)?.skip(&mut self.payload_frame_num)?;
+        ctx.absorb(&mut self.frame_type)?
+            .guard(
+                self.frame_type == INIT_PCF_ID || self.frame_type == INTER_PCF_ID || self.frame_type == FINAL_PCF_ID,
+                InvalidPcfFrameType(self.frame_type.0),
+            )?
+            .skip(&mut self.payload_frame_num)?;
         payload_frame_num_check(&self.payload_frame_num)?;
         self.content.unwrap(store, &mut ctx)?;
         Ok(ctx)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iota_streams_core::prelude::Vec;
+    use iota_streams_core_keccak::sponge::prp::keccak::KeccakF1600;
+    use iota_streams_ddml::link_store::EmptyLinkStore;
+
+    type F = KeccakF1600;
+    type Store = EmptyLinkStore<F, (), ()>;
+
+    struct NoContent;
+
+    impl ContentSizeof<F> for NoContent {
+        fn sizeof<'c>(&self, ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
+            Ok(ctx)
+        }
+    }
+
+    impl ContentWrap<F, Store> for NoContent {
+        fn wrap<'c, OS: io::OStream>(
+            &self,
+            _store: &Store,
+            ctx: &'c mut wrap::Context<F, OS>,
+        ) -> Result<&'c mut wrap::Context<F, OS>> {
+            Ok(ctx)
+        }
+    }
+
+    impl ContentUnwrap<F, Store> for NoContent {
+        fn unwrap<'c, IS: io::IStream>(
+            &mut self,
+            _store: &Store,
+            ctx: &'c mut unwrap::Context<F, IS>,
+        ) -> Result<&'c mut unwrap::Context<F, IS>> {
+            Ok(ctx)
+        }
+    }
+
+    fn wrap_pcf(pcf: &PCF<NoContent>) -> Result<Vec<u8>> {
+        let store = Store::default();
+        let mut ctx = sizeof::Context::<F>::new();
+        pcf.sizeof(&mut ctx)?;
+        let mut buf = vec![0_u8; ctx.get_size()];
+        let mut ctx = wrap::Context::<F, &mut [u8]>::new(&mut buf[..]);
+        pcf.wrap(&store, &mut ctx)?;
+        Ok(buf)
+    }
+
+    fn unwrap_pcf(buf: &[u8]) -> Result<u32> {
+        let store = Store::default();
+        let mut pcf = PCF::default_with_content(NoContent);
+        let mut ctx = unwrap::Context::<F, &[u8]>::new(buf);
+        pcf.unwrap(&store, &mut ctx)?;
+        Ok(pcf.get_payload_frame_num())
+    }
+
+    #[test]
+    fn payload_frame_num_roundtrip() -> Result<()> {
+        for &n in &[0, 1, 0x010203, PAYLOAD_FRAME_NUM_BOUND - 1] {
+            let pcf = PCF::new(FINAL_PCF_ID, n, NoContent)?;
+            assert_eq!(n, pcf.get_payload_frame_num());
+            assert_eq!(n, unwrap_pcf(&wrap_pcf(&pcf)?)?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn payload_frame_num_out_of_range() -> Result<()> {
+        assert!(PCF::new(FINAL_PCF_ID, PAYLOAD_FRAME_NUM_BOUND, NoContent).is_err());
+        assert!(PCF::new(FINAL_PCF_ID, u32::MAX, NoContent).is_err());
+
+        // Values set directly in the field are rejected on wrap and when read from a message
+        let mut pcf = PCF::new(FINAL_PCF_ID, PAYLOAD_FRAME_NUM_BOUND - 1, NoContent)?;
+        let mut buf = wrap_pcf(&pcf)?;
+        pcf.payload_frame_num = NBytes::from(GenericArray::from([0x40, 0, 0]));
+        assert!(wrap_pcf(&pcf).is_err());
+        buf[1] = 0x40;
+        assert!(unwrap_pcf(&buf).is_err());
+        Ok(())
+    }
+}
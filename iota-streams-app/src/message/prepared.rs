@@ -1,16 +1,30 @@
-use core::cell::Ref;
+use core::cell::{
+    Ref,
+    RefCell,
+};
 use iota_streams_core::Result;
 
 use super::*;
 use iota_streams_core::{
-    sponge::prp::PRP,
+    prelude::{
+        HashMap,
+        Vec,
+    },
+    sponge::{
+        prp::PRP,
+        spongos::Spongos,
+    },
     try_or,
-    Errors::OutputStreamNotFullyConsumed,
+    Errors::{
+        MessageSizeExceeded,
+        OutputStreamNotFullyConsumed,
+    },
 };
 use iota_streams_ddml::{
     command::{
         sizeof,
         wrap,
+        Absorb,
     },
     link_store::LinkStore,
     types::*,
@@ -21,6 +35,9 @@ pub struct PreparedMessage<'a, F, Link: Default, Store: 'a, Content> {
     store: Ref<'a, Store>,
     pub header: HDF<Link>,
     pub content: PCF<Content>,
+    max_size: usize,
+    size_hint: Option<usize>,
+    size_cache: Option<&'a RefCell<HashMap<u8, usize>>>,
     _phantom: core::marker::PhantomData<F>,
 }
 
@@ -35,9 +52,33 @@ impl<'a, F, Link: Default, Store: 'a, Content> PreparedMessage<'a, F, Link, Stor
             store,
             header,
             content,
+            max_size: usize::MAX,
+            size_hint: None,
+            size_cache: None,
             _phantom: core::marker::PhantomData,
         }
     }
+
+    /// Limit the size in bytes of the wrapped message body, no limit is set by default.
+    pub fn with_max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Expected size in bytes of the wrapped message body, eg. the size of the last message with the
+    /// same content. When set, the message is wrapped into a buffer of this size without the sizeof
+    /// pass, a wrong hint costs an extra sizeof and wrap pass.
+    pub fn with_size_hint(mut self, size_hint: Option<usize>) -> Self {
+        self.size_hint = size_hint;
+        self
+    }
+
+    /// Sizes of the last wrapped message bodies by content type. The message is hinted with the
+    /// cached size of its content type, unless a hint is set, and its size is cached once wrapped.
+    pub fn with_size_cache(mut self, size_cache: Option<&'a RefCell<HashMap<u8, usize>>>) -> Self {
+        self.size_cache = size_cache;
+        self
+    }
 }
 
 impl<'a, F, Link, Store, Content> PreparedMessage<'a, F, Link, Store, Content>
@@ -49,28 +90,85 @@ where
     HDF<Link>: ContentWrap<F, Store>,
     Content: ContentWrap<F, Store>,
 {
-    pub fn wrap(&self) -> Result<WrappedMessage<F, Link>> {
-        let buf_size = {
-            let mut ctx = sizeof::Context::<F>::new();
-            self.header.sizeof(&mut ctx)?;
-            self.content.sizeof(&mut ctx)?;
-            ctx.get_size()
-        };
+    /// Fingerprint of the linked message state if requested in the header.
+    fn link_fingerprint(&self) -> Result<Option<NBytes<hdf::LinkFingerprintSize>>> {
+        if self.header.has_link_fingerprint() {
+            let linked = Link::from_bytes(&self.header.previous_msg_link.0);
+            let (spongos, _info) = self.store.lookup(linked.rel())?;
+            Ok(Some(hdf::link_fingerprint(spongos)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn wrapped_size(&self, fingerprint: Option<&NBytes<hdf::LinkFingerprintSize>>) -> Result<usize> {
+        let mut ctx = sizeof::Context::<F>::new();
+        self.header.sizeof(&mut ctx)?;
+        if let Some(fingerprint) = fingerprint {
+            ctx.absorb(fingerprint)?;
+        }
+        self.content.sizeof(&mut ctx)?;
+        Ok(ctx.get_size())
+    }
+
+    /// Size in bytes of the message body, computed without allocating and wrapping the message.
+    pub fn size(&self) -> Result<usize> {
+        let fingerprint = self.link_fingerprint()?;
+        self.wrapped_size(fingerprint.as_ref())
+    }
+
+    /// Wrap the message into a buffer of `buf_size` bytes which must be consumed exactly.
+    fn wrap_sized(
+        &self,
+        fingerprint: Option<&NBytes<hdf::LinkFingerprintSize>>,
+        buf_size: usize,
+    ) -> Result<(Vec<u8>, Spongos<F>)> {
+        try_or!(buf_size <= self.max_size, MessageSizeExceeded(buf_size, self.max_size))?;
 
         let mut buf = vec![0; buf_size];
 
         let spongos = {
             let mut ctx = wrap::Context::new(&mut buf[..]);
             self.header.wrap(&*self.store, &mut ctx)?;
+            if let Some(fingerprint) = fingerprint {
+                ctx.absorb(fingerprint)?;
+            }
             self.content.wrap(&*self.store, &mut ctx)?;
             try_or!(ctx.stream.is_empty(), OutputStreamNotFullyConsumed(ctx.stream.len()))?;
             ctx.spongos
         };
+        Ok((buf, spongos))
+    }
+
+    /// Wrap the message into a buffer of the hinted size if it fits the message exactly, otherwise
+    /// into a buffer sized by the sizeof pass.
+    pub fn wrap(&self) -> Result<WrappedMessage<F, Link>> {
+        let fingerprint = self.link_fingerprint()?;
+        let content_type = self.header.content_type;
+        let size_hint = self
+            .size_hint
+            .or_else(|| self.size_cache.and_then(|cache| cache.borrow().get(&content_type).copied()));
+        let hinted = size_hint.and_then(|size_hint| self.wrap_sized(fingerprint.as_ref(), size_hint).ok());
+        let (buf, spongos) = match hinted {
+            Some(wrapped) => wrapped,
+            None => {
+                let buf_size = self.wrapped_size(fingerprint.as_ref())?;
+                self.wrap_sized(fingerprint.as_ref(), buf_size)?
+            }
+        };
+        if let Some(size_cache) = self.size_cache {
+            size_cache.borrow_mut().insert(content_type, buf.len());
+        }
 
         Ok(WrappedMessage {
             wrapped: WrapState {
                 link: self.header.link.clone(),
                 spongos,
+                linked: if self.header.previous_msg_link.0.is_empty() {
+                    None
+                } else {
+                    Some(Link::from_bytes(&self.header.previous_msg_link.0))
+                },
             },
             message: BinaryMessage {
                 link: self.header.link.clone(),
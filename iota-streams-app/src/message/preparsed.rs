@@ -3,12 +3,31 @@ use iota_streams_core::Result;
 use core::fmt;
 
 use super::*;
-use iota_streams_core::sponge::prp::PRP;
-use iota_streams_ddml::command::unwrap;
+use crate::identifier::Identifier;
+use iota_streams_core::{
+    sponge::prp::PRP,
+    try_or,
+    Errors::LinkedSpongosMismatch,
+};
+use iota_streams_ddml::{
+    command::{
+        unwrap,
+        Absorb,
+    },
+    link_store::{
+        EmptyLinkStore,
+        LinkStore,
+    },
+    types::{
+        AbsorbExternalFallback,
+        NBytes,
+    },
+};
 
 /// Message context preparsed for unwrapping.
 pub struct PreparsedMessage<'a, F, Link: Default> {
     pub header: HDF<Link>,
+    pub(crate) header_bytes: &'a [u8],
     pub(crate) ctx: unwrap::Context<F, &'a [u8]>,
 }
 
@@ -21,6 +40,58 @@ impl<'a, F, Link: Default + Clone> PreparsedMessage<'a, F, Link> {
         self.header.get_content_type()
     }
 
+    /// Parsed message header.
+    pub fn header(&self) -> &HDF<Link> {
+        &self.header
+    }
+
+    /// Exact bytes of the message body that were parsed as the header.
+    ///
+    /// Header fields absorbed into spongos (encoding, version, frame type, linked message address and
+    /// sender identifier, plus content type and message link absorbed as external values) are covered
+    /// by the message signature or MAC: the content is unwrapped starting from the spongos state these
+    /// bytes lead to. Skipped fields (header flags, payload length, payload frame count and sequence
+    /// number) are not authenticated.
+    pub fn header_bytes(&self) -> &'a [u8] {
+        self.header_bytes
+    }
+
+    /// Summarize the message header. Peeking doesn't advance any state, the message can still be unwrapped.
+    pub fn peek(&self) -> MessageInfo<Link>
+    where
+        Link: HasLink,
+    {
+        MessageInfo {
+            link: self.header.link.clone(),
+            linked_msg_address: self.linked_msg_address(),
+            content_type: self.content_type(),
+            seq_num: self.header.seq_num.0,
+            sender_id: self.header.sender_id,
+        }
+    }
+
+    /// Link to the previous message this message is attached to.
+    pub fn linked_msg_address(&self) -> Link
+    where
+        Link: HasLink,
+    {
+        Link::from_bytes(&self.header.previous_msg_link.0)
+    }
+
+    /// Link to the previous message, `None` if the message is not attached to any, eg. an announcement.
+    fn linked_msg(&self) -> Option<Link>
+    where
+        Link: HasLink,
+    {
+        if self.header.previous_msg_link.0.is_empty() {
+            None
+        } else {
+            Some(self.linked_msg_address())
+        }
+    }
+
+    /// Unwrap message content. Any content implementing `ContentUnwrap` can be used, including
+    /// application defined ones; the returned message still needs to be committed to the link store.
     pub fn unwrap<Store, Content>(
         mut self,
         store: &Store,
@@ -29,18 +100,85 @@ impl<'a, F, Link: Default + Clone> PreparsedMessage<'a, F, Link> {
     where
         Content: ContentUnwrap<F, Store>,
         F: PRP,
+        Link: HasLink,
+        Store: LinkStore<F, <Link as HasLink>::Rel>,
     {
+        if self.header.has_link_fingerprint() {
+            let mut fingerprint = NBytes::<hdf::LinkFingerprintSize>::default();
+            self.ctx.absorb(&mut fingerprint)?;
+            // Missing linked state is reported by the content join. The header bit isn't absorbed,
+            // a message stripped of it skips the check and is left to the content MAC or signature
+            let linked = self.linked_msg_address();
+            if let Ok((spongos, _info)) = store.lookup(linked.rel()) {
+                try_or!(
+                    fingerprint == hdf::link_fingerprint(spongos),
+                    LinkedSpongosMismatch(hex::encode(linked.to_bytes()))
+                )?;
+            }
+        }
+
         let mut pcf = pcf::PCF::default_with_content(content);
         pcf.unwrap(store, &mut self.ctx)?;
         // Discard what's left of `self.ctx.stream`
+        let linked = self.linked_msg();
         Ok(UnwrappedMessage {
             link: self.header.link,
             pcf,
             spongos: self.ctx.spongos,
+            linked,
         })
     }
 }
 
+/// Message header summary obtained without unwrapping the message content.
+#[derive(Clone)]
+pub struct MessageInfo<Link> {
+    pub link: Link,
+    pub linked_msg_address: Link,
+    pub content_type: u8,
+    pub seq_num: u64,
+    pub sender_id: Identifier,
+}
+
+/// Message header summary parsed from a raw message body. The message link is not part of the body.
+#[derive(Clone)]
+pub struct HeaderInfo<Link> {
+    pub linked_msg_address: Link,
+    pub content_type: u8,
+    pub seq_num: u64,
+    pub sender_id: Identifier,
+}
+
+impl<Link> From<MessageInfo<Link>> for HeaderInfo<Link> {
+    fn from(info: MessageInfo<Link>) -> Self {
+        Self {
+            linked_msg_address: info.linked_msg_address,
+            content_type: info.content_type,
+            seq_num: info.seq_num,
+            sender_id: info.sender_id,
+        }
+    }
+}
+
+/// Parse the header of a raw message body without a user or a link store, eg. to route messages
+/// by type or publisher. Header fields are not authenticated until the message is unwrapped.
+pub fn peek_header<F, Link>(bytes: &[u8]) -> Result<HeaderInfo<Link>>
+where
+    F: PRP,
+    Link: Clone + Default + AbsorbExternalFallback<F> + HasLink + fmt::Debug,
+{
+    let mut ctx = unwrap::Context::<F, &[u8]>::new(bytes);
+    let mut header = HDF::<Link>::new(Link::default());
+    let store = EmptyLinkStore::<F, Link, ()>::default();
+    header.unwrap(&store, &mut ctx)?;
+    Ok(HeaderInfo {
+        linked_msg_address: Link::from_bytes(&header.previous_msg_link.0),
+        content_type: header.get_content_type(),
+        seq_num: header.seq_num.0,
+        sender_id: header.sender_id,
+    })
+}
+
 impl<'a, F, Link> Clone for PreparsedMessage<'a, F, Link>
 where
     F: Clone,
@@ -49,6 +187,7 @@ where
     fn clone(&self) -> Self {
         Self {
             header: self.header.clone(),
+            header_bytes: self.header_bytes,
             ctx: self.ctx.clone(),
         }
     }
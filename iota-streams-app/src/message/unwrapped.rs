@@ -1,5 +1,9 @@
 use core::cell::RefMut;
-use iota_streams_core::Result;
+use iota_streams_core::{
+    try_or,
+    Errors::MessageContentMismatch,
+    Result,
+};
 
 use super::*;
 use iota_streams_core::sponge::{
@@ -13,6 +17,8 @@ pub struct UnwrappedMessage<F, Link, Content> {
     pub link: Link,
     pub pcf: PCF<Content>,
     pub(crate) spongos: Spongos<F>,
+    /// Link of the message this message is linked to, if any.
+    pub(crate) linked: Option<Link>,
 }
 
 impl<F, Link, Content> UnwrappedMessage<F, Link, Content>
@@ -21,6 +27,9 @@ where
     Link: HasLink,
 {
     /// Save link for the current unwrapped message and associated info into the store.
+    ///
+    /// If the link is already in the store, the message must result in the same spongos state as
+    /// the one processed before, a different message at the same link is rejected.
     pub fn commit<Store>(
         mut self,
         mut store: RefMut<Store>,
@@ -30,7 +39,13 @@ where
         Store: LinkStore<F, <Link as HasLink>::Rel>,
     {
         self.spongos.commit();
+        if let Ok((stored, _info)) = store.lookup(self.link.rel()) {
+            try_or!(stored.to_inner()? == self.spongos.to_inner()?, MessageContentMismatch)?;
+        }
         store.update(self.link.rel(), self.spongos, info)?;
+        if let Some(linked) = &self.linked {
+            store.link_to(self.link.rel(), linked.rel());
+        }
         Ok(self.pcf.content)
     }
 }
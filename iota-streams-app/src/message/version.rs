@@ -16,6 +16,10 @@ use iota_streams_ddml::types::Uint8;
 /// Streams version number.
 pub const STREAMS_1_VER: Uint8 = Uint8(0);
 
+/// Streams version number of messages with a content flags byte in the header, see `HDF`.
+/// Messages using no content flag keep `STREAMS_1_VER`.
+pub const STREAMS_2_VER: Uint8 = Uint8(1);
+
 /// Encoding Constants
 pub const UTF8: Uint8 = Uint8(0);
 
@@ -15,6 +15,8 @@ use iota_streams_ddml::link_store::LinkStore;
 pub struct WrapState<F, Link> {
     pub link: Link,
     pub(crate) spongos: Spongos<F>,
+    /// Link of the message this message is linked to, if any.
+    pub(crate) linked: Option<Link>,
 }
 
 impl<F: PRP, Link: HasLink> WrapState<F, Link> {
@@ -30,6 +32,9 @@ impl<F: PRP, Link: HasLink> WrapState<F, Link> {
     {
         self.spongos.commit();
         store.update(self.link.rel(), self.spongos, info)?;
+        if let Some(linked) = &self.linked {
+            store.link_to(self.link.rel(), linked.rel());
+        }
         Ok(self.link)
     }
 }
@@ -0,0 +1,292 @@
+use super::*;
+
+use iota_streams_core::prelude::Box;
+
+/// Object safe counterpart of `Transport`, any cloneable transport implements it. Transports of
+/// different types can be stored as `BoxedTransport` and selected at run-time, eg. from
+/// configuration.
+///
+/// Send and receive options of the boxed transport are not accessible, they have to be set before
+/// the transport is boxed.
+#[cfg(not(feature = "async"))]
+pub trait DynTransport<Link, Msg, Details> {
+    /// Send a message with default options.
+    fn send_message_dyn(&mut self, msg: &Msg) -> Result<()>;
+
+    /// Receive messages with default options.
+    fn recv_messages_dyn(&mut self, link: &Link) -> Result<Vec<Msg>>;
+
+    /// Receive a message with default options.
+    fn recv_message_dyn(&mut self, link: &Link) -> Result<Msg>;
+
+    /// Delete the messages at the link, if supported by the transport.
+    fn delete_message_dyn(&mut self, link: &Link) -> Result<()>;
+
+    /// Wait until the message at the link is confirmed.
+    fn await_confirmation_dyn(&mut self, link: &Link) -> Result<()>;
+
+    /// List links of the messages stored for the channel, if supported by the transport.
+    fn list_links_dyn(&mut self, appinst: &Link) -> Result<Vec<Link>>;
+
+    /// Get details of the message at the link.
+    fn get_link_details_dyn(&mut self, link: &Link) -> Result<Details>;
+
+    /// Clone the transport into a new box.
+    fn clone_boxed(&self) -> BoxedTransport<Link, Msg, Details>;
+}
+
+/// Object safe counterpart of `Transport`, any cloneable transport implements it. Transports of
+/// different types can be stored as `BoxedTransport` and selected at run-time, eg. from
+/// configuration.
+///
+/// Send and receive options of the boxed transport are not accessible, they have to be set before
+/// the transport is boxed.
+#[cfg(feature = "async")]
+#[async_trait(?Send)]
+pub trait DynTransport<Link, Msg, Details> {
+    /// Send a message with default options.
+    async fn send_message_dyn(&mut self, msg: &Msg) -> Result<()>;
+
+    /// Receive messages with default options.
+    async fn recv_messages_dyn(&mut self, link: &Link) -> Result<Vec<Msg>>;
+
+    /// Receive a message with default options.
+    async fn recv_message_dyn(&mut self, link: &Link) -> Result<Msg>;
+
+    /// Delete the messages at the link, if supported by the transport.
+    async fn delete_message_dyn(&mut self, link: &Link) -> Result<()>;
+
+    /// Wait until the message at the link is confirmed.
+    async fn await_confirmation_dyn(&mut self, link: &Link) -> Result<()>;
+
+    /// List links of the messages stored for the channel, if supported by the transport.
+    async fn list_links_dyn(&mut self, appinst: &Link) -> Result<Vec<Link>>;
+
+    /// Get details of the message at the link.
+    async fn get_link_details_dyn(&mut self, link: &Link) -> Result<Details>;
+
+    /// Clone the transport into a new box.
+    fn clone_boxed(&self) -> BoxedTransport<Link, Msg, Details>;
+}
+
+/// Transport chosen at run-time.
+pub type BoxedTransport<Link, Msg, Details = ()> = Box<dyn DynTransport<Link, Msg, Details>>;
+
+/// Box transport, its options are kept as they are at the moment.
+pub fn boxed<T, Link, Msg, Details>(transport: T) -> BoxedTransport<Link, Msg, Details>
+where
+    T: DynTransport<Link, Msg, Details> + 'static,
+{
+    Box::new(transport)
+}
+
+#[cfg(not(feature = "async"))]
+impl<T, Link, Msg> DynTransport<Link, Msg, <T as TransportDetails<Link>>::Details> for T
+where
+    T: Transport<Link, Msg> + Clone + 'static,
+    Link: Debug + Display,
+{
+    fn send_message_dyn(&mut self, msg: &Msg) -> Result<()> {
+        self.send_message(msg)
+    }
+
+    fn recv_messages_dyn(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        self.recv_messages(link)
+    }
+
+    fn recv_message_dyn(&mut self, link: &Link) -> Result<Msg> {
+        self.recv_message(link)
+    }
+
+    fn delete_message_dyn(&mut self, link: &Link) -> Result<()> {
+        self.delete_message(link)
+    }
+
+    fn await_confirmation_dyn(&mut self, link: &Link) -> Result<()> {
+        self.await_confirmation(link)
+    }
+
+    fn list_links_dyn(&mut self, appinst: &Link) -> Result<Vec<Link>> {
+        self.list_links(appinst)
+    }
+
+    fn get_link_details_dyn(&mut self, link: &Link) -> Result<<T as TransportDetails<Link>>::Details> {
+        self.get_link_details(link)
+    }
+
+    fn clone_boxed(&self) -> BoxedTransport<Link, Msg, <T as TransportDetails<Link>>::Details> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait(?Send)]
+impl<T, Link, Msg> DynTransport<Link, Msg, <T as TransportDetails<Link>>::Details> for T
+where
+    T: Transport<Link, Msg> + Clone + 'static,
+    Link: Send + Sync,
+    Msg: Send + Sync,
+{
+    async fn send_message_dyn(&mut self, msg: &Msg) -> Result<()> {
+        self.send_message(msg).await
+    }
+
+    async fn recv_messages_dyn(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        self.recv_messages(link).await
+    }
+
+    async fn recv_message_dyn(&mut self, link: &Link) -> Result<Msg> {
+        self.recv_message(link).await
+    }
+
+    async fn delete_message_dyn(&mut self, link: &Link) -> Result<()> {
+        self.delete_message(link).await
+    }
+
+    async fn await_confirmation_dyn(&mut self, link: &Link) -> Result<()> {
+        self.await_confirmation(link).await
+    }
+
+    async fn list_links_dyn(&mut self, appinst: &Link) -> Result<Vec<Link>> {
+        self.list_links(appinst).await
+    }
+
+    async fn get_link_details_dyn(&mut self, link: &Link) -> Result<<T as TransportDetails<Link>>::Details> {
+        self.get_link_details(link).await
+    }
+
+    fn clone_boxed(&self) -> BoxedTransport<Link, Msg, <T as TransportDetails<Link>>::Details> {
+        Box::new(self.clone())
+    }
+}
+
+impl<Link, Msg, Details> Clone for BoxedTransport<Link, Msg, Details> {
+    fn clone(&self) -> Self {
+        self.clone_boxed()
+    }
+}
+
+impl<Link, Msg, Details> TransportOptions for BoxedTransport<Link, Msg, Details> {
+    type SendOptions = ();
+    fn get_send_options(&self) {}
+    fn set_send_options(&mut self, _opt: ()) {}
+
+    type RecvOptions = ();
+    fn get_recv_options(&self) {}
+    fn set_recv_options(&mut self, _opt: ()) {}
+}
+
+#[cfg(not(feature = "async"))]
+impl<Link, Msg, Details> TransportDetails<Link> for BoxedTransport<Link, Msg, Details> {
+    type Details = Details;
+    fn get_link_details(&mut self, link: &Link) -> Result<Details> {
+        (**self).get_link_details_dyn(link)
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl<Link: Debug + Display, Msg, Details> Transport<Link, Msg> for BoxedTransport<Link, Msg, Details> {
+    fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        (**self).send_message_dyn(msg)
+    }
+
+    fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        (**self).recv_messages_dyn(link)
+    }
+
+    fn recv_message(&mut self, link: &Link) -> Result<Msg> {
+        (**self).recv_message_dyn(link)
+    }
+
+    fn delete_message(&mut self, link: &Link) -> Result<()> {
+        (**self).delete_message_dyn(link)
+    }
+
+    fn await_confirmation(&mut self, link: &Link) -> Result<()> {
+        (**self).await_confirmation_dyn(link)
+    }
+
+    fn list_links(&mut self, appinst: &Link) -> Result<Vec<Link>> {
+        (**self).list_links_dyn(appinst)
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait(?Send)]
+impl<Link, Msg, Details> TransportDetails<Link> for BoxedTransport<Link, Msg, Details>
+where
+    Link: Send + Sync,
+    Msg: Send + Sync,
+{
+    type Details = Details;
+    async fn get_link_details(&mut self, link: &Link) -> Result<Details> {
+        (**self).get_link_details_dyn(link).await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait(?Send)]
+impl<Link, Msg, Details> Transport<Link, Msg> for BoxedTransport<Link, Msg, Details>
+where
+    Link: Send + Sync,
+    Msg: Send + Sync,
+{
+    async fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        (**self).send_message_dyn(msg).await
+    }
+
+    async fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        (**self).recv_messages_dyn(link).await
+    }
+
+    async fn recv_message(&mut self, link: &Link) -> Result<Msg> {
+        (**self).recv_message_dyn(link).await
+    }
+
+    async fn delete_message(&mut self, link: &Link) -> Result<()> {
+        (**self).delete_message_dyn(link).await
+    }
+
+    async fn await_confirmation(&mut self, link: &Link) -> Result<()> {
+        (**self).await_confirmation_dyn(link).await
+    }
+
+    async fn list_links(&mut self, appinst: &Link) -> Result<Vec<Link>> {
+        (**self).list_links_dyn(appinst).await
+    }
+}
+
+#[cfg(all(test, not(feature = "async")))]
+mod tests {
+    use super::*;
+    use crate::message::LinkedMessage;
+
+    #[derive(Clone)]
+    struct TestMessage(u32);
+
+    impl LinkedMessage<u32> for TestMessage {
+        fn link(&self) -> &u32 {
+            &self.0
+        }
+        fn prev_link(&self) -> &u32 {
+            &self.0
+        }
+    }
+
+    type Bucket = BucketTransport<u32, TestMessage>;
+
+    #[test]
+    fn transports_are_selected_at_runtime() {
+        let shared = new_shared_transport(Bucket::new());
+        let transports: Vec<BoxedTransport<u32, TestMessage>> =
+            vec![boxed(Bucket::new()), boxed(shared.clone()), boxed(Retry::new(shared))];
+
+        for mut tsp in transports {
+            tsp.send_message(&TestMessage(1)).unwrap();
+            let mut copy = tsp.clone();
+            assert_eq!(copy.recv_message(&1).unwrap().0, 1);
+            tsp.delete_message(&1).unwrap();
+            tsp.get_link_details(&1).unwrap();
+        }
+    }
+}
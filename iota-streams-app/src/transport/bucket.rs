@@ -78,6 +78,19 @@ where
             err!(MessageLinkNotFound(link.to_string()))
         }
     }
+
+    fn delete_message(&mut self, link: &Link) -> Result<()> {
+        if self.bucket.remove(link).is_some() {
+            Ok(())
+        } else {
+            err!(MessageLinkNotFound(link.to_string()))
+        }
+    }
+
+    /// Links of all the messages in the bucket, messages of all channels are listed.
+    fn list_links(&mut self, _appinst: &Link) -> Result<Vec<Link>> {
+        Ok(self.bucket.keys().cloned().collect())
+    }
 }
 
 #[cfg(feature = "async")]
@@ -114,6 +127,19 @@ where
             err!(MessageLinkNotFound(link.to_string()))?
         }
     }
+
+    async fn delete_message(&mut self, link: &Link) -> Result<()> {
+        if self.bucket.remove(link).is_some() {
+            Ok(())
+        } else {
+            err!(MessageLinkNotFound(link.to_string()))
+        }
+    }
+
+    /// Links of all the messages in the bucket, messages of all channels are listed.
+    async fn list_links(&mut self, _appinst: &Link) -> Result<Vec<Link>> {
+        Ok(self.bucket.keys().cloned().collect())
+    }
 }
 
 #[cfg(feature = "async")]
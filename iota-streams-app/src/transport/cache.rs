@@ -0,0 +1,384 @@
+use super::*;
+use crate::message::LinkedMessage;
+use core::{
+    hash,
+    time::Duration,
+};
+
+use iota_streams_core::prelude::HashMap;
+
+/// Default maximum number of links whose messages are cached.
+pub const DEFAULT_CACHE_CAPACITY: usize = 256;
+
+#[cfg(feature = "std")]
+fn default_now() -> Duration {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+#[cfg(not(feature = "std"))]
+fn default_now() -> Duration {
+    Duration::from_secs(0)
+}
+
+/// Number of reads served from the cache and from the inner transport.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct CacheStats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+#[derive(Clone)]
+struct Entry<Msg> {
+    msgs: Vec<Msg>,
+    fetched_at: Duration,
+    last_used: u64,
+}
+
+/// Transport wrapper caching received messages.
+///
+/// Messages received from the inner transport are kept by link and served from the cache on
+/// subsequent reads, eg. the announcement read on each keyload. Links which are not found are not
+/// cached as the messages may be published later. Sending or deleting a message invalidates the
+/// cached messages at its link. When the cache is full the least recently used link is evicted,
+/// entries older than the TTL, if set, are fetched again.
+///
+/// Note: entries expire based on the `now` function (system time by default with `std`, without
+/// `std` a clock has to be provided with `with_clock` for the TTL to have any effect).
+#[derive(Clone)]
+pub struct Cached<T, Link, Msg> {
+    transport: T,
+    entries: HashMap<Link, Entry<Msg>>,
+    capacity: usize,
+    ttl: Option<Duration>,
+    now: fn() -> Duration,
+    uses: u64,
+    stats: CacheStats,
+}
+
+impl<T, Link, Msg> Cached<T, Link, Msg>
+where
+    Link: Eq + hash::Hash + Clone,
+    Msg: Clone,
+{
+    /// Wrap transport with a cache of default capacity, entries never expire.
+    pub fn new(transport: T) -> Self {
+        Self {
+            transport,
+            entries: HashMap::new(),
+            capacity: DEFAULT_CACHE_CAPACITY,
+            ttl: None,
+            now: default_now,
+            uses: 0,
+            stats: CacheStats::default(),
+        }
+    }
+
+    /// Set the maximum number of links whose messages are cached, 0 disables caching.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Set the time after which cached messages are fetched again, `None` keeps them until evicted.
+    pub fn with_ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Set the function returning the current time entries expire against.
+    pub fn with_clock(mut self, now: fn() -> Duration) -> Self {
+        self.now = now;
+        self
+    }
+
+    /// Number of reads served from the cache and from the inner transport so far.
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    /// Drop all the cached messages, statistics are kept.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Get reference to the wrapped transport.
+    pub fn inner(&self) -> &T {
+        &self.transport
+    }
+
+    /// Get mutable reference to the wrapped transport, messages it sends are not invalidated in
+    /// the cache.
+    pub fn inner_mut(&mut self) -> &mut T {
+        &mut self.transport
+    }
+
+    /// Unwrap the inner transport.
+    pub fn into_inner(self) -> T {
+        self.transport
+    }
+
+    /// Cached messages at the link, if present and not expired. With `single` only a single cached
+    /// message is served.
+    fn lookup(&mut self, link: &Link, single: bool) -> Option<Vec<Msg>> {
+        let now = (self.now)();
+        let ttl = self.ttl;
+        if let Some(entry) = self.entries.get(link) {
+            if ttl.map_or(false, |ttl| now.saturating_sub(entry.fetched_at) >= ttl) {
+                self.entries.remove(link);
+            }
+        }
+        match self.entries.get_mut(link) {
+            Some(entry) if !single || entry.msgs.len() == 1 => {
+                self.uses += 1;
+                self.stats.hits += 1;
+                entry.last_used = self.uses;
+                Some(entry.msgs.clone())
+            }
+            _ => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Cache messages received at the link, evicting the least recently used link if full.
+    fn store(&mut self, link: &Link, msgs: &[Msg]) {
+        if self.capacity == 0 || msgs.is_empty() {
+            return;
+        }
+        if !self.entries.contains_key(link) && self.entries.len() >= self.capacity {
+            let lru = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(link, _)| link.clone());
+            if let Some(lru) = lru {
+                self.entries.remove(&lru);
+            }
+        }
+        self.uses += 1;
+        let entry = Entry {
+            msgs: msgs.to_vec(),
+            fetched_at: (self.now)(),
+            last_used: self.uses,
+        };
+        self.entries.insert(link.clone(), entry);
+    }
+}
+
+impl<T: TransportOptions, Link, Msg> TransportOptions for Cached<T, Link, Msg> {
+    type SendOptions = <T as TransportOptions>::SendOptions;
+    fn get_send_options(&self) -> Self::SendOptions {
+        self.transport.get_send_options()
+    }
+    fn set_send_options(&mut self, opt: Self::SendOptions) {
+        self.transport.set_send_options(opt)
+    }
+
+    type RecvOptions = <T as TransportOptions>::RecvOptions;
+    fn get_recv_options(&self) -> Self::RecvOptions {
+        self.transport.get_recv_options()
+    }
+    fn set_recv_options(&mut self, opt: Self::RecvOptions) {
+        self.transport.set_recv_options(opt)
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl<T: TransportDetails<Link>, Link, Msg> TransportDetails<Link> for Cached<T, Link, Msg> {
+    type Details = <T as TransportDetails<Link>>::Details;
+    fn get_link_details(&mut self, link: &Link) -> Result<Self::Details> {
+        self.transport.get_link_details(link)
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl<Link, Msg, T: Transport<Link, Msg>> Transport<Link, Msg> for Cached<T, Link, Msg>
+where
+    Link: Debug + Display + Eq + hash::Hash + Clone,
+    Msg: LinkedMessage<Link> + Clone,
+{
+    /// Send a message, cached messages at its link are invalidated.
+    fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        self.entries.remove(msg.link());
+        self.transport.send_message(msg)
+    }
+
+    /// Receive messages from the cache or the inner transport.
+    fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        match self.lookup(link, false) {
+            Some(msgs) => Ok(msgs),
+            None => {
+                let msgs = self.transport.recv_messages(link)?;
+                self.store(link, &msgs);
+                Ok(msgs)
+            }
+        }
+    }
+
+    /// Receive a message from the cache or the inner transport.
+    fn recv_message(&mut self, link: &Link) -> Result<Msg> {
+        match self.lookup(link, true).and_then(|mut msgs| msgs.pop()) {
+            Some(msg) => Ok(msg),
+            None => {
+                let msg = self.transport.recv_message(link)?;
+                self.store(link, core::slice::from_ref(&msg));
+                Ok(msg)
+            }
+        }
+    }
+
+    /// Delete messages, cached messages at the link are invalidated.
+    fn delete_message(&mut self, link: &Link) -> Result<()> {
+        self.entries.remove(link);
+        self.transport.delete_message(link)
+    }
+
+    /// Wait until the message at the link is confirmed by the inner transport.
+    fn await_confirmation(&mut self, link: &Link) -> Result<()> {
+        self.transport.await_confirmation(link)
+    }
+
+    /// List links of the messages stored by the inner transport.
+    fn list_links(&mut self, appinst: &Link) -> Result<Vec<Link>> {
+        self.transport.list_links(appinst)
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait(?Send)]
+impl<T: TransportDetails<Link>, Link, Msg> TransportDetails<Link> for Cached<T, Link, Msg>
+where
+    Link: Send + Sync,
+{
+    type Details = <T as TransportDetails<Link>>::Details;
+    async fn get_link_details(&mut self, link: &Link) -> Result<Self::Details> {
+        self.transport.get_link_details(link).await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait(?Send)]
+impl<Link, Msg, T: Transport<Link, Msg>> Transport<Link, Msg> for Cached<T, Link, Msg>
+where
+    Link: Eq + hash::Hash + Clone + Send + Sync,
+    Msg: LinkedMessage<Link> + Clone + Send + Sync,
+{
+    /// Send a message, cached messages at its link are invalidated.
+    async fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        self.entries.remove(msg.link());
+        self.transport.send_message(msg).await
+    }
+
+    /// Receive messages from the cache or the inner transport.
+    async fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        match self.lookup(link, false) {
+            Some(msgs) => Ok(msgs),
+            None => {
+                let msgs = self.transport.recv_messages(link).await?;
+                self.store(link, &msgs);
+                Ok(msgs)
+            }
+        }
+    }
+
+    /// Receive a message from the cache or the inner transport.
+    async fn recv_message(&mut self, link: &Link) -> Result<Msg> {
+        match self.lookup(link, true).and_then(|mut msgs| msgs.pop()) {
+            Some(msg) => Ok(msg),
+            None => {
+                let msg = self.transport.recv_message(link).await?;
+                self.store(link, core::slice::from_ref(&msg));
+                Ok(msg)
+            }
+        }
+    }
+
+    /// Delete messages, cached messages at the link are invalidated.
+    async fn delete_message(&mut self, link: &Link) -> Result<()> {
+        self.entries.remove(link);
+        self.transport.delete_message(link).await
+    }
+
+    /// Wait until the message at the link is confirmed by the inner transport.
+    async fn await_confirmation(&mut self, link: &Link) -> Result<()> {
+        self.transport.await_confirmation(link).await
+    }
+
+    /// List links of the messages stored by the inner transport.
+    async fn list_links(&mut self, appinst: &Link) -> Result<Vec<Link>> {
+        self.transport.list_links(appinst).await
+    }
+}
+
+#[cfg(all(test, not(feature = "async")))]
+mod tests {
+    use super::*;
+    use core::sync::atomic::{
+        AtomicU64,
+        Ordering,
+    };
+
+    #[derive(Clone)]
+    struct TestMessage(u32, u8);
+
+    impl LinkedMessage<u32> for TestMessage {
+        fn link(&self) -> &u32 {
+            &self.0
+        }
+        fn prev_link(&self) -> &u32 {
+            &self.0
+        }
+    }
+
+    type Bucket = BucketTransport<u32, TestMessage>;
+
+    static NOW: AtomicU64 = AtomicU64::new(0);
+
+    fn test_now() -> Duration {
+        Duration::from_secs(NOW.load(Ordering::SeqCst))
+    }
+
+    #[test]
+    fn cached_reads_hit_and_sends_invalidate() {
+        let mut tsp = Cached::new(Bucket::new());
+        tsp.send_message(&TestMessage(1, 0)).unwrap();
+        assert!(tsp.recv_message(&2).is_err());
+        assert_eq!(tsp.recv_message(&1).unwrap().1, 0);
+        assert_eq!(tsp.recv_message(&1).unwrap().1, 0);
+        assert_eq!(tsp.stats(), CacheStats { hits: 1, misses: 2 });
+
+        // Messages sent around the cache are not seen until a send through it invalidates the link
+        tsp.inner_mut().send_message(&TestMessage(1, 1)).unwrap();
+        assert_eq!(tsp.recv_message(&1).unwrap().1, 0);
+        tsp.send_message(&TestMessage(1, 2)).unwrap();
+        assert_eq!(tsp.recv_messages(&1).unwrap().len(), 3);
+        tsp.delete_message(&1).unwrap();
+        assert!(tsp.recv_message(&1).is_err());
+    }
+
+    #[test]
+    fn cache_evicts_least_recently_used_and_expired() {
+        let mut tsp = Cached::new(Bucket::new())
+            .with_capacity(2)
+            .with_ttl(Some(Duration::from_secs(10)))
+            .with_clock(test_now);
+        for link in 1..=3 {
+            tsp.send_message(&TestMessage(link, 0)).unwrap();
+        }
+        tsp.recv_message(&1).unwrap();
+        tsp.recv_message(&2).unwrap();
+        tsp.recv_message(&1).unwrap();
+        tsp.recv_message(&3).unwrap();
+        assert_eq!(tsp.stats(), CacheStats { hits: 1, misses: 3 });
+        tsp.recv_message(&1).unwrap();
+        tsp.recv_message(&2).unwrap();
+        assert_eq!(tsp.stats(), CacheStats { hits: 2, misses: 4 });
+
+        NOW.store(10, Ordering::SeqCst);
+        tsp.recv_message(&2).unwrap();
+        assert_eq!(tsp.stats(), CacheStats { hits: 2, misses: 5 });
+    }
+}
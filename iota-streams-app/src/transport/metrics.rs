@@ -0,0 +1,425 @@
+use super::*;
+use crate::message::BinaryMessage;
+use core::{
+    sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    },
+    time::Duration,
+};
+
+use iota_streams_core::prelude::Arc;
+
+/// Upper bounds in milliseconds of the latency histogram buckets, slower calls are counted in an
+/// extra last bucket.
+pub const LATENCY_BUCKETS_MS: [u64; 8] = [1, 5, 10, 50, 100, 500, 1000, 5000];
+
+/// Number of latency histogram buckets, including the bucket of calls slower than all bounds.
+pub const LATENCY_BUCKETS: usize = LATENCY_BUCKETS_MS.len() + 1;
+
+#[cfg(feature = "std")]
+fn default_now() -> Duration {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+#[cfg(not(feature = "std"))]
+fn default_now() -> Duration {
+    Duration::from_secs(0)
+}
+
+/// Size in bytes of a message, accounted as transferred by `Metered`.
+pub trait MessageSize {
+    fn message_size(&self) -> usize;
+}
+
+impl<F, AbsLink> MessageSize for BinaryMessage<F, AbsLink> {
+    fn message_size(&self) -> usize {
+        self.body.bytes.len()
+    }
+}
+
+#[cfg(feature = "tangle")]
+impl<F> MessageSize for tangle::TangleMessage<F> {
+    fn message_size(&self) -> usize {
+        self.binary.message_size()
+    }
+}
+
+/// Distribution of call latencies.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct LatencyHistogram {
+    /// Number of calls per bucket, `counts[i]` calls took less than `LATENCY_BUCKETS_MS[i]`
+    /// milliseconds and at least the bound of the previous bucket.
+    pub counts: [usize; LATENCY_BUCKETS],
+    /// Total latency of the calls.
+    pub total: Duration,
+}
+
+/// Snapshot of the metrics of a `Metered` transport.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct TransportMetrics {
+    /// Number of `send_message` calls.
+    pub sends: usize,
+    /// Number of failed `send_message` calls.
+    pub send_errors: usize,
+    /// Size in bytes of the messages sent successfully.
+    pub bytes_sent: usize,
+    /// Number of `recv_message` and `recv_messages` calls.
+    pub receives: usize,
+    /// Number of failed `recv_message` and `recv_messages` calls, including messages not found.
+    pub receive_errors: usize,
+    /// Size in bytes of the messages received.
+    pub bytes_received: usize,
+    /// Latency of send calls, if latency tracking is enabled.
+    pub send_latency: Option<LatencyHistogram>,
+    /// Latency of receive calls, if latency tracking is enabled.
+    pub receive_latency: Option<LatencyHistogram>,
+}
+
+#[derive(Default)]
+struct Histogram {
+    counts: [AtomicUsize; LATENCY_BUCKETS],
+    total_micros: AtomicUsize,
+}
+
+impl Histogram {
+    fn record(&self, latency: Duration) {
+        let ms = latency.as_millis();
+        let bucket = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|bound| ms < *bound as u128)
+            .unwrap_or(LATENCY_BUCKETS_MS.len());
+        self.counts[bucket].fetch_add(1, Ordering::Relaxed);
+        self.total_micros
+            .fetch_add(latency.as_micros() as usize, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> LatencyHistogram {
+        let mut counts = [0; LATENCY_BUCKETS];
+        for (count, counter) in counts.iter_mut().zip(self.counts.iter()) {
+            *count = counter.load(Ordering::Relaxed);
+        }
+        LatencyHistogram {
+            counts,
+            total: Duration::from_micros(self.total_micros.load(Ordering::Relaxed) as u64),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Counters {
+    sends: AtomicUsize,
+    send_errors: AtomicUsize,
+    bytes_sent: AtomicUsize,
+    receives: AtomicUsize,
+    receive_errors: AtomicUsize,
+    bytes_received: AtomicUsize,
+    send_latency: Histogram,
+    receive_latency: Histogram,
+}
+
+/// Transport wrapper recording the number of messages sent and received, the bytes transferred,
+/// the number of errors and, optionally, the latency of the calls of the inner transport.
+///
+/// Counters are atomics updated with relaxed ordering, they are shared by the clones of the
+/// wrapper so that metrics can be read while the transport is owned by a user. Latency tracking
+/// reads the clock twice per call, it is disabled by default and enabled with `with_latency`.
+///
+/// Note: latency is measured with the `now` function (system time by default with `std`, without
+/// `std` a clock has to be provided with `with_clock` for latency to be measured).
+#[derive(Clone)]
+pub struct Metered<T> {
+    transport: T,
+    counters: Arc<Counters>,
+    latency: bool,
+    now: fn() -> Duration,
+}
+
+impl<T> Metered<T> {
+    /// Wrap transport with zeroed counters, latency is not tracked.
+    pub fn new(transport: T) -> Self {
+        Self {
+            transport,
+            counters: Arc::new(Counters::default()),
+            latency: false,
+            now: default_now,
+        }
+    }
+
+    /// Enable or disable tracking of call latencies.
+    pub fn with_latency(mut self, latency: bool) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Set the function returning the current time latencies are measured with.
+    pub fn with_clock(mut self, now: fn() -> Duration) -> Self {
+        self.now = now;
+        self
+    }
+
+    /// Snapshot of the metrics recorded so far.
+    pub fn metrics(&self) -> TransportMetrics {
+        let counters = &self.counters;
+        TransportMetrics {
+            sends: counters.sends.load(Ordering::Relaxed),
+            send_errors: counters.send_errors.load(Ordering::Relaxed),
+            bytes_sent: counters.bytes_sent.load(Ordering::Relaxed),
+            receives: counters.receives.load(Ordering::Relaxed),
+            receive_errors: counters.receive_errors.load(Ordering::Relaxed),
+            bytes_received: counters.bytes_received.load(Ordering::Relaxed),
+            send_latency: if self.latency {
+                Some(counters.send_latency.snapshot())
+            } else {
+                None
+            },
+            receive_latency: if self.latency {
+                Some(counters.receive_latency.snapshot())
+            } else {
+                None
+            },
+        }
+    }
+
+    /// Get reference to the wrapped transport.
+    pub fn inner(&self) -> &T {
+        &self.transport
+    }
+
+    /// Get mutable reference to the wrapped transport, its calls are not metered.
+    pub fn inner_mut(&mut self) -> &mut T {
+        &mut self.transport
+    }
+
+    /// Unwrap the inner transport.
+    pub fn into_inner(self) -> T {
+        self.transport
+    }
+
+    /// Time at the start of a call, if latency is tracked.
+    fn start(&self) -> Option<Duration> {
+        if self.latency {
+            Some((self.now)())
+        } else {
+            None
+        }
+    }
+
+    /// Record a send call, `sent` is the size of the message if it was sent successfully.
+    fn record_send(&self, start: Option<Duration>, sent: Option<usize>) {
+        let counters = &self.counters;
+        counters.sends.fetch_add(1, Ordering::Relaxed);
+        match sent {
+            Some(size) => counters.bytes_sent.fetch_add(size, Ordering::Relaxed),
+            None => counters.send_errors.fetch_add(1, Ordering::Relaxed),
+        };
+        if let Some(start) = start {
+            counters.send_latency.record((self.now)().saturating_sub(start));
+        }
+    }
+
+    /// Record a receive call, `received` is the size of the messages if they were received.
+    fn record_receive(&self, start: Option<Duration>, received: Option<usize>) {
+        let counters = &self.counters;
+        counters.receives.fetch_add(1, Ordering::Relaxed);
+        match received {
+            Some(size) => counters.bytes_received.fetch_add(size, Ordering::Relaxed),
+            None => counters.receive_errors.fetch_add(1, Ordering::Relaxed),
+        };
+        if let Some(start) = start {
+            counters.receive_latency.record((self.now)().saturating_sub(start));
+        }
+    }
+}
+
+impl<T: TransportOptions> TransportOptions for Metered<T> {
+    type SendOptions = <T as TransportOptions>::SendOptions;
+    fn get_send_options(&self) -> Self::SendOptions {
+        self.transport.get_send_options()
+    }
+    fn set_send_options(&mut self, opt: Self::SendOptions) {
+        self.transport.set_send_options(opt)
+    }
+
+    type RecvOptions = <T as TransportOptions>::RecvOptions;
+    fn get_recv_options(&self) -> Self::RecvOptions {
+        self.transport.get_recv_options()
+    }
+    fn set_recv_options(&mut self, opt: Self::RecvOptions) {
+        self.transport.set_recv_options(opt)
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl<T: TransportDetails<Link>, Link> TransportDetails<Link> for Metered<T> {
+    type Details = <T as TransportDetails<Link>>::Details;
+    fn get_link_details(&mut self, link: &Link) -> Result<Self::Details> {
+        self.transport.get_link_details(link)
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl<Link: Debug + Display, Msg: MessageSize, T: Transport<Link, Msg>> Transport<Link, Msg> for Metered<T> {
+    /// Send a message, recording its size.
+    fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        let start = self.start();
+        let r = self.transport.send_message(msg);
+        self.record_send(start, r.as_ref().ok().map(|_| msg.message_size()));
+        r
+    }
+
+    /// Receive messages, recording their size.
+    fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        let start = self.start();
+        let r = self.transport.recv_messages(link);
+        let received = r.as_ref().ok().map(|msgs| msgs.iter().map(Msg::message_size).sum());
+        self.record_receive(start, received);
+        r
+    }
+
+    /// Receive a message, recording its size.
+    fn recv_message(&mut self, link: &Link) -> Result<Msg> {
+        let start = self.start();
+        let r = self.transport.recv_message(link);
+        self.record_receive(start, r.as_ref().ok().map(Msg::message_size));
+        r
+    }
+
+    fn delete_message(&mut self, link: &Link) -> Result<()> {
+        self.transport.delete_message(link)
+    }
+
+    fn await_confirmation(&mut self, link: &Link) -> Result<()> {
+        self.transport.await_confirmation(link)
+    }
+
+    fn list_links(&mut self, appinst: &Link) -> Result<Vec<Link>> {
+        self.transport.list_links(appinst)
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait(?Send)]
+impl<T: TransportDetails<Link>, Link> TransportDetails<Link> for Metered<T>
+where
+    Link: Send + Sync,
+{
+    type Details = <T as TransportDetails<Link>>::Details;
+    async fn get_link_details(&mut self, link: &Link) -> Result<Self::Details> {
+        self.transport.get_link_details(link).await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait(?Send)]
+impl<Link, Msg: MessageSize, T: Transport<Link, Msg>> Transport<Link, Msg> for Metered<T>
+where
+    Link: Send + Sync,
+    Msg: Send + Sync,
+{
+    /// Send a message, recording its size.
+    async fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        let start = self.start();
+        let r = self.transport.send_message(msg).await;
+        self.record_send(start, r.as_ref().ok().map(|_| msg.message_size()));
+        r
+    }
+
+    /// Receive messages, recording their size.
+    async fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        let start = self.start();
+        let r = self.transport.recv_messages(link).await;
+        let received = r.as_ref().ok().map(|msgs| msgs.iter().map(Msg::message_size).sum());
+        self.record_receive(start, received);
+        r
+    }
+
+    /// Receive a message, recording its size.
+    async fn recv_message(&mut self, link: &Link) -> Result<Msg> {
+        let start = self.start();
+        let r = self.transport.recv_message(link).await;
+        self.record_receive(start, r.as_ref().ok().map(Msg::message_size));
+        r
+    }
+
+    async fn delete_message(&mut self, link: &Link) -> Result<()> {
+        self.transport.delete_message(link).await
+    }
+
+    async fn await_confirmation(&mut self, link: &Link) -> Result<()> {
+        self.transport.await_confirmation(link).await
+    }
+
+    async fn list_links(&mut self, appinst: &Link) -> Result<Vec<Link>> {
+        self.transport.list_links(appinst).await
+    }
+}
+
+#[cfg(all(test, not(feature = "async")))]
+mod tests {
+    use super::*;
+    use crate::message::LinkedMessage;
+
+    #[derive(Clone)]
+    struct TestMessage(u32, usize);
+
+    impl LinkedMessage<u32> for TestMessage {
+        fn link(&self) -> &u32 {
+            &self.0
+        }
+        fn prev_link(&self) -> &u32 {
+            &self.0
+        }
+    }
+
+    impl MessageSize for TestMessage {
+        fn message_size(&self) -> usize {
+            self.1
+        }
+    }
+
+    static NOW_MS: AtomicUsize = AtomicUsize::new(0);
+
+    /// Clock advancing by 20ms on each reading.
+    fn ticking_now() -> Duration {
+        Duration::from_millis(NOW_MS.fetch_add(20, Ordering::SeqCst) as u64)
+    }
+
+    #[test]
+    fn metered_counts_calls_and_bytes() {
+        let mut tsp = Metered::new(BucketTransport::<u32, TestMessage>::new());
+        let observer = tsp.clone();
+        tsp.send_message(&TestMessage(1, 100)).unwrap();
+        tsp.send_message(&TestMessage(2, 50)).unwrap();
+        assert_eq!(tsp.recv_message(&1).unwrap().1, 100);
+        assert_eq!(tsp.recv_messages(&2).unwrap().len(), 1);
+        assert!(tsp.recv_message(&3).is_err());
+
+        let metrics = observer.metrics();
+        assert_eq!(metrics.sends, 2);
+        assert_eq!(metrics.send_errors, 0);
+        assert_eq!(metrics.bytes_sent, 150);
+        assert_eq!(metrics.receives, 3);
+        assert_eq!(metrics.receive_errors, 1);
+        assert_eq!(metrics.bytes_received, 150);
+        assert_eq!(metrics.send_latency, None);
+    }
+
+    #[test]
+    fn metered_tracks_latency() {
+        let mut tsp = Metered::new(BucketTransport::<u32, TestMessage>::new())
+            .with_latency(true)
+            .with_clock(ticking_now);
+        tsp.send_message(&TestMessage(1, 10)).unwrap();
+        tsp.recv_message(&1).unwrap();
+
+        let metrics = tsp.metrics();
+        let send_latency = metrics.send_latency.unwrap();
+        assert_eq!(send_latency.counts.iter().sum::<usize>(), 1);
+        assert_eq!(send_latency.counts[3], 1);
+        assert_eq!(send_latency.total, Duration::from_millis(20));
+        assert_eq!(metrics.receive_latency.unwrap().counts[3], 1);
+    }
+}
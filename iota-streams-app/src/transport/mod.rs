@@ -72,6 +72,28 @@ pub trait Transport<Link: Debug + Display, Msg>: TransportOptions + TransportDet
             err!(MessageLinkNotFound(link.to_string()))
         }
     }
+
+    /// Delete the messages at the link, if supported by the transport.
+    ///
+    /// Note: messages linked to a deleted one can no longer be unwrapped by users who have not
+    /// processed the deleted message yet as the chain of spongos states is broken.
+    fn delete_message(&mut self, _link: &Link) -> Result<()> {
+        err!(MessageDeletionUnsupported)
+    }
+
+    /// Wait until the message at the link is durably stored, eg. confirmed by the network.
+    /// Transports storing messages as they are sent confirm immediately.
+    fn await_confirmation(&mut self, _link: &Link) -> Result<()> {
+        Ok(())
+    }
+
+    /// List links of the messages stored for the channel of `appinst`, if the transport can
+    /// enumerate its messages, eg. a database or a directory. The list may contain links of other
+    /// channels, callers filter it. Transports which can only look messages up by link, eg. the
+    /// Tangle, don't support it: users then probe the expected links of every publisher.
+    fn list_links(&mut self, _appinst: &Link) -> Result<Vec<Link>> {
+        err!(MessageListingUnsupported)
+    }
 }
 
 #[cfg(feature = "async")]
@@ -89,6 +111,28 @@ where
 
     /// Receive a message with default options.
     async fn recv_message(&mut self, link: &Link) -> Result<Msg>;
+
+    /// Delete the messages at the link, if supported by the transport.
+    ///
+    /// Note: messages linked to a deleted one can no longer be unwrapped by users who have not
+    /// processed the deleted message yet as the chain of spongos states is broken.
+    async fn delete_message(&mut self, _link: &Link) -> Result<()> {
+        err!(MessageDeletionUnsupported)
+    }
+
+    /// Wait until the message at the link is durably stored, eg. confirmed by the network.
+    /// Transports storing messages as they are sent confirm immediately.
+    async fn await_confirmation(&mut self, _link: &Link) -> Result<()> {
+        Ok(())
+    }
+
+    /// List links of the messages stored for the channel of `appinst`, if the transport can
+    /// enumerate its messages, eg. a database or a directory. The list may contain links of other
+    /// channels, callers filter it. Transports which can only look messages up by link, eg. the
+    /// Tangle, don't support it: users then probe the expected links of every publisher.
+    async fn list_links(&mut self, _appinst: &Link) -> Result<Vec<Link>> {
+        err!(MessageListingUnsupported)
+    }
     // For some reason compiler requires (Msg: `async_trait) lifetime bound for this default implementation.
     // {
     // let mut msgs = self.recv_messages(link).await?;
@@ -152,6 +196,30 @@ impl<Link: Debug + Display, Msg, Tsp: Transport<Link, Msg>> Transport<Link, Msg>
             Err(err) => Err(wrapped_err!(TransportNotAvailable, WrappedError(err))),
         }
     }
+
+    /// Delete the messages at the link.
+    fn delete_message(&mut self, link: &Link) -> Result<()> {
+        match (&*self).try_borrow_mut() {
+            Ok(mut tsp) => tsp.delete_message(link),
+            Err(err) => Err(wrapped_err!(TransportNotAvailable, WrappedError(err))),
+        }
+    }
+
+    /// Wait until the message at the link is confirmed.
+    fn await_confirmation(&mut self, link: &Link) -> Result<()> {
+        match (&*self).try_borrow_mut() {
+            Ok(mut tsp) => tsp.await_confirmation(link),
+            Err(err) => Err(wrapped_err!(TransportNotAvailable, WrappedError(err))),
+        }
+    }
+
+    /// List links of the messages stored for the channel.
+    fn list_links(&mut self, appinst: &Link) -> Result<Vec<Link>> {
+        match (&*self).try_borrow_mut() {
+            Ok(mut tsp) => tsp.list_links(appinst),
+            Err(err) => Err(wrapped_err!(TransportNotAvailable, WrappedError(err))),
+        }
+    }
 }
 
 #[cfg(not(feature = "async"))]
@@ -216,17 +284,52 @@ pub fn new_shared_transport<T>(tsp: T) -> Arc<AtomicRefCell<T>> {
 mod bucket;
 pub use bucket::BucketTransport;
 
+/// Transport wrapper retrying failed calls with exponential backoff.
+pub mod retry;
+pub use retry::Retry;
+
+/// Object safe transport selected at run-time.
+pub mod boxed;
+pub use boxed::{
+    BoxedTransport,
+    DynTransport,
+};
+
+/// Transport wrapper caching received messages.
+pub mod cache;
+pub use cache::Cached;
+
+/// Transport wrapper recording metrics of the calls of the inner transport.
+pub mod metrics;
+pub use metrics::{
+    Metered,
+    TransportMetrics,
+};
+
+/// Transport wrapper mirroring messages to two transports.
+pub mod tee;
+pub use tee::{
+    Tee,
+    TeePolicy,
+};
+
 #[cfg(not(feature = "async"))]
 use core::fmt::{
     Debug,
     Display,
 };
 
-use iota_streams_core::try_or;
+use iota_streams_core::{
+    err,
+    try_or,
+    Errors::{
+        MessageDeletionUnsupported,
+        MessageListingUnsupported,
+    },
+};
 
 #[cfg(not(feature = "async"))]
 use iota_streams_core::{
-    err,
     wrapped_err,
     Errors::{
         MessageLinkNotFound,
@@ -0,0 +1,566 @@
+use super::*;
+use core::time::Duration;
+#[cfg(feature = "async")]
+use core::{
+    future::Future,
+    pin::Pin,
+};
+
+use iota_streams_core::{
+    Error,
+    Errors::{
+        self,
+        MessageLinkNotFound,
+    },
+};
+
+/// Default number of retries after the first failed attempt.
+pub const DEFAULT_MAX_RETRIES: usize = 3;
+/// Default delay before the first retry, doubled after each subsequent failure.
+pub const DEFAULT_INITIAL_DELAY: Duration = Duration::from_millis(200);
+
+/// Default retry predicate: missing messages are not retried as it's a regular situation
+/// when fetching links that have not been published yet, any other error is retried.
+pub fn retry_unless_not_found(e: &Error) -> bool {
+    !matches!(e.downcast_ref::<Errors>(), Some(MessageLinkNotFound(_)))
+}
+
+/// Function waiting for the backoff delay between attempts.
+#[cfg(not(feature = "async"))]
+pub type Sleep = fn(Duration);
+
+/// Function returning a future waiting for the backoff delay between attempts, eg. a timer of the
+/// executor in use. It must not block the thread.
+#[cfg(feature = "async")]
+pub type Sleep = fn(Duration) -> Pin<Box<dyn Future<Output = ()>>>;
+
+/// Transport wrapper retrying failed calls of the inner transport with exponential backoff.
+///
+/// The backoff delay is waited for with the `sleep` function. A blocking `std::thread::sleep` is
+/// used by default in sync `std` builds, without `std` or in async builds there is no timer to
+/// fall back to and it has to be provided with `with_sleep_fn`.
+#[derive(Clone)]
+pub struct Retry<T> {
+    transport: T,
+    max_retries: usize,
+    initial_delay: Duration,
+    retry_if: fn(&Error) -> bool,
+    sleep: Sleep,
+}
+
+impl<T> Retry<T> {
+    /// Wrap transport with default retry settings, the delay is waited for with
+    /// `std::thread::sleep`.
+    #[cfg(all(feature = "std", not(feature = "async")))]
+    pub fn new(transport: T) -> Self {
+        Self::with_sleep_fn(transport, std::thread::sleep)
+    }
+
+    /// Wrap transport with default retry settings, the delay is waited for with `sleep`.
+    pub fn with_sleep_fn(transport: T, sleep: Sleep) -> Self {
+        Self {
+            transport,
+            max_retries: DEFAULT_MAX_RETRIES,
+            initial_delay: DEFAULT_INITIAL_DELAY,
+            retry_if: retry_unless_not_found,
+            sleep,
+        }
+    }
+
+    /// Set the number of retries after the first failed attempt.
+    pub fn with_max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Set the delay before the first retry.
+    pub fn with_initial_delay(mut self, initial_delay: Duration) -> Self {
+        self.initial_delay = initial_delay;
+        self
+    }
+
+    /// Set the predicate deciding whether a failed call is retried.
+    pub fn with_retry_if(mut self, retry_if: fn(&Error) -> bool) -> Self {
+        self.retry_if = retry_if;
+        self
+    }
+
+    /// Set the function used to wait between attempts.
+    pub fn with_sleep(mut self, sleep: Sleep) -> Self {
+        self.sleep = sleep;
+        self
+    }
+
+    /// Get reference to the wrapped transport.
+    pub fn inner(&self) -> &T {
+        &self.transport
+    }
+
+    /// Get mutable reference to the wrapped transport.
+    pub fn inner_mut(&mut self) -> &mut T {
+        &mut self.transport
+    }
+
+    /// Unwrap the inner transport.
+    pub fn into_inner(self) -> T {
+        self.transport
+    }
+
+    /// Backoff delay before the retry of a call failed at `attempt`, if it should be retried.
+    fn backoff_delay(&self, attempt: usize, e: &Error) -> Option<Duration> {
+        if attempt < self.max_retries && (self.retry_if)(e) {
+            let factor = 1_u32.checked_shl(attempt as u32).unwrap_or(u32::MAX);
+            Some(self.initial_delay.saturating_mul(factor))
+        } else {
+            None
+        }
+    }
+
+    /// Check whether a call failed at `attempt` should be retried and wait for the backoff delay if so.
+    #[cfg(not(feature = "async"))]
+    fn backoff(&self, attempt: usize, e: &Error) -> bool {
+        match self.backoff_delay(attempt, e) {
+            Some(delay) => {
+                (self.sleep)(delay);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Check whether a call failed at `attempt` should be retried and wait for the backoff delay if so.
+    #[cfg(feature = "async")]
+    async fn backoff(&self, attempt: usize, e: &Error) -> bool {
+        match self.backoff_delay(attempt, e) {
+            Some(delay) => {
+                (self.sleep)(delay).await;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl<T: TransportOptions> TransportOptions for Retry<T> {
+    type SendOptions = <T as TransportOptions>::SendOptions;
+    fn get_send_options(&self) -> Self::SendOptions {
+        self.transport.get_send_options()
+    }
+    fn set_send_options(&mut self, opt: Self::SendOptions) {
+        self.transport.set_send_options(opt)
+    }
+
+    type RecvOptions = <T as TransportOptions>::RecvOptions;
+    fn get_recv_options(&self) -> Self::RecvOptions {
+        self.transport.get_recv_options()
+    }
+    fn set_recv_options(&mut self, opt: Self::RecvOptions) {
+        self.transport.set_recv_options(opt)
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl<T: TransportDetails<Link>, Link> TransportDetails<Link> for Retry<T> {
+    type Details = <T as TransportDetails<Link>>::Details;
+    fn get_link_details(&mut self, link: &Link) -> Result<Self::Details> {
+        let mut attempt = 0;
+        loop {
+            match self.transport.get_link_details(link) {
+                Err(e) if self.backoff(attempt, &e) => attempt += 1,
+                r => return r,
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl<Link: Debug + Display, Msg, T: Transport<Link, Msg>> Transport<Link, Msg> for Retry<T> {
+    /// Send a message, retrying on failure.
+    fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        let mut attempt = 0;
+        loop {
+            match self.transport.send_message(msg) {
+                Err(e) if self.backoff(attempt, &e) => attempt += 1,
+                r => return r,
+            }
+        }
+    }
+
+    /// Receive messages, retrying on failure.
+    fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        let mut attempt = 0;
+        loop {
+            match self.transport.recv_messages(link) {
+                Err(e) if self.backoff(attempt, &e) => attempt += 1,
+                r => return r,
+            }
+        }
+    }
+
+    /// Receive a message, retrying on failure.
+    fn recv_message(&mut self, link: &Link) -> Result<Msg> {
+        let mut attempt = 0;
+        loop {
+            match self.transport.recv_message(link) {
+                Err(e) if self.backoff(attempt, &e) => attempt += 1,
+                r => return r,
+            }
+        }
+    }
+
+    /// Delete messages, retrying on failure.
+    fn delete_message(&mut self, link: &Link) -> Result<()> {
+        let mut attempt = 0;
+        loop {
+            match self.transport.delete_message(link) {
+                Err(e) if self.backoff(attempt, &e) => attempt += 1,
+                r => return r,
+            }
+        }
+    }
+
+    /// Wait for confirmation, retrying on failure.
+    fn await_confirmation(&mut self, link: &Link) -> Result<()> {
+        let mut attempt = 0;
+        loop {
+            match self.transport.await_confirmation(link) {
+                Err(e) if self.backoff(attempt, &e) => attempt += 1,
+                r => return r,
+            }
+        }
+    }
+
+    /// List links, retrying on failure.
+    fn list_links(&mut self, appinst: &Link) -> Result<Vec<Link>> {
+        let mut attempt = 0;
+        loop {
+            match self.transport.list_links(appinst) {
+                Err(e) if self.backoff(attempt, &e) => attempt += 1,
+                r => return r,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait(?Send)]
+impl<T: TransportDetails<Link>, Link> TransportDetails<Link> for Retry<T>
+where
+    Link: Send + Sync,
+{
+    type Details = <T as TransportDetails<Link>>::Details;
+    async fn get_link_details(&mut self, link: &Link) -> Result<Self::Details> {
+        let mut attempt = 0;
+        loop {
+            let e = match self.transport.get_link_details(link).await {
+                Err(e) => e,
+                r => return r,
+            };
+            if !self.backoff(attempt, &e).await {
+                return Err(e);
+            }
+            attempt += 1;
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait(?Send)]
+impl<Link, Msg, T: Transport<Link, Msg>> Transport<Link, Msg> for Retry<T>
+where
+    Link: Send + Sync,
+    Msg: Send + Sync,
+{
+    /// Send a message, retrying on failure.
+    async fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        let mut attempt = 0;
+        loop {
+            let e = match self.transport.send_message(msg).await {
+                Err(e) => e,
+                r => return r,
+            };
+            if !self.backoff(attempt, &e).await {
+                return Err(e);
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Receive messages, retrying on failure.
+    async fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        let mut attempt = 0;
+        loop {
+            let e = match self.transport.recv_messages(link).await {
+                Err(e) => e,
+                r => return r,
+            };
+            if !self.backoff(attempt, &e).await {
+                return Err(e);
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Receive a message, retrying on failure.
+    async fn recv_message(&mut self, link: &Link) -> Result<Msg> {
+        let mut attempt = 0;
+        loop {
+            let e = match self.transport.recv_message(link).await {
+                Err(e) => e,
+                r => return r,
+            };
+            if !self.backoff(attempt, &e).await {
+                return Err(e);
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Delete messages, retrying on failure.
+    async fn delete_message(&mut self, link: &Link) -> Result<()> {
+        let mut attempt = 0;
+        loop {
+            let e = match self.transport.delete_message(link).await {
+                Err(e) => e,
+                r => return r,
+            };
+            if !self.backoff(attempt, &e).await {
+                return Err(e);
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Wait for confirmation, retrying on failure.
+    async fn await_confirmation(&mut self, link: &Link) -> Result<()> {
+        let mut attempt = 0;
+        loop {
+            let e = match self.transport.await_confirmation(link).await {
+                Err(e) => e,
+                r => return r,
+            };
+            if !self.backoff(attempt, &e).await {
+                return Err(e);
+            }
+            attempt += 1;
+        }
+    }
+
+    /// List links, retrying on failure.
+    async fn list_links(&mut self, appinst: &Link) -> Result<Vec<Link>> {
+        let mut attempt = 0;
+        loop {
+            let e = match self.transport.list_links(appinst).await {
+                Err(e) => e,
+                r => return r,
+            };
+            if !self.backoff(attempt, &e).await {
+                return Err(e);
+            }
+            attempt += 1;
+        }
+    }
+}
+
+#[cfg(all(test, not(feature = "async")))]
+mod tests {
+    use super::*;
+    use crate::message::LinkedMessage;
+    use iota_streams_core::{
+        err,
+        Errors::TransportNotAvailable,
+    };
+
+    #[derive(Clone)]
+    struct TestMessage(u32);
+
+    impl LinkedMessage<u32> for TestMessage {
+        fn link(&self) -> &u32 {
+            &self.0
+        }
+        fn prev_link(&self) -> &u32 {
+            &self.0
+        }
+    }
+
+    /// Bucket transport failing the given number of calls before forwarding them.
+    struct FlakyTransport {
+        bucket: BucketTransport<u32, TestMessage>,
+        failures: usize,
+        calls: usize,
+    }
+
+    impl FlakyTransport {
+        fn new(failures: usize) -> Self {
+            Self {
+                bucket: BucketTransport::new(),
+                failures,
+                calls: 0,
+            }
+        }
+
+        fn flake(&mut self) -> Result<()> {
+            self.calls += 1;
+            if self.failures > 0 {
+                self.failures -= 1;
+                err!(TransportNotAvailable)
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    impl TransportOptions for FlakyTransport {
+        type SendOptions = ();
+        fn get_send_options(&self) {}
+        fn set_send_options(&mut self, _opt: ()) {}
+
+        type RecvOptions = ();
+        fn get_recv_options(&self) {}
+        fn set_recv_options(&mut self, _opt: ()) {}
+    }
+
+    impl TransportDetails<u32> for FlakyTransport {
+        type Details = ();
+        fn get_link_details(&mut self, _link: &u32) -> Result<()> {
+            self.flake()
+        }
+    }
+
+    impl Transport<u32, TestMessage> for FlakyTransport {
+        fn send_message(&mut self, msg: &TestMessage) -> Result<()> {
+            self.flake()?;
+            self.bucket.send_message(msg)
+        }
+
+        fn recv_messages(&mut self, link: &u32) -> Result<Vec<TestMessage>> {
+            self.flake()?;
+            self.bucket.recv_messages(link)
+        }
+    }
+
+    fn no_sleep(_delay: Duration) {}
+
+    #[test]
+    fn retry_recovers_from_transient_failures() {
+        let mut tsp = Retry::new(FlakyTransport::new(2)).with_sleep(no_sleep);
+        tsp.send_message(&TestMessage(1)).unwrap();
+        assert_eq!(tsp.inner().calls, 3);
+        assert_eq!(tsp.recv_message(&1).unwrap().0, 1);
+        assert_eq!(tsp.inner().calls, 4);
+    }
+
+    #[test]
+    fn retry_gives_up_after_max_retries() {
+        let mut tsp = Retry::new(FlakyTransport::new(5))
+            .with_max_retries(2)
+            .with_sleep(no_sleep);
+        assert!(tsp.send_message(&TestMessage(1)).is_err());
+        assert_eq!(tsp.inner().calls, 3);
+    }
+
+    #[test]
+    fn retry_skips_non_retryable_errors() {
+        let mut tsp = Retry::new(FlakyTransport::new(0)).with_sleep(no_sleep);
+        assert!(tsp.recv_message(&1).is_err());
+        assert_eq!(tsp.inner().calls, 1);
+
+        let mut tsp = Retry::new(FlakyTransport::new(1))
+            .with_retry_if(|_| false)
+            .with_sleep(no_sleep);
+        assert!(tsp.send_message(&TestMessage(1)).is_err());
+        assert_eq!(tsp.inner().calls, 1);
+    }
+}
+
+#[cfg(all(test, feature = "async"))]
+mod async_tests {
+    use super::*;
+    use iota_streams_core::{
+        err,
+        prelude::vec,
+        Errors::TransportNotAvailable,
+    };
+    use std::{
+        cell::RefCell as StdRefCell,
+        thread_local,
+    };
+
+    thread_local! {
+        static DELAYS: StdRefCell<Vec<Duration>> = StdRefCell::new(Vec::new());
+    }
+
+    /// Record the delay and yield to the executor once instead of blocking the thread.
+    fn recording_sleep(delay: Duration) -> Pin<Box<dyn Future<Output = ()>>> {
+        DELAYS.with(|delays| delays.borrow_mut().push(delay));
+        Box::pin(smol::future::yield_now())
+    }
+
+    /// Transport failing the given number of calls before echoing the link.
+    struct FlakyTransport {
+        failures: usize,
+        calls: usize,
+    }
+
+    impl FlakyTransport {
+        fn flake(&mut self) -> Result<()> {
+            self.calls += 1;
+            if self.failures > 0 {
+                self.failures -= 1;
+                err!(TransportNotAvailable)
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    impl TransportOptions for FlakyTransport {
+        type SendOptions = ();
+        fn get_send_options(&self) {}
+        fn set_send_options(&mut self, _opt: ()) {}
+
+        type RecvOptions = ();
+        fn get_recv_options(&self) {}
+        fn set_recv_options(&mut self, _opt: ()) {}
+    }
+
+    #[async_trait(?Send)]
+    impl TransportDetails<u32> for FlakyTransport {
+        type Details = ();
+        async fn get_link_details(&mut self, _link: &u32) -> Result<()> {
+            self.flake()
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl Transport<u32, u32> for FlakyTransport {
+        async fn send_message(&mut self, _msg: &u32) -> Result<()> {
+            self.flake()
+        }
+
+        async fn recv_messages(&mut self, link: &u32) -> Result<Vec<u32>> {
+            self.flake()?;
+            Ok(vec![*link])
+        }
+
+        async fn recv_message(&mut self, link: &u32) -> Result<u32> {
+            self.flake()?;
+            Ok(*link)
+        }
+    }
+
+    #[test]
+    fn retry_waits_with_async_sleep() {
+        let delay = Duration::from_millis(10);
+        let mut tsp =
+            Retry::with_sleep_fn(FlakyTransport { failures: 2, calls: 0 }, recording_sleep).with_initial_delay(delay);
+        assert_eq!(smol::block_on(tsp.recv_message(&1)).unwrap(), 1);
+        assert_eq!(tsp.inner().calls, 3);
+        DELAYS.with(|delays| assert_eq!(*delays.borrow(), vec![delay, delay * 2]));
+
+        let mut tsp =
+            Retry::with_sleep_fn(FlakyTransport { failures: 5, calls: 0 }, recording_sleep).with_max_retries(1);
+        assert!(smol::block_on(tsp.send_message(&1)).is_err());
+        assert_eq!(tsp.inner().calls, 2);
+    }
+}
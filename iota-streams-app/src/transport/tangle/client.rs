@@ -169,6 +169,16 @@ pub async fn async_get_link_details(client: &iota_client::Client, link: &TangleA
     Ok(Details { metadata, milestone })
 }
 
+/// Wait until the message at the link is included in the ledger, polling the node with the
+/// default interval and number of attempts of the client.
+pub async fn async_await_confirmation(client: &iota_client::Client, link: &TangleAddress) -> Result<()> {
+    let hash = get_hash(link.appinst.as_ref(), link.msgid.as_ref())?;
+    let msg_ids = handle_client_result(client.get_message().index(&hash.to_string()).await)?;
+    try_or!(!msg_ids.is_empty(), IndexNotFound)?;
+    handle_client_result(client.retry_until_included(&msg_ids[0], None, None).await)?;
+    Ok(())
+}
+
 /// Synchronised - Send message to the tangle using a node client
 #[cfg(not(feature = "async"))]
 pub fn sync_send_message_with_options<F>(client: &iota_client::Client, msg: &TangleMessage<F>) -> Result<()> {
@@ -181,6 +191,12 @@ pub fn sync_recv_messages<F>(client: &iota_client::Client, link: &TangleAddress)
     block_on(async_recv_messages(client, link))
 }
 
+/// Synchronised - Wait until the message at the link is included in the ledger
+#[cfg(not(feature = "async"))]
+pub fn sync_await_confirmation(client: &iota_client::Client, link: &TangleAddress) -> Result<()> {
+    block_on(async_await_confirmation(client, link))
+}
+
 /// Synchronised - Retrieve details of a link from the tangle using a node client
 #[cfg(not(feature = "async"))]
 pub fn sync_get_link_details(client: &iota_client::Client, link: &TangleAddress) -> Result<Details> {
@@ -289,6 +305,11 @@ impl<F> Transport<TangleAddress, TangleMessage<F>> for Client {
     fn recv_messages(&mut self, link: &TangleAddress) -> Result<Vec<TangleMessage<F>>> {
         sync_recv_messages(&self.client, link)
     }
+
+    /// Wait until the message is included in the ledger.
+    fn await_confirmation(&mut self, link: &TangleAddress) -> Result<()> {
+        sync_await_confirmation(&self.client, link)
+    }
 }
 
 #[cfg(feature = "async")]
@@ -316,6 +337,11 @@ where
             err!(MessageLinkNotFound(link.to_string()))
         }
     }
+
+    /// Wait until the message is included in the ledger.
+    async fn await_confirmation(&mut self, link: &TangleAddress) -> Result<()> {
+        async_await_confirmation(&self.client, link).await
+    }
 }
 
 #[cfg(feature = "async")]
@@ -376,4 +402,12 @@ where
             Err(_err) => err!(TransportNotAvailable),
         }
     }
+
+    /// Wait until the message is included in the ledger.
+    async fn await_confirmation(&mut self, link: &TangleAddress) -> Result<()> {
+        match (&*self).try_borrow_mut() {
+            Ok(tsp) => async_await_confirmation(&tsp.client, link).await,
+            Err(_err) => err!(TransportNotAvailable),
+        }
+    }
 }
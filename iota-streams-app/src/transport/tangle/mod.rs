@@ -1,6 +1,7 @@
 //! Tangle-specific transport definitions.
 
 use core::{
+    cmp::Ordering,
     convert::{
         AsMut,
         AsRef,
@@ -18,6 +19,7 @@ use iota_streams_core::{
         blake2b,
         Digest,
     },
+    err,
     prelude::{
         typenum::{
             U12,
@@ -32,8 +34,13 @@ use iota_streams_core::{
         prp::PRP,
         spongos::Spongos,
     },
+    try_or,
     wrapped_err,
-    Errors::BadHexFormat,
+    Errors::{
+        BadAddressFormat,
+        BadHexFormat,
+        LengthMismatch,
+    },
     WrappedError,
 };
 use iota_streams_core_edsig::signature::ed25519;
@@ -135,6 +142,27 @@ impl<F> TangleMessage<F> {
     pub fn with_timestamp(msg: BinaryMessage<F, TangleAddress>, timestamp: u64) -> Self {
         Self { binary: msg, timestamp }
     }
+
+    /// Create TangleMessage from raw body bytes, eg. to forward a message without unwrapping it.
+    pub fn from_body(link: TangleAddress, prev_link: TangleAddress, body: Vec<u8>, timestamp: u64) -> Self {
+        Self::with_timestamp(BinaryMessage::new(link, prev_link, body.into()), timestamp)
+    }
+
+    /// Create TangleMessage at `link` from arbitrary body bytes, eg. to check that malformed
+    /// messages are rejected by the handlers. The linked address is parsed from the header.
+    pub fn from_raw_body(link: TangleAddress, body: impl Into<Vec<u8>>) -> Self {
+        Self::from_body(link, TangleAddress::default(), body.into(), 0)
+    }
+
+    /// Raw message body bytes.
+    pub fn body(&self) -> &[u8] {
+        self.binary.body.as_bytes()
+    }
+
+    /// Take raw message body bytes without copying.
+    pub fn into_body(self) -> Vec<u8> {
+        self.binary.body.into_bytes()
+    }
 }
 
 /// Tangle representation of a Message Link
@@ -146,13 +174,30 @@ pub struct TangleAddress {
 
 impl TangleAddress {
     pub fn from_str(appinst_str: &str, msgid_str: &str) -> Result<Self> {
-        let appinst = AppInst::from_str(appinst_str)
-            .map_err(|e| wrapped_err!(BadHexFormat(appinst_str.into()), WrappedError(e)))?;
+        let appinst = AppInst::from_str(appinst_str)?;
+        let msgid = MsgId::from_str(msgid_str)?;
+        Ok(TangleAddress { appinst, msgid })
+    }
 
-        let msgid =
-            MsgId::from_str(msgid_str).map_err(|e| wrapped_err!(BadHexFormat(appinst_str.into()), WrappedError(e)))?;
+    /// Parse address from its `<appinst hex>:<msgid hex>` string form as produced by `to_hex` and
+    /// `Display`.
+    pub fn from_hex(addr_str: &str) -> Result<Self> {
+        let mut parts = addr_str.split(':');
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(appinst_str), Some(msgid_str), None) => Self::from_str(appinst_str, msgid_str),
+            _ => err!(BadAddressFormat(addr_str.into())),
+        }
+    }
 
-        Ok(TangleAddress { appinst, msgid })
+    /// Encode address as `<appinst hex>:<msgid hex>` string, same as `Display`.
+    pub fn to_hex(&self) -> String {
+        self.to_string()
+    }
+
+    /// Index of the message transaction: hex encoded blake2b hash of the address. The hash is
+    /// one-way, the address can't be parsed back from the index.
+    pub fn to_msg_index(&self) -> String {
+        get_hash(self.appinst.as_ref(), self.msgid.as_ref()).unwrap_or_default()
     }
 
     #[allow(clippy::inherent_to_string_shadow_display)]
@@ -170,13 +215,20 @@ impl TangleAddress {
     pub unsafe fn from_c_str(c_addr: *const c_char) -> *const Self {
         c_addr.as_ref().map_or(null(), |c_addr| {
             CStr::from_ptr(c_addr).to_str().map_or(null(), |addr_str| {
-                let addr_vec: Vec<&str> = addr_str.split(':').collect();
-                Self::from_str(addr_vec[0], addr_vec[1]).map_or(null(), |addr| Box::into_raw(Box::new(addr)))
+                Self::from_hex(addr_str).map_or(null(), |addr| Box::into_raw(Box::new(addr)))
             })
         })
     }
 }
 
+/// Inverse of `Display`, ie. `addr.to_string().parse()` yields `addr` back.
+impl FromStr for TangleAddress {
+    type Err = iota_streams_core::Error;
+    fn from_str(s: &str) -> Result<Self> {
+        Self::from_hex(s)
+    }
+}
+
 impl fmt::Debug for TangleAddress {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "<{:?}:{:?}>", self.appinst, self.msgid)
@@ -189,10 +241,10 @@ pub fn get_hash(tx_address: &[u8], tx_tag: &[u8]) -> Result<String> {
     Ok(hex::encode(&hash))
 }
 
+/// Address as `<appinst hex>:<msgid hex>` string, see `to_msg_index` for the transaction index.
 impl fmt::Display for TangleAddress {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let hash = get_hash(self.appinst.as_ref(), self.msgid.as_ref()).unwrap_or_default();
-        write!(f, "<{}>", hash)
+        write!(f, "{}:{}", self.appinst, self.msgid)
     }
 }
 
@@ -212,6 +264,22 @@ impl PartialEq for TangleAddress {
 }
 impl Eq for TangleAddress {}
 
+/// Addresses are ordered lexicographically over their bytes: by application instance, then by
+/// message identifier.
+impl PartialOrd for TangleAddress {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TangleAddress {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.appinst
+            .cmp(&other.appinst)
+            .then_with(|| self.msgid.cmp(&other.msgid))
+    }
+}
+
 impl TangleAddress {
     pub fn new(appinst: AppInst, msgid: MsgId) -> Self {
         Self { appinst, msgid }
@@ -250,17 +318,26 @@ impl HasLink for TangleAddress {
     }
 
     fn from_bytes(bytes: &[u8]) -> Self {
+        // Announcements are not linked, their empty previous link is the default address
+        if !Self::is_link_bytes(bytes) {
+            return Self::default();
+        }
         TangleAddress::new(
             AppInst::from(&bytes[0..APPINST_SIZE]),
             MsgId::from(&bytes[APPINST_SIZE..]),
         )
     }
+
+    fn is_link_bytes(bytes: &[u8]) -> bool {
+        bytes.len() == APPINST_SIZE + MSGID_SIZE
+    }
 }
 
 /// Default Message Identifer Generator. Used for deriving MsgId's for sequencing
 #[derive(Clone)]
 pub struct DefaultTangleLinkGenerator<F> {
     addr: TangleAddress,
+    domain: Vec<u8>,
     _phantom: core::marker::PhantomData<F>,
 }
 
@@ -268,12 +345,22 @@ impl<F> Default for DefaultTangleLinkGenerator<F> {
     fn default() -> Self {
         Self {
             addr: TangleAddress::default(),
+            domain: Vec::new(),
             _phantom: core::marker::PhantomData,
         }
     }
 }
 
 impl<F> DefaultTangleLinkGenerator<F> {
+    /// Create link generator for a network domain, see `set_domain`.
+    pub fn new_in_domain(domain: &[u8]) -> Self {
+        Self {
+            addr: TangleAddress::default(),
+            domain: domain.to_vec(),
+            _phantom: core::marker::PhantomData,
+        }
+    }
+
     pub fn reset_addr(&mut self, addr: TangleAddress) {
         self.addr = addr;
     }
@@ -282,6 +369,10 @@ impl<F> DefaultTangleLinkGenerator<F> {
 impl<F: PRP> DefaultTangleLinkGenerator<F> {
     fn gen_uniform_msgid(&self, cursor: Cursor<&MsgId>) -> MsgId {
         let mut s = Spongos::<F>::init();
+        if !self.domain.is_empty() {
+            s.absorb(&(self.domain.len() as u64).to_be_bytes());
+            s.absorb(&self.domain);
+        }
         s.absorb(self.addr.appinst.id.as_ref());
         s.absorb(cursor.link.id.as_ref());
         s.absorb(&cursor.branch_no.to_be_bytes());
@@ -292,10 +383,109 @@ impl<F: PRP> DefaultTangleLinkGenerator<F> {
         new
     }
     fn gen_msgid(&self, id: &Identifier, cursor: Cursor<&MsgId>) -> MsgId {
+        MsgId::derive_in_domain::<F>(&self.domain, &self.addr.appinst, id, cursor)
+    }
+}
+
+impl<F: PRP> LinkGenerator<TangleAddress> for DefaultTangleLinkGenerator<F> {
+    /// Used by Author to generate a new application instance: channels address and announcement message identifier
+    fn gen(&mut self, pk: &ed25519::PublicKey, channel_idx: u64) {
+        self.addr.appinst = AppInst::new(pk, channel_idx);
+        self.addr.msgid = self.gen_msgid(&Identifier::EdPubKey((*pk).into()), Cursor::default().as_ref());
+    }
+
+    /// Used by Author to get announcement message id, it's just stored internally by link generator
+    fn get(&self) -> TangleAddress {
+        self.addr.clone()
+    }
+
+    /// Used by Subscriber to initialize link generator with the same state as Author
+    fn reset(&mut self, announcement_link: TangleAddress) {
+        self.addr = announcement_link;
+    }
+
+    /// Used by users to pseudo-randomly generate a new uniform message link from a cursor
+    fn uniform_link_from(&self, cursor: Cursor<&MsgId>) -> TangleAddress {
+        TangleAddress {
+            appinst: self.addr.appinst.clone(),
+            msgid: self.gen_uniform_msgid(cursor),
+        }
+    }
+
+    /// Used by users to pseudo-randomly generate a new message link from a cursor
+    fn link_from(&self, id: &Identifier, cursor: Cursor<&MsgId>) -> TangleAddress {
+        TangleAddress {
+            appinst: self.addr.appinst.clone(),
+            msgid: self.gen_msgid(id, cursor),
+        }
+    }
+
+    fn domain(&self) -> &[u8] {
+        &self.domain
+    }
+
+    /// Separate message identifiers of isolated networks: the same public key, channel index and
+    /// cursor yield different identifiers in different domains. The default empty domain keeps
+    /// identifiers derived with `MsgId::derive`.
+    ///
+    /// Note: the domain is not reset with the announcement link, all the participants must use the
+    /// same domain, otherwise they would derive different links.
+    fn set_domain(&mut self, domain: &[u8]) {
+        self.domain = domain.to_vec();
+    }
+}
+
+/// Topic-based Message Identifier Generator. Message identifiers are derived from an application
+/// defined topic and the publisher's sequence number, but not from the linked message, so that
+/// addresses can be predicted knowing the topic only. Application instance and announcement
+/// message identifier are generated the same way as in `DefaultTangleLinkGenerator`.
+///
+/// Note: all the participants must use the same topic, otherwise they would derive different links.
+#[derive(Clone)]
+pub struct TopicTangleLinkGenerator<F> {
+    addr: TangleAddress,
+    topic: Vec<u8>,
+    _phantom: core::marker::PhantomData<F>,
+}
+
+impl<F> Default for TopicTangleLinkGenerator<F> {
+    fn default() -> Self {
+        Self {
+            addr: TangleAddress::default(),
+            topic: Vec::new(),
+            _phantom: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<F> TopicTangleLinkGenerator<F> {
+    /// Create link generator for the topic.
+    pub fn new(topic: &[u8]) -> Self {
+        Self {
+            addr: TangleAddress::default(),
+            topic: topic.to_vec(),
+            _phantom: core::marker::PhantomData,
+        }
+    }
+
+    pub fn topic(&self) -> &[u8] {
+        &self.topic
+    }
+
+    pub fn set_topic(&mut self, topic: &[u8]) {
+        self.topic = topic.to_vec();
+    }
+}
+
+impl<F: PRP> TopicTangleLinkGenerator<F> {
+    fn gen_topic_msgid(&self, id: Option<&Identifier>, cursor: Cursor<&MsgId>) -> MsgId {
         let mut s = Spongos::<F>::init();
         s.absorb(self.addr.appinst.id.as_ref());
-        s.absorb(id.to_bytes());
-        s.absorb(cursor.link.id.as_ref());
+        s.absorb(&(self.topic.len() as u64).to_be_bytes());
+        s.absorb(&self.topic);
+        if let Some(id) = id {
+            s.absorb(id.to_bytes());
+        }
         s.absorb(&cursor.branch_no.to_be_bytes());
         s.absorb(&cursor.seq_no.to_be_bytes());
         s.commit();
@@ -305,11 +495,12 @@ impl<F: PRP> DefaultTangleLinkGenerator<F> {
     }
 }
 
-impl<F: PRP> LinkGenerator<TangleAddress> for DefaultTangleLinkGenerator<F> {
+impl<F: PRP> LinkGenerator<TangleAddress> for TopicTangleLinkGenerator<F> {
     /// Used by Author to generate a new application instance: channels address and announcement message identifier
     fn gen(&mut self, pk: &ed25519::PublicKey, channel_idx: u64) {
-        self.addr.appinst = AppInst::new(pk, channel_idx);
-        self.addr.msgid = self.gen_msgid(&Identifier::EdPubKey((*pk).into()), Cursor::default().as_ref());
+        let mut link_gen = DefaultTangleLinkGenerator::<F>::default();
+        link_gen.gen(pk, channel_idx);
+        self.addr = link_gen.get();
     }
 
     /// Used by Author to get announcement message id, it's just stored internally by link generator
@@ -322,19 +513,19 @@ impl<F: PRP> LinkGenerator<TangleAddress> for DefaultTangleLinkGenerator<F> {
         self.addr = announcement_link;
     }
 
-    /// Used by users to pseudo-randomly generate a new uniform message link from a cursor
+    /// Used by users to generate a new uniform message link from the topic and a cursor
     fn uniform_link_from(&self, cursor: Cursor<&MsgId>) -> TangleAddress {
         TangleAddress {
             appinst: self.addr.appinst.clone(),
-            msgid: self.gen_uniform_msgid(cursor),
+            msgid: self.gen_topic_msgid(None, cursor),
         }
     }
 
-    /// Used by users to pseudo-randomly generate a new message link from a cursor
+    /// Used by users to generate a new message link from the topic and a cursor
     fn link_from(&self, id: &Identifier, cursor: Cursor<&MsgId>) -> TangleAddress {
         TangleAddress {
             appinst: self.addr.appinst.clone(),
-            msgid: self.gen_msgid(id, cursor),
+            msgid: self.gen_topic_msgid(Some(id), cursor),
         }
     }
 }
@@ -358,6 +549,37 @@ impl AppInst {
             id: unsafe { core::mem::transmute(id) },
         }
     }
+
+    /// Derive channel index from a channel name agreed upon by the parties.
+    ///
+    /// The index is a spongos hash of the author public key and the label with the most
+    /// significant bit set, so it can't clash with sequentially allocated indices.
+    pub fn label_index<F: PRP>(pk: &ed25519::PublicKey, label: &str) -> u64 {
+        let mut s = Spongos::<F>::init();
+        s.absorb(b"appinst-label");
+        s.absorb(pk.as_bytes());
+        s.absorb(&(label.len() as u64).to_be_bytes());
+        s.absorb(label.as_bytes());
+        s.commit();
+        let mut idx = [0_u8; 8];
+        s.squeeze(&mut idx);
+        u64::from_be_bytes(idx) | (1 << 63)
+    }
+
+    /// Application instance of the channel named `label` created by the author with public key `pk`.
+    pub fn from_label<F: PRP>(pk: &ed25519::PublicKey, label: &str) -> Self {
+        Self::new(pk, Self::label_index::<F>(pk, label))
+    }
+
+    /// Parse appinst from lowercase hex string.
+    pub fn from_hex(s: &str) -> Result<Self> {
+        Self::from_str(s)
+    }
+
+    /// Encode appinst as lowercase hex string.
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.id)
+    }
 }
 
 impl<'a> From<&'a [u8]> for AppInst {
@@ -369,19 +591,19 @@ impl<'a> From<&'a [u8]> for AppInst {
     }
 }
 
+/// Inverse of `Display`, parse appinst from lowercase hex string.
 impl FromStr for AppInst {
-    type Err = ();
-    fn from_str(s: &str) -> Result<Self, ()> {
+    type Err = iota_streams_core::Error;
+    fn from_str(s: &str) -> Result<Self> {
         // TODO: format for `s`: Bech32 (https://github.com/rust-bitcoin/rust-bech32)
         // currently lowercase hex
-        hex::decode(s).map_or(Err(()), |x| {
-            if x.len() == AppInstSize::USIZE {
-                Ok(AppInst {
-                    id: *<&NBytes<AppInstSize>>::from(&x[..]),
-                })
-            } else {
-                Err(())
-            }
+        let bytes = hex::decode(s).map_err(|e| wrapped_err!(BadHexFormat(s.into()), WrappedError(e)))?;
+        try_or!(
+            bytes.len() == AppInstSize::USIZE,
+            LengthMismatch(AppInstSize::USIZE, bytes.len())
+        )?;
+        Ok(AppInst {
+            id: *<&NBytes<AppInstSize>>::from(&bytes[..]),
         })
     }
 }
@@ -405,6 +627,19 @@ impl PartialEq for AppInst {
 }
 impl Eq for AppInst {}
 
+/// Application instances are ordered lexicographically over their bytes.
+impl PartialOrd for AppInst {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for AppInst {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_ref().cmp(other.as_ref())
+    }
+}
+
 impl AsRef<[u8]> for AppInst {
     fn as_ref(&self) -> &[u8] {
         self.id.as_ref()
@@ -463,6 +698,70 @@ pub struct MsgId {
     pub(crate) id: NBytes<MsgIdSize>,
 }
 
+impl MsgId {
+    /// Parse msgid from lowercase hex string.
+    pub fn from_hex(s: &str) -> Result<Self> {
+        Self::from_str(s)
+    }
+
+    /// Encode msgid as lowercase hex string.
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.id)
+    }
+
+    /// Derive identifier of the message published by `publisher` in application instance `appinst`
+    /// from the publisher's sequencing state `cursor`. This is the derivation `DefaultTangleLinkGenerator`
+    /// uses, it allows external tools to compute message addresses without a user:
+    ///
+    /// ```ddml
+    /// absorb u8 appinst[40];
+    /// absorb u8 publisher[];
+    /// absorb u8 link[12];
+    /// absorb u32 branch_no;
+    /// absorb u32 seq_no;
+    /// commit;
+    /// squeeze u8 msgid[12];
+    /// ```
+    ///
+    /// `publisher` is the ed25519 public key or the pre-shared key identifier bytes, `link` is the
+    /// identifier of the cursor message, sequence numbers are encoded in big endian. The announcement
+    /// identifier is derived from the author's public key and the default cursor.
+    pub fn derive<F: PRP>(appinst: &AppInst, publisher: &Identifier, cursor: Cursor<&MsgId>) -> MsgId {
+        Self::derive_in_domain::<F>(&[], appinst, publisher, cursor)
+    }
+
+    /// Derive message identifier within a network `domain`. A non-empty domain is absorbed first,
+    /// prefixed with its length:
+    ///
+    /// ```ddml
+    /// absorb u64 domain_len;
+    /// absorb u8 domain[domain_len];
+    /// ```
+    ///
+    /// The rest of the derivation is the one of `derive`, which is the derivation in the empty domain.
+    pub fn derive_in_domain<F: PRP>(
+        domain: &[u8],
+        appinst: &AppInst,
+        publisher: &Identifier,
+        cursor: Cursor<&MsgId>,
+    ) -> MsgId {
+        let mut s = Spongos::<F>::init();
+        if !domain.is_empty() {
+            s.absorb(&(domain.len() as u64).to_be_bytes());
+            s.absorb(domain);
+        }
+        s.absorb(appinst.id.as_ref());
+        s.absorb(publisher.to_bytes());
+        s.absorb(cursor.link.id.as_ref());
+        s.absorb(&cursor.branch_no.to_be_bytes());
+        s.absorb(&cursor.seq_no.to_be_bytes());
+        s.commit();
+        let mut new = MsgId::default();
+        s.squeeze(new.id.as_mut());
+        new
+    }
+}
+
 impl<'a> From<&'a [u8]> for MsgId {
     fn from(v: &[u8]) -> MsgId {
         MsgId {
@@ -472,19 +771,19 @@ impl<'a> From<&'a [u8]> for MsgId {
     }
 }
 
+/// Inverse of `Display`, parse msgid from lowercase hex string.
 impl FromStr for MsgId {
-    type Err = ();
-    fn from_str(s: &str) -> Result<Self, ()> {
+    type Err = iota_streams_core::Error;
+    fn from_str(s: &str) -> Result<Self> {
         // TODO: format for `s`: Bech32 (https://github.com/rust-bitcoin/rust-bech32)
         // currently lowercase hex
-        hex::decode(s).map_or(Err(()), |x| {
-            if x.len() == MsgIdSize::USIZE {
-                Ok(MsgId {
-                    id: *<&NBytes<MsgIdSize>>::from(&x[..]),
-                })
-            } else {
-                Err(())
-            }
+        let bytes = hex::decode(s).map_err(|e| wrapped_err!(BadHexFormat(s.into()), WrappedError(e)))?;
+        try_or!(
+            bytes.len() == MsgIdSize::USIZE,
+            LengthMismatch(MsgIdSize::USIZE, bytes.len())
+        )?;
+        Ok(MsgId {
+            id: *<&NBytes<MsgIdSize>>::from(&bytes[..]),
         })
     }
 }
@@ -514,6 +813,19 @@ impl PartialEq for MsgId {
 }
 impl Eq for MsgId {}
 
+/// Message identifiers are ordered lexicographically over their bytes.
+impl PartialOrd for MsgId {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for MsgId {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_ref().cmp(other.as_ref())
+    }
+}
+
 impl AsRef<[u8]> for MsgId {
     fn as_ref(&self) -> &[u8] {
         self.id.as_ref()
@@ -562,3 +874,121 @@ impl<F: PRP> AbsorbFallback<F> for MsgId {
 /// crate for node interfacing
 #[cfg(any(feature = "sync-client", feature = "async-client", feature = "wasm-client"))]
 pub mod client;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iota_streams_core_keccak::sponge::prp::keccak::KeccakF1600;
+
+    fn test_address() -> TangleAddress {
+        let appinst = AppInst::from(&(0..APPINST_SIZE as u8).collect::<Vec<u8>>()[..]);
+        let msgid = MsgId::from(&(100..100 + MSGID_SIZE as u8).collect::<Vec<u8>>()[..]);
+        TangleAddress::new(appinst, msgid)
+    }
+
+    #[test]
+    fn components_roundtrip() {
+        let addr = test_address();
+        assert_eq!(addr.appinst, format!("{}", addr.appinst).parse::<AppInst>().unwrap());
+        assert_eq!(addr.msgid, format!("{}", addr.msgid).parse::<MsgId>().unwrap());
+        assert_eq!(addr.appinst, AppInst::from_hex(&addr.appinst.to_hex()).unwrap());
+        assert_eq!(addr.msgid, MsgId::from_hex(&addr.msgid.to_hex()).unwrap());
+    }
+
+    #[test]
+    fn address_roundtrip() {
+        let addr = test_address();
+        assert_eq!(addr, format!("{}", addr).parse::<TangleAddress>().unwrap());
+        assert_eq!(addr, addr.to_string().parse::<TangleAddress>().unwrap());
+        assert_eq!(addr, TangleAddress::from_hex(&addr.to_hex()).unwrap());
+        assert_eq!(format!("{}", addr), addr.to_hex());
+        assert!(addr.to_msg_index().parse::<TangleAddress>().is_err());
+    }
+
+    #[test]
+    fn addresses_are_ordered_by_bytes() {
+        let addr = test_address();
+        let mut msgid = MsgId::from(addr.msgid.as_ref());
+        msgid.id.as_mut()[0] += 1;
+        let next_msg = TangleAddress::new(addr.appinst.clone(), msgid);
+        let mut appinst = AppInst::from(addr.appinst.as_ref());
+        appinst.id.as_mut()[APPINST_SIZE - 1] += 1;
+        let next_app = TangleAddress::new(appinst, MsgId::default());
+
+        assert!(addr < next_msg && next_msg < next_app);
+        assert_eq!(addr.cmp(&addr.clone()), Ordering::Equal);
+        let mut addrs = vec![next_app.clone(), addr.clone(), next_msg.clone()];
+        addrs.sort();
+        assert_eq!(addrs, vec![addr, next_msg, next_app]);
+    }
+
+    #[test]
+    fn address_rejects_malformed() {
+        let addr = test_address();
+        let appinst = addr.appinst.to_hex();
+        let msgid = addr.msgid.to_hex();
+        assert!(TangleAddress::from_hex("").is_err());
+        assert!(TangleAddress::from_hex(&appinst).is_err());
+        assert!(TangleAddress::from_hex(&[&appinst[..], &msgid[..], &msgid[..]].join(":")).is_err());
+        assert!(TangleAddress::from_hex(&[&msgid[..], &appinst[..]].join(":")).is_err());
+        assert!(TangleAddress::from_hex(&[&appinst[..], &msgid[1..]].join(":")).is_err());
+        assert!(TangleAddress::from_hex(&[&appinst[..], &msgid.replace('6', "x")[..]].join(":")).is_err());
+        assert!(MsgId::from_hex(&appinst).is_err());
+        assert!(AppInst::from_hex(&msgid).is_err());
+        assert!(msgid[1..].parse::<MsgId>().is_err());
+        assert!(appinst.replace('6', "x").parse::<AppInst>().is_err());
+    }
+
+    #[test]
+    fn msgid_derivation_vectors() {
+        let addr = test_address();
+        // Compressed ed25519 base point
+        let mut pk_bytes = [0x66_u8; 32];
+        pk_bytes[0] = 0x58;
+        let pk = Identifier::from_bytes(&pk_bytes).unwrap();
+        let pskid = Identifier::from_bytes(&(200..216).collect::<Vec<u8>>()).unwrap();
+
+        let derive =
+            |id: &Identifier, cursor: Cursor<&MsgId>| MsgId::derive::<KeccakF1600>(&addr.appinst, id, cursor).to_hex();
+        assert_eq!(
+            derive(&pk, Cursor::new_at(&addr.msgid, 0, 2)),
+            "eb68a769300cc1617392c495"
+        );
+        assert_eq!(
+            derive(&pskid, Cursor::new_at(&addr.msgid, 1, 7)),
+            "0ba2e0e79ed54cd8b2426c6d"
+        );
+        assert_eq!(derive(&pk, Cursor::default().as_ref()), "41b2f1300994d2b1ae0165f4");
+
+        let mut link_gen = DefaultTangleLinkGenerator::<KeccakF1600>::default();
+        link_gen.reset(addr.clone());
+        assert_eq!(
+            link_gen
+                .link_from(&pk, Cursor::new_at(&addr.msgid, 0, 2))
+                .msgid
+                .to_hex(),
+            "eb68a769300cc1617392c495"
+        );
+    }
+
+    #[test]
+    fn msgid_derivation_domains() {
+        let addr = test_address();
+        let mut pk_bytes = [0x66_u8; 32];
+        pk_bytes[0] = 0x58;
+        let pk = Identifier::from_bytes(&pk_bytes).unwrap();
+        let cursor = || Cursor::new_at(&addr.msgid, 0, 2);
+        let derive = |domain: &[u8]| MsgId::derive_in_domain::<KeccakF1600>(domain, &addr.appinst, &pk, cursor());
+
+        assert_eq!(derive(b""), MsgId::derive::<KeccakF1600>(&addr.appinst, &pk, cursor()));
+        assert_ne!(derive(b"mainnet"), derive(b""));
+        assert_ne!(derive(b"mainnet"), derive(b"testnet"));
+
+        let mut link_gen = DefaultTangleLinkGenerator::<KeccakF1600>::new_in_domain(b"testnet");
+        link_gen.reset(addr.clone());
+        assert_eq!(link_gen.link_from(&pk, cursor()).msgid, derive(b"testnet"));
+        let mut default_gen = DefaultTangleLinkGenerator::<KeccakF1600>::default();
+        default_gen.reset(addr.clone());
+        assert_ne!(link_gen.uniform_link_from(cursor()), default_gen.uniform_link_from(cursor()));
+    }
+}
@@ -0,0 +1,332 @@
+use super::*;
+
+use iota_streams_core::{
+    Error,
+    Errors::{
+        self,
+        MessageLinkNotFound,
+        MessageLinkNotFoundInTangle,
+    },
+};
+
+/// Policy deciding whether a call mirrored to both transports succeeds.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TeePolicy {
+    /// Fail if either transport fails.
+    RequireBoth,
+    /// Succeed if at least one transport succeeds.
+    RequireAny,
+}
+
+impl Default for TeePolicy {
+    fn default() -> Self {
+        TeePolicy::RequireBoth
+    }
+}
+
+/// Check whether receiving failed because the message is missing rather than because
+/// the transport failed.
+fn is_not_found(e: &Error) -> bool {
+    matches!(
+        e.downcast_ref::<Errors>(),
+        Some(MessageLinkNotFound(_)) | Some(MessageLinkNotFoundInTangle(_))
+    )
+}
+
+/// Transport wrapper mirroring messages to two transports.
+///
+/// Messages are sent and deleted on both the primary and the secondary transport, the `TeePolicy`
+/// decides whether a failure of one of them fails the call. Messages are received from the primary
+/// transport, the secondary one is queried only if the message is not found in the primary.
+#[derive(Clone)]
+pub struct Tee<A, B> {
+    primary: A,
+    secondary: B,
+    policy: TeePolicy,
+}
+
+impl<A, B> Tee<A, B> {
+    /// Mirror `primary` transport to `secondary`.
+    pub fn new(primary: A, secondary: B, policy: TeePolicy) -> Self {
+        Self {
+            primary,
+            secondary,
+            policy,
+        }
+    }
+
+    /// Get policy applied to sends and deletes.
+    pub fn policy(&self) -> TeePolicy {
+        self.policy
+    }
+
+    /// Set policy applied to sends and deletes.
+    pub fn set_policy(&mut self, policy: TeePolicy) {
+        self.policy = policy;
+    }
+
+    /// Get reference to the primary transport.
+    pub fn primary(&self) -> &A {
+        &self.primary
+    }
+
+    /// Get mutable reference to the primary transport.
+    pub fn primary_mut(&mut self) -> &mut A {
+        &mut self.primary
+    }
+
+    /// Get reference to the secondary transport.
+    pub fn secondary(&self) -> &B {
+        &self.secondary
+    }
+
+    /// Get mutable reference to the secondary transport.
+    pub fn secondary_mut(&mut self) -> &mut B {
+        &mut self.secondary
+    }
+
+    /// Unwrap primary and secondary transports.
+    pub fn into_inner(self) -> (A, B) {
+        (self.primary, self.secondary)
+    }
+
+    /// Combine results of a call mirrored to both transports, the primary error is reported
+    /// when both calls fail.
+    fn combine(&self, primary: Result<()>, secondary: Result<()>) -> Result<()> {
+        match self.policy {
+            TeePolicy::RequireBoth => primary.and(secondary),
+            TeePolicy::RequireAny => match (primary, secondary) {
+                (Err(e), Err(_)) => Err(e),
+                _ => Ok(()),
+            },
+        }
+    }
+}
+
+impl<A: TransportOptions, B: TransportOptions> TransportOptions for Tee<A, B> {
+    type SendOptions = (A::SendOptions, B::SendOptions);
+    fn get_send_options(&self) -> Self::SendOptions {
+        (self.primary.get_send_options(), self.secondary.get_send_options())
+    }
+    fn set_send_options(&mut self, opt: Self::SendOptions) {
+        self.primary.set_send_options(opt.0);
+        self.secondary.set_send_options(opt.1);
+    }
+
+    type RecvOptions = (A::RecvOptions, B::RecvOptions);
+    fn get_recv_options(&self) -> Self::RecvOptions {
+        (self.primary.get_recv_options(), self.secondary.get_recv_options())
+    }
+    fn set_recv_options(&mut self, opt: Self::RecvOptions) {
+        self.primary.set_recv_options(opt.0);
+        self.secondary.set_recv_options(opt.1);
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl<A: TransportDetails<Link>, B, Link> TransportDetails<Link> for Tee<A, B> {
+    type Details = <A as TransportDetails<Link>>::Details;
+    fn get_link_details(&mut self, link: &Link) -> Result<Self::Details> {
+        self.primary.get_link_details(link)
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl<Link: Debug + Display, Msg, A: Transport<Link, Msg>, B: Transport<Link, Msg>> Transport<Link, Msg> for Tee<A, B> {
+    /// Send a message to both transports.
+    fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        let primary = self.primary.send_message(msg);
+        let secondary = self.secondary.send_message(msg);
+        self.combine(primary, secondary)
+    }
+
+    /// Receive messages from the primary transport, falling back to the secondary one.
+    fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        match self.primary.recv_messages(link) {
+            Err(e) if is_not_found(&e) => self.secondary.recv_messages(link),
+            r => r,
+        }
+    }
+
+    /// Receive a message from the primary transport, falling back to the secondary one.
+    fn recv_message(&mut self, link: &Link) -> Result<Msg> {
+        match self.primary.recv_message(link) {
+            Err(e) if is_not_found(&e) => self.secondary.recv_message(link),
+            r => r,
+        }
+    }
+
+    /// Delete messages from both transports.
+    fn delete_message(&mut self, link: &Link) -> Result<()> {
+        let primary = self.primary.delete_message(link);
+        let secondary = self.secondary.delete_message(link);
+        self.combine(primary, secondary)
+    }
+
+    /// Wait for confirmation by both transports.
+    fn await_confirmation(&mut self, link: &Link) -> Result<()> {
+        let primary = self.primary.await_confirmation(link);
+        let secondary = self.secondary.await_confirmation(link);
+        self.combine(primary, secondary)
+    }
+
+    /// List links stored by either transport, messages are received from both of them. Fails if
+    /// either transport can't list its messages, the list would be incomplete.
+    fn list_links(&mut self, appinst: &Link) -> Result<Vec<Link>> {
+        let mut links = self.primary.list_links(appinst)?;
+        links.extend(self.secondary.list_links(appinst)?);
+        Ok(links)
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait(?Send)]
+impl<A: TransportDetails<Link>, B, Link> TransportDetails<Link> for Tee<A, B>
+where
+    Link: Send + Sync,
+{
+    type Details = <A as TransportDetails<Link>>::Details;
+    async fn get_link_details(&mut self, link: &Link) -> Result<Self::Details> {
+        self.primary.get_link_details(link).await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait(?Send)]
+impl<Link, Msg, A: Transport<Link, Msg>, B: Transport<Link, Msg>> Transport<Link, Msg> for Tee<A, B>
+where
+    Link: Send + Sync,
+    Msg: Send + Sync,
+{
+    /// Send a message to both transports.
+    async fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        let primary = self.primary.send_message(msg).await;
+        let secondary = self.secondary.send_message(msg).await;
+        self.combine(primary, secondary)
+    }
+
+    /// Receive messages from the primary transport, falling back to the secondary one.
+    async fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        match self.primary.recv_messages(link).await {
+            Err(e) if is_not_found(&e) => self.secondary.recv_messages(link).await,
+            r => r,
+        }
+    }
+
+    /// Receive a message from the primary transport, falling back to the secondary one.
+    async fn recv_message(&mut self, link: &Link) -> Result<Msg> {
+        match self.primary.recv_message(link).await {
+            Err(e) if is_not_found(&e) => self.secondary.recv_message(link).await,
+            r => r,
+        }
+    }
+
+    /// Delete messages from both transports.
+    async fn delete_message(&mut self, link: &Link) -> Result<()> {
+        let primary = self.primary.delete_message(link).await;
+        let secondary = self.secondary.delete_message(link).await;
+        self.combine(primary, secondary)
+    }
+
+    /// Wait for confirmation by both transports.
+    async fn await_confirmation(&mut self, link: &Link) -> Result<()> {
+        let primary = self.primary.await_confirmation(link).await;
+        let secondary = self.secondary.await_confirmation(link).await;
+        self.combine(primary, secondary)
+    }
+
+    /// List links stored by either transport, messages are received from both of them. Fails if
+    /// either transport can't list its messages, the list would be incomplete.
+    async fn list_links(&mut self, appinst: &Link) -> Result<Vec<Link>> {
+        let mut links = self.primary.list_links(appinst).await?;
+        links.extend(self.secondary.list_links(appinst).await?);
+        Ok(links)
+    }
+}
+
+#[cfg(all(test, not(feature = "async")))]
+mod tests {
+    use super::*;
+    use crate::message::LinkedMessage;
+    use iota_streams_core::{
+        err,
+        Errors::TransportNotAvailable,
+    };
+
+    #[derive(Clone)]
+    struct TestMessage(u32);
+
+    impl LinkedMessage<u32> for TestMessage {
+        fn link(&self) -> &u32 {
+            &self.0
+        }
+        fn prev_link(&self) -> &u32 {
+            &self.0
+        }
+    }
+
+    /// Transport failing all the calls.
+    struct DownTransport;
+
+    impl TransportOptions for DownTransport {
+        type SendOptions = ();
+        fn get_send_options(&self) {}
+        fn set_send_options(&mut self, _opt: ()) {}
+
+        type RecvOptions = ();
+        fn get_recv_options(&self) {}
+        fn set_recv_options(&mut self, _opt: ()) {}
+    }
+
+    impl TransportDetails<u32> for DownTransport {
+        type Details = ();
+        fn get_link_details(&mut self, _link: &u32) -> Result<()> {
+            err!(TransportNotAvailable)
+        }
+    }
+
+    impl Transport<u32, TestMessage> for DownTransport {
+        fn send_message(&mut self, _msg: &TestMessage) -> Result<()> {
+            err!(TransportNotAvailable)
+        }
+
+        fn recv_messages(&mut self, _link: &u32) -> Result<Vec<TestMessage>> {
+            err!(TransportNotAvailable)
+        }
+    }
+
+    type Bucket = BucketTransport<u32, TestMessage>;
+
+    #[test]
+    fn tee_mirrors_sends_and_falls_back_on_recv() {
+        let mut tsp = Tee::new(Bucket::new(), Bucket::new(), TeePolicy::RequireBoth);
+        tsp.send_message(&TestMessage(1)).unwrap();
+        assert_eq!(tsp.primary_mut().recv_message(&1).unwrap().0, 1);
+        assert_eq!(tsp.secondary_mut().recv_message(&1).unwrap().0, 1);
+
+        tsp.secondary_mut().send_message(&TestMessage(2)).unwrap();
+        assert_eq!(tsp.recv_message(&2).unwrap().0, 2);
+        assert!(tsp.recv_message(&3).is_err());
+    }
+
+    #[test]
+    fn tee_applies_send_policy() {
+        let mut tsp = Tee::new(Bucket::new(), DownTransport, TeePolicy::RequireBoth);
+        assert!(tsp.send_message(&TestMessage(1)).is_err());
+
+        tsp.set_policy(TeePolicy::RequireAny);
+        tsp.send_message(&TestMessage(2)).unwrap();
+        assert_eq!(tsp.recv_message(&2).unwrap().0, 2);
+
+        let mut tsp = Tee::new(DownTransport, DownTransport, TeePolicy::RequireAny);
+        assert!(tsp.send_message(&TestMessage(1)).is_err());
+    }
+
+    #[test]
+    fn tee_does_not_fall_back_on_transport_failure() {
+        let mut tsp = Tee::new(DownTransport, Bucket::new(), TeePolicy::RequireAny);
+        tsp.send_message(&TestMessage(1)).unwrap();
+        assert!(tsp.recv_message(&1).is_err());
+        assert_eq!(tsp.secondary_mut().recv_message(&1).unwrap().0, 1);
+    }
+}
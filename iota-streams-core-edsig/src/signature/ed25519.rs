@@ -51,3 +51,35 @@ impl<'a> From<&'a mut PublicKey> for &'a mut PublicKeyWrap {
         unsafe { &mut *(ptr as *mut PublicKeyWrap) }
     }
 }
+
+/// Reconstruct key pair from raw secret key bytes (eg. exported from an HSM or another library).
+pub fn keypair_from_secret(secret: [u8; SECRET_KEY_LENGTH]) -> Keypair {
+    // `SecretKey::from_bytes` can only fail on input length mismatch
+    let secret = SecretKey::from_bytes(&secret).unwrap();
+    let public = PublicKey::from(&secret);
+    Keypair { secret, public }
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::{
+        Signer,
+        Verifier,
+    };
+
+    #[test]
+    fn keypair_from_secret_roundtrip() {
+        let kp = super::Keypair::generate(&mut rand::thread_rng());
+        let kp2 = super::keypair_from_secret(kp.secret.to_bytes());
+        assert_eq!(kp.public, kp2.public);
+
+        let msg = b"IOTA Streams";
+        let sig = kp2.sign(msg);
+        assert!(kp.public.verify(msg, &sig).is_ok());
+        assert_eq!(kp.sign(msg), sig);
+
+        let x_kp = crate::key_exchange::x25519::keypair_from_ed25519(&kp);
+        let x_kp2 = crate::key_exchange::x25519::keypair_from_ed25519(&kp2);
+        assert_eq!(x_kp.1.as_bytes(), x_kp2.1.as_bytes());
+    }
+}
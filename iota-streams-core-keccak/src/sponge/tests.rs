@@ -1,6 +1,7 @@
 use super::prp::keccak::KeccakF1600;
 use iota_streams_core::{
     prelude::typenum::Unsigned,
+    psk,
     sponge::{
         prp::PRP,
         tests::*,
@@ -26,3 +27,21 @@ fn encrypt_decrypt_keccak_byte() {
     encrypt_decrypt_n::<KeccakF1600>(rate + 28);
     encrypt_decrypt_n::<KeccakF1600>(2 * rate);
 }
+
+#[test]
+fn psk_from_passphrase_keccak_byte() {
+    let psk1 = psk::psk_from_passphrase::<KeccakF1600>("correct horse battery staple");
+    let psk2 = psk::psk_from_passphrase::<KeccakF1600>("correct horse battery staple");
+    assert_eq!(psk1, psk2);
+    assert_eq!(
+        psk::pskid_from_psk::<KeccakF1600>(&psk1),
+        psk::pskid_from_psk::<KeccakF1600>(&psk2)
+    );
+
+    let psk3 = psk::psk_from_passphrase::<KeccakF1600>("correct horse battery stapler");
+    assert_ne!(psk1, psk3);
+    assert_ne!(
+        psk::pskid_from_psk::<KeccakF1600>(&psk1),
+        psk::pskid_from_psk::<KeccakF1600>(&psk3)
+    );
+}
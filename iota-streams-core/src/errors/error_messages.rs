@@ -28,6 +28,8 @@ pub enum Errors {
     PskAlreadyStored,
     /// Input string {0} is not in hex format
     BadHexFormat(String),
+    /// Input string {0} is not a valid address, expected `<appinst hex>:<msgid hex>`
+    BadAddressFormat(String),
 
     //////////
     // Cryptographic
@@ -48,10 +50,16 @@ pub enum Errors {
     XPublicKeyGenerationFailure,
     /// Integrity violation. Bad MAC
     BadMac,
-    /// No default Random Number Generator available for no_std usage
+    /// No default Random Number Generator available for no_std usage, set one with `prng::set_entropy_source`
     NoStdRngMissing,
     /// Oneof value is unexpected
     BadOneof,
+    /// Declared length of bytes {0} exceeds the limit {1}
+    BytesLengthExceeded(usize, usize),
+    /// Bytes {0} are not a valid utf-8 string
+    InvalidUtf8String(String),
+    /// Value {0} is not a valid char
+    InvalidChar(u32),
 
     //////////
     // DDML IO
@@ -64,6 +72,8 @@ pub enum Errors {
     OutputStreamNotFullyConsumed(usize),
     /// Input stream has not been exhausted. Remaining: {0}
     InputStreamNotFullyConsumed(usize),
+    /// Wrap checkpoint does not fit the output buffer (remaining: {0}, buffer size: {1})
+    CheckpointExceedsBuffer(usize, usize),
 
     //////////
     // Generic Transport
@@ -76,6 +86,10 @@ pub enum Errors {
     MessageLinkNotFoundInTangle(String),
     /// Transport object is already borrowed
     TransportNotAvailable,
+    /// Transport does not support deleting messages
+    MessageDeletionUnsupported,
+    /// Transport does not support listing messages
+    MessageListingUnsupported,
 
     //////////
     // Iota Client
@@ -108,18 +122,56 @@ pub enum Errors {
     InvalidMsgVersion(u8, u8),
     /// Message frame type not supported (expected: {0}, found: {1})
     InvalidMsgType(u8, u8),
+    /// Payload frame type is not known (found: {0})
+    InvalidPcfFrameType(u8),
     /// Message type is not known (found: {0})
     UnknownMsgType(u8),
     /// Reserved bits are improperly formatted
     InvalidBitReservation,
+    /// Message is joined to a wrong state of the linked message {0}, fingerprint does not match
+    LinkedSpongosMismatch(String),
+    /// Message is linked to a malformed address {0}
+    MalformedLinkedMsgAddress(String),
     /// Message is not an announcement (found: {0})
     NotAnnouncement(u8),
+    /// Message is not a signed packet (found: {0})
+    NotSignedPacket(u8),
+    /// Message is not a close message (found: {0})
+    NotCloseMessage(u8),
+    /// Message is not an add author message (found: {0})
+    NotAddAuthorMessage(u8),
+    /// Message is not a seal membership message (found: {0})
+    NotSealMembershipMessage(u8),
+    /// Message is not an ack message (found: {0})
+    NotAckMessage(u8),
+    /// Message is not a checkpoint (found: {0})
+    NotCheckpointMessage(u8),
+    /// Message differs from the message processed before at the same link, it may be replayed or tampered with
+    MessageContentMismatch,
+    /// Message is not published by the expected publisher (expected: {0}, found: {1})
+    UnexpectedPublisher(String, String),
     /// Message info provided is not registered (found: {0})
     BadMessageInfo(u8),
     /// Failed to make message
     MessageCreationFailure,
     /// Identifier could not be generated with given bytes. Must be an ed25519 Public Key or a PskId
     IdentifierGenerationFailure,
+    /// Metadata keys must be distinct utf-8 strings in ascending order (found: {0})
+    BadMetadataKey(String),
+    /// Payload codec {0} is not supported
+    UnsupportedPayloadCodec(u8),
+    /// Payload could not be decompressed
+    PayloadDecompressionFailure,
+    /// Masked payload block size must not be zero
+    ZeroMaskedBlockSize,
+    /// Tagged packet MAC length is not supported (expected: 16 | 24 | 32 up to {0}, found: {1})
+    TagMacLengthUnsupported(usize, usize),
+    /// Masked payload block {0} has unexpected length {1}
+    MaskedBlockLengthMismatch(usize, usize),
+    /// Message of {0} bytes exceeds the maximum message size of {1} bytes
+    MessageSizeExceeded(usize, usize),
+    /// Message breaks the sequence of its publisher (expected: {0}, found: {1})
+    SequenceGap(u32, u32),
 
     //////////
     // Users
@@ -138,12 +190,68 @@ pub enum Errors {
     AuthorSigKeyNotFound,
     /// Error retrieving sequence number for message preparation: No sequence number generated
     SeqNumRetrievalFailure,
+    /// Message {0} of the publisher was already processed, the next expected message is {1}
+    SeqNumAlreadyProcessed(usize, usize),
+    /// No message {0} of the publisher at the computed address {1}
+    SeqNumMessageNotFound(usize, String),
+    /// Message {0} of the publisher at {1} is linked to a message which was not processed
+    SeqNumMessageOrphaned(usize, String),
+    /// Messages sent within a transaction are published once it succeeds, they can't be confirmed before
+    ConfirmationInTransaction,
     /// State store has failed to retrieve
     StateStoreFailure,
     /// Cannot generate new channel, it may already exists. please try using a different seed
     ChannelDuplication,
     /// Subscriber already has a psk stored, cannot add another
     SinglePskAllowance,
+    /// Publisher {0} is not known to the user
+    UnknownPublisher(String),
+    /// Channel author {0} is not trusted by the user
+    UntrustedAuthor(String),
+    /// Close message is not signed by the channel author (found: {0})
+    CloseNotFromAuthor(String),
+    /// Add author message is not signed by a trusted channel author (found: {0})
+    AddAuthorNotFromAuthor(String),
+    /// Seal membership message is not signed by a trusted channel author (found: {0})
+    SealNotFromAuthor(String),
+    /// Checkpoint message is not signed by a trusted channel author (found: {0})
+    CheckpointNotFromAuthor(String),
+    /// Checkpoint digest does not match the state of the summarized message {0}
+    CheckpointDigestMismatch(String),
+    /// Checkpoints are not supported in multi-branch channels
+    CheckpointsUnsupported,
+    /// Channel membership is sealed, subscription of {0} is rejected
+    MembershipSealed(String),
+    /// Channel {0} is not managed by the user
+    UnknownChannel(String),
+    /// Keyload has {0} recipients, more than the limit of {1}; recipients can be split across several keyloads
+    TooManySubscribers(usize, usize),
+    /// More than {0} orphaned messages are buffered, their linked messages may never be found
+    TooManyOrphans(usize),
+    /// Standalone packets are not supported in multi-branch channels
+    StandalonePacketsUnsupported,
+    /// Standalone packets are attached to the announcement, not to {0}
+    StandaloneLinkMismatch(String),
+    /// Only the channel author can create invitations (found: {0})
+    InvitationNotFromAuthor(String),
+    /// Invitation exchange key is not derived from the signature key of author {0}
+    InvitationKeyMismatch(String),
+    /// Announcement is not published by the invitation author (expected: {0}, found: {1})
+    InvitationAuthorMismatch(String, String),
+    /// Psk {0} is not stored by the user
+    UnknownPsk(String),
+    /// Subscriber group {0} is not defined or has no members
+    UnknownSubscriberGroup(String),
+    /// Sealed cursor snapshot can not be verified: it is corrupted, tampered with, taken in another channel or the password is wrong
+    CursorSnapshotIntegrityFailure,
+    /// Keyload {0} is rejected by the keyload policy
+    KeyloadPolicyViolation(String),
+    /// Provenance chain links back to message {0} twice
+    ProvenanceCycle(String),
+    /// Message {0} on the provenance chain is not found
+    ProvenanceLinkMissing(String),
+    /// Provenance chain ends at {1} instead of the channel announcement {0}
+    ProvenanceRootMismatch(String, String),
 
     //////////
     // User Recovery
@@ -156,6 +264,14 @@ pub enum Errors {
     UserVersionRecoveryFailure(u8, u8),
     /// Recovered flag does not match expected: flag (expected: {0}, found: {1})
     UserFlagRecoveryFailure(u8, u8),
+    /// Backup MAC length is not supported (min: {0}, max: {1}, found: {2})
+    BackupMacLengthUnsupported(usize, usize, usize),
+    /// Backup MAC length does not match the length stored in the backup (expected: {0}, found: {1})
+    BackupMacLengthMismatch(usize, usize),
+    /// Restored user state is inconsistent: {0}
+    CorruptBackup(String),
+    /// Backup format version is not supported, the backup was made by a later version (found: {0}, supported: {1})
+    UnsupportedBackupVersion(u8, u8),
 
     //////////
     // Examples
@@ -20,6 +20,7 @@ use crate::{
         },
     },
 };
+use rand::RngCore as _;
 
 /// Generate cryptographically secure bytes.
 /// Suitable for generating session and ephemeral keys.
@@ -32,34 +33,99 @@ where
     rnd
 }
 
-pub type Nonce = GenericArray<u8, U16>;
+#[cfg(not(feature = "std"))]
+mod entropy {
+    use core::sync::atomic::{
+        AtomicPtr,
+        Ordering,
+    };
 
-/// Generate a random nonce.
-#[cfg(feature = "std")]
-pub fn random_nonce() -> Nonce {
-    random_bytes::<rand::rngs::ThreadRng, U16>(&mut rand::thread_rng())
+    static SOURCE: AtomicPtr<()> = AtomicPtr::new(core::ptr::null_mut());
+
+    pub fn set(source: fn(&mut [u8])) {
+        SOURCE.store(source as *mut (), Ordering::Release);
+    }
+
+    pub fn get() -> Option<fn(&mut [u8])> {
+        let source = SOURCE.load(Ordering::Acquire);
+        if source.is_null() {
+            None
+        } else {
+            // Only valid `fn(&mut [u8])` pointers are ever stored in `SOURCE`.
+            Some(unsafe { core::mem::transmute::<*mut (), fn(&mut [u8])>(source) })
+        }
+    }
 }
 
+/// Register entropy source for `no_std` environments (eg. hardware RNG of an embedded target).
+/// The source must fill the buffer with cryptographically secure random bytes.
 #[cfg(not(feature = "std"))]
-pub fn random_nonce() -> Nonce {
-    // TODO: Set default global RNG for `no_std` environment.
-    // Use Rng and init with entropy.
-    panic!("No default global RNG present.");
+pub fn set_entropy_source(source: fn(&mut [u8])) {
+    entropy::set(source)
 }
 
-pub type Key = GenericArray<u8, U32>;
-
-/// Generate a random key.
+/// Check whether `DefaultRng` can be used: always with `std`, after `set_entropy_source` otherwise.
 #[cfg(feature = "std")]
-pub fn random_key() -> Key {
-    random_bytes::<rand::rngs::ThreadRng, U32>(&mut rand::thread_rng())
+pub fn has_default_rng() -> bool {
+    true
 }
 
+/// Check whether `DefaultRng` can be used: always with `std`, after `set_entropy_source` otherwise.
 #[cfg(not(feature = "std"))]
+pub fn has_default_rng() -> bool {
+    entropy::get().is_some()
+}
+
+/// Default cryptographically secure RNG used for session keys, nonces and ephemeral keys.
+/// It is `rand::thread_rng` with `std` and the entropy source registered with `set_entropy_source`
+/// in `no_std` environment.
+#[derive(Clone, Copy, Default)]
+pub struct DefaultRng;
+
+impl rand::RngCore for DefaultRng {
+    fn next_u32(&mut self) -> u32 {
+        let mut v = [0_u8; 4];
+        self.fill_bytes(&mut v);
+        u32::from_le_bytes(v)
+    }
+    fn next_u64(&mut self) -> u64 {
+        let mut v = [0_u8; 8];
+        self.fill_bytes(&mut v);
+        u64::from_le_bytes(v)
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest).expect("No default global RNG present.")
+    }
+    #[cfg(feature = "std")]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        rand::thread_rng().try_fill_bytes(dest)
+    }
+    #[cfg(not(feature = "std"))]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        match entropy::get() {
+            Some(source) => {
+                source(dest);
+                Ok(())
+            }
+            None => Err(core::num::NonZeroU32::new(rand::Error::CUSTOM_START).unwrap().into()),
+        }
+    }
+}
+
+impl rand::CryptoRng for DefaultRng {}
+
+pub type Nonce = GenericArray<u8, U16>;
+
+/// Generate a random nonce with `DefaultRng`.
+pub fn random_nonce() -> Nonce {
+    random_bytes::<DefaultRng, U16>(&mut DefaultRng)
+}
+
+pub type Key = GenericArray<u8, U32>;
+
+/// Generate a random key with `DefaultRng`.
 pub fn random_key() -> Key {
-    // TODO: Set default global RNG for `no_std` environment.
-    // Use Rng and init with entropy.
-    panic!("No default global RNG present.");
+    random_bytes::<DefaultRng, U32>(&mut DefaultRng)
 }
 
 /// Prng fixed key size.
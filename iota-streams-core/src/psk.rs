@@ -55,6 +55,11 @@ pub fn psk_from_seed<F: PRP>(seed_bytes: &[u8]) -> Psk {
     prng::Prng::<F>::init_with_seed(seed_bytes).gen_arr("PSK")
 }
 
+/// Derive a Psk from a human-memorable passphrase. Parties sharing the passphrase derive the same Psk.
+pub fn psk_from_passphrase<F: PRP>(passphrase: &str) -> Psk {
+    prng::from_seed::<F>("IOTA Streams psk passphrase", passphrase).gen_arr("PSK")
+}
+
 /// Derive a PskId from existing Psk.
 pub fn pskid_from_psk<F: PRP>(psk: &Psk) -> PskId {
     prng::Prng::<F>::init_with_seed(psk).gen_arr("PSKID")
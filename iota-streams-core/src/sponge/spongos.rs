@@ -24,6 +24,7 @@ use crate::{
     Errors::{
         LengthMismatch,
         SpongosNotCommitted,
+        ValueOutOfRange,
     },
     Result,
 };
@@ -247,6 +248,18 @@ impl<F: PRP> Spongos<F> {
         }
     }
 
+    /// Update Spongos object with a ciphertext as `decrypt` does, without recovering the plaintext.
+    pub fn skip_decrypt(&mut self, yr: impl AsRef<[u8]>) {
+        let mut y = yr.as_ref();
+        while !y.is_empty() {
+            let s = self.outer_min_mut(y.len());
+            let n = s.len();
+            s.copy_from_slice(&y[..n]);
+            y = &y[n..];
+            self.update(n);
+        }
+    }
+
     /// Decrypt buf.
     pub fn decrypt_arr<N: ArrayLength<u8>>(&mut self, y: impl AsRef<[u8]>) -> Result<GenericArray<u8, N>> {
         let mut x = GenericArray::default();
@@ -310,6 +323,40 @@ impl<F: PRP> Spongos<F> {
         try_or!(self.is_committed(), SpongosNotCommitted)?;
         Ok(self.s.inner().clone().into())
     }
+
+    /// Size of the full state export in bytes.
+    pub fn state_size() -> usize {
+        F::RateSize::USIZE + F::CapacitySize::USIZE + 4
+    }
+
+    /// Export the full state, unlike `to_inner` the state need not be committed.
+    /// The export consists of the outer state, the inner state and the position within the
+    /// outer state encoded as big-endian `u32`.
+    pub fn to_state_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::state_size());
+        bytes.extend_from_slice(self.s.outer().as_ref());
+        bytes.extend_from_slice(self.s.inner().as_ref());
+        bytes.extend_from_slice(&(self.pos as u32).to_be_bytes());
+        bytes
+    }
+
+    /// Restore Spongos object from the state exported with `to_state_bytes`.
+    pub fn from_state_bytes(bytes: &[u8]) -> Result<Self> {
+        try_or!(
+            bytes.len() == Self::state_size(),
+            LengthMismatch(Self::state_size(), bytes.len())
+        )?;
+        let (outer, rest) = bytes.split_at(F::RateSize::USIZE);
+        let (inner, pos) = rest.split_at(F::CapacitySize::USIZE);
+        let mut pos_bytes = [0_u8; 4];
+        pos_bytes.copy_from_slice(pos);
+        let pos = u32::from_be_bytes(pos_bytes) as usize;
+        try_or!(pos < F::RateSize::USIZE, ValueOutOfRange(F::RateSize::USIZE - 1, pos))?;
+
+        let mut s = F::from_inner(GenericArray::from_slice(inner));
+        s.outer_mut().as_mut_slice().copy_from_slice(outer);
+        Ok(Self { s, pos })
+    }
 }
 
 impl<F: PRP> Default for Spongos<F> {
@@ -1,4 +1,7 @@
-use iota_streams_core::Result;
+use iota_streams_core::{
+    prelude::Vec,
+    Result,
+};
 
 use super::Context;
 use crate::{
@@ -9,6 +12,8 @@ use crate::{
         ArrayLength,
         Bytes,
         Fallback,
+        Int32,
+        Int64,
         NBytes,
         Size,
         Uint16,
@@ -83,6 +88,36 @@ impl<F> Absorb<Uint64> for Context<F> {
     }
 }
 
+/// All Int32 values are encoded with 4 bytes.
+impl<F> Absorb<&Int32> for Context<F> {
+    fn absorb(&mut self, _i: &Int32) -> Result<&mut Self> {
+        self.size += 4;
+        Ok(self)
+    }
+}
+
+/// All Int32 values are encoded with 4 bytes.
+impl<F> Absorb<Int32> for Context<F> {
+    fn absorb(&mut self, i: Int32) -> Result<&mut Self> {
+        self.absorb(&i)
+    }
+}
+
+/// All Int64 values are encoded with 8 bytes.
+impl<F> Absorb<&Int64> for Context<F> {
+    fn absorb(&mut self, _i: &Int64) -> Result<&mut Self> {
+        self.size += 8;
+        Ok(self)
+    }
+}
+
+/// All Int64 values are encoded with 8 bytes.
+impl<F> Absorb<Int64> for Context<F> {
+    fn absorb(&mut self, i: Int64) -> Result<&mut Self> {
+        self.absorb(&i)
+    }
+}
+
 /// Size has var-size encoding.
 impl<F> Absorb<&Size> for Context<F> {
     fn absorb(&mut self, size: &Size) -> Result<&mut Self> {
@@ -114,6 +149,22 @@ impl<F> Absorb<Bytes> for Context<F> {
     }
 }
 
+/// Strings are encoded as utf-8 `bytes`.
+impl<'a, F> Absorb<&'a str> for Context<F> {
+    fn absorb(&mut self, s: &'a str) -> Result<&mut Self> {
+        self.size += sizeof_sizet(s.len()) + s.len();
+        Ok(self)
+    }
+}
+
+/// All chars are encoded as Uint32 with 4 bytes.
+impl<'a, F> Absorb<&'a char> for Context<F> {
+    fn absorb(&mut self, _c: &'a char) -> Result<&mut Self> {
+        self.size += 4;
+        Ok(self)
+    }
+}
+
 /// `byte [n]` is fixed-size and is encoded with `n` bytes.
 impl<'a, F, N: ArrayLength<u8>> Absorb<&'a NBytes<N>> for Context<F> {
     fn absorb(&mut self, _nbytes: &'a NBytes<N>) -> Result<&mut Self> {
@@ -160,3 +211,17 @@ impl<'a, F, T: 'a + AbsorbFallback<F>> Absorb<&'a Fallback<T>> for Context<F> {
         Ok(self)
     }
 }
+
+/// Absorb a vector, the size prefixed before the elements is also absorbed.
+impl<'a, F, T> Absorb<&'a Vec<T>> for Context<F>
+where
+    for<'b> Self: Absorb<&'b T>,
+{
+    fn absorb(&mut self, values: &'a Vec<T>) -> Result<&mut Self> {
+        self.absorb(&Size(values.len()))?;
+        for value in values {
+            self.absorb(value)?;
+        }
+        Ok(self)
+    }
+}
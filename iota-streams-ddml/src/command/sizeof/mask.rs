@@ -1,4 +1,7 @@
-use iota_streams_core::Result;
+use iota_streams_core::{
+    prelude::Vec,
+    Result,
+};
 
 use super::Context;
 use crate::{
@@ -7,6 +10,8 @@ use crate::{
         sizeof_sizet,
         ArrayLength,
         Bytes,
+        Int32,
+        Int64,
         NBytes,
         Size,
         Uint16,
@@ -80,6 +85,36 @@ impl<F> Mask<Uint64> for Context<F> {
     }
 }
 
+/// Mask Int32.
+impl<F> Mask<&Int32> for Context<F> {
+    fn mask(&mut self, _val: &Int32) -> Result<&mut Self> {
+        self.size += 4;
+        Ok(self)
+    }
+}
+
+/// Mask Int32.
+impl<F> Mask<Int32> for Context<F> {
+    fn mask(&mut self, val: Int32) -> Result<&mut Self> {
+        self.mask(&val)
+    }
+}
+
+/// Mask Int64.
+impl<F> Mask<&Int64> for Context<F> {
+    fn mask(&mut self, _val: &Int64) -> Result<&mut Self> {
+        self.size += 8;
+        Ok(self)
+    }
+}
+
+/// Mask Int64.
+impl<F> Mask<Int64> for Context<F> {
+    fn mask(&mut self, val: Int64) -> Result<&mut Self> {
+        self.mask(&val)
+    }
+}
+
 /// Mask Size.
 impl<F> Mask<&Size> for Context<F> {
     fn mask(&mut self, val: &Size) -> Result<&mut Self> {
@@ -113,6 +148,23 @@ impl<F> Mask<&Bytes> for Context<F> {
     }
 }
 
+/// Mask utf-8 string.
+impl<F> Mask<&str> for Context<F> {
+    fn mask(&mut self, s: &str) -> Result<&mut Self> {
+        self.mask(&Size(s.len()))?;
+        self.size += s.len();
+        Ok(self)
+    }
+}
+
+/// Mask char.
+impl<F> Mask<&char> for Context<F> {
+    fn mask(&mut self, _c: &char) -> Result<&mut Self> {
+        self.size += 4;
+        Ok(self)
+    }
+}
+
 impl<F> Mask<&x25519::PublicKey> for Context<F> {
     fn mask(&mut self, _pk: &x25519::PublicKey) -> Result<&mut Self> {
         self.size += x25519::PUBLIC_KEY_LENGTH;
@@ -126,3 +178,17 @@ impl<F> Mask<&ed25519::PublicKey> for Context<F> {
         Ok(self)
     }
 }
+
+/// Mask a vector, the size prefixed before the elements is also masked.
+impl<'a, F, T> Mask<&'a Vec<T>> for Context<F>
+where
+    for<'b> Self: Mask<&'b T>,
+{
+    fn mask(&mut self, values: &'a Vec<T>) -> Result<&mut Self> {
+        self.mask(&Size(values.len()))?;
+        for value in values {
+            self.mask(value)?;
+        }
+        Ok(self)
+    }
+}
@@ -4,9 +4,13 @@ use iota_streams_core::{
     prelude::{
         string::ToString,
         typenum::{
+            U1,
+            U100,
+            U3,
             U32,
             U64,
         },
+        String,
         Vec,
     },
     prng,
@@ -121,6 +125,97 @@ fn size() {
     assert!(dbg!(absorb_mask_size::<KeccakF1600>()).is_ok());
 }
 
+fn absorb_mask_int<F: PRP>() -> Result<()> {
+    let mut tag_wrap = External(NBytes::<U32>::default());
+    let mut tag_unwrap = External(NBytes::<U32>::default());
+
+    let ns = [
+        i64::MIN,
+        i32::MIN as i64 - 1,
+        i32::MIN as i64,
+        -256,
+        -1,
+        0,
+        1,
+        255,
+        i32::MAX as i64,
+        i32::MAX as i64 + 1,
+        i64::MAX,
+    ];
+
+    for n in ns.iter() {
+        let i = Int32(*n as i32);
+        let j = Int64(*n);
+        let buf_size = sizeof::Context::<F>::new()
+            .absorb(i)?
+            .mask(i)?
+            .absorb(j)?
+            .mask(j)?
+            .get_size();
+        let buf_size2 = sizeof::Context::<F>::new()
+            .absorb(&i)?
+            .mask(&i)?
+            .absorb(&j)?
+            .mask(&j)?
+            .get_size();
+        try_or!(buf_size == buf_size2, ValueMismatch(buf_size, buf_size2))?;
+        try_or!(buf_size == 24, ValueMismatch(24, buf_size))?;
+
+        let mut buf = vec![0_u8; buf_size];
+
+        {
+            let mut ctx = wrap::Context::<F, &mut [u8]>::new(&mut buf[..]);
+            ctx.commit()?
+                .absorb(&i)?
+                .mask(&i)?
+                .absorb(&j)?
+                .mask(&j)?
+                .commit()?
+                .squeeze(&mut tag_wrap)?;
+            try_or!(ctx.stream.is_empty(), OutputStreamNotFullyConsumed(ctx.stream.len()))?;
+        }
+        try_or!(
+            buf[..4] == i.0.to_be_bytes(),
+            ValueMismatch(i.0 as usize, buf[0] as usize)
+        )?;
+        try_or!(
+            buf[8..16] == j.0.to_be_bytes(),
+            ValueMismatch(j.0 as usize, buf[8] as usize)
+        )?;
+
+        let mut i2 = Int32(0);
+        let mut i3 = Int32(0);
+        let mut j2 = Int64(0);
+        let mut j3 = Int64(0);
+        {
+            let mut ctx = unwrap::Context::<F, &[u8]>::new(&buf[..]);
+            ctx.commit()?
+                .absorb(&mut i2)?
+                .mask(&mut i3)?
+                .absorb(&mut j2)?
+                .mask(&mut j3)?
+                .commit()?
+                .squeeze(&mut tag_unwrap)?;
+            try_or!(ctx.stream.is_empty(), InputStreamNotFullyConsumed(ctx.stream.len()))?;
+        }
+
+        try_or!(i == i2, ValueMismatch(i.0 as usize, i2.0 as usize))?;
+        try_or!(i == i3, ValueMismatch(i.0 as usize, i3.0 as usize))?;
+        try_or!(j == j2, ValueMismatch(j.0 as usize, j2.0 as usize))?;
+        try_or!(j == j3, ValueMismatch(j.0 as usize, j3.0 as usize))?;
+        try_or!(
+            tag_wrap == tag_unwrap,
+            InvalidTagSqueeze(tag_wrap.to_string(), tag_unwrap.to_string())
+        )?;
+    }
+    Ok(())
+}
+
+#[test]
+fn int() {
+    assert!(dbg!(absorb_mask_int::<KeccakF1600>()).is_ok());
+}
+
 fn absorb_mask_squeeze_bytes_mac<F: PRP>() -> Result<()> {
     const NS: [usize; 10] = [0, 3, 255, 256, 257, 483, 486, 489, 1002, 2001];
 
@@ -224,6 +319,234 @@ fn bytes() {
     assert!(dbg!(absorb_mask_squeeze_bytes_mac::<KeccakF1600>()).is_ok());
 }
 
+fn absorb_mask_bounded_bytes<F: PRP>() -> Result<()> {
+    let ta = Bytes(vec![1_u8; 10]);
+    let tm = Bytes(vec![2_u8; 10]);
+    let buf_size = sizeof::Context::<F>::new().absorb(&ta)?.mask(&tm)?.get_size();
+    let mut buf = vec![0_u8; buf_size];
+    {
+        let mut ctx = wrap::Context::<F, &mut [u8]>::new(&mut buf[..]);
+        ctx.absorb(&ta)?.mask(&tm)?;
+        try_or!(ctx.stream.is_empty(), OutputStreamNotFullyConsumed(ctx.stream.len()))?;
+    }
+
+    let mut ta2 = Bytes::default();
+    let mut tm2 = Bytes::default();
+    {
+        let mut ctx = unwrap::Context::<F, &[u8]>::new(&buf[..]);
+        ctx.absorb_bounded_bytes(&mut ta2, 10)?
+            .mask_bounded_bytes(&mut tm2, 10)?;
+        try_or!(ctx.stream.is_empty(), InputStreamNotFullyConsumed(ctx.stream.len()))?;
+    }
+    try_or!(ta == ta2 && tm == tm2, ValueMismatch(ta.0.len(), ta2.0.len()))?;
+
+    let mut ctx = unwrap::Context::<F, &[u8]>::new(&buf[..]);
+    try_or!(
+        ctx.absorb_bounded_bytes(&mut ta2, 9).is_err(),
+        BytesLengthExceeded(ta.0.len(), 9)
+    )?;
+    Ok(())
+}
+
+#[test]
+fn bounded_bytes() {
+    assert!(dbg!(absorb_mask_bounded_bytes::<KeccakF1600>()).is_ok());
+}
+
+fn skip_mask_bounded_bytes<F: PRP>() -> Result<()> {
+    let tm = Bytes(vec![2_u8; 300]);
+    let buf_size = sizeof::Context::<F>::new()
+        .mask(&tm)?
+        .commit()?
+        .squeeze(Mac(32))?
+        .get_size();
+    let mut buf = vec![0_u8; buf_size];
+    {
+        let mut ctx = wrap::Context::<F, &mut [u8]>::new(&mut buf[..]);
+        ctx.mask(&tm)?.commit()?.squeeze(Mac(32))?;
+        try_or!(ctx.stream.is_empty(), OutputStreamNotFullyConsumed(ctx.stream.len()))?;
+    }
+
+    let mut size = Size(0);
+    {
+        // The MAC only matches if the spongos state is the one of the unmasked bytes
+        let mut ctx = unwrap::Context::<F, &[u8]>::new(&buf[..]);
+        ctx.skip_mask_bounded_bytes(&mut size, 300)?
+            .commit()?
+            .squeeze(Mac(32))?;
+        try_or!(ctx.stream.is_empty(), InputStreamNotFullyConsumed(ctx.stream.len()))?;
+    }
+    try_or!(size.0 == tm.0.len(), ValueMismatch(tm.0.len(), size.0))?;
+
+    let mut ctx = unwrap::Context::<F, &[u8]>::new(&buf[..]);
+    try_or!(
+        ctx.skip_mask_bounded_bytes(&mut size, 299).is_err(),
+        BytesLengthExceeded(tm.0.len(), 299)
+    )?;
+    Ok(())
+}
+
+#[test]
+fn skip_masked_bytes() {
+    assert!(dbg!(skip_mask_bounded_bytes::<KeccakF1600>()).is_ok());
+}
+
+fn absorb_mask_vec<F: PRP>() -> Result<()> {
+    let ta: Vec<NBytes<U3>> = (0..5_u8)
+        .map(|i| {
+            let mut t = NBytes::<U3>::default();
+            t.as_mut()[0] = i;
+            t
+        })
+        .collect();
+    let tm: Vec<Bytes> = (0..3_u8).map(|i| Bytes(vec![i; i as usize])).collect();
+    let buf_size = sizeof::Context::<F>::new().absorb(&ta)?.mask(&tm)?.get_size();
+    let mut buf = vec![0_u8; buf_size];
+    {
+        let mut ctx = wrap::Context::<F, &mut [u8]>::new(&mut buf[..]);
+        ctx.absorb(&ta)?.mask(&tm)?;
+        try_or!(ctx.stream.is_empty(), OutputStreamNotFullyConsumed(ctx.stream.len()))?;
+    }
+
+    // Same encoding as the size followed by the elements
+    let mut buf2 = vec![0_u8; buf_size];
+    {
+        let mut ctx = wrap::Context::<F, &mut [u8]>::new(&mut buf2[..]);
+        ctx.absorb(Size(ta.len()))?
+            .repeated(ta.iter(), |ctx, t| ctx.absorb(t))?
+            .mask(&Size(tm.len()))?
+            .repeated(tm.iter(), |ctx, t| ctx.mask(t))?;
+        try_or!(ctx.stream.is_empty(), OutputStreamNotFullyConsumed(ctx.stream.len()))?;
+    }
+    try_or!(buf == buf2, ValueMismatch(buf.len(), buf2.len()))?;
+
+    let mut ta2 = vec![NBytes::<U3>::default()];
+    let mut tm2 = Vec::<Bytes>::new();
+    {
+        let mut ctx = unwrap::Context::<F, &[u8]>::new(&buf[..]);
+        ctx.absorb(&mut ta2)?.mask(&mut tm2)?;
+        try_or!(ctx.stream.is_empty(), InputStreamNotFullyConsumed(ctx.stream.len()))?;
+    }
+    try_or!(ta == ta2, ValueMismatch(ta.len(), ta2.len()))?;
+    try_or!(tm == tm2, ValueMismatch(tm.len(), tm2.len()))?;
+    Ok(())
+}
+
+#[test]
+fn vec() {
+    assert!(dbg!(absorb_mask_vec::<KeccakF1600>()).is_ok());
+}
+
+fn absorb_mask_string<F: PRP>() -> Result<()> {
+    for s in &["", "ascii", "Grüße, 世界 🦀"] {
+        let c = 'ß';
+        let buf_size = sizeof::Context::<F>::new().absorb(*s)?.mask(*s)?.mask(&c)?.get_size();
+        let mut buf = vec![0_u8; buf_size];
+        {
+            let mut ctx = wrap::Context::<F, &mut [u8]>::new(&mut buf[..]);
+            ctx.absorb(*s)?.mask(*s)?.mask(&c)?;
+            try_or!(ctx.stream.is_empty(), OutputStreamNotFullyConsumed(ctx.stream.len()))?;
+        }
+
+        let mut sa = String::new();
+        let mut sm = String::new();
+        let mut c2 = '\0';
+        {
+            let mut ctx = unwrap::Context::<F, &[u8]>::new(&buf[..]);
+            ctx.absorb(&mut sa)?
+                .mask_bounded_string(&mut sm, s.len())?
+                .mask(&mut c2)?;
+            try_or!(ctx.stream.is_empty(), InputStreamNotFullyConsumed(ctx.stream.len()))?;
+        }
+        try_or!(sa == *s && sm == *s && c2 == c, ValueMismatch(s.len(), sm.len()))?;
+
+        if !s.is_empty() {
+            let mut ctx = unwrap::Context::<F, &[u8]>::new(&buf[..]);
+            try_or!(
+                ctx.absorb_bounded_string(&mut sa, s.len() - 1).is_err(),
+                BytesLengthExceeded(s.len(), s.len() - 1)
+            )?;
+        }
+    }
+
+    // Truncated multi-byte sequence and a surrogate code point are rejected
+    let invalid = Bytes(vec![b'a', 0xc3]);
+    let surrogate = Uint32(0xd800);
+    let buf_size = sizeof::Context::<F>::new()
+        .absorb(&invalid)?
+        .absorb(&surrogate)?
+        .get_size();
+    let mut buf = vec![0_u8; buf_size];
+    wrap::Context::<F, &mut [u8]>::new(&mut buf[..])
+        .absorb(&invalid)?
+        .absorb(&surrogate)?;
+
+    let mut s = String::new();
+    let mut c = '\0';
+    try_or!(
+        unwrap::Context::<F, &[u8]>::new(&buf[..]).absorb(&mut s).is_err(),
+        InvalidUtf8String(s)
+    )?;
+    let mut ctx = unwrap::Context::<F, &[u8]>::new(&buf[..]);
+    ctx.drop(Size(sizeof_sizet(invalid.0.len()) + invalid.0.len()))?;
+    try_or!(ctx.absorb(&mut c).is_err(), InvalidChar(surrogate.0))?;
+    Ok(())
+}
+
+#[test]
+fn string() {
+    assert!(dbg!(absorb_mask_string::<KeccakF1600>()).is_ok());
+}
+
+fn absorb_mask_nbytes<F: PRP, N: ArrayLength<u8>>() -> Result<()> {
+    let mut tag_wrap = External(NBytes::<U32>::default());
+    let mut tag_unwrap = External(NBytes::<U32>::default());
+
+    let mut nta = NBytes::<N>::default();
+    let mut ntm = NBytes::<N>::default();
+    for (i, (a, m)) in nta.as_mut_slice().iter_mut().zip(ntm.as_mut_slice()).enumerate() {
+        *a = i as u8;
+        *m = !(i as u8);
+    }
+
+    let buf_size = sizeof::Context::<F>::new().absorb(&nta)?.mask(&ntm)?.get_size();
+    try_or!(buf_size == 2 * N::USIZE, ValueMismatch(2 * N::USIZE, buf_size))?;
+    let mut buf = vec![0_u8; buf_size];
+    {
+        let mut ctx = wrap::Context::<F, &mut [u8]>::new(&mut buf[..]);
+        ctx.absorb(&nta)?.mask(&ntm)?.commit()?.squeeze(&mut tag_wrap)?;
+        try_or!(ctx.stream.is_empty(), OutputStreamNotFullyConsumed(ctx.stream.len()))?;
+    }
+
+    let mut nta2 = NBytes::<N>::default();
+    let mut ntm2 = NBytes::<N>::default();
+    {
+        let mut ctx = unwrap::Context::<F, &[u8]>::new(&buf[..]);
+        ctx.absorb(&mut nta2)?
+            .mask(&mut ntm2)?
+            .commit()?
+            .squeeze(&mut tag_unwrap)?;
+        try_or!(ctx.stream.is_empty(), InputStreamNotFullyConsumed(ctx.stream.len()))?;
+    }
+
+    try_or!(nta == nta2, ValueMismatch(N::USIZE, nta2.as_slice().len()))?;
+    try_or!(ntm == ntm2, ValueMismatch(N::USIZE, ntm2.as_slice().len()))?;
+    try_or!(
+        tag_wrap == tag_unwrap,
+        InvalidTagSqueeze(tag_wrap.to_string(), tag_unwrap.to_string())
+    )?;
+    Ok(())
+}
+
+#[test]
+fn nbytes() {
+    assert!(dbg!(absorb_mask_nbytes::<KeccakF1600, U1>()).is_ok());
+    assert!(dbg!(absorb_mask_nbytes::<KeccakF1600, U3>()).is_ok());
+    assert!(dbg!(absorb_mask_nbytes::<KeccakF1600, U32>()).is_ok());
+    assert!(dbg!(absorb_mask_nbytes::<KeccakF1600, U64>()).is_ok());
+    assert!(dbg!(absorb_mask_nbytes::<KeccakF1600, U100>()).is_ok());
+}
+
 fn absorb_ed25519<F: PRP>() -> Result<()> {
     type N = U64;
     let secret = ed25519::SecretKey::from_bytes(&[7; ed25519::SECRET_KEY_LENGTH]).unwrap();
@@ -407,6 +730,55 @@ fn test_x25519() {
     assert!(dbg!(x25519_transport::<KeccakF1600>()).is_ok());
 }
 
+fn wrap_resume_checkpoint<F: PRP>() -> Result<()> {
+    let head = Bytes((0..50).collect());
+    let tail = Bytes((0..200).map(|i| i as u8).collect());
+    let buf_size = sizeof::Context::<F>::new()
+        .absorb(&head)?
+        .mask(&tail)?
+        .commit()?
+        .squeeze(Mac(16))?
+        .get_size();
+
+    let mut expected = vec![0_u8; buf_size];
+    {
+        let mut ctx = wrap::Context::<F, &mut [u8]>::new(&mut expected[..]);
+        ctx.absorb(&head)?.mask(&tail)?.commit()?.squeeze(Mac(16))?;
+        try_or!(ctx.stream.is_empty(), OutputStreamNotFullyConsumed(ctx.stream.len()))?;
+    }
+
+    // Checkpoint with uncommitted Spongos state, the bytes after it are lost on interruption
+    let mut buf = vec![0_u8; buf_size];
+    let checkpoint = {
+        let mut ctx = wrap::Context::<F, &mut [u8]>::new(&mut buf[..]);
+        ctx.absorb(&head)?;
+        ctx.checkpoint().to_bytes()
+    };
+    let written = buf_size - wrap::Checkpoint::<F>::from_bytes(&checkpoint)?.remaining;
+    let mut resumed = vec![0xff_u8; buf_size];
+    resumed[..written].copy_from_slice(&buf[..written]);
+    {
+        let checkpoint = wrap::Checkpoint::<F>::from_bytes(&checkpoint)?;
+        let mut ctx = wrap::Context::resume(&mut resumed[..], &checkpoint)?;
+        ctx.mask(&tail)?.commit()?.squeeze(Mac(16))?;
+        try_or!(ctx.stream.is_empty(), OutputStreamNotFullyConsumed(ctx.stream.len()))?;
+    }
+    try_or!(expected == resumed, ValueMismatch(expected.len(), resumed.len()))?;
+
+    let checkpoint = wrap::Checkpoint::<F>::from_bytes(&checkpoint)?;
+    let short = checkpoint.remaining - 1;
+    try_or!(
+        wrap::Context::resume(&mut resumed[..short], &checkpoint).is_err(),
+        CheckpointExceedsBuffer(checkpoint.remaining, short)
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_wrap_resume_checkpoint() {
+    assert!(dbg!(wrap_resume_checkpoint::<KeccakF1600>()).is_ok());
+}
+
 // use crate::io;
 // use iota_streams_core::sponge::spongos::{self, Spongos};
 // use std::convert::{AsRef, From, Into};
@@ -1,7 +1,9 @@
 use core::mem;
 
 use super::{
+    unicode_char,
     unwrap::*,
+    utf8_string,
     Context,
 };
 use crate::{
@@ -12,6 +14,8 @@ use crate::{
         ArrayLength,
         Bytes,
         Fallback,
+        Int32,
+        Int64,
         NBytes,
         Size,
         Uint16,
@@ -22,8 +26,16 @@ use crate::{
 };
 use iota_streams_core::{
     err,
+    prelude::{
+        String,
+        Vec,
+    },
     sponge::prp::PRP,
-    Errors::PublicKeyGenerationFailure,
+    try_or,
+    Errors::{
+        BytesLengthExceeded,
+        PublicKeyGenerationFailure,
+    },
     Result,
 };
 use iota_streams_core_edsig::{
@@ -121,6 +133,33 @@ impl<F: PRP, IS: io::IStream> Absorb<&mut Uint64> for Context<F, IS> {
     }
 }
 
+impl<F: PRP, IS: io::IStream> Absorb<&mut Int32> for Context<F, IS> {
+    fn absorb(&mut self, i: &mut Int32) -> Result<&mut Self> {
+        let mut u = Uint32(0);
+        self.absorb(&mut u)?;
+        i.0 = u.0 as i32;
+        Ok(self)
+    }
+}
+
+impl<F: PRP, IS: io::IStream> Absorb<&mut Int64> for Context<F, IS> {
+    fn absorb(&mut self, i: &mut Int64) -> Result<&mut Self> {
+        let mut u = Uint64(0);
+        self.absorb(&mut u)?;
+        i.0 = u.0 as i64;
+        Ok(self)
+    }
+}
+
+impl<F: PRP, IS: io::IStream> Absorb<&mut char> for Context<F, IS> {
+    fn absorb(&mut self, c: &mut char) -> Result<&mut Self> {
+        let mut u = Uint32(0);
+        self.absorb(&mut u)?;
+        *c = unicode_char(u.0)?;
+        Ok(self)
+    }
+}
+
 impl<F: PRP, IS: io::IStream> Absorb<&mut Size> for Context<F, IS> {
     fn absorb(&mut self, size: &mut Size) -> Result<&mut Self> {
         Ok(unwrap_absorb_size(self.as_mut(), size)?.as_mut())
@@ -142,6 +181,35 @@ impl<'a, F: PRP, IS: io::IStream> Absorb<&'a mut Bytes> for Context<F, IS> {
     }
 }
 
+impl<'a, F: PRP, IS: io::IStream> Absorb<&'a mut String> for Context<F, IS> {
+    fn absorb(&mut self, s: &'a mut String) -> Result<&mut Self> {
+        let mut bytes = Bytes::default();
+        self.absorb(&mut bytes)?;
+        *s = utf8_string(bytes.0)?;
+        Ok(self)
+    }
+}
+
+impl<F: PRP, IS: io::IStream> Context<F, IS> {
+    /// Absorb `Bytes` declared to be at most `max_len` bytes long. The length prefix is checked
+    /// before allocation, untrusted input can't trigger an arbitrary large allocation.
+    pub fn absorb_bounded_bytes(&mut self, bytes: &mut Bytes, max_len: usize) -> Result<&mut Self> {
+        let mut size = Size(0);
+        self.absorb(&mut size)?;
+        try_or!(size.0 <= max_len, BytesLengthExceeded(size.0, max_len))?;
+        (bytes.0).resize(size.0, 0);
+        Ok(unwrap_absorb_bytes(self.as_mut(), &mut (bytes.0)[..])?.as_mut())
+    }
+
+    /// Absorb utf-8 string declared to be at most `max_len` bytes long.
+    pub fn absorb_bounded_string(&mut self, s: &mut String, max_len: usize) -> Result<&mut Self> {
+        let mut bytes = Bytes::default();
+        self.absorb_bounded_bytes(&mut bytes, max_len)?;
+        *s = utf8_string(bytes.0)?;
+        Ok(self)
+    }
+}
+
 impl<'a, F: PRP, IS: io::IStream> Absorb<&'a mut ed25519::PublicKey> for Context<F, IS> {
     fn absorb(&mut self, pk: &'a mut ed25519::PublicKey) -> Result<&mut Self> {
         let mut pk_bytes = [0_u8; 32];
@@ -171,3 +239,22 @@ impl<'a, F, T: 'a + AbsorbFallback<F>, IS: io::IStream> Absorb<&'a mut Fallback<
         Ok(self)
     }
 }
+
+/// Absorb a vector. Elements are pushed as they are unwrapped, a large declared size doesn't trigger
+/// an allocation.
+impl<'a, F: PRP, T: Default, IS: io::IStream> Absorb<&'a mut Vec<T>> for Context<F, IS>
+where
+    for<'b> Self: Absorb<&'b mut T>,
+{
+    fn absorb(&mut self, values: &'a mut Vec<T>) -> Result<&mut Self> {
+        let mut size = Size(0);
+        self.absorb(&mut size)?;
+        values.clear();
+        for _ in 0..size.0 {
+            let mut value = T::default();
+            self.absorb(&mut value)?;
+            values.push(value);
+        }
+        Ok(self)
+    }
+}
@@ -2,7 +2,9 @@ use core::mem;
 use iota_streams_core::Result;
 
 use super::{
+    unicode_char,
     unwrap::*,
+    utf8_string,
     Context,
 };
 use crate::{
@@ -11,6 +13,8 @@ use crate::{
     types::{
         ArrayLength,
         Bytes,
+        Int32,
+        Int64,
         NBytes,
         Size,
         Uint16,
@@ -20,9 +24,17 @@ use crate::{
     },
 };
 use iota_streams_core::{
+    prelude::{
+        String,
+        Vec,
+    },
     sponge::prp::PRP,
+    try_or,
     wrapped_err,
-    Errors::PublicKeyGenerationFailure,
+    Errors::{
+        BytesLengthExceeded,
+        PublicKeyGenerationFailure,
+    },
     WrappedError,
 };
 use iota_streams_core_edsig::{
@@ -120,6 +132,33 @@ impl<'a, F: PRP, IS: io::IStream> Mask<&'a mut Uint64> for Context<F, IS> {
     }
 }
 
+impl<'a, F: PRP, IS: io::IStream> Mask<&'a mut Int32> for Context<F, IS> {
+    fn mask(&mut self, i: &'a mut Int32) -> Result<&mut Self> {
+        let mut u = Uint32(0);
+        self.mask(&mut u)?;
+        i.0 = u.0 as i32;
+        Ok(self)
+    }
+}
+
+impl<'a, F: PRP, IS: io::IStream> Mask<&'a mut Int64> for Context<F, IS> {
+    fn mask(&mut self, i: &'a mut Int64) -> Result<&mut Self> {
+        let mut u = Uint64(0);
+        self.mask(&mut u)?;
+        i.0 = u.0 as i64;
+        Ok(self)
+    }
+}
+
+impl<'a, F: PRP, IS: io::IStream> Mask<&'a mut char> for Context<F, IS> {
+    fn mask(&mut self, c: &'a mut char) -> Result<&mut Self> {
+        let mut u = Uint32(0);
+        self.mask(&mut u)?;
+        *c = unicode_char(u.0)?;
+        Ok(self)
+    }
+}
+
 impl<'a, F: PRP, IS: io::IStream> Mask<&'a mut Size> for Context<F, IS> {
     fn mask(&mut self, size: &'a mut Size) -> Result<&mut Self> {
         Ok(unwrap_mask_size(self.as_mut(), size)?.as_mut())
@@ -141,6 +180,46 @@ impl<'a, F: PRP, IS: io::IStream> Mask<&'a mut Bytes> for Context<F, IS> {
     }
 }
 
+impl<'a, F: PRP, IS: io::IStream> Mask<&'a mut String> for Context<F, IS> {
+    fn mask(&mut self, s: &'a mut String) -> Result<&mut Self> {
+        let mut bytes = Bytes::default();
+        self.mask(&mut bytes)?;
+        *s = utf8_string(bytes.0)?;
+        Ok(self)
+    }
+}
+
+impl<F: PRP, IS: io::IStream> Context<F, IS> {
+    /// Mask `Bytes` declared to be at most `max_len` bytes long. The length prefix is checked
+    /// before allocation, untrusted input can't trigger an arbitrary large allocation.
+    pub fn mask_bounded_bytes(&mut self, bytes: &mut Bytes, max_len: usize) -> Result<&mut Self> {
+        let mut size = Size(0);
+        self.mask(&mut size)?;
+        try_or!(size.0 <= max_len, BytesLengthExceeded(size.0, max_len))?;
+        (bytes.0).resize(size.0, 0);
+        Ok(unwrap_mask_bytes(self.as_mut(), &mut (bytes.0)[..])?.as_mut())
+    }
+
+    /// Skip masked `Bytes` declared to be at most `max_len` bytes long. The spongos state is
+    /// updated as by `mask_bounded_bytes` but the bytes are not unmasked, their length is returned
+    /// in `size`.
+    pub fn skip_mask_bounded_bytes(&mut self, size: &mut Size, max_len: usize) -> Result<&mut Self> {
+        self.mask(&mut *size)?;
+        try_or!(size.0 <= max_len, BytesLengthExceeded(size.0, max_len))?;
+        let y = self.stream.try_advance(size.0)?;
+        self.spongos.skip_decrypt(y);
+        Ok(self)
+    }
+
+    /// Mask utf-8 string declared to be at most `max_len` bytes long.
+    pub fn mask_bounded_string(&mut self, s: &mut String, max_len: usize) -> Result<&mut Self> {
+        let mut bytes = Bytes::default();
+        self.mask_bounded_bytes(&mut bytes, max_len)?;
+        *s = utf8_string(bytes.0)?;
+        Ok(self)
+    }
+}
+
 impl<'a, F: PRP, IS: io::IStream> Mask<&'a mut x25519::PublicKey> for Context<F, IS> {
     fn mask(&mut self, pk: &'a mut x25519::PublicKey) -> Result<&mut Self> {
         let mut bytes = [0_u8; 32];
@@ -163,3 +242,22 @@ impl<'a, F: PRP, IS: io::IStream> Mask<&'a mut ed25519::PublicKey> for Context<F
         }
     }
 }
+
+/// Mask a vector. Elements are pushed as they are unwrapped, a large declared size doesn't trigger
+/// an allocation.
+impl<'a, F: PRP, T: Default, IS: io::IStream> Mask<&'a mut Vec<T>> for Context<F, IS>
+where
+    for<'b> Self: Mask<&'b mut T>,
+{
+    fn mask(&mut self, values: &'a mut Vec<T>) -> Result<&mut Self> {
+        let mut size = Size(0);
+        self.mask(&mut size)?;
+        values.clear();
+        for _ in 0..size.0 {
+            let mut value = T::default();
+            self.mask(&mut value)?;
+            values.push(value);
+        }
+        Ok(self)
+    }
+}
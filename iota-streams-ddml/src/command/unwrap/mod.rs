@@ -1,6 +1,18 @@
 //! Implementation of command traits for unwrapping.
 
-use iota_streams_core::Result;
+use iota_streams_core::{
+    err,
+    prelude::{
+        hex,
+        String,
+        Vec,
+    },
+    Errors::{
+        InvalidChar,
+        InvalidUtf8String,
+    },
+    Result,
+};
 
 use core::fmt;
 
@@ -34,6 +46,22 @@ impl<F, IS: io::IStream> Context<F, IS> {
     }
 }
 
+/// Decode unwrapped bytes as an utf-8 string.
+pub(crate) fn utf8_string(bytes: Vec<u8>) -> Result<String> {
+    match String::from_utf8(bytes) {
+        Ok(s) => Ok(s),
+        Err(e) => err!(InvalidUtf8String(hex::encode(e.into_bytes()))),
+    }
+}
+
+/// Decode unwrapped `u32` as a unicode scalar value.
+pub(crate) fn unicode_char(u: u32) -> Result<char> {
+    match core::char::from_u32(u) {
+        Some(c) => Ok(c),
+        None => err!(InvalidChar(u)),
+    }
+}
+
 impl<F, IS> fmt::Debug for Context<F, IS> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{{header: {:?}, ctx: {:?}}}", "self.header", "self.ctx")
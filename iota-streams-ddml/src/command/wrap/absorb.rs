@@ -1,5 +1,8 @@
 use core::mem;
-use iota_streams_core::Result;
+use iota_streams_core::{
+    prelude::Vec,
+    Result,
+};
 
 use super::{
     wrap::*,
@@ -13,6 +16,8 @@ use crate::{
         ArrayLength,
         Bytes,
         Fallback,
+        Int32,
+        Int64,
         NBytes,
         Size,
         Uint16,
@@ -140,6 +145,30 @@ impl<F: PRP, OS: io::OStream> Absorb<Uint64> for Context<F, OS> {
     }
 }
 
+impl<'a, F: PRP, OS: io::OStream> Absorb<&'a Int32> for Context<F, OS> {
+    fn absorb(&mut self, i: &'a Int32) -> Result<&mut Self> {
+        self.absorb(Uint32(i.0 as u32))
+    }
+}
+
+impl<F: PRP, OS: io::OStream> Absorb<Int32> for Context<F, OS> {
+    fn absorb(&mut self, i: Int32) -> Result<&mut Self> {
+        self.absorb(&i)
+    }
+}
+
+impl<'a, F: PRP, OS: io::OStream> Absorb<&'a Int64> for Context<F, OS> {
+    fn absorb(&mut self, i: &'a Int64) -> Result<&mut Self> {
+        self.absorb(Uint64(i.0 as u64))
+    }
+}
+
+impl<F: PRP, OS: io::OStream> Absorb<Int64> for Context<F, OS> {
+    fn absorb(&mut self, i: Int64) -> Result<&mut Self> {
+        self.absorb(&i)
+    }
+}
+
 impl<'a, F: PRP, OS: io::OStream> Absorb<&'a Size> for Context<F, OS> {
     fn absorb(&mut self, size: &'a Size) -> Result<&mut Self> {
         Ok(wrap_absorb_size(self.as_mut(), *size)?.as_mut())
@@ -165,6 +194,19 @@ impl<'a, F: PRP, OS: io::OStream> Absorb<&'a Bytes> for Context<F, OS> {
     }
 }
 
+impl<'a, F: PRP, OS: io::OStream> Absorb<&'a str> for Context<F, OS> {
+    fn absorb(&mut self, s: &'a str) -> Result<&mut Self> {
+        self.absorb(Size(s.len()))?;
+        Ok(wrap_absorb_bytes(self.as_mut(), s.as_bytes())?.as_mut())
+    }
+}
+
+impl<'a, F: PRP, OS: io::OStream> Absorb<&'a char> for Context<F, OS> {
+    fn absorb(&mut self, c: &'a char) -> Result<&mut Self> {
+        self.absorb(Uint32(*c as u32))
+    }
+}
+
 impl<'a, F: PRP, OS: io::OStream> Absorb<&'a ed25519::PublicKey> for Context<F, OS> {
     fn absorb(&mut self, pk: &'a ed25519::PublicKey) -> Result<&mut Self> {
         Ok(wrap_absorb_bytes(self.as_mut(), &pk.to_bytes()[..])?.as_mut())
@@ -183,3 +225,16 @@ impl<'a, F, T: 'a + AbsorbFallback<F>, OS: io::OStream> Absorb<&'a Fallback<T>>
         Ok(self)
     }
 }
+
+impl<'a, F: PRP, T, OS: io::OStream> Absorb<&'a Vec<T>> for Context<F, OS>
+where
+    for<'b> Self: Absorb<&'b T>,
+{
+    fn absorb(&mut self, values: &'a Vec<T>) -> Result<&mut Self> {
+        self.absorb(&Size(values.len()))?;
+        for value in values {
+            self.absorb(value)?;
+        }
+        Ok(self)
+    }
+}
@@ -0,0 +1,70 @@
+use iota_streams_core::{
+    prelude::Vec,
+    sponge::{
+        prp::PRP,
+        spongos::Spongos,
+    },
+    try_or,
+    Errors::{
+        CheckpointExceedsBuffer,
+        LengthMismatch,
+    },
+    Result,
+};
+
+use super::Context;
+
+/// Intermediate state of wrapping a message into a buffer: the Spongos state and the number of
+/// bytes of the buffer not yet written. Wrapping interrupted after a checkpoint can be resumed
+/// with `Context::resume` given the bytes written up to the checkpoint are kept in the buffer.
+#[derive(Clone)]
+pub struct Checkpoint<F> {
+    pub spongos: Spongos<F>,
+    pub remaining: usize,
+}
+
+impl<F: PRP> Checkpoint<F> {
+    /// Encode checkpoint: the Spongos state followed by the remaining size as big-endian `u64`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.spongos.to_state_bytes();
+        bytes.extend_from_slice(&(self.remaining as u64).to_be_bytes());
+        bytes
+    }
+
+    /// Decode checkpoint encoded with `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let size = Spongos::<F>::state_size() + 8;
+        try_or!(bytes.len() == size, LengthMismatch(size, bytes.len()))?;
+        let (spongos, remaining) = bytes.split_at(size - 8);
+        let mut remaining_bytes = [0_u8; 8];
+        remaining_bytes.copy_from_slice(remaining);
+        Ok(Self {
+            spongos: Spongos::from_state_bytes(spongos)?,
+            remaining: u64::from_be_bytes(remaining_bytes) as usize,
+        })
+    }
+}
+
+impl<'a, F: PRP> Context<F, &'a mut [u8]> {
+    /// Export the current state of wrapping.
+    pub fn checkpoint(&self) -> Checkpoint<F> {
+        Checkpoint {
+            spongos: self.spongos.clone(),
+            remaining: self.stream.len(),
+        }
+    }
+
+    /// Resume wrapping into `buf` from a checkpoint taken while wrapping into a buffer of the same
+    /// size, the following commands produce the same output as if wrapping was not interrupted.
+    pub fn resume(buf: &'a mut [u8], checkpoint: &Checkpoint<F>) -> Result<Self> {
+        try_or!(
+            checkpoint.remaining <= buf.len(),
+            CheckpointExceedsBuffer(checkpoint.remaining, buf.len())
+        )?;
+        let written = buf.len() - checkpoint.remaining;
+        Ok(Self {
+            spongos: checkpoint.spongos.clone(),
+            stream: &mut buf[written..],
+        })
+    }
+}
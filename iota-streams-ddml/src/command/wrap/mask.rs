@@ -1,5 +1,8 @@
 use core::mem;
-use iota_streams_core::Result;
+use iota_streams_core::{
+    prelude::Vec,
+    Result,
+};
 
 use super::{
     wrap::*,
@@ -11,6 +14,8 @@ use crate::{
     types::{
         ArrayLength,
         Bytes,
+        Int32,
+        Int64,
         NBytes,
         Size,
         Uint16,
@@ -114,6 +119,18 @@ impl<'a, F: PRP, OS: io::OStream> Mask<&'a Uint64> for Context<F, OS> {
     }
 }
 
+impl<'a, F: PRP, OS: io::OStream> Mask<&'a Int32> for Context<F, OS> {
+    fn mask(&mut self, i: &'a Int32) -> Result<&mut Self> {
+        self.mask(&Uint32(i.0 as u32))
+    }
+}
+
+impl<'a, F: PRP, OS: io::OStream> Mask<&'a Int64> for Context<F, OS> {
+    fn mask(&mut self, i: &'a Int64) -> Result<&mut Self> {
+        self.mask(&Uint64(i.0 as u64))
+    }
+}
+
 impl<'a, F: PRP, OS: io::OStream> Mask<&'a Size> for Context<F, OS> {
     fn mask(&mut self, size: &'a Size) -> Result<&mut Self> {
         Ok(wrap_mask_size(self.as_mut(), *size)?.as_mut())
@@ -134,6 +151,19 @@ impl<'a, F: PRP, OS: io::OStream> Mask<&'a Bytes> for Context<F, OS> {
     }
 }
 
+impl<'a, F: PRP, OS: io::OStream> Mask<&'a str> for Context<F, OS> {
+    fn mask(&mut self, s: &'a str) -> Result<&mut Self> {
+        self.mask(&Size(s.len()))?;
+        Ok(wrap_mask_bytes(self.as_mut(), s.as_bytes())?.as_mut())
+    }
+}
+
+impl<'a, F: PRP, OS: io::OStream> Mask<&'a char> for Context<F, OS> {
+    fn mask(&mut self, c: &'a char) -> Result<&mut Self> {
+        self.mask(&Uint32(*c as u32))
+    }
+}
+
 impl<'a, F: PRP, OS: io::OStream> Mask<&'a x25519::PublicKey> for Context<F, OS> {
     fn mask(&mut self, pk: &'a x25519::PublicKey) -> Result<&mut Self> {
         Ok(wrap_mask_bytes(self.as_mut(), &pk.as_bytes()[..])?.as_mut())
@@ -145,3 +175,16 @@ impl<'a, F: PRP, OS: io::OStream> Mask<&'a ed25519::PublicKey> for Context<F, OS
         Ok(wrap_mask_bytes(self.as_mut(), &pk.to_bytes()[..])?.as_mut())
     }
 }
+
+impl<'a, F: PRP, T, OS: io::OStream> Mask<&'a Vec<T>> for Context<F, OS>
+where
+    for<'b> Self: Mask<&'b T>,
+{
+    fn mask(&mut self, values: &'a Vec<T>) -> Result<&mut Self> {
+        self.mask(&Size(values.len()))?;
+        for value in values {
+            self.mask(value)?;
+        }
+        Ok(self)
+    }
+}
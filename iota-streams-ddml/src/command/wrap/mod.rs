@@ -24,6 +24,7 @@ pub(crate) use wrap::*;
 
 mod absorb;
 mod absorb_external;
+mod checkpoint;
 mod commit;
 mod dump;
 mod fork;
@@ -40,6 +41,7 @@ mod x25519;
 
 pub use absorb::*;
 pub use absorb_external::*;
+pub use checkpoint::*;
 pub use commit::*;
 pub use dump::*;
 pub use fork::*;
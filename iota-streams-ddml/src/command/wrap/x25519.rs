@@ -1,20 +1,18 @@
-#[cfg(not(feature = "std"))]
 use iota_streams_core::{
-    err,
+    prng,
+    try_or,
     Errors::NoStdRngMissing,
+    Result,
 };
 
-use iota_streams_core::Result;
-
 use super::Context;
-#[cfg(feature = "std")]
-use crate::command::{
-    Absorb,
-    Commit,
-    Mask,
-};
 use crate::{
-    command::X25519,
+    command::{
+        Absorb,
+        Commit,
+        Mask,
+        X25519,
+    },
     io,
     types::{
         ArrayLength,
@@ -41,10 +39,10 @@ impl<'a, F: PRP, OS: io::OStream> X25519<x25519::EphemeralSecret, &'a x25519::Pu
     }
 }
 
-#[cfg(feature = "std")]
 impl<'a, F: PRP, N: ArrayLength<u8>, OS: io::OStream> X25519<&'a x25519::PublicKey, &'a NBytes<N>> for Context<F, OS> {
     fn x25519(&mut self, pk: &x25519::PublicKey, key: &NBytes<N>) -> Result<&mut Self> {
-        let ephemeral_ke_sk = x25519::EphemeralSecret::new(&mut rand::thread_rng());
+        try_or!(prng::has_default_rng(), NoStdRngMissing)?;
+        let ephemeral_ke_sk = x25519::EphemeralSecret::new(&mut prng::DefaultRng);
         let ephemeral_ke_pk = x25519::PublicKey::from(&ephemeral_ke_sk);
         self.absorb(&ephemeral_ke_pk)?
             .x25519(ephemeral_ke_sk, pk)?
@@ -52,11 +50,3 @@ impl<'a, F: PRP, N: ArrayLength<u8>, OS: io::OStream> X25519<&'a x25519::PublicK
             .mask(key)
     }
 }
-
-#[cfg(not(feature = "std"))]
-impl<'a, F: PRP, N: ArrayLength<u8>, OS: io::OStream> X25519<&'a x25519::PublicKey, &'a NBytes<N>> for Context<F, OS> {
-    fn x25519(&mut self, _pk: &x25519::PublicKey, _key: &NBytes<N>) -> Result<&mut Self> {
-        // TODO: no_std make default rng
-        err!(NoStdRngMissing)
-    }
-}
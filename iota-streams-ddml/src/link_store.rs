@@ -7,6 +7,7 @@ use iota_streams_core::{
     prelude::{
         string::ToString,
         HashMap,
+        Rc,
         Vec,
     },
     sponge::{
@@ -55,6 +56,18 @@ pub trait LinkStore<F, Link> {
     /// Remove link and associated info from the store.
     fn erase(&mut self, _link: &Link) {}
 
+    /// Reserve capacity for at least `additional` more links. It's only a hint, stores may ignore it.
+    fn reserve(&mut self, _additional: usize) {}
+
+    /// Record the link of the message `link` is linked to. Stores may ignore it.
+    fn link_to(&mut self, _link: &Link, _linked: &Link) {}
+
+    /// Links of stored messages paired with the links of the messages they are linked to, as
+    /// recorded with `link_to`.
+    fn linked(&self) -> Vec<(&Link, &Link)> {
+        Vec::new()
+    }
+
     fn iter(&self) -> Vec<(&Link, &(Inner<F>, Self::Info))>
     where
         F: PRP;
@@ -135,8 +148,14 @@ where
     }
 }
 
+/// Callback invoked with the link, spongos state and info each time a state is updated.
+pub type LinkStoreCallback<F, Link, Info> = Rc<dyn Fn(&Link, &Inner<F>, &Info)>;
+
+#[derive(Clone)]
 pub struct DefaultLinkStore<F: PRP, Link, Info> {
     map: HashMap<Link, (Inner<F>, Info)>,
+    linked: HashMap<Link, Link>,
+    on_update: Option<LinkStoreCallback<F, Link, Info>>,
     _phantom: core::marker::PhantomData<F>,
 }
 
@@ -147,9 +166,34 @@ where
     fn default() -> Self {
         Self {
             map: HashMap::new(),
+            linked: HashMap::new(),
+            on_update: None,
+            _phantom: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<F: PRP, Link, Info> DefaultLinkStore<F, Link, Info>
+where
+    Link: Eq + hash::Hash,
+{
+    /// Create an empty store with space for at least `capacity` links.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            map: HashMap::with_capacity(capacity),
+            linked: HashMap::new(),
+            on_update: None,
             _phantom: core::marker::PhantomData,
         }
     }
+
+    /// Set callback invoked synchronously before a spongos state is stored with `update`, ie.
+    /// when a message is sent or handled. States inserted with `insert`, eg. on import, are not
+    /// reported. The callback allows to persist states incrementally; a panic in the callback
+    /// propagates to the caller and the state is not stored.
+    pub fn set_on_update(&mut self, callback: Option<LinkStoreCallback<F, Link, Info>>) {
+        self.on_update = callback;
+    }
 }
 
 impl<F: PRP, Link, Info> LinkStore<F, Link> for DefaultLinkStore<F, Link, Info>
@@ -170,6 +214,9 @@ where
     /// Try to retrieve info for the link.
     fn update(&mut self, link: &Link, spongos: Spongos<F>, info: Info) -> Result<()> {
         let inner = spongos.to_inner()?;
+        if let Some(on_update) = &self.on_update {
+            on_update(link, &inner, &info);
+        }
         self.map.insert(link.clone(), (inner, info));
         Ok(())
     }
@@ -182,9 +229,22 @@ where
     /// Remove info for the link.
     fn erase(&mut self, link: &Link) {
         self.map.remove(link);
+        self.linked.remove(link);
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.map.reserve(additional);
     }
 
     fn iter(&self) -> Vec<(&Link, &(Inner<F>, Self::Info))> {
         self.map.iter().collect()
     }
+
+    fn link_to(&mut self, link: &Link, linked: &Link) {
+        self.linked.insert(link.clone(), linked.clone());
+    }
+
+    fn linked(&self) -> Vec<(&Link, &Link)> {
+        self.linked.iter().collect()
+    }
 }
@@ -0,0 +1,21 @@
+use core::fmt;
+
+/// Signed 32-bit integer, encoded in two's complement with 4 bytes in big-endian order.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Int32(pub i32);
+
+impl fmt::Display for Int32 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Signed 64-bit integer, encoded in two's complement with 8 bytes in big-endian order.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Int64(pub i64);
+
+impl fmt::Display for Int64 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
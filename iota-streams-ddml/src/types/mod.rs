@@ -21,12 +21,16 @@ mod fallback;
 pub use fallback::*;
 mod hashsig;
 pub use hashsig::*;
+mod int;
+pub use int::*;
 mod mac;
 pub use mac::*;
 mod nbytes;
 pub use nbytes::*;
 mod prehashed;
 pub(crate) use prehashed::*;
+mod signer;
+pub use signer::*;
 mod size;
 pub use size::*;
 mod uint;
@@ -0,0 +1,61 @@
+use iota_streams_core::{
+    try_or,
+    wrapped_err,
+    Errors::{
+        LengthMismatch,
+        SignatureFailure,
+        SignatureMismatch,
+    },
+    Result,
+    WrappedError,
+};
+use iota_streams_core_edsig::signature::ed25519;
+
+use super::{
+    NBytes,
+    Prehashed,
+    U64,
+};
+
+/// Context of Ed25519ph signatures of wrapped messages.
+pub const SIGNATURE_CONTEXT: &[u8] = b"IOTAStreams";
+
+/// Ed25519 key signing message hashes, eg. held in an HSM or a remote KMS.
+///
+/// The public key is absorbed into messages before the hash is signed, it must be available
+/// without contacting the signer. Signing is synchronous as messages are wrapped in a single
+/// synchronous pass, a remote signer waits for the signature to be returned.
+pub trait Signer {
+    /// Public key signatures are verified with.
+    fn public_key(&self) -> &ed25519::PublicKey;
+
+    /// Ed25519ph signature with `SIGNATURE_CONTEXT` of the prehashed message `hash`.
+    fn sign_hash(&self, hash: &NBytes<U64>) -> Result<ed25519::Signature>;
+}
+
+/// In-process signer with the key pair held in memory.
+impl Signer for ed25519::Keypair {
+    fn public_key(&self) -> &ed25519::PublicKey {
+        &self.public
+    }
+
+    fn sign_hash(&self, hash: &NBytes<U64>) -> Result<ed25519::Signature> {
+        let prehashed = Prehashed(hash.0);
+        self.sign_prehashed(prehashed, Some(SIGNATURE_CONTEXT))
+            .map_err(|e| wrapped_err!(SignatureFailure, WrappedError(e)))
+    }
+}
+
+/// Verify Ed25519ph signature with `SIGNATURE_CONTEXT` of the 64-byte prehashed message `hash`.
+///
+/// Counterpart of `Signer::sign_hash`, used to check signatures detached from wrapped messages.
+pub fn verify_hash(pk: &ed25519::PublicKey, hash: &[u8], signature: &ed25519::Signature) -> Result<()> {
+    let mut prehashed = Prehashed::default();
+    try_or!(
+        hash.len() == prehashed.0.len(),
+        LengthMismatch(prehashed.0.len(), hash.len())
+    )?;
+    prehashed.0.as_mut_slice().copy_from_slice(hash);
+    pk.verify_prehashed(prehashed, Some(SIGNATURE_CONTEXT), signature)
+        .map_err(|e| wrapped_err!(SignatureMismatch, WrappedError(e)))
+}